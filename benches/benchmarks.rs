@@ -1,6 +1,6 @@
 #![allow(unused)]
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use grafferous::{generate_cycle_graph, generate_grid_graph, Graph};
+use grafferous::{generate_cycle_graph, generate_grid_graph, Graph, GraphBuilder};
 
 fn benchmark_empty_creation(c: &mut Criterion) {
     c.bench_function("empty", |b| b.iter(Graph::<usize, u32>::new));
@@ -31,6 +31,16 @@ fn benchmark_add_100000_nodes(c: &mut Criterion) {
     });
 }
 
+fn benchmark_build_100000_nodes(c: &mut Criterion) {
+    c.bench_function("build 100000 nodes", |b| {
+        b.iter(|| {
+            GraphBuilder::<usize, u32>::with_capacity(100000, 0)
+                .extend_nodes(0..100000)
+                .build()
+        })
+    });
+}
+
 fn benchmark_add_1000_edges(c: &mut Criterion) {
     c.bench_function("add 1000 edges", |b| {
         b.iter(|| {
@@ -45,6 +55,16 @@ fn benchmark_add_1000_edges(c: &mut Criterion) {
     });
 }
 
+fn benchmark_bulk_add_1000_edges(c: &mut Criterion) {
+    let edges: Vec<(usize, usize)> = (0..1000).map(|i| (i, (i + 1) % 1000)).collect();
+    c.bench_function("bulk add 1000 edges", |b| {
+        b.iter(|| {
+            let mut g = Graph::<usize, u32>::new();
+            g.add_edges(black_box(&edges));
+        })
+    });
+}
+
 fn benchmark_cycle_creation(c: &mut Criterion) {
     c.bench_function("cycle 10_000", |b| {
         b.iter(|| generate_cycle_graph::<u32>(black_box(10_000)))
@@ -57,15 +77,52 @@ fn benchmark_grid(c: &mut Criterion) {
     });
 }
 
+// compare with `cargo bench --features smallvec` to see the effect of inline adjacency lists
+fn benchmark_grid_neighbor_sweep(c: &mut Criterion) {
+    let g = generate_grid_graph::<u32>(100, 100);
+    c.bench_function("grid 100x100 neighbor sweep", |b| {
+        b.iter(|| {
+            g.nodes
+                .iter()
+                .map(|id| g.edges[id].iter().map(|n| n.0 + n.1).sum::<usize>())
+                .sum::<usize>()
+        })
+    });
+}
+
+fn benchmark_compact_grid_bfs(c: &mut Criterion) {
+    let g = generate_grid_graph::<u32>(100, 100).to_compact();
+    c.bench_function("compact grid 100x100 bfs", |b| {
+        b.iter(|| g.distances_from(black_box((0, 0))))
+    });
+}
+
+// usize IDs are tracked in a bitset, the tuple IDs of the same grid are hashed
+fn benchmark_grid_bfs(c: &mut Criterion) {
+    let tuples = generate_grid_graph::<u32>(300, 300);
+    let numbered = tuples.clone().map_ids(|(x, y)| x * 300 + y).unwrap();
+    c.bench_function("grid 300x300 bfs tuple ids", |b| {
+        b.iter(|| tuples.bfs(black_box((0, 0))).count())
+    });
+    c.bench_function("grid 300x300 bfs usize ids", |b| {
+        b.iter(|| numbered.bfs(black_box(0)).count())
+    });
+}
+
 criterion_group!(
     benches,
     benchmark_addition,
     benchmark_empty_creation,
     benchmark_add_1_nodes,
     benchmark_add_100000_nodes,
+    benchmark_build_100000_nodes,
     benchmark_add_1000_edges,
+    benchmark_bulk_add_1000_edges,
     benchmark_cycle_creation,
-    benchmark_grid
+    benchmark_grid,
+    benchmark_grid_neighbor_sweep,
+    benchmark_compact_grid_bfs,
+    benchmark_grid_bfs
 );
 
 criterion_main!(benches);
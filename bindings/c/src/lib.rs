@@ -0,0 +1,7 @@
+//! Builds the C interface of grafferous (`ffi` feature) into a shared and a static library.
+//!
+//! ```sh
+//! cargo build --release -p grafferous-c
+//! ```
+
+pub use grafferous_core::ffi::*;
@@ -0,0 +1,7 @@
+//! Builds the JavaScript bindings of grafferous (`wasm` feature) into a WebAssembly module.
+//!
+//! ```sh
+//! wasm-pack build bindings/wasm
+//! ```
+
+pub use grafferous::wasm::*;
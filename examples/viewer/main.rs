@@ -0,0 +1,7 @@
+use grafferous::{generate_grid_graph, viewer::view};
+
+// run with `cargo run --example viewer --features viewer`
+fn main() -> eframe::Result {
+    let g = generate_grid_graph::<()>(12, 8);
+    view(&g)
+}
@@ -0,0 +1,63 @@
+/*
+ * C interface of grafferous, built with `cargo build --release -p grafferous-c`.
+ * Link against the resulting libgrafferous shared library.
+ *
+ * Functions that fill a buffer write at most `capacity` IDs to `out` and return the full
+ * length, so calling them with a capacity of zero first tells how large the buffer must be.
+ */
+#ifndef GRAFFEROUS_H
+#define GRAFFEROUS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opaque graph with uint64_t node IDs. */
+typedef struct GrafferousGraph GrafferousGraph;
+
+/* Creates a new, empty graph. Release it with grafferous_graph_free. */
+GrafferousGraph *grafferous_graph_new(void);
+
+/* Releases a graph. Passing NULL does nothing. */
+void grafferous_graph_free(GrafferousGraph *graph);
+
+/* Adds a node. Returns true if it was added and false if it already existed. */
+bool grafferous_graph_add_node(GrafferousGraph *graph, uint64_t id);
+
+/* Adds an edge in both directions, adding missing nodes. */
+void grafferous_graph_add_edge(GrafferousGraph *graph, uint64_t from, uint64_t to);
+
+/* Adds an edge from `from` to `to`, adding missing nodes. */
+void grafferous_graph_add_directed_edge(GrafferousGraph *graph, uint64_t from, uint64_t to);
+
+/* Adds `count` edges stored as pairs edges[2 * i] -> edges[2 * i + 1]. Returns false and adds
+ * nothing if graph or edges is NULL or 2 * count overflows. */
+bool grafferous_graph_add_edges(GrafferousGraph *graph, const uint64_t *edges, size_t count,
+                                bool directed);
+
+/* The number of nodes. */
+size_t grafferous_graph_node_count(const GrafferousGraph *graph);
+
+/* Whether a node exists. */
+bool grafferous_graph_contains_node(const GrafferousGraph *graph, uint64_t id);
+
+/* The IDs of all nodes, in insertion order. */
+size_t grafferous_graph_nodes(const GrafferousGraph *graph, uint64_t *out, size_t capacity);
+
+/* The outgoing neighbors of a node. */
+size_t grafferous_graph_neighbors(const GrafferousGraph *graph, uint64_t id, uint64_t *out,
+                                  size_t capacity);
+
+/* A path with the fewest edges, both ends included; returns 0 if there is none. */
+size_t grafferous_graph_shortest_path(const GrafferousGraph *graph, uint64_t from, uint64_t to,
+                                      uint64_t *out, size_t capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GRAFFEROUS_H */
@@ -0,0 +1,257 @@
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    CycleError, Graph,
+};
+
+/// A directed graph that stays acyclic: an edge that would close a cycle is rejected.
+///
+/// The graph keeps a topological order of its nodes and repairs it on every insertion with the
+/// algorithm of Pearce and Kelly. An edge that agrees with the order is added in O(1); otherwise
+/// only the nodes between its endpoints in the order are searched and reordered, instead of
+/// sorting the whole graph again as `Graph::is_directed_acyclic` does. Removing nodes or edges
+/// never creates a cycle, so it leaves the order alone.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::AcyclicGraph;
+///
+/// let mut tasks = AcyclicGraph::<&str, ()>::new();
+/// tasks.try_add_directed_edge("design", "build").unwrap();
+/// tasks.try_add_directed_edge("build", "test").unwrap();
+/// tasks.try_add_directed_edge("design", "test").unwrap();
+///
+/// let cycle = tasks.try_add_directed_edge("test", "design").unwrap_err();
+/// assert_eq!(cycle.nodes, vec!["test", "design", "test"]);
+/// assert_eq!(tasks.topological_order(), vec!["design", "build", "test"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AcyclicGraph<IDDataType, NodeDataType, EdgeDataType = ()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    graph: Graph<IDDataType, NodeDataType, EdgeDataType>,
+    /// the position of every node in a topological order, with gaps left by removed nodes
+    order: NodeMap<IDDataType, usize>,
+    /// the position given to the next new node, after all others
+    next: usize,
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> AcyclicGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Creates a new, empty graph.
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::new(),
+            order: NodeMap::default(),
+            next: 0,
+        }
+    }
+
+    /// Wraps a graph, or fails with the nodes that `Graph::topological_sort` could not order if
+    /// the graph has a cycle.
+    pub fn from_graph(
+        graph: Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Result<Self, CycleError<IDDataType>> {
+        let order: NodeMap<IDDataType, usize> = graph
+            .topological_sort()?
+            .into_iter()
+            .enumerate()
+            .map(|(position, id)| (id, position))
+            .collect();
+        Ok(Self {
+            next: order.len(),
+            graph,
+            order,
+        })
+    }
+
+    /// The wrapped graph.
+    pub fn graph(&self) -> &Graph<IDDataType, NodeDataType, EdgeDataType> {
+        &self.graph
+    }
+
+    /// Unwraps the graph.
+    pub fn into_graph(self) -> Graph<IDDataType, NodeDataType, EdgeDataType> {
+        self.graph
+    }
+
+    /// The nodes in a topological order, so every edge goes from an earlier to a later node.
+    pub fn topological_order(&self) -> Vec<IDDataType> {
+        let mut nodes = self.graph.nodes.clone();
+        nodes.sort_unstable_by_key(|id| self.order[id]);
+        nodes
+    }
+
+    /// Adds a node with the given data, see `Graph::add_node_with_data`.
+    pub fn add_node_with_data(&mut self, id: IDDataType, data: NodeDataType) {
+        if !self.order.contains_key(&id) {
+            self.order.insert(id, self.next);
+            self.next += 1;
+        }
+        self.graph.add_node_with_data(id, data);
+    }
+
+    /// Whether adding the directed edge from `from` to `to` would close a cycle.
+    pub fn would_create_cycle(&self, from: IDDataType, to: IDDataType) -> bool {
+        match (self.order.get(&from), self.order.get(&to)) {
+            (Some(_), Some(_)) if from == to => true,
+            (Some(&from_position), Some(&to_position)) if to_position < from_position => {
+                self.search_forward(to, from, from_position).is_err()
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a node and its edges, see `Graph::remove_node`.
+    pub fn remove_node(&mut self, id: IDDataType) -> Option<NodeDataType> {
+        self.order.remove(&id);
+        self.graph.remove_node(id)
+    }
+
+    /// Removes a directed edge, see `Graph::remove_directed_edge`.
+    pub fn remove_directed_edge(&mut self, from: IDDataType, to: IDDataType) -> bool {
+        self.graph.remove_directed_edge(from, to)
+    }
+
+    /// the nodes reachable from `start` whose position is below `bound`, or the path from
+    /// `start` to `target` if `target` is among them.
+    fn search_forward(
+        &self,
+        start: IDDataType,
+        target: IDDataType,
+        bound: usize,
+    ) -> Result<Vec<IDDataType>, Vec<IDDataType>> {
+        let mut parents: NodeMap<IDDataType, IDDataType> = NodeMap::default();
+        let mut reached = vec![start];
+        let mut stack = vec![start];
+        parents.insert(start, start);
+        while let Some(node) = stack.pop() {
+            for &next in self.graph.neighbors(node) {
+                if next == target {
+                    let mut path = vec![target, node];
+                    let mut current = node;
+                    while current != start {
+                        current = parents[&current];
+                        path.push(current);
+                    }
+                    path.reverse();
+                    return Err(path);
+                }
+                if self.order[&next] < bound && !parents.contains_key(&next) {
+                    parents.insert(next, node);
+                    reached.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        Ok(reached)
+    }
+
+    /// the nodes that reach `start` whose position is above `bound`.
+    fn search_backward(&self, start: IDDataType, bound: usize) -> Vec<IDDataType> {
+        let mut seen: NodeSet<IDDataType> = NodeSet::default();
+        seen.insert(start);
+        let mut reached = vec![start];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for &previous in self.graph.reverse_neighbors(node) {
+                if self.order[&previous] > bound && seen.insert(previous) {
+                    reached.push(previous);
+                    stack.push(previous);
+                }
+            }
+        }
+        reached
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType>
+    AcyclicGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Adds a node with the default data, see `Graph::add_node`.
+    pub fn add_node(&mut self, id: IDDataType) {
+        self.add_node_with_data(id, NodeDataType::default());
+    }
+
+    /// Adds a directed edge, adding missing nodes, unless it would close a cycle. Then nothing is
+    /// added and the error holds the cycle, starting and ending at `from`, with `from` and `to`
+    /// first. A self-loop is a cycle of one node.
+    ///
+    /// Edges that already agree with the topological order are added without a search. Otherwise
+    /// the nodes between `to` and `from` in the order that `to` reaches, and that reach `from`,
+    /// are searched and moved so that the order holds again.
+    pub fn try_add_directed_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Result<(), CycleError<IDDataType>> {
+        if from == to {
+            return Err(CycleError {
+                nodes: vec![from, from],
+            });
+        }
+        for id in [from, to] {
+            if !self.order.contains_key(&id) {
+                self.add_node(id);
+            }
+        }
+        let (upper, lower) = (self.order[&from], self.order[&to]);
+        if lower < upper {
+            let mut forward = self
+                .search_forward(to, from, upper)
+                .map_err(|path| CycleError {
+                    nodes: [from].into_iter().chain(path).collect(),
+                })?;
+            let mut backward = self.search_backward(from, lower);
+            self.reorder(&mut backward, &mut forward);
+        }
+        self.graph.add_directed_edge(from, to);
+        Ok(())
+    }
+
+    /// Like `try_add_directed_edge`, with data for the edge, see `Graph::add_directed_edge_with_data`.
+    pub fn try_add_directed_edge_with_data(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        data: EdgeDataType,
+    ) -> Result<(), CycleError<IDDataType>> {
+        self.try_add_directed_edge(from, to)?;
+        self.graph.edge_data.insert((from, to), data);
+        Ok(())
+    }
+
+    /// gives the positions of both groups to the nodes that reach the new edge first and then to
+    /// the nodes it reaches, keeping the relative order within each group.
+    fn reorder(&mut self, backward: &mut [IDDataType], forward: &mut [IDDataType]) {
+        backward.sort_unstable_by_key(|id| self.order[id]);
+        forward.sort_unstable_by_key(|id| self.order[id]);
+        let mut positions: Vec<usize> = backward
+            .iter()
+            .chain(forward.iter())
+            .map(|id| self.order[id])
+            .collect();
+        positions.sort_unstable();
+        for (id, position) in backward.iter().chain(forward.iter()).zip(positions) {
+            self.order.insert(*id, position);
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Default
+    for AcyclicGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
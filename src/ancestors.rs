@@ -0,0 +1,212 @@
+//! Lowest common ancestors: an index answering them on a rooted tree in logarithmic time, and
+//! all lowest common ancestors of two nodes in a directed acyclic graph.
+//!
+//! Edges lead from an ancestor to its descendants, e.g. from a parent to its children.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    visit::Bfs,
+    Graph,
+};
+
+/// A precomputed index answering lowest common ancestor queries on a rooted tree that does not
+/// change.
+///
+/// The tree is the breadth-first tree of the graph from the root, so on a tree with edges from
+/// parents to children, or an undirected one, it is the tree itself; on other graphs it is the
+/// tree of shortest hop paths from the root. Nodes the root does not reach are not in the index.
+/// Building the index stores the `2^k`-th ancestor of every node (binary lifting), taking
+/// `O(n log n)` time and memory, and every query then takes `O(log n)` time.
+///
+/// The index describes the graph at the time it was built; rebuild it after changing the graph.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{ancestors::AncestorIndex, Graph};
+///
+/// let taxonomy = Graph::<&str, ()>::from_edges(&[
+///     ("animal", "mammal"),
+///     ("mammal", "cat"),
+///     ("mammal", "dog"),
+///     ("animal", "bird"),
+///     ("bird", "owl"),
+/// ]);
+/// let index = AncestorIndex::build(&taxonomy, "animal");
+/// assert_eq!(index.lowest_common_ancestor("cat", "dog"), Some("mammal"));
+/// assert_eq!(index.lowest_common_ancestor("cat", "owl"), Some("animal"));
+/// assert_eq!(index.lowest_common_ancestor("mammal", "cat"), Some("mammal"));
+/// assert_eq!(index.depth("owl"), Some(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AncestorIndex<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// the nodes of the tree in breadth-first order, starting with the root
+    nodes: Vec<IDDataType>,
+    position: NodeMap<IDDataType, usize>,
+    depth: Vec<usize>,
+    /// `jumps[k][v]` is the `2^k`-th ancestor of `v`, or the root if `v` is not that deep
+    jumps: Vec<Vec<usize>>,
+}
+
+impl<IDDataType> AncestorIndex<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Builds the index for the tree of `graph` rooted at `root`; it is empty if `root` is not in
+    /// the graph.
+    pub fn build<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        root: IDDataType,
+    ) -> Self {
+        let mut nodes = Vec::new();
+        let mut position = NodeMap::default();
+        let mut depth = Vec::new();
+        let mut parents = Vec::new();
+        for visit in Bfs::new(graph, root) {
+            position.insert(visit.id, nodes.len());
+            nodes.push(visit.id);
+            depth.push(visit.depth);
+            parents.push(visit.parent.map_or(0, |parent| position[&parent]));
+        }
+
+        let levels = (usize::BITS - nodes.len().leading_zeros()).max(1) as usize;
+        let mut jumps = Vec::with_capacity(levels);
+        jumps.push(parents);
+        for k in 1..levels {
+            let previous: &Vec<usize> = &jumps[k - 1];
+            let next = previous.iter().map(|half| previous[*half]).collect();
+            jumps.push(next);
+        }
+        Self {
+            nodes,
+            position,
+            depth,
+            jumps,
+        }
+    }
+
+    /// The root of the tree, or `None` if the index is empty.
+    pub fn root(&self) -> Option<IDDataType> {
+        self.nodes.first().copied()
+    }
+
+    /// Whether the node is in the tree.
+    pub fn contains(&self, id: IDDataType) -> bool {
+        self.position.contains_key(&id)
+    }
+
+    /// The number of edges between the root and the node, or `None` if it is not in the tree.
+    pub fn depth(&self, id: IDDataType) -> Option<usize> {
+        self.position.get(&id).map(|v| self.depth[*v])
+    }
+
+    /// The parent of the node, or `None` for the root and nodes not in the tree.
+    pub fn parent(&self, id: IDDataType) -> Option<IDDataType> {
+        let v = *self.position.get(&id)?;
+        (v != 0).then(|| self.nodes[self.jumps[0][v]])
+    }
+
+    /// The deepest node that is an ancestor of both nodes, where a node is an ancestor of itself,
+    /// or `None` if either node is not in the tree.
+    pub fn lowest_common_ancestor(&self, a: IDDataType, b: IDDataType) -> Option<IDDataType> {
+        let (mut a, mut b) = (*self.position.get(&a)?, *self.position.get(&b)?);
+        if self.depth[a] < self.depth[b] {
+            core::mem::swap(&mut a, &mut b);
+        }
+        a = self.ancestor_at(a, self.depth[a] - self.depth[b]);
+        if a == b {
+            return Some(self.nodes[a]);
+        }
+        for level in self.jumps.iter().rev() {
+            if level[a] != level[b] {
+                a = level[a];
+                b = level[b];
+            }
+        }
+        Some(self.nodes[self.jumps[0][a]])
+    }
+
+    /// The number of edges on the tree path between two nodes, or `None` if either node is not
+    /// in the tree.
+    pub fn distance(&self, a: IDDataType, b: IDDataType) -> Option<usize> {
+        let ancestor = self.lowest_common_ancestor(a, b)?;
+        let depth = |id| self.depth[self.position[&id]];
+        Some(depth(a) + depth(b) - 2 * depth(ancestor))
+    }
+
+    /// the ancestor `steps` levels above `v`.
+    fn ancestor_at(&self, mut v: usize, steps: usize) -> usize {
+        for (k, level) in self.jumps.iter().enumerate() {
+            if steps >> k & 1 == 1 {
+                v = level[v];
+            }
+        }
+        v
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// All lowest common ancestors of two nodes in a directed acyclic graph: the nodes that reach
+    /// both along directed edges, where a node reaches itself, and from which no other such node
+    /// can be reached. A tree has at most one, a graph where nodes have several parents can have
+    /// many. They are in the order of `nodes`, and there are none if either node is missing.
+    ///
+    /// Every query searches the ancestors of both nodes, taking `O(n + m)` time; use an
+    /// [`AncestorIndex`] for many queries on a tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // 1 and 2 are both parents of 3 and 4
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3; 1 -> 4; 2 -> 4").unwrap();
+    /// assert_eq!(g.lowest_common_ancestors(3, 4), vec![1, 2]);
+    /// assert_eq!(g.lowest_common_ancestors(1, 3), vec![1]);
+    /// assert_eq!(g.lowest_common_ancestors(1, 2), vec![0]);
+    /// ```
+    pub fn lowest_common_ancestors(&self, a: IDDataType, b: IDDataType) -> Vec<IDDataType> {
+        let of_a = self.ancestors_of(a);
+        let of_b = self.ancestors_of(b);
+        let common: NodeSet<IDDataType> = of_a.intersection(&of_b).copied().collect();
+        // a common ancestor with a common ancestor below it has one among its children
+        self.nodes
+            .iter()
+            .filter(|&id| {
+                common.contains(id)
+                    && !self
+                        .neighbors(*id)
+                        .iter()
+                        .any(|child| child != id && common.contains(child))
+            })
+            .copied()
+            .collect()
+    }
+
+    /// the nodes that reach `id` along directed edges, including `id` itself if it exists.
+    fn ancestors_of(&self, id: IDDataType) -> NodeSet<IDDataType> {
+        let mut reached = NodeSet::default();
+        if !self.contains_node(id) {
+            return reached;
+        }
+        reached.insert(id);
+        let mut stack = vec![id];
+        while let Some(node) = stack.pop() {
+            for parent in self.reverse_neighbors(node) {
+                if reached.insert(*parent) {
+                    stack.push(*parent);
+                }
+            }
+        }
+        reached
+    }
+}
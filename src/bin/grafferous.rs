@@ -0,0 +1,264 @@
+//! The `grafferous` command line tool, behind the `cli` feature.
+//!
+//! Reads a graph from an edge list (one `from to` pair of integer IDs per line, `#` starts a
+//! comment, `-` reads standard input) and prints statistics, converts it to other formats or
+//! answers simple queries.
+
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Write as _,
+    fs,
+    io::{self, BufRead, BufReader, Read},
+    process::ExitCode,
+};
+
+use grafferous::{
+    connectivity::DynamicConnectivity,
+    io::StreamOptions,
+    layout::{fruchterman_reingold, ForceConfig},
+    render::{render_ascii, render_svg, SvgStyle},
+    visit::bfs_path,
+    Graph,
+};
+
+const USAGE: &str = "\
+usage: grafferous [--undirected] <command> <input> [arguments]
+
+commands:
+  stats <input>                  print node, edge and degree statistics
+  convert <input> <format>       print the graph as edges, dot, json, svg or ascii
+  components <input>             print the weakly connected components, one per line
+  path <input> <from> <to>       print a path with the fewest edges
+  topo <input>                   print the nodes in topological order
+
+<input> is an edge list file with one `from to` pair per line, or - for standard input.
+--undirected adds every edge in both directions.";
+
+type Id = u64;
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let undirected = match args.iter().position(|arg| arg == "--undirected") {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    };
+    match run(&args, undirected) {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("grafferous: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// runs a command and returns what it prints.
+fn run(args: &[String], undirected: bool) -> Result<String, String> {
+    let (command, input) = match args {
+        [command, input, ..] => (command.as_str(), input.as_str()),
+        _ => return Err(USAGE.to_string()),
+    };
+    let rest = &args[2..];
+    let graph = load(input, undirected)?;
+    match (command, rest) {
+        ("stats", []) => Ok(stats(&graph)),
+        ("convert", [format]) => convert(&graph, format, undirected),
+        ("components", []) => Ok(components(&graph)),
+        ("path", [from, to]) => {
+            let (from, to) = (parse_id(from)?, parse_id(to)?);
+            match bfs_path(&graph, from, to) {
+                Some(path) => Ok(join(&path) + "\n"),
+                None => Err(format!("there is no path from {from} to {to}")),
+            }
+        }
+        ("topo", []) => match graph.topological_sort() {
+            Ok(order) => Ok(join(&order) + "\n"),
+            Err(_) => Err("the graph has a cycle".to_string()),
+        },
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// reads an edge list from a file, or from standard input for `-`.
+fn load(input: &str, undirected: bool) -> Result<Graph<Id, ()>, String> {
+    let reader: Box<dyn Read> = if input == "-" {
+        Box::new(io::stdin())
+    } else {
+        Box::new(fs::File::open(input).map_err(|error| format!("{input}: {error}"))?)
+    };
+    let edges = BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .filter_map(|(number, line)| {
+            let line = match line {
+                Ok(line) => line,
+                Err(error) => return Some(Err(format!("{input}: {error}"))),
+            };
+            let content = line.split('#').next().unwrap_or_default();
+            let fields: Vec<&str> = content.split_whitespace().collect();
+            match fields[..] {
+                [] => None,
+                [from, to] => Some(
+                    parse_id(from)
+                        .and_then(|from| Ok((from, parse_id(to)?)))
+                        .map_err(|error| format!("{input}:{}: {error}", number + 1)),
+                ),
+                _ => Some(Err(format!(
+                    "{input}:{}: expected two node IDs",
+                    number + 1
+                ))),
+            }
+        });
+    Graph::try_from_edge_stream(edges, StreamOptions::new().undirected(undirected))
+}
+
+fn parse_id(text: &str) -> Result<Id, String> {
+    text.parse()
+        .map_err(|_| format!("`{text}` is not a node ID"))
+}
+
+fn join(ids: &[Id]) -> String {
+    ids.iter().map(Id::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// node and edge counts, out-degree statistics and structural flags.
+fn stats(graph: &Graph<Id, ()>) -> String {
+    let n = graph.nodes.len();
+    let degrees: Vec<usize> = graph
+        .nodes
+        .iter()
+        .map(|id| graph.edges.get(id).map_or(0, |n| n.len()))
+        .collect();
+    let edges: usize = degrees.iter().sum();
+    let self_loops = graph
+        .edges
+        .iter()
+        .map(|(from, tos)| tos.iter().filter(|to| *to == from).count())
+        .sum::<usize>();
+    let isolated = graph
+        .nodes
+        .iter()
+        .filter(|id| {
+            graph.edges.get(id).is_none_or(|n| n.is_empty())
+                && graph.reverse_edges.get(id).is_none_or(|n| n.is_empty())
+        })
+        .count();
+    let density = if n > 1 {
+        edges as f64 / (n * (n - 1)) as f64
+    } else {
+        0.0
+    };
+
+    let mut report = String::new();
+    // writing to a String cannot fail
+    let _ = writeln!(report, "nodes: {n}");
+    let _ = writeln!(report, "directed edges: {edges}");
+    let _ = writeln!(report, "self-loops: {self_loops}");
+    let _ = writeln!(report, "isolated nodes: {isolated}");
+    let _ = writeln!(report, "density: {density:.6}");
+    if n > 0 {
+        let min = degrees.iter().min().copied().unwrap_or_default();
+        let max = degrees.iter().max().copied().unwrap_or_default();
+        let mean = edges as f64 / n as f64;
+        let _ = writeln!(report, "out-degree: min {min}, max {max}, mean {mean:.3}");
+    }
+    let _ = writeln!(report, "undirected: {}", graph.is_undirected());
+    let _ = writeln!(
+        report,
+        "weakly connected components: {}",
+        DynamicConnectivity::from_graph(graph).component_count()
+    );
+    let _ = writeln!(report, "acyclic: {}", graph.topological_sort().is_ok());
+    report
+}
+
+/// the graph in one of the output formats.
+fn convert(graph: &Graph<Id, ()>, format: &str, undirected: bool) -> Result<String, String> {
+    // edges in node order; an undirected graph lists every edge once, from the lower ID
+    let edges: Vec<(Id, Id)> = graph
+        .nodes
+        .iter()
+        .flat_map(|from| {
+            let tos = graph.edges.get(from).map_or(&[][..], |n| &n[..]);
+            tos.iter().map(move |to| (*from, *to))
+        })
+        .filter(|(from, to)| !undirected || from <= to)
+        .collect();
+    let mut output = String::new();
+    match format {
+        "edges" => {
+            for (from, to) in edges {
+                let _ = writeln!(output, "{from} {to}");
+            }
+        }
+        "dot" => {
+            let (keyword, arrow) = if undirected {
+                ("graph", "--")
+            } else {
+                ("digraph", "->")
+            };
+            let _ = writeln!(output, "{keyword} {{");
+            for id in &graph.nodes {
+                let _ = writeln!(output, "    {id};");
+            }
+            for (from, to) in edges {
+                let _ = writeln!(output, "    {from} {arrow} {to};");
+            }
+            output.push_str("}\n");
+        }
+        "json" => {
+            let nodes = graph
+                .nodes
+                .iter()
+                .map(Id::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            let edges = edges
+                .iter()
+                .map(|(from, to)| format!("[{from},{to}]"))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = writeln!(
+                output,
+                "{{\"directed\":{},\"nodes\":[{nodes}],\"edges\":[{edges}]}}",
+                !undirected
+            );
+        }
+        "svg" => {
+            let layout = fruchterman_reingold(graph, &ForceConfig::new());
+            output = render_svg(graph, &layout, &SvgStyle::new());
+        }
+        "ascii" => output = render_ascii(graph, 80, 40),
+        _ => return Err(format!("unknown format `{format}`")),
+    }
+    Ok(output)
+}
+
+/// the weakly connected components, largest first, each sorted by ID.
+fn components(graph: &Graph<Id, ()>) -> String {
+    let connectivity = DynamicConnectivity::from_graph(graph);
+    let mut components: Vec<Vec<Id>> = Vec::new();
+    let mut index = HashMap::new();
+    for id in &graph.nodes {
+        let label = connectivity.component_of(*id);
+        let slot = *index.entry(label).or_insert_with(|| {
+            components.push(Vec::new());
+            components.len() - 1
+        });
+        components[slot].push(*id);
+    }
+    for component in &mut components {
+        component.sort_unstable();
+    }
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    components
+        .iter()
+        .map(|component| join(component) + "\n")
+        .collect()
+}
@@ -0,0 +1,420 @@
+//! Loopy belief propagation on discrete factor graphs.
+//!
+//! A [`FactorGraph`] is a bipartite structure of variables, each with a finite number of states
+//! and a prior, and factors, each a table over the joint states of the variables it touches.
+//! [`BeliefPropagation`] passes messages between the two sides until they stop changing and returns
+//! approximate marginals (sum-product) or max-marginals (max-product). On trees the result is exact.
+
+use core::hash::Hash;
+use std::fmt::Debug;
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// A node of the bipartite graph returned by `FactorGraph::to_graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FactorNode<IDDataType> {
+    /// A variable, by ID.
+    Variable(IDDataType),
+    /// A factor, by the index returned from `FactorGraph::add_factor`.
+    Factor(usize),
+}
+
+/// A factor: a non-negative table over the joint states of its scope.
+#[derive(Debug, Clone, PartialEq)]
+struct Factor {
+    scope: Vec<usize>,
+    table: Vec<f64>,
+}
+
+/// Discrete variables connected by factors.
+///
+/// # Example
+///
+/// Two binary variables that prefer to agree, one of which is biased:
+///
+/// ```
+/// use grafferous::bp::{BeliefPropagation, FactorGraph};
+///
+/// let mut fg = FactorGraph::new();
+/// fg.add_variable("rain", 2);
+/// fg.add_variable("wet", 2);
+/// fg.set_prior("rain", vec![0.8, 0.2]);
+/// fg.add_factor(&["rain", "wet"], vec![0.9, 0.1, 0.2, 0.8]);
+///
+/// let beliefs = BeliefPropagation::sum_product().run(&fg);
+/// assert!(beliefs.converged);
+/// assert!((beliefs.marginals["wet"][1] - 0.24).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    variables: Vec<IDDataType>,
+    index: NodeMap<IDDataType, usize>,
+    states: Vec<usize>,
+    priors: Vec<Vec<f64>>,
+    factors: Vec<Factor>,
+}
+
+impl<IDDataType> FactorGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Creates an empty factor graph.
+    pub fn new() -> Self {
+        Self {
+            variables: Vec::new(),
+            index: NodeMap::default(),
+            states: Vec::new(),
+            priors: Vec::new(),
+            factors: Vec::new(),
+        }
+    }
+
+    /// Builds a pairwise Markov random field over `graph`: every node becomes a variable with
+    /// `states` states and the prior returned by `prior`, and every edge becomes a factor with
+    /// the `states * states` table `coupling`. An undirected edge, stored in both directions,
+    /// gives a single factor.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The topology of the field.
+    /// * `states` - The number of states of every variable.
+    /// * `coupling` - The pairwise table, indexed by `from_state * states + to_state`.
+    /// * `prior` - Computes a variable's prior from its node data.
+    ///
+    pub fn from_pairwise<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        states: usize,
+        coupling: &[f64],
+        prior: impl Fn(&NodeDataType) -> Vec<f64>,
+    ) -> Self {
+        let mut fg = Self::new();
+        for id in &graph.nodes {
+            fg.add_variable(*id, states);
+            fg.set_prior(*id, prior(&graph.node_data[id]));
+        }
+        let mut seen = NodeSet::default();
+        for from in &graph.nodes {
+            for to in graph.edges.get(from).into_iter().flatten() {
+                if from != to && !seen.contains(&(*to, *from)) && seen.insert((*from, *to)) {
+                    fg.add_factor(&[*from, *to], coupling.to_vec());
+                }
+            }
+        }
+        fg
+    }
+
+    /// Adds a variable with the given number of states and a uniform prior.
+    /// If the variable already exists, this function will log a warning and do nothing.
+    pub fn add_variable(&mut self, id: IDDataType, states: usize) {
+        if self.index.contains_key(&id) {
+            log::warn!("attempt to add variable {:?}, that already exists", id);
+            return;
+        }
+        self.index.insert(id, self.variables.len());
+        self.variables.push(id);
+        self.states.push(states);
+        self.priors.push(vec![1.0; states]);
+    }
+
+    /// Sets the prior (unary potential) of a variable; it does not need to be normalized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable does not exist or `prior` does not have one entry per state.
+    pub fn set_prior(&mut self, id: IDDataType, prior: Vec<f64>) {
+        let v = self.index[&id];
+        assert_eq!(
+            prior.len(),
+            self.states[v],
+            "prior of {:?} has the wrong length",
+            id
+        );
+        self.priors[v] = prior;
+    }
+
+    /// Adds a factor over `scope` and returns its index.
+    ///
+    /// The table lists the factor's value for every joint state in row-major order, i.e. the
+    /// state of the first variable in `scope` changes slowest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable of the scope does not exist or the table has the wrong length.
+    pub fn add_factor(&mut self, scope: &[IDDataType], table: Vec<f64>) -> usize {
+        let scope: Vec<usize> = scope.iter().map(|id| self.index[id]).collect();
+        let size: usize = scope.iter().map(|&v| self.states[v]).product();
+        assert_eq!(table.len(), size, "factor table has the wrong length");
+        self.factors.push(Factor { scope, table });
+        self.factors.len() - 1
+    }
+
+    /// The number of variables.
+    pub fn variable_count(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// The number of factors.
+    pub fn factor_count(&self) -> usize {
+        self.factors.len()
+    }
+
+    /// The bipartite graph connecting every factor with the variables of its scope, in both directions.
+    pub fn to_graph(&self) -> Graph<FactorNode<IDDataType>, ()> {
+        let mut graph = Graph::new();
+        for id in &self.variables {
+            graph.add_node(FactorNode::Variable(*id));
+        }
+        for (f, factor) in self.factors.iter().enumerate() {
+            graph.add_node(FactorNode::Factor(f));
+            for &v in &factor.scope {
+                graph.add_edge(
+                    FactorNode::Factor(f),
+                    FactorNode::Variable(self.variables[v]),
+                );
+            }
+        }
+        graph
+    }
+}
+
+impl<IDDataType> Default for FactorGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether messages marginalize by summing or by maximizing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Semiring {
+    /// Computes marginal probabilities.
+    SumProduct,
+    /// Computes max-marginals, whose argmax is the most probable joint assignment.
+    MaxProduct,
+}
+
+/// The result of `BeliefPropagation::run`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Beliefs<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The normalized belief of every variable.
+    pub marginals: NodeMap<IDDataType, Vec<f64>>,
+    /// The number of message-passing iterations that were run.
+    pub iterations: usize,
+    /// Whether the messages converged before the iteration limit.
+    pub converged: bool,
+}
+
+impl<IDDataType> Beliefs<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The most likely state of every variable according to its belief.
+    /// With max-product this is the most probable joint assignment, if it is unique.
+    pub fn assignment(&self) -> NodeMap<IDDataType, usize> {
+        self.marginals
+            .iter()
+            .map(|(id, belief)| {
+                let best =
+                    (0..belief.len())
+                        .fold(0, |best, s| if belief[s] > belief[best] { s } else { best });
+                (*id, best)
+            })
+            .collect()
+    }
+}
+
+/// Configuration of a belief propagation run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeliefPropagation {
+    semiring: Semiring,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+}
+
+impl BeliefPropagation {
+    /// Message passing in the given semiring, without damping, until messages change by less than
+    /// `1e-9`, for at most 100 iterations.
+    pub fn new(semiring: Semiring) -> Self {
+        Self {
+            semiring,
+            damping: 0.0,
+            tolerance: 1e-9,
+            max_iterations: 100,
+        }
+    }
+
+    /// Shorthand for `BeliefPropagation::new(Semiring::SumProduct)`.
+    pub fn sum_product() -> Self {
+        Self::new(Semiring::SumProduct)
+    }
+
+    /// Shorthand for `BeliefPropagation::new(Semiring::MaxProduct)`.
+    pub fn max_product() -> Self {
+        Self::new(Semiring::MaxProduct)
+    }
+
+    /// Mixes every new message with the previous one: `damping * old + (1 - damping) * new`.
+    /// Values around `0.5` help loopy graphs that oscillate instead of converging.
+    pub fn damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// The largest change of any message entry at which the messages count as converged.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The maximum number of iterations, each updating every message once.
+    pub fn max_iterations(mut self, iterations: usize) -> Self {
+        self.max_iterations = iterations;
+        self
+    }
+
+    /// Runs message passing with a flooding schedule and returns the resulting beliefs.
+    pub fn run<IDDataType>(&self, fg: &FactorGraph<IDDataType>) -> Beliefs<IDDataType>
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        // messages are stored per factor and position in its scope
+        let uniform = |v: usize| vec![1.0 / fg.states[v] as f64; fg.states[v]];
+        let mut to_factor: Vec<Vec<Vec<f64>>> = fg
+            .factors
+            .iter()
+            .map(|f| f.scope.iter().map(|&v| uniform(v)).collect())
+            .collect();
+        let mut to_variable = to_factor.clone();
+        let mut incident: Vec<Vec<(usize, usize)>> = vec![Vec::new(); fg.variables.len()];
+        for (f, factor) in fg.factors.iter().enumerate() {
+            for (k, &v) in factor.scope.iter().enumerate() {
+                incident[v].push((f, k));
+            }
+        }
+
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < self.max_iterations && !converged {
+            iterations += 1;
+            let mut change: f64 = 0.0;
+
+            for (v, factors) in incident.iter().enumerate() {
+                for &(f, k) in factors {
+                    let mut message = fg.priors[v].clone();
+                    for &(g, j) in factors {
+                        if (g, j) != (f, k) {
+                            multiply(&mut message, &to_variable[g][j]);
+                        }
+                    }
+                    change = change.max(self.store(&mut to_factor[f][k], message));
+                }
+            }
+
+            for (f, factor) in fg.factors.iter().enumerate() {
+                for (k, &v) in factor.scope.iter().enumerate() {
+                    let message = self.factor_message(fg, factor, &to_factor[f], k, fg.states[v]);
+                    change = change.max(self.store(&mut to_variable[f][k], message));
+                }
+            }
+
+            converged = change < self.tolerance;
+        }
+
+        let marginals = incident
+            .iter()
+            .enumerate()
+            .map(|(v, factors)| {
+                let mut belief = fg.priors[v].clone();
+                for &(f, k) in factors {
+                    multiply(&mut belief, &to_variable[f][k]);
+                }
+                normalize(&mut belief);
+                (fg.variables[v], belief)
+            })
+            .collect();
+
+        Beliefs {
+            marginals,
+            iterations,
+            converged,
+        }
+    }
+
+    /// computes the message from a factor to the variable at position `k` of its scope.
+    fn factor_message<IDDataType>(
+        &self,
+        fg: &FactorGraph<IDDataType>,
+        factor: &Factor,
+        incoming: &[Vec<f64>],
+        k: usize,
+        states: usize,
+    ) -> Vec<f64>
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let sizes: Vec<usize> = factor.scope.iter().map(|&v| fg.states[v]).collect();
+        let mut message = vec![0.0; states];
+        let mut assignment = vec![0; sizes.len()];
+        for value in &factor.table {
+            let mut product = *value;
+            for (j, &state) in assignment.iter().enumerate() {
+                if j != k {
+                    product *= incoming[j][state];
+                }
+            }
+            let entry = &mut message[assignment[k]];
+            *entry = match self.semiring {
+                Semiring::SumProduct => *entry + product,
+                Semiring::MaxProduct => entry.max(product),
+            };
+
+            // advance the row-major assignment, last variable fastest
+            for j in (0..sizes.len()).rev() {
+                assignment[j] += 1;
+                if assignment[j] < sizes[j] {
+                    break;
+                }
+                assignment[j] = 0;
+            }
+        }
+        message
+    }
+
+    /// normalizes and damps `message`, stores it and returns the largest change.
+    fn store(&self, old: &mut [f64], mut message: Vec<f64>) -> f64 {
+        normalize(&mut message);
+        let mut change: f64 = 0.0;
+        for (o, n) in old.iter_mut().zip(message) {
+            let damped = self.damping * *o + (1.0 - self.damping) * n;
+            change = change.max((damped - *o).abs());
+            *o = damped;
+        }
+        change
+    }
+}
+
+fn multiply(target: &mut [f64], factor: &[f64]) {
+    for (t, f) in target.iter_mut().zip(factor) {
+        *t *= f;
+    }
+}
+
+/// scales to sum one, leaving all-zero vectors untouched.
+fn normalize(values: &mut [f64]) {
+    let total: f64 = values.iter().sum();
+    if total > 0.0 {
+        values.iter_mut().for_each(|v| *v /= total);
+    }
+}
@@ -0,0 +1,234 @@
+//! A fluent builder that collects nodes and edges and validates them when the graph is built.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeSet, Graph, GraphError};
+
+/// What `GraphBuilder::build` does with a node or edge that was given more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Fails with `GraphError::DuplicateNode` or `GraphError::DuplicateEdge`.
+    Reject,
+    /// Keeps the first node or edge and drops the later ones.
+    Ignore,
+    /// Keeps every edge as a parallel edge; a later node replaces the data of an earlier one.
+    Allow,
+}
+
+/// Collects nodes and edges with chained calls and turns them into a `Graph` with `build`.
+///
+/// By default a node given twice is an error, an edge given twice is added once, and edges may
+/// refer to nodes that were never declared, which are then added with the default data.
+///
+/// For large graphs, `with_capacity` sizes the builder and the graph for the expected number of
+/// nodes and edges up front, and the `extend_` methods add them in bulk from iterators.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{builder::DuplicatePolicy, GraphBuilder, GraphError};
+///
+/// let g = GraphBuilder::<u32, &str>::new()
+///     .node_with(0, "source")
+///     .node(1)
+///     .directed(0, 1)
+///     .edge(1, 2)
+///     .edge(1, 2)
+///     .build()
+///     .unwrap();
+/// assert_eq!(g.nodes, vec![0, 1, 2]);
+/// assert_eq!(g.edge_tuples().len(), 3);
+///
+/// let strict = GraphBuilder::<u32, ()>::new()
+///     .directed(0, 1)
+///     .directed(0, 1)
+///     .duplicate_edges(DuplicatePolicy::Reject)
+///     .build();
+/// assert_eq!(strict.unwrap_err(), GraphError::DuplicateEdge(0, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<IDDataType, NodeDataType> {
+    nodes: Vec<(IDDataType, NodeDataType)>,
+    edges: Vec<(IDDataType, IDDataType)>,
+    duplicate_nodes: DuplicatePolicy,
+    duplicate_edges: DuplicatePolicy,
+    require_nodes: bool,
+    /// the number of nodes to make room for in the graph, see `with_capacity`
+    node_capacity: usize,
+}
+
+impl<IDDataType, NodeDataType: Default> GraphBuilder<IDDataType, NodeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// An empty builder with the default validation.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            duplicate_nodes: DuplicatePolicy::Reject,
+            duplicate_edges: DuplicatePolicy::Ignore,
+            require_nodes: false,
+            node_capacity: 0,
+        }
+    }
+
+    /// An empty builder with room for `nodes` nodes and `edges` directed edges, which also sizes
+    /// the maps of the graph `build` returns, so neither rehashes while it grows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::GraphBuilder;
+    ///
+    /// let n = 10_000;
+    /// let g = GraphBuilder::<usize, ()>::with_capacity(n, n - 1)
+    ///     .extend_nodes(0..n)
+    ///     .extend_directed((1..n).map(|id| (id - 1, id)))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(g.node_count(), n);
+    /// assert_eq!(g.edge_count(), n - 1);
+    /// ```
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Self {
+            nodes: Vec::with_capacity(nodes),
+            edges: Vec::with_capacity(edges),
+            node_capacity: nodes,
+            ..Self::new()
+        }
+    }
+
+    /// Declares a node with the default data.
+    pub fn node(self, id: IDDataType) -> Self {
+        self.node_with(id, NodeDataType::default())
+    }
+
+    /// Declares a node with the given data.
+    pub fn node_with(mut self, id: IDDataType, data: NodeDataType) -> Self {
+        self.nodes.push((id, data));
+        self
+    }
+
+    /// Adds an edge in both directions; a self-loop is added once.
+    pub fn edge(mut self, a: IDDataType, b: IDDataType) -> Self {
+        self.edges.push((a, b));
+        if a != b {
+            self.edges.push((b, a));
+        }
+        self
+    }
+
+    /// Adds an edge from `from` to `to`.
+    pub fn directed(mut self, from: IDDataType, to: IDDataType) -> Self {
+        self.edges.push((from, to));
+        self
+    }
+
+    /// Declares every node of an iterator with the default data.
+    pub fn extend_nodes(self, ids: impl IntoIterator<Item = IDDataType>) -> Self {
+        self.extend_nodes_with(ids.into_iter().map(|id| (id, NodeDataType::default())))
+    }
+
+    /// Declares every node of an iterator of IDs and data.
+    pub fn extend_nodes_with(
+        mut self,
+        nodes: impl IntoIterator<Item = (IDDataType, NodeDataType)>,
+    ) -> Self {
+        self.nodes.extend(nodes);
+        self
+    }
+
+    /// Adds every edge of an iterator in both directions, like `edge`.
+    pub fn extend_edges(
+        mut self,
+        edges: impl IntoIterator<Item = (IDDataType, IDDataType)>,
+    ) -> Self {
+        for (a, b) in edges {
+            self = self.edge(a, b);
+        }
+        self
+    }
+
+    /// Adds every edge of an iterator from its first to its second node, like `directed`.
+    pub fn extend_directed(
+        mut self,
+        edges: impl IntoIterator<Item = (IDDataType, IDDataType)>,
+    ) -> Self {
+        self.edges.extend(edges);
+        self
+    }
+
+    /// What to do with a node declared more than once, `DuplicatePolicy::Reject` by default.
+    pub fn duplicate_nodes(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_nodes = policy;
+        self
+    }
+
+    /// What to do with a directed edge given more than once, `DuplicatePolicy::Ignore` by default.
+    /// An undirected edge counts as both of its directed edges.
+    pub fn duplicate_edges(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_edges = policy;
+        self
+    }
+
+    /// Whether edges may only connect declared nodes; if so, `build` fails with
+    /// `GraphError::MissingNode` for the first undeclared one.
+    pub fn require_nodes(mut self, require: bool) -> Self {
+        self.require_nodes = require;
+        self
+    }
+
+    /// Validates the nodes and edges and builds the graph.
+    ///
+    /// Declared nodes come first in the node list, followed by undeclared nodes in the order
+    /// their first edge was given.
+    pub fn build(self) -> Result<Graph<IDDataType, NodeDataType>, GraphError<IDDataType>> {
+        let mut graph = Graph::with_capacity(self.node_capacity.max(self.nodes.len()));
+        for (id, data) in self.nodes {
+            match graph.node_data.get_mut(&id) {
+                None => graph.add_node_with_data(id, data),
+                Some(existing) => match self.duplicate_nodes {
+                    DuplicatePolicy::Reject => return Err(GraphError::DuplicateNode(id)),
+                    DuplicatePolicy::Ignore => {}
+                    DuplicatePolicy::Allow => *existing = data,
+                },
+            }
+        }
+
+        let mut seen = match self.duplicate_edges {
+            DuplicatePolicy::Allow => NodeSet::default(),
+            _ => NodeSet::with_capacity_and_hasher(self.edges.len(), Default::default()),
+        };
+        let mut edges = Vec::with_capacity(self.edges.len());
+        for (from, to) in self.edges {
+            if self.require_nodes {
+                if let Some(missing) = [from, to]
+                    .into_iter()
+                    .find(|id| !graph.node_data.contains_key(id))
+                {
+                    return Err(GraphError::MissingNode(missing));
+                }
+            }
+            if self.duplicate_edges != DuplicatePolicy::Allow && !seen.insert((from, to)) {
+                if self.duplicate_edges == DuplicatePolicy::Reject {
+                    return Err(GraphError::DuplicateEdge(from, to));
+                }
+                continue;
+            }
+            edges.push((from, to));
+        }
+        graph.add_directed_edges(&edges);
+        Ok(graph)
+    }
+}
+
+impl<IDDataType, NodeDataType: Default> Default for GraphBuilder<IDDataType, NodeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
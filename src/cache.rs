@@ -0,0 +1,251 @@
+use alloc::boxed::Box;
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+
+use crate::collections::{NodeMap, NodeSet};
+use crate::{Adjacency, CompactGraph};
+
+const UNKNOWN: u8 = 0;
+const FALSE: u8 = 1;
+const TRUE: u8 = 2;
+
+/// A lazily computed boolean property of a graph.
+///
+/// The value is stored atomically so the graph stays `Sync`, and it is ignored by
+/// equality comparisons since two graphs with the same content are equal whether or
+/// not the property has been computed yet.
+#[derive(Default)]
+pub(crate) struct CachedFlag(AtomicU8);
+
+impl CachedFlag {
+    /// Returns the cached value, if it has been computed since the last invalidation.
+    pub(crate) fn get(&self) -> Option<bool> {
+        match self.0.load(Ordering::Relaxed) {
+            FALSE => Some(false),
+            TRUE => Some(true),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn set(&self, value: bool) {
+        self.0
+            .store(if value { TRUE } else { FALSE }, Ordering::Relaxed);
+    }
+
+    /// Forgets the cached value so it is recomputed on the next query.
+    pub(crate) fn invalidate(&mut self) {
+        *self.0.get_mut() = UNKNOWN;
+    }
+}
+
+impl Clone for CachedFlag {
+    fn clone(&self) -> Self {
+        Self(AtomicU8::new(self.0.load(Ordering::Relaxed)))
+    }
+}
+
+impl PartialEq for CachedFlag {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for CachedFlag {}
+
+impl Debug for CachedFlag {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+/// The ID that `Graph::add_node_auto` tries first, one past the last ID it handed out, or `None`
+/// before it hands out any.
+///
+/// Like a cached flag it is ignored by equality comparisons, since it does not change which
+/// nodes and edges a graph has.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NextId<IDDataType>(pub(crate) Option<IDDataType>);
+
+impl<IDDataType> Default for NextId<IDDataType> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<IDDataType> PartialEq for NextId<IDDataType> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<IDDataType> Eq for NextId<IDDataType> {}
+
+/// The set of distinct directed edges of a graph, so the edge policy can reject a parallel edge
+/// without scanning adjacency lists.
+///
+/// It is only built once a policy that forbids parallel edges asks for it, kept up to date by
+/// the methods that add and remove single edges, and forgotten by the bulk ones, which rebuild it
+/// on the next query. Like a cached flag it is ignored by equality comparisons.
+#[derive(Debug, Clone)]
+pub(crate) struct EdgeIndex<IDDataType>(Option<NodeSet<(IDDataType, IDDataType)>>);
+
+impl<IDDataType> EdgeIndex<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// whether `edges` has an edge from `from` to `to`, building the index from `edges` first if
+    /// it has been forgotten.
+    pub(crate) fn contains(
+        &mut self,
+        edges: &NodeMap<IDDataType, Adjacency<IDDataType>>,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> bool {
+        self.0
+            .get_or_insert_with(|| {
+                edges
+                    .iter()
+                    .flat_map(|(from, tos)| tos.iter().map(move |to| (*from, *to)))
+                    .collect()
+            })
+            .contains(&(from, to))
+    }
+
+    pub(crate) fn insert(&mut self, from: IDDataType, to: IDDataType) {
+        if let Some(edges) = self.0.as_mut() {
+            edges.insert((from, to));
+        }
+    }
+
+    /// records that the last copy of the edge from `from` to `to` is gone.
+    pub(crate) fn remove(&mut self, from: IDDataType, to: IDDataType) {
+        if let Some(edges) = self.0.as_mut() {
+            edges.remove(&(from, to));
+        }
+    }
+
+    /// forgets the index so it is rebuilt on the next query.
+    pub(crate) fn invalidate(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl<IDDataType> Default for EdgeIndex<IDDataType> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<IDDataType> PartialEq for EdgeIndex<IDDataType> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<IDDataType> Eq for EdgeIndex<IDDataType> {}
+
+/// The index-based core of a graph, see `Graph::core`: a [`CompactGraph`] built on first use and
+/// dropped by every method that changes the nodes or edges.
+///
+/// Like the cached flag it can be filled in through a shared reference and keeps the graph
+/// `Sync`; if two threads build it at once, the first one to finish wins and the other copy is
+/// dropped. It is not cloned along with the graph and is ignored by equality comparisons.
+pub(crate) struct CompactCache<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    core: AtomicPtr<CompactGraph<IDDataType>>,
+    /// the cache owns the core, which keeps the auto traits of the graph honest
+    _owns: PhantomData<Box<CompactGraph<IDDataType>>>,
+}
+
+impl<IDDataType> CompactCache<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// the cached core, built with `build` if there is none.
+    pub(crate) fn get_or_build(
+        &self,
+        build: impl FnOnce() -> CompactGraph<IDDataType>,
+    ) -> &CompactGraph<IDDataType> {
+        let current = self.core.load(Ordering::Acquire);
+        if !current.is_null() {
+            // SAFETY: a non-null pointer comes from `Box::into_raw` below and is only freed
+            // through `&mut self`, so it stays valid for as long as `self` is borrowed.
+            return unsafe { &*current };
+        }
+        let built = Box::into_raw(Box::new(build()));
+        match self.core.compare_exchange(
+            ptr::null_mut(),
+            built,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            // SAFETY: as above, the cache now owns `built`.
+            Ok(_) => unsafe { &*built },
+            Err(winner) => {
+                // SAFETY: `built` was never shared, and `winner` is owned by the cache as above.
+                unsafe {
+                    drop(Box::from_raw(built));
+                    &*winner
+                }
+            }
+        }
+    }
+
+    /// drops the core so it is rebuilt on next use.
+    pub(crate) fn invalidate(&mut self) {
+        let current = core::mem::replace(self.core.get_mut(), ptr::null_mut());
+        if !current.is_null() {
+            // SAFETY: the pointer comes from `Box::into_raw` and `&mut self` rules out borrows.
+            drop(unsafe { Box::from_raw(current) });
+        }
+    }
+}
+
+impl<IDDataType> Default for CompactCache<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self {
+            core: AtomicPtr::new(ptr::null_mut()),
+            _owns: PhantomData,
+        }
+    }
+}
+
+impl<IDDataType> Drop for CompactCache<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn drop(&mut self) {
+        self.invalidate();
+    }
+}
+
+impl<IDDataType> Clone for CompactCache<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<IDDataType> PartialEq for CompactCache<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<IDDataType> Eq for CompactCache<IDDataType> where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy
+{
+}
@@ -0,0 +1,477 @@
+//! Measures of how important or how close to a reference set the nodes of a graph are.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use rand::Rng;
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Computes personalized PageRank scores by power iteration.
+    ///
+    /// The scores are the stationary distribution of a random walk that follows a uniformly
+    /// chosen outgoing edge, but jumps back to a uniformly chosen seed with probability `alpha`
+    /// in every step (and always when it is at a node without outgoing edges). The scores sum
+    /// to one and measure how close each node is to the seed set.
+    ///
+    /// Iterates until the scores change by less than `1e-10` in total, for at most 1000 iterations.
+    /// Seeds that are not in the graph are ignored; if no seed is, the result is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - The nodes the walk restarts at.
+    /// * `alpha` - The restart probability, typically around `0.15`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_cycle_graph;
+    ///
+    /// let g = generate_cycle_graph::<()>(10);
+    /// let scores = g.personalized_pagerank(&[0], 0.15);
+    ///
+    /// assert!(scores[&0] > scores[&1] && scores[&1] > scores[&5]);
+    /// assert!((scores[&1] - scores[&9]).abs() < 1e-9);
+    /// ```
+    pub fn personalized_pagerank(
+        &self,
+        seeds: &[IDDataType],
+        alpha: f64,
+    ) -> NodeMap<IDDataType, f64> {
+        self.power_iteration(seeds, alpha, 1000, 1e-10)
+    }
+
+    /// Computes PageRank scores by power iteration: the stationary distribution of a random walk
+    /// that follows a uniformly chosen outgoing edge with probability `damping` and otherwise
+    /// jumps to a uniformly chosen node, as it always does at a node without outgoing edges. The
+    /// scores sum to one and are high for nodes that many important nodes link to.
+    ///
+    /// # Arguments
+    ///
+    /// * `damping` - The probability of following an edge, typically `0.85`.
+    /// * `max_iterations` - The most iterations to run.
+    /// * `tolerance` - Iteration stops once the scores change by less than this in total.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // everyone links to 0, which links back to 1
+    /// let g = Graph::<u32, ()>::parse("1 -> 0; 2 -> 0; 3 -> 0; 0 -> 1").unwrap();
+    /// let scores = g.pagerank(0.85, 100, 1e-10);
+    ///
+    /// assert!(scores[&0] > scores[&1] && scores[&1] > scores[&2]);
+    /// assert!((scores.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn pagerank(
+        &self,
+        damping: f64,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> NodeMap<IDDataType, f64> {
+        self.power_iteration(&self.nodes, 1.0 - damping, max_iterations, tolerance)
+    }
+
+    /// The degree centrality of every node: the fraction of the other nodes it is linked to, in
+    /// either direction. Parallel edges count once and self-loops not at all. In a graph with a
+    /// single node, its centrality is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let star = Graph::<u32, ()>::parse("0 -- 1; 0 -- 2; 0 -> 3; 4").unwrap();
+    /// let centrality = star.degree_centrality();
+    /// assert_eq!(centrality[&0], 0.75);
+    /// assert_eq!(centrality[&3], 0.25);
+    /// assert_eq!(centrality[&4], 0.0);
+    /// ```
+    pub fn degree_centrality(&self) -> NodeMap<IDDataType, f64> {
+        let others = self.nodes.len().saturating_sub(1).max(1) as f64;
+        self.nodes
+            .iter()
+            .copied()
+            .zip(self.undirected_sets())
+            .map(|(id, neighbors)| (id, neighbors.len() as f64 / others))
+            .collect()
+    }
+
+    /// the random walk with restarts to the seeds behind both PageRank variants.
+    fn power_iteration(
+        &self,
+        seeds: &[IDDataType],
+        alpha: f64,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> NodeMap<IDDataType, f64> {
+        let index: NodeMap<IDDataType, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let seeds: NodeSet<usize> = seeds.iter().filter_map(|s| index.get(s).copied()).collect();
+        if seeds.is_empty() {
+            return NodeMap::default();
+        }
+        let restart = 1.0 / seeds.len() as f64;
+        let neighbors: Vec<Vec<usize>> = self
+            .nodes
+            .iter()
+            .map(|id| {
+                self.edges
+                    .get(id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|to| index.get(to).copied())
+                    .collect()
+            })
+            .collect();
+
+        let mut scores = vec![0.0; self.nodes.len()];
+        for &seed in &seeds {
+            scores[seed] = restart;
+        }
+        let mut next = vec![0.0; self.nodes.len()];
+        for _ in 0..max_iterations {
+            next.iter_mut().for_each(|score| *score = 0.0);
+            let mut returning = alpha;
+            for (i, score) in scores.iter().enumerate() {
+                if neighbors[i].is_empty() {
+                    returning += (1.0 - alpha) * score;
+                } else {
+                    let share = (1.0 - alpha) * score / neighbors[i].len() as f64;
+                    for &j in &neighbors[i] {
+                        next[j] += share;
+                    }
+                }
+            }
+            for &seed in &seeds {
+                next[seed] += returning * restart;
+            }
+
+            let change: f64 = scores.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+            core::mem::swap(&mut scores, &mut next);
+            if change < tolerance {
+                break;
+            }
+        }
+
+        self.nodes.iter().copied().zip(scores).collect()
+    }
+
+    /// Approximates personalized PageRank by local pushes, touching only the part of the graph near the seeds.
+    ///
+    /// This is the push algorithm of Andersen, Chung and Lang: probability mass is kept as a residual
+    /// and pushed to the neighbors of a node until every node's residual is below `epsilon` times its
+    /// out-degree. The running time depends on `alpha` and `epsilon` but not on the size of the graph,
+    /// which makes it suitable for local recommendation on large graphs. Every score underestimates the
+    /// exact value of `personalized_pagerank` by at most `epsilon` times the node's out-degree (or `epsilon`
+    /// for nodes without outgoing edges). Only nodes with a non-zero score are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - The nodes the walk restarts at.
+    /// * `alpha` - The restart probability, typically around `0.15`.
+    /// * `epsilon` - The residual tolerance; smaller values are more accurate and slower.
+    ///
+    pub fn approximate_personalized_pagerank(
+        &self,
+        seeds: &[IDDataType],
+        alpha: f64,
+        epsilon: f64,
+    ) -> NodeMap<IDDataType, f64> {
+        let seeds: Vec<IDDataType> = seeds
+            .iter()
+            .copied()
+            .filter(|s| self.node_data.contains_key(s))
+            .collect::<NodeSet<_>>()
+            .into_iter()
+            .collect();
+        let mut scores = NodeMap::default();
+        if seeds.is_empty() {
+            return scores;
+        }
+        let restart = 1.0 / seeds.len() as f64;
+        let degree = |id: &IDDataType| self.edges.get(id).map_or(0, |n| n.len());
+        let threshold = |id: &IDDataType| epsilon * degree(id).max(1) as f64;
+
+        let mut residuals: NodeMap<IDDataType, f64> = NodeMap::default();
+        let mut queue: VecDeque<IDDataType> = VecDeque::new();
+        for seed in &seeds {
+            residuals.insert(*seed, restart);
+            queue.push_back(*seed);
+        }
+
+        while let Some(id) = queue.pop_front() {
+            let residual = residuals.get(&id).copied().unwrap_or(0.0);
+            if residual < threshold(&id) {
+                continue;
+            }
+            residuals.insert(id, 0.0);
+            *scores.entry(id).or_insert(0.0) += alpha * residual;
+
+            let pushed = (1.0 - alpha) * residual;
+            let neighbors = self.edges.get(&id).map_or(&[][..], |n| &n[..]);
+            let (targets, share) = if neighbors.is_empty() {
+                (&seeds[..], pushed * restart)
+            } else {
+                (neighbors, pushed / neighbors.len() as f64)
+            };
+            for to in targets {
+                let r = residuals.entry(*to).or_insert(0.0);
+                let before = *r;
+                *r += share;
+                if before < threshold(to) && *r >= threshold(to) {
+                    queue.push_back(*to);
+                }
+            }
+        }
+
+        scores
+    }
+
+    /// The betweenness centrality of every node: the fraction of the shortest paths between
+    /// every ordered pair of other nodes that pass through it, summed over the pairs. Paths follow
+    /// edge directions and have the fewest edges; parallel edges count once.
+    ///
+    /// This is Brandes' algorithm, one breadth-first search per node, which takes `O(V·E)` time.
+    /// On an undirected graph every pair is counted in both directions, which doubles the values
+    /// of the usual undirected definition. With `normalized`, the values are divided by the
+    /// number of ordered pairs of other nodes, `(n - 1)(n - 2)`, so they lie between zero and one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // every path between the two ends passes through the middle
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2").unwrap();
+    /// let betweenness = g.betweenness_centrality(false);
+    /// assert_eq!(betweenness[&1], 2.0);
+    /// assert_eq!(betweenness[&0], 0.0);
+    /// assert_eq!(g.betweenness_centrality(true)[&1], 1.0);
+    /// ```
+    pub fn betweenness_centrality(&self, normalized: bool) -> NodeMap<IDDataType, f64> {
+        let sources: Vec<usize> = (0..self.nodes.len()).collect();
+        self.brandes(&sources, normalized)
+    }
+
+    /// Estimates `betweenness_centrality` from the shortest paths that start at `samples`
+    /// randomly chosen nodes, scaled up to all nodes. This takes `O(samples·E)` time, and the
+    /// error shrinks with the square root of `samples`; the result is exact if `samples` is at
+    /// least the number of nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of source nodes to sample.
+    /// * `normalized` - Whether to divide by the number of ordered pairs of other nodes.
+    /// * `rng` - The source of randomness for picking the sources.
+    ///
+    pub fn approximate_betweenness_centrality<R: Rng>(
+        &self,
+        samples: usize,
+        normalized: bool,
+        mut rng: R,
+    ) -> NodeMap<IDDataType, f64> {
+        let sources = sample_indices(self.nodes.len(), samples, &mut rng);
+        self.brandes(&sources, normalized)
+    }
+
+    /// The closeness centrality of every node: how short the paths from it to the nodes it
+    /// reaches are. For a node that reaches `r - 1` other nodes at a total distance of `d`, this is
+    /// `(r - 1) / d` scaled by the fraction `(r - 1) / (n - 1)` of the other nodes it reaches
+    /// (the Wasserman-Faust variant, which stays meaningful on graphs that are not connected).
+    /// Distances count edges and follow edge directions; nodes that reach nothing score zero.
+    ///
+    /// This takes one breadth-first search per node, `O(V·E)` time in total.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3; 4").unwrap();
+    /// let closeness = g.closeness_centrality();
+    /// assert_eq!(closeness[&1], 3.0 / 4.0 * 3.0 / 4.0);
+    /// assert!(closeness[&1] > closeness[&0]);
+    /// assert_eq!(closeness[&4], 0.0);
+    /// ```
+    pub fn closeness_centrality(&self) -> NodeMap<IDDataType, f64> {
+        let (adjacency, _) = self.index_adjacency(false);
+        let n = adjacency.len();
+        let mut distances = vec![usize::MAX; n];
+        let mut queue = VecDeque::new();
+        let mut closeness = NodeMap::default();
+        for (source, id) in self.nodes.iter().enumerate() {
+            distances.iter_mut().for_each(|d| *d = usize::MAX);
+            distances[source] = 0;
+            queue.push_back(source);
+            let (mut reached, mut total) = (0usize, 0usize);
+            while let Some(node) = queue.pop_front() {
+                for next in &adjacency[node] {
+                    if distances[*next] == usize::MAX {
+                        distances[*next] = distances[node] + 1;
+                        reached += 1;
+                        total += distances[*next];
+                        queue.push_back(*next);
+                    }
+                }
+            }
+            closeness.insert(*id, wasserman_faust(reached as f64, total as f64, n));
+        }
+        closeness
+    }
+
+    /// Estimates `closeness_centrality` from the distances to `samples` randomly chosen pivot
+    /// nodes, found with one breadth-first search per pivot along reversed edges (the
+    /// Eppstein-Wang estimator). This takes `O(samples·E)` time; the reached fraction and the
+    /// total distance of every node are extrapolated from the pivots to all nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of pivot nodes to sample.
+    /// * `rng` - The source of randomness for picking the pivots.
+    ///
+    pub fn approximate_closeness_centrality<R: Rng>(
+        &self,
+        samples: usize,
+        mut rng: R,
+    ) -> NodeMap<IDDataType, f64> {
+        let (_, reverse) = self.index_adjacency(true);
+        let n = reverse.len();
+        let pivots = sample_indices(n, samples, &mut rng);
+        // the number of pivots every node reaches, itself excluded, and the total distance
+        let mut reached = vec![0usize; n];
+        let mut total = vec![0usize; n];
+        let mut distances = vec![usize::MAX; n];
+        let mut queue = VecDeque::new();
+        for pivot in &pivots {
+            distances.iter_mut().for_each(|d| *d = usize::MAX);
+            distances[*pivot] = 0;
+            queue.push_back(*pivot);
+            while let Some(node) = queue.pop_front() {
+                for previous in &reverse[node] {
+                    if distances[*previous] == usize::MAX {
+                        distances[*previous] = distances[node] + 1;
+                        reached[*previous] += 1;
+                        total[*previous] += distances[*previous];
+                        queue.push_back(*previous);
+                    }
+                }
+            }
+        }
+        let scale = if pivots.is_empty() {
+            0.0
+        } else {
+            n as f64 / pivots.len() as f64
+        };
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let estimate =
+                    wasserman_faust(reached[i] as f64 * scale, total[i] as f64 * scale, n);
+                (*id, estimate)
+            })
+            .collect()
+    }
+
+    /// Brandes' dependency accumulation over the shortest paths from the given sources, scaled
+    /// up from the sources to all nodes.
+    fn brandes(&self, sources: &[usize], normalized: bool) -> NodeMap<IDDataType, f64> {
+        let (mut adjacency, _) = self.index_adjacency(false);
+        for (u, neighbors) in adjacency.iter_mut().enumerate() {
+            neighbors.retain(|v| *v != u);
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+        let n = adjacency.len();
+        let mut centrality = vec![0.0; n];
+        let mut distances = vec![usize::MAX; n];
+        let mut paths = vec![0.0f64; n];
+        let mut dependency = vec![0.0; n];
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut order = Vec::with_capacity(n);
+        let mut queue = VecDeque::new();
+        for &source in sources {
+            for v in 0..n {
+                distances[v] = usize::MAX;
+                paths[v] = 0.0;
+                dependency[v] = 0.0;
+                predecessors[v].clear();
+            }
+            order.clear();
+            distances[source] = 0;
+            paths[source] = 1.0;
+            queue.push_back(source);
+            while let Some(node) = queue.pop_front() {
+                order.push(node);
+                for next in &adjacency[node] {
+                    if distances[*next] == usize::MAX {
+                        distances[*next] = distances[node] + 1;
+                        queue.push_back(*next);
+                    }
+                    if distances[*next] == distances[node] + 1 {
+                        paths[*next] += paths[node];
+                        predecessors[*next].push(node);
+                    }
+                }
+            }
+            // nodes in order of decreasing distance pass their dependency on to the predecessors
+            for node in order.iter().rev() {
+                for previous in &predecessors[*node] {
+                    dependency[*previous] +=
+                        paths[*previous] / paths[*node] * (1.0 + dependency[*node]);
+                }
+                if *node != source {
+                    centrality[*node] += dependency[*node];
+                }
+            }
+        }
+
+        let mut scale = if sources.is_empty() {
+            0.0
+        } else {
+            n as f64 / sources.len() as f64
+        };
+        if normalized && n > 2 {
+            scale /= ((n - 1) * (n - 2)) as f64;
+        }
+        self.nodes
+            .iter()
+            .copied()
+            .zip(centrality.into_iter().map(|c| c * scale))
+            .collect()
+    }
+}
+
+/// the closeness of a node that reaches `reached` other nodes at a total distance of `total`,
+/// scaled by the fraction of the other nodes it reaches.
+fn wasserman_faust(reached: f64, total: f64, n: usize) -> f64 {
+    if total == 0.0 || n < 2 {
+        return 0.0;
+    }
+    (reached / total) * (reached / (n - 1) as f64)
+}
+
+/// `count` distinct indices below `n` in random order, or all of them if `count >= n`, by a
+/// partial Fisher-Yates shuffle.
+fn sample_indices<R: Rng>(n: usize, count: usize, rng: &mut R) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..n).collect();
+    let count = count.min(n);
+    for i in 0..count {
+        let j = rng.gen_range(i..n);
+        indices.swap(i, j);
+    }
+    indices.truncate(count);
+    indices
+}
@@ -0,0 +1,162 @@
+//! Enumerating the maximal cliques of a graph.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeSet, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Lazily enumerates the maximal cliques: the sets of nodes that are all linked to each
+    /// other and cannot be extended by another node. Edge directions are ignored, parallel edges
+    /// count once and self-loops are left out, so a node without other neighbors is a clique of
+    /// its own. The cliques come in no particular order, the nodes of every clique in the order of
+    /// `nodes`.
+    ///
+    /// This is the Bron–Kerbosch algorithm with pivoting, started from every node in a
+    /// degeneracy order with only its later neighbors as candidates (Eppstein, Löffler and
+    /// Strash). It takes `O(d n 3^(d/3))` time for degeneracy `d`, so it is fast on sparse graphs
+    /// even with many nodes. The adjacency is copied when the iterator is created, so it does not
+    /// borrow the graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 1 -- 3 -- 2; 3 -- 4; 5").unwrap();
+    /// let mut cliques: Vec<Vec<u32>> = g.maximal_cliques().collect();
+    /// cliques.sort();
+    /// assert_eq!(cliques, vec![vec![0, 1, 2], vec![1, 2, 3], vec![3, 4], vec![5]]);
+    /// ```
+    pub fn maximal_cliques(&self) -> impl Iterator<Item = Vec<IDDataType>> {
+        let (order, _) = self.degeneracy_order();
+        MaximalCliques {
+            ids: self.nodes.clone(),
+            adjacency: self.undirected_sets(),
+            rank: {
+                let mut rank = vec![0; order.len()];
+                for (i, node) in order.iter().enumerate() {
+                    rank[*node] = i;
+                }
+                rank
+            },
+            order,
+            next_root: 0,
+            stack: Vec::new(),
+        }
+    }
+}
+
+/// a step of the Bron–Kerbosch search: the clique so far, the nodes that can extend it, the nodes
+/// that could have extended it but were already tried, and the extensions left to try.
+struct Frame {
+    clique: Vec<usize>,
+    candidates: Vec<usize>,
+    excluded: Vec<usize>,
+    branches: Vec<usize>,
+}
+
+struct MaximalCliques<IDDataType> {
+    ids: Vec<IDDataType>,
+    adjacency: Vec<NodeSet<usize>>,
+    /// the nodes by position in a degeneracy order, and the rank of every node in it
+    order: Vec<usize>,
+    rank: Vec<usize>,
+    next_root: usize,
+    stack: Vec<Frame>,
+}
+
+impl<IDDataType: Copy> MaximalCliques<IDDataType> {
+    /// a frame that branches on the candidates that are not neighbors of a pivot, the node of
+    /// either list with the most neighbors among the candidates.
+    fn frame(&self, clique: Vec<usize>, candidates: Vec<usize>, excluded: Vec<usize>) -> Frame {
+        let pivot = candidates
+            .iter()
+            .chain(&excluded)
+            .max_by_key(|u| {
+                let neighbors = &self.adjacency[**u];
+                candidates.iter().filter(|v| neighbors.contains(*v)).count()
+            })
+            .copied();
+        let branches = match pivot {
+            Some(pivot) => candidates
+                .iter()
+                .copied()
+                .filter(|v| !self.adjacency[pivot].contains(v))
+                .collect(),
+            None => Vec::new(),
+        };
+        Frame {
+            clique,
+            candidates,
+            excluded,
+            branches,
+        }
+    }
+
+    /// the clique by node ID, in the order of `nodes`.
+    fn ids(&self, mut clique: Vec<usize>) -> Vec<IDDataType> {
+        clique.sort_unstable();
+        clique.into_iter().map(|node| self.ids[node]).collect()
+    }
+}
+
+impl<IDDataType: Copy> Iterator for MaximalCliques<IDDataType> {
+    type Item = Vec<IDDataType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(frame) = self.stack.last_mut() else {
+                // start from the next node, with its later neighbors in the degeneracy order as
+                // candidates, so every clique is found from its earliest node only
+                let root = *self.order.get(self.next_root)?;
+                self.next_root += 1;
+                let (later, earlier): (Vec<usize>, Vec<usize>) = self.adjacency[root]
+                    .iter()
+                    .partition(|v| self.rank[**v] > self.rank[root]);
+                if later.is_empty() {
+                    if earlier.is_empty() {
+                        return Some(self.ids(vec![root]));
+                    }
+                    continue;
+                }
+                let frame = self.frame(vec![root], later, earlier);
+                self.stack.push(frame);
+                continue;
+            };
+            let Some(v) = frame.branches.pop() else {
+                self.stack.pop();
+                continue;
+            };
+            let neighbors = &self.adjacency[v];
+            let candidates: Vec<usize> = frame
+                .candidates
+                .iter()
+                .copied()
+                .filter(|u| neighbors.contains(u))
+                .collect();
+            let excluded: Vec<usize> = frame
+                .excluded
+                .iter()
+                .copied()
+                .filter(|u| neighbors.contains(u))
+                .collect();
+            let mut clique = frame.clique.clone();
+            clique.push(v);
+            frame.candidates.retain(|u| *u != v);
+            frame.excluded.push(v);
+
+            if candidates.is_empty() {
+                if excluded.is_empty() {
+                    return Some(self.ids(clique));
+                }
+                continue;
+            }
+            let frame = self.frame(clique, candidates, excluded);
+            self.stack.push(frame);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! Multilevel coarsening: repeatedly contracting a matching of the graph into a hierarchy of
+//! smaller graphs, the shared first step of multilevel partitioning, layout and multigrid
+//! solvers.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+/// How `Graph::coarsen` picks the neighbor a node is contracted with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchingStrategy {
+    /// The neighbor connected by the heaviest edge, so the heaviest edges disappear inside the
+    /// coarse nodes and the coarse graph has as little edge weight left as possible.
+    HeavyEdge,
+    /// The neighbor with the heaviest edge relative to the product of the two node weights,
+    /// which keeps the coarse nodes of similar size.
+    NormalizedHeavyEdge,
+    /// The first neighbor that is not matched yet, the cheapest choice.
+    FirstNeighbor,
+}
+
+/// One level of a [`Hierarchy`]: a coarse graph and how the nodes of the level below map onto it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoarseLevel {
+    /// The coarse graph, with the nodes `0..n` and the number of original nodes each of them
+    /// contains as its data. Edges between the same pair of coarse nodes are merged.
+    pub graph: Graph<usize, usize>,
+    /// The number of original edges each directed edge of the coarse graph stands for.
+    pub edge_weights: NodeMap<(usize, usize), usize>,
+    /// For every node of the level below, by index, the coarse node it was contracted into. The
+    /// level below the first one is the original graph, indexed in the order of
+    /// [`Hierarchy::nodes`].
+    pub prolongation: Vec<usize>,
+}
+
+/// The result of `Graph::coarsen`: coarser and coarser versions of a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hierarchy<IDDataType> {
+    /// The nodes of the original graph in the order used by the first prolongation map.
+    pub nodes: Vec<IDDataType>,
+    /// The levels from the finest to the coarsest.
+    pub levels: Vec<CoarseLevel>,
+}
+
+impl<IDDataType> Hierarchy<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The coarse node at `level` that contains each original node, in the order of `nodes`.
+    pub fn projection(&self, level: usize) -> Vec<usize> {
+        let mut map: Vec<usize> = (0..self.nodes.len()).collect();
+        for coarse in &self.levels[..=level] {
+            for node in &mut map {
+                *node = coarse.prolongation[*node];
+            }
+        }
+        map
+    }
+
+    /// The original nodes contained in every coarse node at `level`.
+    pub fn members(&self, level: usize) -> Vec<Vec<IDDataType>> {
+        let mut members = vec![Vec::new(); self.levels[level].graph.nodes.len()];
+        for (id, coarse) in self.nodes.iter().zip(self.projection(level)) {
+            members[coarse].push(*id);
+        }
+        members
+    }
+
+    /// Copies values given for the coarse nodes of `level` to the nodes of the level below, or to
+    /// the original nodes for level 0, as when a multilevel method refines a coarse solution.
+    pub fn prolong<T: Clone>(&self, level: usize, coarse: &[T]) -> Vec<T> {
+        self.levels[level]
+            .prolongation
+            .iter()
+            .map(|node| coarse[*node].clone())
+            .collect()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Coarsens the graph up to `levels` times by matching nodes with one of their neighbors and
+    /// contracting every matched pair into one node, so every level has at least half as many
+    /// nodes as the one below. Edge directions are ignored for the matching. Coarsening
+    /// stops early once no two nodes can be matched.
+    ///
+    /// Nodes are visited from the lowest degree up, so poorly connected nodes find a partner
+    /// before their neighbors are taken; ties go to the node that comes first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{coarsen::MatchingStrategy, Graph};
+    ///
+    /// let path = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3 -- 4 -- 5 -- 6 -- 7").unwrap();
+    /// let hierarchy = path.coarsen(5, MatchingStrategy::HeavyEdge);
+    /// let sizes: Vec<usize> = hierarchy.levels.iter().map(|l| l.graph.nodes.len()).collect();
+    /// assert_eq!(sizes, vec![4, 2, 1]);
+    ///
+    /// // every coarse node knows which original nodes it contains
+    /// assert_eq!(hierarchy.members(1), vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+    /// assert_eq!(hierarchy.levels[1].graph.node_data[&0], 4);
+    /// ```
+    pub fn coarsen(&self, levels: usize, strategy: MatchingStrategy) -> Hierarchy<IDDataType> {
+        let index: NodeMap<IDDataType, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let mut weights = vec![1; self.nodes.len()];
+        let mut edges: NodeMap<(usize, usize), usize> = NodeMap::default();
+        for (from, to) in self.edges_iter() {
+            if let Some(&b) = index.get(&to) {
+                *edges.entry((index[&from], b)).or_default() += 1;
+            }
+        }
+
+        let mut hierarchy = Hierarchy {
+            nodes: self.nodes.clone(),
+            levels: Vec::new(),
+        };
+        while hierarchy.levels.len() < levels {
+            let prolongation = match_nodes(&weights, &edges, strategy);
+            let n = prolongation.iter().max().map_or(0, |max| max + 1);
+            if n == weights.len() {
+                break;
+            }
+            let mut coarse_weights = vec![0; n];
+            for (node, weight) in prolongation.iter().zip(&weights) {
+                coarse_weights[*node] += weight;
+            }
+            let mut coarse_edges: NodeMap<(usize, usize), usize> = NodeMap::default();
+            for ((a, b), weight) in &edges {
+                let (a, b) = (prolongation[*a], prolongation[*b]);
+                if a != b {
+                    *coarse_edges.entry((a, b)).or_default() += weight;
+                }
+            }
+
+            let mut graph = Graph::new();
+            for (id, weight) in coarse_weights.iter().enumerate() {
+                graph.add_node_with_data(id, *weight);
+            }
+            let mut pairs: Vec<(usize, usize)> = coarse_edges.keys().copied().collect();
+            pairs.sort_unstable();
+            graph.add_directed_edges(&pairs);
+            hierarchy.levels.push(CoarseLevel {
+                graph,
+                edge_weights: coarse_edges.clone(),
+                prolongation,
+            });
+            weights = coarse_weights;
+            edges = coarse_edges;
+        }
+        hierarchy
+    }
+}
+
+/// matches every node with at most one neighbor and returns the coarse node of every node,
+/// numbered in the order of the nodes.
+fn match_nodes(
+    weights: &[usize],
+    edges: &NodeMap<(usize, usize), usize>,
+    strategy: MatchingStrategy,
+) -> Vec<usize> {
+    let n = weights.len();
+    // undirected neighbors with the weight of the edges in both directions
+    let mut adjacency: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    for ((a, b), weight) in edges {
+        if a != b {
+            adjacency[*a].push((*b, *weight));
+            adjacency[*b].push((*a, *weight));
+        }
+    }
+    for neighbors in &mut adjacency {
+        neighbors.sort_unstable();
+        neighbors.dedup_by(|later, first| {
+            let same = later.0 == first.0;
+            if same {
+                first.1 += later.1;
+            }
+            same
+        });
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|v| adjacency[*v].len());
+    let mut mate: Vec<Option<usize>> = vec![None; n];
+    for v in order {
+        if mate[v].is_some() {
+            continue;
+        }
+        let candidates = adjacency[v].iter().filter(|(u, _)| mate[*u].is_none());
+        let partner = match strategy {
+            MatchingStrategy::FirstNeighbor => candidates.map(|(u, _)| *u).next(),
+            MatchingStrategy::HeavyEdge => candidates
+                .fold(
+                    None,
+                    |best: Option<(usize, usize)>, &(u, weight)| match best {
+                        Some((_, heaviest)) if heaviest >= weight => best,
+                        _ => Some((u, weight)),
+                    },
+                )
+                .map(|(u, _)| u),
+            MatchingStrategy::NormalizedHeavyEdge => candidates
+                .fold(None, |best: Option<(usize, f64)>, &(u, weight)| {
+                    let score = weight as f64 / (weights[v] * weights[u]) as f64;
+                    match best {
+                        Some((_, highest)) if highest >= score => best,
+                        _ => Some((u, score)),
+                    }
+                })
+                .map(|(u, _)| u),
+        };
+        if let Some(u) = partner {
+            mate[v] = Some(u);
+            mate[u] = Some(v);
+        }
+    }
+
+    let mut coarse = vec![usize::MAX; n];
+    let mut next = 0;
+    for v in 0..n {
+        if coarse[v] == usize::MAX {
+            coarse[v] = next;
+            if let Some(u) = mate[v] {
+                coarse[u] = next;
+            }
+            next += 1;
+        }
+    }
+    coarse
+}
@@ -0,0 +1,30 @@
+//! The hash maps used throughout the crate, all hashing with [`GraphHasher`].
+//!
+//! With `std` they are the standard maps, otherwise `hashbrown`'s maps with the same API.
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{hash_map::Entry, HashMap, HashSet};
+
+#[cfg(feature = "siphash")]
+type SelectedHasher = std::collections::hash_map::RandomState;
+#[cfg(all(feature = "ahash", not(feature = "siphash")))]
+type SelectedHasher = ahash::RandomState;
+#[cfg(not(any(feature = "ahash", feature = "siphash")))]
+type SelectedHasher = fnv::FnvBuildHasher;
+
+/// The hasher used by every map in the crate, chosen at compile time.
+///
+/// By default this is FNV, which is very fast for small keys such as integers but slows down on
+/// long tuple or struct keys and can be attacked with crafted IDs. The `ahash` feature switches to
+/// `ahash`, which is fast for all key sizes and randomly seeded, and the `siphash` feature switches
+/// to the standard library's DoS-resistant SipHash. If both are enabled, SipHash wins.
+pub type GraphHasher = SelectedHasher;
+
+/// A hash map using [`GraphHasher`], used for node data, adjacency and algorithm results.
+pub type NodeMap<K, V> = HashMap<K, V, GraphHasher>;
+
+/// A hash set using [`GraphHasher`].
+pub type NodeSet<T> = HashSet<T, GraphHasher>;
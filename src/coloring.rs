@@ -0,0 +1,71 @@
+//! Greedy vertex coloring, as used for register allocation and scheduling without conflicts.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A proper coloring of the nodes: colors `0, 1, 2, ...` such that no two neighbors share a
+    /// color. Edge directions are ignored and self-loops are left out, as a node cannot differ in
+    /// color from itself.
+    ///
+    /// The nodes are colored largest degree first, ties in the order of `nodes`, each with the
+    /// smallest color none of its neighbors has yet (the Welsh-Powell order). This takes
+    /// `O(V log V + E)` time and uses at most one color more than the largest degree, but is not
+    /// always optimal, which is NP-hard.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // live ranges that interfere need different registers
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 2 -- 3; 3 -- 4").unwrap();
+    /// let colors = g.greedy_coloring();
+    /// assert_eq!(colors[&2], 0);
+    /// assert!(g.edge_tuples().iter().all(|(a, b)| colors[a] != colors[b]));
+    /// assert_eq!(g.chromatic_number_upper_bound(), 3);
+    /// ```
+    pub fn greedy_coloring(&self) -> NodeMap<IDDataType, usize> {
+        self.nodes
+            .iter()
+            .copied()
+            .zip(self.index_greedy_coloring())
+            .collect()
+    }
+
+    /// The number of colors `greedy_coloring` uses, an upper bound on the chromatic number: the
+    /// fewest colors any proper coloring needs. It is zero for a graph without nodes.
+    pub fn chromatic_number_upper_bound(&self) -> usize {
+        self.index_greedy_coloring()
+            .into_iter()
+            .max()
+            .map_or(0, |color| color + 1)
+    }
+
+    /// the greedy color of every node by position in `nodes`.
+    fn index_greedy_coloring(&self) -> Vec<usize> {
+        let adjacency = self.undirected_sets();
+        let mut order: Vec<usize> = (0..adjacency.len()).collect();
+        order.sort_by_key(|u| core::cmp::Reverse(adjacency[*u].len()));
+
+        let mut colors = vec![usize::MAX; adjacency.len()];
+        // the node that last took every color, to find the taken colors without clearing
+        let mut taken_by = vec![usize::MAX; adjacency.len() + 1];
+        for u in order {
+            for v in &adjacency[u] {
+                if let Some(slot) = taken_by.get_mut(colors[*v]) {
+                    *slot = u;
+                }
+            }
+            colors[u] = (0..)
+                .find(|color| taken_by[*color] != u)
+                .unwrap_or_default();
+        }
+        colors
+    }
+}
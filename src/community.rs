@@ -0,0 +1,297 @@
+//! Community detection by label propagation and the Louvain method, and the modularity of a
+//! partition.
+//!
+//! All methods treat the graph as a simple undirected graph, ignoring edge directions, parallel
+//! edges and self-loops. Communities are numbered `0..k` in the order of their first node in
+//! `nodes`.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use rand::Rng;
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// the number of passes over all nodes after which label propagation stops even if labels still
+/// change, which only happens when ties keep flipping.
+const MAX_PROPAGATION_PASSES: usize = 100;
+
+/// the smallest modularity gain for which the Louvain method moves a node, so rounding errors
+/// cannot keep nodes moving back and forth.
+const MIN_GAIN: f64 = 1e-12;
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Communities found by label propagation: every node starts in a community of its own and
+    /// then, visiting the nodes in random order, repeatedly joins the community most of its
+    /// neighbors are in, breaking ties at random, until no node wants to move.
+    ///
+    /// This takes `O(E)` time per pass and usually needs only a handful of passes, which makes it
+    /// the fastest choice for large graphs, but the result depends on `rng` and does not
+    /// optimize any score.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 3 -- 4 -- 5 -- 3").unwrap();
+    /// let communities = g.communities_label_propagation(StdRng::seed_from_u64(1));
+    /// assert_eq!(communities[&0], communities[&2]);
+    /// assert_ne!(communities[&0], communities[&3]);
+    /// ```
+    pub fn communities_label_propagation<R: Rng>(&self, mut rng: R) -> NodeMap<IDDataType, usize> {
+        let adjacency = self.undirected_sets();
+        let n = adjacency.len();
+        let mut labels: Vec<usize> = (0..n).collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        let mut counts = vec![0usize; n];
+        let mut best = Vec::new();
+        for _ in 0..MAX_PROPAGATION_PASSES {
+            for i in (1..n).rev() {
+                order.swap(i, rng.gen_range(0..=i));
+            }
+            let mut changed = false;
+            for node in &order {
+                if adjacency[*node].is_empty() {
+                    continue;
+                }
+                let mut most = 0;
+                for neighbor in &adjacency[*node] {
+                    counts[labels[*neighbor]] += 1;
+                    most = most.max(counts[labels[*neighbor]]);
+                }
+                best.clear();
+                for neighbor in &adjacency[*node] {
+                    let label = labels[*neighbor];
+                    if counts[label] == most && !best.contains(&label) {
+                        best.push(label);
+                    }
+                }
+                for neighbor in &adjacency[*node] {
+                    counts[labels[*neighbor]] = 0;
+                }
+                // a node stays in its community as long as it is one of the most common ones
+                if !best.contains(&labels[*node]) {
+                    labels[*node] = best[rng.gen_range(0..best.len())];
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        self.numbered_communities(&labels)
+    }
+
+    /// Communities found by the Louvain method, which greedily maximizes `modularity`: every
+    /// node repeatedly moves to the neighboring community that raises the modularity the most,
+    /// then every community is merged into a single node and the moves are repeated on the
+    /// smaller graph, until no move helps.
+    ///
+    /// The nodes are visited in the order of `nodes`, so the result is deterministic. Every
+    /// round takes `O(E)` time, and there are usually only a few.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // two triangles joined by a single edge
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 3 -- 4 -- 5 -- 3; 2 -- 3").unwrap();
+    /// let communities = g.communities_louvain();
+    /// assert_eq!(communities[&0], communities[&2]);
+    /// assert_eq!(communities[&3], communities[&5]);
+    /// assert_ne!(communities[&0], communities[&3]);
+    /// assert!((g.modularity(&communities) - 5.0 / 14.0).abs() < 1e-12);
+    /// ```
+    pub fn communities_louvain(&self) -> NodeMap<IDDataType, usize> {
+        let mut level = WeightedLevel::from_sets(&self.undirected_sets());
+        let mut labels: Vec<usize> = (0..self.nodes.len()).collect();
+        loop {
+            let (community, count) = level.local_moves();
+            if count == level.adjacency.len() {
+                break;
+            }
+            for label in &mut labels {
+                *label = community[*label];
+            }
+            level = level.aggregate(&community, count);
+        }
+        self.numbered_communities(&labels)
+    }
+
+    /// The modularity of a partition of the nodes into communities, given as the community label
+    /// of every node: the fraction of the edges inside communities minus the fraction expected if
+    /// the edges were placed at random between nodes of the same degrees. It is at most one,
+    /// zero for a single community, and higher for partitions with denser communities.
+    ///
+    /// Returns zero for a graph without edges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node has no label in `partition`.
+    pub fn modularity(&self, partition: &NodeMap<IDDataType, usize>) -> f64 {
+        let adjacency = self.undirected_sets();
+        let degree_sum: usize = adjacency.iter().map(NodeSet::len).sum();
+        if degree_sum == 0 {
+            return 0.0;
+        }
+        let labels: Vec<usize> = self.nodes.iter().map(|id| partition[id]).collect();
+        // the sum of the degrees and the twice-counted edges inside every community
+        let mut degrees = NodeMap::default();
+        let mut inside = 0;
+        for (u, neighbors) in adjacency.iter().enumerate() {
+            *degrees.entry(labels[u]).or_insert(0) += neighbors.len();
+            inside += neighbors
+                .iter()
+                .filter(|v| labels[**v] == labels[u])
+                .count();
+        }
+        let total = degree_sum as f64;
+        let expected: f64 = degrees
+            .values()
+            .map(|degree| {
+                let share = *degree as f64 / total;
+                share * share
+            })
+            .sum();
+        inside as f64 / total - expected
+    }
+
+    /// the community of every node from the community labels by position in `nodes`, renumbered
+    /// in the order of the first node of every community.
+    fn numbered_communities(&self, labels: &[usize]) -> NodeMap<IDDataType, usize> {
+        let mut numbers = NodeMap::default();
+        self.nodes
+            .iter()
+            .zip(labels)
+            .map(|(id, label)| {
+                let next = numbers.len();
+                (*id, *numbers.entry(*label).or_insert(next))
+            })
+            .collect()
+    }
+}
+
+/// an undirected graph with weighted edges and self-loops, a level of the Louvain method.
+struct WeightedLevel {
+    /// the neighbors of every node with the weights of the edges, without self-loops.
+    adjacency: Vec<Vec<(usize, f64)>>,
+    /// the weight of the self-loop of every node, the edges inside it on lower levels.
+    loops: Vec<f64>,
+}
+
+impl WeightedLevel {
+    fn from_sets(sets: &[NodeSet<usize>]) -> Self {
+        WeightedLevel {
+            adjacency: sets
+                .iter()
+                .map(|neighbors| neighbors.iter().map(|v| (*v, 1.0)).collect())
+                .collect(),
+            loops: vec![0.0; sets.len()],
+        }
+    }
+
+    /// the weighted degree of every node, counting self-loops twice.
+    fn degrees(&self) -> Vec<f64> {
+        self.adjacency
+            .iter()
+            .zip(&self.loops)
+            .map(|(neighbors, weight)| neighbors.iter().map(|(_, w)| w).sum::<f64>() + 2.0 * weight)
+            .collect()
+    }
+
+    /// moves nodes between communities while that raises the modularity, returning the
+    /// community of every node, numbered `0..count`, and `count`.
+    fn local_moves(&self) -> (Vec<usize>, usize) {
+        let n = self.adjacency.len();
+        let degrees = self.degrees();
+        let total: f64 = degrees.iter().sum();
+        let mut community: Vec<usize> = (0..n).collect();
+        if total == 0.0 {
+            return (community, n);
+        }
+        // the degree sum of every community and the weight from the current node to it
+        let mut totals = degrees.clone();
+        let mut links = vec![0.0; n];
+        let mut touched = Vec::new();
+        let mut moved = true;
+        while moved {
+            moved = false;
+            for node in 0..n {
+                let own = community[node];
+                for (neighbor, weight) in &self.adjacency[node] {
+                    let c = community[*neighbor];
+                    if links[c] == 0.0 {
+                        touched.push(c);
+                    }
+                    links[c] += weight;
+                }
+                totals[own] -= degrees[node];
+                let gain = |c: usize| links[c] - totals[c] * degrees[node] / total;
+                let mut best = (own, gain(own));
+                for c in &touched {
+                    if gain(*c) > best.1 + MIN_GAIN {
+                        best = (*c, gain(*c));
+                    }
+                }
+                totals[best.0] += degrees[node];
+                if best.0 != own {
+                    community[node] = best.0;
+                    moved = true;
+                }
+                for c in touched.drain(..) {
+                    links[c] = 0.0;
+                }
+            }
+        }
+        let mut numbers = vec![usize::MAX; n];
+        let mut count = 0;
+        for c in &mut community {
+            if numbers[*c] == usize::MAX {
+                numbers[*c] = count;
+                count += 1;
+            }
+            *c = numbers[*c];
+        }
+        (community, count)
+    }
+
+    /// the level with one node per community, the edges between communities merged and the
+    /// edges inside them turned into self-loops.
+    fn aggregate(&self, community: &[usize], count: usize) -> Self {
+        let mut weights: Vec<NodeMap<usize, f64>> =
+            (0..count).map(|_| NodeMap::default()).collect();
+        let mut loops = vec![0.0; count];
+        for (u, neighbors) in self.adjacency.iter().enumerate() {
+            let a = community[u];
+            loops[a] += self.loops[u];
+            for (v, weight) in neighbors {
+                let b = community[*v];
+                if a == b {
+                    // every edge inside a community is seen from both of its ends
+                    loops[a] += weight / 2.0;
+                } else {
+                    *weights[a].entry(b).or_insert(0.0) += weight;
+                }
+            }
+        }
+        WeightedLevel {
+            adjacency: weights
+                .into_iter()
+                .map(|neighbors| {
+                    let mut neighbors: Vec<(usize, f64)> = neighbors.into_iter().collect();
+                    neighbors.sort_unstable_by_key(|(v, _)| *v);
+                    neighbors
+                })
+                .collect(),
+            loops,
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! The index-based core of a graph's topology, for traversal-heavy, read-only work.
+//!
+//! `Graph` stores its topology in hash maps, so it can be changed cheaply. The traversals,
+//! component algorithms and shortest path searches of `Graph` instead run on a [`CompactGraph`],
+//! which maps every node ID once to a dense `u32` slot. The graph builds it on first use, keeps it
+//! until its nodes or edges change and only hashes IDs to translate the inputs and results.
+//! `Graph::to_compact` hands out a copy of it for work on a graph that no longer changes.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+/// A frozen, index-based copy of a graph's topology.
+///
+/// Every node ID is mapped once to a dense `u32` slot, and the outgoing and incoming
+/// edges are stored in compressed sparse row (CSR) form over those slots. The hash map
+/// is only consulted at the API boundary, when translating IDs to slots and back, so
+/// traversals over graphs with tuple or struct IDs avoid hashing on every step.
+///
+/// Slots are assigned in the order of `Graph::nodes`.
+///
+/// A compact graph is a snapshot: it is built from a graph in `O(n + m)` time and does not follow
+/// later changes to it. The graph keeps its own copy up to date for its traversals, so a separate
+/// one is only needed to traverse by slot, e.g. through the [`visit`](crate::visit) traits.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CompactGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    ids: Vec<IDDataType>,
+    index: NodeMap<IDDataType, u32>,
+    offsets: Vec<usize>,
+    targets: Vec<u32>,
+    reverse_offsets: Vec<usize>,
+    reverse_targets: Vec<u32>,
+}
+
+impl<IDDataType> CompactGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Builds the compact representation of a graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph has more than `u32::MAX` nodes.
+    pub fn from_graph<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self {
+        assert!(
+            graph.nodes.len() <= u32::MAX as usize,
+            "graph has too many nodes for 32 bit indices"
+        );
+
+        let ids = graph.nodes.clone();
+        let index: NodeMap<IDDataType, u32> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i as u32))
+            .collect();
+
+        let (offsets, targets) = Self::csr(&ids, &index, |id| graph.edges.get(id));
+        let (reverse_offsets, reverse_targets) =
+            Self::csr(&ids, &index, |id| graph.reverse_edges.get(id));
+
+        Self {
+            ids,
+            index,
+            offsets,
+            targets,
+            reverse_offsets,
+            reverse_targets,
+        }
+    }
+
+    /// lays out the adjacency lists returned by `lists` as offsets into one flat target array.
+    fn csr<'a, List>(
+        ids: &[IDDataType],
+        index: &NodeMap<IDDataType, u32>,
+        lists: impl Fn(&IDDataType) -> Option<&'a List>,
+    ) -> (Vec<usize>, Vec<u32>)
+    where
+        List: AsRef<[IDDataType]> + 'a,
+        IDDataType: 'a,
+    {
+        let mut offsets = Vec::with_capacity(ids.len() + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for id in ids {
+            if let Some(list) = lists(id) {
+                // edges pointing at IDs that are not nodes are dropped
+                targets.extend(list.as_ref().iter().filter_map(|to| index.get(to)));
+            }
+            offsets.push(targets.len());
+        }
+        (offsets, targets)
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The number of directed edges.
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// The slot assigned to a node ID, if the node exists.
+    pub fn index_of(&self, id: &IDDataType) -> Option<u32> {
+        self.index.get(id).copied()
+    }
+
+    /// The node ID stored in a slot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn id_of(&self, index: u32) -> IDDataType {
+        self.ids[index as usize]
+    }
+
+    /// All node IDs, ordered by slot.
+    pub fn ids(&self) -> &[IDDataType] {
+        &self.ids
+    }
+
+    /// The slots of the outgoing neighbors of the node in slot `index`.
+    pub fn neighbors(&self, index: u32) -> &[u32] {
+        let i = index as usize;
+        &self.targets[self.offsets[i]..self.offsets[i + 1]]
+    }
+
+    /// The slots of the incoming neighbors of the node in slot `index`.
+    pub fn reverse_neighbors(&self, index: u32) -> &[u32] {
+        let i = index as usize;
+        &self.reverse_targets[self.reverse_offsets[i]..self.reverse_offsets[i + 1]]
+    }
+
+    /// The raw CSR arrays `(offsets, targets)` of the outgoing edges.
+    /// The neighbors of slot `i` are `targets[offsets[i]..offsets[i + 1]]`.
+    pub fn csr_parts(&self) -> (&[usize], &[u32]) {
+        (&self.offsets, &self.targets)
+    }
+
+    /// the outgoing neighbors of every slot, as positions for the algorithms on adjacency lists.
+    pub(crate) fn adjacency_lists(&self) -> Vec<Vec<usize>> {
+        (0..self.len() as u32)
+            .map(|slot| self.neighbors(slot).iter().map(|to| *to as usize).collect())
+            .collect()
+    }
+
+    /// the map with its slots translated back to node IDs.
+    pub(crate) fn map_slots<V>(&self, map: NodeMap<u32, V>) -> NodeMap<IDDataType, V> {
+        let mut mapped = NodeMap::with_capacity_and_hasher(map.len(), Default::default());
+        mapped.extend(
+            map.into_iter()
+                .map(|(slot, value)| (self.id_of(slot), value)),
+        );
+        mapped
+    }
+
+    /// the slots translated back to node IDs.
+    pub(crate) fn ids_of(&self, slots: impl IntoIterator<Item = u32>) -> Vec<IDDataType> {
+        slots.into_iter().map(|slot| self.id_of(slot)).collect()
+    }
+
+    /// Breadth-first hop distances from the node in slot `start`, indexed by slot.
+    /// Unreachable nodes get `None`.
+    pub fn distances_from_index(&self, start: u32) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.len()];
+        let mut queue = VecDeque::new();
+        distances[start as usize] = Some(0);
+        queue.push_back(start);
+
+        while let Some(node) = queue.pop_front() {
+            let next = distances[node as usize].map(|d| d + 1);
+            for &neighbor in self.neighbors(node) {
+                if distances[neighbor as usize].is_none() {
+                    distances[neighbor as usize] = next;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Breadth-first hop distances from `start` to every node reachable from it.
+    /// Returns an empty map if `start` is not in the graph.
+    pub fn distances_from(&self, start: IDDataType) -> NodeMap<IDDataType, usize> {
+        let Some(start) = self.index_of(&start) else {
+            return NodeMap::default();
+        };
+        self.distances_from_index(start)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, d)| d.map(|d| (self.ids[i], d as usize)))
+            .collect()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Builds a frozen, index-based snapshot of the topology for fast repeated traversals.
+    /// Later changes to the graph are not reflected in it. See [`CompactGraph`].
+    pub fn to_compact(&self) -> CompactGraph<IDDataType> {
+        self.core().clone()
+    }
+
+    /// the index-based core the traversals run on, built on first use and kept until the nodes
+    /// or edges change.
+    pub(crate) fn core(&self) -> &CompactGraph<IDDataType> {
+        self.compact.get_or_build(|| CompactGraph::from_graph(self))
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> From<&Graph<IDDataType, NodeDataType, EdgeDataType>>
+    for CompactGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn from(graph: &Graph<IDDataType, NodeDataType, EdgeDataType>) -> Self {
+        Self::from_graph(graph)
+    }
+}
@@ -0,0 +1,271 @@
+//! Connected and strongly connected components, the condensation of a directed graph and
+//! reachability.
+//!
+//! The components are found by the functions of this module, which run on any graph that
+//! implements [`Neighbors`], including filtered views; the methods of `Graph` call them.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    visit::{index_successors, Neighbors},
+    Graph,
+};
+
+/// marks a node that Tarjan's algorithm has not reached yet.
+const UNVISITED: usize = usize::MAX;
+
+/// The strongly connected components of any graph, see `Graph::strongly_connected_components`.
+/// The nodes of every component are in the order of `node_ids`.
+pub fn strongly_connected_components<G: Neighbors>(graph: &G) -> Vec<Vec<G::Id>> {
+    let (nodes, adjacency) = index_successors(graph);
+    let n = adjacency.len();
+    let mut index = vec![UNVISITED; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // the nodes of the depth-first path with the position of their next neighbor
+        let mut path = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            if let Some(&neighbor) = adjacency[node].get(*next) {
+                *next += 1;
+                if index[neighbor] == UNVISITED {
+                    index[neighbor] = next_index;
+                    lowlink[neighbor] = next_index;
+                    next_index += 1;
+                    stack.push(neighbor);
+                    on_stack[neighbor] = true;
+                    path.push((neighbor, 0));
+                } else if on_stack[neighbor] {
+                    lowlink[node] = lowlink[node].min(index[neighbor]);
+                }
+                continue;
+            }
+            path.pop();
+            if let Some((parent, _)) = path.last() {
+                lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+            }
+            if lowlink[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    // Tarjan's algorithm finds every component after all components it reaches
+    components
+        .iter()
+        .rev()
+        .map(|component| component.iter().map(|i| nodes[*i]).collect())
+        .collect()
+}
+
+/// The connected components of any graph, ignoring edge directions, see
+/// `Graph::connected_components`. The nodes of every component are in the order of `node_ids`.
+pub fn connected_components<G: Neighbors>(graph: &G) -> Vec<Vec<G::Id>> {
+    let (nodes, adjacency) = index_successors(graph);
+    let labels = weak_labels(&adjacency);
+    let count = labels.iter().max().map_or(0, |label| label + 1);
+    let mut components = vec![Vec::new(); count];
+    for (id, label) in nodes.into_iter().zip(labels) {
+        components[label].push(id);
+    }
+    components
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The strongly connected components: the largest groups of nodes that can all reach each
+    /// other along directed edges. Every node is in exactly one component, a node on no cycle
+    /// being a component of its own.
+    ///
+    /// The components are in topological order, so edges between two components only lead to
+    /// later ones, and the nodes of every component are in the order of `nodes`. This is Tarjan's
+    /// algorithm, which takes `O(V + E)` time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 3 -> 4 -> 3; 5").unwrap();
+    /// assert_eq!(
+    ///     g.strongly_connected_components(),
+    ///     vec![vec![5], vec![0, 1, 2], vec![3, 4]]
+    /// );
+    /// ```
+    pub fn strongly_connected_components(&self) -> Vec<Vec<IDDataType>> {
+        let core = self.core();
+        strongly_connected_components(core)
+            .into_iter()
+            .map(|component| core.ids_of(component))
+            .collect()
+    }
+
+    /// The condensation: the graph with one node per strongly connected component, numbered in
+    /// the order of `strongly_connected_components` and holding the members of the component,
+    /// and one edge between two components if any edge leads from the first to the second. The
+    /// condensation has no cycles, so it is a directed acyclic graph unless it has no edges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 0; 1 -> 2 -> 3 -> 2; 0 -> 3").unwrap();
+    /// let condensed = g.condensation();
+    /// assert_eq!(condensed.node_data[&0], vec![0, 1]);
+    /// assert_eq!(condensed.node_data[&1], vec![2, 3]);
+    /// assert_eq!(condensed.neighbors(0), &[1]);
+    /// ```
+    pub fn condensation(&self) -> Graph<usize, Vec<IDDataType>> {
+        let components = self.strongly_connected_components();
+        let mut component_of = NodeMap::default();
+        for (i, component) in components.iter().enumerate() {
+            for id in component {
+                component_of.insert(*id, i);
+            }
+        }
+        let mut condensed = Graph::new();
+        let mut linked = NodeSet::default();
+        for (i, component) in components.into_iter().enumerate() {
+            condensed.add_node_with_data(i, component);
+        }
+        for from in &self.nodes {
+            for to in self.neighbors(*from) {
+                let (Some(&a), Some(&b)) = (component_of.get(from), component_of.get(to)) else {
+                    continue;
+                };
+                if a != b && linked.insert((a, b)) {
+                    condensed.add_directed_edge(a, b);
+                }
+            }
+        }
+        condensed
+    }
+
+    /// The connected components, ignoring edge directions: the largest groups of nodes that are
+    /// linked by a path. For a directed graph these are its weakly connected components. The
+    /// components are ordered by their first node and the nodes of every component are in the
+    /// order of `nodes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1; 2 -- 3 -- 4; 5").unwrap();
+    /// assert_eq!(
+    ///     g.connected_components(),
+    ///     vec![vec![0, 1], vec![2, 3, 4], vec![5]]
+    /// );
+    /// assert!(!g.is_connected());
+    /// assert_eq!(g.component_labels()[&4], 1);
+    /// ```
+    pub fn connected_components(&self) -> Vec<Vec<IDDataType>> {
+        let core = self.core();
+        connected_components(core)
+            .into_iter()
+            .map(|component| core.ids_of(component))
+            .collect()
+    }
+
+    /// The weakly connected components of a directed graph: the largest groups of nodes that are
+    /// linked by a path when edge directions are ignored. This is the same as
+    /// `connected_components`, named for directed graphs, e.g. to split a dependency graph into
+    /// pieces that can be processed independently.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1; 2 -> 1; 3 -> 4; 5").unwrap();
+    /// assert_eq!(
+    ///     g.weakly_connected_components(),
+    ///     vec![vec![0, 1, 2], vec![3, 4], vec![5]]
+    /// );
+    /// ```
+    pub fn weakly_connected_components(&self) -> Vec<Vec<IDDataType>> {
+        self.connected_components()
+    }
+
+    /// The position in `connected_components` of the component of every node.
+    pub fn component_labels(&self) -> NodeMap<IDDataType, usize> {
+        let core = self.core();
+        core.ids()
+            .iter()
+            .copied()
+            .zip(weak_labels(&core.adjacency_lists()))
+            .collect()
+    }
+
+    /// Whether every node can be reached from every other ignoring edge directions, i.e. whether
+    /// there is at most one connected component. A graph without nodes is connected.
+    pub fn is_connected(&self) -> bool {
+        weak_labels(&self.core().adjacency_lists())
+            .iter()
+            .all(|label| *label == 0)
+    }
+
+    /// The nodes reachable from `start` along directed edges, including `start` itself; on an
+    /// undirected graph, the connected component of `start`. The set is empty if `start` does not
+    /// exist.
+    pub fn reachable_from(&self, start: IDDataType) -> NodeSet<IDDataType> {
+        self.bfs(start).collect()
+    }
+}
+
+/// the label of the connected component of every node of an index adjacency list, ignoring edge
+/// directions and numbering the components in the order of their first node.
+fn weak_labels(forward: &[Vec<usize>]) -> Vec<usize> {
+    let mut backward = vec![Vec::new(); forward.len()];
+    for (from, tos) in forward.iter().enumerate() {
+        for to in tos {
+            backward[*to].push(from);
+        }
+    }
+    let mut labels = vec![UNVISITED; forward.len()];
+    let mut count = 0;
+    let mut queue = Vec::new();
+    for root in 0..forward.len() {
+        if labels[root] != UNVISITED {
+            continue;
+        }
+        labels[root] = count;
+        queue.push(root);
+        while let Some(node) = queue.pop() {
+            for neighbor in forward[node].iter().chain(&backward[node]) {
+                if labels[*neighbor] == UNVISITED {
+                    labels[*neighbor] = count;
+                    queue.push(*neighbor);
+                }
+            }
+        }
+        count += 1;
+    }
+    labels
+}
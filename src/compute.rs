@@ -0,0 +1,417 @@
+//! Pregel-style bulk-synchronous computation on node data.
+//!
+//! A computation is a per-node program that runs in synchronized supersteps. In every
+//! superstep each active node reads the messages sent to it in the previous superstep,
+//! updates its own data and sends messages to other nodes. A node becomes inactive by
+//! calling [`Context::vote_to_halt`] and is woken up again by any incoming message. The
+//! computation ends once every node has halted and no messages are in flight.
+//!
+//! For the common special case where every node simply recomputes its data from the data of
+//! its neighbors, `Graph::step`, `Graph::par_step` and `Graph::run_until` update all nodes
+//! synchronously, reading the old data and writing the new data to a second buffer, without
+//! any message passing.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Adjacency, Graph,
+};
+
+/// The view a node program gets of the computation, used to inspect the node and send messages.
+pub struct Context<'a, IDDataType, Message> {
+    id: IDDataType,
+    superstep: usize,
+    neighbors: &'a [IDDataType],
+    outbox: Vec<(IDDataType, Message)>,
+    halted: bool,
+}
+
+impl<IDDataType: Copy, Message> Context<'_, IDDataType, Message> {
+    /// The ID of the node the program is running on.
+    pub fn id(&self) -> IDDataType {
+        self.id
+    }
+
+    /// The index of the current superstep, starting at 0.
+    pub fn superstep(&self) -> usize {
+        self.superstep
+    }
+
+    /// The outgoing neighbors of the node.
+    pub fn neighbors(&self) -> &[IDDataType] {
+        self.neighbors
+    }
+
+    /// Sends a message to `to`, delivered at the start of the next superstep.
+    /// Messages to nodes that are not in the graph are dropped.
+    pub fn send(&mut self, to: IDDataType, message: Message) {
+        self.outbox.push((to, message));
+    }
+
+    /// Sends a copy of `message` along every outgoing edge of the node.
+    pub fn send_to_neighbors(&mut self, message: Message)
+    where
+        Message: Clone,
+    {
+        for &to in self.neighbors {
+            self.outbox.push((to, message.clone()));
+        }
+    }
+
+    /// Deactivates the node until it receives a message.
+    pub fn vote_to_halt(&mut self) {
+        self.halted = true;
+    }
+}
+
+/// The outcome of a [`Pregel`] run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PregelSummary {
+    /// The number of supersteps that were executed.
+    pub supersteps: usize,
+    /// The total number of messages delivered.
+    pub messages: usize,
+    /// Whether the computation ended because every node halted, rather than by hitting the superstep limit.
+    pub converged: bool,
+}
+
+/// Runs a node program over a graph in bulk-synchronous supersteps.
+///
+/// # Example
+///
+/// Propagate the largest value in each component to all of its nodes:
+///
+/// ```
+/// use grafferous::{compute::Pregel, generate_cycle_graph};
+///
+/// let mut g = generate_cycle_graph::<usize>(10);
+/// for (id, data) in g.node_data.iter_mut() {
+///     *data = *id;
+/// }
+///
+/// let summary = Pregel::new().run(&mut g, |ctx, value: &mut usize, messages: &[usize]| {
+///     let largest = messages.iter().copied().max().unwrap_or(0);
+///     if ctx.superstep() == 0 || largest > *value {
+///         *value = largest.max(*value);
+///         ctx.send_to_neighbors(*value);
+///     }
+///     ctx.vote_to_halt();
+/// });
+///
+/// assert!(summary.converged);
+/// assert!(g.node_data.values().all(|&value| value == 9));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Pregel {
+    max_supersteps: Option<usize>,
+}
+
+/// What a single node produced in one superstep.
+struct Outcome<IDDataType, Message> {
+    id: IDDataType,
+    outbox: Vec<(IDDataType, Message)>,
+    halted: bool,
+}
+
+impl Pregel {
+    /// A computation without a superstep limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the computation after at most `supersteps` supersteps, even if it has not converged.
+    pub fn max_supersteps(mut self, supersteps: usize) -> Self {
+        self.max_supersteps = Some(supersteps);
+        self
+    }
+
+    /// Runs `program` on every active node of `graph`, one node after the other, until convergence.
+    ///
+    /// The program receives the node's context, its data and the messages sent to it in the
+    /// previous superstep. Messages sent to a node within one superstep arrive in node order.
+    pub fn run<IDDataType, NodeDataType, EdgeDataType, Message, F>(
+        &self,
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        program: F,
+    ) -> PregelSummary
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        F: Fn(&mut Context<IDDataType, Message>, &mut NodeDataType, &[Message]),
+    {
+        self.execute(graph, |graph, superstep, inbox, halted| {
+            let Graph {
+                node_data,
+                edges,
+                nodes,
+                ..
+            } = graph;
+            nodes
+                .iter()
+                .filter_map(|id| {
+                    let data = node_data.get_mut(id)?;
+                    run_node(*id, superstep, edges, data, inbox, halted, &program)
+                })
+                .collect()
+        })
+    }
+
+    /// Like `run`, but executes the nodes of every superstep in parallel.
+    ///
+    /// Messages sent to a node within one superstep arrive in an unspecified order.
+    #[cfg(feature = "rayon")]
+    pub fn par_run<IDDataType, NodeDataType, EdgeDataType, Message, F>(
+        &self,
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        program: F,
+    ) -> PregelSummary
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + Send + Sync,
+        NodeDataType: Send + Sync,
+        Message: Send + Sync,
+        F: Fn(&mut Context<IDDataType, Message>, &mut NodeDataType, &[Message]) + Sync,
+    {
+        self.execute(graph, |graph, superstep, inbox, halted| {
+            let edges = &graph.edges;
+            graph
+                .node_data
+                .par_iter_mut()
+                .filter_map(|(id, data)| {
+                    run_node(*id, superstep, edges, data, inbox, halted, &program)
+                })
+                .collect()
+        })
+    }
+
+    /// drives the superstep loop, delegating the execution of a single superstep to `step`.
+    fn execute<IDDataType, NodeDataType, EdgeDataType, Message>(
+        &self,
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        mut step: impl FnMut(
+            &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+            usize,
+            &NodeMap<IDDataType, Vec<Message>>,
+            &NodeSet<IDDataType>,
+        ) -> Vec<Outcome<IDDataType, Message>>,
+    ) -> PregelSummary
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let mut inbox = NodeMap::default();
+        let mut halted = NodeSet::default();
+        let mut messages = 0;
+        let mut superstep = 0;
+        loop {
+            if self.max_supersteps == Some(superstep) {
+                return PregelSummary {
+                    supersteps: superstep,
+                    messages,
+                    converged: false,
+                };
+            }
+            let outcomes = step(graph, superstep, &inbox, &halted);
+            if outcomes.is_empty() {
+                return PregelSummary {
+                    supersteps: superstep,
+                    messages,
+                    converged: true,
+                };
+            }
+            inbox.clear();
+            for outcome in outcomes {
+                if outcome.halted {
+                    halted.insert(outcome.id);
+                } else {
+                    halted.remove(&outcome.id);
+                }
+                for (to, message) in outcome.outbox {
+                    if graph.node_data.contains_key(&to) {
+                        inbox.entry(to).or_insert_with(Vec::new).push(message);
+                        messages += 1;
+                    }
+                }
+            }
+            superstep += 1;
+        }
+    }
+}
+
+/// runs the program on one node, unless it has halted and has no new messages.
+fn run_node<IDDataType, NodeDataType, Message, F>(
+    id: IDDataType,
+    superstep: usize,
+    edges: &NodeMap<IDDataType, Adjacency<IDDataType>>,
+    data: &mut NodeDataType,
+    inbox: &NodeMap<IDDataType, Vec<Message>>,
+    halted: &NodeSet<IDDataType>,
+    program: &F,
+) -> Option<Outcome<IDDataType, Message>>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    F: Fn(&mut Context<IDDataType, Message>, &mut NodeDataType, &[Message]),
+{
+    let received = inbox.get(&id).map_or(&[][..], |messages| &messages[..]);
+    if received.is_empty() && halted.contains(&id) {
+        return None;
+    }
+    let mut context = Context {
+        id,
+        superstep,
+        neighbors: edges.get(&id).map_or(&[][..], |neighbors| &neighbors[..]),
+        outbox: Vec::new(),
+        halted: false,
+    };
+    program(&mut context, data, received);
+    Some(Outcome {
+        id,
+        outbox: context.outbox,
+        halted: context.halted,
+    })
+}
+
+/// When `Graph::run_until` should stop stepping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopCondition {
+    /// Run exactly this many steps.
+    Steps(usize),
+    /// Run until a step leaves every node's data unchanged, but at most `max_steps` steps.
+    Convergence {
+        /// The maximum number of steps to run.
+        max_steps: usize,
+    },
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Updates every node synchronously, like a cellular automaton.
+    ///
+    /// `rule` is called with the ID of each node, its current data and the current data of its
+    /// outgoing neighbors, and returns the node's next data. All next states are computed from the
+    /// current states before any of them is written back, so the order of the nodes does not matter.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - Computes the next data of a node from `(id, data, neighbor_data)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_cycle_graph;
+    ///
+    /// // every node becomes alive if exactly one of its neighbors is alive
+    /// let mut g = generate_cycle_graph::<bool>(8);
+    /// *g.node_data.get_mut(&0).unwrap() = true;
+    /// g.step(|_, _, neighbors| neighbors.iter().filter(|alive| ***alive).count() == 1);
+    ///
+    /// assert!(g.node_data[&1] && g.node_data[&7] && !g.node_data[&0]);
+    /// ```
+    pub fn step<F>(&mut self, mut rule: F)
+    where
+        F: FnMut(IDDataType, &NodeDataType, &[&NodeDataType]) -> NodeDataType,
+    {
+        let next = self.next_states(&mut rule);
+        for (id, data) in self.nodes.iter().zip(next) {
+            if let Some(current) = self.node_data.get_mut(id) {
+                *current = data;
+            }
+        }
+    }
+
+    /// Like `step`, but computes the next states of the nodes in parallel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// // every node takes the largest value among itself and its neighbors
+    /// let mut g = generate_grid_graph::<usize>(20, 20);
+    /// g.par_update_all_data(|id, data| *data = id.0 + id.1);
+    /// g.par_step(|_, value, neighbors| neighbors.iter().copied().fold(*value, |a, b| a.max(*b)));
+    /// assert_eq!(g.node_data[&(0, 0)], 1);
+    /// assert_eq!(g.node_data[&(19, 18)], 38);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_step<F>(&mut self, rule: F)
+    where
+        IDDataType: Send + Sync,
+        NodeDataType: Send + Sync,
+        F: Fn(IDDataType, &NodeDataType, &[&NodeDataType]) -> NodeDataType + Sync,
+    {
+        let next: Vec<(IDDataType, NodeDataType)> = self
+            .nodes
+            .par_iter()
+            .map_init(Vec::new, |neighbor_data, id| {
+                let data = self.node_data.get(id)?;
+                neighbor_data.clear();
+                if let Some(neighbors) = self.edges.get(id) {
+                    neighbor_data.extend(neighbors.iter().filter_map(|n| self.node_data.get(n)));
+                }
+                Some((*id, rule(*id, data, neighbor_data)))
+            })
+            .flatten()
+            .collect();
+        for (id, data) in next {
+            if let Some(current) = self.node_data.get_mut(&id) {
+                *current = data;
+            }
+        }
+    }
+
+    /// Repeatedly applies `step` with the same rule until `condition` is met.
+    /// Returns the number of steps that were run.
+    ///
+    /// # Arguments
+    ///
+    /// * `condition` - Whether to run a fixed number of steps or until nothing changes.
+    /// * `rule` - The update rule, see `step`.
+    ///
+    pub fn run_until<F>(&mut self, condition: StopCondition, mut rule: F) -> usize
+    where
+        NodeDataType: PartialEq,
+        F: FnMut(IDDataType, &NodeDataType, &[&NodeDataType]) -> NodeDataType,
+    {
+        let (max_steps, until_converged) = match condition {
+            StopCondition::Steps(steps) => (steps, false),
+            StopCondition::Convergence { max_steps } => (max_steps, true),
+        };
+        for steps in 0..max_steps {
+            let next = self.next_states(&mut rule);
+            let mut changed = false;
+            for (id, data) in self.nodes.iter().zip(next) {
+                if let Some(current) = self.node_data.get_mut(id) {
+                    changed |= *current != data;
+                    *current = data;
+                }
+            }
+            if until_converged && !changed {
+                return steps + 1;
+            }
+        }
+        max_steps
+    }
+
+    /// computes the next state of every node, in the order of `nodes`.
+    fn next_states<F>(&self, rule: &mut F) -> Vec<NodeDataType>
+    where
+        F: FnMut(IDDataType, &NodeDataType, &[&NodeDataType]) -> NodeDataType,
+    {
+        let mut neighbor_data = Vec::new();
+        self.nodes
+            .iter()
+            .filter_map(|id| {
+                let data = self.node_data.get(id)?;
+                neighbor_data.clear();
+                if let Some(neighbors) = self.edges.get(id) {
+                    neighbor_data.extend(neighbors.iter().filter_map(|n| self.node_data.get(n)));
+                }
+                Some(rule(*id, data, &neighbor_data))
+            })
+            .collect()
+    }
+}
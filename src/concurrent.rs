@@ -0,0 +1,386 @@
+use core::hash::{BuildHasher, Hash};
+use std::{
+    fmt::Debug,
+    sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::{
+    collections::{GraphHasher, NodeMap},
+    Adjacency, Graph,
+};
+
+/// The part of a concurrent graph stored behind one lock. The data of an edge is stored in the
+/// shard of the node it starts at.
+#[derive(Debug)]
+struct Shard<IDDataType, NodeDataType, EdgeDataType> {
+    node_data: NodeMap<IDDataType, NodeDataType>,
+    edges: NodeMap<IDDataType, Adjacency<IDDataType>>,
+    reverse_edges: NodeMap<IDDataType, Adjacency<IDDataType>>,
+    edge_data: NodeMap<(IDDataType, IDDataType), EdgeDataType>,
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Shard<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn new() -> Self {
+        Self {
+            node_data: NodeMap::default(),
+            edges: NodeMap::default(),
+            reverse_edges: NodeMap::default(),
+            edge_data: NodeMap::default(),
+        }
+    }
+
+    /// inserts a node if it is missing, returning whether it was inserted.
+    fn insert_node(&mut self, id: IDDataType, data: impl FnOnce() -> NodeDataType) -> bool {
+        if self.node_data.contains_key(&id) {
+            return false;
+        }
+        self.node_data.insert(id, data());
+        self.edges.insert(id, Adjacency::new());
+        self.reverse_edges.insert(id, Adjacency::new());
+        true
+    }
+}
+
+/// A graph that can be shared between threads and read and mutated through `&self`.
+///
+/// Nodes are distributed over a number of shards by the hash of their ID, and every shard
+/// is protected by its own read-write lock, so operations on different parts of the graph
+/// rarely contend. Adding an edge locks the shards of both endpoints at once, so readers
+/// never observe an edge without its reverse edge.
+///
+/// The method names mirror those of [`Graph`], including the optional data per edge. Because
+/// there is no global node order, `to_graph` lists nodes shard by shard rather than in insertion
+/// order.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::ConcurrentGraph;
+///
+/// let g = ConcurrentGraph::<usize, u32>::new();
+/// std::thread::scope(|scope| {
+///     for t in 0..4 {
+///         let g = &g;
+///         scope.spawn(move || {
+///             for i in 0..100 {
+///                 g.add_edge(t * 100 + i, t * 100 + i + 1);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(g.edge_count(), 800);
+/// ```
+pub struct ConcurrentGraph<IDDataType, NodeDataType, EdgeDataType = ()> {
+    shards: Box<[RwLock<Shard<IDDataType, NodeDataType, EdgeDataType>>]>,
+    hasher: GraphHasher,
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType>
+    ConcurrentGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Creates a new, empty graph with a shard count based on the available parallelism.
+    pub fn new() -> Self {
+        let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+        Self::with_shards(threads * 4)
+    }
+
+    /// Creates a new, empty graph with the given number of shards (at least one).
+    pub fn with_shards(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(Shard::new()))
+                .collect(),
+            hasher: GraphHasher::default(),
+        }
+    }
+
+    fn shard_index(&self, id: &IDDataType) -> usize {
+        (BuildHasher::hash_one(&self.hasher, id) % self.shards.len() as u64) as usize
+    }
+
+    fn read(
+        &self,
+        id: &IDDataType,
+    ) -> RwLockReadGuard<'_, Shard<IDDataType, NodeDataType, EdgeDataType>> {
+        self.shards[self.shard_index(id)]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(
+        &self,
+        id: &IDDataType,
+    ) -> RwLockWriteGuard<'_, Shard<IDDataType, NodeDataType, EdgeDataType>> {
+        self.write_shard(self.shard_index(id))
+    }
+
+    fn write_shard(
+        &self,
+        index: usize,
+    ) -> RwLockWriteGuard<'_, Shard<IDDataType, NodeDataType, EdgeDataType>> {
+        self.shards[index]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Adds a new node with default data. Returns `false` if the node already exists.
+    pub fn add_node(&self, id: IDDataType) -> bool {
+        self.write(&id).insert_node(id, NodeDataType::default)
+    }
+
+    /// Adds a new node with the given data. Returns `false` if the node already exists,
+    /// in which case the existing data is kept.
+    pub fn add_node_with_data(&self, id: IDDataType, data: NodeDataType) -> bool {
+        self.write(&id).insert_node(id, || data)
+    }
+
+    /// Adds a directed edge, creating missing nodes with default data.
+    pub fn add_directed_edge(&self, from: IDDataType, to: IDDataType) {
+        self.insert_directed_edge(from, to, None);
+    }
+
+    /// Adds a directed edge with the given data, creating missing nodes with default data. The
+    /// data replaces that of parallel copies of the edge.
+    pub fn add_directed_edge_with_data(
+        &self,
+        from: IDDataType,
+        to: IDDataType,
+        data: EdgeDataType,
+    ) {
+        self.insert_directed_edge(from, to, Some(data));
+    }
+
+    /// adds a directed edge and its data under the locks of both shards.
+    fn insert_directed_edge(&self, from: IDDataType, to: IDDataType, data: Option<EdgeDataType>) {
+        let (from_index, to_index) = (self.shard_index(&from), self.shard_index(&to));
+
+        if from_index == to_index {
+            let mut shard = self.write_shard(from_index);
+            shard.insert_node(from, NodeDataType::default);
+            shard.insert_node(to, NodeDataType::default);
+            shard.edges.entry(from).or_default().push(to);
+            shard.reverse_edges.entry(to).or_default().push(from);
+            if let Some(data) = data {
+                shard.edge_data.insert((from, to), data);
+            }
+            return;
+        }
+
+        // always lock the lower shard first so concurrent edge insertions cannot deadlock
+        let (mut from_shard, mut to_shard) = if from_index < to_index {
+            let from_shard = self.write_shard(from_index);
+            (from_shard, self.write_shard(to_index))
+        } else {
+            let to_shard = self.write_shard(to_index);
+            (self.write_shard(from_index), to_shard)
+        };
+        from_shard.insert_node(from, NodeDataType::default);
+        to_shard.insert_node(to, NodeDataType::default);
+        from_shard.edges.entry(from).or_default().push(to);
+        to_shard.reverse_edges.entry(to).or_default().push(from);
+        if let Some(data) = data {
+            from_shard.edge_data.insert((from, to), data);
+        }
+    }
+
+    /// Adds an undirected edge, creating missing nodes with default data.
+    pub fn add_edge(&self, from: IDDataType, to: IDDataType) {
+        self.add_directed_edge(from, to);
+        self.add_directed_edge(to, from);
+    }
+
+    /// Whether the node exists.
+    pub fn contains_node(&self, id: IDDataType) -> bool {
+        self.read(&id).node_data.contains_key(&id)
+    }
+
+    /// A snapshot of the outgoing neighbors of a node, or an empty vector if it does not exist.
+    pub fn neighbors(&self, id: IDDataType) -> Vec<IDDataType> {
+        self.read(&id)
+            .edges
+            .get(&id)
+            .map(|tos| tos.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// A snapshot of the incoming neighbors of a node, or an empty vector if it does not exist.
+    pub fn reverse_neighbors(&self, id: IDDataType) -> Vec<IDDataType> {
+        self.read(&id)
+            .reverse_edges
+            .get(&id)
+            .map(|froms| froms.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Calls `f` with the data of a node while holding a read lock on its shard.
+    /// Returns `None` if the node does not exist.
+    pub fn with_node_data<R>(
+        &self,
+        id: IDDataType,
+        f: impl FnOnce(&NodeDataType) -> R,
+    ) -> Option<R> {
+        self.read(&id).node_data.get(&id).map(f)
+    }
+
+    /// Calls `f` with mutable access to the data of a node while holding a write lock on its shard.
+    /// Returns `None` if the node does not exist.
+    pub fn update_node_data<R>(
+        &self,
+        id: IDDataType,
+        f: impl FnOnce(&mut NodeDataType) -> R,
+    ) -> Option<R> {
+        self.write(&id).node_data.get_mut(&id).map(f)
+    }
+
+    /// Calls `f` with the data of the edge `from -> to` while holding a read lock on the shard of
+    /// `from`. Returns `None` if the edge does not exist or has no data.
+    pub fn with_edge_data<R>(
+        &self,
+        from: IDDataType,
+        to: IDDataType,
+        f: impl FnOnce(&EdgeDataType) -> R,
+    ) -> Option<R> {
+        self.read(&from).edge_data.get(&(from, to)).map(f)
+    }
+
+    /// The number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                shard
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .node_data
+                    .len()
+            })
+            .sum()
+    }
+
+    /// The number of directed edges.
+    pub fn edge_count(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let shard = shard.read().unwrap_or_else(PoisonError::into_inner);
+                shard.edges.values().map(|tos| tos.len()).sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Consumes the concurrent graph and returns a regular [`Graph`].
+    ///
+    /// Insertions from different threads have no common order, so the nodes are listed shard by
+    /// shard in the order of the shard maps.
+    pub fn into_graph(self) -> Graph<IDDataType, NodeDataType, EdgeDataType> {
+        let mut graph = Graph::new();
+        for shard in self.shards.into_vec() {
+            let shard = shard.into_inner().unwrap_or_else(PoisonError::into_inner);
+            graph.nodes.extend(shard.node_data.keys().copied());
+            graph.node_data.extend(shard.node_data);
+            graph.edges.extend(shard.edges);
+            graph.reverse_edges.extend(shard.reverse_edges);
+            graph.edge_data.extend(shard.edge_data);
+        }
+        graph
+    }
+}
+
+impl<IDDataType, NodeDataType: Default + Clone, EdgeDataType: Clone>
+    ConcurrentGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A copy of the data of a node, if it exists.
+    pub fn node_data(&self, id: IDDataType) -> Option<NodeDataType> {
+        self.with_node_data(id, NodeDataType::clone)
+    }
+
+    /// A copy of the data of the edge `from -> to`, if the edge exists and has data.
+    pub fn edge_data(&self, from: IDDataType, to: IDDataType) -> Option<EdgeDataType> {
+        self.with_edge_data(from, to, EdgeDataType::clone)
+    }
+
+    /// Copies the current state into a regular [`Graph`].
+    ///
+    /// Shards are locked one at a time, so edges added concurrently may be only partially
+    /// included; take the snapshot while writers are paused if you need an exact copy.
+    pub fn to_graph(&self) -> Graph<IDDataType, NodeDataType, EdgeDataType> {
+        let mut graph = Graph::new();
+        for shard in self.shards.iter() {
+            let shard = shard.read().unwrap_or_else(PoisonError::into_inner);
+            graph.nodes.extend(shard.node_data.keys().copied());
+            graph
+                .node_data
+                .extend(shard.node_data.iter().map(|(id, data)| (*id, data.clone())));
+            graph
+                .edges
+                .extend(shard.edges.iter().map(|(id, tos)| (*id, tos.clone())));
+            graph.reverse_edges.extend(
+                shard
+                    .reverse_edges
+                    .iter()
+                    .map(|(id, froms)| (*id, froms.clone())),
+            );
+            graph.edge_data.extend(
+                shard
+                    .edge_data
+                    .iter()
+                    .map(|(edge, data)| (*edge, data.clone())),
+            );
+        }
+        graph
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Default
+    for ConcurrentGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType>
+    From<Graph<IDDataType, NodeDataType, EdgeDataType>>
+    for ConcurrentGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn from(mut graph: Graph<IDDataType, NodeDataType, EdgeDataType>) -> Self {
+        let concurrent = Self::new();
+        for id in graph.nodes.iter() {
+            let mut shard = concurrent.write(id);
+            if let Some(data) = graph.node_data.remove(id) {
+                shard.insert_node(*id, || data);
+            }
+            shard
+                .edges
+                .insert(*id, graph.edges.remove(id).unwrap_or_default());
+            shard
+                .reverse_edges
+                .insert(*id, graph.reverse_edges.remove(id).unwrap_or_default());
+        }
+        for ((from, to), data) in graph.edge_data {
+            concurrent.write(&from).edge_data.insert((from, to), data);
+        }
+        concurrent
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Debug
+    for ConcurrentGraph<IDDataType, NodeDataType, EdgeDataType>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConcurrentGraph")
+            .field("shards", &self.shards.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,569 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// Answers connectivity queries on an undirected view of a graph while edges are inserted and deleted.
+///
+/// Every component carries a label, so `connected` is a single comparison. The structure
+/// maintains a spanning forest of the graph:
+///
+/// * inserting an edge between two components relabels the smaller one,
+/// * deleting a non-tree edge is O(1),
+/// * deleting a tree edge explores only the smaller of the two resulting trees and looks
+///   for a replacement edge leaving it, relabelling it if none exists.
+///
+/// Direction is ignored, so for directed graphs this tracks weak connectivity.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::DynamicConnectivity;
+///
+/// let mut connectivity = DynamicConnectivity::new();
+/// connectivity.insert_edge(0, 1);
+/// connectivity.insert_edge(1, 2);
+/// connectivity.insert_edge(2, 0);
+/// assert!(connectivity.connected(0, 2));
+///
+/// connectivity.delete_edge(0, 1);
+/// assert!(connectivity.connected(0, 1));
+/// connectivity.delete_edge(1, 2);
+/// assert!(!connectivity.connected(0, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicConnectivity<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// undirected multiplicity of every edge
+    adjacency: NodeMap<IDDataType, NodeMap<IDDataType, usize>>,
+    /// the edges of the spanning forest
+    tree: NodeMap<IDDataType, NodeSet<IDDataType>>,
+    /// component label of every node
+    labels: NodeMap<IDDataType, usize>,
+    /// size of every component by label
+    sizes: NodeMap<usize, usize>,
+    next_label: usize,
+}
+
+impl<IDDataType> DynamicConnectivity<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Creates an empty structure.
+    pub fn new() -> Self {
+        Self {
+            adjacency: NodeMap::default(),
+            tree: NodeMap::default(),
+            labels: NodeMap::default(),
+            sizes: NodeMap::default(),
+            next_label: 0,
+        }
+    }
+
+    /// Builds the structure from the nodes and edges of a graph.
+    pub fn from_graph<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self {
+        let mut connectivity = Self::new();
+        for id in graph.nodes.iter() {
+            connectivity.insert_node(*id);
+        }
+        for (from, to) in graph.edges_iter() {
+            connectivity.insert_edge(from, to);
+        }
+        connectivity
+    }
+
+    /// Builds the structure from a graph and registers observers on the graph that keep
+    /// it up to date as nodes and edges are added or removed.
+    ///
+    /// The structure is shared with the observers, so it is returned behind a mutex.
+    #[cfg(feature = "std")]
+    pub fn attach<NodeDataType, EdgeDataType>(
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Arc<Mutex<Self>>
+    where
+        IDDataType: Send + 'static,
+    {
+        let connectivity = Arc::new(Mutex::new(Self::from_graph(graph)));
+
+        let shared = connectivity.clone();
+        graph.on_node_added(move |id| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert_node(id);
+        });
+        let shared = connectivity.clone();
+        graph.on_edge_added(move |from, to| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert_edge(from, to);
+        });
+        let shared = connectivity.clone();
+        graph.on_node_removed(move |id| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove_node(id);
+        });
+        let shared = connectivity.clone();
+        graph.on_edge_removed(move |from, to| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .delete_edge(from, to);
+        });
+
+        connectivity
+    }
+
+    /// Adds an isolated node. Does nothing if it already exists.
+    pub fn insert_node(&mut self, id: IDDataType) {
+        if self.labels.contains_key(&id) {
+            return;
+        }
+        let label = self.next_label;
+        self.next_label += 1;
+        self.labels.insert(id, label);
+        self.sizes.insert(label, 1);
+        self.adjacency.insert(id, NodeMap::default());
+        self.tree.insert(id, NodeSet::default());
+    }
+
+    /// Adds an (undirected) edge, creating missing nodes.
+    pub fn insert_edge(&mut self, a: IDDataType, b: IDDataType) {
+        self.insert_node(a);
+        self.insert_node(b);
+        *self.adjacency.get_mut(&a).unwrap().entry(b).or_default() += 1;
+        if a != b {
+            *self.adjacency.get_mut(&b).unwrap().entry(a).or_default() += 1;
+        }
+
+        let (label_a, label_b) = (self.labels[&a], self.labels[&b]);
+        if label_a == label_b {
+            return;
+        }
+
+        // relabel the smaller component with the label of the larger one
+        let (small, large_label) = if self.sizes[&label_a] < self.sizes[&label_b] {
+            (a, label_b)
+        } else {
+            (b, label_a)
+        };
+        let members = self.tree_component(small);
+        let small_label = self.labels[&small];
+        self.link(a, b);
+        self.sizes.remove(&small_label);
+        *self.sizes.get_mut(&large_label).unwrap() += members.len();
+        for member in members {
+            self.labels.insert(member, large_label);
+        }
+    }
+
+    /// Removes one copy of an (undirected) edge. Returns `false` if the edge did not exist.
+    pub fn delete_edge(&mut self, a: IDDataType, b: IDDataType) -> bool {
+        let Some(count) = self.adjacency.get_mut(&a).and_then(|n| n.get_mut(&b)) else {
+            return false;
+        };
+        *count -= 1;
+        let remaining = *count;
+        if remaining == 0 {
+            self.adjacency.get_mut(&a).unwrap().remove(&b);
+        }
+        if a != b {
+            let count = self.adjacency.get_mut(&b).unwrap().get_mut(&a).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.adjacency.get_mut(&b).unwrap().remove(&a);
+            }
+        }
+
+        if remaining == 0 && self.tree[&a].contains(&b) {
+            self.cut(a, b);
+            self.reconnect(a, b);
+        }
+        true
+    }
+
+    /// Removes a node and all of its edges. Returns `false` if the node did not exist.
+    pub fn remove_node(&mut self, id: IDDataType) -> bool {
+        let Some(neighbors) = self.adjacency.get(&id) else {
+            return false;
+        };
+        let incident: Vec<(IDDataType, usize)> = neighbors.iter().map(|(n, c)| (*n, *c)).collect();
+        for (neighbor, count) in incident {
+            for _ in 0..count {
+                self.delete_edge(id, neighbor);
+            }
+        }
+
+        let label = self.labels.remove(&id).unwrap();
+        self.sizes.remove(&label);
+        self.adjacency.remove(&id);
+        self.tree.remove(&id);
+        true
+    }
+
+    /// Whether there is a path between `a` and `b`, ignoring edge direction.
+    /// Returns `false` if either node is unknown.
+    pub fn connected(&self, a: IDDataType, b: IDDataType) -> bool {
+        match (self.labels.get(&a), self.labels.get(&b)) {
+            (Some(label_a), Some(label_b)) => label_a == label_b,
+            _ => false,
+        }
+    }
+
+    /// The label of the component containing `id`. Labels are stable until the component
+    /// is merged or split.
+    pub fn component_of(&self, id: IDDataType) -> Option<usize> {
+        self.labels.get(&id).copied()
+    }
+
+    /// The number of nodes in the component containing `id`.
+    pub fn component_size(&self, id: IDDataType) -> Option<usize> {
+        self.labels.get(&id).map(|label| self.sizes[label])
+    }
+
+    /// The number of connected components.
+    pub fn component_count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    fn link(&mut self, a: IDDataType, b: IDDataType) {
+        self.tree.get_mut(&a).unwrap().insert(b);
+        self.tree.get_mut(&b).unwrap().insert(a);
+    }
+
+    fn cut(&mut self, a: IDDataType, b: IDDataType) {
+        self.tree.get_mut(&a).unwrap().remove(&b);
+        self.tree.get_mut(&b).unwrap().remove(&a);
+    }
+
+    /// all nodes in the spanning tree containing `start`.
+    fn tree_component(&self, start: IDDataType) -> Vec<IDDataType> {
+        let mut seen = NodeSet::default();
+        let mut queue = VecDeque::from([start]);
+        seen.insert(start);
+        let mut members = Vec::new();
+        while let Some(node) = queue.pop_front() {
+            members.push(node);
+            for next in self.tree[&node].iter() {
+                if seen.insert(*next) {
+                    queue.push_back(*next);
+                }
+            }
+        }
+        members
+    }
+
+    /// after cutting the tree edge `a - b`, either finds a replacement edge or splits the component.
+    fn reconnect(&mut self, a: IDDataType, b: IDDataType) {
+        let side = self.smaller_tree_side(a, b);
+        let side_set: NodeSet<IDDataType> = side.iter().copied().collect();
+
+        let replacement = side.iter().find_map(|node| {
+            self.adjacency[node]
+                .keys()
+                .find(|&neighbor| !side_set.contains(neighbor))
+                .map(|neighbor| (*node, *neighbor))
+        });
+
+        match replacement {
+            Some((inside, outside)) => self.link(inside, outside),
+            None => {
+                let old_label = self.labels[&a];
+                let new_label = self.next_label;
+                self.next_label += 1;
+                *self.sizes.get_mut(&old_label).unwrap() -= side.len();
+                self.sizes.insert(new_label, side.len());
+                for node in side {
+                    self.labels.insert(node, new_label);
+                }
+            }
+        }
+    }
+
+    /// explores the trees containing `a` and `b` in lockstep and returns the nodes of the
+    /// one that is exhausted first, so the work is proportional to the smaller side.
+    fn smaller_tree_side(&self, a: IDDataType, b: IDDataType) -> Vec<IDDataType> {
+        let mut sides = [a, b].map(|start| {
+            let mut seen = NodeSet::default();
+            seen.insert(start);
+            (VecDeque::from([start]), seen, vec![start])
+        });
+
+        loop {
+            for (queue, seen, members) in sides.iter_mut() {
+                let Some(node) = queue.pop_front() else {
+                    return core::mem::take(members);
+                };
+                for next in self.tree[&node].iter() {
+                    if seen.insert(*next) {
+                        queue.push_back(*next);
+                        members.push(*next);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<IDDataType> Default for DynamicConnectivity<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sets of nodes merged by edges, answering whether two nodes are connected while edges are only
+/// ever added (a union-find, or disjoint-set, structure).
+///
+/// Unlike [`DynamicConnectivity`], it cannot split a component when an edge is removed, but
+/// adding an edge and querying take near constant time: the sets are trees merged by size, and
+/// the paths to the roots are halved on every merge. Direction is ignored, so for directed graphs
+/// this tracks weak connectivity.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::DisjointSet;
+///
+/// let mut sets = DisjointSet::new();
+/// sets.union(0, 1);
+/// sets.union(2, 3);
+/// sets.insert(4);
+/// assert!(sets.same_component(1, 0));
+/// assert!(!sets.same_component(1, 2));
+/// assert_eq!(sets.set_count(), 3);
+///
+/// assert!(sets.union(1, 3));
+/// assert!(!sets.union(0, 2));
+/// assert_eq!(sets.set_size(3), Some(4));
+/// assert_eq!(sets.sets(), vec![vec![0, 1, 2, 3], vec![4]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisjointSet<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// the elements in insertion order
+    ids: Vec<IDDataType>,
+    index: NodeMap<IDDataType, usize>,
+    /// the parent of every element by index, roots being their own parent
+    parent: Vec<usize>,
+    /// the number of elements in the set of every root
+    size: Vec<usize>,
+    set_count: usize,
+}
+
+impl<IDDataType> DisjointSet<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Creates an empty structure.
+    pub fn new() -> Self {
+        Self {
+            ids: Vec::new(),
+            index: NodeMap::default(),
+            parent: Vec::new(),
+            size: Vec::new(),
+            set_count: 0,
+        }
+    }
+
+    /// Builds the structure from the nodes and edges of a graph, see
+    /// `Graph::components_union_find`.
+    pub fn from_graph<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self {
+        let mut sets = Self::new();
+        for id in &graph.nodes {
+            sets.insert(*id);
+        }
+        for (from, to) in graph.edges_iter() {
+            sets.union(from, to);
+        }
+        sets
+    }
+
+    /// Builds the structure from a graph and registers observers on the graph that add its new
+    /// nodes and edges, so connectivity queries stay current as the graph grows.
+    ///
+    /// Removals cannot be undone in a union-find structure, so after removing nodes or edges the
+    /// structure still considers the nodes connected; use [`DynamicConnectivity::attach`] if the
+    /// graph also shrinks. The structure is shared with the observers, so it is returned behind a
+    /// mutex.
+    #[cfg(feature = "std")]
+    pub fn attach<NodeDataType, EdgeDataType>(
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Arc<Mutex<Self>>
+    where
+        IDDataType: Send + 'static,
+    {
+        let sets = Arc::new(Mutex::new(Self::from_graph(graph)));
+
+        let shared = sets.clone();
+        graph.on_node_added(move |id| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(id);
+        });
+        let shared = sets.clone();
+        graph.on_edge_added(move |from, to| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .union(from, to);
+        });
+
+        sets
+    }
+
+    /// Adds an element in a set of its own. Does nothing if it already exists.
+    pub fn insert(&mut self, id: IDDataType) {
+        if self.index.contains_key(&id) {
+            return;
+        }
+        let i = self.ids.len();
+        self.ids.push(id);
+        self.index.insert(id, i);
+        self.parent.push(i);
+        self.size.push(1);
+        self.set_count += 1;
+    }
+
+    /// Merges the sets of two elements, adding missing ones. Returns whether they were in
+    /// different sets.
+    pub fn union(&mut self, a: IDDataType, b: IDDataType) -> bool {
+        self.insert(a);
+        self.insert(b);
+        let (a, b) = (
+            self.root_halving(self.index[&a]),
+            self.root_halving(self.index[&b]),
+        );
+        if a == b {
+            return false;
+        }
+        let (small, large) = if self.size[a] < self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.set_count -= 1;
+        true
+    }
+
+    /// The representative of the set containing `id`, the same for all its elements until the
+    /// set is merged, or `None` if the element is unknown.
+    pub fn find(&self, id: IDDataType) -> Option<IDDataType> {
+        self.index.get(&id).map(|i| self.ids[self.root(*i)])
+    }
+
+    /// Whether two elements are in the same set. Returns `false` if either is unknown.
+    pub fn same_component(&self, a: IDDataType, b: IDDataType) -> bool {
+        match (self.index.get(&a), self.index.get(&b)) {
+            (Some(a), Some(b)) => self.root(*a) == self.root(*b),
+            _ => false,
+        }
+    }
+
+    /// The number of elements in the set containing `id`.
+    pub fn set_size(&self, id: IDDataType) -> Option<usize> {
+        self.index.get(&id).map(|i| self.size[self.root(*i)])
+    }
+
+    /// The number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The sets, ordered by their first element, with the elements of every set in insertion
+    /// order.
+    pub fn sets(&self) -> Vec<Vec<IDDataType>> {
+        let mut position = vec![usize::MAX; self.ids.len()];
+        let mut sets: Vec<Vec<IDDataType>> = Vec::with_capacity(self.set_count);
+        for (i, id) in self.ids.iter().enumerate() {
+            let root = self.root(i);
+            if position[root] == usize::MAX {
+                position[root] = sets.len();
+                sets.push(Vec::new());
+            }
+            sets[position[root]].push(*id);
+        }
+        sets
+    }
+
+    fn root(&self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// the root of `i`, pointing every node on the way to its grandparent.
+    fn root_halving(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+}
+
+impl<IDDataType> Default for DisjointSet<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The connected components, ignoring edge directions, as a [`DisjointSet`] that can keep
+    /// absorbing new edges; its `sets` are the same as `connected_components`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1; 2 -> 1; 3 -> 4; 5").unwrap();
+    /// let mut components = g.components_union_find();
+    /// assert_eq!(components.sets(), g.connected_components());
+    /// assert!(components.same_component(0, 2));
+    ///
+    /// components.union(4, 5);
+    /// assert_eq!(components.set_count(), 2);
+    /// ```
+    pub fn components_union_find(&self) -> DisjointSet<IDDataType> {
+        DisjointSet::from_graph(self)
+    }
+}
@@ -0,0 +1,242 @@
+//! Checking and restoring the agreement between the public fields of a graph.
+//!
+//! The methods of `Graph` keep `nodes`, `node_data`, `edges` and `reverse_edges` in step, but all
+//! four fields are public and code that writes to them directly can leave them out of step, which
+//! makes queries such as `reverse_neighbors` or the traversals silently disagree with each other.
+
+use alloc::vec::Vec;
+use core::{cmp::Ordering, fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{Entry, NodeMap, NodeSet},
+    Adjacency, Graph,
+};
+
+/// A way in which the fields of a graph disagree, found by `Graph::check_consistency`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantViolation<IDDataType> {
+    /// The node is listed more than once in `nodes`.
+    DuplicateNode(IDDataType),
+    /// The node is listed in `nodes` but has no entry in `node_data`.
+    MissingData(IDDataType),
+    /// The node has an entry in `node_data` but is not listed in `nodes`.
+    UnlistedNode(IDDataType),
+    /// The node has no adjacency list in `edges` or `reverse_edges`, so the `try_` neighbor
+    /// queries report it as missing.
+    MissingAdjacency(IDDataType),
+    /// The edge in `edges` starts or ends at an ID that is not a node.
+    DanglingEdge(IDDataType, IDDataType),
+    /// The edge is in `edges` more often than `reverse_edges` lists it.
+    MissingReverseEdge(IDDataType, IDDataType),
+    /// The edge is listed in `reverse_edges` more often than it is in `edges`.
+    StrayReverseEdge(IDDataType, IDDataType),
+    /// The edge has an entry in `edge_data` but is not in `edges`.
+    StrayEdgeData(IDDataType, IDDataType),
+}
+
+impl<IDDataType: Debug> core::fmt::Display for InvariantViolation<IDDataType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InvariantViolation::DuplicateNode(id) => write!(f, "node {id:?} is listed twice"),
+            InvariantViolation::MissingData(id) => write!(f, "node {id:?} has no data"),
+            InvariantViolation::UnlistedNode(id) => {
+                write!(f, "node {id:?} has data but is not listed")
+            }
+            InvariantViolation::MissingAdjacency(id) => {
+                write!(f, "node {id:?} has no adjacency list")
+            }
+            InvariantViolation::DanglingEdge(from, to) => {
+                write!(
+                    f,
+                    "edge {from:?} -> {to:?} has an endpoint that is not a node"
+                )
+            }
+            InvariantViolation::MissingReverseEdge(from, to) => {
+                write!(
+                    f,
+                    "edge {from:?} -> {to:?} is missing from the reverse edges"
+                )
+            }
+            InvariantViolation::StrayReverseEdge(from, to) => {
+                write!(f, "reverse edge {from:?} -> {to:?} has no forward edge")
+            }
+            InvariantViolation::StrayEdgeData(from, to) => {
+                write!(f, "edge {from:?} -> {to:?} has data but does not exist")
+            }
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Lists every way in which `nodes`, `node_data`, `edges` and `reverse_edges` disagree; an
+    /// empty list means the graph is consistent. The problems with the nodes come first, in the
+    /// order of `nodes`, followed by the problems with the edges.
+    ///
+    /// Every node must be listed once in `nodes` and have data and an adjacency list in both
+    /// edge maps, every edge must connect two nodes, and `reverse_edges` must list every edge of
+    /// `edges`, as often as it occurs there, and nothing else. Edge data may only exist for edges
+    /// in `edges`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{consistency::InvariantViolation, Graph};
+    ///
+    /// let mut g = Graph::<u32, ()>::parse("0 -> 1").unwrap();
+    /// assert!(g.check_consistency().is_empty());
+    ///
+    /// g.edges.get_mut(&1).unwrap().push(0);
+    /// assert_eq!(
+    ///     g.check_consistency(),
+    ///     vec![InvariantViolation::MissingReverseEdge(1, 0)]
+    /// );
+    /// ```
+    pub fn check_consistency(&self) -> Vec<InvariantViolation<IDDataType>> {
+        let mut violations = Vec::new();
+        let mut listed = NodeSet::default();
+        for id in &self.nodes {
+            if !listed.insert(*id) {
+                violations.push(InvariantViolation::DuplicateNode(*id));
+                continue;
+            }
+            if !self.node_data.contains_key(id) {
+                violations.push(InvariantViolation::MissingData(*id));
+            }
+            if !self.edges.contains_key(id) || !self.reverse_edges.contains_key(id) {
+                violations.push(InvariantViolation::MissingAdjacency(*id));
+            }
+        }
+        for id in self.node_data.keys().filter(|id| !listed.contains(*id)) {
+            violations.push(InvariantViolation::UnlistedNode(*id));
+        }
+
+        let is_node = |id: &IDDataType| listed.contains(id) && self.node_data.contains_key(id);
+        // forward edges count up and reverse edges down, so a nonzero balance is a mismatch
+        let mut balance: NodeMap<(IDDataType, IDDataType), isize> = NodeMap::default();
+        let mut order = Vec::new();
+        let mut count = |edge, delta| match balance.entry(edge) {
+            Entry::Vacant(slot) => {
+                order.push(edge);
+                slot.insert(delta);
+            }
+            Entry::Occupied(mut slot) => *slot.get_mut() += delta,
+        };
+        for (from, tos) in &self.edges {
+            for to in tos {
+                if !is_node(from) || !is_node(to) {
+                    violations.push(InvariantViolation::DanglingEdge(*from, *to));
+                }
+                count((*from, *to), 1);
+            }
+        }
+        for (to, froms) in &self.reverse_edges {
+            for from in froms {
+                count((*from, *to), -1);
+            }
+        }
+        for (from, to) in order {
+            match balance[&(from, to)].cmp(&0) {
+                Ordering::Greater => {
+                    violations.push(InvariantViolation::MissingReverseEdge(from, to))
+                }
+                Ordering::Less => violations.push(InvariantViolation::StrayReverseEdge(from, to)),
+                Ordering::Equal => {}
+            }
+        }
+        for (from, to) in self.edge_data.keys() {
+            if !self.neighbors(*from).contains(to) {
+                violations.push(InvariantViolation::StrayEdgeData(*from, *to));
+            }
+        }
+        violations
+    }
+
+    /// Checks that `nodes`, `node_data`, `edges` and `reverse_edges` agree, returning every way
+    /// in which they do not as the error, see `check_consistency`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{consistency::InvariantViolation, Graph};
+    ///
+    /// let mut g = Graph::<u32, ()>::parse("0 -> 1").unwrap();
+    /// assert_eq!(g.validate(), Ok(()));
+    ///
+    /// g.node_data.insert(2, ());
+    /// assert_eq!(g.validate(), Err(vec![InvariantViolation::UnlistedNode(2)]));
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<InvariantViolation<IDDataType>>> {
+        let violations = self.check_consistency();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Makes the graph consistent and returns the violations that were found, see
+    /// `check_consistency`. Only the cached properties are forgotten if there were none, see
+    /// `invalidate_caches`.
+    ///
+    /// The repair never invents node data: `edges` is taken as the truth and
+    ///
+    /// * duplicates are removed from `nodes`, keeping the first, and nodes without data are
+    ///   removed together with their edges,
+    /// * nodes with data that are not listed are appended to `nodes`,
+    /// * edges with an endpoint that is not a node are removed, and so is the data of edges that
+    ///   do not exist,
+    /// * `reverse_edges` is rebuilt from `edges`, in the order of `nodes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let mut g = generate_grid_graph::<()>(3, 3);
+    /// g.reverse_edges.clear();
+    /// assert!(!g.repair().is_empty());
+    /// assert!(g.check_consistency().is_empty());
+    /// assert_eq!(g.reverse_neighbors((0, 0)).len(), 2);
+    /// ```
+    pub fn repair(&mut self) -> Vec<InvariantViolation<IDDataType>> {
+        // the fields may have been changed consistently by hand, so the caches go either way
+        self.invalidate_caches();
+        let violations = self.check_consistency();
+        if violations.is_empty() {
+            return violations;
+        }
+
+        let mut listed = NodeSet::default();
+        let data = &self.node_data;
+        self.nodes
+            .retain(|id| data.contains_key(id) && listed.insert(*id));
+        for id in self.node_data.keys() {
+            if listed.insert(*id) {
+                self.nodes.push(*id);
+            }
+        }
+
+        self.edges.retain(|from, _| listed.contains(from));
+        for tos in self.edges.values_mut() {
+            tos.retain(|to| listed.contains(to));
+        }
+        let edges = &self.edges;
+        self.edge_data
+            .retain(|(from, to), _| edges.get(from).is_some_and(|tos| tos.contains(to)));
+        self.reverse_edges.clear();
+        for id in &self.nodes {
+            self.edges.entry(*id).or_default();
+            self.reverse_edges.insert(*id, Adjacency::new());
+        }
+        for from in &self.nodes {
+            for to in &self.edges[from] {
+                self.reverse_edges.entry(*to).or_default().push(*from);
+            }
+        }
+        self.invalidate_caches();
+        violations
+    }
+}
@@ -0,0 +1,159 @@
+//! Contracting edges and groups of nodes into single nodes, the basic step of coarsening and of
+//! building quotient graphs.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeSet, Graph, GraphError};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Contracts the edge between `from` and `to`: `to` is merged into `from`, see
+    /// `contract_nodes`. Fails with `GraphError::MissingNode` if either node does not exist and
+    /// with `GraphError::MissingEdge` if there is no edge between them in either direction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, u32>::new();
+    /// for id in 0..4 {
+    ///     g.add_node_with_data(id, 1);
+    /// }
+    /// g.add_edges(&[(0, 1), (1, 2), (2, 3), (3, 0)]);
+    ///
+    /// g.contract_edge(1, 2, |a, b| a + b, false).unwrap();
+    /// assert_eq!(g.nodes, vec![0, 1, 3]);
+    /// assert_eq!(g.node_data[&1], 2);
+    /// assert!(g.neighbors(1).contains(&3));
+    /// assert!(g.is_undirected());
+    /// ```
+    pub fn contract_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        merge: impl FnMut(NodeDataType, NodeDataType) -> NodeDataType,
+        keep_self_loops: bool,
+    ) -> Result<(), GraphError<IDDataType>> {
+        self.require_nodes(from, to)?;
+        if !self.neighbors(from).contains(&to) && !self.neighbors(to).contains(&from) {
+            return Err(GraphError::MissingEdge(from, to));
+        }
+        self.contract_nodes(&[from, to], merge, keep_self_loops)
+    }
+
+    /// Merges `nodes` into their first node, which keeps its ID: the other nodes are removed and
+    /// every edge to or from one of them is redirected to the first node, keeping its edge data.
+    /// The data of the merged node is the data of all nodes folded with `merge` in the given
+    /// order.
+    ///
+    /// Edges inside the group become self-loops of the merged node, which are dropped along with
+    /// its existing self-loops unless `keep_self_loops` is set. Redirected edges the edge policy
+    /// forbids are dropped, so a graph without parallel edges keeps one copy of the edges that
+    /// now coincide, with the data of the edge that was there first. Observers see the removal
+    /// of the other nodes and the addition of the redirected edges.
+    ///
+    /// Fails with `GraphError::MissingNode`, without changing the graph, if any of the nodes does
+    /// not exist. Nothing happens for fewer than two distinct nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, Vec<u32>, &str>::new();
+    /// for id in 0..5 {
+    ///     g.add_node_with_data(id, vec![id]);
+    /// }
+    /// g.add_directed_edge_with_data(0, 1, "a");
+    /// g.add_directed_edge_with_data(1, 2, "b");
+    /// g.add_directed_edge_with_data(2, 3, "c");
+    /// g.add_directed_edge_with_data(4, 2, "d");
+    ///
+    /// g.contract_nodes(&[1, 2, 3], |mut a, b| { a.extend(b); a }, true).unwrap();
+    /// assert_eq!(g.nodes, vec![0, 1, 4]);
+    /// assert_eq!(g.node_data[&1], vec![1, 2, 3]);
+    /// assert_eq!(g.edge_data(4, 1), Some(&"d"));
+    /// assert_eq!(g.neighbors(1), &[1, 1]);
+    /// ```
+    pub fn contract_nodes(
+        &mut self,
+        nodes: &[IDDataType],
+        mut merge: impl FnMut(NodeDataType, NodeDataType) -> NodeDataType,
+        keep_self_loops: bool,
+    ) -> Result<(), GraphError<IDDataType>> {
+        if let Some(missing) = nodes.iter().find(|id| !self.node_data.contains_key(*id)) {
+            return Err(GraphError::MissingNode(*missing));
+        }
+        let Some((&kept, rest)) = nodes.split_first() else {
+            return Ok(());
+        };
+        let mut group = NodeSet::default();
+        group.insert(kept);
+        let merged: Vec<IDDataType> = rest
+            .iter()
+            .copied()
+            .filter(|id| group.insert(*id))
+            .collect();
+        if merged.is_empty() {
+            return Ok(());
+        }
+
+        // the edges of the merged nodes, each listed once, with the data taken out of the map
+        let mut moved = Vec::new();
+        for id in &merged {
+            for to in self.neighbors(*id) {
+                moved.push((*id, *to));
+            }
+            for from in self.reverse_neighbors(*id) {
+                if *from == kept || !group.contains(from) {
+                    moved.push((*from, *id));
+                }
+            }
+        }
+        let moved: Vec<_> = moved
+            .into_iter()
+            .map(|(from, to)| (from, to, self.edge_data.remove(&(from, to))))
+            .collect();
+
+        let mut data = self
+            .node_data
+            .remove(&kept)
+            .expect("the node was checked to exist");
+        for id in &merged {
+            data = merge(
+                data,
+                self.remove_node(*id)
+                    .expect("the node was checked to exist"),
+            );
+        }
+        self.node_data.insert(kept, data);
+
+        let redirect = |id: IDDataType| if group.contains(&id) { kept } else { id };
+        for (from, to, edge_data) in moved {
+            let (from, to) = (redirect(from), redirect(to));
+            if from == to && !keep_self_loops {
+                continue;
+            }
+            if self.admit_edge(from, to).is_err() {
+                continue;
+            }
+            self.edges.entry(from).or_default().push(to);
+            self.reverse_edges.entry(to).or_default().push(from);
+            self.edge_index.insert(from, to);
+            self.observers.edge_added(from, to);
+            if let Some(edge_data) = edge_data {
+                self.edge_data.entry((from, to)).or_insert(edge_data);
+            }
+        }
+        if !keep_self_loops {
+            while self.remove_directed_edge(kept, kept) {}
+        }
+        self.undirected.invalidate();
+        self.compact.invalidate();
+        Ok(())
+    }
+}
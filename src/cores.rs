@@ -0,0 +1,122 @@
+//! Core decomposition: the nested subgraphs in which every node has at least `k` neighbors.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The core number of every node: the largest `k` such that the node belongs to the
+    /// `k`-core, see [`Graph::k_core`]. Edge directions are ignored, parallel edges count once
+    /// and self-loops are left out.
+    ///
+    /// Nodes are peeled off in the order of their remaining degree, kept in buckets, which takes
+    /// `O(n + m)` time (Batagelj and Zaversnik).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // a triangle with a tail
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 2 -- 3 -- 4; 5").unwrap();
+    /// let cores = g.core_numbers();
+    /// assert_eq!(cores[&0], 2);
+    /// assert_eq!(cores[&3], 1);
+    /// assert_eq!(cores[&5], 0);
+    /// assert_eq!(g.degeneracy(), 2);
+    /// ```
+    pub fn core_numbers(&self) -> NodeMap<IDDataType, usize> {
+        let (_, cores) = self.degeneracy_order();
+        self.nodes.iter().copied().zip(cores).collect()
+    }
+
+    /// The degeneracy: the largest core number, the smallest `d` such that every subgraph has a
+    /// node with at most `d` neighbors. It is 0 for a graph without edges.
+    pub fn degeneracy(&self) -> usize {
+        self.degeneracy_order().1.into_iter().max().unwrap_or(0)
+    }
+
+    /// The `k`-core: the largest induced subgraph in which every node has at least `k` neighbors,
+    /// ignoring edge directions. It contains the nodes with a core number of at least `k`, with
+    /// their data and the edges between them, and is empty if there are none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 2 -- 3 -- 4; 5").unwrap();
+    /// assert_eq!(g.k_core(2).nodes, vec![0, 1, 2]);
+    /// assert_eq!(g.k_core(1).nodes.len(), 5);
+    /// assert!(g.k_core(3).nodes.is_empty());
+    /// ```
+    pub fn k_core(&self, k: usize) -> Self
+    where
+        NodeDataType: Clone,
+        EdgeDataType: Clone,
+    {
+        let (_, cores) = self.degeneracy_order();
+        let kept: Vec<IDDataType> = self
+            .nodes
+            .iter()
+            .zip(cores)
+            .filter(|(_, core)| *core >= k)
+            .map(|(id, _)| *id)
+            .collect();
+        self.subgraph(&kept)
+    }
+
+    /// the positions of the nodes in the order they are peeled off, each with at most the
+    /// degeneracy many neighbors among the later ones, and the core number of every node by
+    /// position.
+    pub(crate) fn degeneracy_order(&self) -> (Vec<usize>, Vec<usize>) {
+        let adjacency: Vec<Vec<usize>> = self
+            .undirected_sets()
+            .into_iter()
+            .map(|neighbors| neighbors.into_iter().collect())
+            .collect();
+        let n = adjacency.len();
+        let mut degree: Vec<usize> = adjacency.iter().map(Vec::len).collect();
+        let max_degree = degree.iter().copied().max().unwrap_or(0);
+
+        // the nodes sorted by degree, with the start of every degree's bucket
+        let mut start = vec![0; max_degree + 2];
+        for d in &degree {
+            start[*d + 1] += 1;
+        }
+        for d in 1..start.len() {
+            start[d] += start[d - 1];
+        }
+        let mut order = vec![0; n];
+        let mut position = vec![0; n];
+        let mut next = start.clone();
+        for (node, d) in degree.iter().enumerate() {
+            position[node] = next[*d];
+            order[next[*d]] = node;
+            next[*d] += 1;
+        }
+
+        for i in 0..n {
+            let node = order[i];
+            for neighbor in &adjacency[node] {
+                let d = degree[*neighbor];
+                if d <= degree[node] {
+                    continue;
+                }
+                // move the neighbor to the front of its bucket and shrink the bucket by one
+                let front = start[d].max(i + 1);
+                let other = order[front];
+                order.swap(position[*neighbor], front);
+                position[other] = position[*neighbor];
+                position[*neighbor] = front;
+                start[d] = front + 1;
+                degree[*neighbor] -= 1;
+            }
+        }
+        (order, degree)
+    }
+}
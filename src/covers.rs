@@ -0,0 +1,114 @@
+//! Matchings, vertex covers and independent sets, found greedily: fast approximations to
+//! problems that are NP-hard to solve exactly.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{collections::NodeSet, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A maximal matching: edges without common endpoints such that every other edge shares an
+    /// endpoint with one of them. Edge directions are ignored and self-loops are left out; the
+    /// edges are taken greedily in the order of `edge_tuples`, so a matching edge keeps its
+    /// direction.
+    ///
+    /// A maximal matching has at least half as many edges as a maximum one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 3").unwrap();
+    /// assert_eq!(g.maximal_matching(), vec![(0, 1), (2, 3)]);
+    /// ```
+    pub fn maximal_matching(&self) -> Vec<(IDDataType, IDDataType)> {
+        let mut matched = NodeSet::default();
+        let mut matching = Vec::new();
+        for (from, to) in self.edges_iter() {
+            if from != to && !matched.contains(&from) && !matched.contains(&to) {
+                matched.insert(from);
+                matched.insert(to);
+                matching.push((from, to));
+            }
+        }
+        matching
+    }
+
+    /// A vertex cover at most twice as large as the smallest one: nodes such that every edge has
+    /// an endpoint among them. These are the endpoints of a `maximal_matching` and the nodes with
+    /// self-loops, in the order of `nodes`; every cover needs a different node for each matching
+    /// edge, which gives the bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // a star is covered by its center, the approximation takes one leaf as well
+    /// let g = Graph::<u32, ()>::parse("0 -- 1; 0 -- 2; 0 -- 3").unwrap();
+    /// let cover = g.approx_min_vertex_cover();
+    /// assert_eq!(cover, vec![0, 1]);
+    /// assert!(g.edge_tuples().iter().all(|(a, b)| cover.contains(a) || cover.contains(b)));
+    /// ```
+    pub fn approx_min_vertex_cover(&self) -> Vec<IDDataType> {
+        let mut cover: NodeSet<IDDataType> = self
+            .maximal_matching()
+            .into_iter()
+            .flat_map(|(from, to)| [from, to])
+            .collect();
+        cover.extend(
+            self.edges_iter()
+                .filter(|(from, to)| from == to)
+                .map(|(id, _)| id),
+        );
+        self.nodes
+            .iter()
+            .copied()
+            .filter(|id| cover.contains(id))
+            .collect()
+    }
+
+    /// A maximal independent set: nodes of which no two are linked, such that every other node is
+    /// linked to one of them. Edge directions are ignored, and nodes with self-loops are never in
+    /// the set. The result is in the order of `nodes`.
+    ///
+    /// The nodes are tried in an order drawn from `rng`, each joining the set unless one of its
+    /// neighbors is already in it. This is the set that Luby's parallel algorithm finds with the
+    /// same random priorities, and takes `O(n + m)` time. Its complement is a vertex cover.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_cycle_graph;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let g = generate_cycle_graph::<()>(10);
+    /// let set = g.maximal_independent_set(StdRng::seed_from_u64(1));
+    /// assert!(set.len() >= 4 && set.len() <= 5);
+    /// assert!(g.edge_tuples().iter().all(|(a, b)| !(set.contains(a) && set.contains(b))));
+    /// ```
+    pub fn maximal_independent_set<R: Rng>(&self, mut rng: R) -> Vec<IDDataType> {
+        let mut order = self.nodes.clone();
+        order.shuffle(&mut rng);
+        let mut blocked: NodeSet<IDDataType> = NodeSet::default();
+        let mut chosen = NodeSet::default();
+        for id in order {
+            if blocked.contains(&id) || self.neighbors(id).contains(&id) {
+                continue;
+            }
+            chosen.insert(id);
+            blocked.extend(self.neighbors(id).iter().chain(self.reverse_neighbors(id)));
+        }
+        self.nodes
+            .iter()
+            .copied()
+            .filter(|id| chosen.contains(id))
+            .collect()
+    }
+}
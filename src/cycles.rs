@@ -0,0 +1,265 @@
+//! Enumerating the cycles of a directed graph and finding cycles that are optimal with respect to
+//! edge weights.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Every elementary cycle of the directed graph, a closed walk that visits no node twice,
+    /// see `find_cycles_limited`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 1 -> 0; 2 -> 2; 3 -> 0").unwrap();
+    /// assert_eq!(
+    ///     g.find_all_cycles(),
+    ///     vec![vec![0, 1], vec![0, 1, 2], vec![2]]
+    /// );
+    /// ```
+    pub fn find_all_cycles(&self) -> Vec<Vec<IDDataType>> {
+        self.find_cycles_limited(None, None)
+    }
+
+    /// The elementary cycles of the directed graph, closed walks that visit no node twice, with
+    /// at most `max_length` nodes each and at most `max_cycles` of them. A cycle is given by its
+    /// nodes in order, starting at the one that comes first in `nodes` and without repeating it
+    /// at the end; a self-loop is a cycle of one node and parallel edges count once. Undirected
+    /// edges form a cycle of two nodes.
+    ///
+    /// This is Johnson's algorithm, which takes `O((n + m)(c + 1))` time for `c` cycles. A graph
+    /// can have exponentially many cycles, so bounding them is advisable on anything but small
+    /// or sparse graphs; the length bound also prunes the search.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_complete_graph;
+    ///
+    /// let g = generate_complete_graph::<()>(5);
+    /// assert_eq!(g.find_all_cycles().len(), 84);
+    /// assert_eq!(g.find_cycles_limited(None, Some(2)).len(), 10);
+    /// assert_eq!(g.find_cycles_limited(Some(3), None).len(), 3);
+    /// ```
+    pub fn find_cycles_limited(
+        &self,
+        max_cycles: Option<usize>,
+        max_length: Option<usize>,
+    ) -> Vec<Vec<IDDataType>> {
+        let n = self.nodes.len();
+        let (mut forward, mut backward) = self.index_adjacency(true);
+        for neighbors in forward.iter_mut().chain(backward.iter_mut()) {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+        let max_cycles = max_cycles.unwrap_or(usize::MAX);
+        let max_length = max_length.unwrap_or(n);
+        let mut cycles: Vec<Vec<IDDataType>> = Vec::new();
+        if max_cycles == 0 || max_length == 0 {
+            return cycles;
+        }
+
+        let mut component = vec![false; n];
+        let mut blocked = vec![false; n];
+        // the nodes to unblock along with a node, Johnson's B lists
+        let mut blocking: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for start in 0..n {
+            // the cycles through `start` among the nodes from `start` on lie in its strongly
+            // connected component there
+            let reach = |adjacency: &[Vec<usize>]| {
+                let mut reached = vec![false; n];
+                reached[start] = true;
+                let mut queue = VecDeque::from([start]);
+                while let Some(node) = queue.pop_front() {
+                    for &next in &adjacency[node] {
+                        if next > start && !reached[next] {
+                            reached[next] = true;
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                reached
+            };
+            let (from_start, to_start) = (reach(&forward), reach(&backward));
+            for node in 0..n {
+                component[node] = from_start[node] && to_start[node];
+                blocked[node] = false;
+                blocking[node].clear();
+            }
+
+            // depth-first search for paths back to `start`, with the position in the neighbor
+            // list of every node on the path and whether a cycle was found from it
+            let mut path = vec![start];
+            let mut frames = vec![(0, false)];
+            blocked[start] = true;
+            while let Some(&node) = path.last() {
+                let frame = frames.len() - 1;
+                let next = forward[node].get(frames[frame].0).copied();
+                frames[frame].0 += 1;
+                match next {
+                    Some(next) if !component[next] => {}
+                    Some(next) if next == start => {
+                        cycles.push(path.iter().map(|i| self.nodes[*i]).collect());
+                        if cycles.len() == max_cycles {
+                            return cycles;
+                        }
+                        frames[frame].1 = true;
+                    }
+                    Some(next) if !blocked[next] => {
+                        if path.len() < max_length {
+                            path.push(next);
+                            frames.push((0, false));
+                            blocked[next] = true;
+                        } else {
+                            // a longer cycle may run through the node, so it must not stay
+                            // blocked as if there were none
+                            frames[frame].1 = true;
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        let (_, found) = frames.pop().expect("every node on the path has a frame");
+                        path.pop();
+                        if found {
+                            unblock(node, &mut blocked, &mut blocking);
+                        } else {
+                            for &next in forward[node].iter().filter(|next| component[**next]) {
+                                if !blocking[next].contains(&node) {
+                                    blocking[next].push(node);
+                                }
+                            }
+                        }
+                        if let Some(parent) = frames.last_mut() {
+                            parent.1 |= found;
+                        }
+                    }
+                }
+            }
+        }
+        cycles
+    }
+
+    /// The directed cycle with the smallest mean edge weight and that mean, or `None` if the
+    /// graph has no cycle. The cycle is given by its nodes in order, without repeating the first
+    /// one at the end; a self-loop is a cycle of one node.
+    ///
+    /// This is Karp's algorithm, which takes `O(n·m)` time and `O(n²)` memory. The minimum mean
+    /// bounds the throughput of a system modelled as a graph, and with logarithms of exchange
+    /// rates as weights a negative mean reveals an arbitrage opportunity. Undirected edges form a
+    /// cycle of two nodes, so on undirected graphs the result is usually the lightest edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - The weight of the directed edge between two nodes; it may be negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 3 -> 2").unwrap();
+    /// let weight = |from: u32, to: u32| if (from, to) == (2, 3) { 10.0 } else { 1.0 };
+    ///
+    /// let (mean, cycle) = g.minimum_mean_cycle(weight).unwrap();
+    /// assert_eq!(mean, 1.0);
+    /// assert_eq!(cycle.len(), 3);
+    /// ```
+    pub fn minimum_mean_cycle(
+        &self,
+        weight: impl Fn(IDDataType, IDDataType) -> f64,
+    ) -> Option<(f64, Vec<IDDataType>)> {
+        let n = self.nodes.len();
+        let (forward, _) = self.index_adjacency(false);
+        let edges: Vec<(usize, usize, f64)> = forward
+            .iter()
+            .enumerate()
+            .flat_map(|(from, tos)| tos.iter().map(move |to| (from, *to)))
+            .map(|(from, to)| (from, to, weight(self.nodes[from], self.nodes[to])))
+            .collect();
+
+        // walks[k * n + v] is the lightest walk of exactly k edges ending at v, starting anywhere
+        let mut walks = vec![f64::INFINITY; (n + 1) * n];
+        let mut parents = vec![usize::MAX; (n + 1) * n];
+        walks[..n].fill(0.0);
+        for k in 1..=n {
+            for &(from, to, w) in &edges {
+                let through = walks[(k - 1) * n + from] + w;
+                if through < walks[k * n + to] {
+                    walks[k * n + to] = through;
+                    parents[k * n + to] = from;
+                }
+            }
+        }
+
+        // Karp: the minimum mean is min over v of max over k of (D_n(v) - D_k(v)) / (n - k)
+        let mut best: Option<(f64, usize)> = None;
+        for v in 0..n {
+            let last = walks[n * n + v];
+            if last == f64::INFINITY {
+                continue;
+            }
+            let worst = (0..n)
+                .filter(|k| walks[k * n + v] != f64::INFINITY)
+                .map(|k| (last - walks[k * n + v]) / (n - k) as f64)
+                .fold(f64::NEG_INFINITY, f64::max);
+            if best.is_none_or(|(mean, _)| worst < mean) {
+                best = Some((worst, v));
+            }
+        }
+        let (_, end) = best?;
+
+        // the lightest walk of n edges to `end` repeats a node; split it into its cycles and keep
+        // the one with the smallest mean
+        let mut walk = Vec::with_capacity(n + 1);
+        let mut node = end;
+        for k in (0..=n).rev() {
+            walk.push(node);
+            if k > 0 {
+                node = parents[k * n + node];
+            }
+        }
+        walk.reverse();
+        let mut best_cycle: Option<(f64, Vec<IDDataType>)> = None;
+        let mut stack: Vec<usize> = Vec::new();
+        let mut position: NodeMap<usize, usize> = NodeMap::default();
+        for node in walk {
+            if let Some(&start) = position.get(&node) {
+                let cycle: Vec<usize> = stack.drain(start..).collect();
+                for removed in &cycle {
+                    position.remove(removed);
+                }
+                let total: f64 = (0..cycle.len())
+                    .map(|i| {
+                        let (from, to) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+                        weight(self.nodes[from], self.nodes[to])
+                    })
+                    .sum();
+                let mean = total / cycle.len() as f64;
+                if best_cycle.as_ref().is_none_or(|(known, _)| mean < *known) {
+                    best_cycle = Some((mean, cycle.iter().map(|i| self.nodes[*i]).collect()));
+                }
+            }
+            position.insert(node, stack.len());
+            stack.push(node);
+        }
+        best_cycle
+    }
+}
+
+/// unblocks `node` and, transitively, the nodes that were waiting for it.
+fn unblock(node: usize, blocked: &mut [bool], blocking: &mut [Vec<usize>]) {
+    let mut pending = vec![node];
+    while let Some(node) = pending.pop() {
+        if blocked[node] {
+            blocked[node] = false;
+            pending.append(&mut blocking[node]);
+        }
+    }
+}
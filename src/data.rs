@@ -0,0 +1,203 @@
+//! Attaching node data from elsewhere to the topology of a graph, and re-typing the node data
+//! or IDs of a graph.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    observer::Observers,
+    Graph, GraphError,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Returns a graph with the same nodes, edges, edge data and edge policy whose node data is
+    /// taken from a table, e.g. a `HashMap` of attributes loaded separately from the edge list.
+    ///
+    /// Nodes missing from the table get the default data, and entries for IDs that are not in the
+    /// graph are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use grafferous::Graph;
+    ///
+    /// let topology = Graph::<u32, ()>::parse("1 -> 2 -> 3").unwrap();
+    /// let names = HashMap::from([(1, "one"), (2, "two"), (9, "nine")]);
+    ///
+    /// let g = topology.with_data_from(names);
+    /// assert_eq!(g.node_data[&2], "two");
+    /// assert_eq!(g.node_data[&3], "");
+    /// assert_eq!(g.edge_tuples().len(), 2);
+    /// ```
+    pub fn with_data_from<U: Default>(
+        &self,
+        data: impl IntoIterator<Item = (IDDataType, U)>,
+    ) -> Graph<IDDataType, U, EdgeDataType>
+    where
+        EdgeDataType: Clone,
+    {
+        let mut data: NodeMap<IDDataType, U> = data
+            .into_iter()
+            .filter(|(id, _)| self.node_data.contains_key(id))
+            .collect();
+        self.map_topology(|id, _| data.remove(id).unwrap_or_default())
+    }
+
+    /// Returns a graph with the same nodes, edges, edge data and edge policy whose node data pairs
+    /// the data of this graph with the data of the same node in `other`, or the default data if
+    /// `other` lacks the node. The edges of `other` are ignored.
+    pub fn zip_data<OtherDataType: Clone + Default, OtherEdgeDataType>(
+        &self,
+        other: &Graph<IDDataType, OtherDataType, OtherEdgeDataType>,
+    ) -> Graph<IDDataType, (NodeDataType, OtherDataType), EdgeDataType>
+    where
+        NodeDataType: Clone,
+        EdgeDataType: Clone,
+    {
+        self.map_topology(|id, data| {
+            let theirs = other.node_data.get(id).cloned().unwrap_or_default();
+            (data.clone(), theirs)
+        })
+    }
+
+    /// Turns the graph into one with the same nodes and edges, keeping its edge data and edge
+    /// policy, whose node data is `f(id, data)`. Observers are not carried over.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, &str>::parse("1 -> 2 -> 3").unwrap();
+    /// g.node_data.insert(2, "two");
+    ///
+    /// let lengths = g.map_data(|_, name| name.len());
+    /// assert_eq!(lengths.node_data[&2], 3);
+    /// assert_eq!(lengths.neighbors(1), &[2]);
+    /// ```
+    pub fn map_data<U>(
+        mut self,
+        mut f: impl FnMut(&IDDataType, NodeDataType) -> U,
+    ) -> Graph<IDDataType, U, EdgeDataType> {
+        let mut node_data = NodeMap::with_capacity_and_hasher(
+            self.node_data.len(),
+            self.node_data.hasher().clone(),
+        );
+        let old = core::mem::take(&mut self.node_data);
+        node_data.extend(old.into_iter().map(|(id, data)| {
+            let data = f(&id, data);
+            (id, data)
+        }));
+        self.replace_node_data(node_data)
+    }
+
+    /// Turns the graph into the same graph with every node ID replaced by `f(id)`, keeping the
+    /// node and edge data, the order of the nodes and neighbors and the edge policy. Observers are
+    /// not carried over.
+    ///
+    /// Fails with `GraphError::DuplicateNode` with the new ID if `f` maps two nodes to the same
+    /// ID; `f` is called once per node, in the order of `nodes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{Graph, GraphError};
+    ///
+    /// let g = Graph::<u32, ()>::parse("1 -> 2 -> 3").unwrap();
+    /// let named = g.clone().map_ids(|id| ["zero", "one", "two", "three"][id as usize]).unwrap();
+    /// assert_eq!(named.nodes, vec!["one", "two", "three"]);
+    /// assert_eq!(named.neighbors("two"), &["three"]);
+    ///
+    /// assert_eq!(g.map_ids(|id| id / 2), Err(GraphError::DuplicateNode(1)));
+    /// ```
+    pub fn map_ids<J>(
+        self,
+        mut f: impl FnMut(IDDataType) -> J,
+    ) -> Result<Graph<J, NodeDataType, EdgeDataType>, GraphError<J>>
+    where
+        J: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let hasher = self.node_data.hasher().clone();
+        let mut mapping = NodeMap::with_capacity_and_hasher(self.nodes.len(), hasher.clone());
+        let mut taken = NodeSet::with_capacity_and_hasher(self.nodes.len(), hasher.clone());
+        for id in &self.nodes {
+            let new = f(*id);
+            if !taken.insert(new) {
+                return Err(GraphError::DuplicateNode(new));
+            }
+            mapping.insert(*id, new);
+        }
+
+        let mut graph = Graph::with_hasher(hasher);
+        graph.set_edge_policy(self.policy);
+        graph.nodes = self.nodes.iter().map(|id| mapping[id]).collect::<Vec<J>>();
+        graph.node_data.extend(
+            self.node_data
+                .into_iter()
+                .map(|(id, data)| (mapping[&id], data)),
+        );
+        for (map, source) in [
+            (&mut graph.edges, self.edges),
+            (&mut graph.reverse_edges, self.reverse_edges),
+        ] {
+            map.extend(source.into_iter().map(|(id, neighbors)| {
+                let neighbors = neighbors.into_iter().map(|to| mapping[&to]).collect();
+                (mapping[&id], neighbors)
+            }));
+        }
+        graph.edge_data.extend(
+            self.edge_data
+                .into_iter()
+                .map(|((from, to), data)| ((mapping[&from], mapping[&to]), data)),
+        );
+        Ok(graph)
+    }
+
+    /// the graph with the same topology, edge data and edge policy and the given node data, which
+    /// has to hold an entry for every node.
+    pub(crate) fn replace_node_data<U>(
+        self,
+        node_data: NodeMap<IDDataType, U>,
+    ) -> Graph<IDDataType, U, EdgeDataType> {
+        Graph {
+            node_data,
+            edges: self.edges,
+            reverse_edges: self.reverse_edges,
+            nodes: self.nodes,
+            edge_data: self.edge_data,
+            policy: self.policy,
+            undirected: self.undirected,
+            edge_index: self.edge_index,
+            compact: self.compact,
+            observers: Observers::default(),
+            next_id: self.next_id,
+        }
+    }
+
+    /// copies the nodes, edges, edge data and edge policy with the same hasher, computing the new
+    /// data of every node from its old data.
+    fn map_topology<U>(
+        &self,
+        mut data: impl FnMut(&IDDataType, &NodeDataType) -> U,
+    ) -> Graph<IDDataType, U, EdgeDataType>
+    where
+        EdgeDataType: Clone,
+    {
+        let mut graph = Graph::with_hasher(self.node_data.hasher().clone());
+        graph.set_edge_policy(self.policy);
+        graph.node_data.reserve(self.node_data.len());
+        graph
+            .node_data
+            .extend(self.node_data.iter().map(|(id, old)| (*id, data(id, old))));
+        graph.edges = self.edges.clone();
+        graph.reverse_edges = self.reverse_edges.clone();
+        graph.nodes = self.nodes.clone();
+        graph.edge_data = self.edge_data.clone();
+        graph
+    }
+}
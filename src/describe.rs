@@ -0,0 +1,286 @@
+//! Human-readable summaries of a graph for quick inspection, see `Graph::stats` and
+//! `Graph::describe`.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display, Write},
+    hash::Hash,
+};
+
+use crate::{collections::NodeMap, Graph};
+
+/// the bars of the degree sparkline, from the smallest to the largest count.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// the most buckets the degree histogram is split into.
+const MAX_BUCKETS: usize = 32;
+/// how many nodes and components are listed.
+const TOP: usize = 5;
+
+/// The basic numbers of a graph, as computed by `Graph::stats`. Displays as one line per number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphStats {
+    /// The number of nodes.
+    pub nodes: usize,
+    /// The number of directed edges, counting an undirected edge twice.
+    pub edges: usize,
+    /// The directed edges as a fraction of the `n * (n - 1)` possible ones, or `0.0` for graphs
+    /// with less than two nodes.
+    pub density: f64,
+    /// The smallest number of outgoing edges of a node, `0` for an empty graph.
+    pub min_out_degree: usize,
+    /// The largest number of outgoing edges of a node, `0` for an empty graph.
+    pub max_out_degree: usize,
+    /// The mean number of outgoing edges of a node, `0.0` for an empty graph.
+    pub mean_out_degree: f64,
+    /// The number of edges from a node to itself.
+    pub self_loops: usize,
+    /// The number of nodes without incoming or outgoing edges.
+    pub isolated_nodes: usize,
+    /// Whether every node can be reached from every other ignoring edge directions, see
+    /// `Graph::is_connected`.
+    pub weakly_connected: bool,
+}
+
+impl Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "nodes: {}", self.nodes)?;
+        writeln!(f, "directed edges: {}", self.edges)?;
+        writeln!(f, "density: {:.6}", self.density)?;
+        writeln!(
+            f,
+            "out-degree: min {}, max {}, mean {:.3}",
+            self.min_out_degree, self.max_out_degree, self.mean_out_degree
+        )?;
+        writeln!(f, "self-loops: {}", self.self_loops)?;
+        writeln!(f, "isolated nodes: {}", self.isolated_nodes)?;
+        writeln!(f, "weakly connected: {}", self.weakly_connected)
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Computes the basic numbers of the graph for a quick health check, in time linear in its
+    /// size. See `describe` for a longer report.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 2; 3").unwrap();
+    /// let stats = g.stats();
+    /// assert_eq!(stats.edges, 4);
+    /// assert_eq!(stats.max_out_degree, 2);
+    /// assert_eq!(stats.self_loops, 1);
+    /// assert_eq!(stats.isolated_nodes, 1);
+    /// assert!(!stats.weakly_connected);
+    /// println!("{stats}");
+    /// ```
+    pub fn stats(&self) -> GraphStats {
+        let n = self.nodes.len();
+        let out_degrees = self.out_degrees();
+        let edges: usize = out_degrees.iter().sum();
+        let in_degrees = self.in_degrees();
+        GraphStats {
+            nodes: n,
+            edges,
+            density: if n > 1 {
+                edges as f64 / (n * (n - 1)) as f64
+            } else {
+                0.0
+            },
+            min_out_degree: out_degrees.iter().copied().min().unwrap_or_default(),
+            max_out_degree: out_degrees.iter().copied().max().unwrap_or_default(),
+            mean_out_degree: if n > 0 { edges as f64 / n as f64 } else { 0.0 },
+            self_loops: self
+                .edges
+                .iter()
+                .map(|(from, tos)| tos.iter().filter(|to| *to == from).count())
+                .sum(),
+            isolated_nodes: self
+                .nodes
+                .iter()
+                .zip(&out_degrees)
+                .filter(|(id, out)| **out == 0 && in_degrees[*id] == 0)
+                .count(),
+            weakly_connected: self.component_sizes().len() <= 1,
+        }
+    }
+
+    /// Returns a multi-line report for exploring a graph, e.g. in a REPL after importing a dataset:
+    /// node and edge counts, an out-degree histogram as a sparkline, the nodes with the highest
+    /// degree, the sizes of the largest weakly connected components and whether the graph is
+    /// undirected or acyclic.
+    ///
+    /// Computing the report takes time linear in the size of the graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let report = generate_grid_graph::<()>(3, 3).describe();
+    /// assert!(report.contains("nodes: 9"));
+    /// assert!(report.contains("undirected: true"));
+    /// println!("{report}");
+    /// ```
+    pub fn describe(&self) -> String {
+        let n = self.nodes.len();
+        let out_degrees = self.out_degrees();
+        let stats = self.stats();
+
+        let mut report = String::new();
+        // writing to a String cannot fail
+        let _ = writeln!(report, "nodes: {n}");
+        let _ = writeln!(report, "directed edges: {}", stats.edges);
+        let _ = writeln!(report, "self-loops: {}", stats.self_loops);
+        let _ = writeln!(report, "density: {:.6}", stats.density);
+        let _ = writeln!(report, "undirected: {}", self.is_undirected());
+        let in_degrees = self.in_degrees();
+        let _ = writeln!(report, "acyclic: {}", self.is_acyclic(in_degrees.clone()));
+        if n == 0 {
+            return report;
+        }
+
+        let max = stats.max_out_degree;
+        let _ = writeln!(
+            report,
+            "out-degree: min {}, max {max}, mean {:.3}",
+            stats.min_out_degree, stats.mean_out_degree
+        );
+        let _ = writeln!(
+            report,
+            "out-degree histogram 0..={max}: {}",
+            sparkline(&out_degrees, max)
+        );
+
+        let mut by_degree: Vec<(IDDataType, usize)> = self
+            .nodes
+            .iter()
+            .zip(&out_degrees)
+            .map(|(id, out)| (*id, out + in_degrees[id]))
+            .collect();
+        by_degree.sort_by_key(|(_, degree)| core::cmp::Reverse(*degree));
+        let top: Vec<String> = by_degree
+            .iter()
+            .take(TOP)
+            .map(|(id, degree)| format!("{id:?} ({degree})"))
+            .collect();
+        let _ = writeln!(report, "highest degree (in + out): {}", top.join(", "));
+
+        let sizes = self.component_sizes();
+        let mut largest: Vec<String> = sizes.iter().take(TOP).map(usize::to_string).collect();
+        if sizes.len() > TOP {
+            largest.push(String::from("..."));
+        }
+        let _ = writeln!(
+            report,
+            "weakly connected components: {}, largest: {}",
+            sizes.len(),
+            largest.join(", ")
+        );
+        report
+    }
+
+    /// the number of outgoing edges of every node, in the order of `nodes`.
+    fn out_degrees(&self) -> Vec<usize> {
+        self.nodes
+            .iter()
+            .map(|id| self.edges.get(id).map_or(0, |n| n.len()))
+            .collect()
+    }
+
+    /// the number of incoming edges of every node, counted from the outgoing edges so that
+    /// graphs with incomplete `reverse_edges` are described correctly.
+    fn in_degrees(&self) -> NodeMap<IDDataType, usize> {
+        let mut in_degrees: NodeMap<IDDataType, usize> =
+            self.nodes.iter().map(|id| (*id, 0)).collect();
+        for to in self.edges.values().flatten() {
+            *in_degrees.entry(*to).or_default() += 1;
+        }
+        in_degrees
+    }
+
+    /// the sizes of the weakly connected components, largest first.
+    fn component_sizes(&self) -> Vec<usize> {
+        let index: NodeMap<IDDataType, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        for (from, to) in self.edges_iter() {
+            if let Some(&b) = index.get(&to) {
+                let (a, b) = (root(&mut parent, index[&from]), root(&mut parent, b));
+                parent[a] = b;
+            }
+        }
+        let mut sizes = vec![0; self.nodes.len()];
+        for i in 0..self.nodes.len() {
+            sizes[root(&mut parent, i)] += 1;
+        }
+        sizes.retain(|size| *size > 0);
+        sizes.sort_unstable_by_key(|size| core::cmp::Reverse(*size));
+        sizes
+    }
+
+    /// whether the directed edges contain no cycle, by repeatedly removing nodes without
+    /// incoming edges.
+    fn is_acyclic(&self, mut in_degrees: NodeMap<IDDataType, usize>) -> bool {
+        let mut ready: Vec<IDDataType> = self
+            .nodes
+            .iter()
+            .copied()
+            .filter(|id| in_degrees[id] == 0)
+            .collect();
+        let mut removed = 0;
+        while let Some(id) = ready.pop() {
+            removed += 1;
+            for to in self.neighbors(id) {
+                let degree = in_degrees.entry(*to).or_default();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(*to);
+                }
+            }
+        }
+        removed == self.nodes.len()
+    }
+}
+
+/// the representative of the set containing `i`, halving the path on the way.
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// the number of values per bucket of `0..=max`, drawn with one bar per bucket.
+fn sparkline(values: &[usize], max: usize) -> String {
+    let buckets = (max + 1).min(MAX_BUCKETS);
+    let mut counts = vec![0usize; buckets];
+    for value in values {
+        counts[value * buckets / (max + 1)] += 1;
+    }
+    let highest = counts.iter().copied().max().unwrap_or_default().max(1);
+    counts
+        .iter()
+        .map(|count| {
+            if *count == 0 {
+                ' '
+            } else {
+                BARS[(count * BARS.len() - 1) / highest]
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,163 @@
+//! Comparing two graphs and applying one on top of another.
+//!
+//! Like the set operations, the comparison treats the edges as a set of directed `(from, to)`
+//! pairs, so parallel edges are not told apart, and it ignores the data of the edges.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeSet, Graph};
+
+/// The differences between two graphs, as computed by `Graph::diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphDiff<IDDataType> {
+    /// Nodes present in the newer graph only.
+    pub added_nodes: Vec<IDDataType>,
+    /// Nodes present in the older graph only.
+    pub removed_nodes: Vec<IDDataType>,
+    /// Directed edges present in the newer graph only.
+    pub added_edges: Vec<(IDDataType, IDDataType)>,
+    /// Directed edges present in the older graph only.
+    pub removed_edges: Vec<(IDDataType, IDDataType)>,
+    /// Nodes present in both graphs whose data differs.
+    pub changed_data: Vec<IDDataType>,
+}
+
+impl<IDDataType> GraphDiff<IDDataType> {
+    /// Whether the two graphs are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_data.is_empty()
+    }
+}
+
+impl<IDDataType, NodeDataType: PartialEq, EdgeDataType>
+    Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// What changed from this graph to `other`: the nodes and edges only in `other` are added,
+    /// the ones only in `self` are removed. Every list follows the node order of the graph the
+    /// entries are found in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut before = Graph::<u32, char>::new();
+    /// before.add_node_with_data(0, 'a');
+    /// before.add_directed_edge(0, 1);
+    /// let mut after = before.clone();
+    /// after.node_data.insert(0, 'b');
+    /// after.remove_directed_edge(0, 1);
+    /// after.add_directed_edge(1, 2);
+    ///
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.added_nodes, vec![2]);
+    /// assert_eq!(diff.added_edges, vec![(1, 2)]);
+    /// assert_eq!(diff.removed_edges, vec![(0, 1)]);
+    /// assert_eq!(diff.changed_data, vec![0]);
+    /// assert!(after.diff(&after).is_empty());
+    /// ```
+    pub fn diff(&self, other: &Self) -> GraphDiff<IDDataType> {
+        let ours: NodeSet<(IDDataType, IDDataType)> = self.edges_iter().collect();
+        let theirs: NodeSet<(IDDataType, IDDataType)> = other.edges_iter().collect();
+
+        GraphDiff {
+            added_nodes: other
+                .nodes
+                .iter()
+                .filter(|&id| !self.node_data.contains_key(id))
+                .copied()
+                .collect(),
+            removed_nodes: self
+                .nodes
+                .iter()
+                .filter(|&id| !other.node_data.contains_key(id))
+                .copied()
+                .collect(),
+            added_edges: other
+                .edges_iter()
+                .filter(|edge| !ours.contains(edge))
+                .collect(),
+            removed_edges: self
+                .edges_iter()
+                .filter(|edge| !theirs.contains(edge))
+                .collect(),
+            changed_data: other
+                .nodes
+                .iter()
+                .filter(
+                    |&id| matches!(self.node_data.get(id), Some(data) if *data != other.node_data[id]),
+                )
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Default + Clone + PartialEq,
+    EdgeDataType: Clone,
+{
+    /// Applies `other` on top of this graph in place: its missing nodes and edges are added, and
+    /// the data of a node in both that differs becomes `conflict(id, data in self, data in other)`.
+    /// An edge in both keeps its data from `self`, or takes it from `other` if it has none in
+    /// `self`. Nothing is removed, so afterwards `self.diff(other)` only reports removals.
+    ///
+    /// The changes go through the usual insertion methods, so observers are notified and the edge
+    /// policy of this graph applies. See `union` for a merged copy instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut local = Graph::<u32, u32>::new();
+    /// local.add_node_with_data(0, 1);
+    /// local.add_directed_edge(0, 1);
+    /// let mut remote = Graph::<u32, u32>::new();
+    /// remote.add_node_with_data(0, 5);
+    /// remote.add_directed_edge(1, 2);
+    ///
+    /// local.merge(&remote, |_, ours, theirs| *ours.max(theirs));
+    /// assert_eq!(local.node_data[&0], 5);
+    /// assert_eq!(local.edge_tuples(), vec![(0, 1), (1, 2)]);
+    /// assert!(local.diff(&remote).added_nodes.is_empty());
+    /// ```
+    pub fn merge(
+        &mut self,
+        other: &Self,
+        mut conflict: impl FnMut(IDDataType, &NodeDataType, &NodeDataType) -> NodeDataType,
+    ) {
+        for id in &other.nodes {
+            let theirs = &other.node_data[id];
+            match self.node_data.get(id) {
+                None => self.add_node_with_data(*id, theirs.clone()),
+                Some(ours) if ours != theirs => {
+                    let merged = conflict(*id, ours, theirs);
+                    self.node_data.insert(*id, merged);
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut ours: NodeSet<(IDDataType, IDDataType)> = self.edges_iter().collect();
+        for (from, to) in other.edges_iter() {
+            if ours.insert((from, to)) {
+                self.add_directed_edge(from, to);
+            }
+            if let Some(data) = other.edge_data(from, to) {
+                if self.has_edge(from, to) && !self.edge_data.contains_key(&(from, to)) {
+                    self.edge_data.insert((from, to), data.clone());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,679 @@
+//! Dense matrices of hop distances between many pairs of nodes, and the summary statistics built
+//! on them: eccentricity, diameter, radius and center. Weighted distances between all pairs of
+//! nodes are found with the Floyd–Warshall or Johnson's algorithm.
+
+use alloc::{
+    collections::{BinaryHeap, VecDeque},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cmp::Reverse,
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Sub},
+};
+use rand::Rng;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{
+    collections::{Entry, NodeMap},
+    routing::Ranked,
+    visit::{index_successors, Neighbors},
+    Graph, NegativeCycleError,
+};
+
+/// The value stored for a node that cannot be reached from a source.
+pub const UNREACHABLE: u32 = u32::MAX;
+
+/// the number of landmarks `Graph::approx_center` measures distances to.
+const CENTER_LANDMARKS: usize = 8;
+
+/// Hop distances from a list of source nodes to every node, stored row by row in one `Vec<u32>`.
+///
+/// Nodes are mapped to the column indices `0..node_count` in the order of [`DistanceMatrix::nodes`];
+/// the row of a source is its position in [`DistanceMatrix::sources`]. Unreachable nodes hold
+/// [`UNREACHABLE`].
+///
+/// # Example
+///
+/// ```
+/// use grafferous::Graph;
+///
+/// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2; 3").unwrap();
+/// let matrix = g.distance_matrix();
+///
+/// assert_eq!(matrix.get(0, 2), Some(2));
+/// assert_eq!(matrix.get(2, 0), None);
+/// assert_eq!(matrix.row(1), Some(&[u32::MAX, 0, 1, u32::MAX][..]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    nodes: Vec<IDDataType>,
+    index: NodeMap<IDDataType, usize>,
+    sources: Vec<IDDataType>,
+    source_index: NodeMap<IDDataType, usize>,
+    distances: Vec<u32>,
+}
+
+impl<IDDataType> DistanceMatrix<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + Send + Sync,
+{
+    /// Runs one breadth-first search per source over any graph representation. Sources that are
+    /// not in the graph are skipped, as are repeated sources.
+    ///
+    /// The graph is converted to an index-based adjacency list once, so every search only touches
+    /// plain vectors. With the `rayon` feature the searches run in parallel.
+    pub fn compute<G>(graph: &G, sources: impl IntoIterator<Item = IDDataType>) -> Self
+    where
+        G: Neighbors<Id = IDDataType>,
+    {
+        let (nodes, adjacency) = index_successors(graph);
+        let index: NodeMap<IDDataType, usize> =
+            nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let mut source_index = NodeMap::default();
+        let mut source_rows = Vec::new();
+        let mut source_ids = Vec::new();
+        for id in sources {
+            if let (Some(&row), Entry::Vacant(slot)) = (index.get(&id), source_index.entry(id)) {
+                slot.insert(source_ids.len());
+                source_ids.push(id);
+                source_rows.push(row);
+            }
+        }
+
+        let n = nodes.len();
+        let mut distances = vec![UNREACHABLE; source_rows.len() * n];
+        if n > 0 {
+            #[cfg(feature = "rayon")]
+            distances
+                .par_chunks_mut(n)
+                .zip(source_rows.par_iter())
+                .for_each(|(row, start)| bfs_row(&adjacency, *start, row));
+            #[cfg(not(feature = "rayon"))]
+            for (row, start) in distances.chunks_mut(n).zip(&source_rows) {
+                bfs_row(&adjacency, *start, row);
+            }
+        }
+
+        Self {
+            nodes,
+            index,
+            sources: source_ids,
+            source_index,
+            distances,
+        }
+    }
+}
+
+impl<IDDataType> DistanceMatrix<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The number of hops from `from` to `to`, or `None` if `to` is unreachable or either node is
+    /// not part of the matrix.
+    pub fn get(&self, from: IDDataType, to: IDDataType) -> Option<u32> {
+        let column = *self.index.get(&to)?;
+        let distance = self.row(from)?[column];
+        (distance != UNREACHABLE).then_some(distance)
+    }
+
+    /// The distances from a source to every node, in the order of `nodes`.
+    pub fn row(&self, source: IDDataType) -> Option<&[u32]> {
+        let row = *self.source_index.get(&source)?;
+        let n = self.nodes.len();
+        Some(&self.distances[row * n..(row + 1) * n])
+    }
+
+    /// The nodes in column order.
+    pub fn nodes(&self) -> &[IDDataType] {
+        &self.nodes
+    }
+
+    /// The column of a node.
+    pub fn index_of(&self, id: IDDataType) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    /// The sources in row order.
+    pub fn sources(&self) -> &[IDDataType] {
+        &self.sources
+    }
+
+    /// All distances, row by row.
+    pub fn as_slice(&self) -> &[u32] {
+        &self.distances
+    }
+}
+
+/// Weighted shortest path lengths between all pairs of nodes, stored row by row in one `Vec`, as
+/// found by `Graph::all_pairs_shortest_paths` and `Graph::all_pairs_shortest_paths_johnson`.
+///
+/// Rows and columns are both in the order of [`WeightedDistanceMatrix::nodes`]. Nodes that cannot
+/// be reached hold `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedDistanceMatrix<IDDataType, C>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    nodes: Vec<IDDataType>,
+    index: NodeMap<IDDataType, usize>,
+    distances: Vec<Option<C>>,
+}
+
+impl<IDDataType, C> WeightedDistanceMatrix<IDDataType, C>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    C: Copy,
+{
+    /// wraps the distances of a graph's nodes, row by row in the order of `nodes`.
+    fn new(nodes: &[IDDataType], distances: Vec<Option<C>>) -> Self {
+        Self {
+            nodes: nodes.to_vec(),
+            index: nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect(),
+            distances,
+        }
+    }
+
+    /// The length of a shortest path from `from` to `to`, or `None` if `to` is unreachable or
+    /// either node is not part of the matrix.
+    pub fn get(&self, from: IDDataType, to: IDDataType) -> Option<C> {
+        let column = *self.index.get(&to)?;
+        self.row(from)?[column]
+    }
+
+    /// The distances from a node to every node, in the order of `nodes`.
+    pub fn row(&self, from: IDDataType) -> Option<&[Option<C>]> {
+        let row = *self.index.get(&from)?;
+        let n = self.nodes.len();
+        Some(&self.distances[row * n..(row + 1) * n])
+    }
+
+    /// The nodes in row and column order.
+    pub fn nodes(&self) -> &[IDDataType] {
+        &self.nodes
+    }
+
+    /// The row and column of a node.
+    pub fn index_of(&self, id: IDDataType) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    /// All distances, row by row.
+    pub fn as_slice(&self) -> &[Option<C>] {
+        &self.distances
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + Send + Sync,
+{
+    /// Hop distances between all pairs of nodes, with one row per node in the order of
+    /// `self.nodes`. This needs `n²` entries of four bytes; use `distance_matrix_from` to compute
+    /// only the rows of some sources.
+    pub fn distance_matrix(&self) -> DistanceMatrix<IDDataType> {
+        DistanceMatrix::compute(self, self.nodes.iter().copied())
+    }
+
+    /// Hop distances from each of the given sources to every node.
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - The nodes to compute rows for; IDs not in the graph are skipped.
+    ///
+    pub fn distance_matrix_from(&self, sources: &[IDDataType]) -> DistanceMatrix<IDDataType> {
+        DistanceMatrix::compute(self, sources.iter().copied())
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The eccentricity of a node: the largest hop distance from it to any node. `None` if the
+    /// node is not in the graph or cannot reach every node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3; 1 -- 4").unwrap();
+    /// assert_eq!(g.eccentricity(1), Some(2));
+    /// assert_eq!(g.eccentricity(3), Some(3));
+    /// assert_eq!(g.eccentricity(7), None);
+    /// ```
+    pub fn eccentricity(&self, id: IDDataType) -> Option<usize> {
+        let start = self.nodes.iter().position(|node| *node == id)?;
+        let (forward, _) = self.index_adjacency(false);
+        let mut row = vec![UNREACHABLE; self.nodes.len()];
+        bfs_row(&forward, start, &mut row);
+        row_eccentricity(&row)
+    }
+
+    /// The diameter, the largest hop distance between two nodes, or `None` if the graph is empty
+    /// or not every node can reach every other one.
+    ///
+    /// This runs a breadth-first search from every node, `O(n·m)` time in all; `approx_diameter`
+    /// estimates the diameter of large graphs with a few double sweeps instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let g = generate_grid_graph::<()>(6, 4);
+    /// assert_eq!(g.diameter(), Some(8));
+    /// assert_eq!(g.radius(), Some(5));
+    /// ```
+    pub fn diameter(&self) -> Option<usize> {
+        self.eccentricity_list()?.into_iter().max()
+    }
+
+    /// The radius, the smallest eccentricity of any node, or `None` if the graph is empty or not
+    /// every node can reach every other one. Like `diameter`, it takes one breadth-first search
+    /// per node.
+    pub fn radius(&self) -> Option<usize> {
+        self.eccentricity_list()?.into_iter().min()
+    }
+
+    /// The center, the nodes whose eccentricity is the radius, in the order of `nodes`. Empty if
+    /// the graph is empty or not every node can reach every other one. Like `diameter`, it takes
+    /// one breadth-first search per node; `approx_center` finds one central node of large
+    /// graphs with far fewer searches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let path = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3").unwrap();
+    /// assert_eq!(path.center(), vec![1, 2]);
+    /// ```
+    pub fn center(&self) -> Vec<IDDataType> {
+        let Some(eccentricities) = self.eccentricity_list() else {
+            return Vec::new();
+        };
+        let radius = eccentricities.iter().min().copied();
+        self.nodes
+            .iter()
+            .zip(&eccentricities)
+            .filter(|(_, eccentricity)| Some(**eccentricity) == radius)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// The length of a shortest path between every pair of nodes, with the edge data as the
+    /// lengths of the edges, found with the Floyd–Warshall algorithm. Edges without data are not
+    /// followed. The edge data may be negative, but if a cycle with a negative total weight
+    /// exists, that cycle is returned as the error; an undirected edge with a negative weight is
+    /// such a cycle.
+    ///
+    /// This takes `O(n³)` time regardless of the number of edges, which suits dense graphs;
+    /// `all_pairs_shortest_paths_johnson` is faster on sparse ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, (), i32>::new();
+    /// g.add_directed_edge_with_data(0, 1, 4);
+    /// g.add_directed_edge_with_data(0, 2, 5);
+    /// g.add_directed_edge_with_data(2, 1, -3);
+    /// g.add_directed_edge_with_data(1, 3, 2);
+    ///
+    /// let matrix = g.all_pairs_shortest_paths().unwrap();
+    /// assert_eq!(matrix.get(0, 3), Some(4));
+    /// assert_eq!(matrix.get(2, 3), Some(-1));
+    /// assert_eq!(matrix.get(3, 0), None);
+    /// assert_eq!(matrix, g.all_pairs_shortest_paths_johnson().unwrap());
+    /// ```
+    pub fn all_pairs_shortest_paths(
+        &self,
+    ) -> Result<WeightedDistanceMatrix<IDDataType, EdgeDataType>, NegativeCycleError<IDDataType>>
+    where
+        EdgeDataType: Copy + PartialOrd + Add<Output = EdgeDataType> + Default,
+    {
+        self.floyd_warshall(|from, to| self.edge_data(from, to).copied())
+    }
+
+    /// Like `all_pairs_shortest_paths`, but with the length of every directed edge given by
+    /// `weight`.
+    pub fn all_pairs_shortest_paths_by<C>(
+        &self,
+        weight: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> Result<WeightedDistanceMatrix<IDDataType, C>, NegativeCycleError<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        self.floyd_warshall(|from, to| Some(weight(from, to)))
+    }
+
+    /// Like `all_pairs_shortest_paths`, but with Johnson's algorithm: the Bellman–Ford algorithm
+    /// finds a potential for every node that makes all edge weights non-negative, and then
+    /// Dijkstra's algorithm runs from every node. This takes `O(n·m·log n)` time, much less
+    /// than Floyd–Warshall on sparse graphs, and gives the same distances.
+    pub fn all_pairs_shortest_paths_johnson(
+        &self,
+    ) -> Result<WeightedDistanceMatrix<IDDataType, EdgeDataType>, NegativeCycleError<IDDataType>>
+    where
+        EdgeDataType:
+            Copy + PartialOrd + Add<Output = EdgeDataType> + Sub<Output = EdgeDataType> + Default,
+    {
+        self.johnson(|from, to| self.edge_data(from, to).copied())
+    }
+
+    /// Like `all_pairs_shortest_paths_johnson`, but with the length of every directed edge given
+    /// by `weight`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{generate_random_weighted_graph, WeightDistribution};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let uniform = WeightDistribution::Uniform { low: 1.0, high: 2.0 };
+    /// let (g, weights) =
+    ///     generate_random_weighted_graph::<()>(200, 0.02, uniform, StdRng::seed_from_u64(3));
+    /// let matrix = g
+    ///     .all_pairs_shortest_paths_johnson_by(|from, to| weights[&(from, to)])
+    ///     .unwrap();
+    /// let lengths = g.shortest_path_lengths_by(5, |from, to| weights[&(from, to)]);
+    /// assert_eq!(matrix.get(5, 17), lengths.get(&17).copied());
+    /// ```
+    pub fn all_pairs_shortest_paths_johnson_by<C>(
+        &self,
+        weight: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> Result<WeightedDistanceMatrix<IDDataType, C>, NegativeCycleError<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Sub<Output = C> + Default,
+    {
+        self.johnson(|from, to| Some(weight(from, to)))
+    }
+
+    /// all shortest path lengths with the Floyd–Warshall algorithm, or a negative cycle. Edges
+    /// without a weight are not followed.
+    fn floyd_warshall<C>(
+        &self,
+        weight: impl Fn(IDDataType, IDDataType) -> Option<C>,
+    ) -> Result<WeightedDistanceMatrix<IDDataType, C>, NegativeCycleError<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        let n = self.nodes.len();
+        let edges = self.index_weighted_edges(weight);
+        let mut distances = vec![None; n * n];
+        for i in 0..n {
+            distances[i * n + i] = Some(C::default());
+        }
+        for &(from, to, w) in &edges {
+            let cell = &mut distances[from * n + to];
+            if cell.is_none_or(|known| w < known) {
+                *cell = Some(w);
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                let Some(to_k) = distances[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    let Some(from_k) = distances[k * n + j] else {
+                        continue;
+                    };
+                    let through = to_k + from_k;
+                    if distances[i * n + j].is_none_or(|known| through < known) {
+                        distances[i * n + j] = Some(through);
+                    }
+                }
+            }
+            // stop at the first negative cycle, before paths around it grow without bound
+            let negative = (0..n).find(|i| distances[i * n + i].is_some_and(|d| d < C::default()));
+            if let Some(start) = negative {
+                let mut lengths = vec![None; n];
+                lengths[start] = Some(C::default());
+                self.bellman_ford_rounds(&edges, &mut lengths)?;
+            }
+        }
+        Ok(WeightedDistanceMatrix::new(&self.nodes, distances))
+    }
+
+    /// all shortest path lengths with Johnson's algorithm, or a negative cycle. Edges without a
+    /// weight are not followed.
+    fn johnson<C>(
+        &self,
+        weight: impl Fn(IDDataType, IDDataType) -> Option<C>,
+    ) -> Result<WeightedDistanceMatrix<IDDataType, C>, NegativeCycleError<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Sub<Output = C> + Default,
+    {
+        let n = self.nodes.len();
+        let edges = self.index_weighted_edges(weight);
+        // the potentials are the lengths from an extra node with an edge of length zero to every
+        // node, so no edge can shorten them and `w + p(from) - p(to)` is never negative
+        let mut potentials = vec![Some(C::default()); n];
+        self.bellman_ford_rounds(&edges, &mut potentials)?;
+        let potentials: Vec<C> = potentials.into_iter().flatten().collect();
+        let mut adjacency = vec![Vec::new(); n];
+        for (from, to, w) in edges {
+            adjacency[from].push((to, w + potentials[from] - potentials[to]));
+        }
+
+        let mut distances = vec![None; n * n];
+        if n > 0 {
+            for (start, row) in distances.chunks_mut(n).enumerate() {
+                dijkstra_row(&adjacency, start, row);
+                for (distance, potential) in row.iter_mut().zip(&potentials) {
+                    if let Some(distance) = distance {
+                        *distance = *distance + *potential - potentials[start];
+                    }
+                }
+            }
+        }
+        Ok(WeightedDistanceMatrix::new(&self.nodes, distances))
+    }
+
+    /// the eccentricity of every node in the order of `nodes`, or `None` if some node cannot
+    /// reach every node.
+    fn eccentricity_list(&self) -> Option<Vec<usize>> {
+        let n = self.nodes.len();
+        let (forward, _) = self.index_adjacency(false);
+        let mut row = vec![UNREACHABLE; n];
+        (0..n)
+            .map(|start| {
+                row.fill(UNREACHABLE);
+                bfs_row(&forward, start, &mut row);
+                row_eccentricity(&row)
+            })
+            .collect()
+    }
+
+    /// Estimates the diameter, the largest hop distance between two nodes, with double sweeps:
+    /// a breadth-first search from a random node finds the node farthest from it, and a second
+    /// search from there gives a lower bound on the diameter that is often exact.
+    ///
+    /// Only finite distances count, so on a disconnected graph this estimates the largest
+    /// diameter of its parts. Each sample costs two searches.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of double sweeps; the largest result is returned.
+    /// * `rng` - The source of randomness for picking the start nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let g = generate_grid_graph::<()>(20, 10);
+    /// assert_eq!(g.approx_diameter(4, StdRng::seed_from_u64(7)), 28);
+    /// ```
+    pub fn approx_diameter<R: Rng>(&self, samples: usize, mut rng: R) -> usize {
+        let n = self.nodes.len();
+        if n == 0 {
+            return 0;
+        }
+        let (forward, _) = self.index_adjacency(false);
+        let mut row = vec![UNREACHABLE; n];
+        let mut diameter = 0;
+        for _ in 0..samples {
+            row.fill(UNREACHABLE);
+            bfs_row(&forward, rng.gen_range(0..n), &mut row);
+            let (far, distance) = farthest(&row);
+            diameter = diameter.max(distance);
+            row.fill(UNREACHABLE);
+            bfs_row(&forward, far, &mut row);
+            diameter = diameter.max(farthest(&row).1);
+        }
+        diameter as usize
+    }
+
+    /// Estimates a center, a node whose largest hop distance to any other node is as small as
+    /// possible, or `None` for an empty graph.
+    ///
+    /// The distance of every node to a few landmarks is measured, starting at a node of highest
+    /// degree and adding the node farthest from the landmarks so far. The nodes with the smallest
+    /// largest distance to the landmarks are candidates, and the candidate with the smallest
+    /// largest distance to all nodes, measured exactly, wins. Nodes that cannot reach every node
+    /// only win if no candidate can.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let g = generate_grid_graph::<()>(9, 5);
+    /// assert_eq!(g.approx_center(), Some((4, 2)));
+    /// ```
+    pub fn approx_center(&self) -> Option<IDDataType> {
+        let n = self.nodes.len();
+        let (forward, backward) = self.index_adjacency(true);
+        let mut landmark = (0..n).max_by_key(|&i| (forward[i].len() + backward[i].len(), n - i))?;
+
+        // (landmarks out of reach, largest distance to a reachable landmark) for every node
+        let mut scores = vec![(0usize, 0u32); n];
+        let mut row = vec![UNREACHABLE; n];
+        let mut nearest = vec![UNREACHABLE; n];
+        for _ in 0..CENTER_LANDMARKS.min(n) {
+            // distances to the landmark are distances from it along reversed edges
+            row.fill(UNREACHABLE);
+            bfs_row(&backward, landmark, &mut row);
+            for ((score, distance), closest) in scores.iter_mut().zip(&row).zip(&mut nearest) {
+                if *distance == UNREACHABLE {
+                    score.0 += 1;
+                } else {
+                    score.1 = score.1.max(*distance);
+                }
+                *closest = (*closest).min(*distance);
+            }
+            let (next, distance) = farthest(&nearest);
+            if distance == 0 {
+                break;
+            }
+            landmark = next;
+        }
+
+        // measure the largest distance of the most promising nodes exactly
+        let mut candidates: Vec<usize> = (0..n).collect();
+        candidates.sort_by_key(|&i| scores[i]);
+        let best = candidates
+            .into_iter()
+            .take(CENTER_LANDMARKS)
+            .min_by_key(|&i| {
+                row.fill(UNREACHABLE);
+                bfs_row(&forward, i, &mut row);
+                let unreachable = row.iter().filter(|d| **d == UNREACHABLE).count();
+                (unreachable, farthest(&row).1)
+            })?;
+        Some(self.nodes[best])
+    }
+
+    /// the outgoing and, if asked for, incoming neighbors of every node by position in `nodes`,
+    /// taken from `edges` alone by way of the core of the graph.
+    pub(crate) fn index_adjacency(&self, incoming: bool) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+        let forward = self.core().adjacency_lists();
+        let mut backward = vec![Vec::new(); if incoming { forward.len() } else { 0 }];
+        if incoming {
+            for (from, tos) in forward.iter().enumerate() {
+                for to in tos {
+                    backward[*to].push(from);
+                }
+            }
+        }
+        (forward, backward)
+    }
+}
+
+/// the position and value of the largest finite distance in a row, the first one on ties.
+pub(crate) fn farthest(row: &[u32]) -> (usize, u32) {
+    row.iter()
+        .enumerate()
+        .filter(|(_, distance)| **distance != UNREACHABLE)
+        .fold((0, 0), |best, (i, distance)| {
+            if *distance > best.1 {
+                (i, *distance)
+            } else {
+                best
+            }
+        })
+}
+
+/// the largest distance in a row, or `None` if a node is unreachable.
+fn row_eccentricity(row: &[u32]) -> Option<usize> {
+    row.iter()
+        .try_fold(0, |largest: u32, distance| {
+            (*distance != UNREACHABLE).then(|| largest.max(*distance))
+        })
+        .map(|largest| largest as usize)
+}
+
+/// fills `row` with the hop distances from `start` by breadth-first search.
+pub(crate) fn bfs_row(adjacency: &[Vec<usize>], start: usize, row: &mut [u32]) {
+    let mut queue = VecDeque::new();
+    row[start] = 0;
+    queue.push_back(start);
+    while let Some(node) = queue.pop_front() {
+        let next = row[node] + 1;
+        for &to in &adjacency[node] {
+            if row[to] == UNREACHABLE {
+                row[to] = next;
+                queue.push_back(to);
+            }
+        }
+    }
+}
+
+/// fills `row` with the shortest path lengths from `start` by Dijkstra's algorithm, over edges
+/// given by the position and non-negative weight of their target.
+fn dijkstra_row<C>(adjacency: &[Vec<(usize, C)>], start: usize, row: &mut [Option<C>])
+where
+    C: Copy + PartialOrd + Add<Output = C> + Default,
+{
+    let mut done = vec![false; row.len()];
+    let mut queue = BinaryHeap::from([Reverse(Ranked(C::default(), start))]);
+    row[start] = Some(C::default());
+    while let Some(Reverse(Ranked(length, node))) = queue.pop() {
+        // entries for nodes that were reached by a shorter path later are stale
+        if core::mem::replace(&mut done[node], true) {
+            continue;
+        }
+        for &(to, w) in &adjacency[node] {
+            let through = length + w;
+            if !done[to] && row[to].is_none_or(|known| through < known) {
+                row[to] = Some(through);
+                queue.push(Reverse(Ranked(through, to)));
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Dominators of a flow graph, such as the control flow graph of a program.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// marks a node whose immediate dominator has not been found yet.
+const UNDEFINED: usize = usize::MAX;
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The immediate dominator of every node reachable from `root` along directed edges, except
+    /// the root itself. A node `d` dominates `n` if every path from the root to `n` passes through
+    /// `d`; the immediate dominator of `n` is the dominator closest to it other than `n` itself,
+    /// so following the map from any node leads to the root through all of its dominators, and
+    /// the map is the dominator tree. Nodes the root does not reach are not in the map, which is
+    /// empty if `root` does not exist.
+    ///
+    /// This is the iterative algorithm of Cooper, Harvey and Kennedy, which takes `O(n + m)` time
+    /// per pass and needs few passes on the graphs of structured programs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // an if-else in a loop: 0 enters, 1 branches to 2 or 3, which join at 4, which loops
+    /// // back to 1 or exits to 5
+    /// let cfg = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 4; 1 -> 3 -> 4 -> 1; 4 -> 5").unwrap();
+    /// let idom = cfg.dominators(0);
+    /// assert_eq!(idom[&1], 0);
+    /// assert_eq!(idom[&2], 1);
+    /// assert_eq!(idom[&4], 1);
+    /// assert_eq!(idom[&5], 4);
+    /// assert!(!idom.contains_key(&0));
+    /// ```
+    pub fn dominators(&self, root: IDDataType) -> NodeMap<IDDataType, IDDataType> {
+        let postorder = self.postorder_from(root);
+        let rank: NodeMap<IDDataType, usize> = postorder
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        // the predecessors of every reachable node by postorder rank
+        let predecessors: Vec<Vec<usize>> = postorder
+            .iter()
+            .map(|id| {
+                self.reverse_neighbors(*id)
+                    .iter()
+                    .filter_map(|from| rank.get(from).copied())
+                    .collect()
+            })
+            .collect();
+
+        let Some(start) = postorder.len().checked_sub(1) else {
+            return NodeMap::default();
+        };
+        let mut idom = vec![UNDEFINED; postorder.len()];
+        idom[start] = start;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            // reverse postorder, skipping the root
+            for node in (0..start).rev() {
+                let mut processed = predecessors[node]
+                    .iter()
+                    .copied()
+                    .filter(|p| idom[*p] != UNDEFINED);
+                let Some(first) = processed.next() else {
+                    continue;
+                };
+                let new = processed.fold(first, |a, b| intersect(&idom, a, b));
+                if idom[node] != new {
+                    idom[node] = new;
+                    changed = true;
+                }
+            }
+        }
+
+        (0..start)
+            .map(|node| (postorder[node], postorder[idom[node]]))
+            .collect()
+    }
+
+    /// the nodes reachable from `root` in the postorder of a depth-first search, ending with the
+    /// root.
+    fn postorder_from(&self, root: IDDataType) -> Vec<IDDataType> {
+        let mut order = Vec::new();
+        if !self.contains_node(root) {
+            return order;
+        }
+        let mut seen = NodeSet::default();
+        seen.insert(root);
+        let mut stack = vec![(root, 0)];
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            if let Some(&child) = self.neighbors(node).get(*next) {
+                *next += 1;
+                if seen.insert(child) {
+                    stack.push((child, 0));
+                }
+                continue;
+            }
+            stack.pop();
+            order.push(node);
+        }
+        order
+    }
+}
+
+/// the closest common dominator of two nodes by postorder rank, walking up the dominator tree
+/// found so far; dominators always have a higher rank.
+fn intersect(idom: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while a < b {
+            a = idom[a];
+        }
+        while b < a {
+            b = idom[b];
+        }
+    }
+    a
+}
@@ -0,0 +1,161 @@
+//! Simulations of dynamical processes on graphs.
+
+pub mod epidemic;
+pub mod spin;
+
+use core::hash::Hash;
+use std::fmt::Debug;
+
+use crate::{collections::NodeMap, spectral::Laplacian, Graph};
+
+/// Diffuses a numeric field stored in the node data by discrete Laplacian smoothing.
+///
+/// Every step moves each node's value towards its outgoing neighbors:
+/// `x[i] += rate * sum(x[j] - x[i])`. All nodes are updated simultaneously.
+/// Boundary behavior follows from the topology: on a cycle or periodic lattice the field wraps
+/// around, while nodes on the edge of an open lattice simply have fewer neighbors and no flux
+/// crosses the boundary. On an undirected graph the total of the field is conserved.
+///
+/// The update is stable as long as `rate * max_degree <= 1`; larger rates make the field oscillate and grow.
+///
+/// # Arguments
+///
+/// * `graph` - The graph whose node data holds the field.
+/// * `rate` - The fraction of each difference that flows across an edge per step.
+/// * `steps` - The number of steps to simulate.
+/// * `field` - Returns a mutable reference to the value stored in a node's data.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{dynamics::diffuse, generate_cycle_graph};
+///
+/// let mut g = generate_cycle_graph::<f64>(10);
+/// *g.node_data.get_mut(&0).unwrap() = 10.0;
+/// diffuse(&mut g, 0.25, 500, |heat| heat);
+///
+/// assert!(g.node_data.values().all(|heat| (heat - 1.0).abs() < 1e-6));
+/// ```
+pub fn diffuse<IDDataType, NodeDataType, EdgeDataType, F>(
+    graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+    rate: f64,
+    steps: usize,
+    mut field: F,
+) where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    F: FnMut(&mut NodeDataType) -> &mut f64,
+{
+    let (ids, neighbors) = dense_topology(graph);
+
+    let mut values: Vec<f64> = ids
+        .iter()
+        .map(|id| *field(graph.node_data.get_mut(id).unwrap()))
+        .collect();
+    let mut next = values.clone();
+    for _ in 0..steps {
+        for (i, value) in values.iter().enumerate() {
+            let flux: f64 = neighbors[i].iter().map(|&j| values[j] - value).sum();
+            next[i] = value + rate * flux;
+        }
+        std::mem::swap(&mut values, &mut next);
+    }
+
+    for (id, value) in ids.iter().zip(values) {
+        *field(graph.node_data.get_mut(id).unwrap()) = value;
+    }
+}
+
+/// Distributed averaging of a numeric field stored in the node data.
+///
+/// Every node repeatedly moves its value towards those of its outgoing neighbors, which is
+/// `diffuse` with the largest rate that is stable on every topology, `1 / (max_degree + 1)`.
+/// On a connected undirected graph all values converge to the average of the initial values;
+/// the disagreement shrinks by a factor of about `1 - lambda_2 / (max_degree + 1)` per step, where
+/// `lambda_2` is the algebraic connectivity, see `estimate_algebraic_connectivity`.
+///
+/// # Arguments
+///
+/// * `graph` - The graph whose node data holds the field.
+/// * `steps` - The number of averaging steps.
+/// * `field` - Returns a mutable reference to the value stored in a node's data.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{dynamics::consensus, generate_grid_graph};
+///
+/// let mut g = generate_grid_graph::<f64>(5, 5);
+/// for (id, value) in g.node_data.iter_mut() {
+///     *value = id.0 as f64;
+/// }
+/// consensus(&mut g, 500, |value| value);
+///
+/// assert!(g.node_data.values().all(|value| (value - 2.0).abs() < 1e-6));
+/// ```
+pub fn consensus<IDDataType, NodeDataType, EdgeDataType, F>(
+    graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+    steps: usize,
+    field: F,
+) where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    F: FnMut(&mut NodeDataType) -> &mut f64,
+{
+    let max_degree = graph.edges.values().map(|tos| tos.len()).max().unwrap_or(0);
+    diffuse(graph, 1.0 / (max_degree + 1) as f64, steps, field);
+}
+
+/// Estimates the algebraic connectivity, the second smallest eigenvalue of the graph Laplacian,
+/// by power iteration.
+///
+/// The graph is treated as undirected, i.e. every edge should be stored in both directions.
+/// The result is zero (up to the accuracy of the iteration) exactly when the graph is disconnected,
+/// and larger values mean faster mixing of diffusion and consensus dynamics. Convergence is slow
+/// when the second and third smallest eigenvalues are close; a few hundred iterations are usually
+/// enough for a rough estimate on moderate graphs.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to analyze.
+/// * `iterations` - The number of power iterations.
+///
+pub fn estimate_algebraic_connectivity<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    iterations: usize,
+) -> f64
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let (_, neighbors) = dense_topology(graph);
+    Laplacian::new(&neighbors)
+        .smallest_eigenpairs(1, iterations, 0.0)
+        .first()
+        .map_or(0.0, |(value, _)| *value)
+}
+
+/// lists the nodes in order together with their outgoing neighbors as indices into that list.
+fn dense_topology<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> (Vec<IDDataType>, Vec<Vec<usize>>)
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let ids: Vec<IDDataType> = graph
+        .nodes
+        .iter()
+        .copied()
+        .filter(|id| graph.node_data.contains_key(id))
+        .collect();
+    let index: NodeMap<IDDataType, usize> =
+        ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let neighbors = ids
+        .iter()
+        .map(|id| {
+            graph
+                .edges
+                .get(id)
+                .map(|tos| tos.iter().filter_map(|to| index.get(to).copied()).collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    (ids, neighbors)
+}
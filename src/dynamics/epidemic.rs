@@ -0,0 +1,233 @@
+//! Stochastic SIR and SIS epidemic spreading.
+//!
+//! Infection travels along the outgoing edges of the graph. In every step each infected node
+//! infects each of its susceptible neighbors independently with the infection rate, and then
+//! recovers with the recovery rate. All nodes are updated simultaneously, so a node infected in
+//! a step only becomes contagious in the next one.
+
+use core::hash::Hash;
+use rand::Rng;
+use std::fmt::Debug;
+
+use crate::{collections::NodeMap, Graph};
+
+/// The compartment a node is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Health {
+    /// Can be infected by an infected neighbor.
+    Susceptible,
+    /// Infects its neighbors until it recovers.
+    Infected,
+    /// Immune for the rest of the simulation (SIR only).
+    Recovered,
+}
+
+/// What happens to a node when it recovers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// Recovered nodes are immune.
+    Sir,
+    /// Recovered nodes become susceptible again.
+    Sis,
+}
+
+/// The number of nodes in each compartment at one point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counts {
+    /// The number of susceptible nodes.
+    pub susceptible: usize,
+    /// The number of infected nodes.
+    pub infected: usize,
+    /// The number of recovered nodes.
+    pub recovered: usize,
+}
+
+/// The result of `Epidemic::run`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outbreak<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The compartment sizes before the first step and after every step.
+    pub curve: Vec<Counts>,
+    /// The state of every node at the same points in time as `curve`, if snapshots were requested.
+    pub snapshots: Vec<NodeMap<IDDataType, Health>>,
+    /// The state of every node when the simulation ended.
+    pub final_state: NodeMap<IDDataType, Health>,
+}
+
+impl<IDDataType> Outbreak<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The number of steps that were simulated.
+    pub fn steps(&self) -> usize {
+        self.curve.len() - 1
+    }
+
+    /// The step with the most infected nodes, and that number. Ties resolve to the earliest step.
+    pub fn peak(&self) -> (usize, usize) {
+        self.curve
+            .iter()
+            .enumerate()
+            .fold((0, 0), |peak, (step, counts)| {
+                if counts.infected > peak.1 {
+                    (step, counts.infected)
+                } else {
+                    peak
+                }
+            })
+    }
+}
+
+/// A configurable SIR/SIS simulation.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{dynamics::epidemic::Epidemic, generate_grid_graph};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let g = generate_grid_graph::<()>(20, 20);
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let outbreak = Epidemic::sir(0.5, 0.2).run(&g, &[(10, 10)], &mut rng);
+///
+/// let last = outbreak.curve.last().unwrap();
+/// assert_eq!(last.infected, 0);
+/// assert_eq!(last.susceptible + last.recovered, 400);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Epidemic {
+    model: Model,
+    infection_rate: f64,
+    recovery_rate: f64,
+    max_steps: usize,
+    snapshots: bool,
+}
+
+impl Epidemic {
+    /// A simulation of the given model, running for at most 10 000 steps without snapshots.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Whether recovered nodes are immune or susceptible again.
+    /// * `infection_rate` - The probability that an infected node infects a susceptible neighbor in one step.
+    /// * `recovery_rate` - The probability that an infected node recovers in one step.
+    ///
+    pub fn new(model: Model, infection_rate: f64, recovery_rate: f64) -> Self {
+        Self {
+            model,
+            infection_rate,
+            recovery_rate,
+            max_steps: 10_000,
+            snapshots: false,
+        }
+    }
+
+    /// Shorthand for `Epidemic::new(Model::Sir, infection_rate, recovery_rate)`.
+    pub fn sir(infection_rate: f64, recovery_rate: f64) -> Self {
+        Self::new(Model::Sir, infection_rate, recovery_rate)
+    }
+
+    /// Shorthand for `Epidemic::new(Model::Sis, infection_rate, recovery_rate)`.
+    pub fn sis(infection_rate: f64, recovery_rate: f64) -> Self {
+        Self::new(Model::Sis, infection_rate, recovery_rate)
+    }
+
+    /// Ends the simulation after `steps` steps even if the infection has not died out.
+    /// Mostly relevant for SIS, where an endemic state can persist indefinitely.
+    pub fn max_steps(mut self, steps: usize) -> Self {
+        self.max_steps = steps;
+        self
+    }
+
+    /// Whether to record the state of every node after every step.
+    pub fn record_snapshots(mut self, snapshots: bool) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Simulates the spread of an infection starting from `infected`, until no node is infected
+    /// or the step limit is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The contact network.
+    /// * `infected` - The initially infected nodes; IDs that are not in the graph are ignored.
+    /// * `rng` - The source of randomness; pass a seeded RNG for reproducible runs.
+    ///
+    pub fn run<IDDataType, NodeDataType, EdgeDataType, R>(
+        &self,
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        infected: &[IDDataType],
+        rng: &mut R,
+    ) -> Outbreak<IDDataType>
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        R: Rng + ?Sized,
+    {
+        let mut state: NodeMap<IDDataType, Health> = graph
+            .nodes
+            .iter()
+            .map(|id| (*id, Health::Susceptible))
+            .collect();
+        for id in infected {
+            if let Some(health) = state.get_mut(id) {
+                *health = Health::Infected;
+            }
+        }
+
+        let mut curve = vec![count(&state)];
+        let mut snapshots = Vec::new();
+        if self.snapshots {
+            snapshots.push(state.clone());
+        }
+
+        let recovered = match self.model {
+            Model::Sir => Health::Recovered,
+            Model::Sis => Health::Susceptible,
+        };
+        while curve.last().unwrap().infected > 0 && curve.len() <= self.max_steps {
+            let mut next = state.clone();
+            for id in &graph.nodes {
+                if state[id] != Health::Infected {
+                    continue;
+                }
+                for to in graph.edges.get(id).into_iter().flatten() {
+                    if state.get(to) == Some(&Health::Susceptible)
+                        && rng.gen::<f64>() < self.infection_rate
+                    {
+                        next.insert(*to, Health::Infected);
+                    }
+                }
+                if rng.gen::<f64>() < self.recovery_rate {
+                    next.insert(*id, recovered);
+                }
+            }
+            state = next;
+            curve.push(count(&state));
+            if self.snapshots {
+                snapshots.push(state.clone());
+            }
+        }
+
+        Outbreak {
+            curve,
+            snapshots,
+            final_state: state,
+        }
+    }
+}
+
+/// tallies the compartment sizes.
+fn count<IDDataType>(state: &NodeMap<IDDataType, Health>) -> Counts {
+    let mut counts = Counts::default();
+    for health in state.values() {
+        match health {
+            Health::Susceptible => counts.susceptible += 1,
+            Health::Infected => counts.infected += 1,
+            Health::Recovered => counts.recovered += 1,
+        }
+    }
+    counts
+}
@@ -0,0 +1,401 @@
+//! Monte Carlo sampling of Ising and Potts models.
+//!
+//! Spins are integers stored in the node data and accessed through a closure, so they can live
+//! in any field of a larger node type. Ising spins are `-1` or `1`, Potts spins are `0..states`.
+//! The graph is treated as undirected: every edge is expected to be stored in both directions, as
+//! `add_edge` and the lattice generators do, and each such pair contributes to the energy once.
+
+use core::hash::Hash;
+use rand::Rng;
+use std::fmt::Debug;
+
+use crate::Graph;
+
+/// The interaction between neighboring spins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpinModel {
+    /// Spins `-1` and `1` with energy `-coupling * sum(s_i * s_j) - field * sum(s_i)`.
+    Ising {
+        /// The interaction strength; positive values favor aligned neighbors.
+        coupling: f64,
+        /// The external field; positive values favor spin `1`.
+        field: f64,
+    },
+    /// Spins `0..states` with energy `-coupling * (number of neighboring pairs in the same state)`.
+    Potts {
+        /// The number of possible states.
+        states: i32,
+        /// The interaction strength; positive values favor equal neighbors.
+        coupling: f64,
+    },
+}
+
+/// The rule used to accept a proposed spin change that changes the energy by `delta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dynamics {
+    /// Accept with probability `min(1, exp(-delta / T))`.
+    Metropolis,
+    /// Accept with probability `1 / (1 + exp(delta / T))`.
+    Glauber,
+}
+
+/// Measurements taken after a sweep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observables {
+    /// The temperature the sweep ran at.
+    pub temperature: f64,
+    /// The total energy of the configuration.
+    pub energy: f64,
+    /// The order parameter: the mean spin for Ising, `(q * largest_fraction - 1) / (q - 1)` for Potts.
+    pub magnetization: f64,
+}
+
+/// A Monte Carlo sampler for a spin model.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::dynamics::spin::{geometric_schedule, Dynamics, SpinModel, SpinSampler};
+/// use grafferous::generate_grid_graph;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut g = generate_grid_graph::<i32>(16, 16);
+/// let sampler = SpinSampler::new(
+///     SpinModel::Ising { coupling: 1.0, field: 0.0 },
+///     Dynamics::Metropolis,
+/// );
+/// let mut rng = StdRng::seed_from_u64(3);
+/// sampler.randomize(&mut g, &mut rng, |spin| spin);
+///
+/// // cool down from a disordered to an ordered phase
+/// let trace = sampler.anneal(&mut g, geometric_schedule(5.0, 0.5, 200), &mut rng, |spin| spin);
+/// assert_eq!(trace.len(), 200);
+/// assert!(trace.last().unwrap().energy < trace[0].energy);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpinSampler {
+    model: SpinModel,
+    dynamics: Dynamics,
+}
+
+/// The spins of all nodes in a dense layout, with neighbors as indices.
+struct Lattice<IDDataType> {
+    ids: Vec<IDDataType>,
+    neighbors: Vec<Vec<usize>>,
+    spins: Vec<i32>,
+}
+
+impl SpinSampler {
+    /// A sampler for `model` that accepts proposals according to `dynamics`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a Potts model has no states.
+    pub fn new(model: SpinModel, dynamics: Dynamics) -> Self {
+        if let SpinModel::Potts { states, .. } = model {
+            assert!(
+                states > 0,
+                "a Potts model needs at least one state, got {states}"
+            );
+        }
+        Self { model, dynamics }
+    }
+
+    /// Sets every spin to a uniformly random valid value.
+    pub fn randomize<IDDataType, NodeDataType, EdgeDataType, R, F>(
+        &self,
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        rng: &mut R,
+        mut spin: F,
+    ) where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        R: Rng + ?Sized,
+        F: FnMut(&mut NodeDataType) -> &mut i32,
+    {
+        for id in &graph.nodes {
+            if let Some(data) = graph.node_data.get_mut(id) {
+                *spin(data) = match self.model {
+                    SpinModel::Ising { .. } => {
+                        if rng.gen::<bool>() {
+                            1
+                        } else {
+                            -1
+                        }
+                    }
+                    SpinModel::Potts { states, .. } => rng.gen_range(0..states),
+                };
+            }
+        }
+    }
+
+    /// Runs `sweeps` sweeps at a fixed temperature. A sweep proposes as many single-spin
+    /// changes, at uniformly chosen nodes, as there are nodes. With zero sweeps the spins are
+    /// left as they are and the observables of the current configuration are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph whose node data holds the spins.
+    /// * `temperature` - The temperature; `0.0` only accepts changes that do not raise the energy.
+    /// * `sweeps` - The number of sweeps.
+    /// * `rng` - The source of randomness.
+    /// * `spin` - Returns a mutable reference to the spin stored in a node's data.
+    ///
+    pub fn sample<IDDataType, NodeDataType, EdgeDataType, R, F>(
+        &self,
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        temperature: f64,
+        sweeps: usize,
+        rng: &mut R,
+        mut spin: F,
+    ) -> Observables
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        R: Rng + ?Sized,
+        F: FnMut(&mut NodeDataType) -> &mut i32,
+    {
+        if sweeps == 0 {
+            return self.observe(&Lattice::load(graph, &mut spin), temperature);
+        }
+        self.anneal(graph, std::iter::repeat_n(temperature, sweeps), rng, spin)
+            .pop()
+            .unwrap()
+    }
+
+    /// Runs one sweep per temperature of `schedule`, recording the observables after each sweep.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph whose node data holds the spins.
+    /// * `schedule` - The temperature of every sweep, see `linear_schedule` and `geometric_schedule`.
+    /// * `rng` - The source of randomness.
+    /// * `spin` - Returns a mutable reference to the spin stored in a node's data.
+    ///
+    pub fn anneal<IDDataType, NodeDataType, EdgeDataType, R, F>(
+        &self,
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        schedule: impl IntoIterator<Item = f64>,
+        rng: &mut R,
+        mut spin: F,
+    ) -> Vec<Observables>
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        R: Rng + ?Sized,
+        F: FnMut(&mut NodeDataType) -> &mut i32,
+    {
+        let mut lattice = Lattice::load(graph, &mut spin);
+        let mut trace = Vec::new();
+        for temperature in schedule {
+            for _ in 0..lattice.spins.len() {
+                let site = rng.gen_range(0..lattice.spins.len());
+                self.update(&mut lattice, site, temperature, rng);
+            }
+            trace.push(self.observe(&lattice, temperature));
+        }
+        for (id, value) in lattice.ids.iter().zip(&lattice.spins) {
+            *spin(graph.node_data.get_mut(id).unwrap()) = *value;
+        }
+        trace
+    }
+
+    /// The total energy of the spins currently stored in `graph`.
+    pub fn energy<IDDataType, NodeDataType, EdgeDataType, F>(
+        &self,
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        spin: F,
+    ) -> f64
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        F: Fn(&NodeDataType) -> i32,
+    {
+        self.model.energy(&Lattice::read(graph, spin))
+    }
+
+    /// The order parameter of the spins currently stored in `graph`, see [`Observables::magnetization`].
+    pub fn magnetization<IDDataType, NodeDataType, EdgeDataType, F>(
+        &self,
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        spin: F,
+    ) -> f64
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        F: Fn(&NodeDataType) -> i32,
+    {
+        self.model.magnetization(&Lattice::read(graph, spin).spins)
+    }
+
+    /// the observables of the current configuration.
+    fn observe<IDDataType>(&self, lattice: &Lattice<IDDataType>, temperature: f64) -> Observables {
+        Observables {
+            temperature,
+            energy: self.model.energy(lattice),
+            magnetization: self.model.magnetization(&lattice.spins),
+        }
+    }
+
+    /// proposes a change of one spin and accepts or rejects it.
+    fn update<IDDataType, R: Rng + ?Sized>(
+        &self,
+        lattice: &mut Lattice<IDDataType>,
+        site: usize,
+        temperature: f64,
+        rng: &mut R,
+    ) {
+        let current = lattice.spins[site];
+        let neighbors = lattice.neighbors[site].iter().map(|&j| lattice.spins[j]);
+        let (proposed, delta) = match self.model {
+            SpinModel::Ising { coupling, field } => {
+                let local: i32 = neighbors.sum();
+                let delta = 2.0 * current as f64 * (coupling * local as f64 + field);
+                (-current, delta)
+            }
+            SpinModel::Potts { states, coupling } => {
+                if states < 2 {
+                    return;
+                }
+                // a uniformly chosen state different from the current one
+                let mut proposed = rng.gen_range(0..states - 1);
+                if proposed >= current {
+                    proposed += 1;
+                }
+                let (mut same_before, mut same_after) = (0, 0);
+                for neighbor in neighbors {
+                    same_before += (neighbor == current) as i32;
+                    same_after += (neighbor == proposed) as i32;
+                }
+                (proposed, -coupling * (same_after - same_before) as f64)
+            }
+        };
+
+        let accept = if temperature <= 0.0 {
+            match self.dynamics {
+                Dynamics::Metropolis => delta <= 0.0,
+                Dynamics::Glauber => delta < 0.0 || (delta == 0.0 && rng.gen::<bool>()),
+            }
+        } else {
+            let probability = match self.dynamics {
+                Dynamics::Metropolis => (-delta / temperature).exp().min(1.0),
+                Dynamics::Glauber => 1.0 / (1.0 + (delta / temperature).exp()),
+            };
+            rng.gen::<f64>() < probability
+        };
+        if accept {
+            lattice.spins[site] = proposed;
+        }
+    }
+}
+
+impl SpinModel {
+    /// the total energy, counting every undirected pair once.
+    fn energy<IDDataType>(&self, lattice: &Lattice<IDDataType>) -> f64 {
+        let mut pairs = 0.0;
+        let mut total_spin = 0.0;
+        for (i, &s) in lattice.spins.iter().enumerate() {
+            total_spin += s as f64;
+            for &j in &lattice.neighbors[i] {
+                let t = lattice.spins[j];
+                pairs += match self {
+                    SpinModel::Ising { .. } => (s * t) as f64,
+                    SpinModel::Potts { .. } => (s == t) as i32 as f64,
+                };
+            }
+        }
+        match *self {
+            SpinModel::Ising { coupling, field } => -coupling * pairs / 2.0 - field * total_spin,
+            SpinModel::Potts { coupling, .. } => -coupling * pairs / 2.0,
+        }
+    }
+
+    /// the order parameter of a configuration.
+    fn magnetization(&self, spins: &[i32]) -> f64 {
+        if spins.is_empty() {
+            return 0.0;
+        }
+        let n = spins.len() as f64;
+        match *self {
+            SpinModel::Ising { .. } => spins.iter().map(|&s| s as f64).sum::<f64>() / n,
+            SpinModel::Potts { states, .. } => {
+                let mut counts = vec![0usize; states.max(1) as usize];
+                for &s in spins {
+                    if let Some(count) = counts.get_mut(s as usize) {
+                        *count += 1;
+                    }
+                }
+                let largest = *counts.iter().max().unwrap() as f64 / n;
+                if states > 1 {
+                    (states as f64 * largest - 1.0) / (states as f64 - 1.0)
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+impl<IDDataType> Lattice<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// copies the topology and the spins, read through a mutable accessor.
+    fn load<NodeDataType, EdgeDataType>(
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        spin: &mut impl FnMut(&mut NodeDataType) -> &mut i32,
+    ) -> Self {
+        let mut lattice = Self::topology(graph);
+        lattice.spins = lattice
+            .ids
+            .iter()
+            .map(|id| *spin(graph.node_data.get_mut(id).unwrap()))
+            .collect();
+        lattice
+    }
+
+    /// copies the topology and the spins, read through a shared accessor.
+    fn read<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        spin: impl Fn(&NodeDataType) -> i32,
+    ) -> Self {
+        let mut lattice = Self::topology(graph);
+        lattice.spins = lattice
+            .ids
+            .iter()
+            .map(|id| spin(&graph.node_data[id]))
+            .collect();
+        lattice
+    }
+
+    fn topology<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self {
+        let (ids, neighbors) = super::dense_topology(graph);
+        Self {
+            ids,
+            neighbors,
+            spins: Vec::new(),
+        }
+    }
+}
+
+/// `steps` temperatures decreasing linearly from `from` to `to`.
+pub fn linear_schedule(from: f64, to: f64, steps: usize) -> Vec<f64> {
+    match steps {
+        0 => Vec::new(),
+        1 => vec![to],
+        _ => (0..steps)
+            .map(|i| from + (to - from) * i as f64 / (steps - 1) as f64)
+            .collect(),
+    }
+}
+
+/// `steps` temperatures decreasing geometrically from `from` to `to`, which must both be positive.
+pub fn geometric_schedule(from: f64, to: f64, steps: usize) -> Vec<f64> {
+    match steps {
+        0 => Vec::new(),
+        1 => vec![to],
+        _ => {
+            let ratio = (to / from).powf(1.0 / (steps - 1) as f64);
+            let mut schedule: Vec<f64> = (0..steps).map(|i| from * ratio.powi(i as i32)).collect();
+            schedule[steps - 1] = to;
+            schedule
+        }
+    }
+}
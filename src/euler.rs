@@ -0,0 +1,170 @@
+//! Eulerian paths and circuits, which use every edge exactly once, with Hierholzer's algorithm.
+//!
+//! A graph for which `is_undirected` holds is treated as an undirected multigraph: an edge
+//! stored in both directions counts once, and so does a self-loop added with `add_edge`. Any
+//! other graph is treated as directed, with every parallel edge counted. Nodes without edges
+//! are ignored, so they do not make a graph disconnected.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Whether there is a closed walk that uses every edge exactly once: the edges are
+    /// connected and every node has as many incoming as outgoing edges, or an even degree in an
+    /// undirected graph. A graph without edges has the empty circuit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let square = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3 -- 0").unwrap();
+    /// assert!(square.has_eulerian_circuit());
+    /// let path = Graph::<u32, ()>::parse("0 -> 1 -> 2").unwrap();
+    /// assert!(!path.has_eulerian_circuit());
+    /// ```
+    pub fn has_eulerian_circuit(&self) -> bool {
+        self.eulerian_path()
+            .is_some_and(|path| path.first() == path.last())
+    }
+
+    /// Whether there is a walk that uses every edge exactly once: the edges are connected and
+    /// either every node is balanced as for `has_eulerian_circuit`, or one node has one more
+    /// outgoing than incoming edge and another one more incoming than outgoing edge, or exactly
+    /// two nodes have an odd degree in an undirected graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // the seven bridges of Königsberg
+    /// let mut bridges = Graph::<char, ()>::new();
+    /// bridges.add_edges(&[('a', 'b'), ('a', 'b'), ('a', 'c'), ('a', 'c')]);
+    /// bridges.add_edges(&[('a', 'd'), ('b', 'd'), ('c', 'd')]);
+    /// assert!(!bridges.has_eulerian_path());
+    ///
+    /// bridges.remove_edge('a', 'd');
+    /// assert!(bridges.has_eulerian_path());
+    /// ```
+    pub fn has_eulerian_path(&self) -> bool {
+        self.eulerian_path().is_some()
+    }
+
+    /// A walk that uses every edge exactly once, as the list of the nodes it visits, or `None`
+    /// if there is none, see `has_eulerian_path`. It is a circuit, starting and ending at the
+    /// same node, whenever the graph has one. The walk is empty for a graph without edges.
+    ///
+    /// Takes `O(V + E)` time with Hierholzer's algorithm.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0 -> 3").unwrap();
+    /// assert_eq!(g.eulerian_path(), Some(vec![0, 1, 2, 0, 3]));
+    ///
+    /// let triangle = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0").unwrap();
+    /// let circuit = triangle.eulerian_path().unwrap();
+    /// assert_eq!(circuit.len(), 4);
+    /// assert_eq!(circuit.first(), circuit.last());
+    /// ```
+    pub fn eulerian_path(&self) -> Option<Vec<IDDataType>> {
+        let n = self.nodes.len();
+        let index: NodeMap<IDDataType, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let undirected = self.is_undirected();
+
+        // every edge once as a pair of node indices, pairing up the two directions of an
+        // undirected edge
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut unpaired: NodeMap<(usize, usize), usize> = NodeMap::default();
+        for (i, from) in self.nodes.iter().enumerate() {
+            for j in self.neighbors(*from).iter().filter_map(|to| index.get(to)) {
+                if undirected {
+                    if let Some(count) = unpaired.get_mut(&(*j, i)).filter(|count| **count > 0) {
+                        *count -= 1;
+                        continue;
+                    }
+                    *unpaired.entry((i, *j)).or_default() += 1;
+                }
+                edges.push((i, *j));
+            }
+        }
+        if edges.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let mut incident: Vec<Vec<usize>> = vec![Vec::new(); n];
+        // outgoing minus incoming edges, or the degree of an undirected graph
+        let mut balance = vec![0isize; n];
+        for (e, &(i, j)) in edges.iter().enumerate() {
+            incident[i].push(e);
+            if undirected {
+                if i != j {
+                    incident[j].push(e);
+                }
+                balance[i] += 1;
+                balance[j] += 1;
+            } else {
+                balance[i] += 1;
+                balance[j] -= 1;
+            }
+        }
+        let start = if undirected {
+            let odd: Vec<usize> = (0..n).filter(|i| balance[*i] % 2 != 0).collect();
+            match odd[..] {
+                [] => None,
+                [first, _] => Some(first),
+                _ => return None,
+            }
+        } else {
+            let unbalanced: Vec<usize> = (0..n).filter(|i| balance[*i] != 0).collect();
+            match unbalanced[..] {
+                [] => None,
+                [a, b] if balance[a] == 1 && balance[b] == -1 => Some(a),
+                [a, b] if balance[a] == -1 && balance[b] == 1 => Some(b),
+                _ => return None,
+            }
+        };
+        let start = start.unwrap_or(edges[0].0);
+
+        // Hierholzer: follow unused edges until stuck, then back up, recording the nodes in
+        // reverse order of the walk
+        let mut used = vec![false; edges.len()];
+        let mut next = vec![0; n];
+        let mut stack = vec![start];
+        let mut walk = Vec::with_capacity(edges.len() + 1);
+        while let Some(&node) = stack.last() {
+            let unused = loop {
+                match incident[node].get(next[node]) {
+                    Some(e) if used[*e] => next[node] += 1,
+                    other => break other.copied(),
+                }
+            };
+            match unused {
+                Some(e) => {
+                    used[e] = true;
+                    let (i, j) = edges[e];
+                    stack.push(if i == node { j } else { i });
+                }
+                None => walk.push(stack.pop().expect("the stack is not empty")),
+            }
+        }
+        // edges in another component were never reached
+        if walk.len() != edges.len() + 1 {
+            return None;
+        }
+        Some(walk.into_iter().rev().map(|i| self.nodes[i]).collect())
+    }
+}
@@ -0,0 +1,226 @@
+//! A C interface behind the `ffi` feature, declared in `include/grafferous.h`.
+//!
+//! Graphs are passed around as an opaque `GrafferousGraph` handle with `uint64_t` node IDs and no
+//! node data. Functions that return a list copy it into a caller-provided buffer and return its full
+//! length, so a first call with a capacity of zero tells how large the buffer must be.
+//!
+//! ```c
+//! GrafferousGraph *g = grafferous_graph_new();
+//! grafferous_graph_add_edge(g, 1, 2);
+//! grafferous_graph_add_edge(g, 2, 3);
+//!
+//! uint64_t path[16];
+//! size_t length = grafferous_graph_shortest_path(g, 1, 3, path, 16); // 3: {1, 2, 3}
+//! grafferous_graph_free(g);
+//! ```
+
+use std::{ptr, slice};
+
+use crate::{visit::bfs_path, Graph};
+
+/// The opaque graph handle of the C interface.
+pub struct GrafferousGraph {
+    graph: Graph<u64, ()>,
+}
+
+/// Creates a new, empty graph. Release it with `grafferous_graph_free`.
+#[no_mangle]
+pub extern "C" fn grafferous_graph_new() -> *mut GrafferousGraph {
+    Box::into_raw(Box::new(GrafferousGraph {
+        graph: Graph::new(),
+    }))
+}
+
+/// Releases a graph. Passing null does nothing.
+///
+/// # Safety
+///
+/// `graph` must be null or a handle from `grafferous_graph_new` that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_free(graph: *mut GrafferousGraph) {
+    if !graph.is_null() {
+        drop(Box::from_raw(graph));
+    }
+}
+
+/// Adds a node. Returns `true` if it was added and `false` if it already existed or `graph` is null.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not used by another thread at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_add_node(graph: *mut GrafferousGraph, id: u64) -> bool {
+    let Some(handle) = graph.as_mut() else {
+        return false;
+    };
+    if handle.graph.node_data.contains_key(&id) {
+        return false;
+    }
+    handle.graph.add_node(id);
+    true
+}
+
+/// Adds an edge in both directions, adding missing nodes.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not used by another thread at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_add_edge(
+    graph: *mut GrafferousGraph,
+    from: u64,
+    to: u64,
+) {
+    if let Some(handle) = graph.as_mut() {
+        handle.graph.add_edge(from, to);
+    }
+}
+
+/// Adds an edge from `from` to `to`, adding missing nodes.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not used by another thread at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_add_directed_edge(
+    graph: *mut GrafferousGraph,
+    from: u64,
+    to: u64,
+) {
+    if let Some(handle) = graph.as_mut() {
+        handle.graph.add_directed_edge(from, to);
+    }
+}
+
+/// Adds many edges given as `count` pairs `edges[2 * i] -> edges[2 * i + 1]`, in both directions
+/// unless `directed` is true. Returns false and adds nothing if `graph` or `edges` is null or
+/// `2 * count` overflows.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not used by another thread at the same time, and
+/// `edges` must be valid for reading `2 * count` IDs.
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_add_edges(
+    graph: *mut GrafferousGraph,
+    edges: *const u64,
+    count: usize,
+    directed: bool,
+) -> bool {
+    let Some(handle) = graph.as_mut() else {
+        return false;
+    };
+    let Some(length) = count.checked_mul(2) else {
+        return false;
+    };
+    if edges.is_null() {
+        return false;
+    }
+    let pairs: Vec<(u64, u64)> = slice::from_raw_parts(edges, length)
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    if directed {
+        handle.graph.add_directed_edges(&pairs);
+    } else {
+        handle.graph.add_edges(&pairs);
+    }
+    true
+}
+
+/// The number of nodes, or zero if `graph` is null.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not modified at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_node_count(graph: *const GrafferousGraph) -> usize {
+    graph.as_ref().map_or(0, |handle| handle.graph.nodes.len())
+}
+
+/// The IDs of all nodes, in insertion order, copied like `grafferous_graph_neighbors`.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not modified at the same time, and `out` must be
+/// valid for writing `capacity` IDs (it may be null if `capacity` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_nodes(
+    graph: *const GrafferousGraph,
+    out: *mut u64,
+    capacity: usize,
+) -> usize {
+    graph
+        .as_ref()
+        .map_or(0, |handle| copy_out(&handle.graph.nodes, out, capacity))
+}
+
+/// Whether a node exists; `false` if `graph` is null.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not modified at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_contains_node(
+    graph: *const GrafferousGraph,
+    id: u64,
+) -> bool {
+    graph
+        .as_ref()
+        .is_some_and(|handle| handle.graph.node_data.contains_key(&id))
+}
+
+/// Copies the outgoing neighbors of a node into `out` and returns how many there are.
+/// At most `capacity` IDs are written.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not modified at the same time, and `out` must be
+/// valid for writing `capacity` IDs (it may be null if `capacity` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_neighbors(
+    graph: *const GrafferousGraph,
+    id: u64,
+    out: *mut u64,
+    capacity: usize,
+) -> usize {
+    let Some(handle) = graph.as_ref() else {
+        return 0;
+    };
+    let neighbors = handle.graph.edges.get(&id).map_or(&[][..], |n| &n[..]);
+    copy_out(neighbors, out, capacity)
+}
+
+/// Finds a path with the fewest edges from `from` to `to`, copies its nodes (both ends included)
+/// into `out` and returns its number of nodes, or zero if there is no path.
+/// At most `capacity` IDs are written.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle that is not modified at the same time, and `out` must be
+/// valid for writing `capacity` IDs (it may be null if `capacity` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn grafferous_graph_shortest_path(
+    graph: *const GrafferousGraph,
+    from: u64,
+    to: u64,
+    out: *mut u64,
+    capacity: usize,
+) -> usize {
+    let Some(handle) = graph.as_ref() else {
+        return 0;
+    };
+    match bfs_path(&handle.graph, from, to) {
+        Some(path) => copy_out(&path, out, capacity),
+        None => 0,
+    }
+}
+
+/// writes as many IDs as fit into `out` and returns the full length.
+unsafe fn copy_out(ids: &[u64], out: *mut u64, capacity: usize) -> usize {
+    let count = ids.len().min(capacity);
+    if count > 0 && !out.is_null() {
+        ptr::copy_nonoverlapping(ids.as_ptr(), out, count);
+    }
+    ids.len()
+}
@@ -0,0 +1,255 @@
+//! Maximum flows and minimum cuts with Dinic's algorithm.
+//!
+//! Every directed edge is an arc whose capacity is either its edge data or given by a function of
+//! its two nodes. Parallel edges share their edge data and so count as one arc, and edges without
+//! data or with a capacity of zero carry no flow. Capacities must not be negative; integer
+//! capacities give an exact result.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Sub},
+};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// A maximum flow between two nodes together with a minimum cut, as computed by
+/// `Graph::max_flow`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxFlow<IDDataType, C>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The amount of flow from the source to the sink, which equals the capacity of the cut.
+    pub value: C,
+    /// The flow along every edge that carries any.
+    pub flow: NodeMap<(IDDataType, IDDataType), C>,
+    /// The nodes on the source side of the minimum cut: those the source still reaches through
+    /// edges that are not saturated.
+    pub source_side: NodeSet<IDDataType>,
+    /// The edges of the minimum cut, which lead from the source side to the rest and are all
+    /// saturated, in the order of `nodes` and of the neighbor lists. Removing them disconnects
+    /// the sink from the source.
+    pub cut: Vec<(IDDataType, IDDataType)>,
+}
+
+/// an arc of the residual network; the arc at `i ^ 1` is its reverse.
+struct Arc<C> {
+    to: usize,
+    residual: C,
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A maximum flow from `source` to `sink` and a minimum cut, with the edge data as the
+    /// capacities of the edges. Returns `None` if either node is not in the graph or they are the
+    /// same node.
+    ///
+    /// This is Dinic's algorithm, which takes `O(V²E)` time and far less on most graphs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<char, (), u32>::new();
+    /// g.add_directed_edge_with_data('s', 'a', 10);
+    /// g.add_directed_edge_with_data('s', 'b', 5);
+    /// g.add_directed_edge_with_data('a', 'b', 15);
+    /// g.add_directed_edge_with_data('a', 't', 4);
+    /// g.add_directed_edge_with_data('b', 't', 10);
+    ///
+    /// let result = g.max_flow('s', 't').unwrap();
+    /// assert_eq!(result.value, 14);
+    /// assert_eq!(result.cut, vec![('a', 't'), ('b', 't')]);
+    /// assert_eq!(result.flow[&('a', 'b')], 5);
+    /// ```
+    pub fn max_flow(
+        &self,
+        source: IDDataType,
+        sink: IDDataType,
+    ) -> Option<MaxFlow<IDDataType, EdgeDataType>>
+    where
+        EdgeDataType:
+            Copy + Ord + Add<Output = EdgeDataType> + Sub<Output = EdgeDataType> + Default,
+    {
+        self.dinic(source, sink, |from, to| self.edge_data(from, to).copied())
+    }
+
+    /// Like `max_flow`, but with the capacity of every directed edge given by `capacity`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// // every edge of the grid can carry one unit, so the corner is the bottleneck
+    /// let g = generate_grid_graph::<()>(4, 4);
+    /// let result = g.max_flow_by((0, 0), (3, 3), |_, _| 1u32).unwrap();
+    /// assert_eq!(result.value, 2);
+    /// ```
+    pub fn max_flow_by<C>(
+        &self,
+        source: IDDataType,
+        sink: IDDataType,
+        capacity: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> Option<MaxFlow<IDDataType, C>>
+    where
+        C: Copy + Ord + Add<Output = C> + Sub<Output = C> + Default,
+    {
+        self.dinic(source, sink, |from, to| Some(capacity(from, to)))
+    }
+
+    /// Dinic's algorithm on the residual network of the edges with a capacity.
+    fn dinic<C>(
+        &self,
+        source: IDDataType,
+        sink: IDDataType,
+        capacity: impl Fn(IDDataType, IDDataType) -> Option<C>,
+    ) -> Option<MaxFlow<IDDataType, C>>
+    where
+        C: Copy + Ord + Add<Output = C> + Sub<Output = C> + Default,
+    {
+        let zero = C::default();
+        let index: NodeMap<IDDataType, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let (&s, &t) = (index.get(&source)?, index.get(&sink)?);
+        if s == t {
+            return None;
+        }
+
+        let mut arcs: Vec<Arc<C>> = Vec::new();
+        let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+        // the original edges with their arc and capacity, in the order of the neighbor lists
+        let mut edges = Vec::new();
+        let mut seen = NodeSet::default();
+        for (u, from) in self.nodes.iter().enumerate() {
+            for to in self.neighbors(*from) {
+                let Some(&v) = index.get(to) else {
+                    continue;
+                };
+                let Some(cap) = capacity(*from, *to) else {
+                    continue;
+                };
+                if u == v || cap <= zero || !seen.insert((u, v)) {
+                    continue;
+                }
+                edges.push((*from, *to, arcs.len(), cap));
+                outgoing[u].push(arcs.len());
+                arcs.push(Arc {
+                    to: v,
+                    residual: cap,
+                });
+                outgoing[v].push(arcs.len());
+                arcs.push(Arc {
+                    to: u,
+                    residual: zero,
+                });
+            }
+        }
+
+        let mut value = zero;
+        while let Some(level) = levels(&arcs, &outgoing, s, Some(t)) {
+            // the next arc to try at every node; arcs before it lead to dead ends
+            let mut next = vec![0; outgoing.len()];
+            let mut path: Vec<usize> = Vec::new();
+            let mut node = s;
+            loop {
+                if node == t {
+                    let bottleneck = path.iter().map(|a| arcs[*a].residual).min().unwrap_or(zero);
+                    for a in &path {
+                        arcs[*a].residual = arcs[*a].residual - bottleneck;
+                        arcs[*a ^ 1].residual = arcs[*a ^ 1].residual + bottleneck;
+                    }
+                    value = value + bottleneck;
+                    path.clear();
+                    node = s;
+                    continue;
+                }
+                let advance = outgoing[node][next[node]..].iter().position(|a| {
+                    arcs[*a].residual > zero && level[arcs[*a].to] == level[node] + 1
+                });
+                match advance {
+                    Some(offset) => {
+                        next[node] += offset;
+                        let a = outgoing[node][next[node]];
+                        path.push(a);
+                        node = arcs[a].to;
+                    }
+                    None => {
+                        next[node] = outgoing[node].len();
+                        // retreat from the dead end, skipping the arc that led to it
+                        let Some(a) = path.pop() else {
+                            break;
+                        };
+                        node = arcs[a ^ 1].to;
+                        next[node] += 1;
+                    }
+                }
+            }
+        }
+
+        let reached = levels(&arcs, &outgoing, s, None).unwrap_or_default();
+        let on_source_side = |i: usize| reached[i] != usize::MAX;
+        let mut flow = NodeMap::default();
+        let mut cut = Vec::new();
+        for (from, to, arc, cap) in edges {
+            let carried = cap - arcs[arc].residual;
+            if carried > zero {
+                flow.insert((from, to), carried);
+            }
+            if on_source_side(index[&from]) && !on_source_side(index[&to]) {
+                cut.push((from, to));
+            }
+        }
+        let source_side = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| on_source_side(*i))
+            .map(|(_, id)| *id)
+            .collect();
+        Some(MaxFlow {
+            value,
+            flow,
+            source_side,
+            cut,
+        })
+    }
+}
+
+/// the breadth-first level of every node in the residual network, `usize::MAX` for nodes that
+/// cannot be reached, or `None` if `target` is given and cannot be reached.
+fn levels<C: Copy + Ord + Default>(
+    arcs: &[Arc<C>],
+    outgoing: &[Vec<usize>],
+    source: usize,
+    target: Option<usize>,
+) -> Option<Vec<usize>> {
+    let mut level = vec![usize::MAX; outgoing.len()];
+    level[source] = 0;
+    let mut queue = VecDeque::from([source]);
+    while let Some(node) = queue.pop_front() {
+        for a in &outgoing[node] {
+            let arc = &arcs[*a];
+            if arc.residual > C::default() && level[arc.to] == usize::MAX {
+                level[arc.to] = level[node] + 1;
+                queue.push_back(arc.to);
+            }
+        }
+    }
+    match target {
+        Some(target) if level[target] == usize::MAX => None,
+        _ => Some(level),
+    }
+}
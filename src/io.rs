@@ -0,0 +1,474 @@
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::{fmt::Debug, hash::Hash, str::FromStr};
+
+use crate::Graph;
+
+/// How far a streaming load has progressed, passed to the progress callback of [`StreamOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// The number of edges read from the stream so far.
+    pub edges: usize,
+    /// The number of nodes in the graph so far.
+    pub nodes: usize,
+}
+
+/// Options for `Graph::from_edge_stream`.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{io::StreamOptions, Graph};
+///
+/// let edges = (0..10_000usize).map(|i| (i, i + 1));
+/// let mut reports = 0;
+/// let options = StreamOptions::new().progress_every(2_500, |_| reports += 1);
+/// let g = Graph::<usize, ()>::from_edge_stream(edges, options);
+///
+/// assert_eq!(g.nodes.len(), 10_001);
+/// assert_eq!(reports, 4);
+/// ```
+pub struct StreamOptions<'a> {
+    chunk_size: usize,
+    undirected: bool,
+    progress_interval: usize,
+    progress: Option<Box<dyn FnMut(LoadProgress) + 'a>>,
+}
+
+impl<'a> StreamOptions<'a> {
+    /// Default options: directed edges, inserted in chunks of 64k, without progress reporting.
+    pub fn new() -> Self {
+        Self {
+            chunk_size: 1 << 16,
+            undirected: false,
+            progress_interval: 0,
+            progress: None,
+        }
+    }
+
+    /// The number of edges buffered before they are inserted into the graph as one batch.
+    pub fn chunk_size(mut self, edges: usize) -> Self {
+        self.chunk_size = edges.max(1);
+        self
+    }
+
+    /// Whether each streamed pair should be added as an undirected edge.
+    pub fn undirected(mut self, undirected: bool) -> Self {
+        self.undirected = undirected;
+        self
+    }
+
+    /// Calls `callback` every time another `interval` edges have been loaded.
+    pub fn progress_every(
+        mut self,
+        interval: usize,
+        callback: impl FnMut(LoadProgress) + 'a,
+    ) -> Self {
+        self.progress_interval = interval.max(1);
+        self.progress = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Default for StreamOptions<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Builds a graph from a stream of edges without collecting the stream first.
+    ///
+    /// Edges are buffered and inserted in chunks with `add_directed_edges` (or `add_edges`
+    /// for undirected streams), and the internal maps grow with the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - Any iterator of `(from, to)` pairs.
+    /// * `options` - Chunking, direction and progress reporting, see [`StreamOptions`].
+    ///
+    pub fn from_edge_stream(
+        edges: impl IntoIterator<Item = (IDDataType, IDDataType)>,
+        options: StreamOptions<'_>,
+    ) -> Self {
+        let result: Result<Self, core::convert::Infallible> =
+            Self::try_from_edge_stream(edges.into_iter().map(Ok), options);
+        match result {
+            Ok(graph) => graph,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Like `from_edge_stream`, but for fallible streams such as edges parsed from a reader.
+    /// Loading stops at the first error, which is returned.
+    pub fn try_from_edge_stream<E>(
+        edges: impl IntoIterator<Item = Result<(IDDataType, IDDataType), E>>,
+        mut options: StreamOptions<'_>,
+    ) -> Result<Self, E> {
+        let mut graph = Self::new();
+        let edges = edges.into_iter();
+        let expected = edges.size_hint().0;
+        graph.node_data.reserve(expected);
+        graph.edges.reserve(expected);
+        graph.reverse_edges.reserve(expected);
+
+        let mut buffer = Vec::with_capacity(options.chunk_size.min(expected.max(1)));
+        let mut loaded = 0;
+        let mut last_report = 0;
+
+        for edge in edges {
+            buffer.push(edge?);
+
+            let at_report = options.progress.is_some()
+                && (loaded + buffer.len()) % options.progress_interval == 0;
+            if buffer.len() >= options.chunk_size || at_report {
+                loaded += buffer.len();
+                graph.insert_chunk(&mut buffer, options.undirected);
+            }
+            if at_report {
+                last_report = loaded;
+                graph.report(&mut options, loaded);
+            }
+        }
+
+        loaded += buffer.len();
+        graph.insert_chunk(&mut buffer, options.undirected);
+        if loaded != last_report {
+            graph.report(&mut options, loaded);
+        }
+        Ok(graph)
+    }
+
+    fn insert_chunk(&mut self, buffer: &mut Vec<(IDDataType, IDDataType)>, undirected: bool) {
+        if undirected {
+            self.add_edges(buffer);
+        } else {
+            self.add_directed_edges(buffer);
+        }
+        buffer.clear();
+    }
+
+    fn report(&self, options: &mut StreamOptions<'_>, edges: usize) {
+        if let Some(progress) = options.progress.as_mut() {
+            progress(LoadProgress {
+                edges,
+                nodes: self.nodes.len(),
+            });
+        }
+    }
+}
+
+/// An error in the text given to `Graph::parse`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// The byte offset of the error within the line, starting at 1.
+    pub column: usize,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// a token of the graph DSL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Node(&'a str),
+    Directed,
+    Undirected,
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + FromStr,
+{
+    /// Builds a graph from a small textual language, the runtime counterpart of the `graph!` macro.
+    ///
+    /// Statements are separated by `;` or line breaks and `#` starts a comment. `a -> b` adds a
+    /// directed edge and `a -- b` an edge in both directions; edges can be chained into paths
+    /// like `a -> b -- c`, and a node on its own is added without edges. Node IDs are parsed with
+    /// `FromStr` and nodes get the default data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1; 1 -> 2; 3 -- 4").unwrap();
+    /// assert_eq!(g.nodes, vec![0, 1, 2, 3, 4]);
+    /// assert_eq!(g.edge_tuples().len(), 4);
+    ///
+    /// let error = Graph::<u32, ()>::parse("0 -> 1\n1 -> two").unwrap_err();
+    /// assert_eq!(error.to_string(), "2:6: `two` is not a valid node ID");
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut graph = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            let content = line.split('#').next().unwrap_or_default();
+            let mut offset = 0;
+            for statement in content.split(';') {
+                graph.parse_statement(statement, number + 1, offset)?;
+                offset += statement.len() + 1;
+            }
+        }
+        Ok(graph)
+    }
+
+    /// parses one statement that starts at byte `offset` of line `line`.
+    fn parse_statement(
+        &mut self,
+        statement: &str,
+        line: usize,
+        offset: usize,
+    ) -> Result<(), ParseError> {
+        let error = |at: usize, message: String| ParseError {
+            line,
+            column: offset + at + 1,
+            message,
+        };
+        let tokens = tokenize(statement);
+        let mut previous: Option<IDDataType> = None;
+        let mut operator: Option<(usize, Token<'_>)> = None;
+        for (at, token) in tokens {
+            match (token, operator) {
+                (Token::Node(text), _) => {
+                    if previous.is_some() && operator.is_none() {
+                        return Err(error(at, format!("expected `->` or `--` before `{text}`")));
+                    }
+                    let id: IDDataType = text
+                        .parse()
+                        .map_err(|_| error(at, format!("`{text}` is not a valid node ID")))?;
+                    match (previous, operator.take()) {
+                        (Some(from), Some((_, Token::Directed))) => {
+                            self.add_directed_edge(from, id)
+                        }
+                        (Some(from), Some((_, Token::Undirected))) => self.add_edge(from, id),
+                        _ if !self.node_data.contains_key(&id) => self.add_node(id),
+                        _ => {}
+                    }
+                    previous = Some(id);
+                }
+                (_, None) if previous.is_some() => operator = Some((at, token)),
+                (_, _) => {
+                    let symbol = if token == Token::Directed { "->" } else { "--" };
+                    return Err(error(at, format!("expected a node ID before `{symbol}`")));
+                }
+            }
+        }
+        match operator {
+            Some((at, _)) => Err(error(at, String::from("expected a node ID after the edge"))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// splits a statement into node IDs and the edge operators `->` and `--`.
+fn tokenize(statement: &str) -> Vec<(usize, Token<'_>)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut rest = statement.char_indices().peekable();
+    while let Some((at, c)) = rest.next() {
+        let operator = match (c, rest.peek()) {
+            ('-', Some((_, '>'))) => Some(Token::Directed),
+            ('-', Some((_, '-'))) => Some(Token::Undirected),
+            _ => None,
+        };
+        if operator.is_some() || c.is_whitespace() {
+            if let Some(begin) = start.take() {
+                tokens.push((begin, Token::Node(&statement[begin..at])));
+            }
+        }
+        if let Some(operator) = operator {
+            rest.next();
+            tokens.push((at, operator));
+        } else if !c.is_whitespace() && start.is_none() {
+            start = Some(at);
+        }
+    }
+    if let Some(begin) = start {
+        tokens.push((begin, Token::Node(&statement[begin..])));
+    }
+    tokens
+}
+
+/// Edge data that can be read from and written to the weight column of an edge list, see
+/// `Graph::from_edge_list_reader`.
+pub trait EdgeWeight: Sized {
+    /// Whether the weight column is stored as edge data; if not, it is read but ignored.
+    const WEIGHTED: bool = true;
+    /// Parses the text of the weight column, or returns `None` if it is not a valid weight.
+    fn parse_weight(text: &str) -> Option<Self>;
+    /// The text of the weight column, or `None` to write the edge without a weight.
+    fn format_weight(&self) -> Option<String>;
+}
+
+impl EdgeWeight for () {
+    const WEIGHTED: bool = false;
+    fn parse_weight(_: &str) -> Option<Self> {
+        Some(())
+    }
+    fn format_weight(&self) -> Option<String> {
+        None
+    }
+}
+
+macro_rules! impl_edge_weight {
+    ($($t:ty),*) => {
+        $(
+            impl EdgeWeight for $t {
+                fn parse_weight(text: &str) -> Option<Self> {
+                    text.parse().ok()
+                }
+                fn format_weight(&self) -> Option<String> {
+                    Some(alloc::string::ToString::to_string(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_edge_weight!(f32, f64, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String);
+
+/// An error while reading an edge list with `Graph::from_edge_list_reader`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum EdgeListError {
+    /// Reading from the source failed.
+    Io(std::io::Error),
+    /// A row is not of the form `from to [weight]`.
+    Parse(ParseError),
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for EdgeListError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EdgeListError::Io(error) => write!(f, "{error}"),
+            EdgeListError::Parse(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::error::Error for EdgeListError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            EdgeListError::Io(error) => Some(error),
+            EdgeListError::Parse(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for EdgeListError {
+    fn from(error: std::io::Error) -> Self {
+        EdgeListError::Io(error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + FromStr,
+    EdgeDataType: EdgeWeight,
+{
+    /// Reads a directed graph from an edge list with one `from to [weight]` row per line, such
+    /// as the SNAP datasets or a CSV or TSV export.
+    ///
+    /// The fields of a row are separated by whitespace, tabs or commas, empty lines are skipped
+    /// and `#` starts a comment. Node IDs are parsed with `FromStr` and nodes get the default
+    /// data. The optional weight is parsed with [`EdgeWeight`] and stored as the edge data; graphs
+    /// without edge data ignore it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let text = "# from to weight\n0,1,0.5\n1\t2\n";
+    /// let g = Graph::<u32, (), f64>::from_edge_list_reader(text.as_bytes()).unwrap();
+    /// assert_eq!(g.nodes, vec![0, 1, 2]);
+    /// assert_eq!(g.neighbors(1), &[2]);
+    /// assert_eq!(g.edge_data(0, 1), Some(&0.5));
+    /// assert_eq!(g.edge_data(1, 2), None);
+    ///
+    /// let error = Graph::<u32, ()>::from_edge_list_reader("0 1\n1 x".as_bytes()).unwrap_err();
+    /// assert_eq!(error.to_string(), "2:3: `x` is not a valid node ID");
+    /// ```
+    pub fn from_edge_list_reader(reader: impl std::io::BufRead) -> Result<Self, EdgeListError> {
+        let mut graph = Self::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let content = line.split('#').next().unwrap_or_default();
+            let fields: Vec<&str> = content
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|field| !field.is_empty())
+                .collect();
+            let error = |field: &str, message: String| {
+                EdgeListError::Parse(ParseError {
+                    line: number + 1,
+                    column: field.as_ptr() as usize - content.as_ptr() as usize + 1,
+                    message,
+                })
+            };
+            let id = |field: &str| {
+                field
+                    .parse::<IDDataType>()
+                    .map_err(|_| error(field, format!("`{field}` is not a valid node ID")))
+            };
+            match fields[..] {
+                [] => {}
+                [from, to] => graph.add_directed_edge(id(from)?, id(to)?),
+                [from, to, weight] => {
+                    let (from, to) = (id(from)?, id(to)?);
+                    let data = EdgeDataType::parse_weight(weight).ok_or_else(|| {
+                        error(weight, format!("`{weight}` is not a valid weight"))
+                    })?;
+                    if EdgeDataType::WEIGHTED {
+                        graph.add_directed_edge_with_data(from, to, data);
+                    } else {
+                        graph.add_directed_edge(from, to);
+                    }
+                }
+                [.., extra] => {
+                    return Err(error(extra, String::from("expected `from to [weight]`")));
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + core::fmt::Display,
+    EdgeDataType: EdgeWeight,
+{
+    /// Writes the edges as an edge list that `from_edge_list_reader` reads back: one tab
+    /// separated `from to` row per edge, in the order of `nodes` and of the neighbor lists,
+    /// followed by the weight if the edge has data. Nodes without edges are not written.
+    pub fn write_edge_list(&self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        for from in &self.nodes {
+            for to in self.neighbors(*from) {
+                match self
+                    .edge_data(*from, *to)
+                    .and_then(EdgeWeight::format_weight)
+                {
+                    Some(weight) => writeln!(writer, "{from}\t{to}\t{weight}")?,
+                    None => writeln!(writer, "{from}\t{to}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
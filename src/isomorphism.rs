@@ -0,0 +1,460 @@
+//! Graph and subgraph isomorphism with the VF2 algorithm of Cordella, Foggia, Sansone and
+//! Vento, for comparing structures and finding a pattern in a larger graph, such as a functional
+//! group in a molecule.
+//!
+//! Edges are compared as a set of directed `(from, to)` pairs, so a parallel edge counts as
+//! present if any copy of it is, and edge data is ignored. Node data is ignored too unless a
+//! compatibility predicate is given with the `_by` variants.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Whether the two graphs are isomorphic: whether there is a bijection between their nodes
+    /// that maps every edge of one graph to an edge of the other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let square = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3 -- 0").unwrap();
+    /// let relabeled = Graph::<char, ()>::parse("a -- c -- b -- d -- a").unwrap();
+    /// let path = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3").unwrap();
+    /// assert!(square.is_isomorphic_to(&relabeled));
+    /// assert!(!square.is_isomorphic_to(&path));
+    /// ```
+    pub fn is_isomorphic_to<OtherID, OtherNodeData, OtherEdgeData>(
+        &self,
+        other: &Graph<OtherID, OtherNodeData, OtherEdgeData>,
+    ) -> bool
+    where
+        OtherID: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        self.is_isomorphic_to_by(other, |_, _| true)
+    }
+
+    /// Whether the two graphs are isomorphic with a bijection that only maps a node of this graph
+    /// to a node of `other` if `node_match` returns `true` for their data, in that order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut water = Graph::<u32, char>::new();
+    /// water.add_node_with_data(0, 'O');
+    /// water.add_node_with_data(1, 'H');
+    /// water.add_node_with_data(2, 'H');
+    /// water.add_edges(&[(0, 1), (0, 2)]);
+    /// let mut other = water.clone();
+    /// other.node_data.insert(0, 'H');
+    /// other.node_data.insert(1, 'O');
+    ///
+    /// assert!(water.is_isomorphic_to(&other));
+    /// assert!(!water.is_isomorphic_to_by(&other, |a, b| a == b));
+    /// ```
+    pub fn is_isomorphic_to_by<OtherID, OtherNodeData, OtherEdgeData>(
+        &self,
+        other: &Graph<OtherID, OtherNodeData, OtherEdgeData>,
+        mut node_match: impl FnMut(&NodeDataType, &OtherNodeData) -> bool,
+    ) -> bool
+    where
+        OtherID: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let (target, pattern) = (IndexedGraph::new(self), IndexedGraph::new(other));
+        if target.nodes.len() != pattern.nodes.len() || target.edges != pattern.edges {
+            return false;
+        }
+        if target.degree_sequence() != pattern.degree_sequence() {
+            return false;
+        }
+        let mut compatible = |t: usize, p: usize| {
+            node_match(
+                &self.node_data[&target.nodes[t]],
+                &other.node_data[&pattern.nodes[p]],
+            )
+        };
+        let mut found = false;
+        Vf2::new(&target, &pattern, Mode::Isomorphism, &mut compatible).search(&mut |_| {
+            found = true;
+            false
+        });
+        found
+    }
+
+    /// Every way `pattern` occurs as an induced subgraph of this graph: the maps from the pattern
+    /// nodes to distinct nodes of this graph such that two pattern nodes are linked exactly if
+    /// the nodes they are mapped to are. A pattern with an automorphism, like a ring that can
+    /// be rotated, is found once per automorphism. Use `rewrite::Rule::matches` to also find
+    /// occurrences with additional edges between the matched nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 3 -> 4").unwrap();
+    /// let chain = Graph::<char, ()>::parse("a -> b -> c").unwrap();
+    /// let found = g.find_subgraph_isomorphisms(&chain);
+    /// // the chains inside the triangle have an extra edge, so only 1 -> 2 -> 3 and 2 -> 3 -> 4
+    /// let mut middles: Vec<u32> = found.iter().map(|m| m[&'b']).collect();
+    /// middles.sort();
+    /// assert_eq!(middles, vec![2, 3]);
+    /// ```
+    pub fn find_subgraph_isomorphisms<PatternID, PatternNodeData, PatternEdgeData>(
+        &self,
+        pattern: &Graph<PatternID, PatternNodeData, PatternEdgeData>,
+    ) -> Vec<NodeMap<PatternID, IDDataType>>
+    where
+        PatternID: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        self.find_subgraph_isomorphisms_by(pattern, |_, _| true)
+    }
+
+    /// Like `find_subgraph_isomorphisms`, but only maps a pattern node to a node of this graph if
+    /// `node_match` returns `true` for the data of the node of this graph and of the pattern
+    /// node, in that order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // ethanol, C-C-O with the hydrogens left out
+    /// let mut molecule = Graph::<u32, char>::new();
+    /// for (id, element) in [(0, 'C'), (1, 'C'), (2, 'O')] {
+    ///     molecule.add_node_with_data(id, element);
+    /// }
+    /// molecule.add_edges(&[(0, 1), (1, 2)]);
+    /// // a hydroxyl group bound to a carbon
+    /// let mut group = Graph::<u32, char>::new();
+    /// group.add_node_with_data(0, 'C');
+    /// group.add_node_with_data(1, 'O');
+    /// group.add_edge(0, 1);
+    ///
+    /// let found = molecule.find_subgraph_isomorphisms_by(&group, |a, b| a == b);
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0][&1], 2);
+    /// ```
+    pub fn find_subgraph_isomorphisms_by<PatternID, PatternNodeData, PatternEdgeData>(
+        &self,
+        pattern: &Graph<PatternID, PatternNodeData, PatternEdgeData>,
+        mut node_match: impl FnMut(&NodeDataType, &PatternNodeData) -> bool,
+    ) -> Vec<NodeMap<PatternID, IDDataType>>
+    where
+        PatternID: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let (indexed_target, indexed_pattern) =
+            (IndexedGraph::new(self), IndexedGraph::new(pattern));
+        let mut compatible = |t: usize, p: usize| {
+            node_match(
+                &self.node_data[&indexed_target.nodes[t]],
+                &pattern.node_data[&indexed_pattern.nodes[p]],
+            )
+        };
+        let mut found = Vec::new();
+        Vf2::new(
+            &indexed_target,
+            &indexed_pattern,
+            Mode::Subgraph,
+            &mut compatible,
+        )
+        .search(&mut |core: &[usize]| {
+            found.push(
+                core.iter()
+                    .enumerate()
+                    .map(|(p, t)| (indexed_pattern.nodes[p], indexed_target.nodes[*t]))
+                    .collect(),
+            );
+            true
+        });
+        found
+    }
+}
+
+/// a graph over the dense indices of its nodes, with sorted and deduplicated neighbor lists.
+struct IndexedGraph<Id> {
+    nodes: Vec<Id>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+    /// the number of distinct directed edges.
+    edges: usize,
+}
+
+impl<Id: Debug + PartialEq + Eq + Hash + Clone + Copy> IndexedGraph<Id> {
+    fn new<NodeDataType, EdgeDataType>(graph: &Graph<Id, NodeDataType, EdgeDataType>) -> Self {
+        let index: NodeMap<Id, usize> = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let indices = |ids: &[Id]| {
+            let mut indices: Vec<usize> =
+                ids.iter().filter_map(|id| index.get(id).copied()).collect();
+            indices.sort_unstable();
+            indices.dedup();
+            indices
+        };
+        let successors: Vec<Vec<usize>> = graph
+            .nodes
+            .iter()
+            .map(|id| indices(graph.neighbors(*id)))
+            .collect();
+        let predecessors = graph
+            .nodes
+            .iter()
+            .map(|id| indices(graph.reverse_neighbors(*id)))
+            .collect();
+        Self {
+            nodes: graph.nodes.clone(),
+            edges: successors.iter().map(Vec::len).sum(),
+            successors,
+            predecessors,
+        }
+    }
+
+    /// the sorted pairs of out- and in-degree of the nodes, which isomorphic graphs share.
+    fn degree_sequence(&self) -> Vec<(usize, usize)> {
+        let mut degrees: Vec<(usize, usize)> = self
+            .successors
+            .iter()
+            .zip(&self.predecessors)
+            .map(|(successors, predecessors)| (successors.len(), predecessors.len()))
+            .collect();
+        degrees.sort_unstable();
+        degrees
+    }
+
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.successors[from].binary_search(&to).is_ok()
+    }
+}
+
+/// whether the whole target graph has to be matched or only a part of it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Isomorphism,
+    Subgraph,
+}
+
+/// marks a node that is not matched yet.
+const UNMATCHED: usize = usize::MAX;
+
+/// The VF2 search state, matching the pattern nodes to target nodes one pair at a time.
+///
+/// Besides the partial matching, every node remembers the depth at which it entered the "in" or
+/// "out" terminal set of its graph, the unmatched nodes with an edge from or to a matched one;
+/// zero means it is in neither. Candidate pairs are drawn from the terminal sets, and the sizes
+/// of a candidate's neighborhoods in them prune matchings that cannot be completed.
+struct Vf2<'a, T, P> {
+    target: &'a IndexedGraph<T>,
+    pattern: &'a IndexedGraph<P>,
+    mode: Mode,
+    compatible: &'a mut dyn FnMut(usize, usize) -> bool,
+    /// the pattern node of every target node and the target node of every pattern node.
+    core_target: Vec<usize>,
+    core_pattern: Vec<usize>,
+    in_target: Vec<usize>,
+    out_target: Vec<usize>,
+    in_pattern: Vec<usize>,
+    out_pattern: Vec<usize>,
+    depth: usize,
+}
+
+impl<'a, T, P> Vf2<'a, T, P>
+where
+    T: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    P: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn new(
+        target: &'a IndexedGraph<T>,
+        pattern: &'a IndexedGraph<P>,
+        mode: Mode,
+        compatible: &'a mut dyn FnMut(usize, usize) -> bool,
+    ) -> Self {
+        let (n, m) = (target.nodes.len(), pattern.nodes.len());
+        Self {
+            target,
+            pattern,
+            mode,
+            compatible,
+            core_target: vec![UNMATCHED; n],
+            core_pattern: vec![UNMATCHED; m],
+            in_target: vec![0; n],
+            out_target: vec![0; n],
+            in_pattern: vec![0; m],
+            out_pattern: vec![0; m],
+            depth: 0,
+        }
+    }
+
+    /// calls `visit` with the target node of every pattern node for every complete matching,
+    /// until it returns `false`; returns `false` once it did.
+    fn search(&mut self, visit: &mut dyn FnMut(&[usize]) -> bool) -> bool {
+        if self.depth == self.pattern.nodes.len() {
+            return visit(&self.core_pattern);
+        }
+        let Some((candidates, p)) = self.candidates() else {
+            return true;
+        };
+        for t in candidates {
+            if !self.feasible(t, p) {
+                continue;
+            }
+            self.push(t, p);
+            let go_on = self.search(visit);
+            self.pop(t, p);
+            if !go_on {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// the target nodes to try for the next pattern node: the first unmatched pattern node in
+    /// the out terminal set with the unmatched target nodes in it if both sets have some, else
+    /// the same for the in terminal sets, else the first unmatched pattern node with all unmatched
+    /// target nodes.
+    fn candidates(&self) -> Option<(Vec<usize>, usize)> {
+        let unmatched_target = |set: &[usize]| -> Vec<usize> {
+            (0..set.len())
+                .filter(|t| set[*t] != 0 && self.core_target[*t] == UNMATCHED)
+                .collect()
+        };
+        let first_unmatched_pattern = |set: &[usize]| {
+            (0..set.len()).find(|p| set[*p] != 0 && self.core_pattern[*p] == UNMATCHED)
+        };
+        for (target_set, pattern_set) in [
+            (&self.out_target, &self.out_pattern),
+            (&self.in_target, &self.in_pattern),
+        ] {
+            let targets = unmatched_target(target_set);
+            if let (false, Some(p)) = (targets.is_empty(), first_unmatched_pattern(pattern_set)) {
+                return Some((targets, p));
+            }
+        }
+        let p = (0..self.pattern.nodes.len()).find(|p| self.core_pattern[*p] == UNMATCHED)?;
+        let all = (0..self.target.nodes.len())
+            .filter(|t| self.core_target[*t] == UNMATCHED)
+            .collect();
+        Some((all, p))
+    }
+
+    /// whether the pair can extend the matching: the edges to matched nodes correspond, and the
+    /// target node has at least as many (exactly as many for an isomorphism) neighbors in each
+    /// terminal set and outside of them as the pattern node.
+    fn feasible(&mut self, t: usize, p: usize) -> bool {
+        let (target, pattern) = (self.target, self.pattern);
+        if target.has_edge(t, t) != pattern.has_edge(p, p) {
+            return false;
+        }
+        for (target_neighbors, pattern_neighbors, outgoing) in [
+            (&target.successors[t], &pattern.successors[p], true),
+            (&target.predecessors[t], &pattern.predecessors[p], false),
+        ] {
+            let linked = |a: usize, b: usize| if outgoing { (a, b) } else { (b, a) };
+            for &other in target_neighbors {
+                let matched = self.core_target[other];
+                if other != t && matched != UNMATCHED {
+                    let (from, to) = linked(p, matched);
+                    if !pattern.has_edge(from, to) {
+                        return false;
+                    }
+                }
+            }
+            for &other in pattern_neighbors {
+                let matched = self.core_pattern[other];
+                if other != p && matched != UNMATCHED {
+                    let (from, to) = linked(t, matched);
+                    if !target.has_edge(from, to) {
+                        return false;
+                    }
+                }
+            }
+            let target_counts = self.terminal_counts(target_neighbors, true);
+            let pattern_counts = self.terminal_counts(pattern_neighbors, false);
+            let fits = match self.mode {
+                Mode::Isomorphism => target_counts == pattern_counts,
+                Mode::Subgraph => target_counts
+                    .iter()
+                    .zip(&pattern_counts)
+                    .all(|(a, b)| a >= b),
+            };
+            if !fits {
+                return false;
+            }
+        }
+        (self.compatible)(t, p)
+    }
+
+    /// how many of the unmatched `nodes` are in the in terminal set, in the out terminal set and
+    /// in neither.
+    fn terminal_counts(&self, nodes: &[usize], in_target: bool) -> [usize; 3] {
+        let (core, ins, outs) = if in_target {
+            (&self.core_target, &self.in_target, &self.out_target)
+        } else {
+            (&self.core_pattern, &self.in_pattern, &self.out_pattern)
+        };
+        let mut counts = [0; 3];
+        for &node in nodes.iter().filter(|node| core[**node] == UNMATCHED) {
+            if ins[node] != 0 {
+                counts[0] += 1;
+            }
+            if outs[node] != 0 {
+                counts[1] += 1;
+            }
+            if ins[node] == 0 && outs[node] == 0 {
+                counts[2] += 1;
+            }
+        }
+        counts
+    }
+
+    /// matches `t` with `p` and adds their neighbors to the terminal sets.
+    fn push(&mut self, t: usize, p: usize) {
+        self.depth += 1;
+        let depth = self.depth;
+        self.core_target[t] = p;
+        self.core_pattern[p] = t;
+        let mark = |set: &mut [usize], nodes: &[usize]| {
+            for node in nodes {
+                if set[*node] == 0 {
+                    set[*node] = depth;
+                }
+            }
+        };
+        mark(&mut self.in_target, &[t]);
+        mark(&mut self.out_target, &[t]);
+        mark(&mut self.in_pattern, &[p]);
+        mark(&mut self.out_pattern, &[p]);
+        mark(&mut self.in_target, &self.target.predecessors[t]);
+        mark(&mut self.out_target, &self.target.successors[t]);
+        mark(&mut self.in_pattern, &self.pattern.predecessors[p]);
+        mark(&mut self.out_pattern, &self.pattern.successors[p]);
+    }
+
+    /// undoes the `push` of `t` and `p`.
+    fn pop(&mut self, t: usize, p: usize) {
+        let depth = self.depth;
+        for set in [
+            &mut self.in_target,
+            &mut self.out_target,
+            &mut self.in_pattern,
+            &mut self.out_pattern,
+        ] {
+            set.iter_mut()
+                .filter(|entered| **entered == depth)
+                .for_each(|entered| *entered = 0);
+        }
+        self.core_target[t] = UNMATCHED;
+        self.core_pattern[p] = UNMATCHED;
+        self.depth -= 1;
+    }
+}
@@ -0,0 +1,843 @@
+//! Computing 2D positions for the nodes of a graph, e.g. for drawing it.
+
+mod quadtree;
+
+use core::hash::Hash;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::fmt::Debug;
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    spectral::Laplacian,
+    Graph,
+};
+use quadtree::QuadTree;
+
+/// The position of every node.
+pub type Layout<IDDataType> = NodeMap<IDDataType, (f64, f64)>;
+
+/// Settings for force-directed layouts.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_cycle_graph, layout::{fruchterman_reingold, ForceConfig}};
+///
+/// let g = generate_cycle_graph::<()>(12);
+/// let positions = fruchterman_reingold(&g, &ForceConfig::new().iterations(200).seed(1));
+///
+/// assert_eq!(positions.len(), 12);
+/// assert!(positions.values().all(|(x, y)| (0.0..=1.0).contains(x) && (0.0..=1.0).contains(y)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForceConfig {
+    iterations: usize,
+    width: f64,
+    height: f64,
+    seed: u64,
+    theta: f64,
+    gravity: f64,
+}
+
+impl ForceConfig {
+    /// 100 iterations in the unit square, with seed 0 for the initial positions.
+    /// Barnes-Hut layouts use `theta = 1.2` and `gravity = 1.0`.
+    pub fn new() -> Self {
+        Self {
+            iterations: 100,
+            width: 1.0,
+            height: 1.0,
+            seed: 0,
+            theta: 1.2,
+            gravity: 1.0,
+        }
+    }
+
+    /// The number of simulation steps.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// The size of the frame; positions are kept within `[0, width] x [0, height]`.
+    pub fn size(mut self, width: f64, height: f64) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// The seed of the random initial positions. The same seed always gives the same layout.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The Barnes-Hut accuracy: a group of nodes is approximated by its center of mass when its
+    /// size is less than `theta` times its distance. Smaller is more accurate, `0.0` is exact.
+    pub fn theta(mut self, theta: f64) -> Self {
+        self.theta = theta;
+        self
+    }
+
+    /// The strength of the pull towards the center that keeps disconnected components together.
+    pub fn gravity(mut self, gravity: f64) -> Self {
+        self.gravity = gravity;
+        self
+    }
+}
+
+impl Default for ForceConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Positions the nodes with the force-directed algorithm of Fruchterman and Reingold.
+///
+/// Every pair of nodes repels each other and every edge pulls its endpoints together, so
+/// connected nodes end up close and the layout spreads out evenly. Edge direction is ignored.
+/// Each iteration takes O(n² + e) time, which is fine for graphs with up to a few thousand nodes.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to lay out.
+/// * `config` - The number of iterations, frame size and seed.
+///
+pub fn fruchterman_reingold<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    config: &ForceConfig,
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fruchterman_reingold_from(graph, config, &Layout::default())
+}
+
+/// Like `fruchterman_reingold`, but starts from the given positions, clamped to the frame, instead
+/// of random ones. Nodes without a position start at a random one, so a previous layout can be
+/// refined after nodes were added.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_path_graph, layout::{fruchterman_reingold_from, ForceConfig, Layout}};
+///
+/// let g = generate_path_graph::<()>(3);
+/// let start: Layout<usize> = [(0, (0.0, 0.5)), (1, (0.5, 0.5)), (2, (1.0, 0.5))].into_iter().collect();
+/// let positions = fruchterman_reingold_from(&g, &ForceConfig::new().iterations(0), &start);
+/// assert_eq!(positions, start);
+/// ```
+pub fn fruchterman_reingold_from<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    config: &ForceConfig,
+    initial: &Layout<IDDataType>,
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let (ids, pairs) = undirected_pairs(graph);
+    let n = ids.len();
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut positions: Vec<(f64, f64)> = ids
+        .iter()
+        .map(|id| {
+            let random = (
+                rng.gen::<f64>() * config.width,
+                rng.gen::<f64>() * config.height,
+            );
+            match initial.get(id) {
+                Some(&(x, y)) => (x.clamp(0.0, config.width), y.clamp(0.0, config.height)),
+                None => random,
+            }
+        })
+        .collect();
+    if n < 2 {
+        return ids.into_iter().zip(positions).collect();
+    }
+
+    // the ideal distance between nodes, so that they fill the frame
+    let k = (config.width * config.height / n as f64).sqrt();
+    let initial_temperature = config.width.max(config.height) / 10.0;
+    let mut displacement = vec![(0.0, 0.0); n];
+    for iteration in 0..config.iterations {
+        displacement.iter_mut().for_each(|d| *d = (0.0, 0.0));
+        for i in 0..n {
+            for j in i + 1..n {
+                let (dx, dy, distance) = offset(positions[i], positions[j]);
+                let force = k * k / distance;
+                displacement[i].0 += dx / distance * force;
+                displacement[i].1 += dy / distance * force;
+                displacement[j].0 -= dx / distance * force;
+                displacement[j].1 -= dy / distance * force;
+            }
+        }
+        for &(i, j) in &pairs {
+            let (dx, dy, distance) = offset(positions[i], positions[j]);
+            let force = distance * distance / k;
+            displacement[i].0 -= dx / distance * force;
+            displacement[i].1 -= dy / distance * force;
+            displacement[j].0 += dx / distance * force;
+            displacement[j].1 += dy / distance * force;
+        }
+
+        // the temperature limits how far a node may move and cools down linearly
+        let temperature = initial_temperature * (1.0 - iteration as f64 / config.iterations as f64);
+        for (position, (dx, dy)) in positions.iter_mut().zip(&displacement) {
+            let length = (dx * dx + dy * dy).sqrt();
+            if length > 0.0 {
+                let step = length.min(temperature);
+                position.0 = (position.0 + dx / length * step).clamp(0.0, config.width);
+                position.1 = (position.1 + dy / length * step).clamp(0.0, config.height);
+            }
+        }
+    }
+
+    ids.into_iter().zip(positions).collect()
+}
+
+/// Positions the nodes with `fruchterman_reingold` and the default `ForceConfig`, for a quick look
+/// at a graph.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_wheel_graph, layout::spring_layout};
+///
+/// let positions = spring_layout(&generate_wheel_graph::<()>(8));
+/// assert_eq!(positions.len(), 8);
+/// ```
+pub fn spring_layout<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fruchterman_reingold(graph, &ForceConfig::new())
+}
+
+/// Positions a graph with grid coordinates as IDs with `fruchterman_reingold_from`, starting from
+/// the grid itself scaled to the frame rather than from random positions. Grids that were rewired,
+/// e.g. by `perturb`, then relax from their original shape instead of untangling from scratch.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_grid_graph, layout::{grid_spring_layout, ForceConfig}};
+///
+/// let g = generate_grid_graph::<()>(4, 4);
+/// let positions = grid_spring_layout(&g, &ForceConfig::new().iterations(0));
+/// assert_eq!(positions[&(0, 0)], (0.0, 0.0));
+/// assert_eq!(positions[&(3, 3)], (1.0, 1.0));
+/// ```
+pub fn grid_spring_layout<NodeDataType>(
+    graph: &Graph<(usize, usize), NodeDataType>,
+    config: &ForceConfig,
+) -> Layout<(usize, usize)> {
+    let columns = graph.nodes.iter().map(|id| id.0).max().unwrap_or(0).max(1) as f64;
+    let rows = graph.nodes.iter().map(|id| id.1).max().unwrap_or(0).max(1) as f64;
+    let initial = graph
+        .nodes
+        .iter()
+        .map(|&(x, y)| {
+            let position = (
+                x as f64 / columns * config.width,
+                y as f64 / rows * config.height,
+            );
+            ((x, y), position)
+        })
+        .collect();
+    fruchterman_reingold_from(graph, config, &initial)
+}
+
+/// Positions the nodes with a ForceAtlas2-style algorithm, using a Barnes-Hut quadtree for the repulsion.
+///
+/// Nodes repel each other in proportion to their degrees, edges pull their endpoints together
+/// linearly with distance, and a gravity proportional to the degree keeps components near the center.
+/// The repulsion of distant groups of nodes is approximated by their center of mass (see
+/// `ForceConfig::theta`), so each iteration takes O(n log n + e) time instead of O(n²), which makes
+/// the layout practical for hundreds of thousands of nodes. The step size adapts to how much the nodes
+/// oscillate, as in ForceAtlas2. Edge direction is ignored, and the final positions are scaled to fill
+/// the configured frame.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to lay out.
+/// * `config` - The number of iterations, frame size, seed, `theta` and `gravity`.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_grid_graph, layout::{force_atlas2, ForceConfig}};
+///
+/// let g = generate_grid_graph::<()>(30, 30);
+/// let positions = force_atlas2(&g, &ForceConfig::new().size(800.0, 600.0));
+///
+/// assert_eq!(positions.len(), 900);
+/// assert!(positions.values().all(|(x, y)| (0.0..=800.0).contains(x) && (0.0..=600.0).contains(y)));
+/// ```
+pub fn force_atlas2<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    config: &ForceConfig,
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    const REPULSION: f64 = 2.0;
+
+    let (ids, pairs) = undirected_pairs(graph);
+    let n = ids.len();
+    let mut masses = vec![1.0; n];
+    for &(i, j) in &pairs {
+        masses[i] += 1.0;
+        masses[j] += 1.0;
+    }
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let spread = (n as f64).sqrt() * 10.0;
+    let mut positions: Vec<(f64, f64)> = (0..n)
+        .map(|_| (rng.gen::<f64>() * spread, rng.gen::<f64>() * spread))
+        .collect();
+
+    let mut forces = vec![(0.0, 0.0); n];
+    let mut previous = vec![(0.0, 0.0); n];
+    let mut speed = 1.0;
+    for _ in 0..config.iterations {
+        if n < 2 {
+            break;
+        }
+        std::mem::swap(&mut forces, &mut previous);
+        forces.iter_mut().for_each(|f| *f = (0.0, 0.0));
+
+        let tree = QuadTree::new(&positions, &masses);
+        for (i, force) in forces.iter_mut().enumerate() {
+            tree.visit(i, positions[i], config.theta, &mut |center, mass| {
+                let (dx, dy, distance) = offset(positions[i], center);
+                let strength = REPULSION * masses[i] * mass / (distance * distance);
+                force.0 += dx * strength;
+                force.1 += dy * strength;
+            });
+            // gravity towards the origin, independent of the distance
+            let (x, y) = positions[i];
+            let distance = (x * x + y * y).sqrt();
+            if distance > 0.0 {
+                force.0 -= x / distance * config.gravity * masses[i];
+                force.1 -= y / distance * config.gravity * masses[i];
+            }
+        }
+        for &(i, j) in &pairs {
+            let (dx, dy) = (
+                positions[i].0 - positions[j].0,
+                positions[i].1 - positions[j].1,
+            );
+            forces[i].0 -= dx;
+            forces[i].1 -= dy;
+            forces[j].0 += dx;
+            forces[j].1 += dy;
+        }
+
+        // adapt the global speed to the ratio of useful movement (traction) to oscillation (swing)
+        let mut swings = vec![0.0; n];
+        let (mut swing, mut traction) = (0.0, 0.0);
+        for i in 0..n {
+            let (f, p) = (forces[i], previous[i]);
+            swings[i] = ((f.0 - p.0).powi(2) + (f.1 - p.1).powi(2)).sqrt();
+            swing += masses[i] * swings[i];
+            traction += masses[i] * ((f.0 + p.0).powi(2) + (f.1 + p.1).powi(2)).sqrt() / 2.0;
+        }
+        if swing > 0.0 {
+            speed = (traction / swing).min(1.5 * speed);
+        }
+        for i in 0..n {
+            let factor = speed / (1.0 + (speed * swings[i]).sqrt());
+            let (fx, fy) = forces[i];
+            // never move a node further than ten times its ideal spacing in one step
+            let length = (fx * fx + fy * fy).sqrt();
+            let factor = factor.min(10.0 / length.max(1e-12));
+            positions[i].0 += fx * factor;
+            positions[i].1 += fy * factor;
+        }
+    }
+
+    ids.into_iter()
+        .zip(fit(&positions, config.width, config.height))
+        .collect()
+}
+
+/// Positions the nodes by the eigenvectors of the second and third smallest eigenvalues of the
+/// graph Laplacian.
+///
+/// The result is deterministic and reveals global structure: cycles become circles, grids become
+/// grids and weakly connected clusters are pulled apart. The eigenvectors are found by power iteration,
+/// which needs more iterations for large graphs with a small spectral gap. Edge direction is ignored.
+/// Disconnected graphs collapse each component towards a point, so lay out components separately.
+/// Positions are scaled to fill the unit square.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_cycle_graph, layout::spectral};
+///
+/// let g = generate_cycle_graph::<()>(8);
+/// let positions = spectral(&g);
+///
+/// // all nodes lie on a circle around their mean position
+/// let (cx, cy) = positions.values().fold((0.0, 0.0), |c, p| (c.0 + p.0 / 8.0, c.1 + p.1 / 8.0));
+/// let radius = |(x, y): (f64, f64)| ((x - cx).powi(2) + (y - cy).powi(2)).sqrt();
+/// let r = radius(positions[&0]);
+/// assert!(positions.values().all(|p| (radius(*p) - r).abs() < 1e-6));
+/// ```
+pub fn spectral<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let (ids, pairs) = undirected_pairs(graph);
+    let mut neighbors = vec![Vec::new(); ids.len()];
+    for &(i, j) in &pairs {
+        neighbors[i].push(j);
+        neighbors[j].push(i);
+    }
+    let pairs = Laplacian::new(&neighbors).smallest_eigenpairs(2, 10_000, 1e-10);
+    let coordinate = |k: usize, i: usize| pairs.get(k).map_or(0.0, |(_, v)| v[i]);
+    let positions: Vec<(f64, f64)> = (0..ids.len())
+        .map(|i| (coordinate(0, i), coordinate(1, i)))
+        .collect();
+    ids.into_iter().zip(fit(&positions, 1.0, 1.0)).collect()
+}
+
+/// Places the nodes evenly on a circle in the order of `graph.nodes`.
+///
+/// The circle is inscribed in the unit square and the first node is at the rightmost point.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_cycle_graph, layout::circular};
+///
+/// let g = generate_cycle_graph::<()>(6);
+/// let positions = circular(&g);
+///
+/// let radius = |(x, y): (f64, f64)| ((x - 0.5).powi(2) + (y - 0.5).powi(2)).sqrt();
+/// assert!(positions.values().all(|p| (radius(*p) - 0.5).abs() < 1e-12));
+/// ```
+pub fn circular<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let mut layout = Layout::default();
+    place_on_circle(&mut layout, &graph.nodes, 0.5);
+    layout
+}
+
+/// Places the nodes on concentric circles, one per group, with the first group innermost.
+///
+/// Useful for bipartite graphs, communities or distance classes around a root. Nodes that are not in
+/// any group are put on an additional outermost circle; IDs in `groups` that are not in the graph are
+/// ignored. If the first group has a single node, it is placed in the center. The outermost circle is
+/// inscribed in the unit square.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to lay out.
+/// * `groups` - The nodes of every shell, from the inside out, each in the order to place them in.
+///
+pub fn shell<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    groups: &[Vec<IDDataType>],
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let mut placed = NodeSet::default();
+    let mut shells: Vec<Vec<IDDataType>> = groups
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .copied()
+                .filter(|id| graph.node_data.contains_key(id) && placed.insert(*id))
+                .collect::<Vec<_>>()
+        })
+        .filter(|shell| !shell.is_empty())
+        .collect();
+    let rest: Vec<IDDataType> = graph
+        .nodes
+        .iter()
+        .copied()
+        .filter(|id| !placed.contains(id))
+        .collect();
+    if !rest.is_empty() {
+        shells.push(rest);
+    }
+
+    let mut layout = Layout::default();
+    let Some(first) = shells.first() else {
+        return layout;
+    };
+    let skip_center = if first.len() == 1 { 0.0 } else { 1.0 };
+    let outermost = shells.len() as f64 - 1.0 + skip_center;
+    for (k, shell) in shells.iter().enumerate() {
+        let radius = if outermost > 0.0 {
+            0.5 * (k as f64 + skip_center) / outermost
+        } else {
+            0.0
+        };
+        place_on_circle(&mut layout, shell, radius);
+    }
+    layout
+}
+
+/// spreads `ids` evenly on a circle of the given radius around the center of the unit square.
+fn place_on_circle<IDDataType>(layout: &mut Layout<IDDataType>, ids: &[IDDataType], radius: f64)
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let step = std::f64::consts::TAU / ids.len().max(1) as f64;
+    for (i, id) in ids.iter().enumerate() {
+        let angle = i as f64 * step;
+        layout.insert(
+            *id,
+            (0.5 + radius * angle.cos(), 0.5 + radius * angle.sin()),
+        );
+    }
+}
+
+/// The geometry of a lattice whose nodes are identified by `(x, y)` coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeKind {
+    /// A square grid, as built by `generate_grid_graph`.
+    Square,
+    /// A triangular lattice in offset rows: every odd row is shifted right by half a spacing,
+    /// and rows are `sqrt(3) / 2` apart, so that every node has six equidistant neighbors: the
+    /// two in its row, `(x, y ± 1)`, and `(x + 1, y ± 1)` in odd rows or `(x - 1, y ± 1)` in even rows.
+    Triangular,
+    /// A honeycomb in brick-wall coordinates: every row is a zigzag chain, and `(x, y)` is joined to
+    /// `(x, y + 1)` when `x + y` is odd. Every node then has three neighbors at unit distance.
+    Hexagonal,
+}
+
+/// Places the nodes of a lattice at their geometric positions, without any simulation.
+///
+/// Node IDs are read as lattice coordinates, so lattices from the generators render faithfully and
+/// instantly. Neighboring nodes are at distance one; the positions are not scaled to a frame.
+///
+/// # Arguments
+///
+/// * `graph` - A graph whose IDs are lattice coordinates.
+/// * `kind` - How the coordinates map to the plane.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_grid_graph, layout::{lattice, LatticeKind}};
+///
+/// let g = generate_grid_graph::<()>(3, 3);
+/// let positions = lattice(&g, LatticeKind::Triangular);
+///
+/// assert_eq!(positions[&(2, 0)], (2.0, 0.0));
+/// assert!((positions[&(0, 1)].0 - 0.5).abs() < 1e-12);
+/// ```
+pub fn lattice<NodeDataType>(
+    graph: &Graph<(usize, usize), NodeDataType>,
+    kind: LatticeKind,
+) -> Layout<(usize, usize)> {
+    let row_height = 3.0_f64.sqrt() / 2.0;
+    graph
+        .nodes
+        .iter()
+        .map(|&(x, y)| {
+            let (fx, fy) = (x as f64, y as f64);
+            let position = match kind {
+                LatticeKind::Square => (fx, fy),
+                LatticeKind::Triangular => (fx + 0.5 * (y % 2) as f64, fy * row_height),
+                LatticeKind::Hexagonal => (
+                    fx * row_height,
+                    fy * 1.5 + if (x + y) % 2 == 1 { 0.5 } else { 0.0 },
+                ),
+            };
+            ((x, y), position)
+        })
+        .collect()
+}
+
+/// Draws a directed graph top-down in layers, in the style of Sugiyama et al.
+///
+/// Every edge points downwards: nodes are assigned to layers by the longest path from a source,
+/// edges spanning several layers are routed through invisible dummy nodes, and the order within
+/// each layer is improved by barycenter sweeps to reduce edge crossings. If the graph has cycles,
+/// the edges closing them (found by depth-first search) are ignored for the layering.
+///
+/// Positions lie in the unit square: `y` grows with the layer, sources are at the top, and every
+/// layer is centered horizontally.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{layout::layered, Graph};
+///
+/// let dag = Graph::<&str, ()>::from_edges(&[("app", "net"), ("app", "ui"), ("ui", "core"), ("net", "core")]);
+/// let positions = layered(&dag);
+///
+/// assert!(positions["app"].1 < positions["ui"].1 && positions["ui"].1 < positions["core"].1);
+/// assert_eq!(positions["net"].1, positions["ui"].1);
+/// ```
+pub fn layered<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Layout<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let ids = graph.nodes.clone();
+    let n = ids.len();
+    let index: NodeMap<IDDataType, usize> =
+        ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut successors: Vec<Vec<usize>> = ids
+        .iter()
+        .map(|id| {
+            let mut targets: Vec<usize> = graph
+                .edges
+                .get(id)
+                .into_iter()
+                .flatten()
+                .filter_map(|to| index.get(to).copied())
+                .collect();
+            let mut seen = NodeSet::default();
+            targets.retain(|j| seen.insert(*j));
+            targets
+        })
+        .collect();
+    remove_back_edges(&mut successors);
+
+    // longest-path layering over a topological order
+    let mut in_degree = vec![0; n];
+    for targets in &successors {
+        for &j in targets {
+            in_degree[j] += 1;
+        }
+    }
+    let mut order: Vec<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+    let mut layer = vec![0; n];
+    let mut next = 0;
+    while next < order.len() {
+        let i = order[next];
+        next += 1;
+        for &j in &successors[i] {
+            layer[j] = layer[j].max(layer[i] + 1);
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                order.push(j);
+            }
+        }
+    }
+
+    // split long edges with dummy nodes so that every edge connects adjacent layers
+    let mut node_layer = layer.clone();
+    let mut down: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for i in 0..n {
+        for &j in &successors[i] {
+            let mut from = i;
+            for l in layer[i] + 1..layer[j] {
+                node_layer.push(l);
+                down.push(Vec::new());
+                let dummy = node_layer.len() - 1;
+                down[from].push(dummy);
+                from = dummy;
+            }
+            down[from].push(j);
+        }
+    }
+    let mut up: Vec<Vec<usize>> = vec![Vec::new(); node_layer.len()];
+    for (i, targets) in down.iter().enumerate() {
+        for &j in targets {
+            up[j].push(i);
+        }
+    }
+    let layer_count = node_layer.iter().max().map_or(0, |l| l + 1);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for i in order.iter().copied().chain(n..node_layer.len()) {
+        layers[node_layer[i]].push(i);
+    }
+
+    reduce_crossings(&mut layers, &up, &down);
+
+    let widest = layers.iter().map(Vec::len).max().unwrap_or(1) as f64;
+    let mut positions = vec![(0.0, 0.0); n];
+    for (l, nodes) in layers.iter().enumerate() {
+        let margin = (widest - nodes.len() as f64) / 2.0;
+        for (position, &i) in nodes.iter().enumerate() {
+            if i < n {
+                positions[i] = (
+                    (margin + position as f64 + 0.5) / widest,
+                    (l as f64 + 0.5) / layer_count as f64,
+                );
+            }
+        }
+    }
+    ids.into_iter().zip(positions).collect()
+}
+
+/// removes the edges that close a cycle in a depth-first search, making the graph acyclic.
+fn remove_back_edges(successors: &mut [Vec<usize>]) {
+    // 0 = unvisited, 1 = on the stack, 2 = finished
+    let mut state = vec![0u8; successors.len()];
+    for root in 0..successors.len() {
+        if state[root] != 0 {
+            continue;
+        }
+        state[root] = 1;
+        let mut stack = vec![(root, 0)];
+        while let Some(&mut (i, ref mut next)) = stack.last_mut() {
+            if *next < successors[i].len() {
+                let j = successors[i][*next];
+                match state[j] {
+                    0 => {
+                        *next += 1;
+                        state[j] = 1;
+                        stack.push((j, 0));
+                    }
+                    1 => {
+                        successors[i].remove(*next);
+                    }
+                    _ => *next += 1,
+                }
+            } else {
+                state[i] = 2;
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// reorders every layer by the barycenters of its neighbors, alternating downward and upward sweeps,
+/// and keeps the ordering with the fewest crossings.
+fn reduce_crossings(layers: &mut [Vec<usize>], up: &[Vec<usize>], down: &[Vec<usize>]) {
+    let mut position = vec![0.0; up.len()];
+    let mut best = layers.to_vec();
+    let mut fewest = count_crossings(layers, down);
+    for sweep in 0..24 {
+        if fewest == 0 {
+            break;
+        }
+        let (range, neighbors): (Vec<usize>, _) = if sweep % 2 == 0 {
+            ((1..layers.len()).collect(), up)
+        } else {
+            ((0..layers.len().saturating_sub(1)).rev().collect(), down)
+        };
+        for l in range {
+            for nodes in layers.iter() {
+                for (p, &i) in nodes.iter().enumerate() {
+                    position[i] = p as f64;
+                }
+            }
+            let barycenter = |i: usize| {
+                let ns = &neighbors[i];
+                if ns.is_empty() {
+                    position[i]
+                } else {
+                    ns.iter().map(|&j| position[j]).sum::<f64>() / ns.len() as f64
+                }
+            };
+            let mut keyed: Vec<(f64, usize)> =
+                layers[l].iter().map(|&i| (barycenter(i), i)).collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[l] = keyed.into_iter().map(|(_, i)| i).collect();
+        }
+        let crossings = count_crossings(layers, down);
+        if crossings < fewest {
+            fewest = crossings;
+            best = layers.to_vec();
+        }
+    }
+    layers.clone_from_slice(&best);
+}
+
+/// counts the pairs of edges that cross between every two adjacent layers.
+fn count_crossings(layers: &[Vec<usize>], down: &[Vec<usize>]) -> usize {
+    let mut position = vec![0; down.len()];
+    for nodes in layers {
+        for (p, &i) in nodes.iter().enumerate() {
+            position[i] = p;
+        }
+    }
+    let mut crossings = 0;
+    for nodes in layers {
+        let edges: Vec<(usize, usize)> = nodes
+            .iter()
+            .flat_map(|&i| down[i].iter().map(move |&j| (i, j)))
+            .map(|(i, j)| (position[i], position[j]))
+            .collect();
+        for (a, e) in edges.iter().enumerate() {
+            for f in &edges[a + 1..] {
+                if (e.0 < f.0 && e.1 > f.1) || (e.0 > f.0 && e.1 < f.1) {
+                    crossings += 1;
+                }
+            }
+        }
+    }
+    crossings
+}
+
+/// scales and translates positions uniformly so they fill the frame `[0, width] x [0, height]`.
+fn fit(positions: &[(f64, f64)], width: f64, height: f64) -> Vec<(f64, f64)> {
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in positions {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let scale = (width / (max_x - min_x)).min(height / (max_y - min_y));
+    let scale = if scale.is_finite() { scale } else { 0.0 };
+    let (offset_x, offset_y) = (
+        (width - (max_x - min_x) * scale) / 2.0,
+        (height - (max_y - min_y) * scale) / 2.0,
+    );
+    positions
+        .iter()
+        .map(|&(x, y)| {
+            (
+                ((x - min_x) * scale + offset_x).clamp(0.0, width),
+                ((y - min_y) * scale + offset_y).clamp(0.0, height),
+            )
+        })
+        .collect()
+}
+
+/// the vector from `b` to `a` and its length, nudged away from zero so coincident nodes still separate.
+fn offset(a: (f64, f64), b: (f64, f64)) -> (f64, f64, f64) {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    let distance = (dx * dx + dy * dy).sqrt();
+    if distance > 1e-9 {
+        (dx, dy, distance)
+    } else {
+        (1e-9, 0.0, 1e-9)
+    }
+}
+
+/// lists the nodes in order and every pair of adjacent nodes once, ignoring direction and self-loops.
+fn undirected_pairs<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> (Vec<IDDataType>, Vec<(usize, usize)>)
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let ids: Vec<IDDataType> = graph.nodes.clone();
+    let index: NodeMap<IDDataType, usize> =
+        ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut pairs = NodeSet::default();
+    for (i, id) in ids.iter().enumerate() {
+        for to in graph.edges.get(id).into_iter().flatten() {
+            if let Some(&j) = index.get(to) {
+                if i != j {
+                    pairs.insert((i.min(j), i.max(j)));
+                }
+            }
+        }
+    }
+    let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+    pairs.sort_unstable();
+    (ids, pairs)
+}
@@ -0,0 +1,148 @@
+//! A Barnes-Hut quadtree approximating the combined repulsion of distant groups of nodes.
+
+/// A square cell of the tree, holding either a single body or four children.
+struct Cell {
+    center: (f64, f64),
+    half: f64,
+    mass: f64,
+    mass_center: (f64, f64),
+    body: Option<usize>,
+    children: Option<[usize; 4]>,
+}
+
+/// A quadtree over weighted points, rebuilt for every iteration of a layout.
+pub(super) struct QuadTree {
+    cells: Vec<Cell>,
+}
+
+/// below this cell size coincident bodies are merged instead of split further.
+const MIN_HALF: f64 = 1e-9;
+
+impl QuadTree {
+    /// builds the tree over `positions`, where body `i` has mass `masses[i]`.
+    pub(super) fn new(positions: &[(f64, f64)], masses: &[f64]) -> Self {
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(x, y) in positions {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let half = ((max_x - min_x).max(max_y - min_y) / 2.0).max(MIN_HALF) * 1.01;
+        let mut tree = Self {
+            cells: vec![Cell::empty(
+                ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0),
+                half,
+            )],
+        };
+        for (body, &position) in positions.iter().enumerate() {
+            tree.insert(0, body, position, masses[body], positions);
+        }
+        tree
+    }
+
+    fn insert(
+        &mut self,
+        cell: usize,
+        body: usize,
+        position: (f64, f64),
+        mass: f64,
+        positions: &[(f64, f64)],
+    ) {
+        let c = &mut self.cells[cell];
+        let total = c.mass + mass;
+        c.mass_center = (
+            (c.mass_center.0 * c.mass + position.0 * mass) / total,
+            (c.mass_center.1 * c.mass + position.1 * mass) / total,
+        );
+        c.mass = total;
+
+        if let Some(children) = c.children {
+            let child = children[Self::quadrant(c.center, position)];
+            self.insert(child, body, position, mass, positions);
+            return;
+        }
+        let Some(existing) = c.body else {
+            c.body = Some(body);
+            return;
+        };
+        if c.half < MIN_HALF {
+            // too small to separate, treat the bodies as one
+            return;
+        }
+
+        // split the leaf and push both bodies down
+        let (center, half) = (c.center, c.half / 2.0);
+        let first = self.cells.len();
+        for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+            self.cells.push(Cell::empty(
+                (center.0 + dx * half, center.1 + dy * half),
+                half,
+            ));
+        }
+        let c = &mut self.cells[cell];
+        c.children = Some([first, first + 1, first + 2, first + 3]);
+        c.body = None;
+        let existing_mass = c.mass - mass;
+        let child = first + Self::quadrant(center, positions[existing]);
+        self.reinsert(child, existing, positions[existing], existing_mass);
+        let child = first + Self::quadrant(center, position);
+        self.insert(child, body, position, mass, positions);
+    }
+
+    /// places a body that was already counted in the parent into an empty child.
+    fn reinsert(&mut self, cell: usize, body: usize, position: (f64, f64), mass: f64) {
+        let c = &mut self.cells[cell];
+        c.body = Some(body);
+        c.mass = mass;
+        c.mass_center = position;
+    }
+
+    fn quadrant(center: (f64, f64), position: (f64, f64)) -> usize {
+        (position.0 >= center.0) as usize + 2 * (position.1 >= center.1) as usize
+    }
+
+    /// calls `apply(mass_center, mass)` for every cell that is far enough from `position`
+    /// to be treated as a single point, and for every other body, skipping `body` itself.
+    pub(super) fn visit(
+        &self,
+        body: usize,
+        position: (f64, f64),
+        theta: f64,
+        apply: &mut impl FnMut((f64, f64), f64),
+    ) {
+        let mut stack = vec![0];
+        while let Some(cell) = stack.pop() {
+            let c = &self.cells[cell];
+            if c.mass == 0.0 || c.body == Some(body) {
+                continue;
+            }
+            match c.children {
+                Some(children) => {
+                    let (dx, dy) = (position.0 - c.mass_center.0, position.1 - c.mass_center.1);
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    if 2.0 * c.half < theta * distance {
+                        apply(c.mass_center, c.mass);
+                    } else {
+                        stack.extend(children);
+                    }
+                }
+                None => apply(c.mass_center, c.mass),
+            }
+        }
+    }
+}
+
+impl Cell {
+    fn empty(center: (f64, f64), half: f64) -> Self {
+        Self {
+            center,
+            half,
+            mass: 0.0,
+            mass_center: center,
+            body: None,
+            children: None,
+        }
+    }
+}
@@ -1,51 +1,190 @@
-use core::hash::Hash;
-use fnv::FnvHashMap;
-
-use std::{collections::HashSet, fmt::Debug};
-
-#[derive(Debug, PartialEq, Eq, Clone)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+pub mod acyclic;
+pub mod ancestors;
+#[cfg(feature = "std")]
+pub mod bp;
+pub mod builder;
+mod cache;
+pub mod centrality;
+mod cliques;
+mod clustering;
+pub mod coarsen;
+mod collections;
+mod coloring;
+mod community;
+pub mod compact;
+pub mod components;
+pub mod compute;
+#[cfg(feature = "std")]
+pub mod concurrent;
+pub mod connectivity;
+pub mod consistency;
+mod contract;
+mod cores;
+mod covers;
+mod cycles;
+mod data;
+mod describe;
+mod diff;
+pub mod distance;
+mod dominators;
+#[cfg(feature = "std")]
+pub mod dynamics;
+mod euler;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod flow;
+pub mod io;
+mod isomorphism;
+#[cfg(feature = "std")]
+pub mod layout;
+mod line_graph;
+#[cfg(feature = "std")]
+pub mod link_prediction;
+pub mod matrix;
+mod mixing;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+mod observer;
+mod operators;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "im")]
+pub mod persistent;
+pub mod perturb;
+#[cfg(feature = "petgraph")]
+mod petgraph_interop;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod query;
+pub mod reachability;
+#[cfg(feature = "std")]
+pub mod render;
+pub mod rewrite;
+pub mod routing;
+pub mod sampling;
+pub mod schedule;
+#[cfg(feature = "serde")]
+mod serialize;
+mod shortest_path;
+#[cfg(feature = "std")]
+mod spectral;
+mod subgraph;
+pub mod temporal;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transaction;
+mod truss;
+pub mod versioned;
+pub mod view;
+#[cfg(feature = "viewer")]
+pub mod viewer;
+pub mod visit;
+pub mod walk;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use cache::{CachedFlag, CompactCache, EdgeIndex, NextId};
+use collections::HashMap;
+use observer::Observers;
+
+pub use acyclic::AcyclicGraph;
+pub use builder::GraphBuilder;
+pub use collections::{GraphHasher, NodeMap, NodeSet};
+pub use compact::CompactGraph;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentGraph;
+pub use connectivity::{DisjointSet, DynamicConnectivity};
+pub use describe::GraphStats;
+pub use diff::GraphDiff;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapGraph;
+#[cfg(feature = "im")]
+pub use persistent::PersistentGraph;
+pub use query::NodeIndex;
+pub use temporal::TemporalGraph;
+pub use transaction::{Changeset, History, Transaction};
+pub use versioned::VersionedGraph;
+
+/// The list type used to store the outgoing or incoming neighbors of a node.
+///
+/// This is a plain `Vec` by default. With the `smallvec` feature enabled it is a
+/// `SmallVec` that stores up to four neighbors inline, which avoids one heap
+/// allocation per node and improves locality for low-degree graphs such as grids and cycles.
+#[cfg(not(feature = "smallvec"))]
+pub type Adjacency<IDDataType> = Vec<IDDataType>;
+
+/// The list type used to store the outgoing or incoming neighbors of a node,
+/// storing up to four neighbors inline (`smallvec` feature).
+#[cfg(feature = "smallvec")]
+pub type Adjacency<IDDataType> = smallvec::SmallVec<[IDDataType; 4]>;
+
+#[derive(PartialEq, Eq, Clone)]
 /// A graph data structure with nodes of type `NodeDataType` and edges between them.
-pub struct Graph<IDDataType, NodeDataType>
+///
+/// The fields are public for fast bulk access, but the methods keep them in step with each other,
+/// and writing to them directly can leave them out of step: use `node_data_mut`,
+/// `edge_data_mut` and the methods that add and remove nodes and edges where possible, and
+/// `repair`, or `invalidate_caches` if the fields are known to be consistent, after changing the
+/// fields by hand.
+///
+/// The algorithms and wrappers accept graphs with any edge data type, and the ones that copy edges
+/// copy their data. The exceptions build new graphs without edge data: the generators,
+/// `GraphBuilder`, the snapshots of a `TemporalGraph`, which stores time intervals per edge instead,
+/// the graph products, `line_graph` and `condensation`. `Perturbation::revert` restores removed
+/// edges without their data.
+pub struct Graph<IDDataType, NodeDataType, EdgeDataType = ()>
 where
     IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
 {
     /// A map from node IDs to their associated data.
-    pub node_data: FnvHashMap<IDDataType, NodeDataType>,
+    pub node_data: NodeMap<IDDataType, NodeDataType>,
     /// A map from node IDs to a vector of their outgoing edges.
-    pub edges: FnvHashMap<IDDataType, Vec<IDDataType>>,
+    pub edges: NodeMap<IDDataType, Adjacency<IDDataType>>,
     /// A map from node IDs to a vector of their incoming edges.
-    pub reverse_edges: FnvHashMap<IDDataType, Vec<IDDataType>>,
-    /// A vector of all node IDs in the graph.
+    pub reverse_edges: NodeMap<IDDataType, Adjacency<IDDataType>>,
+    /// A vector of all node IDs in the graph, in the order they were added. The generators add
+    /// their nodes in the order of their IDs, so this order, and with it `edge_tuples` and the
+    /// tie-breaking of the traversals, never depends on the hasher.
     pub nodes: Vec<IDDataType>,
+    /// A map from directed edges `(from, to)` to their data, such as a weight. Edges without an
+    /// entry have no data, and parallel edges share one entry.
+    pub edge_data: NodeMap<(IDDataType, IDDataType), EdgeDataType>,
+    /// Which edges the insertion methods accept, see `set_edge_policy`.
+    policy: EdgePolicy,
+    /// Cached result of `is_undirected`, invalidated by every method that changes the edges.
+    undirected: CachedFlag,
+    /// The distinct edges, for checking the edge policy when it forbids parallel edges.
+    edge_index: EdgeIndex<IDDataType>,
+    /// The index-based copy of the topology the traversals run on, built on first use.
+    compact: CompactCache<IDDataType>,
+    /// Callbacks notified about mutations, see `on_node_added` and friends.
+    observers: Observers<IDDataType>,
+    /// The counter `add_node_auto` hands out IDs from.
+    next_id: NextId<IDDataType>,
 }
 
-impl<IDDataType, NodeDataType: Default> Graph<IDDataType, NodeDataType>
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
 where
     IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
 {
-    /// Creates a new, empty graph.
-    pub fn new() -> Self {
-        Self {
-            node_data: FnvHashMap::default(),
-            edges: FnvHashMap::default(),
-            reverse_edges: FnvHashMap::default(),
-            nodes: Vec::new(),
-        }
-    }
-
     // graph from edges
     pub fn from_edges(edges: &[(IDDataType, IDDataType)]) -> Self {
         let mut graph = Self::new();
-
-        for (from, to) in edges {
-            graph.add_directed_edge(*from, *to);
-        }
+        graph.add_directed_edges(edges);
         graph
     }
 
     /// Adds a new node to the graph with the given ID.
     ///
-    /// If a node with the given ID already exists, this function will print a warning message and do nothing.
+    /// If a node with the given ID already exists, this function will log a warning and do nothing;
+    /// use `try_add_node` to get an error instead.
     ///
     /// # Arguments
     ///
@@ -56,30 +195,13 @@ where
         self.add_node_with_data(id, NodeDataType::default());
     }
 
-    /// Adds a new node to the graph with the given ID and data.
-    ///
-    /// If a node with the given ID already exists, this function will print a warning message and do nothing.
-    ///
-    /// # Arguments
-    ///
-    /// * `id` - The ID of the new node to be added.
-    /// * `data` - The data to be associated with the new node.
-    ///
-    pub fn add_node_with_data(&mut self, id: IDDataType, data: NodeDataType) {
-        if self.node_data.contains_key(&id) {
-            println!("Attempt to add node {:?}, that already exists: ", id);
-            return;
-        }
-
-        self.nodes.push(id);
-        self.edges.insert(id, Vec::new());
-        self.reverse_edges.insert(id, Vec::new());
-        self.node_data.insert(id, data);
-    }
-
     /// Add a directed edge from one node to another.
     /// If either node does not exist, this function will add them.
-    /// If the edge already exists, this function will do nothing.
+    /// If the edge already exists, a parallel edge is added; use `simplify` to remove duplicates.
+    ///
+    /// If the edge policy of the graph forbids the edge as a self-loop or a parallel edge, this
+    /// function will log a warning and do nothing; use `try_add_directed_edge` to get an error
+    /// instead.
     ///
     /// # Arguments
     ///
@@ -87,19 +209,38 @@ where
     /// * `to` - The ID of the node to add the edge to.
     ///
     pub fn add_directed_edge(&mut self, from: IDDataType, to: IDDataType) {
+        if let Err(error) = self.admit_edge(from, to) {
+            self.reject_edge(from, to, error);
+            return;
+        }
+
         // if the node does not exist, add it
         if !self.node_data.contains_key(&from) {
-            // println!("Attempt to add edge from {:?} to {:?}, but {:?} does not exist. Adding {:?} to the graph.", from, to, from, from);
+            log::debug!(
+                "adding missing node {:?} for the edge {:?} -> {:?}",
+                from,
+                from,
+                to
+            );
             self.add_node(from);
         }
 
         if !self.node_data.contains_key(&to) {
-            // println!("Attempt to add edge from {:?} to {:?}, but {:?} does not exist. Adding {:?} to the graph.", from, to, to, to);
+            log::debug!(
+                "adding missing node {:?} for the edge {:?} -> {:?}",
+                to,
+                from,
+                to
+            );
             self.add_node(to);
         }
 
         self.edges.entry(from).or_default().push(to);
         self.reverse_edges.entry(to).or_default().push(from);
+        self.undirected.invalidate();
+        self.compact.invalidate();
+        self.edge_index.insert(from, to);
+        self.observers.edge_added(from, to);
     }
 
     /// Add an undirected edge between two nodes.
@@ -115,393 +256,2558 @@ where
         self.add_directed_edge(to, from);
     }
 
-    /// Get the neighbors of a node.
-    /// If the node does not exist, this function will return an empty vector.
+    /// Add a directed edge with data, such as a weight, see `add_directed_edge`.
+    /// Parallel edges share their data, so adding the edge again replaces the data.
     ///
     /// # Arguments
     ///
-    /// * `id` - The ID of the node to get the neighbors of.
+    /// * `from` - The ID of the node to add the edge from.
+    /// * `to` - The ID of the node to add the edge to.
+    /// * `data` - The data of the edge.
     ///
-    pub fn neighbors(&self, id: IDDataType) -> Vec<IDDataType> {
-        //check if node exists
-        if !self.edges.contains_key(&id) {
-            Vec::new()
-        } else {
-            self.edges[&id].clone()
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut roads = Graph::<&str, (), f64>::new();
+    /// roads.add_directed_edge_with_data("home", "bridge", 2.0);
+    /// roads.add_edge_with_data("bridge", "work", 3.5);
+    /// assert_eq!(roads.edge_data("bridge", "work"), Some(&3.5));
+    /// assert_eq!(roads.edge_data("work", "home"), None);
+    ///
+    /// // weights feed the algorithms that take them as a function of the edge
+    /// let path = roads.widest_path("home", "work", |a, b| roads.edge_data[&(a, b)]);
+    /// assert_eq!(path, Some(vec!["home", "bridge", "work"]));
+    /// let total: f64 = roads.weighted_edges().map(|(_, _, w)| w).sum();
+    /// assert_eq!(total, 9.0);
+    /// ```
+    pub fn add_directed_edge_with_data(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        data: EdgeDataType,
+    ) {
+        if let Err(error) = self.admit_edge(from, to) {
+            self.reject_edge(from, to, error);
+            return;
         }
+        self.add_directed_edge(from, to);
+        self.edge_data.insert((from, to), data);
     }
 
-    /// Get the neighborhood of a node (which includes the node itself).
-    /// If the node does not exist, this function will return an empty vector.
+    /// Add an undirected edge with the same data in both directions, see `add_edge`.
+    pub fn add_edge_with_data(&mut self, from: IDDataType, to: IDDataType, data: EdgeDataType)
+    where
+        EdgeDataType: Clone,
+    {
+        self.add_directed_edge_with_data(from, to, data.clone());
+        self.add_directed_edge_with_data(to, from, data);
+    }
+
+    /// Like `add_node`, but fails with `GraphError::DuplicateNode` if the node already exists.
+    pub fn try_add_node(&mut self, id: IDDataType) -> Result<(), GraphError<IDDataType>> {
+        self.try_add_node_with_data(id, NodeDataType::default())
+    }
+
+    /// Like `add_directed_edge`, but fails with `GraphError::MissingNode` instead of adding a
+    /// missing node, and with `GraphError::SelfLoop` or `GraphError::DuplicateEdge` if the edge
+    /// policy forbids the edge. Nothing is added if either node is missing.
+    pub fn try_add_directed_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Result<(), GraphError<IDDataType>> {
+        self.require_nodes(from, to)?;
+        self.admit_edge(from, to)?;
+        self.add_directed_edge(from, to);
+        Ok(())
+    }
+
+    /// Like `add_edge`, but fails with `GraphError::MissingNode` instead of adding a missing node,
+    /// and with `GraphError::SelfLoop` or `GraphError::DuplicateEdge` if the edge policy forbids
+    /// either direction. Nothing is added if the edge fails.
+    pub fn try_add_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Result<(), GraphError<IDDataType>> {
+        self.require_nodes(from, to)?;
+        self.admit_edge(from, to)?;
+        self.admit_edge(to, from)?;
+        self.add_edge(from, to);
+        Ok(())
+    }
+
+    /// Add many directed edges at once.
+    /// Missing nodes are added in the order they first appear in `edges`.
+    ///
+    /// The edges are grouped per source (and per target for the reverse edges) first,
+    /// so every adjacency list is looked up once and grown to its final size in a single allocation.
+    /// This is considerably faster than calling `add_directed_edge` in a loop for large edge lists.
     ///
     /// # Arguments
     ///
-    /// * `id` - The ID of the node to get the neighborhood of.
+    /// * `edges` - The `(from, to)` pairs to add.
     ///
-    pub fn neighborhood(&self, id: IDDataType) -> Vec<IDDataType> {
-        //combine neighbors and self
-        let mut neighborhood = self.neighbors(id);
-        neighborhood.push(id);
-        neighborhood
+    pub fn add_directed_edges(&mut self, edges: &[(IDDataType, IDDataType)]) {
+        self.add_edge_groups(edges.iter().copied(), edges.len());
     }
 
-    /// get the nodes for which the given node is a neighbor.
-    /// If the node does not exist, this function will return an empty vector.
+    /// Add many undirected edges at once.
+    /// Equivalent to calling `add_edge` for every pair, but with the same batching as `add_directed_edges`.
     ///
     /// # Arguments
     ///
-    /// * `id` - The ID of the node to get the reverse neighbors of.
+    /// * `edges` - The pairs of nodes to connect.
     ///
-    pub fn reverse_neighbors(&self, id: IDDataType) -> &Vec<IDDataType> {
-        &self.reverse_edges[&id]
+    pub fn add_edges(&mut self, edges: &[(IDDataType, IDDataType)]) {
+        let both_ways = edges
+            .iter()
+            .flat_map(|&(from, to)| [(from, to), (to, from)]);
+        self.add_edge_groups(both_ways, 2 * edges.len());
     }
 
-    ///edge tuples
-    /// get the edges of the graph as a vector of tuples.
-    ///
-    ///
-    pub fn edge_tuples(&self) -> Vec<(IDDataType, IDDataType)> {
-        let mut edge_tuples = Vec::new();
-        for (from, tos) in self.edges.iter() {
-            for to in tos {
-                edge_tuples.push((*from, *to));
-            }
+    /// adds directed edges with `append_edge_groups`, skipping and logging the edges the edge
+    /// policy forbids, including repeats within `edges`.
+    fn add_edge_groups(
+        &mut self,
+        edges: impl Iterator<Item = (IDDataType, IDDataType)> + Clone,
+        count: usize,
+    ) {
+        if self.policy == EdgePolicy::default() {
+            self.append_edge_groups(edges, count);
+            return;
         }
-        edge_tuples
+        let mut batch = NodeSet::default();
+        let admitted: Vec<(IDDataType, IDDataType)> = edges
+            .filter(|&(from, to)| {
+                let admitted = self.admit_edge(from, to).and_then(|()| {
+                    if self.policy.allow_parallel_edges || batch.insert((from, to)) {
+                        Ok(())
+                    } else {
+                        Err(GraphError::DuplicateEdge(from, to))
+                    }
+                });
+                if let Err(error) = admitted {
+                    self.reject_edge(from, to, error);
+                }
+                admitted.is_ok()
+            })
+            .collect();
+        self.append_edge_groups(admitted.iter().copied(), admitted.len());
     }
 
-    /// checks if the graph is undirected.
-    pub fn is_undirected(&self) -> bool {
-        for (from, tos) in self.edges.iter() {
-            for to in tos {
-                if !self.edges.contains_key(to) {
-                    return false;
-                }
-                if !self.edges.get(to).unwrap().contains(from) {
-                    return false;
-                }
+    /// groups directed edges by endpoint and appends each group to the adjacency maps in one go.
+    fn append_edge_groups(
+        &mut self,
+        edges: impl Iterator<Item = (IDDataType, IDDataType)> + Clone,
+        count: usize,
+    ) {
+        for (from, to) in edges.clone() {
+            if !self.node_data.contains_key(&from) {
+                self.add_node(from);
+            }
+            if !self.node_data.contains_key(&to) {
+                self.add_node(to);
             }
         }
-        true
-    }
 
-    /// checks if the graph is directed and acyclic.
-    pub fn is_directed_acyclic(&self) -> bool {
-        //check if graph is directed
-        if self.is_undirected() {
-            return false;
+        let mut outgoing: NodeMap<IDDataType, Vec<IDDataType>> =
+            NodeMap::with_capacity_and_hasher(count, Default::default());
+        let mut incoming: NodeMap<IDDataType, Vec<IDDataType>> =
+            NodeMap::with_capacity_and_hasher(count, Default::default());
+        for (from, to) in edges.clone() {
+            outgoing.entry(from).or_default().push(to);
+            incoming.entry(to).or_default().push(from);
+            self.edge_index.insert(from, to);
         }
 
-        //check if graph is acyclic
-        for node in self.nodes.iter() {
-            if self.is_part_of_a_cycle(*node) {
-                return false;
-            }
+        for (from, tos) in outgoing {
+            self.edges.entry(from).or_default().extend(tos);
         }
-        true
-    }
-
-    /// checks if the given node is part of a cycle.
-    ///
-    /// # Arguments
-    ///
-    /// * `origin` - The ID of the node to check.
-    ///
-    fn is_part_of_a_cycle(&self, origin: IDDataType) -> bool {
-        // Potentially check for cycles instead by checking for sources and sinks?
-        let mut depth = 0;
-
-        let mut current_layer = self.neighbors(origin);
-
-        while depth < self.nodes.len() {
-            let mut next_layer = Vec::new();
+        for (to, froms) in incoming {
+            self.reverse_edges.entry(to).or_default().extend(froms);
+        }
+        self.undirected.invalidate();
+        self.compact.invalidate();
 
-            for node in current_layer {
-                if node == origin {
-                    return true;
-                } else {
-                    next_layer.append(&mut self.neighbors(node));
-                }
+        // observers only hear about the edges once they are in the graph
+        if !self.observers.is_empty() {
+            for (from, to) in edges {
+                self.observers.edge_added(from, to);
             }
-            depth += 1;
-            current_layer = next_layer;
         }
-        false
     }
 }
 
-impl<IDDataType, NodeDataType: Default> Default for Graph<IDDataType, NodeDataType>
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
 where
     IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
 {
-    fn default() -> Self {
-        Self::new()
+    /// Creates a new, empty graph.
+    pub fn new() -> Self {
+        Self {
+            node_data: NodeMap::default(),
+            edges: NodeMap::default(),
+            reverse_edges: NodeMap::default(),
+            nodes: Vec::new(),
+            edge_data: NodeMap::default(),
+            policy: EdgePolicy::default(),
+            undirected: CachedFlag::default(),
+            edge_index: EdgeIndex::default(),
+            compact: CompactCache::default(),
+            observers: Observers::default(),
+            next_id: NextId::default(),
+        }
     }
-}
-
-//macro to create a graph from a list of edges
-#[macro_export]
-macro_rules! graph {
-    ($($from:expr => $to:expr),*) => {
-        {
-            let mut g = Graph::new();
 
-            //just add the first node
+    /// Creates a new, empty graph with room for at least `nodes` nodes, so adding them does not
+    /// rehash the maps along the way.
+    pub fn with_capacity(nodes: usize) -> Self {
+        let mut graph = Self::new();
+        graph.reserve(nodes);
+        graph
+    }
 
-            $(
+    /// Reserves room for at least `additional` more nodes in the maps and the list of nodes.
+    pub fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+        self.node_data.reserve(additional);
+        self.edges.reserve(additional);
+        self.reverse_edges.reserve(additional);
+    }
 
-                g.add_directed_edge($from, $to);
-            )*
-            g
+    /// Creates a new, empty graph whose maps use the given hasher, e.g. an `ahash::RandomState`
+    /// with fixed seeds for reproducible iteration order. See [`GraphHasher`] for how the hasher
+    /// type is chosen.
+    pub fn with_hasher(hasher: GraphHasher) -> Self {
+        Self {
+            node_data: NodeMap::with_hasher(hasher.clone()),
+            edges: NodeMap::with_hasher(hasher.clone()),
+            reverse_edges: NodeMap::with_hasher(hasher.clone()),
+            edge_data: NodeMap::with_hasher(hasher),
+            ..Self::new()
         }
-    };
-
-    ($($from:expr ; $to:expr),*) => {
-        {
-            let mut g = Graph::new();
+    }
 
-            //just add the first node
+    /// Adds a new node to the graph with the given ID and data.
+    ///
+    /// If a node with the given ID already exists, this function will log a warning and do nothing;
+    /// use `try_add_node_with_data` to get an error instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the new node to be added.
+    /// * `data` - The data to be associated with the new node.
+    ///
+    pub fn add_node_with_data(&mut self, id: IDDataType, data: NodeDataType) {
+        if self.node_data.contains_key(&id) {
+            log::warn!("attempt to add node {:?}, that already exists", id);
+            return;
+        }
 
-            $(
+        self.nodes.push(id);
+        self.edges.insert(id, Adjacency::new());
+        self.reverse_edges.insert(id, Adjacency::new());
+        self.node_data.insert(id, data);
+        self.compact.invalidate();
+        self.observers.node_added(id);
+    }
 
-                g.add_edge($from, $to);
-            )*
-            g
+    /// Like `add_node_with_data`, but fails with `GraphError::DuplicateNode` if the node already
+    /// exists, leaving its data unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{Graph, GraphError};
+    ///
+    /// let mut g = Graph::<u32, &str>::new();
+    /// assert_eq!(g.try_add_node_with_data(1, "a"), Ok(()));
+    /// assert_eq!(g.try_add_node_with_data(1, "b"), Err(GraphError::DuplicateNode(1)));
+    /// assert_eq!(g.try_add_directed_edge(1, 2), Err(GraphError::MissingNode(2)));
+    /// ```
+    pub fn try_add_node_with_data(
+        &mut self,
+        id: IDDataType,
+        data: NodeDataType,
+    ) -> Result<(), GraphError<IDDataType>> {
+        if self.node_data.contains_key(&id) {
+            return Err(GraphError::DuplicateNode(id));
         }
-    };
-
+        self.add_node_with_data(id, data);
+        Ok(())
+    }
 
-    (($($id:expr,$data:expr),*),($($from:expr => $to:expr),*)) => {
+    /// fails with the first of the two nodes that does not exist.
+    fn require_nodes(
+        &self,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Result<(), GraphError<IDDataType>> {
+        match [from, to]
+            .into_iter()
+            .find(|id| !self.node_data.contains_key(id))
         {
-            let mut g = Graph::new();
-
-            $(
-                $(
-                    g.add_node_with_data($id,$data);
-                )*
-
-                $(
-                    g.add_directed_edge($from, $to);
-                )*
-            )*
-            g
+            Some(missing) => Err(GraphError::MissingNode(missing)),
+            None => Ok(()),
         }
-    };
-}
-
-/// generates a grid graph with the given width and height.
-pub fn generate_grid_graph<NodeDataType: Default + Send>(
-    width: usize,
-    height: usize,
-) -> Graph<(usize, usize), NodeDataType> {
-    let mut g = Graph::new();
-
-    g.node_data = (0..width)
-        .flat_map(|x| (0..height).map(move |y| ((x, y), NodeDataType::default())))
-        .collect();
-
-    g.nodes = g.node_data.keys().cloned().collect();
+    }
 
-    g.edges = g
-        .nodes
-        .iter()
-        .map(|id| {
-            let mut tos = Vec::new();
-            if id.0 > 0 {
-                tos.push((id.0 - 1, id.1));
+    /// Removes a node together with all incident edges and their data, from `nodes`,
+    /// `node_data`, `edges`, `reverse_edges` and `edge_data`.
+    /// Returns the data of the node, or `None` if it did not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the node to remove.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    /// assert_eq!(g.remove_node(1), Some(()));
+    /// assert_eq!(g.nodes, vec![0, 2]);
+    /// assert!(g.neighbors(0).is_empty());
+    /// assert!(g.reverse_neighbors(2).is_empty());
+    /// assert!(g.remove_node(1).is_none());
+    /// ```
+    pub fn remove_node(&mut self, id: IDDataType) -> Option<NodeDataType> {
+        let data = self.node_data.remove(&id)?;
+
+        // recently added nodes are the most likely to be removed, so search from the back
+        if let Some(position) = self.nodes.iter().rposition(|node| *node == id) {
+            self.nodes.remove(position);
+        }
+        let outgoing = self.edges.remove(&id).unwrap_or_default();
+        let incoming = self.reverse_edges.remove(&id).unwrap_or_default();
+        for to in outgoing.iter() {
+            self.edge_data.remove(&(id, *to));
+            self.edge_index.remove(id, *to);
+            if let Some(froms) = self.reverse_edges.get_mut(to) {
+                froms.retain(|from| *from != id);
             }
-            if id.0 < width - 1 {
-                tos.push((id.0 + 1, id.1));
+        }
+        for from in incoming.iter() {
+            self.edge_data.remove(&(*from, id));
+            self.edge_index.remove(*from, id);
+            if let Some(tos) = self.edges.get_mut(from) {
+                tos.retain(|to| *to != id);
             }
-            if id.1 > 0 {
-                tos.push((id.0, id.1 - 1));
+        }
+
+        self.undirected.invalidate();
+
+        self.compact.invalidate();
+        if !self.observers.is_empty() {
+            // self-loops are in both lists, so they are only reported with the outgoing edges
+            for to in outgoing {
+                self.observers.edge_removed(id, to);
             }
-            if id.1 < height - 1 {
-                tos.push((id.0, id.1 + 1));
+            for from in incoming.into_iter().filter(|from| *from != id) {
+                self.observers.edge_removed(from, id);
             }
-            (*id, tos)
-        })
-        .collect();
-
-    g
-}
+        }
+        self.observers.node_removed(id);
+        Some(data)
+    }
 
-/// generates a cycle graph with the given number of nodes.
-pub fn generate_cycle_graph<NodeDataType: Default + Send>(n: usize) -> Graph<usize, NodeDataType> {
-    let mut g = Graph::new();
+    /// Removes the most recently added copy of a directed edge from `edges` and `reverse_edges`,
+    /// and its data with the last copy. Returns whether the edge existed.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The ID of the node the edge starts at.
+    /// * `to` - The ID of the node the edge ends at.
+    ///
+    pub fn remove_directed_edge(&mut self, from: IDDataType, to: IDDataType) -> bool {
+        let Some(tos) = self.edges.get_mut(&from) else {
+            return false;
+        };
+        let Some(position) = tos.iter().rposition(|node| *node == to) else {
+            return false;
+        };
+        tos.remove(position);
+        if !tos.contains(&to) {
+            self.edge_data.remove(&(from, to));
+            self.edge_index.remove(from, to);
+        }
 
-    //create a hashmap of nodes
-    g.node_data = (0..n)
-        .map(|i| {
-            let id = i;
-            let node = NodeDataType::default();
-            (id, node)
-        })
-        .collect();
+        if let Some(froms) = self.reverse_edges.get_mut(&to) {
+            if let Some(position) = froms.iter().rposition(|node| *node == from) {
+                froms.remove(position);
+            }
+        }
+        self.undirected.invalidate();
+        self.compact.invalidate();
+        self.observers.edge_removed(from, to);
+        true
+    }
 
-    g.nodes = g.node_data.keys().cloned().collect();
+    /// Removes one copy of the edge in each direction, undoing `add_edge`.
+    /// Returns whether an edge was removed in either direction.
+    pub fn remove_edge(&mut self, from: IDDataType, to: IDDataType) -> bool {
+        let forward = self.remove_directed_edge(from, to);
+        let backward = self.remove_directed_edge(to, from);
+        forward || backward
+    }
 
-    //create a HashMap of edges
-    g.edges = g
-        .nodes
-        .iter()
-        .map(|id| {
-            let tos = vec![(id + 1) % n, (id + n - 1) % n];
-            (*id, tos)
-        })
-        .collect::<FnvHashMap<usize, Vec<usize>>>();
+    /// Like `remove_node`, but fails with `GraphError::MissingNode` if the node does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{Graph, GraphError};
+    ///
+    /// let mut g = Graph::<u32, &str>::new();
+    /// g.add_node_with_data(1, "a");
+    /// assert_eq!(g.try_remove_node(1), Ok("a"));
+    /// assert_eq!(g.try_remove_node(1), Err(GraphError::MissingNode(1)));
+    /// assert_eq!(g.try_remove_edge(1, 2), Err(GraphError::MissingNode(1)));
+    /// ```
+    pub fn try_remove_node(
+        &mut self,
+        id: IDDataType,
+    ) -> Result<NodeDataType, GraphError<IDDataType>> {
+        self.remove_node(id).ok_or(GraphError::MissingNode(id))
+    }
 
-    g
-}
+    /// Like `remove_directed_edge`, but fails with `GraphError::MissingNode` if either node does
+    /// not exist and with `GraphError::MissingEdge` if the edge does not.
+    pub fn try_remove_directed_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Result<(), GraphError<IDDataType>> {
+        self.require_nodes(from, to)?;
+        match self.remove_directed_edge(from, to) {
+            true => Ok(()),
+            false => Err(GraphError::MissingEdge(from, to)),
+        }
+    }
 
-/// generates a random graph with the given number of nodes and edge probability.
-///
-/// # Arguments
-///
-/// * `n` - The number of nodes in the graph.
-/// * `p` - The probability of an edge between two nodes.
-///
-pub fn generate_random_graph<NodeDataType: Default + Send>(
-    n: usize,
-    p: f64,
-) -> Graph<usize, NodeDataType> {
-    let mut g = Graph::new();
+    /// Like `remove_edge`, but fails with `GraphError::MissingNode` if either node does not exist
+    /// and with `GraphError::MissingEdge` if either direction is missing. Nothing is removed if
+    /// the removal fails.
+    pub fn try_remove_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Result<(), GraphError<IDDataType>> {
+        self.require_nodes(from, to)?;
+        for (a, b) in [(from, to), (to, from)] {
+            if !self.neighbors(a).contains(&b) {
+                return Err(GraphError::MissingEdge(a, b));
+            }
+        }
+        self.remove_edge(from, to);
+        Ok(())
+    }
 
-    //create a hashmap of nodes
-    g.node_data = (0..n)
-        .map(|i| {
-            let id = i;
-            let node = NodeDataType::default();
-            (id, node)
-        })
-        .collect();
+    /// Get the outgoing neighbors of a node without allocating.
+    /// If the node does not exist, this function will return an empty slice;
+    /// use `try_neighbors` to tell a missing node apart from one without edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the node to get the neighbors of.
+    ///
+    pub fn neighbors(&self, id: IDDataType) -> &[IDDataType] {
+        self.try_neighbors(id).unwrap_or_default()
+    }
 
-    g.nodes = g.node_data.keys().cloned().collect();
+    /// Get the outgoing neighbors of a node, or `None` if the node does not exist.
+    pub fn try_neighbors(&self, id: IDDataType) -> Option<&[IDDataType]> {
+        self.edges.get(&id).map(|n| &n[..])
+    }
 
-    //create a HashMap of edges
-    g.edges = g
-        .nodes
-        .iter()
-        .map(|id| {
-            let mut tos = Vec::new();
-            for to in 0..n {
-                if rand::random::<f64>() < p {
-                    tos.push(to);
-                }
-            }
-            (*id, tos)
-        })
-        .collect::<FnvHashMap<usize, Vec<usize>>>();
+    /// Iterates over the outgoing neighbors of a node without allocating, like `neighbors`.
+    pub fn neighbors_iter(&self, id: IDDataType) -> impl Iterator<Item = &IDDataType> {
+        self.neighbors(id).iter()
+    }
 
-    g
-}
+    /// Iterates over the outgoing neighbors of a node together with their data. Neighbors
+    /// without data, which only happens if the public fields were changed by hand, are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, &str>::new();
+    /// g.add_node_with_data(1, "one");
+    /// g.add_node_with_data(2, "two");
+    /// g.add_edge(1, 2);
+    ///
+    /// let names: Vec<_> = g.neighbors_with_data(1).map(|(_, name)| *name).collect();
+    /// assert_eq!(names, vec!["two"]);
+    /// ```
+    pub fn neighbors_with_data(
+        &self,
+        id: IDDataType,
+    ) -> impl Iterator<Item = (&IDDataType, &NodeDataType)> {
+        self.neighbors(id)
+            .iter()
+            .filter_map(move |to| Some((to, self.node_data.get(to)?)))
+    }
 
-//consider adding triangular grid and hexagonal grid
+    /// Iterates over the node IDs in insertion order, the order of `nodes`.
+    pub fn nodes_iter(&self) -> impl Iterator<Item = &IDDataType> {
+        self.nodes.iter()
+    }
 
-pub fn count_paths<IDDataType, NodeDataType: Default>(
-    graph: &Graph<IDDataType, NodeDataType>,
-    start: &IDDataType,
-    end: &IDDataType,
-    max_depth: Option<usize>,
-) -> usize
+    /// Iterates over every directed edge as `(from, to)`, in the order of `nodes` and then of
+    /// the neighbors of every node, without allocating. Parallel edges are yielded once per copy
+    /// and an undirected edge once per direction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -- 2").unwrap();
+    /// let edges: Vec<(u32, u32)> = g.edges_iter().collect();
+    /// assert_eq!(edges, vec![(0, 1), (1, 2), (2, 1)]);
+    /// ```
+    pub fn edges_iter(&self) -> impl Iterator<Item = (IDDataType, IDDataType)> + '_ {
+        self.nodes
+            .iter()
+            .flat_map(move |from| self.neighbors(*from).iter().map(move |to| (*from, *to)))
+    }
+
+    /// Get the neighborhood of a node (which includes the node itself).
+    /// If the node does not exist, this function will return an empty vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the node to get the neighborhood of.
+    ///
+    pub fn neighborhood(&self, id: IDDataType) -> Vec<IDDataType> {
+        //combine neighbors and self
+        let mut neighborhood = self.neighbors(id).to_vec();
+        neighborhood.push(id);
+        neighborhood
+    }
+
+    /// get the nodes for which the given node is a neighbor, without allocating.
+    /// If the node does not exist, this function will return an empty slice;
+    /// use `try_reverse_neighbors` to tell a missing node apart from one without edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the node to get the reverse neighbors of.
+    ///
+    pub fn reverse_neighbors(&self, id: IDDataType) -> &[IDDataType] {
+        self.try_reverse_neighbors(id).unwrap_or_default()
+    }
+
+    /// get the nodes for which the given node is a neighbor, or `None` if the node does not exist.
+    pub fn try_reverse_neighbors(&self, id: IDDataType) -> Option<&[IDDataType]> {
+        self.reverse_edges.get(&id).map(|n| &n[..])
+    }
+
+    /// Whether the graph has a node with the given ID, in constant time.
+    pub fn contains_node(&self, id: IDDataType) -> bool {
+        self.node_data.contains_key(&id)
+    }
+
+    /// The number of nodes, in constant time.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The number of directed edges, counting parallel edges, so an undirected edge counts
+    /// twice. Takes `O(n)` time, summing the lengths of the lists of neighbors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -- 2; 3").unwrap();
+    /// assert_eq!(g.node_count(), 4);
+    /// assert_eq!(g.edge_count(), 3);
+    /// assert!(g.contains_node(3));
+    /// assert!(!g.contains_node(4));
+    /// ```
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().map(|tos| tos.len()).sum()
+    }
+
+    /// The number of outgoing edges of a node, counting parallel edges and self-loops, or zero if
+    /// the node does not exist.
+    pub fn out_degree(&self, id: IDDataType) -> usize {
+        self.neighbors(id).len()
+    }
+
+    /// The number of incoming edges of a node, counting parallel edges and self-loops, or zero if
+    /// the node does not exist.
+    pub fn in_degree(&self, id: IDDataType) -> usize {
+        self.reverse_neighbors(id).len()
+    }
+
+    /// The number of parallel directed edges from `from` to `to`, zero if there is no such edge.
+    /// Graphs are multigraphs unless their edge policy forbids parallel edges, see
+    /// `set_edge_policy`.
+    ///
+    /// Scans the shorter of the outgoing edges of `from` and the incoming edges of `to`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, ()>::parse("0 -> 1; 1 -> 0").unwrap();
+    /// g.add_directed_edge(0, 1);
+    /// assert_eq!(g.edge_multiplicity(0, 1), 2);
+    /// assert_eq!(g.edge_multiplicity(1, 0), 1);
+    /// assert_eq!(g.edge_multiplicity(1, 2), 0);
+    /// ```
+    pub fn edge_multiplicity(&self, from: IDDataType, to: IDDataType) -> usize {
+        let (outgoing, incoming) = (self.neighbors(from), self.reverse_neighbors(to));
+        if outgoing.len() <= incoming.len() {
+            outgoing.iter().filter(|id| **id == to).count()
+        } else {
+            incoming.iter().filter(|id| **id == from).count()
+        }
+    }
+
+    /// Whether there is a directed edge from `from` to `to`, in `O(min(out_degree(from),
+    /// in_degree(to)))` time. For an undirected edge both directions hold.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -- 2").unwrap();
+    /// assert!(g.has_edge(0, 1));
+    /// assert!(!g.has_edge(1, 0));
+    /// assert!(g.has_edge(2, 1));
+    /// assert!(!g.has_edge(0, 7));
+    /// ```
+    pub fn has_edge(&self, from: IDDataType, to: IDDataType) -> bool {
+        let (outgoing, incoming) = (self.neighbors(from), self.reverse_neighbors(to));
+        if outgoing.len() <= incoming.len() {
+            outgoing.contains(&to)
+        } else {
+            incoming.contains(&from)
+        }
+    }
+
+    /// The number of incoming plus outgoing edges of a node, so a self-loop counts twice. An
+    /// undirected edge is stored in both directions and counts twice too; use `out_degree` for
+    /// the number of undirected edges of a node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1; 2 -> 1; 1 -- 3").unwrap();
+    /// assert_eq!(g.out_degree(1), 1);
+    /// assert_eq!(g.in_degree(1), 3);
+    /// assert_eq!(g.degree(1), 4);
+    /// assert_eq!(g.degree(7), 0);
+    /// ```
+    pub fn degree(&self, id: IDDataType) -> usize {
+        self.out_degree(id) + self.in_degree(id)
+    }
+
+    /// The number of nodes of every `degree` that occurs, ordered by degree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let star = Graph::<u32, ()>::parse("0 -> 1; 0 -> 2; 0 -> 3").unwrap();
+    /// let distribution = star.degree_distribution();
+    /// assert_eq!(distribution.into_iter().collect::<Vec<_>>(), vec![(1, 3), (3, 1)]);
+    /// ```
+    pub fn degree_distribution(&self) -> alloc::collections::BTreeMap<usize, usize> {
+        let mut distribution = alloc::collections::BTreeMap::new();
+        for id in &self.nodes {
+            *distribution.entry(self.degree(*id)).or_default() += 1;
+        }
+        distribution
+    }
+
+    /// The sources: the nodes without incoming edges, in the order of `nodes`. A self-loop is an
+    /// incoming edge, and a node linked by an undirected edge is never a source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2; 3 -> 2; 4").unwrap();
+    /// assert_eq!(g.sources(), vec![0, 3, 4]);
+    /// assert_eq!(g.sinks(), vec![2, 4]);
+    /// ```
+    pub fn sources(&self) -> Vec<IDDataType> {
+        self.nodes
+            .iter()
+            .copied()
+            .filter(|id| self.in_degree(*id) == 0)
+            .collect()
+    }
+
+    /// The sinks: the nodes without outgoing edges, in the order of `nodes`. A self-loop is an
+    /// outgoing edge, and a node linked by an undirected edge is never a sink.
+    pub fn sinks(&self) -> Vec<IDDataType> {
+        self.nodes
+            .iter()
+            .copied()
+            .filter(|id| self.out_degree(*id) == 0)
+            .collect()
+    }
+
+    /// The data of a node, or `None` if the node does not exist.
+    pub fn node_data(&self, id: IDDataType) -> Option<&NodeDataType> {
+        self.node_data.get(&id)
+    }
+
+    /// Mutable access to the data of a node, or `None` if the node does not exist. Unlike
+    /// inserting into the `node_data` field, this cannot give data to a node that is not in the
+    /// graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, u32>::new();
+    /// g.add_node_with_data(0, 1);
+    /// *g.node_data_mut(0).unwrap() += 1;
+    /// assert_eq!(g.node_data(0), Some(&2));
+    /// assert_eq!(g.node_data_mut(1), None);
+    /// ```
+    pub fn node_data_mut(&mut self, id: IDDataType) -> Option<&mut NodeDataType> {
+        self.node_data.get_mut(&id)
+    }
+
+    /// Iterates over the nodes with their data, in the order of `nodes`.
+    pub fn nodes_with_data(&self) -> impl Iterator<Item = (IDDataType, &NodeDataType)> {
+        self.nodes
+            .iter()
+            .filter_map(|id| Some((*id, self.node_data.get(id)?)))
+    }
+
+    /// Iterates over the nodes with mutable access to their data, in no particular order.
+    pub fn nodes_with_data_mut(&mut self) -> impl Iterator<Item = (IDDataType, &mut NodeDataType)> {
+        self.node_data.iter_mut().map(|(id, data)| (*id, data))
+    }
+
+    /// Calls `f` with every node and mutable access to its data, in no particular order. See
+    /// `par_update_all_data` (`rayon` feature) for a parallel version, and `step` for updates that
+    /// read the data of the neighbors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_cycle_graph;
+    ///
+    /// let mut g = generate_cycle_graph::<u32>(5);
+    /// g.update_all_data(|id, data| *data = *id as u32 * 10);
+    /// assert_eq!(g.node_data(3), Some(&30));
+    /// ```
+    pub fn update_all_data(&mut self, mut f: impl FnMut(&IDDataType, &mut NodeDataType)) {
+        for (id, data) in self.node_data.iter_mut() {
+            f(id, data);
+        }
+    }
+
+    /// The data of the directed edge from `from` to `to`, or `None` if it has none.
+    pub fn edge_data(&self, from: IDDataType, to: IDDataType) -> Option<&EdgeDataType> {
+        self.edge_data.get(&(from, to))
+    }
+
+    /// Mutable access to the data of the directed edge from `from` to `to`, or `None` if it has
+    /// none.
+    pub fn edge_data_mut(&mut self, from: IDDataType, to: IDDataType) -> Option<&mut EdgeDataType> {
+        self.edge_data.get_mut(&(from, to))
+    }
+
+    /// The edges that have data, with their data, in the order of `nodes` and their neighbors.
+    /// Parallel edges are listed once per copy, with the data they share.
+    pub fn weighted_edges(&self) -> impl Iterator<Item = (IDDataType, IDDataType, &EdgeDataType)> {
+        self.nodes.iter().flat_map(move |from| {
+            self.neighbors(*from)
+                .iter()
+                .filter_map(move |to| Some((*from, *to, self.edge_data.get(&(*from, *to))?)))
+        })
+    }
+
+    ///edge tuples
+    /// get the edges of the graph as a vector of tuples, in the order of `edges_iter`.
+    ///
+    ///
+    pub fn edge_tuples(&self) -> Vec<(IDDataType, IDDataType)> {
+        self.edges_iter().collect()
+    }
+
+    /// Draws a random weight for every edge, keyed by `(from, to)` like the weights of the
+    /// `graph!` macro. Parallel edges share one weight. Edges are visited in the order of
+    /// `nodes` and their neighbors, so a seeded `rng` gives the same weights on every run.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight_dist` - The distribution the weights are drawn from.
+    /// * `rng` - The source of randomness.
+    ///
+    #[cfg(feature = "std")]
+    pub fn assign_random_weights(
+        &self,
+        weight_dist: WeightDistribution,
+        mut rng: impl rand::Rng,
+    ) -> NodeMap<(IDDataType, IDDataType), f64> {
+        let mut weights = NodeMap::default();
+        for from in &self.nodes {
+            for to in self.neighbors(*from) {
+                if let collections::Entry::Vacant(slot) = weights.entry((*from, *to)) {
+                    slot.insert(weight_dist.sample(&mut rng));
+                }
+            }
+        }
+        weights
+    }
+
+    /// The edges the insertion methods accept.
+    pub fn edge_policy(&self) -> EdgePolicy {
+        self.policy
+    }
+
+    /// Sets which edges the insertion methods accept from now on. Edges the policy rejects are
+    /// skipped with a warning by `add_directed_edge`, `add_edge` and the batch methods, unless
+    /// self-loops are set to panic with `SelfLoopPolicy::Error`, and are an error for the `try_`
+    /// variants. Existing edges are kept; use `remove_self_loops` or
+    /// `simplify` to bring the graph in line with a stricter policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{EdgePolicy, Graph, GraphError};
+    ///
+    /// let mut g = Graph::<u32, ()>::new();
+    /// g.set_edge_policy(EdgePolicy::SIMPLE);
+    /// g.add_edges(&[(0, 1), (1, 1), (0, 1)]);
+    /// assert_eq!(g.edge_tuples().len(), 2);
+    /// assert_eq!(g.try_add_directed_edge(1, 0), Err(GraphError::DuplicateEdge(1, 0)));
+    /// assert_eq!(g.try_add_directed_edge(0, 0), Err(GraphError::SelfLoop(0)));
+    /// ```
+    pub fn set_edge_policy(&mut self, policy: EdgePolicy) {
+        self.policy = policy;
+        if policy.allow_parallel_edges {
+            self.edge_index.invalidate();
+        }
+    }
+
+    /// logs an edge that the edge policy rejected, or panics if the policy makes it an error.
+    fn reject_edge(&self, from: IDDataType, to: IDDataType, error: GraphError<IDDataType>) {
+        let fatal = self.policy.self_loops == SelfLoopPolicy::Error;
+        if fatal && matches!(error, GraphError::SelfLoop(_)) {
+            panic!("attempt to add the edge {:?} -> {:?}: {}", from, to, error);
+        }
+        log::warn!("attempt to add the edge {:?} -> {:?}: {}", from, to, error);
+    }
+
+    /// fails if the edge policy forbids adding the edge from `from` to `to`.
+    fn admit_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Result<(), GraphError<IDDataType>> {
+        if self.policy.self_loops != SelfLoopPolicy::Allow && from == to {
+            return Err(GraphError::SelfLoop(from));
+        }
+        if !self.policy.allow_parallel_edges && self.edge_index.contains(&self.edges, from, to) {
+            return Err(GraphError::DuplicateEdge(from, to));
+        }
+        Ok(())
+    }
+
+    /// The nodes with an edge to themselves, in insertion order, each listed once.
+    pub fn self_loops(&self) -> Vec<IDDataType> {
+        self.nodes
+            .iter()
+            .copied()
+            .filter(|id| self.neighbors(*id).contains(id))
+            .collect()
+    }
+
+    /// Removes every edge from a node to itself, with its data, and returns how many there were,
+    /// counting parallel loops separately. Observers see every removed loop.
+    pub fn remove_self_loops(&mut self) -> usize {
+        let mut removed = 0;
+        let observers = &mut self.observers;
+        for (from, tos) in self.edges.iter_mut() {
+            let before = tos.len();
+            tos.retain(|to| to != from);
+            for _ in tos.len()..before {
+                observers.edge_removed(*from, *from);
+            }
+            removed += before - tos.len();
+        }
+        for (to, froms) in self.reverse_edges.iter_mut() {
+            froms.retain(|from| from != to);
+        }
+        self.edge_data.retain(|(from, to), _| from != to);
+        self.undirected.invalidate();
+        self.compact.invalidate();
+        self.edge_index.invalidate();
+        removed
+    }
+
+    /// Removes duplicate (parallel) edges and, optionally, self-loops.
+    /// Both `edges` and `reverse_edges` are cleaned, keeping the first occurrence of every edge.
+    /// Observers see every removed copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `remove_self_loops` - Whether edges from a node to itself should be removed as well.
+    ///
+    pub fn simplify(&mut self, remove_self_loops: bool) -> Simplification {
+        let mut removed = Simplification::default();
+
+        let observers = &mut self.observers;
+        for (from, tos) in self.edges.iter_mut() {
+            let mut seen = NodeSet::with_capacity_and_hasher(tos.len(), Default::default());
+            tos.retain(|to| {
+                let kept = if remove_self_loops && to == from {
+                    removed.self_loops += 1;
+                    false
+                } else if !seen.insert(*to) {
+                    removed.parallel_edges += 1;
+                    false
+                } else {
+                    true
+                };
+                if !kept {
+                    observers.edge_removed(*from, *to);
+                }
+                kept
+            });
+        }
+
+        for (to, froms) in self.reverse_edges.iter_mut() {
+            let mut seen = NodeSet::with_capacity_and_hasher(froms.len(), Default::default());
+            froms.retain(|from| !(remove_self_loops && from == to) && seen.insert(*from));
+        }
+        if remove_self_loops {
+            self.edge_data.retain(|(from, to), _| from != to);
+        }
+
+        self.undirected.invalidate();
+
+        self.compact.invalidate();
+        self.edge_index.invalidate();
+        removed
+    }
+
+    /// Shrinks the capacity of every internal map and vector as much as possible, and drops the
+    /// index-based core the traversals build, see `to_compact`. Useful after bulk removals or
+    /// construction to release over-allocated memory.
+    pub fn shrink_to_fit(&mut self) {
+        self.compact.invalidate();
+        self.nodes.shrink_to_fit();
+        self.node_data.shrink_to_fit();
+        self.edges.shrink_to_fit();
+        self.reverse_edges.shrink_to_fit();
+        self.edge_data.shrink_to_fit();
+        for tos in self.edges.values_mut() {
+            tos.shrink_to_fit();
+        }
+        for froms in self.reverse_edges.values_mut() {
+            froms.shrink_to_fit();
+        }
+    }
+
+    /// Estimates the number of bytes allocated by the graph.
+    ///
+    /// The estimate is based on the capacities of the internal containers,
+    /// so it includes reserved but unused space. Memory owned indirectly by
+    /// the node data (e.g. a `Vec` inside `NodeDataType`) is not counted.
+    pub fn approx_memory_usage(&self) -> MemoryUsage {
+        // hashbrown stores one control byte per bucket next to each entry
+        fn map_bytes<K, V, S>(map: &HashMap<K, V, S>) -> usize {
+            map.capacity() * (core::mem::size_of::<(K, V)>() + 1)
+        }
+
+        let id_size = core::mem::size_of::<IDDataType>();
+        let adjacency_lists: usize = self
+            .edges
+            .values()
+            .chain(self.reverse_edges.values())
+            .map(|list| {
+                // inline lists live inside the map entries, which are already counted
+                #[cfg(feature = "smallvec")]
+                if !list.spilled() {
+                    return 0;
+                }
+                list.capacity() * id_size
+            })
+            .sum();
+
+        MemoryUsage {
+            nodes: self.nodes.capacity() * id_size,
+            adjacency: map_bytes(&self.edges) + map_bytes(&self.reverse_edges) + adjacency_lists,
+            data: map_bytes(&self.node_data) + map_bytes(&self.edge_data),
+        }
+    }
+
+    /// checks if the graph is undirected, i.e. every edge has a matching edge in the opposite direction.
+    ///
+    /// The check runs in O(E) and its result is cached until the edges are next changed through
+    /// one of the graph's methods. If you modify the public `edges` map directly, call
+    /// `invalidate_caches` afterwards.
+    pub fn is_undirected(&self) -> bool {
+        if let Some(undirected) = self.undirected.get() {
+            return undirected;
+        }
+
+        let edge_set: NodeSet<(IDDataType, IDDataType)> = self
+            .edges
+            .iter()
+            .flat_map(|(from, tos)| tos.iter().map(move |to| (*from, *to)))
+            .collect();
+        let undirected = edge_set
+            .iter()
+            .all(|(from, to)| edge_set.contains(&(*to, *from)));
+
+        self.undirected.set(undirected);
+        undirected
+    }
+
+    /// Forgets cached structural properties such as the result of `is_undirected`, the set of
+    /// edges the edge policy checks for duplicates and the index-based core the traversals run
+    /// on. Only needed after modifying the public maps directly.
+    pub fn invalidate_caches(&mut self) {
+        self.undirected.invalidate();
+        self.compact.invalidate();
+        self.edge_index.invalidate();
+    }
+
+    /// checks if the graph is directed and acyclic. A graph without edges counts as undirected
+    /// and so is not directed acyclic.
+    pub fn is_directed_acyclic(&self) -> bool {
+        !self.is_undirected() && self.topological_sort().is_ok()
+    }
+
+    /// Finds a directed cycle, returned as its nodes with the first node repeated at the end, or
+    /// `None` if the graph has no cycle. A self-loop is a cycle of one node, and on an undirected
+    /// graph any edge is a cycle of two nodes.
+    ///
+    /// The search is a single depth-first search that closes a cycle when it reaches a node on
+    /// its current path again, which takes O(V + E) time. Use `topological_sort` to learn which
+    /// nodes are affected by cycles instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 3 -> 1; 3 -> 4").unwrap();
+    /// assert_eq!(g.find_cycle(), Some(vec![1, 2, 3, 1]));
+    ///
+    /// let dag = Graph::<u32, ()>::parse("0 -> 1 -> 2; 0 -> 2").unwrap();
+    /// assert_eq!(dag.find_cycle(), None);
+    /// ```
+    pub fn find_cycle(&self) -> Option<Vec<IDDataType>> {
+        let mut finished: NodeSet<IDDataType> = NodeSet::default();
+        // the position of every node of the current path
+        let mut on_path: NodeMap<IDDataType, usize> = NodeMap::default();
+        for root in &self.nodes {
+            if finished.contains(root) {
+                continue;
+            }
+            let mut path: Vec<(IDDataType, usize)> = Vec::from([(*root, 0)]);
+            on_path.insert(*root, 0);
+            while let Some((node, next)) = path.last_mut() {
+                let node = *node;
+                match self.neighbors(node).get(*next) {
+                    Some(&child) => {
+                        *next += 1;
+                        if let Some(&start) = on_path.get(&child) {
+                            let mut cycle: Vec<IDDataType> =
+                                path[start..].iter().map(|(id, _)| *id).collect();
+                            cycle.push(child);
+                            return Some(cycle);
+                        }
+                        if !finished.contains(&child) {
+                            on_path.insert(child, path.len());
+                            path.push((child, 0));
+                        }
+                    }
+                    None => {
+                        finished.insert(node);
+                        on_path.remove(&node);
+                        path.pop();
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The nodes in an order in which every edge points forward, or the nodes that cannot be
+    /// ordered if the graph has a cycle. This is Kahn's algorithm: nodes without incoming edges
+    /// are taken first, in the order of `nodes`, then every node as soon as all of its
+    /// predecessors were taken, in O(V + E) time. Self-loops count as cycles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{CycleError, Graph};
+    ///
+    /// let dag = Graph::<u32, ()>::parse("3 -> 1 -> 0; 3 -> 2 -> 0").unwrap();
+    /// assert_eq!(dag.topological_sort(), Ok(vec![3, 1, 2, 0]));
+    ///
+    /// let cyclic = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 1 -> 3; 4").unwrap();
+    /// assert_eq!(
+    ///     cyclic.topological_sort(),
+    ///     Err(CycleError { nodes: vec![1, 2, 3] })
+    /// );
+    /// ```
+    pub fn topological_sort(&self) -> Result<Vec<IDDataType>, CycleError<IDDataType>> {
+        let mut in_degrees: NodeMap<IDDataType, usize> =
+            self.nodes.iter().map(|id| (*id, 0)).collect();
+        for to in self.edges.values().flatten() {
+            *in_degrees.entry(*to).or_default() += 1;
+        }
+        let mut order: Vec<IDDataType> = self
+            .nodes
+            .iter()
+            .copied()
+            .filter(|id| in_degrees[id] == 0)
+            .collect();
+        let mut next = 0;
+        while let Some(id) = order.get(next).copied() {
+            next += 1;
+            for to in self.neighbors(id) {
+                let degree = in_degrees.entry(*to).or_default();
+                *degree -= 1;
+                if *degree == 0 {
+                    order.push(*to);
+                }
+            }
+        }
+        if order.len() < self.nodes.len() {
+            let nodes = self
+                .nodes
+                .iter()
+                .copied()
+                .filter(|id| in_degrees[id] > 0)
+                .collect();
+            return Err(CycleError { nodes });
+        }
+        Ok(order)
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: NodeIdAllocator,
+{
+    /// Adds a node with an ID that is not in use yet and returns the ID.
+    ///
+    /// The IDs come from a counter on the graph, so in a graph whose nodes were all added this way
+    /// they are `0, 1, 2, ...`. The counter starts at the node count and skips IDs that are
+    /// already in use, such as those of nodes added with `add_node`. When it runs past the largest
+    /// value of the ID type it wraps around to the smallest, so the ID of a removed node is only
+    /// handed out again after that. Every node added with a chosen ID costs at most one extra
+    /// probe, so handing out IDs takes constant amortized time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ID type has no free value left; use `try_add_node_auto` to get an error
+    /// instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u8, &str>::new();
+    /// let a = g.add_node_auto("a");
+    /// let b = g.add_node_auto("b");
+    /// g.add_edge(a, b);
+    /// assert_eq!((a, b), (0, 1));
+    /// ```
+    pub fn add_node_auto(&mut self, data: NodeDataType) -> IDDataType {
+        match self.try_add_node_auto(data) {
+            Ok(id) => id,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Like `add_node_auto`, but fails with `GraphError::NoFreeId` instead of panicking if the ID
+    /// type has no free value left.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{Graph, GraphError};
+    ///
+    /// let mut g = Graph::<u8, ()>::new();
+    /// for _ in 0..256 {
+    ///     g.add_node_auto(());
+    /// }
+    /// assert_eq!(g.try_add_node_auto(()), Err(GraphError::NoFreeId));
+    /// ```
+    pub fn try_add_node_auto(
+        &mut self,
+        data: NodeDataType,
+    ) -> Result<IDDataType, GraphError<IDDataType>> {
+        let start = self
+            .next_id
+            .0
+            .or_else(|| IDDataType::from_index(self.nodes.len()));
+        let id = self.free_id(start).ok_or(GraphError::NoFreeId)?;
+        self.add_node_with_data(id, data);
+        self.next_id.0 = id.successor().or_else(IDDataType::smallest);
+        Ok(id)
+    }
+
+    /// the first ID from `start` upwards that is not in use, wrapping around to the smallest ID
+    /// once, or `None` if every ID is taken. Every taken ID on the way is a node, so this probes
+    /// at most two more IDs than there are nodes.
+    fn free_id(&self, start: Option<IDDataType>) -> Option<IDDataType> {
+        let mut id = start;
+        let mut wrapped = false;
+        for _ in 0..self.nodes.len() + 2 {
+            match id {
+                Some(free) if !self.node_data.contains_key(&free) => return Some(free),
+                Some(taken) => id = taken.successor(),
+                None if wrapped => return None,
+                None => {
+                    wrapped = true;
+                    id = IDDataType::smallest();
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<NodeDataType, EdgeDataType> Graph<NodeId, NodeDataType, EdgeDataType> {
+    /// Adds a node for which there is no natural ID and returns the ID it was given, see
+    /// [`NodeId`]. The IDs come from the counter of `add_node_auto`, under a name that reads
+    /// better in graphs of anonymous nodes; nodes with chosen IDs can still be added to the same
+    /// graph and are skipped by the counter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{Graph, NodeId};
+    ///
+    /// let mut g = Graph::<NodeId, &str>::new();
+    /// let a = g.add_anonymous_node("a");
+    /// let b = g.add_anonymous_node("b");
+    /// g.add_edge(a, b);
+    /// g.add_node_with_data(NodeId(7), "c");
+    /// assert_eq!(g.neighbors(a), &[NodeId(1)]);
+    /// assert_eq!(g.add_anonymous_node("d"), NodeId(2));
+    /// ```
+    pub fn add_anonymous_node(&mut self, data: NodeDataType) -> NodeId {
+        self.add_node_auto(data)
+    }
+}
+
+/// Which edges the insertion methods of a graph accept, see `Graph::set_edge_policy`. The default
+/// allows everything, so a graph is a multigraph unless it opts into a stricter policy; use
+/// `Graph::edge_multiplicity` to count parallel edges. The graph generators produce simple graphs
+/// whatever their size, without self-loops or parallel edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgePolicy {
+    /// What happens to an edge from a node to itself.
+    pub self_loops: SelfLoopPolicy,
+    /// Whether an edge may be added again between the same two nodes in the same direction.
+    /// Without parallel edges the graph keeps a set of its edges, so checking a new edge takes
+    /// constant time however many neighbors its nodes have.
+    pub allow_parallel_edges: bool,
+}
+
+impl EdgePolicy {
+    /// The policy of a simple graph: no self-loops and no parallel edges.
+    pub const SIMPLE: Self = Self {
+        self_loops: SelfLoopPolicy::Ignore,
+        allow_parallel_edges: false,
+    };
+
+    /// The policy of a multigraph, the default: self-loops and parallel edges are allowed.
+    pub const MULTIGRAPH: Self = Self {
+        self_loops: SelfLoopPolicy::Allow,
+        allow_parallel_edges: true,
+    };
+}
+
+/// What the insertion methods of a graph do with an edge from a node to itself, see
+/// [`EdgePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelfLoopPolicy {
+    /// Self-loops are added like any other edge.
+    Allow,
+    /// Self-loops are skipped with a warning in the log, like the other edges a policy forbids,
+    /// and are an error for the `try_` variants.
+    Ignore,
+    /// Self-loops are a bug: `add_directed_edge`, `add_edge` and the batch methods panic, and the
+    /// `try_` variants fail with `GraphError::SelfLoop`.
+    Error,
+}
+
+impl Default for EdgePolicy {
+    fn default() -> Self {
+        Self::MULTIGRAPH
+    }
+}
+
+/// The errors of the strict `try_` variants of the mutating methods, such as `Graph::try_add_node`
+/// and `Graph::try_remove_node`, and of `GraphBuilder::build`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphError<IDDataType> {
+    /// A node with this ID already exists.
+    DuplicateNode(IDDataType),
+    /// No node with this ID exists.
+    MissingNode(IDDataType),
+    /// The directed edge from the first to the second node already exists.
+    DuplicateEdge(IDDataType, IDDataType),
+    /// The edge policy of the graph forbids a self-loop on this node.
+    SelfLoop(IDDataType),
+    /// There is no directed edge from the first to the second node.
+    MissingEdge(IDDataType, IDDataType),
+    /// Every value of the ID type is already taken by a node.
+    NoFreeId,
+}
+
+impl<IDDataType: Debug> core::fmt::Display for GraphError<IDDataType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GraphError::DuplicateNode(id) => write!(f, "node {id:?} already exists"),
+            GraphError::MissingNode(id) => write!(f, "node {id:?} does not exist"),
+            GraphError::DuplicateEdge(from, to) => {
+                write!(f, "edge {from:?} -> {to:?} already exists")
+            }
+            GraphError::SelfLoop(id) => write!(f, "self-loops are not allowed, on node {id:?}"),
+            GraphError::MissingEdge(from, to) => {
+                write!(f, "edge {from:?} -> {to:?} does not exist")
+            }
+            GraphError::NoFreeId => write!(f, "no free node ID left"),
+        }
+    }
+}
+
+impl<IDDataType: Debug> core::error::Error for GraphError<IDDataType> {}
+
+/// The error of algorithms that need a directed acyclic graph, such as `Graph::topological_sort`
+/// and `schedule::list_schedule`, when the graph has a cycle, and of `AcyclicGraph` when an edge
+/// would close one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<IDDataType> {
+    /// The nodes that could not be ordered because they lie on a cycle or after one, in the order
+    /// of `Graph::nodes`, or for `AcyclicGraph` the cycle the rejected edge would close.
+    pub nodes: Vec<IDDataType>,
+}
+
+impl<IDDataType: Debug> core::fmt::Display for CycleError<IDDataType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "the graph has a cycle through or before {:?}",
+            self.nodes
+        )
+    }
+}
+
+impl<IDDataType: Debug> core::error::Error for CycleError<IDDataType> {}
+
+/// The error of `Graph::shortest_path_bellman_ford` when a cycle with a negative total weight can
+/// be reached from the start, so that paths through it get arbitrarily short.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCycleError<IDDataType> {
+    /// The nodes of the cycle in the direction of its edges, without repeating the first one at
+    /// the end, starting with the one that comes first in `Graph::nodes`.
+    pub cycle: Vec<IDDataType>,
+}
+
+impl<IDDataType: Debug> core::fmt::Display for NegativeCycleError<IDDataType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "the graph has a negative cycle {:?}", self.cycle)
+    }
+}
+
+impl<IDDataType: Debug> core::error::Error for NegativeCycleError<IDDataType> {}
+
+/// Integer-like node ID types that `Graph::add_node_auto` can allocate.
+pub trait NodeIdAllocator: Debug + PartialEq + Eq + Hash + Clone + Copy {
+    /// The ID with the given index, or `None` if the type cannot represent it.
+    fn from_index(index: usize) -> Option<Self>;
+    /// The next ID, or `None` if this is the largest one.
+    fn successor(self) -> Option<Self>;
+    /// The smallest ID, where the search for a free ID wraps around to after the largest one.
+    /// Defaults to `from_index(0)`.
+    fn smallest() -> Option<Self> {
+        Self::from_index(0)
+    }
+}
+
+macro_rules! impl_node_id_allocator {
+    ($($t:ty),*) => {
+        $(
+            impl NodeIdAllocator for $t {
+                fn from_index(index: usize) -> Option<Self> {
+                    <$t>::try_from(index).ok()
+                }
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+                fn smallest() -> Option<Self> {
+                    Some(<$t>::MIN)
+                }
+            }
+        )*
+    };
+}
+
+impl_node_id_allocator!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// An opaque node ID for graphs whose nodes have no natural key, handed out by
+/// `Graph::add_anonymous_node`. It hashes as a single integer, so the traversals keep their
+/// visited nodes in a vector indexed by the ID, see [`visit::CompactIds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId(pub u32);
+
+impl NodeIdAllocator for NodeId {
+    fn from_index(index: usize) -> Option<Self> {
+        u32::from_index(index).map(NodeId)
+    }
+    fn successor(self) -> Option<Self> {
+        self.0.successor().map(NodeId)
+    }
+}
+
+impl core::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The number of edges removed by `Graph::simplify`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Simplification {
+    /// Duplicate copies of an edge that was already present.
+    pub parallel_edges: usize,
+    /// Edges from a node to itself.
+    pub self_loops: usize,
+}
+
+/// Estimated heap usage of a graph in bytes, as reported by `Graph::approx_memory_usage`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct MemoryUsage {
+    /// The list of node IDs.
+    pub nodes: usize,
+    /// The outgoing and incoming adjacency maps, including the lists they hold.
+    pub adjacency: usize,
+    /// The maps from node IDs to node data and from edges to edge data.
+    pub data: usize,
+}
+
+impl MemoryUsage {
+    /// The total estimated number of bytes.
+    pub fn total(&self) -> usize {
+        self.nodes + self.adjacency + self.data
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Default
+    for Graph<IDDataType, NodeDataType, EdgeDataType>
 where
     IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
 {
-    _count_paths(graph, start, end, max_depth, 0, Vec::new())
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists every node in insertion order with its data and its outgoing neighbors, which are sorted
+/// by the insertion order of the neighbors. Use `{:#?}` for one node per line.
+impl<IDDataType, NodeDataType: Debug, EdgeDataType> Debug
+    for Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let position: NodeMap<IDDataType, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        f.write_str("Graph ")?;
+        let mut map = f.debug_map();
+        for id in &self.nodes {
+            let mut neighbors = self.neighbors(*id).to_vec();
+            neighbors.sort_by_key(|to| position.get(to).copied());
+            map.entry(
+                id,
+                &NodeDebug {
+                    data: self.node_data.get(id),
+                    neighbors,
+                },
+            );
+        }
+        map.finish()
+    }
+}
+
+/// one node in the `Debug` output of a graph.
+struct NodeDebug<'a, IDDataType, NodeDataType> {
+    data: Option<&'a NodeDataType>,
+    neighbors: Vec<IDDataType>,
+}
+
+impl<IDDataType: Debug, NodeDataType: Debug> Debug for NodeDebug<'_, IDDataType, NodeDataType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut node = f.debug_struct("Node");
+        if let Some(data) = self.data {
+            node.field("data", data);
+        }
+        node.field("neighbors", &self.neighbors).finish()
+    }
+}
+
+/// Creates a graph from a list of edges and nodes.
+///
+/// Entries are separated by commas. `a => b` adds a directed edge, `a ; b` an edge in both
+/// directions and `a -(w)-> b` or `a -(w)> b` a directed edge with the weight `w`; the kinds can
+/// be mixed freely. Edges can be chained into paths, so `a => b ; c` is short for `a => b, b ; c`.
+/// A node on its own, such as `a` or `a: data`, is added without edges.
+///
+/// A node is a literal, an identifier or a parenthesized expression, optionally followed by
+/// `: data` to set its data, both in an edge and on its own. The data is a single token
+/// such as `"start"` or `x`; wrap anything longer in braces or parentheses, like `:{-1}`. Nodes
+/// without data get the default data, and a later `: data` for the same node replaces it.
+///
+/// `Graph::parse` reads the same kind of edge lists from text at runtime.
+///
+/// Without weights the macro evaluates to the graph. If any edge has a weight it evaluates to the
+/// graph and a `NodeMap<(ID, ID), W>` from directed edges to their weights.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{graph, Graph};
+///
+/// let g: Graph<i32, &str> = graph! {
+///     0: "start" => 1 => 2 ; 3:{"end"},
+///     1 ; 3,
+///     4: "alone",
+/// };
+/// assert_eq!(g.node_data[&0], "start");
+/// assert_eq!(g.node_data[&1], "");
+/// assert_eq!(g.node_data[&4], "alone");
+/// assert_eq!(g.edge_tuples().len(), 6);
+///
+/// let (g, weights): (Graph<i32, ()>, _) = graph! {
+///     0 -(3.5)-> 1,
+///     1 -(0.5)> 2,
+/// };
+/// assert_eq!(g.nodes.len(), 3);
+/// assert_eq!(weights[&(0, 1)], 3.5);
+/// assert_eq!(weights[&(1, 2)], 0.5);
+/// ```
+///
+/// Malformed entries are rejected at compile time, such as an edge without a target
+///
+/// ```compile_fail
+/// let g: grafferous::Graph<i32, ()> = grafferous::graph! { 0 => };
+/// ```
+///
+/// an unknown kind of edge
+///
+/// ```compile_fail
+/// let g: grafferous::Graph<i32, ()> = grafferous::graph! { 0 -> 1 };
+/// ```
+///
+/// a weight without parentheses
+///
+/// ```compile_fail
+/// let (g, w): (grafferous::Graph<i32, ()>, _) = grafferous::graph! { 0 -1.0-> 1 };
+/// ```
+///
+/// two nodes without an edge or a comma between them
+///
+/// ```compile_fail
+/// let g: grafferous::Graph<i32, ()> = grafferous::graph! { 0 => 1 2 };
+/// ```
+///
+/// or data of more than one token.
+///
+/// ```compile_fail
+/// let g: grafferous::Graph<i32, i32> = grafferous::graph! { 0: -1 => 1 };
+/// ```
+#[macro_export]
+macro_rules! graph {
+    // all entries are read, build the graph and the weights if there are any
+    (@build $g:ident $weights:ident [] [$($statement:tt)*]) => {{
+        let mut $g = $crate::Graph::new();
+        $($statement)*
+        $g
+    }};
+    (@build $g:ident $weights:ident [$($weighted:tt)+] [$($statement:tt)*]) => {{
+        let mut $g = $crate::Graph::new();
+        let mut $weights = $crate::NodeMap::default();
+        $($statement)*
+        ($g, $weights)
+    }};
+
+    // sets the data of a node, adding the node if it is missing
+    (@data $g:ident $id:tt) => {};
+    (@data $g:ident $id:tt $data:tt) => {
+        // `:{data}` is the way to write longer data, not a stray block
+        #[allow(unused_braces)]
+        let data = $data;
+        match $g.node_data.get_mut(&$id) {
+            Some(existing) => *existing = data,
+            None => $g.add_node_with_data($id, data),
+        }
+    };
+
+    // reads one entry at a time, collecting the statements that build the graph
+    (@edges $g:ident $weights:ident $flags:tt $statements:tt) => {
+        $crate::graph!(@build $g $weights $flags $statements)
+    };
+    (@edges $g:ident $weights:ident $flags:tt [$($statement:tt)*]
+        $from:tt $(: $from_data:tt)? => $to:tt $($rest:tt)*
+    ) => {
+        $crate::graph!(@next $g $weights $flags [
+            $($statement)*
+            $crate::graph!(@data $g $from $($from_data)?);
+            $g.add_directed_edge($from, $to);
+        ] $to $($rest)*)
+    };
+    (@edges $g:ident $weights:ident $flags:tt [$($statement:tt)*]
+        $from:tt $(: $from_data:tt)? ; $to:tt $($rest:tt)*
+    ) => {
+        $crate::graph!(@next $g $weights $flags [
+            $($statement)*
+            $crate::graph!(@data $g $from $($from_data)?);
+            $g.add_edge($from, $to);
+        ] $to $($rest)*)
+    };
+    (@edges $g:ident $weights:ident [$($flag:tt)*] [$($statement:tt)*]
+        $from:tt $(: $from_data:tt)? -($weight:expr)-> $to:tt $($rest:tt)*
+    ) => {
+        $crate::graph!(@weighted $g $weights [$($flag)*] [$($statement)*]
+            $from [$($from_data)?] ($weight) $to $($rest)*)
+    };
+    (@edges $g:ident $weights:ident [$($flag:tt)*] [$($statement:tt)*]
+        $from:tt $(: $from_data:tt)? -($weight:expr)> $to:tt $($rest:tt)*
+    ) => {
+        $crate::graph!(@weighted $g $weights [$($flag)*] [$($statement)*]
+            $from [$($from_data)?] ($weight) $to $($rest)*)
+    };
+    (@edges $g:ident $weights:ident $flags:tt [$($statement:tt)*]
+        $id:tt $(: $data:tt)? $(, $($rest:tt)*)?
+    ) => {
+        $crate::graph!(@edges $g $weights $flags [
+            $($statement)*
+            if !$g.contains_node($id) {
+                $g.add_node($id);
+            }
+            $crate::graph!(@data $g $id $($data)?);
+        ] $($($rest)*)?)
+    };
+    (@edges $g:ident $weights:ident $flags:tt $statements:tt $($rest:tt)+) => {
+        ::core::compile_error!(::core::concat!(
+            "expected an edge or a node followed by a comma, found `",
+            ::core::stringify!($($rest)+),
+            "`"
+        ))
+    };
+
+    // a weighted edge, in either spelling of the arrow
+    (@weighted $g:ident $weights:ident [$($flag:tt)*] [$($statement:tt)*]
+        $from:tt [$($from_data:tt)?] ($weight:expr) $to:tt $($rest:tt)*
+    ) => {
+        $crate::graph!(@next $g $weights [$($flag)* weighted] [
+            $($statement)*
+            $crate::graph!(@data $g $from $($from_data)?);
+            $g.add_directed_edge($from, $to);
+            $weights.insert(($from, $to), $weight);
+        ] $to $($rest)*)
+    };
+
+    // after an edge: sets the data of its target, then either starts the next entry after a
+    // comma or continues the path from the target
+    (@next $g:ident $weights:ident $flags:tt [$($statement:tt)*]
+        $last:tt : $data:tt $($rest:tt)*
+    ) => {
+        $crate::graph!(@next $g $weights $flags [
+            $($statement)*
+            $crate::graph!(@data $g $last $data);
+        ] $last $($rest)*)
+    };
+    (@next $g:ident $weights:ident $flags:tt $statements:tt $last:tt $(, $($rest:tt)*)?) => {
+        $crate::graph!(@edges $g $weights $flags $statements $($($rest)*)?)
+    };
+    (@next $g:ident $weights:ident $flags:tt $statements:tt $last:tt $($rest:tt)+) => {
+        $crate::graph!(@edges $g $weights $flags $statements $last $($rest)+)
+    };
+
+    ($($entries:tt)*) => {
+        $crate::graph!(@edges g weights [] [] $($entries)*)
+    };
+}
+
+/// generates a grid graph with the given width and height.
+pub fn generate_grid_graph<NodeDataType: Default + Send>(
+    width: usize,
+    height: usize,
+) -> Graph<(usize, usize), NodeDataType> {
+    let mut g = Graph::new();
+
+    // list the nodes in the order they are generated rather than the order of the keys of
+    // `node_data`, which depends on the hasher
+    g.nodes = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .collect();
+
+    g.node_data = g
+        .nodes
+        .iter()
+        .map(|id| (*id, NodeDataType::default()))
+        .collect();
+
+    g.edges = g
+        .nodes
+        .iter()
+        .map(|id| {
+            let mut tos = Adjacency::new();
+            if id.0 > 0 {
+                tos.push((id.0 - 1, id.1));
+            }
+            if id.0 < width - 1 {
+                tos.push((id.0 + 1, id.1));
+            }
+            if id.1 > 0 {
+                tos.push((id.0, id.1 - 1));
+            }
+            if id.1 < height - 1 {
+                tos.push((id.0, id.1 + 1));
+            }
+            (*id, tos)
+        })
+        .collect();
+
+    // the edges were written directly, mirror them in `reverse_edges`
+    g.repair();
+    g
+}
+
+/// generates a grid graph with the given width and height and periodic boundaries: the nodes on
+/// each edge of the grid are linked to the nodes on the opposite edge, so the grid wraps around
+/// into a torus and every node has four neighbors.
+///
+/// Grids narrower than three nodes in a direction would wrap onto the same neighbor twice or
+/// onto the node itself; those links are only added once and self-loops are left out.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_grid_graph_periodic;
+///
+/// let g = generate_grid_graph_periodic::<()>(4, 3);
+/// assert!(g.nodes.iter().all(|id| g.neighbors(*id).len() == 4));
+/// assert!(g.neighbors((0, 0)).contains(&(3, 0)));
+/// assert!(g.neighbors((0, 0)).contains(&(0, 2)));
+/// ```
+pub fn generate_grid_graph_periodic<NodeDataType: Default + Send>(
+    width: usize,
+    height: usize,
+) -> Graph<(usize, usize), NodeDataType> {
+    let mut g = Graph::new();
+
+    g.nodes = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .collect();
+
+    g.node_data = g
+        .nodes
+        .iter()
+        .map(|id| (*id, NodeDataType::default()))
+        .collect();
+
+    g.edges = g
+        .nodes
+        .iter()
+        .map(|&(x, y)| {
+            let mut tos = Adjacency::new();
+            for to in [
+                ((x + width - 1) % width, y),
+                ((x + 1) % width, y),
+                (x, (y + height - 1) % height),
+                (x, (y + 1) % height),
+            ] {
+                if to != (x, y) && !tos.contains(&to) {
+                    tos.push(to);
+                }
+            }
+            ((x, y), tos)
+        })
+        .collect();
+
+    // the edges were written directly, mirror them in `reverse_edges`
+    g.repair();
+    g
+}
+
+/// generates a three dimensional grid graph with the given size along each axis, where every
+/// node `(x, y, z)` is linked to the nodes one step away along one of the axes.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_grid_graph_3d;
+///
+/// let g = generate_grid_graph_3d::<()>(3, 3, 3);
+/// assert_eq!(g.nodes.len(), 27);
+/// assert_eq!(g.neighbors((1, 1, 1)).len(), 6);
+/// assert_eq!(g.neighbors((0, 0, 0)).len(), 3);
+/// ```
+pub fn generate_grid_graph_3d<NodeDataType: Default + Send>(
+    x: usize,
+    y: usize,
+    z: usize,
+) -> Graph<(usize, usize, usize), NodeDataType> {
+    let size = [x, y, z];
+    let mut g = Graph::new();
+
+    g.nodes = (0..x)
+        .flat_map(|i| (0..y).flat_map(move |j| (0..z).map(move |k| (i, j, k))))
+        .collect();
+
+    g.node_data = g
+        .nodes
+        .iter()
+        .map(|id| (*id, NodeDataType::default()))
+        .collect();
+
+    g.edges = g
+        .nodes
+        .iter()
+        .map(|&(i, j, k)| {
+            let mut tos = Adjacency::new();
+            for axis in 0..3 {
+                let mut coordinates = [i, j, k];
+                if coordinates[axis] > 0 {
+                    coordinates[axis] -= 1;
+                    tos.push(coordinates.into());
+                    coordinates[axis] += 1;
+                }
+                if coordinates[axis] + 1 < size[axis] {
+                    coordinates[axis] += 1;
+                    tos.push(coordinates.into());
+                }
+            }
+            ((i, j, k), tos)
+        })
+        .collect();
+
+    // the edges were written directly, mirror them in `reverse_edges`
+    g.repair();
+    g
 }
 
-/// counts the number of paths from the start node to the end node.
-fn _count_paths<IDDataType, NodeDataType: Default>(
-    graph: &Graph<IDDataType, NodeDataType>,
-    start: &IDDataType,
-    end: &IDDataType,
-    max_depth: Option<usize>,
-    depth: usize,
-    mut path: Vec<IDDataType>,
-) -> usize
-where
-    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
-{
-    // function body
+/// generates a cycle graph with the nodes `0..n`, each linked to the next and the last to the
+/// first. With fewer than three nodes the cycle would need a self-loop or a parallel edge, so the
+/// graph is a path instead.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_cycle_graph;
+///
+/// let g = generate_cycle_graph::<()>(5);
+/// assert_eq!(g.neighbors(0), &[1, 4]);
+/// assert_eq!(generate_cycle_graph::<()>(2).neighbors(0), &[1]);
+/// ```
+pub fn generate_cycle_graph<NodeDataType: Default + Send>(n: usize) -> Graph<usize, NodeDataType> {
+    let mut edges: Vec<(usize, usize)> = (1..n).map(|id| (id - 1, id)).collect();
+    if n >= 3 {
+        edges.push((n - 1, 0));
+    }
+    generate_undirected_graph(n, &edges)
+}
+
+/// generates a complete graph with the nodes `0..n`, where every node is linked to every other.
+pub fn generate_complete_graph<NodeDataType: Default + Send>(
+    n: usize,
+) -> Graph<usize, NodeDataType> {
+    let edges: Vec<(usize, usize)> = (0..n)
+        .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+        .collect();
+    generate_undirected_graph(n, &edges)
+}
+
+/// generates a star graph with the nodes `0..n`: the hub `0` linked to every other node.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_star_graph;
+///
+/// let g = generate_star_graph::<()>(5);
+/// assert_eq!(g.neighbors(0), &[1, 2, 3, 4]);
+/// assert_eq!(g.neighbors(3), &[0]);
+/// ```
+pub fn generate_star_graph<NodeDataType: Default + Send>(n: usize) -> Graph<usize, NodeDataType> {
+    let edges: Vec<(usize, usize)> = (1..n).map(|leaf| (0, leaf)).collect();
+    generate_undirected_graph(n, &edges)
+}
+
+/// generates a wheel graph with the nodes `0..n`: the hub `0` linked to every node of a cycle
+/// through `1..n`. With fewer than four nodes the rim is a path rather than a cycle.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_wheel_graph;
+///
+/// let g = generate_wheel_graph::<()>(6);
+/// assert_eq!(g.neighbors(0).len(), 5);
+/// assert_eq!(g.neighbors(1), &[0, 2, 5]);
+/// ```
+pub fn generate_wheel_graph<NodeDataType: Default + Send>(n: usize) -> Graph<usize, NodeDataType> {
+    let mut edges: Vec<(usize, usize)> = (1..n).map(|rim| (0, rim)).collect();
+    edges.extend((1..n.saturating_sub(1)).map(|rim| (rim, rim + 1)));
+    if n >= 4 {
+        edges.push((n - 1, 1));
+    }
+    generate_undirected_graph(n, &edges)
+}
+
+/// generates a path graph with the nodes `0..n`, each linked to the next.
+pub fn generate_path_graph<NodeDataType: Default + Send>(n: usize) -> Graph<usize, NodeDataType> {
+    let edges: Vec<(usize, usize)> = (1..n).map(|id| (id - 1, id)).collect();
+    generate_undirected_graph(n, &edges)
+}
+
+/// generates a complete bipartite graph with the nodes `0..m` on one side and `m..m + n` on the
+/// other, where every node is linked to every node of the other side.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_complete_bipartite_graph;
+///
+/// let g = generate_complete_bipartite_graph::<()>(2, 3);
+/// assert_eq!(g.nodes.len(), 5);
+/// assert_eq!(g.neighbors(0), &[2, 3, 4]);
+/// assert_eq!(g.neighbors(4), &[0, 1]);
+/// ```
+pub fn generate_complete_bipartite_graph<NodeDataType: Default + Send>(
+    m: usize,
+    n: usize,
+) -> Graph<usize, NodeDataType> {
+    let edges: Vec<(usize, usize)> = (0..m)
+        .flat_map(|a| (m..m + n).map(move |b| (a, b)))
+        .collect();
+    generate_undirected_graph(m + n, &edges)
+}
+
+/// a graph with the nodes `0..n` in order and the given undirected edges.
+fn generate_undirected_graph<NodeDataType: Default>(
+    n: usize,
+    edges: &[(usize, usize)],
+) -> Graph<usize, NodeDataType> {
+    let mut g = Graph::new();
+    for id in 0..n {
+        g.add_node(id);
+    }
+    g.add_edges(edges);
+    g
+}
+
+/// generates a random graph with the given number of nodes and edge probability, drawing from
+/// the thread-local random number generator. Use `generate_random_graph_with_rng` for
+/// reproducible graphs.
+///
+/// # Arguments
+///
+/// * `n` - The number of nodes in the graph.
+/// * `p` - The probability of an edge from one node to another. There are no self-loops.
+///
+#[cfg(feature = "std")]
+pub fn generate_random_graph<NodeDataType: Default + Send>(
+    n: usize,
+    p: f64,
+) -> Graph<usize, NodeDataType> {
+    generate_random_graph_with_rng(n, p, rand::thread_rng())
+}
+
+/// generates a random graph with the given number of nodes and edge probability, drawing from
+/// `rng`, so that a seeded generator always gives the same graph.
+///
+/// # Arguments
+///
+/// * `n` - The number of nodes in the graph.
+/// * `p` - The probability of an edge from one node to another. There are no self-loops.
+/// * `rng` - The source of randomness for the edges, or a mutable reference to one.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_random_graph_with_rng;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let edges = |seed| {
+///     let g = generate_random_graph_with_rng::<()>(20, 0.3, StdRng::seed_from_u64(seed));
+///     let mut edges = g.edge_tuples();
+///     edges.sort();
+///     edges
+/// };
+/// assert_eq!(edges(7), edges(7));
+/// assert_ne!(edges(7), edges(8));
+/// ```
+pub fn generate_random_graph_with_rng<NodeDataType: Default + Send>(
+    n: usize,
+    p: f64,
+    mut rng: impl rand::Rng,
+) -> Graph<usize, NodeDataType> {
+    let mut g = Graph::new();
+
+    //create a hashmap of nodes
+    g.nodes = (0..n).collect();
+
+    g.node_data = g
+        .nodes
+        .iter()
+        .map(|id| (*id, NodeDataType::default()))
+        .collect();
+
+    // draw the edges in the order of the IDs rather than of `nodes`, so they only depend on `rng`
+    g.edges = (0..n)
+        .map(|id| {
+            let mut tos = Adjacency::new();
+            for to in (0..n).filter(|to| *to != id) {
+                if rng.gen::<f64>() < p {
+                    tos.push(to);
+                }
+            }
+            (id, tos)
+        })
+        .collect::<NodeMap<usize, Adjacency<usize>>>();
+
+    g.repair();
+    g
+}
+
+/// A distribution to draw random edge weights from, see `generate_random_weighted_graph` and
+/// `Graph::assign_random_weights`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightDistribution {
+    /// Uniformly distributed between `low` and `high`.
+    Uniform { low: f64, high: f64 },
+    /// Normally distributed around `mean`, drawn with the Box-Muller transform. Weights can be
+    /// negative unless the mean is several standard deviations above zero.
+    Normal { mean: f64, std_dev: f64 },
+    /// Exponentially distributed with the mean `1 / rate`, like the waiting times between
+    /// random events.
+    Exponential { rate: f64 },
+}
 
-    assert!(graph.nodes.contains(start), "graph does not contain start");
-    assert!(graph.nodes.contains(end), "graph does not contain end");
+#[cfg(feature = "std")]
+impl WeightDistribution {
+    /// Draws one weight.
+    pub fn sample<R: rand::Rng>(&self, mut rng: R) -> f64 {
+        match *self {
+            WeightDistribution::Uniform { low, high } => low + (high - low) * rng.gen::<f64>(),
+            WeightDistribution::Normal { mean, std_dev } => {
+                // `1 - u` lies in (0, 1], so the logarithm is finite
+                let u = 1.0 - rng.gen::<f64>();
+                let v = rng.gen::<f64>();
+                let z = (-2.0 * u.ln()).sqrt() * (core::f64::consts::TAU * v).cos();
+                mean + std_dev * z
+            }
+            WeightDistribution::Exponential { rate } => -(1.0 - rng.gen::<f64>()).ln() / rate,
+        }
+    }
+}
 
-    if max_depth.is_none() {
-        assert!(
-            graph.is_directed_acyclic(),
-            "graph must directed acyclic, or a depth must be given."
-        );
+/// generates a random graph with the given number of nodes and edge probability, and a random
+/// weight for every edge.
+///
+/// Every directed edge between two distinct nodes is added with the probability `p`, so there
+/// are no self-loops or parallel edges. The weights are returned like the weighted form of the
+/// `graph!` macro does.
+///
+/// # Arguments
+///
+/// * `n` - The number of nodes in the graph.
+/// * `p` - The probability of an edge from one node to another.
+/// * `weight_dist` - The distribution the weights are drawn from.
+/// * `rng` - The source of randomness for the edges and the weights.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_random_weighted_graph, WeightDistribution};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let uniform = WeightDistribution::Uniform { low: 1.0, high: 2.0 };
+/// let (g, weights) =
+///     generate_random_weighted_graph::<()>(20, 0.3, uniform, StdRng::seed_from_u64(1));
+/// assert_eq!(weights.len(), g.edge_tuples().len());
+/// assert!(weights.values().all(|w| (1.0..2.0).contains(w)));
+/// ```
+#[cfg(feature = "std")]
+pub fn generate_random_weighted_graph<NodeDataType: Default>(
+    n: usize,
+    p: f64,
+    weight_dist: WeightDistribution,
+    mut rng: impl rand::Rng,
+) -> (Graph<usize, NodeDataType>, NodeMap<(usize, usize), f64>) {
+    let mut g = Graph::new();
+    for id in 0..n {
+        g.add_node(id);
     }
+    let mut weights = NodeMap::default();
+    for from in 0..n {
+        for to in (0..n).filter(|to| *to != from) {
+            if rng.gen_bool(p) {
+                g.add_directed_edge(from, to);
+                weights.insert((from, to), weight_dist.sample(&mut rng));
+            }
+        }
+    }
+    (g, weights)
+}
 
-    path.push(end.clone());
+/// generates a random graph with planted communities from the stochastic block model. The nodes
+/// `0..n` are split into consecutive blocks of the given sizes, and every two distinct nodes are
+/// linked by an undirected edge with the probability that `p_matrix` gives for their blocks.
+///
+/// With higher probabilities on the diagonal than off it, the blocks are communities that
+/// detection algorithms such as `Graph::communities_louvain` should recover; the closer the two
+/// are, the harder the task.
+///
+/// # Arguments
+///
+/// * `block_sizes` - The number of nodes in every block; the first block is `0..block_sizes[0]`.
+/// * `p_matrix` - The probability of an edge between a node of block `a` and one of block `b`,
+///   at `p_matrix[a][b]`. Only the entries with `a <= b` are used, so the matrix is symmetric
+///   in effect.
+/// * `rng` - The source of randomness for the edges.
+///
+/// # Panics
+///
+/// Panics if `p_matrix` has fewer rows or columns than there are blocks, or if a probability is
+/// not between 0 and 1.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_stochastic_block_model;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let p = [vec![0.5, 0.01], vec![0.01, 0.5]];
+/// let g = generate_stochastic_block_model::<()>(&[30, 30], &p, StdRng::seed_from_u64(2));
+/// assert!(g.is_undirected());
+///
+/// let communities = g.communities_louvain();
+/// assert!((0..30).all(|id| communities[&id] == communities[&0]));
+/// assert!((30..60).all(|id| communities[&id] == communities[&30]));
+/// ```
+pub fn generate_stochastic_block_model<NodeDataType: Default>(
+    block_sizes: &[usize],
+    p_matrix: &[Vec<f64>],
+    mut rng: impl rand::Rng,
+) -> Graph<usize, NodeDataType> {
+    let blocks: Vec<usize> = block_sizes
+        .iter()
+        .enumerate()
+        .flat_map(|(block, size)| core::iter::repeat_n(block, *size))
+        .collect();
+    let n = blocks.len();
+    let mut edges = Vec::new();
+    for from in 0..n {
+        for to in from + 1..n {
+            if rng.gen_bool(p_matrix[blocks[from]][blocks[to]]) {
+                edges.push((from, to));
+            }
+        }
+    }
+    generate_undirected_graph(n, &edges)
+}
 
-    // base case
-    if start == end && depth > 0 {
-        return 0;
+/// generates a random directed acyclic graph with the given number of nodes, where every edge
+/// from a node to a node with a higher index is added with the probability `p`. The nodes are
+/// `0..n` in order, which is also a topological order of the graph.
+///
+/// # Arguments
+///
+/// * `n` - The number of nodes in the graph.
+/// * `p` - The probability of an edge from one node to a later one, between 0 and 1.
+/// * `rng` - The source of randomness for the edges.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_random_dag;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let g = generate_random_dag::<()>(30, 0.2, StdRng::seed_from_u64(3));
+/// assert!(g.is_directed_acyclic());
+/// assert!(g.edge_tuples().iter().all(|(from, to)| from < to));
+/// ```
+pub fn generate_random_dag<NodeDataType: Default>(
+    n: usize,
+    p: f64,
+    mut rng: impl rand::Rng,
+) -> Graph<usize, NodeDataType> {
+    let mut g = Graph::new();
+    for id in 0..n {
+        g.add_node(id);
     }
+    for from in 0..n {
+        for to in from + 1..n {
+            if rng.gen_bool(p) {
+                g.add_directed_edge(from, to);
+            }
+        }
+    }
+    g
+}
+
+/// generates a uniformly random labeled tree on the nodes `0..n`, with every edge in both
+/// directions. Every one of the `n^(n - 2)` trees is equally likely: the tree is decoded from a
+/// random Prüfer sequence.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_random_tree;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let g = generate_random_tree::<()>(50, StdRng::seed_from_u64(3));
+/// assert_eq!(g.edge_tuples().len(), 2 * 49);
+/// assert!(g.is_connected());
+/// ```
+pub fn generate_random_tree<NodeDataType: Default>(
+    n: usize,
+    mut rng: impl rand::Rng,
+) -> Graph<usize, NodeDataType> {
+    use alloc::collections::BinaryHeap;
+    use core::cmp::Reverse;
 
-    if max_depth.is_some() && depth >= max_depth.unwrap() {
-        // println!("max depth reached with start {:?} and end {:?}, depth {:?}, max depth: {:?}", start, end, depth, max_depth);
-        return 0;
+    if n < 2 {
+        return generate_undirected_graph(n, &[]);
+    }
+    let sequence: Vec<usize> = (0..n - 2).map(|_| rng.gen_range(0..n)).collect();
+    // every node appears in the sequence one time less than its degree in the tree
+    let mut degree = alloc::vec![1; n];
+    for id in &sequence {
+        degree[*id] += 1;
     }
+    let mut leaves: BinaryHeap<Reverse<usize>> =
+        (0..n).filter(|id| degree[*id] == 1).map(Reverse).collect();
+    let mut edges = Vec::with_capacity(n - 1);
+    for id in sequence {
+        let Reverse(leaf) = leaves.pop().expect("a tree has at least two leaves");
+        edges.push((leaf, id));
+        degree[id] -= 1;
+        if degree[id] == 1 {
+            leaves.push(Reverse(id));
+        }
+    }
+    let Reverse(a) = leaves.pop().expect("two leaves are left");
+    let Reverse(b) = leaves.pop().expect("two leaves are left");
+    edges.push((a, b));
+    generate_undirected_graph(n, &edges)
+}
+
+/// generates a triangular lattice with the given width and height, where every inner node has six
+/// neighbors.
+///
+/// Nodes are `(x, y)` coordinates in offset rows, as drawn by `layout::lattice` with
+/// `LatticeKind::Triangular`: every odd row is shifted right by half a spacing. A node is linked
+/// to `(x ± 1, y)` in its row and to `(x, y ± 1)` and `(x + 1, y ± 1)` if `y` is odd, or
+/// `(x - 1, y ± 1)` if `y` is even, in the rows above and below.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_triangular_grid_graph;
+///
+/// let g = generate_triangular_grid_graph::<()>(4, 4);
+/// assert_eq!(g.neighbors((1, 1)).len(), 6);
+/// assert!(g.neighbors((1, 1)).contains(&(2, 2)));
+/// assert!(g.neighbors((1, 2)).contains(&(0, 1)));
+/// ```
+pub fn generate_triangular_grid_graph<NodeDataType: Default + Send>(
+    width: usize,
+    height: usize,
+) -> Graph<(usize, usize), NodeDataType> {
+    let mut g = Graph::new();
 
-    let mut paths = 0;
+    g.nodes = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .collect();
 
-    let reverse_neighbors = graph.reverse_neighbors(*end);
+    g.node_data = g
+        .nodes
+        .iter()
+        .map(|id| (*id, NodeDataType::default()))
+        .collect();
 
-    for reverse_neighbor in reverse_neighbors {
-        // println!("reverse neighbor: {:?}", reverse_neighbor);
-        if reverse_neighbor == start {
-            path.push(reverse_neighbor.clone());
-            println!("path: {:?}", path);
-            paths += 1;
-        } else {
-            // path.push(reverse_neighbor.clone());
-            paths += _count_paths(
-                graph,
-                start,
-                reverse_neighbor,
-                max_depth,
-                depth + 1,
-                path.clone(),
-            );
+    g.edges = g
+        .nodes
+        .iter()
+        .map(|&(x, y)| {
+            // the column of the diagonal neighbors in the rows above and below
+            let diagonal = if y % 2 == 1 {
+                x.checked_add(1).filter(|x| *x < width)
+            } else {
+                x.checked_sub(1)
+            };
+            let mut tos = Adjacency::new();
+            let columns = [x.checked_sub(1), x.checked_add(1).filter(|x| *x < width)];
+            tos.extend(columns.into_iter().flatten().map(|x| (x, y)));
+            for row in [y.checked_sub(1), y.checked_add(1).filter(|y| *y < height)]
+                .into_iter()
+                .flatten()
+            {
+                tos.push((x, row));
+                if let Some(diagonal) = diagonal {
+                    tos.push((diagonal, row));
+                }
+            }
+            ((x, y), tos)
+        })
+        .collect();
+
+    // the edges were written directly, mirror them in `reverse_edges`
+    g.repair();
+    g
+}
+
+/// generates a hexagonal (honeycomb) lattice with the given width and height, where every inner
+/// node has three neighbors.
+///
+/// Nodes are `(x, y)` coordinates in brick-wall form, as drawn by `layout::lattice` with
+/// `LatticeKind::Hexagonal`: every row is a zigzag chain linking `(x, y)` to `(x ± 1, y)`, and
+/// `(x, y)` is linked to `(x, y + 1)` when `x + y` is odd. Every hexagon is then made of three
+/// consecutive nodes in each of two neighboring rows.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::generate_hex_grid_graph;
+///
+/// let g = generate_hex_grid_graph::<()>(5, 4);
+/// assert_eq!(g.neighbors((2, 1)).len(), 3);
+/// assert!(g.neighbors((2, 1)).contains(&(2, 2)));
+/// assert!(!g.neighbors((2, 2)).contains(&(2, 3)));
+/// ```
+pub fn generate_hex_grid_graph<NodeDataType: Default + Send>(
+    width: usize,
+    height: usize,
+) -> Graph<(usize, usize), NodeDataType> {
+    let mut g = Graph::new();
+
+    g.nodes = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .collect();
+
+    g.node_data = g
+        .nodes
+        .iter()
+        .map(|id| (*id, NodeDataType::default()))
+        .collect();
+
+    g.edges = g
+        .nodes
+        .iter()
+        .map(|&(x, y)| {
+            let mut tos = Adjacency::new();
+            if x > 0 {
+                tos.push((x - 1, y));
+            }
+            if x < width - 1 {
+                tos.push((x + 1, y));
+            }
+            // the rungs between rows alternate, so every node has one
+            if (x + y) % 2 == 1 && y < height - 1 {
+                tos.push((x, y + 1));
+            }
+            if (x + y) % 2 == 0 && y > 0 {
+                tos.push((x, y - 1));
+            }
+            ((x, y), tos)
+        })
+        .collect();
+
+    // the edges were written directly, mirror them in `reverse_edges`
+    g.repair();
+    g
+}
+
+/// Counts the paths from `start` to `end`, the trivial path from a node to itself included.
+/// Paths cannot return to `start`, so the count from a node to itself is always 1, even on a
+/// cycle and with a `max_depth` of 0.
+///
+/// Without `max_depth` the graph must not have a cycle on any path to `end`, and the paths are
+/// counted by dynamic programming over the predecessors of `end`, remembering the count of every
+/// node, which takes O(V + E) time. With `max_depth`, the paths may repeat nodes other than
+/// `start` and have at most `max_depth` edges; they are counted layer by layer in O(max_depth·E)
+/// time, so cycles are allowed.
+///
+/// # Panics
+///
+/// Panics if `start` or `end` is not in the graph, or if no `max_depth` is given and a cycle lies
+/// on a path to `end`.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{count_paths, Graph};
+///
+/// let g = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3; 1 -> 2").unwrap();
+/// assert_eq!(count_paths(&g, &0, &3, None), 3);
+/// assert_eq!(count_paths(&g, &0, &3, Some(2)), 2);
+///
+/// let cycle = Graph::<u32, ()>::parse("0 -- 1 -- 2").unwrap();
+/// assert_eq!(count_paths(&cycle, &0, &2, Some(4)), 2);
+/// ```
+pub fn count_paths<IDDataType, NodeDataType: Default, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    start: &IDDataType,
+    end: &IDDataType,
+    max_depth: Option<usize>,
+) -> usize
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    assert!(
+        graph.node_data.contains_key(start),
+        "graph does not contain start"
+    );
+    assert!(
+        graph.node_data.contains_key(end),
+        "graph does not contain end"
+    );
+    if start == end {
+        return 1;
+    }
+    match max_depth {
+        Some(max_depth) => count_bounded_paths(graph, *start, *end, max_depth),
+        None => count_acyclic_paths(graph, *start, *end),
+    }
+}
+
+/// counts the paths from `start` to `end` by memoized depth-first search over the predecessors of
+/// `end`, panicking on a cycle.
+fn count_acyclic_paths<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    start: IDDataType,
+    end: IDDataType,
+) -> usize
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let mut paths: NodeMap<IDDataType, usize> = NodeMap::default();
+    paths.insert(start, 1);
+    // the nodes whose count is being computed, with the position of their next predecessor
+    let mut stack = Vec::from([(end, 0)]);
+    let mut in_progress = NodeSet::default();
+    in_progress.insert(end);
+    while let Some((node, next)) = stack.last_mut() {
+        let node = *node;
+        let predecessors = graph.reverse_neighbors(node);
+        if let Some(predecessor) = predecessors.get(*next) {
+            *next += 1;
+            if !paths.contains_key(predecessor) {
+                assert!(
+                    in_progress.insert(*predecessor),
+                    "graph must directed acyclic, or a depth must be given."
+                );
+                stack.push((*predecessor, 0));
+            }
+            continue;
         }
+        stack.pop();
+        in_progress.remove(&node);
+        let count = predecessors.iter().map(|p| paths[p]).sum();
+        paths.insert(node, count);
     }
+    paths[&end]
+}
 
-    paths
+/// counts the walks from `start` to `end` with at most `max_depth` edges that do not return to
+/// `start`, one layer of walk lengths at a time.
+fn count_bounded_paths<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    start: IDDataType,
+    end: IDDataType,
+    max_depth: usize,
+) -> usize
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let mut total = 0;
+    let mut layer: NodeMap<IDDataType, usize> = NodeMap::default();
+    layer.insert(start, 1);
+    for _ in 0..max_depth {
+        let mut next: NodeMap<IDDataType, usize> = NodeMap::default();
+        for (node, walks) in &layer {
+            for to in graph.neighbors(*node).iter().filter(|to| **to != start) {
+                *next.entry(*to).or_default() += walks;
+            }
+        }
+        total += next.get(&end).copied().unwrap_or_default();
+        if next.is_empty() {
+            break;
+        }
+        layer = next;
+    }
+    total
 }
 
 pub fn find_circuits<'a, Node, NodeDataType: Default>(
     graph: &'a Graph<Node, NodeDataType>,
     start: &'a Node,
-    max_length:usize,
-) -> Vec<(Node,Node)>
+    max_length: usize,
+) -> Vec<(Node, Node)>
 where
     Node: Debug + PartialEq + Eq + Hash + Clone + Copy,
 {
     let mut circuits = Vec::new();
     let mut stack = Vec::new();
-    let mut visited = HashSet::new();
+    let mut visited = NodeSet::default();
 
-    stack.push((*start, *start,0));
+    stack.push((*start, *start, 0));
 
-    while let Some((start, end,length)) = stack.pop() {
+    while let Some((start, end, length)) = stack.pop() {
         if length >= max_length {
             continue;
         }
-        
-
-    
 
         visited.insert(end);
 
-        for neighbor in graph.neighbors(end) {
-            if neighbor == start && length > 0{
-                // println!("found circuit: {:?} -> {:?}", start, end);
-                circuits.push((start,neighbor));
+        for &neighbor in graph.neighbors(end) {
+            if neighbor == start && length > 0 {
+                log::trace!("found circuit {:?} -> {:?}", start, end);
+                circuits.push((start, neighbor));
             } else {
-                stack.push( (start,neighbor, length+1));
+                stack.push((start, neighbor, length + 1));
             }
         }
     }
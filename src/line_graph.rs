@@ -0,0 +1,92 @@
+//! The line graph, which turns the edges of a graph into nodes.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The line graph: one node per edge, identified by its endpoints, and an edge between two of
+    /// them wherever the edges they stand for meet, so that coloring its nodes colors the edges of
+    /// this graph.
+    ///
+    /// For a directed graph, there is a directed edge from `(u, v)` to `(v, w)` for every two
+    /// edges that form a path `u -> v -> w`; a self-loop is a path with itself. If the graph is
+    /// undirected, every undirected edge becomes one node, with the endpoint that comes first in
+    /// `nodes` first, and two of them are linked by an undirected edge if they share an endpoint.
+    /// Parallel edges become one node either way. The nodes are in the order of `edge_tuples`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let path = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 3").unwrap();
+    /// let line = path.line_graph();
+    /// assert_eq!(line.nodes, vec![(0, 1), (1, 2), (2, 3)]);
+    /// assert_eq!(line.edge_tuples(), vec![((0, 1), (1, 2)), ((1, 2), (2, 3))]);
+    ///
+    /// // the line graph of a star is complete
+    /// let star = Graph::<u32, ()>::parse("0 -- 1; 0 -- 2; 0 -- 3").unwrap();
+    /// let line = star.line_graph();
+    /// assert_eq!(line.nodes, vec![(0, 1), (0, 2), (0, 3)]);
+    /// assert!(line.is_undirected());
+    /// assert_eq!(line.neighbors((0, 2)), &[(0, 1), (0, 3)]);
+    /// ```
+    pub fn line_graph(&self) -> Graph<(IDDataType, IDDataType), ()> {
+        let mut line = Graph::new();
+        if self.is_undirected() {
+            let position: NodeMap<IDDataType, usize> = self
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (*id, i))
+                .collect();
+            // the undirected edges at every node, in the order they become nodes
+            let mut incident: NodeMap<IDDataType, Vec<(IDDataType, IDDataType)>> =
+                NodeMap::default();
+            for (from, to) in self.edges_iter() {
+                if position[&from] > position[&to] || line.contains_node((from, to)) {
+                    continue;
+                }
+                line.add_node((from, to));
+                incident.entry(from).or_default().push((from, to));
+                if from != to {
+                    incident.entry(to).or_default().push((from, to));
+                }
+            }
+            // two edges share at most both endpoints, then they are the same node
+            for node in &self.nodes {
+                let Some(edges) = incident.get(node) else {
+                    continue;
+                };
+                for (i, a) in edges.iter().enumerate() {
+                    for b in &edges[i + 1..] {
+                        line.add_edge(*a, *b);
+                    }
+                }
+            }
+        } else {
+            for edge in self.edges_iter() {
+                if !line.contains_node(edge) {
+                    line.add_node(edge);
+                }
+            }
+            let mut linked = NodeSet::default();
+            for (u, v) in self.edges_iter() {
+                for w in self.neighbors(v) {
+                    if linked.insert(((u, v), (v, *w))) {
+                        line.add_directed_edge((u, v), (v, *w));
+                    }
+                }
+            }
+        }
+        line
+    }
+}
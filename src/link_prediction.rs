@@ -0,0 +1,160 @@
+//! Similarity scores between pairs of nodes for predicting missing or future links.
+//!
+//! The scores are all computed from the neighborhoods of the two nodes, ignoring edge directions:
+//! pairs with many or rare shared neighbors, or with high degrees, are likely to be linked.
+//!
+//! # Example
+//!
+//! ```
+//! use grafferous::{
+//!     link_prediction::{LinkPredictor, Similarity},
+//!     Graph,
+//! };
+//!
+//! let g = Graph::<u32, ()>::parse("0 -- 1 -- 2; 0 -- 3 -- 2; 0 -- 4").unwrap();
+//! let predictor = LinkPredictor::new(&g);
+//!
+//! assert_eq!(predictor.score(0, 2, Similarity::CommonNeighbors), 2.0);
+//! assert_eq!(predictor.score(0, 2, Similarity::Jaccard), 2.0 / 3.0);
+//! assert_eq!(predictor.top_k(0, 1, Similarity::AdamicAdar), vec![(2, 2.0 / 2f64.ln())]);
+//! ```
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// How [`LinkPredictor`] scores a pair of nodes `a` and `b` with the neighborhoods `N(a)` and
+/// `N(b)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Similarity {
+    /// The number of shared neighbors, `|N(a) ∩ N(b)|`.
+    CommonNeighbors,
+    /// The fraction of all neighbors that are shared, `|N(a) ∩ N(b)| / |N(a) ∪ N(b)|`, or zero if
+    /// both nodes have no neighbors.
+    Jaccard,
+    /// The shared neighbors weighted by how rare they are, the sum of `1 / ln |N(z)|` over the
+    /// shared neighbors `z`.
+    AdamicAdar,
+    /// The product of the degrees, `|N(a)| · |N(b)|`, following the idea that hubs attract new
+    /// links.
+    PreferentialAttachment,
+}
+
+/// The undirected neighborhoods of a graph, collected once to score many pairs of nodes.
+#[derive(Debug, Clone)]
+pub struct LinkPredictor<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    nodes: Vec<IDDataType>,
+    index: NodeMap<IDDataType, usize>,
+    neighbors: NodeMap<IDDataType, NodeSet<IDDataType>>,
+}
+
+impl<IDDataType> LinkPredictor<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Collects the neighbors of every node, in either edge direction and without the node
+    /// itself.
+    pub fn new<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self {
+        let mut neighbors: NodeMap<IDDataType, NodeSet<IDDataType>> = graph
+            .nodes
+            .iter()
+            .map(|id| (*id, NodeSet::default()))
+            .collect();
+        for (from, tos) in &graph.edges {
+            for to in tos.iter().filter(|to| *to != from) {
+                if neighbors.contains_key(from) && neighbors.contains_key(to) {
+                    neighbors.entry(*from).or_default().insert(*to);
+                    neighbors.entry(*to).or_default().insert(*from);
+                }
+            }
+        }
+        Self {
+            nodes: graph.nodes.clone(),
+            index: graph
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (*id, i))
+                .collect(),
+            neighbors,
+        }
+    }
+
+    /// The score of the pair `a`, `b`; zero if either node is not in the graph.
+    pub fn score(&self, a: IDDataType, b: IDDataType, similarity: Similarity) -> f64 {
+        let (Some(na), Some(nb)) = (self.neighbors.get(&a), self.neighbors.get(&b)) else {
+            return 0.0;
+        };
+        // intersections scan the smaller neighborhood
+        let (small, large) = if na.len() <= nb.len() {
+            (na, nb)
+        } else {
+            (nb, na)
+        };
+        let shared = small.iter().filter(|z| large.contains(*z));
+        match similarity {
+            Similarity::CommonNeighbors => shared.count() as f64,
+            Similarity::Jaccard => {
+                let common = shared.count();
+                let union = na.len() + nb.len() - common;
+                if union == 0 {
+                    0.0
+                } else {
+                    common as f64 / union as f64
+                }
+            }
+            // a shared neighbor has at least the degree 2, so the logarithm is positive
+            Similarity::AdamicAdar => shared
+                .map(|z| 1.0 / (self.neighbors[z].len() as f64).ln())
+                .sum(),
+            Similarity::PreferentialAttachment => (na.len() * nb.len()) as f64,
+        }
+    }
+
+    /// The scores of many pairs, in the order given.
+    pub fn scores(&self, pairs: &[(IDDataType, IDDataType)], similarity: Similarity) -> Vec<f64> {
+        pairs
+            .iter()
+            .map(|(a, b)| self.score(*a, *b, similarity))
+            .collect()
+    }
+
+    /// The `k` nodes not yet adjacent to `node` with the highest scores, best first, with ties
+    /// going to the node that comes first in the graph. Only nodes with a positive score are
+    /// returned; for the neighborhood based scores these are the nodes two steps away.
+    pub fn top_k(
+        &self,
+        node: IDDataType,
+        k: usize,
+        similarity: Similarity,
+    ) -> Vec<(IDDataType, f64)> {
+        let Some(own) = self.neighbors.get(&node) else {
+            return Vec::new();
+        };
+        let candidates: NodeSet<IDDataType> = match similarity {
+            Similarity::PreferentialAttachment => self.nodes.iter().copied().collect(),
+            _ => own
+                .iter()
+                .flat_map(|z| self.neighbors[z].iter().copied())
+                .collect(),
+        };
+        let mut scored: Vec<(IDDataType, f64)> = candidates
+            .into_iter()
+            .filter(|id| *id != node && !own.contains(id))
+            .map(|id| (id, self.score(node, id, similarity)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        scored.sort_by(|(a, x), (b, y)| y.total_cmp(x).then(self.index[a].cmp(&self.index[b])));
+        scored.truncate(k);
+        scored
+    }
+}
@@ -0,0 +1,297 @@
+//! Dense adjacency matrices, the bridge to linear algebra libraries for spectral analysis and
+//! the like.
+//!
+//! Row `i` of a matrix holds the edges leaving the `i`-th node and column `j` the edges entering
+//! the `j`-th node, with the nodes in the order of the accompanying list of IDs.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{collections::NodeMap, Graph};
+
+/// An error while building a graph from an adjacency matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError<IDDataType> {
+    /// The matrix has `rows` rows but there are `ids` node IDs.
+    RowCount { rows: usize, ids: usize },
+    /// Row `row` has `len` entries but there are `ids` node IDs.
+    RowLength { row: usize, len: usize, ids: usize },
+    /// The ID is given for more than one row.
+    DuplicateNode(IDDataType),
+    /// The matrix has `rows` rows but `columns` columns.
+    NotSquare { rows: usize, columns: usize },
+    /// The matrix of an undirected graph differs from its transpose at entry `(row, column)`.
+    Asymmetric { row: usize, column: usize },
+}
+
+impl<IDDataType: Debug> core::fmt::Display for MatrixError<IDDataType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MatrixError::RowCount { rows, ids } => {
+                write!(f, "the matrix has {rows} rows for {ids} node IDs")
+            }
+            MatrixError::RowLength { row, len, ids } => {
+                write!(f, "row {row} has {len} entries for {ids} node IDs")
+            }
+            MatrixError::DuplicateNode(id) => write!(f, "node {id:?} is given twice"),
+            MatrixError::NotSquare { rows, columns } => {
+                write!(f, "the matrix has {rows} rows but {columns} columns")
+            }
+            MatrixError::Asymmetric { row, column } => write!(
+                f,
+                "entry ({row}, {column}) differs from entry ({column}, {row}) of an undirected graph"
+            ),
+        }
+    }
+}
+
+impl<IDDataType: Debug> core::error::Error for MatrixError<IDDataType> {}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The adjacency matrix of the graph and the node IDs of its rows and columns, in the order
+    /// of `nodes`. Entry `(i, j)` is the number of directed edges from the `i`-th to the `j`-th
+    /// node, so an undirected graph has a symmetric matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -> 2").unwrap();
+    /// let (ids, matrix) = g.to_adjacency_matrix();
+    /// assert_eq!(ids, vec![0, 1, 2]);
+    /// assert_eq!(
+    ///     matrix,
+    ///     vec![
+    ///         vec![0.0, 1.0, 0.0],
+    ///         vec![1.0, 0.0, 1.0],
+    ///         vec![0.0, 0.0, 0.0],
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_adjacency_matrix(&self) -> (Vec<IDDataType>, Vec<Vec<f64>>) {
+        self.to_adjacency_matrix_by(|_, _, _| 1.0)
+    }
+
+    /// The adjacency matrix of the graph with entry `(i, j)` the sum of `weight` over the directed
+    /// edges from the `i`-th to the `j`-th node, given the two nodes of an edge and its data, and
+    /// the node IDs of its rows and columns in the order of `nodes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, (), f64>::new();
+    /// g.add_directed_edge_with_data(0, 1, 2.5);
+    /// g.add_directed_edge(1, 0);
+    ///
+    /// let (_, matrix) = g.to_adjacency_matrix_by(|_, _, weight| weight.copied().unwrap_or(1.0));
+    /// assert_eq!(matrix, vec![vec![0.0, 2.5], vec![1.0, 0.0]]);
+    /// ```
+    pub fn to_adjacency_matrix_by(
+        &self,
+        mut weight: impl FnMut(IDDataType, IDDataType, Option<&EdgeDataType>) -> f64,
+    ) -> (Vec<IDDataType>, Vec<Vec<f64>>) {
+        let n = self.nodes.len();
+        let index: NodeMap<IDDataType, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let mut matrix = vec![vec![0.0; n]; n];
+        for (i, from) in self.nodes.iter().enumerate() {
+            for to in self.neighbors(*from) {
+                if let Some(&j) = index.get(to) {
+                    matrix[i][j] += weight(*from, *to, self.edge_data(*from, *to));
+                }
+            }
+        }
+        (self.nodes.clone(), matrix)
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Default,
+    EdgeDataType: Clone + Default + PartialEq,
+{
+    /// A graph with the nodes `ids`, in that order and with the default data, and a directed edge
+    /// from the `i`-th to the `j`-th node for every entry `(i, j)` of `matrix` that is not the
+    /// default value of its type, such as zero or `false`. The entry becomes the data of the
+    /// edge, so a weighted matrix gives a weighted graph.
+    ///
+    /// Fails if the matrix is not square with one row and column per ID or if an ID is given
+    /// twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let matrix = vec![vec![0.0, 0.5], vec![2.0, 0.0]];
+    /// let g = Graph::<char, (), f64>::from_adjacency_matrix(&matrix, &['x', 'y']).unwrap();
+    /// assert_eq!(g.edge_data('x', 'y'), Some(&0.5));
+    /// assert_eq!(g.edge_data('y', 'x'), Some(&2.0));
+    /// assert_eq!(g.to_adjacency_matrix_by(|_, _, w| *w.unwrap()).1, matrix);
+    /// ```
+    pub fn from_adjacency_matrix(
+        matrix: &[Vec<EdgeDataType>],
+        ids: &[IDDataType],
+    ) -> Result<Self, MatrixError<IDDataType>> {
+        let n = ids.len();
+        if matrix.len() != n {
+            return Err(MatrixError::RowCount {
+                rows: matrix.len(),
+                ids: n,
+            });
+        }
+        if let Some((row, entries)) = matrix.iter().enumerate().find(|(_, row)| row.len() != n) {
+            return Err(MatrixError::RowLength {
+                row,
+                len: entries.len(),
+                ids: n,
+            });
+        }
+        let mut graph = Self::new();
+        for id in ids {
+            if graph.try_add_node(*id).is_err() {
+                return Err(MatrixError::DuplicateNode(*id));
+            }
+        }
+        let zero = EdgeDataType::default();
+        for (from, row) in ids.iter().zip(matrix) {
+            for (to, entry) in ids.iter().zip(row) {
+                if *entry != zero {
+                    graph.add_directed_edge_with_data(*from, *to, entry.clone());
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+impl<NodeDataType, EdgeDataType> Graph<usize, NodeDataType, EdgeDataType>
+where
+    NodeDataType: Default,
+    EdgeDataType: Clone + Default + PartialEq,
+{
+    /// A graph with the nodes `0..n` for an `n` by `n` matrix and an edge from `i` to `j` for
+    /// every entry `(i, j)` that is not the default value of its type, holding the entry as its
+    /// data, like `from_adjacency_matrix` with the row numbers as IDs.
+    ///
+    /// If `undirected` is set, the matrix must be symmetric and every pair of entries `(i, j)` and
+    /// `(j, i)` becomes one undirected edge; a diagonal entry becomes a single self-loop. Fails if
+    /// the matrix is not square or, for an undirected graph, not symmetric.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{matrix::MatrixError, Graph};
+    ///
+    /// let distances = vec![
+    ///     vec![0.0, 1.5, 0.0],
+    ///     vec![1.5, 0.0, 2.0],
+    ///     vec![0.0, 2.0, 0.0],
+    /// ];
+    /// let g = Graph::<usize, (), f64>::from_matrix(&distances, true).unwrap();
+    /// assert_eq!(g.nodes, vec![0, 1, 2]);
+    /// assert!(g.is_undirected());
+    /// assert_eq!(g.edge_data(2, 1), Some(&2.0));
+    ///
+    /// let flows = vec![vec![0.0, 1.0], vec![0.0, 0.0]];
+    /// assert_eq!(
+    ///     Graph::<usize, (), f64>::from_matrix(&flows, true).unwrap_err(),
+    ///     MatrixError::Asymmetric { row: 0, column: 1 }
+    /// );
+    /// assert_eq!(Graph::<usize, (), f64>::from_matrix(&flows, false).unwrap().edge_count(), 1);
+    /// ```
+    pub fn from_matrix(
+        matrix: &[Vec<EdgeDataType>],
+        undirected: bool,
+    ) -> Result<Self, MatrixError<usize>> {
+        let n = matrix.len();
+        if let Some((row, entries)) = matrix.iter().enumerate().find(|(_, row)| row.len() != n) {
+            return Err(MatrixError::RowLength {
+                row,
+                len: entries.len(),
+                ids: n,
+            });
+        }
+        Self::from_entries(n, |i, j| &matrix[i][j], undirected)
+    }
+
+    /// Like `from_matrix`, for a two-dimensional `ndarray` array such as a matrix returned by
+    /// numerical code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let matrix = ndarray::array![[0, 3], [1, 0]];
+    /// let g = Graph::<usize, (), i32>::from_ndarray(&matrix, false).unwrap();
+    /// assert_eq!(g.edge_data(0, 1), Some(&3));
+    /// assert_eq!(g.edge_data(1, 0), Some(&1));
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn from_ndarray<S>(
+        matrix: &ndarray::ArrayBase<S, ndarray::Ix2>,
+        undirected: bool,
+    ) -> Result<Self, MatrixError<usize>>
+    where
+        S: ndarray::Data<Elem = EdgeDataType>,
+    {
+        let (rows, columns) = matrix.dim();
+        if rows != columns {
+            return Err(MatrixError::NotSquare { rows, columns });
+        }
+        Self::from_entries(rows, |i, j| &matrix[[i, j]], undirected)
+    }
+
+    /// the graph of the `n` by `n` matrix with the given entries.
+    fn from_entries<'a>(
+        n: usize,
+        entry: impl Fn(usize, usize) -> &'a EdgeDataType,
+        undirected: bool,
+    ) -> Result<Self, MatrixError<usize>>
+    where
+        EdgeDataType: 'a,
+    {
+        if undirected {
+            for row in 0..n {
+                for column in row + 1..n {
+                    if entry(row, column) != entry(column, row) {
+                        return Err(MatrixError::Asymmetric { row, column });
+                    }
+                }
+            }
+        }
+        let mut graph = Self::with_capacity(n);
+        for id in 0..n {
+            graph.add_node(id);
+        }
+        let zero = EdgeDataType::default();
+        for from in 0..n {
+            // the entries below the diagonal of a symmetric matrix repeat the ones above it
+            let first = if undirected { from } else { 0 };
+            for to in first..n {
+                let data = entry(from, to);
+                if *data == zero {
+                    continue;
+                }
+                if undirected && from != to {
+                    graph.add_edge_with_data(from, to, data.clone());
+                } else {
+                    graph.add_directed_edge_with_data(from, to, data.clone());
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
@@ -0,0 +1,166 @@
+//! How nodes of different degrees connect: the rich-club coefficient and degree correlations.
+//!
+//! All measures treat the graph as a simple undirected graph, ignoring edge directions, parallel
+//! edges and self-loops.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use rand::Rng;
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// the number of swap attempts per edge when rewiring for the normalized rich-club coefficient.
+const SWAPS_PER_EDGE: usize = 10;
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The rich-club coefficient: the fraction of possible edges among the nodes with a degree
+    /// above `k` that exist, or `None` if there are fewer than two such nodes.
+    ///
+    /// On its own the coefficient grows with `k` even in random graphs, since nodes of high
+    /// degree are more likely to be linked; use
+    /// [`Graph::normalized_rich_club_coefficient`] to tell whether the hubs prefer each other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // hubs 0, 1 and 2 form a triangle and each has two leaves
+    /// let g = Graph::<u32, ()>::parse(
+    ///     "0 -- 1 -- 2 -- 0; 0 -- 3; 0 -- 4; 1 -- 5; 1 -- 6; 2 -- 7; 2 -- 8",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(g.rich_club_coefficient(1), Some(1.0));
+    /// assert_eq!(g.rich_club_coefficient(0), Some(9.0 / 36.0));
+    /// assert_eq!(g.rich_club_coefficient(4), None);
+    /// ```
+    pub fn rich_club_coefficient(&self, k: usize) -> Option<f64> {
+        rich_club(&self.undirected_sets(), k)
+    }
+
+    /// The rich-club coefficient divided by its mean over `randomizations` random graphs with the
+    /// same degrees, or `None` if it is undefined for the graph or all random graphs have no
+    /// edges among the nodes above degree `k`.
+    ///
+    /// The random graphs are made by repeated double edge swaps, which replace the edges
+    /// `a - b` and `c - d` by `a - d` and `c - b` unless that creates a self-loop or a parallel
+    /// edge, ten attempts per edge. A value above one means that the nodes of high degree are more
+    /// tightly linked than their degrees alone explain.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The degree the nodes of the club must exceed.
+    /// * `randomizations` - The number of random graphs to average over.
+    /// * `rng` - The source of randomness for the swaps.
+    ///
+    pub fn normalized_rich_club_coefficient<R: Rng>(
+        &self,
+        k: usize,
+        randomizations: usize,
+        mut rng: R,
+    ) -> Option<f64> {
+        let adjacency = self.undirected_sets();
+        let observed = rich_club(&adjacency, k)?;
+        let mut total = 0.0;
+        for _ in 0..randomizations {
+            let mut random = adjacency.clone();
+            rewire_preserving_degrees(&mut random, &mut rng);
+            total += rich_club(&random, k)?;
+        }
+        let expected = total / randomizations as f64;
+        (expected > 0.0).then(|| observed / expected)
+    }
+
+    /// The joint degree distribution: the number of edges between a node of degree `k` and a
+    /// node of degree `l`, keyed by `(k, l)` with `k <= l`.
+    pub fn joint_degree_distribution(&self) -> NodeMap<(usize, usize), usize> {
+        let adjacency = self.undirected_sets();
+        let mut joint = NodeMap::default();
+        for (u, neighbors) in adjacency.iter().enumerate() {
+            for v in neighbors.iter().filter(|v| u < **v) {
+                let (a, b) = (adjacency[u].len(), adjacency[*v].len());
+                *joint.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        joint
+    }
+
+    /// The degree correlation profile: for every degree `k` that occurs, the mean degree of the
+    /// neighbors of the nodes of degree `k`. A profile that rises with `k` marks an assortative
+    /// graph in which hubs link to hubs, a falling one a disassortative graph. Nodes without
+    /// neighbors are left out.
+    pub fn average_neighbor_degree(&self) -> NodeMap<usize, f64> {
+        let adjacency = self.undirected_sets();
+        let mut sums: NodeMap<usize, (usize, usize)> = NodeMap::default();
+        for neighbors in adjacency.iter().filter(|n| !n.is_empty()) {
+            let entry = sums.entry(neighbors.len()).or_default();
+            entry.0 += neighbors.iter().map(|v| adjacency[*v].len()).sum::<usize>();
+            entry.1 += neighbors.len();
+        }
+        sums.into_iter()
+            .map(|(k, (total, count))| (k, total as f64 / count as f64))
+            .collect()
+    }
+}
+
+/// the rich-club coefficient of an undirected adjacency.
+fn rich_club(adjacency: &[NodeSet<usize>], k: usize) -> Option<f64> {
+    let club: Vec<usize> = (0..adjacency.len())
+        .filter(|u| adjacency[*u].len() > k)
+        .collect();
+    let n = club.len();
+    if n < 2 {
+        return None;
+    }
+    let links: usize = club
+        .iter()
+        .map(|u| {
+            adjacency[*u]
+                .iter()
+                .filter(|v| adjacency[**v].len() > k)
+                .count()
+        })
+        .sum();
+    // every link was counted from both ends
+    Some(links as f64 / (n * (n - 1)) as f64)
+}
+
+/// randomizes an undirected adjacency by double edge swaps that keep every degree.
+fn rewire_preserving_degrees<R: Rng>(adjacency: &mut [NodeSet<usize>], rng: &mut R) {
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (u, neighbors) in adjacency.iter().enumerate() {
+        edges.extend(neighbors.iter().filter(|v| u < **v).map(|v| (u, *v)));
+    }
+    // sets iterate in an arbitrary order, so sort for swaps that only depend on the RNG
+    edges.sort_unstable();
+    if edges.len() < 2 {
+        return;
+    }
+    for _ in 0..SWAPS_PER_EDGE * edges.len() {
+        let (i, j) = (rng.gen_range(0..edges.len()), rng.gen_range(0..edges.len()));
+        let (a, b) = edges[i];
+        let (mut c, mut d) = edges[j];
+        if rng.gen_bool(0.5) {
+            (c, d) = (d, c);
+        }
+        if i == j || a == d || c == b || adjacency[a].contains(&d) || adjacency[c].contains(&b) {
+            continue;
+        }
+        adjacency[a].remove(&b);
+        adjacency[b].remove(&a);
+        adjacency[c].remove(&d);
+        adjacency[d].remove(&c);
+        adjacency[a].insert(d);
+        adjacency[d].insert(a);
+        adjacency[c].insert(b);
+        adjacency[b].insert(c);
+        edges[i] = (a, d);
+        edges[j] = (c, b);
+    }
+}
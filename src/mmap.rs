@@ -0,0 +1,316 @@
+use core::hash::Hash;
+use memmap2::Mmap;
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{self, BufWriter, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+use crate::{
+    visit::{Neighbors, Nodes},
+    Graph,
+};
+
+const MAGIC: &[u8; 8] = b"GRAFCSR1";
+const HEADER_LEN: usize = 24;
+
+/// Node ID types that can be stored in a memory-mapped graph file.
+///
+/// IDs are stored as 64 bit integers, so this is implemented for the primitive integer types.
+pub trait MmapId: Debug + PartialEq + Eq + Hash + Clone + Copy {
+    /// Encodes the ID as 64 bits.
+    fn to_bits(self) -> u64;
+    /// Decodes an ID encoded with `to_bits`.
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! impl_mmap_id {
+    ($($t:ty),*) => {
+        $(
+            impl MmapId for $t {
+                fn to_bits(self) -> u64 {
+                    self as u64
+                }
+                fn from_bits(bits: u64) -> Self {
+                    bits as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_mmap_id!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A frozen, read-only graph stored in a file and accessed through memory mapping.
+///
+/// The file holds the graph in compressed sparse row form. Opening it reads it through once
+/// to validate it, after which the operating system keeps only the pages that traversals touch
+/// in memory, so graphs larger than RAM can be queried.
+/// Node IDs are found by binary search in a sorted table inside the file, so opening a
+/// graph does not build any in-memory index.
+///
+/// `MmapGraph` implements the [`Nodes`] and [`Neighbors`] traits, so the generic
+/// algorithms in [`crate::visit`] run on it directly.
+///
+/// # File layout
+///
+/// All integers are little endian.
+///
+/// | section        | contents                                  |
+/// |----------------|-------------------------------------------|
+/// | header         | magic `GRAFCSR1`, node count `n`, edge count `m` (`u64`) |
+/// | offsets        | `n + 1` × `u64`, edges of slot `i` are `targets[offsets[i]..offsets[i + 1]]` |
+/// | ids            | `n` × `u64`, the ID of every slot          |
+/// | sorted ids     | `n` × `u64`, the IDs in ascending order    |
+/// | sorted slots   | `n` × `u32`, the slot of every sorted ID   |
+/// | targets        | `m` × `u32`, target slots                  |
+pub struct MmapGraph<IDDataType: MmapId> {
+    map: Mmap,
+    node_count: usize,
+    edge_count: usize,
+    _id: PhantomData<IDDataType>,
+}
+
+impl<IDDataType: MmapId> MmapGraph<IDDataType> {
+    /// Writes `graph` to `path` in the memory-mapped format and opens the result.
+    ///
+    /// Signed IDs are ordered by their bit pattern, which is consistent between writing and reading.
+    pub fn create<NodeDataType, EdgeDataType>(
+        path: impl AsRef<Path>,
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> io::Result<Self> {
+        Self::write(path.as_ref(), graph)?;
+        Self::open(path)
+    }
+
+    /// Writes `graph` to `path` in the memory-mapped format.
+    pub fn write<NodeDataType, EdgeDataType>(
+        path: impl AsRef<Path>,
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> io::Result<()> {
+        let compact = graph.to_compact();
+        let (offsets, targets) = compact.csr_parts();
+
+        let mut sorted: Vec<(u64, u32)> = compact
+            .ids()
+            .iter()
+            .enumerate()
+            .map(|(slot, id)| (id.to_bits(), slot as u32))
+            .collect();
+        sorted.sort_unstable();
+
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&(compact.len() as u64).to_le_bytes())?;
+        out.write_all(&(targets.len() as u64).to_le_bytes())?;
+        for offset in offsets {
+            out.write_all(&(*offset as u64).to_le_bytes())?;
+        }
+        for id in compact.ids() {
+            out.write_all(&id.to_bits().to_le_bytes())?;
+        }
+        for (bits, _) in sorted.iter() {
+            out.write_all(&bits.to_le_bytes())?;
+        }
+        for (_, slot) in sorted.iter() {
+            out.write_all(&slot.to_le_bytes())?;
+        }
+        for target in targets {
+            out.write_all(&target.to_le_bytes())?;
+        }
+        out.flush()
+    }
+
+    /// Opens a graph file written by `write`.
+    ///
+    /// The whole file is read once to check that its sections fit together, so a corrupt file is
+    /// an `InvalidData` error rather than a panic later on. The file must not be modified while it
+    /// is mapped.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read. As with any memory mapping, the file must not be
+        // modified or truncated by another process while it is mapped; `open` checks the contents
+        // once, and later reads rely on them staying the same.
+        let map = unsafe { Mmap::map(&file)? };
+
+        let invalid =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        if map.len() < HEADER_LEN || &map[..8] != MAGIC {
+            return Err(invalid("not a grafferous memory-mapped graph"));
+        }
+        let read_u64 = |at: usize| u64::from_le_bytes(map[at..at + 8].try_into().unwrap());
+        let (node_count, edge_count) = (read_u64(8), read_u64(16));
+        if layout_len(node_count, edge_count) != Some(map.len()) {
+            return Err(invalid("memory-mapped graph file has the wrong size"));
+        }
+
+        // the sizes fit the file, so none of the section arithmetic below can overflow
+        let graph = Self {
+            map,
+            node_count: node_count as usize,
+            edge_count: edge_count as usize,
+            _id: PhantomData,
+        };
+        let last = graph.offsets_start() + 8 * graph.node_count;
+        if graph.u64_at(graph.offsets_start()) != 0 || graph.u64_at(last) != edge_count {
+            return Err(invalid("memory-mapped graph offsets are inconsistent"));
+        }
+        let mut previous = 0;
+        for slot in 1..graph.node_count {
+            let offset = graph.u64_at(graph.offsets_start() + 8 * slot);
+            if offset < previous || offset > edge_count {
+                return Err(invalid("memory-mapped graph offsets are inconsistent"));
+            }
+            previous = offset;
+        }
+        let in_range = |at: usize| (graph.u32_at(at) as u64) < node_count;
+        if !(0..graph.node_count).all(|i| in_range(graph.sorted_slots_start() + 4 * i)) {
+            return Err(invalid("memory-mapped graph has an out of range slot"));
+        }
+        if !(0..graph.edge_count).all(|i| in_range(graph.targets_start() + 4 * i)) {
+            return Err(invalid(
+                "memory-mapped graph has an edge to an out of range slot",
+            ));
+        }
+        let sorted = |i: usize| graph.u64_at(graph.sorted_ids_start() + 8 * i);
+        if !(1..graph.node_count).all(|i| sorted(i - 1) < sorted(i)) {
+            return Err(invalid("memory-mapped graph IDs are not sorted"));
+        }
+        Ok(graph)
+    }
+
+    fn offsets_start(&self) -> usize {
+        HEADER_LEN
+    }
+
+    fn ids_start(&self) -> usize {
+        self.offsets_start() + 8 * (self.node_count + 1)
+    }
+
+    fn sorted_ids_start(&self) -> usize {
+        self.ids_start() + 8 * self.node_count
+    }
+
+    fn sorted_slots_start(&self) -> usize {
+        self.sorted_ids_start() + 8 * self.node_count
+    }
+
+    fn targets_start(&self) -> usize {
+        self.sorted_slots_start() + 4 * self.node_count
+    }
+
+    fn u64_at(&self, at: usize) -> u64 {
+        u64::from_le_bytes(self.map[at..at + 8].try_into().unwrap())
+    }
+
+    fn u32_at(&self, at: usize) -> u32 {
+        u32::from_le_bytes(self.map[at..at + 4].try_into().unwrap())
+    }
+
+    fn offset(&self, slot: usize) -> usize {
+        self.u64_at(self.offsets_start() + 8 * slot) as usize
+    }
+
+    fn id_at(&self, slot: usize) -> IDDataType {
+        IDDataType::from_bits(self.u64_at(self.ids_start() + 8 * slot))
+    }
+
+    /// finds the slot of an ID by binary search over the sorted ID table.
+    fn slot_of(&self, id: IDDataType) -> Option<usize> {
+        let bits = id.to_bits();
+        let (mut low, mut high) = (0, self.node_count);
+        while low < high {
+            let mid = (low + high) / 2;
+            let probe = self.u64_at(self.sorted_ids_start() + 8 * mid);
+            match probe.cmp(&bits) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => {
+                    return Some(self.u32_at(self.sorted_slots_start() + 4 * mid) as usize)
+                }
+            }
+        }
+        None
+    }
+
+    /// The number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// The number of directed edges.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Whether the node exists.
+    pub fn contains_node(&self, id: IDDataType) -> bool {
+        self.slot_of(id).is_some()
+    }
+
+    /// Iterates over all node IDs in the order of the original graph's `nodes`.
+    pub fn ids(&self) -> impl Iterator<Item = IDDataType> + '_ {
+        (0..self.node_count).map(|slot| self.id_at(slot))
+    }
+
+    /// Iterates over the outgoing neighbors of a node; empty if the node does not exist.
+    pub fn neighbors(&self, id: IDDataType) -> impl Iterator<Item = IDDataType> + '_ {
+        let range = match self.slot_of(id) {
+            Some(slot) => self.offset(slot)..self.offset(slot + 1),
+            None => 0..0,
+        };
+        range.map(move |edge| self.id_at(self.u32_at(self.targets_start() + 4 * edge) as usize))
+    }
+
+    /// The number of outgoing edges of a node, or `None` if it does not exist.
+    pub fn out_degree(&self, id: IDDataType) -> Option<usize> {
+        self.slot_of(id)
+            .map(|slot| self.offset(slot + 1) - self.offset(slot))
+    }
+}
+
+/// the length of a file with `node_count` nodes and `edge_count` edges, or `None` if it does not
+/// fit in memory.
+fn layout_len(node_count: u64, edge_count: u64) -> Option<usize> {
+    let n = usize::try_from(node_count).ok()?;
+    let m = usize::try_from(edge_count).ok()?;
+    // offsets, ids, sorted ids and sorted slots, then targets
+    let per_node = n.checked_mul(8 + 8 + 8 + 4)?;
+    HEADER_LEN
+        .checked_add(8)?
+        .checked_add(per_node)?
+        .checked_add(m.checked_mul(4)?)
+}
+
+impl<IDDataType: MmapId> Nodes for MmapGraph<IDDataType> {
+    type Id = IDDataType;
+
+    fn node_ids(&self) -> impl Iterator<Item = IDDataType> + '_ {
+        self.ids()
+    }
+
+    fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    fn contains_node(&self, id: IDDataType) -> bool {
+        self.slot_of(id).is_some()
+    }
+}
+
+impl<IDDataType: MmapId> Neighbors for MmapGraph<IDDataType> {
+    fn successors(&self, id: IDDataType) -> impl Iterator<Item = IDDataType> + '_ {
+        self.neighbors(id)
+    }
+}
+
+impl<IDDataType: MmapId> Debug for MmapGraph<IDDataType> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MmapGraph")
+            .field("node_count", &self.node_count)
+            .field("edge_count", &self.edge_count)
+            .finish()
+    }
+}
@@ -0,0 +1,154 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::Graph;
+
+type NodeCallback<IDDataType> = Box<dyn FnMut(IDDataType) + Send + Sync>;
+type EdgeCallback<IDDataType> = Box<dyn FnMut(IDDataType, IDDataType) + Send + Sync>;
+
+/// The callbacks registered on a graph through `Graph::on_node_added` and friends.
+///
+/// Every method of a graph that adds or removes nodes or edges reports them, but writing to the
+/// public fields of the graph directly does not.
+///
+/// Observers are not part of a graph's value: cloning a graph produces a copy
+/// without observers, and they are ignored when comparing graphs.
+pub(crate) struct Observers<IDDataType> {
+    node_added: Vec<NodeCallback<IDDataType>>,
+    edge_added: Vec<EdgeCallback<IDDataType>>,
+    node_removed: Vec<NodeCallback<IDDataType>>,
+    edge_removed: Vec<EdgeCallback<IDDataType>>,
+}
+
+impl<IDDataType: Copy> Observers<IDDataType> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.node_added.is_empty()
+            && self.edge_added.is_empty()
+            && self.node_removed.is_empty()
+            && self.edge_removed.is_empty()
+    }
+
+    pub(crate) fn node_added(&mut self, id: IDDataType) {
+        for callback in self.node_added.iter_mut() {
+            callback(id);
+        }
+    }
+
+    pub(crate) fn edge_added(&mut self, from: IDDataType, to: IDDataType) {
+        for callback in self.edge_added.iter_mut() {
+            callback(from, to);
+        }
+    }
+
+    pub(crate) fn node_removed(&mut self, id: IDDataType) {
+        for callback in self.node_removed.iter_mut() {
+            callback(id);
+        }
+    }
+
+    pub(crate) fn edge_removed(&mut self, from: IDDataType, to: IDDataType) {
+        for callback in self.edge_removed.iter_mut() {
+            callback(from, to);
+        }
+    }
+}
+
+impl<IDDataType> Default for Observers<IDDataType> {
+    fn default() -> Self {
+        Self {
+            node_added: Vec::new(),
+            edge_added: Vec::new(),
+            node_removed: Vec::new(),
+            edge_removed: Vec::new(),
+        }
+    }
+}
+
+impl<IDDataType> Clone for Observers<IDDataType> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<IDDataType> PartialEq for Observers<IDDataType> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<IDDataType> Eq for Observers<IDDataType> {}
+
+impl<IDDataType> Debug for Observers<IDDataType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Observers")
+            .field("node_added", &self.node_added.len())
+            .field("edge_added", &self.edge_added.len())
+            .field("node_removed", &self.node_removed.len())
+            .field("edge_removed", &self.edge_removed.len())
+            .finish()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Registers a callback that is called with the ID of every node added to the graph,
+    /// including nodes created implicitly when adding an edge.
+    ///
+    /// Observers are not cloned along with the graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let added = Arc::new(Mutex::new(Vec::new()));
+    /// let mut g = Graph::<usize, ()>::new();
+    /// let log = added.clone();
+    /// g.on_node_added(move |id| log.lock().unwrap().push(id));
+    ///
+    /// g.add_edge(0, 1);
+    /// assert_eq!(*added.lock().unwrap(), vec![0, 1]);
+    /// ```
+    pub fn on_node_added(&mut self, callback: impl FnMut(IDDataType) + Send + Sync + 'static) {
+        self.observers.node_added.push(Box::new(callback));
+    }
+
+    /// Registers a callback that is called with `(from, to)` for every directed edge added to the graph.
+    /// An undirected edge is reported as two directed edges.
+    ///
+    /// Observers are not cloned along with the graph.
+    pub fn on_edge_added(
+        &mut self,
+        callback: impl FnMut(IDDataType, IDDataType) + Send + Sync + 'static,
+    ) {
+        self.observers.edge_added.push(Box::new(callback));
+    }
+
+    /// Registers a callback that is called with the ID of every node removed from the graph.
+    /// The edges removed along with the node are reported to the edge observers first.
+    ///
+    /// Observers are not cloned along with the graph.
+    pub fn on_node_removed(&mut self, callback: impl FnMut(IDDataType) + Send + Sync + 'static) {
+        self.observers.node_removed.push(Box::new(callback));
+    }
+
+    /// Registers a callback that is called with `(from, to)` for every copy of a directed edge
+    /// removed from the graph, e.g. with `remove_directed_edge`, `remove_edge`, `simplify` or
+    /// `remove_node`, which reports the edges of the node before the node itself.
+    ///
+    /// Observers are not cloned along with the graph.
+    pub fn on_edge_removed(
+        &mut self,
+        callback: impl FnMut(IDDataType, IDDataType) + Send + Sync + 'static,
+    ) {
+        self.observers.edge_removed.push(Box::new(callback));
+    }
+
+    /// Removes all registered observers.
+    pub fn clear_observers(&mut self) {
+        self.observers = Observers::default();
+    }
+}
@@ -0,0 +1,371 @@
+//! Combining two graphs into one, graph products and reversing edge directions.
+//!
+//! The set operations treat the edges as a set of directed `(from, to)` pairs, so a parallel
+//! edge counts as present if any copy of it is. The result keeps the nodes and neighbor lists in
+//! the order of `self` followed by anything new from `other`, and the edge policy and hasher of
+//! `self`.
+
+use core::{fmt::Debug, hash::Hash, mem};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+{
+    /// The union of the two graphs: every node and edge of either. The data of a node in both
+    /// is `merge(data in self, data in other)`; an edge in both keeps its data from `self`, or
+    /// from `other` if it has none in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut a = Graph::<u32, u32>::new();
+    /// a.add_node_with_data(0, 1);
+    /// a.add_node_with_data(1, 10);
+    /// a.add_directed_edge(0, 1);
+    /// let mut b = Graph::<u32, u32>::new();
+    /// b.add_node_with_data(1, 20);
+    /// b.add_node_with_data(2, 5);
+    /// b.add_directed_edge(1, 2);
+    /// b.add_directed_edge(0, 1);
+    ///
+    /// let union = a.union(&b, |x, y| x + y);
+    /// assert_eq!(union.nodes, vec![0, 1, 2]);
+    /// assert_eq!(union.node_data[&1], 30);
+    /// assert_eq!(union.neighbors(0), &[1]);
+    /// assert_eq!(union.neighbors(1), &[2]);
+    /// ```
+    pub fn union(
+        &self,
+        other: &Self,
+        mut merge: impl FnMut(&NodeDataType, &NodeDataType) -> NodeDataType,
+    ) -> Self {
+        let mut result = self.empty_like();
+        for id in &self.nodes {
+            let data = match other.node_data.get(id) {
+                Some(theirs) => merge(&self.node_data[id], theirs),
+                None => self.node_data[id].clone(),
+            };
+            result.add_node_with_data(*id, data);
+        }
+        for id in &other.nodes {
+            if !result.node_data.contains_key(id) {
+                result.add_node_with_data(*id, other.node_data[id].clone());
+            }
+        }
+        let ours = self.edge_set();
+        result.copy_edges(self, other, |_, _| true);
+        result.copy_edges(other, self, |from, to| !ours.contains(&(from, to)));
+        result
+    }
+
+    /// The intersection of the two graphs: the nodes in both, with their data given by
+    /// `merge(data in self, data in other)`, and the edges in both. An edge keeps its data from
+    /// `self`, or from `other` if it has none in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let a = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    /// let b = Graph::<u32, ()>::parse("1 -> 2 -> 3; 0 -> 1").unwrap();
+    /// let common = a.intersection(&b, |_, _| ());
+    /// assert_eq!(common.nodes, vec![0, 1, 2]);
+    /// assert_eq!(common.edge_tuples().len(), 2);
+    /// assert!(common.neighbors(2).is_empty());
+    /// ```
+    pub fn intersection(
+        &self,
+        other: &Self,
+        mut merge: impl FnMut(&NodeDataType, &NodeDataType) -> NodeDataType,
+    ) -> Self {
+        let mut result = self.empty_like();
+        for id in &self.nodes {
+            if let Some(theirs) = other.node_data.get(id) {
+                result.add_node_with_data(*id, merge(&self.node_data[id], theirs));
+            }
+        }
+        let theirs = other.edge_set();
+        result.copy_edges(self, other, |from, to| theirs.contains(&(from, to)));
+        result
+    }
+
+    /// The difference of the two graphs: every node of `self` with its data, and the edges of
+    /// `self` that are not in `other`. Nodes are kept even if they are in `other`, so the result
+    /// can be combined with `other` again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let a = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    /// let b = Graph::<u32, ()>::parse("1 -> 2").unwrap();
+    /// let rest = a.difference(&b);
+    /// assert_eq!(rest.nodes, vec![0, 1, 2]);
+    /// assert!(rest.neighbors(1).is_empty());
+    /// assert_eq!(rest.neighbors(2), &[0]);
+    /// ```
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.empty_like();
+        for id in &self.nodes {
+            result.add_node_with_data(*id, self.node_data[id].clone());
+        }
+        let theirs = other.edge_set();
+        result.copy_edges(self, other, |from, to| !theirs.contains(&(from, to)));
+        result
+    }
+
+    /// A copy of the graph with every edge reversed, see `transpose`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2").unwrap();
+    /// let r = g.reversed();
+    /// assert_eq!(r.neighbors(2), &[1]);
+    /// assert_eq!(r.reverse_neighbors(0), &[1]);
+    /// ```
+    pub fn reversed(&self) -> Self {
+        let mut reversed = self.clone();
+        reversed.transpose();
+        reversed
+    }
+
+    /// an empty graph with the hasher and edge policy of this one.
+    fn empty_like(&self) -> Self {
+        let mut empty = Graph::with_hasher(self.node_data.hasher().clone());
+        empty.set_edge_policy(self.edge_policy());
+        empty
+    }
+
+    /// every directed edge as a `(from, to)` pair.
+    fn edge_set(&self) -> NodeSet<(IDDataType, IDDataType)> {
+        self.nodes
+            .iter()
+            .flat_map(|from| self.neighbors(*from).iter().map(move |to| (*from, *to)))
+            .collect()
+    }
+
+    /// appends the edges of `source` between nodes of this graph for which `keep` returns
+    /// `true`, with their data from `source` or else from `fallback`.
+    fn copy_edges(
+        &mut self,
+        source: &Self,
+        fallback: &Self,
+        keep: impl Fn(IDDataType, IDDataType) -> bool,
+    ) {
+        for from in &source.nodes {
+            if !self.node_data.contains_key(from) {
+                continue;
+            }
+            for to in source.neighbors(*from) {
+                if !self.node_data.contains_key(to) || !keep(*from, *to) {
+                    continue;
+                }
+                self.edges.entry(*from).or_default().push(*to);
+                self.reverse_edges.entry(*to).or_default().push(*from);
+                self.edge_index.insert(*from, *to);
+                let data = source
+                    .edge_data(*from, *to)
+                    .or_else(|| fallback.edge_data(*from, *to));
+                if let Some(data) = data {
+                    self.edge_data.insert((*from, *to), data.clone());
+                }
+            }
+        }
+        self.undirected.invalidate();
+        self.compact.invalidate();
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Reverses every edge in place by swapping the outgoing and incoming neighbor lists, moving
+    /// the data of every edge along with it. Takes `O(E)` time for the edge data and constant
+    /// time otherwise. Observers see every edge removed and added back reversed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, (), &str>::new();
+    /// g.add_directed_edge_with_data(0, 1, "up");
+    /// g.transpose();
+    /// assert_eq!(g.neighbors(1), &[0]);
+    /// assert_eq!(g.edge_data(1, 0), Some(&"up"));
+    /// assert_eq!(g.edge_data(0, 1), None);
+    /// ```
+    pub fn transpose(&mut self) {
+        if !self.observers.is_empty() {
+            for from in &self.nodes {
+                for to in &self.edges[from] {
+                    self.observers.edge_removed(*from, *to);
+                }
+            }
+        }
+        mem::swap(&mut self.edges, &mut self.reverse_edges);
+        let mut reversed = NodeMap::with_capacity_and_hasher(
+            self.edge_data.len(),
+            self.edge_data.hasher().clone(),
+        );
+        reversed.extend(
+            mem::take(&mut self.edge_data)
+                .into_iter()
+                .map(|((from, to), data)| ((to, from), data)),
+        );
+        self.edge_data = reversed;
+        self.edge_index.invalidate();
+        self.compact.invalidate();
+        if !self.observers.is_empty() {
+            for from in &self.nodes {
+                for to in &self.edges[from] {
+                    self.observers.edge_added(*from, *to);
+                }
+            }
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+{
+    /// The Cartesian product of the two graphs: a node `(a, x)` for every node `a` of `self` and
+    /// `x` of `other`, holding both of their data, and an edge from `(a, x)` to `(b, x)` for every
+    /// edge from `a` to `b` in `self` as well as from `(a, x)` to `(a, y)` for every edge from `x`
+    /// to `y` in `other`. The product of two paths is a grid, of two cycles a torus, of a cycle
+    /// and an edge a prism, and the `n`-fold product of an edge is a hypercube.
+    ///
+    /// The nodes are ordered by their node in `self` first and in `other` second; the edges have
+    /// no data and follow the directions, and parallel edges, of the factors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{generate_cycle_graph, generate_path_graph};
+    ///
+    /// let torus = generate_cycle_graph::<()>(4).cartesian_product(&generate_cycle_graph::<()>(5));
+    /// assert_eq!(torus.nodes.len(), 20);
+    /// assert!(torus.nodes.iter().all(|id| torus.neighbors(*id).len() == 4));
+    ///
+    /// let edge = generate_path_graph::<()>(2);
+    /// let square = edge.cartesian_product(&edge);
+    /// let cube = square.cartesian_product(&edge);
+    /// assert_eq!(cube.nodes.len(), 8);
+    /// assert_eq!(cube.edge_count(), 2 * 12);
+    /// ```
+    pub fn cartesian_product<J, M, F>(
+        &self,
+        other: &Graph<J, M, F>,
+    ) -> Graph<(IDDataType, J), (NodeDataType, M)>
+    where
+        J: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        M: Clone,
+    {
+        let mut product = self.product_nodes(other);
+        for a in &self.nodes {
+            for x in &other.nodes {
+                for b in self.neighbors(*a) {
+                    product.link((*a, *x), (*b, *x));
+                }
+                for y in other.neighbors(*x) {
+                    product.link((*a, *x), (*a, *y));
+                }
+            }
+        }
+        product
+    }
+
+    /// The tensor (or categorical) product of the two graphs: a node `(a, x)` for every node `a`
+    /// of `self` and `x` of `other`, holding both of their data, and an edge from `(a, x)` to
+    /// `(b, y)` for every edge from `a` to `b` in `self` and from `x` to `y` in `other`, so that
+    /// both coordinates move at once.
+    ///
+    /// The nodes are ordered by their node in `self` first and in `other` second; the edges have
+    /// no data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{generate_complete_graph, generate_path_graph};
+    ///
+    /// // the tensor product of two edges is two disjoint edges
+    /// let edge = generate_path_graph::<()>(2);
+    /// let product = edge.tensor_product(&edge);
+    /// assert_eq!(product.neighbors((0, 0)), &[(1, 1)]);
+    /// assert_eq!(product.neighbors((0, 1)), &[(1, 0)]);
+    /// assert_eq!(product.connected_components().len(), 2);
+    ///
+    /// let triangle = generate_complete_graph::<()>(3);
+    /// assert_eq!(triangle.tensor_product(&triangle).edge_count(), 36);
+    /// ```
+    pub fn tensor_product<J, M, F>(
+        &self,
+        other: &Graph<J, M, F>,
+    ) -> Graph<(IDDataType, J), (NodeDataType, M)>
+    where
+        J: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        M: Clone,
+    {
+        let mut product = self.product_nodes(other);
+        for a in &self.nodes {
+            for x in &other.nodes {
+                for b in self.neighbors(*a) {
+                    for y in other.neighbors(*x) {
+                        product.link((*a, *x), (*b, *y));
+                    }
+                }
+            }
+        }
+        product
+    }
+
+    /// a graph with a node for every pair of nodes of the two graphs and no edges.
+    fn product_nodes<J, M, F>(
+        &self,
+        other: &Graph<J, M, F>,
+    ) -> Graph<(IDDataType, J), (NodeDataType, M)>
+    where
+        J: Debug + PartialEq + Eq + Hash + Clone + Copy,
+        M: Clone,
+    {
+        let mut product = Graph::with_capacity(self.nodes.len() * other.nodes.len());
+        for a in &self.nodes {
+            for x in &other.nodes {
+                let data = (self.node_data[a].clone(), other.node_data[x].clone());
+                product.add_node_with_data((*a, *x), data);
+            }
+        }
+        product
+    }
+}
+
+impl<IDDataType, NodeDataType> Graph<IDDataType, NodeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// adds a directed edge between two existing nodes of a product.
+    fn link(&mut self, from: IDDataType, to: IDDataType) {
+        self.edges.entry(from).or_default().push(to);
+        self.reverse_edges.entry(to).or_default().push(from);
+        self.undirected.invalidate();
+        self.compact.invalidate();
+        self.edge_index.insert(from, to);
+    }
+}
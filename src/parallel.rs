@@ -0,0 +1,204 @@
+//! Parallel iteration over the nodes and parallel versions of the basic algorithms (`rayon`
+//! feature).
+//!
+//! The algorithms work on index-based adjacency lists shared by all threads and claim nodes with
+//! atomic operations, so their results are the same as those of the sequential versions.
+
+use core::{
+    hash::Hash,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+use std::fmt::Debug;
+
+use rayon::prelude::*;
+
+use crate::{collections::NodeMap, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + Send + Sync,
+    NodeDataType: Send + Sync,
+{
+    /// Returns a parallel iterator over the node IDs of the graph.
+    pub fn par_nodes(&self) -> impl IndexedParallelIterator<Item = &IDDataType> {
+        self.nodes.par_iter()
+    }
+
+    /// Returns a parallel iterator over `(id, data)` pairs for every node.
+    pub fn par_node_data(&self) -> impl ParallelIterator<Item = (&IDDataType, &NodeDataType)> {
+        self.node_data.par_iter()
+    }
+
+    /// Returns a parallel iterator over `(id, data)` pairs with mutable access to the data.
+    ///
+    /// Only the node data can be modified, so the topology of the graph stays intact.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut g = generate_grid_graph::<u32>(10, 10);
+    /// g.par_node_data_mut().for_each(|(id, data)| *data = (id.0 + id.1) as u32);
+    /// assert_eq!(g.node_data[&(3, 4)], 7);
+    /// ```
+    pub fn par_node_data_mut(
+        &mut self,
+    ) -> impl ParallelIterator<Item = (&IDDataType, &mut NodeDataType)> {
+        self.node_data.par_iter_mut()
+    }
+
+    /// Like `update_all_data`, but updates the nodes in parallel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let mut g = generate_grid_graph::<usize>(100, 100);
+    /// g.par_update_all_data(|id, data| *data = id.0 * id.1);
+    /// assert_eq!(g.node_data[&(30, 40)], 1200);
+    /// ```
+    pub fn par_update_all_data(&mut self, f: impl Fn(&IDDataType, &mut NodeDataType) + Sync) {
+        self.node_data
+            .par_iter_mut()
+            .for_each(|(id, data)| f(id, data));
+    }
+
+    /// Like `map_data`, but computes the new node data in parallel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let g = generate_grid_graph::<()>(100, 100);
+    /// let sums = g.par_map_data(|id, _| id.0 + id.1);
+    /// assert_eq!(sums.node_data[&(30, 40)], 70);
+    /// ```
+    pub fn par_map_data<U: Send>(
+        mut self,
+        f: impl Fn(&IDDataType, NodeDataType) -> U + Sync,
+    ) -> Graph<IDDataType, U, EdgeDataType> {
+        let old = core::mem::take(&mut self.node_data);
+        let mut node_data = NodeMap::with_capacity_and_hasher(old.len(), old.hasher().clone());
+        node_data.par_extend(old.into_par_iter().map(|(id, data)| {
+            let data = f(&id, data);
+            (id, data)
+        }));
+        self.replace_node_data(node_data)
+    }
+
+    /// Like `distances_from`, but expands every level of the breadth-first search in parallel.
+    /// Worth it for graphs with wide frontiers, such as social networks; on long thin graphs the
+    /// levels are too small to share out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let g = generate_grid_graph::<()>(50, 50);
+    /// assert_eq!(g.par_distances_from((0, 0)), g.distances_from((0, 0)));
+    /// ```
+    pub fn par_distances_from(&self, start: IDDataType) -> NodeMap<IDDataType, usize> {
+        let mut distances = NodeMap::default();
+        let Some(start) = self.nodes.iter().position(|id| *id == start) else {
+            return distances;
+        };
+        let (adjacency, _) = self.index_adjacency(false);
+        let discovered: Vec<AtomicBool> = (0..adjacency.len())
+            .map(|i| AtomicBool::new(i == start))
+            .collect();
+        let mut frontier = vec![start];
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            for node in &frontier {
+                distances.insert(self.nodes[*node], depth);
+            }
+            frontier = frontier
+                .par_iter()
+                .flat_map_iter(|node| {
+                    adjacency[*node]
+                        .iter()
+                        .copied()
+                        .filter(|next| !discovered[*next].swap(true, Ordering::Relaxed))
+                })
+                .collect();
+            depth += 1;
+        }
+        distances
+    }
+
+    /// Like `connected_components`, but joins the ends of all edges in parallel with a lock-free
+    /// union-find. The components and their nodes are in the same order as in
+    /// `connected_components`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1; 2 -- 3 -- 4; 5").unwrap();
+    /// assert_eq!(g.par_connected_components(), g.connected_components());
+    /// ```
+    pub fn par_connected_components(&self) -> Vec<Vec<IDDataType>> {
+        let (adjacency, _) = self.index_adjacency(false);
+        let parent: Vec<AtomicUsize> = (0..adjacency.len()).map(AtomicUsize::new).collect();
+        adjacency.par_iter().enumerate().for_each(|(from, tos)| {
+            for to in tos {
+                union(&parent, from, *to);
+            }
+        });
+        let roots: Vec<usize> = (0..parent.len())
+            .into_par_iter()
+            .map(|node| find(&parent, node))
+            .collect();
+
+        // every root is the smallest node of its component, so numbering the roots in order
+        // orders the components by their first node
+        let mut number = vec![usize::MAX; roots.len()];
+        let mut components: Vec<Vec<IDDataType>> = Vec::new();
+        for (node, root) in roots.iter().enumerate() {
+            if number[*root] == usize::MAX {
+                number[*root] = components.len();
+                components.push(Vec::new());
+            }
+            components[number[*root]].push(self.nodes[node]);
+        }
+        components
+    }
+}
+
+/// the root of the tree containing `node`, halving the path to it along the way.
+fn find(parent: &[AtomicUsize], mut node: usize) -> usize {
+    loop {
+        let up = parent[node].load(Ordering::Acquire);
+        if up == node {
+            return node;
+        }
+        let grandparent = parent[up].load(Ordering::Acquire);
+        // a failed shortcut only means another thread changed the parent first
+        let _ = parent[node].compare_exchange(up, grandparent, Ordering::AcqRel, Ordering::Relaxed);
+        node = grandparent;
+    }
+}
+
+/// joins the trees containing `a` and `b`. The larger root always points to the smaller one, so
+/// concurrent joins cannot create a cycle.
+fn union(parent: &[AtomicUsize], a: usize, b: usize) {
+    loop {
+        let (a, b) = (find(parent, a), find(parent, b));
+        if a == b {
+            return;
+        }
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        if parent[high]
+            .compare_exchange(high, low, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            return;
+        }
+    }
+}
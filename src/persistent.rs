@@ -0,0 +1,279 @@
+use core::fmt::Debug;
+use core::hash::Hash;
+
+use crate::{collections::GraphHasher, Graph};
+
+type PersistentMap<K, V> = im::HashMap<K, V, GraphHasher>;
+
+/// An immutable graph whose mutating methods return a new graph.
+///
+/// The new graph shares all unchanged structure with the old one (using the persistent
+/// collections of the `im` crate), so keeping many versions around, e.g. while
+/// branching and backtracking in a search, is cheap. Cloning is O(1).
+///
+/// Like [`Graph`], it stores optional data per edge, shared by parallel copies of the edge and
+/// removed with the last one.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::PersistentGraph;
+///
+/// let base = PersistentGraph::<usize, ()>::new().add_edge(0, 1);
+/// let branch = base.add_edge(1, 2);
+///
+/// assert_eq!(base.node_count(), 2);
+/// assert_eq!(branch.node_count(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistentGraph<IDDataType, NodeDataType, EdgeDataType = ()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+{
+    node_data: PersistentMap<IDDataType, NodeDataType>,
+    edges: PersistentMap<IDDataType, im::Vector<IDDataType>>,
+    reverse_edges: PersistentMap<IDDataType, im::Vector<IDDataType>>,
+    nodes: im::Vector<IDDataType>,
+    edge_data: PersistentMap<(IDDataType, IDDataType), EdgeDataType>,
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> PersistentGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone + Default,
+    EdgeDataType: Clone,
+{
+    /// Creates a new, empty graph.
+    pub fn new() -> Self {
+        Self {
+            node_data: PersistentMap::default(),
+            edges: PersistentMap::default(),
+            reverse_edges: PersistentMap::default(),
+            nodes: im::Vector::new(),
+            edge_data: PersistentMap::default(),
+        }
+    }
+
+    /// Returns a graph with a new node with default data.
+    /// If the node already exists the returned graph is identical to this one.
+    pub fn add_node(&self, id: IDDataType) -> Self {
+        self.add_node_with_data(id, NodeDataType::default())
+    }
+
+    /// Returns a graph with a new node with the given data.
+    /// If the node already exists the returned graph is identical to this one.
+    pub fn add_node_with_data(&self, id: IDDataType, data: NodeDataType) -> Self {
+        let mut graph = self.clone();
+        graph.insert_node(id, data);
+        graph
+    }
+
+    fn insert_node(&mut self, id: IDDataType, data: NodeDataType) {
+        if self.node_data.contains_key(&id) {
+            return;
+        }
+        self.nodes.push_back(id);
+        self.edges.insert(id, im::Vector::new());
+        self.reverse_edges.insert(id, im::Vector::new());
+        self.node_data.insert(id, data);
+    }
+
+    /// Returns a graph with an additional directed edge, adding missing nodes with default data.
+    pub fn add_directed_edge(&self, from: IDDataType, to: IDDataType) -> Self {
+        let mut graph = self.clone();
+        graph.insert_directed_edge(from, to);
+        graph
+    }
+
+    fn insert_directed_edge(&mut self, from: IDDataType, to: IDDataType) {
+        self.insert_node(from, NodeDataType::default());
+        self.insert_node(to, NodeDataType::default());
+        self.edges.entry(from).or_default().push_back(to);
+        self.reverse_edges.entry(to).or_default().push_back(from);
+    }
+
+    /// Returns a graph with an additional directed edge with the given data, adding missing nodes
+    /// with default data. The data replaces that of parallel copies of the edge.
+    pub fn add_directed_edge_with_data(
+        &self,
+        from: IDDataType,
+        to: IDDataType,
+        data: EdgeDataType,
+    ) -> Self {
+        let mut graph = self.clone();
+        graph.insert_directed_edge(from, to);
+        graph.edge_data.insert((from, to), data);
+        graph
+    }
+
+    /// Returns a graph with an additional undirected edge, adding missing nodes with default data.
+    pub fn add_edge(&self, from: IDDataType, to: IDDataType) -> Self {
+        let mut graph = self.clone();
+        graph.insert_directed_edge(from, to);
+        graph.insert_directed_edge(to, from);
+        graph
+    }
+
+    /// Returns a graph without one copy of the directed edge `from -> to`, and without its data if
+    /// that was the last copy. If there is no such edge the returned graph is identical to this
+    /// one.
+    pub fn remove_directed_edge(&self, from: IDDataType, to: IDDataType) -> Self {
+        let mut graph = self.clone();
+        let Some(tos) = graph.edges.get_mut(&from) else {
+            return graph;
+        };
+        let Some(position) = tos.index_of(&to) else {
+            return graph;
+        };
+        tos.remove(position);
+        if !tos.contains(&to) {
+            graph.edge_data.remove(&(from, to));
+        }
+        if let Some(froms) = graph.reverse_edges.get_mut(&to) {
+            if let Some(position) = froms.index_of(&from) {
+                froms.remove(position);
+            }
+        }
+        graph
+    }
+
+    /// Returns a graph without the given node and its incident edges with their data.
+    pub fn remove_node(&self, id: IDDataType) -> Self {
+        let mut graph = self.clone();
+        if graph.node_data.remove(&id).is_none() {
+            return graph;
+        }
+        graph.nodes.retain(|node| *node != id);
+        for to in graph.edges.remove(&id).unwrap_or_default() {
+            graph.edge_data.remove(&(id, to));
+            if let Some(froms) = graph.reverse_edges.get_mut(&to) {
+                froms.retain(|from| *from != id);
+            }
+        }
+        for from in graph.reverse_edges.remove(&id).unwrap_or_default() {
+            graph.edge_data.remove(&(from, id));
+            if let Some(tos) = graph.edges.get_mut(&from) {
+                tos.retain(|to| *to != id);
+            }
+        }
+        graph
+    }
+
+    /// Returns a graph where the data of `id` is replaced.
+    /// If the node does not exist the returned graph is identical to this one.
+    pub fn set_node_data(&self, id: IDDataType, data: NodeDataType) -> Self {
+        let mut graph = self.clone();
+        if let Some(slot) = graph.node_data.get_mut(&id) {
+            *slot = data;
+        }
+        graph
+    }
+
+    /// The data of a node, if it exists.
+    pub fn node_data(&self, id: IDDataType) -> Option<&NodeDataType> {
+        self.node_data.get(&id)
+    }
+
+    /// The data of the edge `from -> to`, if the edge exists and has data.
+    pub fn edge_data(&self, from: IDDataType, to: IDDataType) -> Option<&EdgeDataType> {
+        self.edge_data.get(&(from, to))
+    }
+
+    /// The outgoing neighbors of a node, or an empty vector if it does not exist.
+    pub fn neighbors(&self, id: IDDataType) -> Vec<IDDataType> {
+        self.edges
+            .get(&id)
+            .map(|tos| tos.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// The incoming neighbors of a node, or an empty vector if it does not exist.
+    pub fn reverse_neighbors(&self, id: IDDataType) -> Vec<IDDataType> {
+        self.reverse_edges
+            .get(&id)
+            .map(|froms| froms.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// All node IDs in insertion order.
+    pub fn nodes(&self) -> impl Iterator<Item = &IDDataType> {
+        self.nodes.iter()
+    }
+
+    /// Whether the node exists.
+    pub fn contains_node(&self, id: IDDataType) -> bool {
+        self.node_data.contains_key(&id)
+    }
+
+    /// The number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The number of directed edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().map(|tos| tos.len()).sum()
+    }
+
+    /// Converts into a regular, mutable [`Graph`].
+    pub fn to_graph(&self) -> Graph<IDDataType, NodeDataType, EdgeDataType> {
+        let mut graph = Graph::new();
+        for id in self.nodes.iter() {
+            graph.add_node_with_data(*id, self.node_data[id].clone());
+        }
+        for id in self.nodes.iter() {
+            graph
+                .edges
+                .insert(*id, self.edges[id].iter().copied().collect());
+            graph
+                .reverse_edges
+                .insert(*id, self.reverse_edges[id].iter().copied().collect());
+        }
+        graph.edge_data.extend(
+            self.edge_data
+                .iter()
+                .map(|(edge, data)| (*edge, data.clone())),
+        );
+        graph.invalidate_caches();
+        graph
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Default
+    for PersistentGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone + Default,
+    EdgeDataType: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> From<&Graph<IDDataType, NodeDataType, EdgeDataType>>
+    for PersistentGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone + Default,
+    EdgeDataType: Clone,
+{
+    fn from(graph: &Graph<IDDataType, NodeDataType, EdgeDataType>) -> Self {
+        let mut persistent = Self::new();
+        for id in graph.nodes.iter() {
+            persistent.insert_node(*id, graph.node_data[id].clone());
+        }
+        for (from, to) in graph.edges_iter() {
+            persistent.insert_directed_edge(from, to);
+        }
+        persistent.edge_data.extend(
+            graph
+                .edge_data
+                .iter()
+                .map(|(edge, data)| (*edge, data.clone())),
+        );
+        persistent
+    }
+}
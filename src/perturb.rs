@@ -0,0 +1,361 @@
+//! Random perturbations for robustness experiments: removing, adding and rewiring nodes or edges.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use rand::Rng;
+
+use crate::{collections::NodeMap, Graph};
+
+/// What a perturbation does to the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerturbAction {
+    /// Removes nodes together with their edges, e.g. to model failures or attacks.
+    RemoveNodes,
+    /// Removes edges.
+    RemoveEdges,
+    /// Adds edges between distinct nodes that are not connected yet.
+    AddEdges,
+    /// Moves the target of edges to another node that the source is not connected to yet.
+    RewireEdges,
+}
+
+/// How the nodes or edges affected by a perturbation are picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Every node or edge is equally likely.
+    Uniform,
+    /// Nodes are picked with probability proportional to their degree (incoming plus outgoing
+    /// edges) and edges proportional to the sum of the degrees of their ends. Nodes without edges
+    /// are only picked once no other nodes are left.
+    DegreeBiased,
+}
+
+/// Describes a perturbation for `Graph::perturb`.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_grid_graph, perturb::PerturbSpec};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut g = generate_grid_graph::<()>(10, 10);
+/// let attack = PerturbSpec::remove_nodes(5).degree_biased();
+/// let delta = g.perturb(StdRng::seed_from_u64(1), &attack);
+/// assert_eq!(g.nodes.len(), 95);
+///
+/// delta.revert(&mut g);
+/// assert_eq!(g.nodes.len(), 100);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PerturbSpec {
+    /// What to do.
+    pub action: PerturbAction,
+    /// How many nodes or edges to affect; fewer are affected if the graph runs out of candidates.
+    pub count: usize,
+    /// How the affected nodes or edges are picked.
+    pub selection: Selection,
+}
+
+impl PerturbSpec {
+    /// Affects `count` nodes or edges picked uniformly at random.
+    pub fn new(action: PerturbAction, count: usize) -> Self {
+        Self {
+            action,
+            count,
+            selection: Selection::Uniform,
+        }
+    }
+
+    /// Removes `count` nodes.
+    pub fn remove_nodes(count: usize) -> Self {
+        Self::new(PerturbAction::RemoveNodes, count)
+    }
+
+    /// Removes `count` edges.
+    pub fn remove_edges(count: usize) -> Self {
+        Self::new(PerturbAction::RemoveEdges, count)
+    }
+
+    /// Adds `count` edges.
+    pub fn add_edges(count: usize) -> Self {
+        Self::new(PerturbAction::AddEdges, count)
+    }
+
+    /// Rewires `count` edges.
+    pub fn rewire_edges(count: usize) -> Self {
+        Self::new(PerturbAction::RewireEdges, count)
+    }
+
+    /// Picks nodes and edges with the given selection.
+    pub fn selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Picks nodes and edges proportional to their degree, see [`Selection::DegreeBiased`].
+    pub fn degree_biased(self) -> Self {
+        self.selection(Selection::DegreeBiased)
+    }
+}
+
+/// The changes made by `Graph::perturb`.
+///
+/// Edges are listed as directed edges; on an undirected graph every edge appears in both
+/// directions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perturbation<IDDataType, NodeDataType> {
+    /// The removed nodes with their data, in the order they were removed.
+    pub removed_nodes: Vec<(IDDataType, NodeDataType)>,
+    /// The removed edges, including the edges of removed nodes.
+    pub removed_edges: Vec<(IDDataType, IDDataType)>,
+    /// The added edges.
+    pub added_edges: Vec<(IDDataType, IDDataType)>,
+}
+
+impl<IDDataType, NodeDataType> Default for Perturbation<IDDataType, NodeDataType> {
+    fn default() -> Self {
+        Self {
+            removed_nodes: Vec::new(),
+            removed_edges: Vec::new(),
+            added_edges: Vec::new(),
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType: Default> Perturbation<IDDataType, NodeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Undoes the perturbation. Restored nodes are appended to the node list, so the order of
+    /// `graph.nodes` may differ from before the perturbation. The data of removed edges is not
+    /// recorded, so restored edges have none.
+    pub fn revert<EdgeDataType>(self, graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>) {
+        for (from, to) in self.added_edges.into_iter().rev() {
+            graph.remove_directed_edge(from, to);
+        }
+        for (id, data) in self.removed_nodes {
+            graph.add_node_with_data(id, data);
+        }
+        for (from, to) in self.removed_edges {
+            graph.add_directed_edge(from, to);
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Randomly removes, adds or rewires nodes or edges and returns what changed.
+    ///
+    /// If the graph is undirected, edges are treated as undirected: both directions are removed,
+    /// added or rewired together. Degrees for degree-biased selection are taken before the
+    /// perturbation starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The source of randomness, e.g. `&mut rng` for a seeded RNG.
+    /// * `spec` - What to do, to how many nodes or edges and how to pick them.
+    ///
+    pub fn perturb<R: Rng>(
+        &mut self,
+        mut rng: R,
+        spec: &PerturbSpec,
+    ) -> Perturbation<IDDataType, NodeDataType> {
+        let undirected = self.is_undirected();
+        let degrees = self.degrees();
+        let biased = spec.selection == Selection::DegreeBiased;
+        let node_weights: Vec<usize> = self.nodes.iter().map(|id| degrees[id]).collect();
+        let nodes = Sampler::new(&node_weights, biased);
+        let mut delta = Perturbation::default();
+
+        match spec.action {
+            PerturbAction::RemoveNodes => {
+                let picked = pick(&mut rng, &node_weights, biased, spec.count);
+                let ids: Vec<IDDataType> = picked.into_iter().map(|i| self.nodes[i]).collect();
+                for id in ids {
+                    let out = self.edges.get(&id).map_or(&[][..], |n| &n[..]);
+                    let into = self.reverse_edges.get(&id).map_or(&[][..], |n| &n[..]);
+                    // a self-loop is both outgoing and incoming, so keep only its outgoing copy
+                    let incident: Vec<(IDDataType, IDDataType)> = out
+                        .iter()
+                        .map(|to| (id, *to))
+                        .chain(
+                            into.iter()
+                                .filter(|from| **from != id)
+                                .map(|from| (*from, id)),
+                        )
+                        .collect();
+                    if let Some(data) = self.remove_node(id) {
+                        delta.removed_nodes.push((id, data));
+                        delta.removed_edges.extend(incident);
+                    }
+                }
+            }
+            PerturbAction::RemoveEdges | PerturbAction::RewireEdges => {
+                // list each undirected edge once, from the node that comes first
+                let position: NodeMap<IDDataType, usize> = self
+                    .nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, id)| (*id, i))
+                    .collect();
+                let edges: Vec<(IDDataType, IDDataType)> = self
+                    .edges_iter()
+                    .filter(|(from, to)| !undirected || position[from] <= position[to])
+                    .collect();
+                let weights: Vec<usize> = edges
+                    .iter()
+                    .map(|(from, to)| degrees[from] + degrees[to])
+                    .collect();
+                for i in pick(&mut rng, &weights, biased, spec.count) {
+                    let (from, to) = edges[i];
+                    let target = if spec.action == PerturbAction::RewireEdges {
+                        match self.new_target(&mut rng, from, &nodes) {
+                            Some(target) => Some(target),
+                            None => continue,
+                        }
+                    } else {
+                        None
+                    };
+                    self.remove_pair(from, to, undirected, &mut delta.removed_edges);
+                    if let Some(target) = target {
+                        self.add_pair(from, target, undirected, &mut delta.added_edges);
+                    }
+                }
+            }
+            PerturbAction::AddEdges => {
+                for _ in 0..spec.count {
+                    let Some(from) = nodes.draw(&mut rng) else {
+                        break;
+                    };
+                    let from = self.nodes[from];
+                    if let Some(to) = self.new_target(&mut rng, from, &nodes) {
+                        self.add_pair(from, to, undirected, &mut delta.added_edges);
+                    }
+                }
+            }
+        }
+        delta
+    }
+
+    /// the number of incoming plus outgoing edges of every node.
+    fn degrees(&self) -> NodeMap<IDDataType, usize> {
+        self.nodes
+            .iter()
+            .map(|id| (*id, self.degree(*id)))
+            .collect()
+    }
+
+    /// picks a node other than `from` that `from` has no edge to yet, giving up after a few tries.
+    fn new_target<R: Rng>(
+        &self,
+        rng: &mut R,
+        from: IDDataType,
+        nodes: &Sampler,
+    ) -> Option<IDDataType> {
+        let neighbors = self.edges.get(&from).map_or(&[][..], |n| &n[..]);
+        (0..4 * self.nodes.len().max(1))
+            .filter_map(|_| nodes.draw(rng).map(|i| self.nodes[i]))
+            .find(|to| *to != from && !neighbors.contains(to))
+    }
+
+    fn remove_pair(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        undirected: bool,
+        removed: &mut Vec<(IDDataType, IDDataType)>,
+    ) {
+        if self.remove_directed_edge(from, to) {
+            removed.push((from, to));
+        }
+        if undirected && from != to && self.remove_directed_edge(to, from) {
+            removed.push((to, from));
+        }
+    }
+
+    fn add_pair(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        undirected: bool,
+        added: &mut Vec<(IDDataType, IDDataType)>,
+    ) {
+        self.add_directed_edge(from, to);
+        added.push((from, to));
+        if undirected {
+            self.add_directed_edge(to, from);
+            added.push((to, from));
+        }
+    }
+}
+
+/// picks up to `count` distinct indices into `weights`, uniformly or with probability
+/// proportional to the weights; zero weights are only picked once nothing else is left.
+fn pick<R: Rng>(rng: &mut R, weights: &[usize], biased: bool, count: usize) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (0..weights.len()).collect();
+    let mut picked = Vec::with_capacity(count.min(weights.len()));
+    while picked.len() < count && !remaining.is_empty() {
+        let total: usize = remaining.iter().map(|&i| weights[i]).sum();
+        let slot = if biased && total > 0 {
+            let mut target = rng.gen_range(0..total);
+            remaining
+                .iter()
+                .position(|&i| {
+                    if target < weights[i] {
+                        return true;
+                    }
+                    target -= weights[i];
+                    false
+                })
+                .unwrap_or(0)
+        } else {
+            rng.gen_range(0..remaining.len())
+        };
+        picked.push(remaining.swap_remove(slot));
+    }
+    picked
+}
+
+/// draws indices with replacement, uniformly or with probability proportional to the weights.
+struct Sampler {
+    /// running totals of the weights; empty for uniform draws
+    cumulative: Vec<usize>,
+    len: usize,
+}
+
+impl Sampler {
+    /// falls back to uniform draws if all weights are zero.
+    fn new(weights: &[usize], biased: bool) -> Self {
+        let cumulative: Vec<usize> = if biased {
+            weights
+                .iter()
+                .scan(0, |total, weight| {
+                    *total += weight;
+                    Some(*total)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let uniform = cumulative.last().is_none_or(|total| *total == 0);
+        Self {
+            cumulative: if uniform { Vec::new() } else { cumulative },
+            len: weights.len(),
+        }
+    }
+
+    fn draw<R: Rng>(&self, rng: &mut R) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(match self.cumulative.last() {
+            Some(total) => {
+                let target = rng.gen_range(0..*total);
+                self.cumulative.partition_point(|sum| *sum <= target)
+            }
+            None => rng.gen_range(0..self.len),
+        })
+    }
+}
@@ -0,0 +1,142 @@
+//! Conversions to and from [`petgraph`] (`petgraph` feature), to build a graph with custom IDs
+//! here and run petgraph's algorithms on it, or the other way round.
+//!
+//! The nodes of a converted graph are indexed in the order of `nodes`, so the `NodeIndex` of a
+//! node is its position there. Edges without data get the `Default` edge weight, and since
+//! parallel edges share their data here, the weight of the last copy of a parallel edge from
+//! petgraph is the one that is kept.
+
+use alloc::vec;
+use core::{fmt::Debug, hash::Hash};
+
+use petgraph::{
+    graph::{IndexType, NodeIndex},
+    EdgeType,
+};
+
+use crate::{
+    collections::{GraphHasher, NodeMap},
+    Graph,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A directed petgraph graph with the same nodes, node data and edges, and the `NodeIndex`
+    /// of every node. Every parallel edge becomes its own petgraph edge.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    /// use petgraph::algo::dijkstra;
+    ///
+    /// let g = Graph::<&str, ()>::from_edges(&[("home", "shop"), ("shop", "work")]);
+    /// let (pg, index) = g.to_petgraph();
+    /// let hops = dijkstra(&pg, index[&"home"], None, |_| 1);
+    /// assert_eq!(hops[&index[&"work"]], 2);
+    /// ```
+    pub fn to_petgraph(
+        &self,
+    ) -> (
+        petgraph::Graph<NodeDataType, EdgeDataType>,
+        NodeMap<IDDataType, NodeIndex>,
+    )
+    where
+        NodeDataType: Clone,
+        EdgeDataType: Clone + Default,
+    {
+        let edge_count = self.edge_count();
+        let mut graph = petgraph::Graph::with_capacity(self.nodes.len(), edge_count);
+        let mut index =
+            NodeMap::with_capacity_and_hasher(self.nodes.len(), self.node_data.hasher().clone());
+        for id in &self.nodes {
+            index.insert(*id, graph.add_node(self.node_data[id].clone()));
+        }
+        for from in &self.nodes {
+            for to in self.neighbors(*from) {
+                let Some(&target) = index.get(to) else {
+                    continue;
+                };
+                let weight = self.edge_data(*from, *to).cloned().unwrap_or_default();
+                graph.add_edge(index[from], target, weight);
+            }
+        }
+        (graph, index)
+    }
+}
+
+impl<NodeDataType, EdgeDataType> Graph<usize, NodeDataType, EdgeDataType> {
+    /// The graph with the nodes, node data and edges of a petgraph graph, each node identified
+    /// by its index. An undirected petgraph edge becomes an edge in both directions. Use
+    /// `map_ids` to switch to other IDs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut pg = petgraph::Graph::<&str, u32, petgraph::Undirected>::new_undirected();
+    /// let a = pg.add_node("a");
+    /// let b = pg.add_node("b");
+    /// pg.add_edge(a, b, 7);
+    ///
+    /// let g = Graph::from_petgraph(&pg);
+    /// assert_eq!(g.node_data[&1], "b");
+    /// assert_eq!(g.edge_data(1, 0), Some(&7));
+    /// assert!(g.is_undirected());
+    /// ```
+    pub fn from_petgraph<Ty: EdgeType, Ix: IndexType>(
+        graph: &petgraph::Graph<NodeDataType, EdgeDataType, Ty, Ix>,
+    ) -> Self
+    where
+        NodeDataType: Clone,
+        EdgeDataType: Clone,
+    {
+        let mut converted = Graph::with_hasher(GraphHasher::default());
+        for node in graph.node_indices() {
+            converted.add_node_with_data(node.index(), graph[node].clone());
+        }
+        for edge in graph.raw_edges() {
+            let (from, to) = (edge.source().index(), edge.target().index());
+            let mut directions = vec![(from, to)];
+            if !graph.is_directed() && from != to {
+                directions.push((to, from));
+            }
+            for (from, to) in directions {
+                converted.edges.entry(from).or_default().push(to);
+                converted.reverse_edges.entry(to).or_default().push(from);
+                converted.edge_data.insert((from, to), edge.weight.clone());
+            }
+        }
+        converted
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> From<&Graph<IDDataType, NodeDataType, EdgeDataType>>
+    for petgraph::Graph<NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone + Default,
+{
+    /// See `Graph::to_petgraph`, which also returns the index of every node.
+    fn from(graph: &Graph<IDDataType, NodeDataType, EdgeDataType>) -> Self {
+        graph.to_petgraph().0
+    }
+}
+
+impl<NodeDataType, EdgeDataType, Ty, Ix> From<&petgraph::Graph<NodeDataType, EdgeDataType, Ty, Ix>>
+    for Graph<usize, NodeDataType, EdgeDataType>
+where
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    /// See `Graph::from_petgraph`.
+    fn from(graph: &petgraph::Graph<NodeDataType, EdgeDataType, Ty, Ix>) -> Self {
+        Graph::from_petgraph(graph)
+    }
+}
@@ -0,0 +1,233 @@
+//! Python bindings built with `pyo3`, behind the `python` feature.
+//!
+//! Build a wheel with `maturin build --release` (the `pyproject.toml` enables the right features)
+//! and use the `grafferous` module from Python:
+//!
+//! ```python
+//! import grafferous
+//!
+//! g = grafferous.grid_graph(10, 10)
+//! g[0]["state"] = "infected"          # every node has a dict of data
+//! g.shortest_path(0, 99)              # [0, 1, ..., 99]
+//! g.personalized_pagerank([0])        # {node: score}
+//! ```
+//!
+//! Node IDs are non-negative integers and node data is a Python `dict`, created on first access.
+
+use core::hash::Hash;
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+use std::fmt::Debug;
+
+use crate::{
+    collections::NodeMap,
+    count_paths, generate_cycle_graph, generate_grid_graph, generate_random_graph,
+    visit::{bfs_distances, bfs_path},
+    Graph,
+};
+
+/// A graph with integer node IDs and a `dict` of data per node, exported to Python as `Graph`.
+#[pyclass(name = "Graph", module = "grafferous", unsendable)]
+#[derive(Debug, Default)]
+pub struct PyGraph {
+    graph: Graph<usize, Option<Py<PyDict>>>,
+}
+
+#[pymethods]
+impl PyGraph {
+    /// Creates a new, empty graph.
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node with an optional data dict; an existing node only gets its data replaced.
+    #[pyo3(signature = (id, data = None))]
+    pub fn add_node(&mut self, id: usize, data: Option<Bound<'_, PyDict>>) {
+        let data = data.map(Bound::unbind);
+        match self.graph.node_data.get_mut(&id) {
+            Some(existing) => {
+                if data.is_some() {
+                    *existing = data;
+                }
+            }
+            None => self.graph.add_node_with_data(id, data),
+        }
+    }
+
+    /// Adds an edge in both directions, adding missing nodes.
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.graph.add_edge(from, to);
+    }
+
+    /// Adds an edge from `from` to `to`, adding missing nodes.
+    pub fn add_directed_edge(&mut self, from: usize, to: usize) {
+        self.graph.add_directed_edge(from, to);
+    }
+
+    /// Adds many edges at once, in both directions unless `directed` is true.
+    #[pyo3(signature = (edges, directed = false))]
+    pub fn add_edges(&mut self, edges: Vec<(usize, usize)>, directed: bool) {
+        if directed {
+            self.graph.add_directed_edges(&edges);
+        } else {
+            self.graph.add_edges(&edges);
+        }
+    }
+
+    /// The IDs of all nodes, in insertion order.
+    pub fn nodes(&self) -> Vec<usize> {
+        self.graph.nodes.clone()
+    }
+
+    /// All directed edges as `(from, to)` tuples.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.graph.edge_tuples()
+    }
+
+    /// The outgoing neighbors of a node.
+    pub fn neighbors(&self, id: usize) -> Vec<usize> {
+        self.graph.neighbors(id).to_vec()
+    }
+
+    /// The number of nodes.
+    pub fn __len__(&self) -> usize {
+        self.graph.nodes.len()
+    }
+
+    /// Whether the node exists.
+    pub fn __contains__(&self, id: usize) -> bool {
+        self.graph.node_data.contains_key(&id)
+    }
+
+    /// The data dict of a node. Changes to it are stored in the graph.
+    pub fn __getitem__(&mut self, py: Python<'_>, id: usize) -> PyResult<Py<PyDict>> {
+        let data = self
+            .graph
+            .node_data
+            .get_mut(&id)
+            .ok_or_else(|| PyKeyError::new_err(id))?;
+        Ok(data
+            .get_or_insert_with(|| PyDict::new(py).unbind())
+            .clone_ref(py))
+    }
+
+    /// Replaces the data dict of a node, adding the node if it is missing.
+    pub fn __setitem__(&mut self, id: usize, data: Bound<'_, PyDict>) {
+        self.add_node(id, Some(data));
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Graph(nodes={}, edges={})",
+            self.graph.node_count(),
+            self.graph.edge_count()
+        )
+    }
+
+    /// Whether every edge has a matching edge in the opposite direction.
+    pub fn is_undirected(&self) -> bool {
+        self.graph.is_undirected()
+    }
+
+    /// Whether the graph is directed and acyclic.
+    pub fn is_directed_acyclic(&self) -> bool {
+        self.graph.is_directed_acyclic()
+    }
+
+    /// Hop distances from `start` to every node reachable from it.
+    pub fn bfs_distances(&self, start: usize) -> NodeMap<usize, usize> {
+        bfs_distances(&self.graph, start)
+    }
+
+    /// A path with the fewest edges from `start` to `target`, or `None` if there is none.
+    pub fn shortest_path(&self, start: usize, target: usize) -> Option<Vec<usize>> {
+        bfs_path(&self.graph, start, target)
+    }
+
+    /// The number of paths from `start` to `end`. Unless the graph is a directed acyclic graph,
+    /// `max_depth` must limit the number of edges on a path.
+    #[pyo3(signature = (start, end, max_depth = None))]
+    pub fn count_paths(
+        &self,
+        start: usize,
+        end: usize,
+        max_depth: Option<usize>,
+    ) -> PyResult<usize> {
+        for id in [start, end] {
+            if !self.__contains__(id) {
+                return Err(PyKeyError::new_err(id));
+            }
+        }
+        if max_depth.is_none() && !self.graph.is_directed_acyclic() {
+            return Err(PyValueError::new_err(
+                "the graph has cycles, so max_depth must be given",
+            ));
+        }
+        Ok(count_paths(&self.graph, &start, &end, max_depth))
+    }
+
+    /// Personalized PageRank scores for a set of seed nodes.
+    #[pyo3(signature = (seeds, alpha = 0.15))]
+    pub fn personalized_pagerank(&self, seeds: Vec<usize>, alpha: f64) -> NodeMap<usize, f64> {
+        self.graph.personalized_pagerank(&seeds, alpha)
+    }
+}
+
+impl PyGraph {
+    /// copies the topology of a generated graph, mapping its IDs with `id`, in order of the new IDs.
+    fn relabel<IDDataType, NodeDataType>(
+        graph: &Graph<IDDataType, NodeDataType>,
+        id: impl Fn(&IDDataType) -> usize,
+    ) -> Self
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        // generators fill their nodes in hash order, so sort them for stable IDs
+        let mut nodes: Vec<&IDDataType> = graph.nodes.iter().collect();
+        nodes.sort_by_key(|node| id(node));
+        let mut relabeled = Self::new();
+        for from in &nodes {
+            relabeled.graph.add_node(id(from));
+        }
+        for from in nodes {
+            for to in graph.edges.get(from).into_iter().flatten() {
+                relabeled.graph.add_directed_edge(id(from), id(to));
+            }
+        }
+        relabeled
+    }
+}
+
+/// A `width` by `height` grid; the node at column `x` and row `y` has the ID `y * width + x`.
+#[pyfunction]
+pub fn grid_graph(width: usize, height: usize) -> PyGraph {
+    PyGraph::relabel(&generate_grid_graph::<()>(width, height), |&(x, y)| {
+        y * width + x
+    })
+}
+
+/// A cycle of `n` nodes with IDs `0..n`.
+#[pyfunction]
+pub fn cycle_graph(n: usize) -> PyGraph {
+    PyGraph::relabel(&generate_cycle_graph::<()>(n), |&i| i)
+}
+
+/// A random directed graph of `n` nodes in which every edge exists with probability `p`.
+#[pyfunction]
+pub fn random_graph(n: usize, p: f64) -> PyGraph {
+    PyGraph::relabel(&generate_random_graph::<()>(n, p), |&i| i)
+}
+
+/// The `grafferous` Python module.
+#[pymodule]
+fn grafferous(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGraph>()?;
+    module.add_function(wrap_pyfunction!(grid_graph, module)?)?;
+    module.add_function(wrap_pyfunction!(cycle_graph, module)?)?;
+    module.add_function(wrap_pyfunction!(random_graph, module)?)?;
+    Ok(())
+}
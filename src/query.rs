@@ -0,0 +1,243 @@
+//! Finding nodes by their data, with a scan or with a secondary index from a key derived from the
+//! node data back to the nodes.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{collections::NodeMap, Graph};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The nodes for which `predicate(id, data)` holds, in the order of `nodes`.
+    ///
+    /// This scans every node; use a [`NodeIndex`] to look nodes up by a key of their data
+    /// repeatedly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, &str>::new();
+    /// g.add_node_with_data(1, "apple");
+    /// g.add_node_with_data(2, "banana");
+    /// g.add_node_with_data(3, "avocado");
+    ///
+    /// let found: Vec<u32> = g.find_nodes(|_, name| name.starts_with('a')).collect();
+    /// assert_eq!(found, vec![1, 3]);
+    /// ```
+    pub fn find_nodes<'a>(
+        &'a self,
+        mut predicate: impl FnMut(IDDataType, &NodeDataType) -> bool + 'a,
+    ) -> impl Iterator<Item = IDDataType> + 'a {
+        self.nodes
+            .iter()
+            .copied()
+            .filter(move |id| predicate(*id, &self.node_data[id]))
+    }
+}
+
+type KeyFunction<NodeDataType, K> = Box<dyn Fn(&NodeDataType) -> K + Send + Sync>;
+
+/// A secondary index from a key derived from the node data, such as a name, to the nodes with
+/// that key, so that looking nodes up by their data does not scan the whole graph.
+///
+/// The index is kept up to date either by calling `insert` and `remove` along with the changes
+/// to the graph, or by attaching it to the graph with `attach`. Changes made to `node_data` in
+/// place are not seen by the index; call `insert` again for the changed node.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{Graph, NodeIndex};
+///
+/// let mut g = Graph::<u32, (&str, u32)>::new();
+/// g.add_node_with_data(1, ("ada", 36));
+/// g.add_node_with_data(2, ("alan", 41));
+/// g.add_node_with_data(3, ("ada", 7));
+///
+/// let mut by_name = NodeIndex::from_graph(&g, |(name, _)| *name);
+/// assert_eq!(by_name.get(&"ada"), &[1, 3]);
+///
+/// g.remove_node(1);
+/// by_name.remove(1);
+/// assert_eq!(by_name.get(&"ada"), &[3]);
+/// assert!(by_name.get(&"grace").is_empty());
+/// ```
+pub struct NodeIndex<K, IDDataType, NodeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    key: KeyFunction<NodeDataType, K>,
+    /// the nodes with every key, in the order they were indexed
+    nodes: NodeMap<K, Vec<IDDataType>>,
+    /// the key of every indexed node
+    keys: NodeMap<IDDataType, K>,
+    /// nodes added to an attached graph since the last `sync`, whose data the observers cannot
+    /// see
+    pending: Vec<IDDataType>,
+}
+
+impl<K, IDDataType, NodeDataType> NodeIndex<K, IDDataType, NodeDataType>
+where
+    K: Hash + Eq + Clone,
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Creates an empty index that keys every node by `key(data)`.
+    pub fn new(key: impl Fn(&NodeDataType) -> K + Send + Sync + 'static) -> Self {
+        Self {
+            key: Box::new(key),
+            nodes: NodeMap::default(),
+            keys: NodeMap::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Builds the index for the nodes of a graph, in the order of `nodes`.
+    pub fn from_graph<EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        key: impl Fn(&NodeDataType) -> K + Send + Sync + 'static,
+    ) -> Self {
+        let mut index = Self::new(key);
+        for id in &graph.nodes {
+            index.insert(*id, &graph.node_data[id]);
+        }
+        index
+    }
+
+    /// Builds the index for a graph and registers observers on the graph that keep it up to
+    /// date as nodes are added or removed.
+    ///
+    /// The observers only see the IDs of new nodes, so their data is keyed by `sync` or
+    /// `lookup`, which take the graph. The index is shared with the observers, so it is
+    /// returned behind a mutex.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{Graph, NodeIndex};
+    ///
+    /// let mut g = Graph::<u32, u32>::new();
+    /// g.add_node_with_data(1, 10);
+    /// let by_decade = NodeIndex::attach(&mut g, |age| age / 10);
+    ///
+    /// g.add_node_with_data(2, 15);
+    /// g.add_node_with_data(3, 42);
+    /// g.remove_node(1);
+    ///
+    /// let mut by_decade = by_decade.lock().unwrap();
+    /// assert_eq!(by_decade.lookup(&g, &1), &[2]);
+    /// assert_eq!(by_decade.lookup(&g, &4), &[3]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn attach<EdgeDataType>(
+        graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        key: impl Fn(&NodeDataType) -> K + Send + Sync + 'static,
+    ) -> Arc<Mutex<Self>>
+    where
+        IDDataType: Send + 'static,
+        K: Send + 'static,
+        NodeDataType: 'static,
+    {
+        let index = Arc::new(Mutex::new(Self::from_graph(graph, key)));
+
+        let shared = index.clone();
+        graph.on_node_added(move |id| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .pending
+                .push(id);
+        });
+        let shared = index.clone();
+        graph.on_node_removed(move |id| {
+            shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(id);
+        });
+
+        index
+    }
+
+    /// Indexes a node with its data, or moves it to its new key if it is already indexed.
+    pub fn insert(&mut self, id: IDDataType, data: &NodeDataType) {
+        let key = (self.key)(data);
+        if self.keys.get(&id) == Some(&key) {
+            return;
+        }
+        self.remove(id);
+        self.nodes.entry(key.clone()).or_default().push(id);
+        self.keys.insert(id, key);
+    }
+
+    /// Removes a node from the index. Does nothing if it is not indexed.
+    pub fn remove(&mut self, id: IDDataType) {
+        self.pending.retain(|pending| *pending != id);
+        let Some(key) = self.keys.remove(&id) else {
+            return;
+        };
+        if let Some(ids) = self.nodes.get_mut(&key) {
+            ids.retain(|other| *other != id);
+            if ids.is_empty() {
+                self.nodes.remove(&key);
+            }
+        }
+    }
+
+    /// The nodes with the given key, in the order they were indexed.
+    pub fn get(&self, key: &K) -> &[IDDataType] {
+        self.nodes.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// The key of a node, if it is indexed.
+    pub fn key_of(&self, id: IDDataType) -> Option<&K> {
+        self.keys.get(&id)
+    }
+
+    /// Indexes the nodes added to an attached graph since the last call, see `attach`.
+    pub fn sync<EdgeDataType>(&mut self, graph: &Graph<IDDataType, NodeDataType, EdgeDataType>) {
+        for id in core::mem::take(&mut self.pending) {
+            if let Some(data) = graph.node_data.get(&id) {
+                self.insert(id, data);
+            }
+        }
+    }
+
+    /// Like `get`, after indexing the nodes added to an attached graph with `sync`.
+    pub fn lookup<EdgeDataType>(
+        &mut self,
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        key: &K,
+    ) -> &[IDDataType] {
+        self.sync(graph);
+        self.get(key)
+    }
+
+    /// The number of indexed nodes.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether no node is indexed.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl<K, IDDataType, NodeDataType> Debug for NodeIndex<K, IDDataType, NodeDataType>
+where
+    K: Debug,
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NodeIndex")
+            .field("nodes", &self.nodes)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
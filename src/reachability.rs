@@ -0,0 +1,321 @@
+//! Which nodes can reach which: the transitive closure of a graph and an index that answers
+//! reachability queries on a static graph without searching it every time.
+//!
+//! Both work on the condensation, since the nodes of a strongly connected component reach the
+//! same nodes.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// the number of interval labels `ReachabilityIndex` gives every component.
+const LABELS: usize = 3;
+
+/// the strongly connected components of a graph in topological order, the component of every
+/// node by position in `nodes`, and the deduplicated edges between components, which all lead to
+/// later components.
+struct Condensed {
+    members: Vec<Vec<usize>>,
+    component_of: Vec<usize>,
+    successors: Vec<Vec<usize>>,
+    /// whether the component contains a cycle, i.e. its nodes reach themselves.
+    cyclic: Vec<bool>,
+}
+
+impl Condensed {
+    fn new<IDDataType, NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let index: NodeMap<IDDataType, usize> = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let members: Vec<Vec<usize>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|component| component.iter().map(|id| index[id]).collect())
+            .collect();
+        let mut component_of = vec![0; graph.nodes.len()];
+        for (c, component) in members.iter().enumerate() {
+            for node in component {
+                component_of[*node] = c;
+            }
+        }
+        let mut successors = vec![Vec::new(); members.len()];
+        let mut cyclic: Vec<bool> = members
+            .iter()
+            .map(|component| component.len() > 1)
+            .collect();
+        for (from, id) in graph.nodes.iter().enumerate() {
+            for to in graph.neighbors(*id).iter().filter_map(|to| index.get(to)) {
+                let (a, b) = (component_of[from], component_of[*to]);
+                if a == b {
+                    cyclic[a] |= from == *to;
+                } else {
+                    successors[a].push(b);
+                }
+            }
+        }
+        for list in &mut successors {
+            list.sort_unstable();
+            list.dedup();
+        }
+        Self {
+            members,
+            component_of,
+            successors,
+            cyclic,
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The transitive closure: a graph with the same nodes and an edge from every node to every
+    /// node it reaches along a path of at least one edge. A node gets a self-loop if it lies on a
+    /// cycle. The neighbors of every node are in the order of `nodes`.
+    ///
+    /// The reachable sets are computed once per strongly connected component as bitsets, in
+    /// `O(c·(c + m) / 64)` time for `c` components, but the closure itself can have up to `n²`
+    /// edges; use a [`ReachabilityIndex`] to answer queries on large graphs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2; 3 -> 3").unwrap();
+    /// let closure = g.transitive_closure();
+    /// assert_eq!(closure.neighbors(0), &[1, 2]);
+    /// assert!(closure.neighbors(2).is_empty());
+    /// assert_eq!(closure.neighbors(3), &[3]);
+    /// ```
+    pub fn transitive_closure(&self) -> Graph<IDDataType, ()> {
+        let condensed = Condensed::new(self);
+        let c = condensed.members.len();
+        let words = c.div_ceil(64);
+        // the components reachable from every component along at least one edge
+        let mut reaches = vec![0u64; c * words];
+        for a in (0..c).rev() {
+            for &b in &condensed.successors[a] {
+                // b > a, so its row is complete
+                let (head, tail) = reaches.split_at_mut(b * words);
+                let row = &mut head[a * words..(a + 1) * words];
+                row.iter_mut()
+                    .zip(&tail[..words])
+                    .for_each(|(word, other)| *word |= other);
+                row[b / 64] |= 1 << (b % 64);
+            }
+            if condensed.cyclic[a] {
+                reaches[a * words + a / 64] |= 1 << (a % 64);
+            }
+        }
+
+        let mut closure = Graph::new();
+        for id in &self.nodes {
+            closure.add_node_with_data(*id, ());
+        }
+        for (a, component) in condensed.members.iter().enumerate() {
+            let row = &reaches[a * words..(a + 1) * words];
+            let targets: Vec<IDDataType> = (0..self.nodes.len())
+                .filter(|node| {
+                    let b = condensed.component_of[*node];
+                    row[b / 64] & (1 << (b % 64)) != 0
+                })
+                .map(|node| self.nodes[node])
+                .collect();
+            for from in component {
+                let from = self.nodes[*from];
+                for to in &targets {
+                    closure.add_directed_edge(from, *to);
+                }
+            }
+        }
+        closure
+    }
+}
+
+/// A precomputed index answering whether one node can reach another along directed edges, for
+/// many queries on a graph that does not change.
+///
+/// The index labels every strongly connected component with a few intervals from depth-first
+/// traversals of the condensation (GRAIL labeling): a component can only reach another if each
+/// interval of the other lies inside its own, and the spanning forest of the first traversal
+/// confirms many reachable pairs outright. Most queries are answered from the labels in
+/// constant time, and the rest by a search that the labels prune. Building the index takes
+/// `O(n + m)` time and memory.
+///
+/// The index describes the graph at the time it was built; rebuild it after changing the graph.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{reachability::ReachabilityIndex, Graph};
+///
+/// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 1; 0 -> 3; 4 -> 3").unwrap();
+/// let index = ReachabilityIndex::build(&g);
+/// assert!(index.reaches(0, 2));
+/// assert!(index.reaches(2, 1));
+/// assert!(!index.reaches(3, 0));
+/// assert!(!index.reaches(4, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReachabilityIndex<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    component_of: NodeMap<IDDataType, usize>,
+    successors: Vec<Vec<usize>>,
+    /// for every traversal and component, the smallest post-order rank it reaches and its own
+    tree_labels: Vec<Vec<(usize, usize)>>,
+    /// the pre-order rank of every component in the first traversal and the last pre-order rank
+    /// of its subtree in the spanning forest
+    tree_span: Vec<(usize, usize)>,
+}
+
+impl<IDDataType> ReachabilityIndex<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Builds the index for a graph.
+    pub fn build<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self {
+        let condensed = Condensed::new(graph);
+        let c = condensed.members.len();
+        let mut has_parent = vec![false; c];
+        for list in &condensed.successors {
+            for b in list {
+                has_parent[*b] = true;
+            }
+        }
+        let roots: Vec<usize> = (0..c).filter(|a| !has_parent[*a]).collect();
+
+        let mut tree_labels = Vec::with_capacity(LABELS);
+        let mut tree_span = vec![(0, 0); c];
+        for traversal in 0..LABELS {
+            // vary the order of the children between traversals so that the intervals differ
+            let child = |list: &[usize], i: usize| match traversal % 2 {
+                0 => list[i],
+                _ => list[list.len() - 1 - i],
+            };
+            let mut labels = vec![(usize::MAX, 0); c];
+            let mut visited = vec![false; c];
+            let (mut post, mut pre) = (0, 0);
+            let ordered_roots: Vec<usize> = if traversal % 2 == 0 {
+                roots.clone()
+            } else {
+                roots.iter().rev().copied().collect()
+            };
+            for root in ordered_roots {
+                visited[root] = true;
+                if traversal == 0 {
+                    tree_span[root].0 = pre;
+                }
+                pre += 1;
+                let mut stack = vec![(root, 0)];
+                while let Some((node, next)) = stack.last_mut() {
+                    let node = *node;
+                    let list = &condensed.successors[node];
+                    if *next < list.len() {
+                        let successor = child(list, *next);
+                        *next += 1;
+                        if !visited[successor] {
+                            visited[successor] = true;
+                            if traversal == 0 {
+                                tree_span[successor].0 = pre;
+                            }
+                            pre += 1;
+                            stack.push((successor, 0));
+                        }
+                        continue;
+                    }
+                    stack.pop();
+                    let low = list.iter().map(|s| labels[*s].0).fold(post, usize::min);
+                    labels[node] = (low, post);
+                    post += 1;
+                    if traversal == 0 {
+                        tree_span[node].1 = pre - 1;
+                    }
+                }
+            }
+            tree_labels.push(labels);
+        }
+
+        let component_of = graph
+            .nodes
+            .iter()
+            .zip(&condensed.component_of)
+            .map(|(id, c)| (*id, *c))
+            .collect();
+        Self {
+            component_of,
+            successors: condensed.successors,
+            tree_labels,
+            tree_span,
+        }
+    }
+
+    /// Whether `to` can be reached from `from` along directed edges. A node reaches itself, and
+    /// nodes that were not in the graph reach nothing.
+    pub fn reaches(&self, from: IDDataType, to: IDDataType) -> bool {
+        let (Some(&a), Some(&b)) = (self.component_of.get(&from), self.component_of.get(&to))
+        else {
+            return false;
+        };
+        if a == b {
+            return true;
+        }
+        // components are in topological order, so edges only lead to later ones
+        if a > b || !self.may_reach(a, b) {
+            return false;
+        }
+        if self.tree_reaches(a, b) {
+            return true;
+        }
+        let mut visited = NodeSet::default();
+        let mut stack = vec![a];
+        while let Some(node) = stack.pop() {
+            for &next in &self.successors[node] {
+                if next == b || self.tree_reaches(next, b) {
+                    return true;
+                }
+                if next < b && self.may_reach(next, b) && visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// The number of strongly connected components of the graph, the size of the index.
+    pub fn component_count(&self) -> usize {
+        self.successors.len()
+    }
+
+    /// whether every interval of `b` lies inside the interval of `a`, which holds if `a` reaches
+    /// `b`.
+    fn may_reach(&self, a: usize, b: usize) -> bool {
+        self.tree_labels.iter().all(|labels| {
+            let ((low_a, post_a), (low_b, post_b)) = (labels[a], labels[b]);
+            low_a <= low_b && post_b <= post_a
+        })
+    }
+
+    /// whether `b` lies below `a` in the spanning forest of the first traversal.
+    fn tree_reaches(&self, a: usize, b: usize) -> bool {
+        let ((start_a, end_a), (start_b, _)) = (self.tree_span[a], self.tree_span[b]);
+        start_a <= start_b && start_b <= end_a
+    }
+}
@@ -0,0 +1,653 @@
+//! Drawing graphs, as SVG or bitmap pictures from the positions computed by the
+//! [`layout`](crate::layout) module, or as text for the terminal.
+
+use core::hash::Hash;
+use std::fmt::{Debug, Write};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    layout::Layout,
+    visit::bfs_distances,
+    Graph,
+};
+
+type NodeText<'a, IDDataType, NodeDataType> = Box<dyn Fn(IDDataType, &NodeDataType) -> String + 'a>;
+
+/// How `render_svg` draws a graph.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_cycle_graph, layout::circular, render::{render_svg, SvgStyle}};
+///
+/// let g = generate_cycle_graph::<u32>(5);
+/// let style = SvgStyle::new()
+///     .size(200.0, 200.0)
+///     .labels(|id: usize, _| id.to_string())
+///     .node_color(|id, _| if id == 0 { "red".to_string() } else { "gray".to_string() });
+/// let svg = render_svg(&g, &circular(&g), &style);
+///
+/// assert!(svg.starts_with("<svg"));
+/// assert_eq!(svg.matches("<circle").count(), 5);
+/// assert!(svg.contains("fill=\"red\""));
+/// ```
+pub struct SvgStyle<'a, IDDataType, NodeDataType> {
+    width: f64,
+    height: f64,
+    margin: f64,
+    node_radius: f64,
+    edge_color: String,
+    background: Option<String>,
+    label: Option<NodeText<'a, IDDataType, NodeDataType>>,
+    node_color: Option<NodeText<'a, IDDataType, NodeDataType>>,
+}
+
+impl<'a, IDDataType, NodeDataType> SvgStyle<'a, IDDataType, NodeDataType> {
+    /// A 400 by 400 picture of steel blue nodes and gray edges, without labels.
+    pub fn new() -> Self {
+        Self {
+            width: 400.0,
+            height: 400.0,
+            margin: 20.0,
+            node_radius: 6.0,
+            edge_color: "#999999".to_string(),
+            background: None,
+            label: None,
+            node_color: None,
+        }
+    }
+
+    /// The size of the picture in pixels.
+    pub fn size(mut self, width: f64, height: f64) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// The free space between the outermost nodes and the border of the picture.
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// The radius of the circle drawn for every node.
+    pub fn node_radius(mut self, radius: f64) -> Self {
+        self.node_radius = radius;
+        self
+    }
+
+    /// The stroke color of the edges, as any SVG color.
+    pub fn edge_color(mut self, color: &str) -> Self {
+        self.edge_color = color.to_string();
+        self
+    }
+
+    /// Fills the picture with a background color; it is transparent by default.
+    pub fn background(mut self, color: &str) -> Self {
+        self.background = Some(color.to_string());
+        self
+    }
+
+    /// Writes the text returned by `label` next to every node.
+    pub fn labels(mut self, label: impl Fn(IDDataType, &NodeDataType) -> String + 'a) -> Self {
+        self.label = Some(Box::new(label));
+        self
+    }
+
+    /// Fills every node with the SVG color returned by `color`.
+    pub fn node_color(mut self, color: impl Fn(IDDataType, &NodeDataType) -> String + 'a) -> Self {
+        self.node_color = Some(Box::new(color));
+        self
+    }
+}
+
+impl<IDDataType, NodeDataType> Default for SvgStyle<'_, IDDataType, NodeDataType> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws the graph as an SVG document.
+///
+/// The layout is scaled uniformly to fit the picture. An edge whose reverse edge also exists is
+/// drawn as a single plain line; every other edge gets an arrowhead at its target. Self-loops and
+/// nodes without a position are skipped.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to draw.
+/// * `layout` - The position of every node, e.g. from `layout::fruchterman_reingold`.
+/// * `style` - Sizes, colors and labels.
+///
+pub fn render_svg<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    layout: &Layout<IDDataType>,
+    style: &SvgStyle<IDDataType, NodeDataType>,
+) -> String
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let project = Projection::new(layout, style.width, style.height, style.margin);
+    let mut svg = String::new();
+    // writing to a String cannot fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = style.width,
+        h = style.height
+    );
+    let _ = writeln!(
+        svg,
+        r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker></defs>"#,
+        escape(&style.edge_color)
+    );
+    if let Some(background) = &style.background {
+        let _ = writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            escape(background)
+        );
+    }
+
+    let _ = writeln!(
+        svg,
+        r#"<g stroke="{}" stroke-width="1.5">"#,
+        escape(&style.edge_color)
+    );
+    for (from, to, directed) in drawn_edges(graph) {
+        let (Some(a), Some(b)) = (project.point(&from), project.point(&to)) else {
+            continue;
+        };
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length <= 2.0 * style.node_radius {
+            continue;
+        }
+        // end the line at the border of the target circle so the arrowhead stays visible
+        let shorten = if directed {
+            style.node_radius / length
+        } else {
+            0.0
+        };
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}"{}/>"#,
+            a.0,
+            a.1,
+            b.0 - dx * shorten,
+            b.1 - dy * shorten,
+            if directed {
+                r#" marker-end="url(#arrow)""#
+            } else {
+                ""
+            }
+        );
+    }
+    svg.push_str("</g>\n");
+
+    for id in &graph.nodes {
+        let (Some(p), Some(data)) = (project.point(id), graph.node_data.get(id)) else {
+            continue;
+        };
+        let fill = style
+            .node_color
+            .as_ref()
+            .map_or_else(|| "steelblue".to_string(), |color| color(*id, data));
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="{}" fill="{}"/>"#,
+            p.0,
+            p.1,
+            style.node_radius,
+            escape(&fill)
+        );
+        if let Some(label) = &style.label {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}" font-family="sans-serif" font-size="{:.0}">{}</text>"#,
+                p.0 + style.node_radius + 2.0,
+                p.1 - style.node_radius,
+                (style.node_radius * 2.0).max(10.0),
+                escape(&label(*id, data))
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(feature = "plotters")]
+type NodeColor<'a, IDDataType, NodeDataType> =
+    Box<dyn Fn(IDDataType, &NodeDataType) -> (u8, u8, u8) + 'a>;
+
+/// How `render_png` draws a graph. Colors are RGB triples.
+#[cfg(feature = "plotters")]
+pub struct PngStyle<'a, IDDataType, NodeDataType> {
+    width: u32,
+    height: u32,
+    margin: f64,
+    node_radius: f64,
+    edge_color: (u8, u8, u8),
+    background: (u8, u8, u8),
+    node_color: Option<NodeColor<'a, IDDataType, NodeDataType>>,
+}
+
+#[cfg(feature = "plotters")]
+impl<'a, IDDataType, NodeDataType> PngStyle<'a, IDDataType, NodeDataType> {
+    /// A 400 by 400 picture of steel blue nodes and gray edges on white.
+    pub fn new() -> Self {
+        Self {
+            width: 400,
+            height: 400,
+            margin: 20.0,
+            node_radius: 6.0,
+            edge_color: (153, 153, 153),
+            background: (255, 255, 255),
+            node_color: None,
+        }
+    }
+
+    /// The size of the picture in pixels.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// The free space between the outermost nodes and the border of the picture.
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// The radius of the circle drawn for every node.
+    pub fn node_radius(mut self, radius: f64) -> Self {
+        self.node_radius = radius;
+        self
+    }
+
+    /// The color of the edges.
+    pub fn edge_color(mut self, color: (u8, u8, u8)) -> Self {
+        self.edge_color = color;
+        self
+    }
+
+    /// The color of the background.
+    pub fn background(mut self, color: (u8, u8, u8)) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Fills every node with the color returned by `color`, e.g. to show the state of a simulation.
+    pub fn node_color(
+        mut self,
+        color: impl Fn(IDDataType, &NodeDataType) -> (u8, u8, u8) + 'a,
+    ) -> Self {
+        self.node_color = Some(Box::new(color));
+        self
+    }
+}
+
+#[cfg(feature = "plotters")]
+impl<IDDataType, NodeDataType> Default for PngStyle<'_, IDDataType, NodeDataType> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws the graph into a bitmap file, using the same conventions as `render_svg`.
+///
+/// The image format follows the extension of `path` (`.png`, `.jpg` or `.bmp`). Rendering one file
+/// per step of a simulation gives the frames of an animation.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to draw.
+/// * `layout` - The position of every node.
+/// * `path` - The file to write.
+/// * `style` - Sizes and colors.
+///
+/// # Example
+///
+/// ```no_run
+/// use grafferous::{generate_grid_graph, layout::fruchterman_reingold, layout::ForceConfig};
+/// use grafferous::render::{render_png, PngStyle};
+///
+/// let g = generate_grid_graph::<bool>(10, 10);
+/// let layout = fruchterman_reingold(&g, &ForceConfig::new());
+/// let style = PngStyle::new().node_color(|_, &on: &bool| if on { (200, 0, 0) } else { (0, 0, 200) });
+/// render_png(&g, &layout, "frame_000.png", &style).unwrap();
+/// ```
+#[cfg(feature = "plotters")]
+pub fn render_png<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    layout: &Layout<IDDataType>,
+    path: impl AsRef<std::path::Path>,
+    style: &PngStyle<IDDataType, NodeDataType>,
+) -> std::io::Result<()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    use plotters::prelude::*;
+
+    let error = |e: DrawingAreaErrorKind<_>| std::io::Error::other(e.to_string());
+    let project = Projection::new(
+        layout,
+        style.width as f64,
+        style.height as f64,
+        style.margin,
+    );
+    let pixel = |p: (f64, f64)| (p.0.round() as i32, p.1.round() as i32);
+    let rgb = |(r, g, b): (u8, u8, u8)| RGBColor(r, g, b);
+
+    let area = BitMapBackend::new(path.as_ref(), (style.width, style.height)).into_drawing_area();
+    area.fill(&rgb(style.background)).map_err(error)?;
+
+    let edge_color = rgb(style.edge_color);
+    for (from, to, directed) in drawn_edges(graph) {
+        let (Some(a), Some(b)) = (project.point(&from), project.point(&to)) else {
+            continue;
+        };
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length <= 2.0 * style.node_radius {
+            continue;
+        }
+        area.draw(&PathElement::new(
+            vec![pixel(a), pixel(b)],
+            edge_color.stroke_width(1),
+        ))
+        .map_err(error)?;
+        if directed {
+            // a triangle whose tip touches the border of the target circle
+            let (ux, uy) = (dx / length, dy / length);
+            let size = style.node_radius.max(4.0);
+            let tip = (b.0 - ux * style.node_radius, b.1 - uy * style.node_radius);
+            let base = (tip.0 - ux * size, tip.1 - uy * size);
+            let corners = vec![
+                pixel(tip),
+                pixel((base.0 - uy * size / 2.0, base.1 + ux * size / 2.0)),
+                pixel((base.0 + uy * size / 2.0, base.1 - ux * size / 2.0)),
+            ];
+            area.draw(&Polygon::new(corners, edge_color.filled()))
+                .map_err(error)?;
+        }
+    }
+
+    for id in &graph.nodes {
+        let (Some(p), Some(data)) = (project.point(id), graph.node_data.get(id)) else {
+            continue;
+        };
+        let fill = style
+            .node_color
+            .as_ref()
+            .map_or((70, 130, 180), |color| color(*id, data));
+        area.draw(&Circle::new(
+            pixel(p),
+            style.node_radius.round() as i32,
+            rgb(fill).filled(),
+        ))
+        .map_err(error)?;
+    }
+    area.present().map_err(error)
+}
+
+/// Draws the graph as text of at most `width` columns and `height` lines, for debugging in tests
+/// and command line tools.
+///
+/// A graph that is exactly a rectangular grid, such as the ones from `generate_grid_graph`, is drawn
+/// with box-drawing characters, every node being the junction of its edges; the longer side runs
+/// horizontally. Any other graph, or a grid that does not fit, is printed as an adjacency list with
+/// one `node: neighbors` line per node, in insertion order. Lines that are too long end in `…`, and
+/// if there are too many nodes the last line counts the ones left out.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_grid_graph, render::render_ascii, Graph};
+///
+/// let grid = generate_grid_graph::<()>(3, 2);
+/// assert_eq!(render_ascii(&grid, 80, 24), "┌─┬─┐\n│ │ │\n└─┴─┘\n");
+///
+/// let g = Graph::<u32, ()>::from_edges(&[(1, 2), (1, 3)]);
+/// assert_eq!(render_ascii(&g, 80, 24), "1: 2, 3\n2:\n3:\n");
+/// ```
+pub fn render_ascii<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    width: usize,
+    height: usize,
+) -> String
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    if let Some((columns, rows)) = grid_shape(graph) {
+        if 2 * columns - 1 <= width && 2 * rows - 1 <= height {
+            return draw_grid(columns, rows);
+        }
+    }
+
+    let mut text = String::new();
+    for (i, id) in graph.nodes.iter().enumerate() {
+        if i + 1 == height && i + 1 < graph.nodes.len() {
+            let rest = format!("… {} more nodes", graph.nodes.len() - i);
+            text.push_str(&truncate(&rest, width));
+            text.push('\n');
+            break;
+        }
+        let mut line = format!("{id:?}:");
+        for (j, neighbor) in graph.edges.get(id).into_iter().flatten().enumerate() {
+            line.push_str(if j == 0 { " " } else { ", " });
+            line.push_str(&format!("{neighbor:?}"));
+        }
+        text.push_str(&truncate(&line, width));
+        text.push('\n');
+    }
+    text
+}
+
+/// the number of columns and rows if the graph is a full rectangular grid, with at least as many
+/// columns as rows.
+///
+/// Coordinates follow from the hop distances to two corners `a` and `b` on the same side:
+/// `x = (d_a - d_b + d_ab) / 2` and `y = d_a - x`. The result is then checked edge by edge.
+fn grid_shape<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Option<(usize, usize)>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    if graph.nodes.is_empty() || !graph.is_undirected() {
+        return None;
+    }
+    let degree = |id: &IDDataType| graph.edges.get(id).map_or(0, |n| n.len());
+    let min_degree = graph.nodes.iter().map(degree).min()?;
+    let a = *graph.nodes.iter().find(|id| degree(id) == min_degree)?;
+    let from_a = bfs_distances(graph, a);
+    if from_a.len() != graph.nodes.len() {
+        return None;
+    }
+    let b = graph
+        .nodes
+        .iter()
+        .filter(|id| **id != a && degree(id) == min_degree)
+        .min_by_key(|id| from_a[*id])
+        .copied()
+        .unwrap_or(a);
+    let from_b = bfs_distances(graph, b);
+    let side = from_a[&b];
+
+    let mut coordinates = NodeMap::default();
+    for id in &graph.nodes {
+        let twice_x = (from_a[id] + side).checked_sub(from_b[id])?;
+        let x = twice_x / 2;
+        if twice_x % 2 == 1 || x > side {
+            return None;
+        }
+        coordinates.insert(*id, (x, from_a[id].checked_sub(x)?));
+    }
+    let columns = side + 1;
+    let rows = coordinates.values().map(|c| c.1).max()? + 1;
+    let cells: NodeSet<(usize, usize)> = coordinates.values().copied().collect();
+    let edges: NodeSet<(IDDataType, IDDataType)> = graph
+        .edges
+        .iter()
+        .flat_map(|(from, tos)| tos.iter().map(move |to| (*from, *to)))
+        .collect();
+    let unit_steps = edges.iter().all(|(from, to)| {
+        let (p, q) = (coordinates[from], coordinates[to]);
+        p.0.abs_diff(q.0) + p.1.abs_diff(q.1) == 1
+    });
+    let full = cells.len() == columns * rows
+        && edges.len() == 2 * ((columns - 1) * rows + columns * (rows - 1))
+        && edges.len() == graph.edges.values().map(|n| n.len()).sum::<usize>();
+    if !unit_steps || !full {
+        return None;
+    }
+    Some((columns.max(rows), columns.min(rows)))
+}
+
+/// draws a full grid with a box-drawing junction for every node.
+fn draw_grid(columns: usize, rows: usize) -> String {
+    let mut text = String::new();
+    for y in 0..rows {
+        for x in 0..columns {
+            let (up, down) = (y > 0, y + 1 < rows);
+            let (left, right) = (x > 0, x + 1 < columns);
+            text.push(match (up, down, left, right) {
+                (false, false, false, false) => '·',
+                (false, false, false, true) => '╶',
+                (false, false, true, false) => '╴',
+                (false, false, true, true) => '─',
+                (false, true, false, false) => '╷',
+                (true, false, false, false) => '╵',
+                (true, true, false, false) => '│',
+                (false, true, false, true) => '┌',
+                (false, true, true, false) => '┐',
+                (true, false, false, true) => '└',
+                (true, false, true, false) => '┘',
+                (false, true, true, true) => '┬',
+                (true, false, true, true) => '┴',
+                (true, true, false, true) => '├',
+                (true, true, true, false) => '┤',
+                (true, true, true, true) => '┼',
+            });
+            if right {
+                text.push('─');
+            }
+        }
+        text.push('\n');
+        if y + 1 < rows {
+            for x in 0..columns {
+                text.push('│');
+                if x + 1 < columns {
+                    text.push(' ');
+                }
+            }
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// cuts a line to at most `width` characters, ending it in `…` if anything was cut.
+fn truncate(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+    let mut cut: String = line.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        cut.push('…');
+    }
+    cut
+}
+
+/// every edge to draw once, with whether it needs an arrowhead: an edge whose reverse edge also
+/// exists is returned once as undirected, self-loops are left out.
+pub(crate) fn drawn_edges<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Vec<(IDDataType, IDDataType, bool)>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let mut seen = NodeSet::default();
+    let mut drawn = Vec::new();
+    for &from in &graph.nodes {
+        for &to in graph.edges.get(&from).into_iter().flatten() {
+            if from == to || !seen.insert((from, to)) || seen.contains(&(to, from)) {
+                continue;
+            }
+            let directed = !graph
+                .edges
+                .get(&to)
+                .is_some_and(|back| back.contains(&from));
+            drawn.push((from, to, directed));
+        }
+    }
+    drawn
+}
+
+/// maps layout coordinates into the picture, keeping the aspect ratio.
+pub(crate) struct Projection<'a, IDDataType> {
+    layout: &'a Layout<IDDataType>,
+    min: (f64, f64),
+    scale: f64,
+    offset: (f64, f64),
+}
+
+impl<'a, IDDataType> Projection<'a, IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    pub(crate) fn new(
+        layout: &'a Layout<IDDataType>,
+        width: f64,
+        height: f64,
+        margin: f64,
+    ) -> Self {
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(x, y) in layout.values() {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let (inner_w, inner_h) = (width - 2.0 * margin, height - 2.0 * margin);
+        let scale = (inner_w / (max_x - min_x)).min(inner_h / (max_y - min_y));
+        let scale = if scale.is_finite() { scale } else { 0.0 };
+        Self {
+            layout,
+            min: (min_x, min_y),
+            scale,
+            offset: (
+                margin + (inner_w - (max_x - min_x) * scale) / 2.0,
+                margin + (inner_h - (max_y - min_y) * scale) / 2.0,
+            ),
+        }
+    }
+
+    /// the picture coordinates of a node, if it has a position.
+    pub(crate) fn point(&self, id: &IDDataType) -> Option<(f64, f64)> {
+        let (x, y) = self.layout.get(id)?;
+        Some((
+            (x - self.min.0) * self.scale + self.offset.0,
+            (y - self.min.1) * self.scale + self.offset.1,
+        ))
+    }
+}
+
+/// escapes the characters that are special in XML text and attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,372 @@
+//! Rule-based graph rewriting, for graph grammars and L-system style generative models.
+//!
+//! A [`Rule`] has a pattern and a replacement, both small graphs over the same labels. A match
+//! maps every pattern node to a distinct node of the host graph that satisfies the predicates
+//! of its label and has all the pattern edges, other edges may exist too. Applying the rule
+//! then
+//!
+//! * removes the host nodes of labels that are missing from the replacement, with their edges,
+//! * removes the host edges of pattern edges that are missing from the replacement,
+//! * adds a node with the replacement data for every label that is only in the replacement,
+//! * adds the replacement edges that are not in the pattern.
+//!
+//! Host nodes of labels in both graphs keep their data unless the rule updates it. A
+//! [`RewriteSystem`] applies a list of rules one match at a time, at a random match or at as many
+//! non-overlapping matches as possible at once.
+//!
+//! # Example
+//!
+//! ```
+//! use grafferous::{
+//!     rewrite::{Rule, RewriteSystem},
+//!     Graph,
+//! };
+//!
+//! // every tip grows a new tip and stops being one: tip -> stem => tip
+//! let pattern = Graph::<usize, ()>::parse("0").unwrap();
+//! let mut replacement = Graph::<usize, bool>::new();
+//! replacement.add_node_with_data(0, false);
+//! replacement.add_node_with_data(1, true);
+//! replacement.add_directed_edge(0, 1);
+//! let grow = Rule::new(pattern, replacement)
+//!     .when(0, |tip: &bool| *tip)
+//!     .update(0, |_| false);
+//! let system = RewriteSystem::new().rule(grow);
+//!
+//! let mut plant = Graph::<u32, bool>::new();
+//! plant.add_node_with_data(0, true);
+//! for _ in 0..3 {
+//!     system.step_parallel(&mut plant);
+//! }
+//! assert_eq!(plant.nodes, vec![0, 1, 2, 3]);
+//! assert_eq!(plant.edge_tuples().len(), 3);
+//! assert!(plant.node_data[&3]);
+//! ```
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use rand::Rng;
+
+use crate::{
+    collections::{Entry, NodeMap, NodeSet},
+    Graph, NodeIdAllocator,
+};
+
+/// a test on the data of a host node.
+type Predicate<'a, NodeDataType> = Box<dyn Fn(&NodeDataType) -> bool + 'a>;
+/// the new data of a kept host node, computed from its old data.
+type Update<'a, NodeDataType> = Box<dyn Fn(&NodeDataType) -> NodeDataType + 'a>;
+
+/// A rewriting rule: a pattern to find in a host graph and what to replace it with, see the
+/// [module documentation](self).
+pub struct Rule<'a, NodeDataType> {
+    pattern: Graph<usize, ()>,
+    replacement: Graph<usize, NodeDataType>,
+    predicates: Vec<(usize, Predicate<'a, NodeDataType>)>,
+    updates: Vec<(usize, Update<'a, NodeDataType>)>,
+}
+
+impl<'a, NodeDataType> Rule<'a, NodeDataType> {
+    /// A rule that replaces `pattern` with `replacement`. The node data of the replacement is
+    /// the data of the nodes the rule adds; it is ignored for labels that are also in the
+    /// pattern.
+    pub fn new(pattern: Graph<usize, ()>, replacement: Graph<usize, NodeDataType>) -> Self {
+        Self {
+            pattern,
+            replacement,
+            predicates: Vec::new(),
+            updates: Vec::new(),
+        }
+    }
+
+    /// Only matches host nodes whose data satisfies `predicate` to the pattern node `label`.
+    /// Several predicates for the same label must all hold.
+    pub fn when(mut self, label: usize, predicate: impl Fn(&NodeDataType) -> bool + 'a) -> Self {
+        self.predicates.push((label, Box::new(predicate)));
+        self
+    }
+
+    /// Replaces the data of the host node matched to `label`, which must be in both the pattern
+    /// and the replacement, when the rule is applied.
+    pub fn update(
+        mut self,
+        label: usize,
+        update: impl Fn(&NodeDataType) -> NodeDataType + 'a,
+    ) -> Self {
+        self.updates.push((label, Box::new(update)));
+        self
+    }
+
+    /// All matches of the pattern in `host`, each mapping the pattern labels to host nodes.
+    /// Matches are found by backtracking over the pattern nodes, trying the successors of an
+    /// already matched node before all host nodes, and listed in a deterministic order.
+    pub fn matches<IDDataType, EdgeDataType>(
+        &self,
+        host: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Vec<NodeMap<usize, IDDataType>>
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let mut found = Vec::new();
+        self.search(host, &mut |matched| {
+            found.push(matched.clone());
+            true
+        });
+        found
+    }
+
+    /// The first of the matches listed by `matches`, without looking for the others.
+    pub fn first_match<IDDataType, EdgeDataType>(
+        &self,
+        host: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Option<NodeMap<usize, IDDataType>>
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let mut found = None;
+        self.search(host, &mut |matched| {
+            found = Some(matched.clone());
+            false
+        });
+        found
+    }
+
+    /// Rewrites `host` at a match and returns the IDs of the added nodes, in the order of the
+    /// replacement nodes. New IDs are allocated like `Graph::add_node_auto` does. Removed edges
+    /// take their edge data with them, and added edges have none.
+    pub fn apply<IDDataType, EdgeDataType>(
+        &self,
+        host: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        matched: &NodeMap<usize, IDDataType>,
+    ) -> Vec<IDDataType>
+    where
+        IDDataType: NodeIdAllocator,
+        NodeDataType: Clone + Default,
+    {
+        let pattern_edges: NodeSet<(usize, usize)> = self.pattern.edges_iter().collect();
+        let replacement_edges: NodeSet<(usize, usize)> = self.replacement.edges_iter().collect();
+        let kept = |label: &usize| self.replacement.node_data.contains_key(label);
+
+        for (from, to) in &pattern_edges {
+            if kept(from) && kept(to) && !replacement_edges.contains(&(*from, *to)) {
+                host.remove_directed_edge(matched[from], matched[to]);
+            }
+        }
+        for label in self.pattern.nodes.iter().filter(|label| !kept(label)) {
+            host.remove_node(matched[label]);
+        }
+        for (label, update) in &self.updates {
+            if let Some(data) = matched.get(label).and_then(|id| host.node_data.get_mut(id)) {
+                *data = update(data);
+            }
+        }
+
+        let mut ids = matched.clone();
+        let mut added = Vec::new();
+        for label in &self.replacement.nodes {
+            if let Entry::Vacant(slot) = ids.entry(*label) {
+                let id = host.add_node_auto(self.replacement.node_data[label].clone());
+                slot.insert(id);
+                added.push(id);
+            }
+        }
+        let mut new_edges: Vec<(usize, usize)> = replacement_edges
+            .into_iter()
+            .filter(|edge| !pattern_edges.contains(edge))
+            .collect();
+        // sets iterate in an arbitrary order, so sort for a reproducible edge order
+        new_edges.sort_unstable_by_key(|(from, to)| {
+            let position = |label| self.replacement.nodes.iter().position(|l| *l == label);
+            (position(*from), position(*to))
+        });
+        for (from, to) in new_edges {
+            host.add_directed_edge(ids[&from], ids[&to]);
+        }
+        added
+    }
+
+    /// calls `visit` with every match until it returns `false`.
+    fn search<IDDataType, EdgeDataType>(
+        &self,
+        host: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        visit: &mut dyn FnMut(&NodeMap<usize, IDDataType>) -> bool,
+    ) where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        // visit every pattern node after one it is connected to, where possible
+        let mut order: Vec<usize> = Vec::new();
+        for start in &self.pattern.nodes {
+            if order.contains(start) {
+                continue;
+            }
+            order.push(*start);
+            let mut next = order.len() - 1;
+            while let Some(label) = order.get(next).copied() {
+                next += 1;
+                for other in &self.pattern.nodes {
+                    let linked = self.pattern.neighbors(label).contains(other)
+                        || self.pattern.neighbors(*other).contains(&label);
+                    if linked && !order.contains(other) {
+                        order.push(*other);
+                    }
+                }
+            }
+        }
+        let mut matched = NodeMap::default();
+        let mut used = NodeSet::default();
+        self.extend(host, &order, 0, &mut matched, &mut used, visit);
+    }
+
+    /// matches the pattern nodes in `order` from position `depth` on, the ones before being
+    /// matched already; returns `false` once `visit` asked to stop.
+    fn extend<IDDataType, EdgeDataType>(
+        &self,
+        host: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        order: &[usize],
+        depth: usize,
+        matched: &mut NodeMap<usize, IDDataType>,
+        used: &mut NodeSet<IDDataType>,
+        visit: &mut dyn FnMut(&NodeMap<usize, IDDataType>) -> bool,
+    ) -> bool
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let Some(&label) = order.get(depth) else {
+            return visit(matched);
+        };
+        // the successors of a matched predecessor are the only candidates, if there is one
+        let predecessor = order[..depth]
+            .iter()
+            .find(|q| self.pattern.neighbors(**q).contains(&label));
+        let candidates: &[IDDataType] = match predecessor {
+            Some(q) => host.neighbors(matched[q]),
+            None => &host.nodes,
+        };
+        for &candidate in candidates {
+            if used.contains(&candidate) || !self.fits(host, label, candidate, matched) {
+                continue;
+            }
+            matched.insert(label, candidate);
+            used.insert(candidate);
+            let go_on = self.extend(host, order, depth + 1, matched, used, visit);
+            matched.remove(&label);
+            used.remove(&candidate);
+            if !go_on {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// whether `candidate` can stand for `label`, given the nodes matched so far.
+    fn fits<IDDataType, EdgeDataType>(
+        &self,
+        host: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        label: usize,
+        candidate: IDDataType,
+        matched: &NodeMap<usize, IDDataType>,
+    ) -> bool
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        let Some(data) = host.node_data.get(&candidate) else {
+            return false;
+        };
+        if !self
+            .predicates
+            .iter()
+            .all(|(l, predicate)| *l != label || predicate(data))
+        {
+            return false;
+        }
+        let has_edge = |from: IDDataType, to: IDDataType| host.neighbors(from).contains(&to);
+        if self.pattern.neighbors(label).contains(&label) && !has_edge(candidate, candidate) {
+            return false;
+        }
+        matched.iter().all(|(other, id)| {
+            (!self.pattern.neighbors(label).contains(other) || has_edge(candidate, *id))
+                && (!self.pattern.neighbors(*other).contains(&label) || has_edge(*id, candidate))
+        })
+    }
+}
+
+/// A list of rules applied to a host graph step by step, see the [module documentation](self).
+pub struct RewriteSystem<'a, NodeDataType> {
+    rules: Vec<Rule<'a, NodeDataType>>,
+}
+
+impl<'a, NodeDataType: Clone + Default> RewriteSystem<'a, NodeDataType> {
+    /// A system without rules.
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Adds a rule; earlier rules take precedence.
+    pub fn rule(mut self, rule: Rule<'a, NodeDataType>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Applies the first rule that matches at its first match. Returns whether a rule was
+    /// applied.
+    pub fn step_first<IDDataType: NodeIdAllocator, EdgeDataType>(
+        &self,
+        host: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> bool {
+        for rule in &self.rules {
+            if let Some(matched) = rule.first_match(host) {
+                rule.apply(host, &matched);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Applies a rule at a match picked uniformly from the matches of all rules. Returns whether
+    /// there was a match.
+    pub fn step_random<IDDataType: NodeIdAllocator, EdgeDataType, R: Rng>(
+        &self,
+        host: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+        mut rng: R,
+    ) -> bool {
+        let mut all: Vec<(usize, NodeMap<usize, IDDataType>)> = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            all.extend(rule.matches(host).into_iter().map(|matched| (i, matched)));
+        }
+        if all.is_empty() {
+            return false;
+        }
+        let (i, matched) = all.swap_remove(rng.gen_range(0..all.len()));
+        self.rules[i].apply(host, &matched);
+        true
+    }
+
+    /// Applies the rules at as many matches as possible at once, as an L-system rewrites every
+    /// symbol in one step. Matches are taken greedily, rules in order, skipping every match that
+    /// shares a host node with one taken before. Returns the number of rewrites.
+    pub fn step_parallel<IDDataType: NodeIdAllocator, EdgeDataType>(
+        &self,
+        host: &mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> usize {
+        let mut used = NodeSet::default();
+        let mut taken = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            for matched in rule.matches(host) {
+                if matched.values().all(|id| !used.contains(id)) {
+                    used.extend(matched.values().copied());
+                    taken.push((i, matched));
+                }
+            }
+        }
+        for (i, matched) in &taken {
+            self.rules[*i].apply(host, matched);
+        }
+        taken.len()
+    }
+}
+
+impl<NodeDataType: Clone + Default> Default for RewriteSystem<'_, NodeDataType> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,520 @@
+//! Shortest paths under routing constraints, built on the searches in [`crate::visit`], and
+//! landmark bounds for goal-directed search.
+
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+use core::{
+    cmp::{Ordering, Reverse},
+    fmt::Debug,
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    distance::{bfs_row, farthest, UNREACHABLE},
+    visit::{bfs_path, Neighbors, Nodes},
+    Graph,
+};
+
+/// A view of a graph that hides some nodes and directed edges, so the generic searches in
+/// [`crate::visit`] can route around them without copying the graph.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{routing::Avoiding, visit::bfs_distances, Graph};
+///
+/// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2; 0 -> 3 -> 4 -> 2").unwrap();
+/// let detour = Avoiding::new(&g, [1], []);
+/// assert_eq!(bfs_distances(&detour, 0)[&2], 3);
+/// ```
+pub struct Avoiding<'a, G: Nodes> {
+    graph: &'a G,
+    nodes: NodeSet<G::Id>,
+    edges: NodeSet<(G::Id, G::Id)>,
+}
+
+impl<'a, G: Nodes> Avoiding<'a, G> {
+    /// Hides the given nodes, with all their edges, and the given directed edges.
+    pub fn new(
+        graph: &'a G,
+        nodes: impl IntoIterator<Item = G::Id>,
+        edges: impl IntoIterator<Item = (G::Id, G::Id)>,
+    ) -> Self {
+        Self {
+            graph,
+            nodes: nodes.into_iter().collect(),
+            edges: edges.into_iter().collect(),
+        }
+    }
+}
+
+impl<G: Nodes> Nodes for Avoiding<'_, G> {
+    type Id = G::Id;
+
+    fn node_ids(&self) -> impl Iterator<Item = G::Id> + '_ {
+        self.graph.node_ids().filter(|id| !self.nodes.contains(id))
+    }
+
+    fn node_count(&self) -> usize {
+        self.node_ids().count()
+    }
+
+    fn contains_node(&self, id: G::Id) -> bool {
+        !self.nodes.contains(&id) && self.graph.contains_node(id)
+    }
+}
+
+impl<G: Neighbors> Neighbors for Avoiding<'_, G> {
+    fn successors(&self, id: G::Id) -> impl Iterator<Item = G::Id> + '_ {
+        let hidden = self.nodes.contains(&id);
+        self.graph.successors(id).filter(move |to| {
+            !hidden && !self.nodes.contains(to) && !self.edges.contains(&(id, *to))
+        })
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A path with the fewest edges from `from` to `to` that does not pass through any of the
+    /// banned nodes or use any of the banned edges, including both ends. Returns `None` if there
+    /// is no such path or an end is banned.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the path.
+    /// * `to` - The end of the path.
+    /// * `banned_nodes` - Nodes the path must not visit.
+    /// * `banned_edges` - Directed edges the path must not use; ban both directions to block an
+    ///   undirected edge.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let g = generate_grid_graph::<()>(3, 3);
+    /// let path = g.shortest_path_avoiding((0, 0), (2, 0), &[(1, 0)], &[]).unwrap();
+    /// assert_eq!(path.len(), 5);
+    /// assert!(!path.contains(&(1, 0)));
+    /// ```
+    pub fn shortest_path_avoiding(
+        &self,
+        from: IDDataType,
+        to: IDDataType,
+        banned_nodes: &[IDDataType],
+        banned_edges: &[(IDDataType, IDDataType)],
+    ) -> Option<Vec<IDDataType>> {
+        let view = Avoiding::new(
+            self,
+            banned_nodes.iter().copied(),
+            banned_edges.iter().copied(),
+        );
+        bfs_path(&view, from, to)
+    }
+
+    /// A route from `from` to `to` that visits the waypoints in order, made of a path with the
+    /// fewest edges between each pair of consecutive stops. The route includes both ends and every
+    /// waypoint, and may visit a node more than once. Returns `None` if a stop cannot be reached
+    /// from the previous one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3; 1 -- 4").unwrap();
+    /// assert_eq!(g.route_via(0, &[4], 3), Some(vec![0, 1, 4, 1, 2, 3]));
+    /// ```
+    pub fn route_via(
+        &self,
+        from: IDDataType,
+        waypoints: &[IDDataType],
+        to: IDDataType,
+    ) -> Option<Vec<IDDataType>> {
+        let mut route = Vec::from([from]);
+        let mut current = from;
+        for stop in waypoints.iter().copied().chain([to]) {
+            let leg = bfs_path(self, current, stop)?;
+            route.extend_from_slice(&leg[1..]);
+            current = stop;
+        }
+        Some(route)
+    }
+
+    /// A path from `from` to `to` whose narrowest edge is as wide as possible, as used for
+    /// routing by bandwidth. The search is Dijkstra's algorithm with the bottleneck capacity in
+    /// place of the length, always extending the widest path found so far. Returns `None` if `to`
+    /// cannot be reached or either node is not in the graph; a path from a node to itself is just
+    /// the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the path.
+    /// * `to` - The end of the path.
+    /// * `capacity` - The capacity of the directed edge between two nodes. Capacities that cannot
+    ///   be compared, like `NaN`, count as equal to any other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3").unwrap();
+    /// let capacity = |from: u32, to: u32| match (from, to) {
+    ///     (0, 1) => 10.0,
+    ///     (1, 3) => 1.0,
+    ///     _ => 5.0,
+    /// };
+    /// assert_eq!(g.widest_path(0, 3, capacity), Some(vec![0, 2, 3]));
+    /// ```
+    pub fn widest_path<C: Copy + PartialOrd>(
+        &self,
+        from: IDDataType,
+        to: IDDataType,
+        capacity: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> Option<Vec<IDDataType>> {
+        if !self.node_data.contains_key(&from) || !self.node_data.contains_key(&to) {
+            return None;
+        }
+        // the bottleneck of the widest known path to every node but `from`, which has none
+        let mut widths: NodeMap<IDDataType, C> = NodeMap::default();
+        let mut parents: NodeMap<IDDataType, IDDataType> = NodeMap::default();
+        let mut done = NodeSet::default();
+        let mut queue = BinaryHeap::new();
+        let mut next = Some(from);
+        while let Some(node) = next {
+            if node == to {
+                let mut path = Vec::from([to]);
+                let mut current = to;
+                while current != from {
+                    current = parents[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            done.insert(node);
+            let width = widths.get(&node).copied();
+            for neighbor in self.neighbors(node) {
+                if done.contains(neighbor) {
+                    continue;
+                }
+                let edge = capacity(node, *neighbor);
+                let through = match width {
+                    Some(width) if width < edge => width,
+                    _ => edge,
+                };
+                if widths.get(neighbor).is_none_or(|known| through > *known) {
+                    widths.insert(*neighbor, through);
+                    parents.insert(*neighbor, node);
+                    queue.push(Ranked(through, *neighbor));
+                }
+            }
+            // entries for nodes that were reached by a wider path later are stale
+            next = core::iter::from_fn(|| queue.pop())
+                .map(|Ranked(_, id)| id)
+                .find(|id| !done.contains(id));
+        }
+        None
+    }
+
+    /// A cheapest path from `from` to `to` whose total use of a second resource, such as time,
+    /// battery charge or tolls, stays within a budget. Plain shortest paths cannot express such a
+    /// limit, since the cheapest way to a node may use too much of the resource to continue.
+    ///
+    /// The search is label setting: every node keeps the (cost, resource) pairs of the paths that
+    /// reach it and are not dominated, meaning no other path there is both as cheap and as
+    /// frugal, and paths are extended cheapest first. Costs and resources must not be negative,
+    /// and the zero of both is their `Default`. Returns `None` if no path stays within the budget
+    /// or either node is not in the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The start of the path.
+    /// * `to` - The end of the path.
+    /// * `cost` - The cost of the directed edge between two nodes, which is minimized.
+    /// * `resource` - The resource the directed edge between two nodes uses up.
+    /// * `budget` - The most resource the whole path may use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // the toll road 0 -> 1 -> 3 is faster than the free road 0 -> 2 -> 3
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3").unwrap();
+    /// let minutes = |from: u32, _to: u32| if from == 2 { 30 } else { 10 };
+    /// let toll = |from: u32, to: u32| if (from, to) == (0, 1) { 5 } else { 0 };
+    ///
+    /// assert_eq!(g.constrained_shortest_path(0, 3, minutes, toll, 5), Some(vec![0, 1, 3]));
+    /// assert_eq!(g.constrained_shortest_path(0, 3, minutes, toll, 4), Some(vec![0, 2, 3]));
+    /// ```
+    pub fn constrained_shortest_path<C, R>(
+        &self,
+        from: IDDataType,
+        to: IDDataType,
+        cost: impl Fn(IDDataType, IDDataType) -> C,
+        resource: impl Fn(IDDataType, IDDataType) -> R,
+        budget: R,
+    ) -> Option<Vec<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+        R: Copy + PartialOrd + Add<Output = R> + Default,
+    {
+        if !self.node_data.contains_key(&from) || !self.node_data.contains_key(&to) {
+            return None;
+        }
+        let mut labels = Vec::from([Label {
+            node: from,
+            cost: C::default(),
+            resource: R::default(),
+            parent: None,
+            dominated: false,
+        }]);
+        // the labels at every node that are not dominated
+        let mut frontier: NodeMap<IDDataType, Vec<usize>> = NodeMap::default();
+        frontier.insert(from, Vec::from([0]));
+        let mut queue = BinaryHeap::from([Reverse(Ranked(C::default(), 0))]);
+        while let Some(Reverse(Ranked(_, current))) = queue.pop() {
+            if labels[current].dominated {
+                continue;
+            }
+            let node = labels[current].node;
+            if node == to {
+                let mut path = Vec::new();
+                let mut label = Some(current);
+                while let Some(i) = label {
+                    path.push(labels[i].node);
+                    label = labels[i].parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for neighbor in self.neighbors(node) {
+                let next_cost = labels[current].cost + cost(node, *neighbor);
+                let next_resource = labels[current].resource + resource(node, *neighbor);
+                if next_resource > budget {
+                    continue;
+                }
+                let known = frontier.entry(*neighbor).or_default();
+                if known
+                    .iter()
+                    .any(|&i| labels[i].cost <= next_cost && labels[i].resource <= next_resource)
+                {
+                    continue;
+                }
+                known.retain(|&i| {
+                    let dominated =
+                        next_cost <= labels[i].cost && next_resource <= labels[i].resource;
+                    labels[i].dominated |= dominated;
+                    !dominated
+                });
+                known.push(labels.len());
+                queue.push(Reverse(Ranked(next_cost, labels.len())));
+                labels.push(Label {
+                    node: *neighbor,
+                    cost: next_cost,
+                    resource: next_resource,
+                    parent: Some(current),
+                    dominated: false,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// a partial path in `Graph::constrained_shortest_path`, ending at `node`.
+struct Label<IDDataType, C, R> {
+    node: IDDataType,
+    cost: C,
+    resource: R,
+    /// the label the path was extended from
+    parent: Option<usize>,
+    /// whether a path to the same node that is as cheap and as frugal was found since
+    dominated: bool,
+}
+
+/// Precomputed hop distances to and from a few landmark nodes, giving a lower bound on the
+/// distance between any two nodes by the triangle inequality (the ALT technique: A*, landmarks,
+/// triangle inequality).
+///
+/// The bound is an admissible A* heuristic, so repeated goal-directed searches on a large graph
+/// explore far fewer nodes than breadth-first search. The index describes the graph at the time
+/// it was built; rebuild it after removing edges, or the bound may overestimate.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_grid_graph, routing::AltIndex};
+///
+/// let g = generate_grid_graph::<()>(30, 30);
+/// let index = AltIndex::build(&g, 4);
+/// assert!(index.lower_bound((0, 0), (29, 29)) <= 58);
+///
+/// let path = index.shortest_path(&g, (3, 4), (20, 25)).unwrap();
+/// assert_eq!(path.len(), 17 + 21 + 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AltIndex<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    index: NodeMap<IDDataType, usize>,
+    landmarks: Vec<IDDataType>,
+    /// for every landmark, the distances from it to every node
+    from_landmark: Vec<Vec<u32>>,
+    /// for every landmark, the distances from every node to it
+    to_landmark: Vec<Vec<u32>>,
+}
+
+impl<IDDataType> AltIndex<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Picks up to `landmarks` landmarks and measures the distances to and from them, with two
+    /// breadth-first searches per landmark. The first landmark is a node of highest degree and
+    /// every further one is the node farthest from the landmarks so far, which spreads them
+    /// around the edge of the graph where they give the tightest bounds.
+    pub fn build<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        landmarks: usize,
+    ) -> Self {
+        let n = graph.nodes.len();
+        let (forward, backward) = graph.index_adjacency(true);
+        let mut alt = Self {
+            index: graph
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (*id, i))
+                .collect(),
+            landmarks: Vec::new(),
+            from_landmark: Vec::new(),
+            to_landmark: Vec::new(),
+        };
+        let mut next = (0..n).max_by_key(|&i| (forward[i].len() + backward[i].len(), n - i));
+        let mut nearest = vec![UNREACHABLE; n];
+        while let Some(landmark) = next.filter(|_| alt.landmarks.len() < landmarks) {
+            let mut from = vec![UNREACHABLE; n];
+            bfs_row(&forward, landmark, &mut from);
+            let mut to = vec![UNREACHABLE; n];
+            bfs_row(&backward, landmark, &mut to);
+            for (closest, distance) in nearest.iter_mut().zip(&from) {
+                *closest = (*closest).min(*distance);
+            }
+            alt.landmarks.push(graph.nodes[landmark]);
+            alt.from_landmark.push(from);
+            alt.to_landmark.push(to);
+
+            let (far, distance) = farthest(&nearest);
+            // once every reachable node is a landmark, start over in a part not reached yet
+            next = if distance > 0 {
+                Some(far)
+            } else {
+                nearest.iter().position(|d| *d == UNREACHABLE)
+            };
+        }
+        alt
+    }
+
+    /// The landmarks, in the order they were picked.
+    pub fn landmarks(&self) -> &[IDDataType] {
+        &self.landmarks
+    }
+
+    /// A lower bound on the number of hops from `from` to `to`; zero if either node was not in
+    /// the graph when the index was built.
+    pub fn lower_bound(&self, from: IDDataType, to: IDDataType) -> u32 {
+        let (Some(&v), Some(&t)) = (self.index.get(&from), self.index.get(&to)) else {
+            return 0;
+        };
+        let mut bound = 0;
+        for (from_l, to_l) in self.from_landmark.iter().zip(&self.to_landmark) {
+            // d(l, t) <= d(l, v) + d(v, t)
+            if from_l[v] != UNREACHABLE && from_l[t] != UNREACHABLE {
+                bound = bound.max(from_l[t].saturating_sub(from_l[v]));
+            }
+            // d(v, l) <= d(v, t) + d(t, l)
+            if to_l[v] != UNREACHABLE && to_l[t] != UNREACHABLE {
+                bound = bound.max(to_l[v].saturating_sub(to_l[t]));
+            }
+        }
+        bound
+    }
+
+    /// The lower bound towards `goal` as a heuristic for A* searches.
+    pub fn heuristic(&self, goal: IDDataType) -> impl Fn(IDDataType) -> u32 + '_ {
+        move |id| self.lower_bound(id, goal)
+    }
+
+    /// A path with the fewest edges from `from` to `to`, found by A* search guided by the
+    /// landmarks. Returns `None` if `to` cannot be reached or either node is not in the graph.
+    pub fn shortest_path<NodeDataType, EdgeDataType>(
+        &self,
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+        from: IDDataType,
+        to: IDDataType,
+    ) -> Option<Vec<IDDataType>> {
+        if !graph.node_data.contains_key(&from) || !graph.node_data.contains_key(&to) {
+            return None;
+        }
+        let heuristic = self.heuristic(to);
+        let mut hops: NodeMap<IDDataType, u32> = NodeMap::default();
+        let mut parents: NodeMap<IDDataType, IDDataType> = NodeMap::default();
+        // the queue holds positions in `entries`, ordered by estimated total length
+        let mut entries = Vec::from([from]);
+        let mut queue = BinaryHeap::from([(Reverse(heuristic(from)), 0)]);
+        hops.insert(from, 0);
+        while let Some((_, entry)) = queue.pop() {
+            let node = entries[entry];
+            if node == to {
+                let mut path = Vec::from([to]);
+                let mut current = to;
+                while current != from {
+                    current = parents[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let next = hops[&node] + 1;
+            for neighbor in graph.neighbors(node) {
+                if hops.get(neighbor).is_none_or(|known| next < *known) {
+                    hops.insert(*neighbor, next);
+                    parents.insert(*neighbor, node);
+                    queue.push((Reverse(next + heuristic(*neighbor)), entries.len()));
+                    entries.push(*neighbor);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// a queue entry ordered by its key alone, so that `BinaryHeap` pops the largest key first; keys
+/// that cannot be compared count as equal.
+pub(crate) struct Ranked<C, IDDataType>(pub(crate) C, pub(crate) IDDataType);
+
+impl<C: PartialOrd, IDDataType> PartialEq for Ranked<C, IDDataType> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: PartialOrd, IDDataType> Eq for Ranked<C, IDDataType> {}
+
+impl<C: PartialOrd, IDDataType> PartialOrd for Ranked<C, IDDataType> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: PartialOrd, IDDataType> Ord for Ranked<C, IDDataType> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
@@ -0,0 +1,334 @@
+//! Downsampling large graphs to induced subgraphs of a target size or around random seeds.
+//!
+//! Every strategy picks a set of nodes and returns the subgraph they induce: the picked nodes
+//! with their data, in the order of `Graph::nodes`, and every edge between two of them with its
+//! data. The strategies differ in which structure they preserve, and all of them are reproducible
+//! with a seeded RNG.
+//!
+//! # Example
+//!
+//! ```
+//! use grafferous::{generate_grid_graph, sampling};
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let g = generate_grid_graph::<()>(50, 50);
+//! let sample = sampling::forest_fire_sample(&g, 100, 0.7, StdRng::seed_from_u64(3));
+//! assert_eq!(sample.nodes.len(), 100);
+//!
+//! let again = sampling::forest_fire_sample(&g, 100, 0.7, StdRng::seed_from_u64(3));
+//! assert_eq!(sample.nodes, again.nodes);
+//! ```
+
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use rand::Rng;
+
+use crate::{collections::NodeSet, Graph};
+
+/// the probability that a random walk sample jumps back to its start in a step.
+const RESTART_PROBABILITY: f64 = 0.15;
+/// the number of steps without a new node after which a random walk sample starts over
+/// somewhere else.
+const STALL_STEPS: usize = 100;
+
+/// Picks `size` nodes uniformly at random, or all nodes if the graph is smaller.
+///
+/// This keeps the node data distribution but thins out the edges quadratically, so small samples
+/// of sparse graphs have few edges.
+pub fn node_sample<IDDataType, NodeDataType, EdgeDataType, R>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    size: usize,
+    mut rng: R,
+) -> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+    R: Rng,
+{
+    let mut unpicked = Unpicked::new(graph);
+    let mut picked = NodeSet::default();
+    while picked.len() < size {
+        let Some(id) = unpicked.next(&picked, &mut rng) else {
+            break;
+        };
+        picked.insert(id);
+    }
+    induced(graph, &picked)
+}
+
+/// Picks directed edges uniformly at random and keeps both of their ends until `size` nodes are
+/// picked; if the edges run out, the rest are picked uniformly from the remaining nodes.
+///
+/// Nodes are picked with probability growing with their degree, so hubs are kept.
+pub fn edge_sample<IDDataType, NodeDataType, EdgeDataType, R>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    size: usize,
+    mut rng: R,
+) -> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+    R: Rng,
+{
+    let mut edges: Vec<(IDDataType, IDDataType)> = graph
+        .nodes
+        .iter()
+        .flat_map(|from| graph.neighbors(*from).iter().map(|to| (*from, *to)))
+        .collect();
+    let mut picked = NodeSet::default();
+    while picked.len() < size && !edges.is_empty() {
+        let (from, to) = edges.swap_remove(rng.gen_range(0..edges.len()));
+        picked.insert(from);
+        if picked.len() < size && graph.node_data.contains_key(&to) {
+            picked.insert(to);
+        }
+    }
+    let mut unpicked = Unpicked::new(graph);
+    while picked.len() < size {
+        let Some(id) = unpicked.next(&picked, &mut rng) else {
+            break;
+        };
+        picked.insert(id);
+    }
+    induced(graph, &picked)
+}
+
+/// Picks the nodes visited by a random walk with restarts until `size` nodes are picked.
+///
+/// The walk starts at a random node, follows a random outgoing edge in every step and jumps back
+/// to its start with probability 0.15, or when it reaches a node without outgoing edges. If it
+/// finds no new node for 100 steps, it starts over at a random node that was not picked yet.
+/// The sample keeps the local structure around the start nodes.
+pub fn random_walk_sample<IDDataType, NodeDataType, EdgeDataType, R>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    size: usize,
+    mut rng: R,
+) -> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+    R: Rng,
+{
+    let mut unpicked = Unpicked::new(graph);
+    let mut picked = NodeSet::default();
+    let mut start = None;
+    let mut current = None;
+    let mut stalled = 0;
+    while picked.len() < size {
+        if stalled >= STALL_STEPS {
+            start = None;
+        }
+        let node = match (start, current) {
+            (Some(start), Some(current)) => {
+                let neighbors = graph.neighbors(current);
+                if neighbors.is_empty() || rng.gen_bool(RESTART_PROBABILITY) {
+                    start
+                } else {
+                    neighbors[rng.gen_range(0..neighbors.len())]
+                }
+            }
+            _ => {
+                let Some(id) = unpicked.next(&picked, &mut rng) else {
+                    break;
+                };
+                start = Some(id);
+                id
+            }
+        };
+        current = Some(node);
+        if picked.insert(node) {
+            stalled = 0;
+        } else {
+            stalled += 1;
+        }
+    }
+    induced(graph, &picked)
+}
+
+/// Picks nodes by forest fire sampling until `size` nodes are picked.
+///
+/// A fire starts at a random node and spreads in waves: every burning node sets a random number
+/// of its unburnt outgoing neighbors on fire, geometrically distributed with mean
+/// `p / (1 - p)` for the `forward_probability` `p`. When the fire dies out, a new one starts at a
+/// random unburnt node. Forest fires keep communities and the heavy tail of the degree
+/// distribution better than uniform samples.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to sample.
+/// * `size` - The number of nodes to pick.
+/// * `forward_probability` - How far the fire spreads, between 0 and 1; 0.7 is a common choice.
+/// * `rng` - The source of randomness.
+pub fn forest_fire_sample<IDDataType, NodeDataType, EdgeDataType, R>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    size: usize,
+    forward_probability: f64,
+    mut rng: R,
+) -> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+    R: Rng,
+{
+    let forward_probability = forward_probability.clamp(0.0, 1.0);
+    let mut unpicked = Unpicked::new(graph);
+    let mut picked = NodeSet::default();
+    let mut burning = VecDeque::new();
+    while picked.len() < size {
+        let Some(node) = burning.pop_front() else {
+            let Some(seed) = unpicked.next(&picked, &mut rng) else {
+                break;
+            };
+            picked.insert(seed);
+            burning.push_back(seed);
+            continue;
+        };
+        let mut unburnt: Vec<IDDataType> = graph
+            .neighbors(node)
+            .iter()
+            .copied()
+            .filter(|id| !picked.contains(id) && graph.node_data.contains_key(id))
+            .collect();
+        let mut spread = 0;
+        while spread < unburnt.len() && rng.gen_bool(forward_probability) {
+            spread += 1;
+        }
+        for _ in 0..spread {
+            let next = unburnt.swap_remove(rng.gen_range(0..unburnt.len()));
+            if picked.len() < size && picked.insert(next) {
+                burning.push_back(next);
+            }
+        }
+    }
+    induced(graph, &picked)
+}
+
+/// Picks `seeds` nodes uniformly at random and every node within `depth` outgoing edges of them,
+/// by snowball sampling: every wave adds the unpicked outgoing neighbors of the previous one.
+///
+/// Unlike the other strategies, the sample has no target size: it keeps the complete
+/// neighborhoods of the seeds, which suits estimating local statistics such as degrees or
+/// clustering around them. A depth of 0 picks the seeds alone.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to sample.
+/// * `seeds` - The number of nodes to start from, or all nodes if the graph is smaller.
+/// * `depth` - The number of waves, the largest hop distance from a seed to a picked node.
+/// * `rng` - The source of randomness for picking the seeds.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{generate_cycle_graph, sampling};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // a path of three nodes to either side of the seed
+/// let g = generate_cycle_graph::<()>(100);
+/// let sample = sampling::snowball_sample(&g, 1, 3, StdRng::seed_from_u64(4));
+/// assert_eq!(sample.nodes.len(), 7);
+/// assert_eq!(sample.edge_count(), 12);
+/// ```
+pub fn snowball_sample<IDDataType, NodeDataType, EdgeDataType, R>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    seeds: usize,
+    depth: usize,
+    mut rng: R,
+) -> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+    R: Rng,
+{
+    let mut unpicked = Unpicked::new(graph);
+    let mut picked = NodeSet::default();
+    let mut wave = Vec::new();
+    while wave.len() < seeds {
+        let Some(id) = unpicked.next(&picked, &mut rng) else {
+            break;
+        };
+        picked.insert(id);
+        wave.push(id);
+    }
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for node in wave {
+            for neighbor in graph.neighbors(node) {
+                if graph.node_data.contains_key(neighbor) && picked.insert(*neighbor) {
+                    next.push(*neighbor);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        wave = next;
+    }
+    induced(graph, &picked)
+}
+
+/// the nodes of a graph in random order, drawn lazily.
+struct Unpicked<IDDataType> {
+    remaining: Vec<IDDataType>,
+}
+
+impl<IDDataType> Unpicked<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn new<NodeDataType, EdgeDataType>(
+        graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ) -> Self {
+        Self {
+            remaining: graph.nodes.clone(),
+        }
+    }
+
+    /// a uniformly random node that is not picked yet, or `None` if all are.
+    fn next<R: Rng>(&mut self, picked: &NodeSet<IDDataType>, rng: &mut R) -> Option<IDDataType> {
+        while !self.remaining.is_empty() {
+            let id = self
+                .remaining
+                .swap_remove(rng.gen_range(0..self.remaining.len()));
+            if !picked.contains(&id) {
+                return Some(id);
+            }
+        }
+        None
+    }
+}
+
+/// the picked nodes with their data, in the order of `graph.nodes`, and the edges between them
+/// with their data.
+fn induced<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    picked: &NodeSet<IDDataType>,
+) -> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+{
+    let mut sample = Graph::with_hasher(graph.node_data.hasher().clone());
+    sample.set_edge_policy(graph.edge_policy());
+    for id in graph.nodes.iter().filter(|id| picked.contains(*id)) {
+        sample.add_node_with_data(*id, graph.node_data[id].clone());
+    }
+    for from in &graph.nodes {
+        for to in graph.neighbors(*from) {
+            if picked.contains(from) && picked.contains(to) {
+                sample.edges.entry(*from).or_default().push(*to);
+                sample.reverse_edges.entry(*to).or_default().push(*from);
+                if let Some(data) = graph.edge_data(*from, *to) {
+                    sample.edge_data.insert((*from, *to), data.clone());
+                }
+            }
+        }
+    }
+    sample
+}
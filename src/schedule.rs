@@ -0,0 +1,300 @@
+//! Scheduling the tasks of a dependency graph on a fixed number of workers.
+//!
+//! Every node is a task and a directed edge `a -> b` means that `b` can only start once `a` has
+//! finished, as in a build system or a task runner.
+
+use alloc::{
+    collections::{BinaryHeap, VecDeque},
+    vec,
+    vec::Vec,
+};
+use core::{cmp::Reverse, fmt::Debug, hash::Hash, ops::Add};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    routing::Ranked,
+    CycleError, Graph,
+};
+
+/// When and where every task runs, as computed by [`list_schedule`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule<IDDataType, T>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The start time of every task.
+    pub start: NodeMap<IDDataType, T>,
+    /// The worker, in `0..workers`, that runs every task.
+    pub worker: NodeMap<IDDataType, usize>,
+    /// The tasks in the order they start.
+    pub order: Vec<IDDataType>,
+    /// The time at which the last task finishes.
+    pub makespan: T,
+}
+
+/// The length of the longest chain of tasks starting at every task, including its own duration.
+///
+/// This is the critical path priority used by [`list_schedule`]: the tasks with the longest
+/// remaining chain are the ones that delay the end of the schedule if they start late.
+pub fn critical_path_lengths<IDDataType, NodeDataType, EdgeDataType, T>(
+    dag: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    duration: impl Fn(IDDataType, &NodeDataType) -> T,
+) -> Result<NodeMap<IDDataType, T>, CycleError<IDDataType>>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    T: Copy + PartialOrd + Add<Output = T> + Default,
+{
+    let order = dag.topological_sort()?;
+    let mut lengths: NodeMap<IDDataType, T> = NodeMap::default();
+    for id in order.iter().rev() {
+        let mut longest = T::default();
+        for next in dag.neighbors(*id) {
+            if lengths[next] > longest {
+                longest = lengths[next];
+            }
+        }
+        lengths.insert(*id, duration(*id, &dag.node_data[id]) + longest);
+    }
+    Ok(lengths)
+}
+
+/// Schedules the tasks of a directed acyclic graph on `workers` identical workers.
+///
+/// This is list scheduling: whenever a worker is free, it starts the ready task with the longest
+/// critical path, a task being ready once all of its predecessors have finished. Ties go to the
+/// task that comes first in `dag.nodes`. The result is not always optimal, which is NP-hard, but
+/// never takes longer than twice the optimum.
+///
+/// # Arguments
+///
+/// * `dag` - The tasks, with an edge from every task to the tasks that depend on it.
+/// * `workers` - The number of tasks that can run at the same time; must not be zero.
+/// * `duration` - How long a task takes; durations must not be negative.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{schedule::list_schedule, Graph};
+///
+/// // compiling 1 and 2 needs 0, linking 3 needs both
+/// let mut dag = Graph::<u32, u64>::new();
+/// for (id, seconds) in [(0, 1), (1, 4), (2, 2), (3, 1)] {
+///     dag.add_node_with_data(id, seconds);
+/// }
+/// dag.add_directed_edges(&[(0, 1), (0, 2), (1, 3), (2, 3)]);
+///
+/// let schedule = list_schedule(&dag, 2, |_, seconds| *seconds).unwrap();
+/// assert_eq!(schedule.start[&3], 5);
+/// assert_eq!(schedule.makespan, 6);
+/// assert_ne!(schedule.worker[&1], schedule.worker[&2]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `workers` is zero.
+pub fn list_schedule<IDDataType, NodeDataType, EdgeDataType, T>(
+    dag: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    workers: usize,
+    duration: impl Fn(IDDataType, &NodeDataType) -> T,
+) -> Result<Schedule<IDDataType, T>, CycleError<IDDataType>>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    T: Copy + PartialOrd + Add<Output = T> + Default,
+{
+    assert!(workers > 0, "a schedule needs at least one worker");
+    let priorities = critical_path_lengths(dag, &duration)?;
+    let index: NodeMap<IDDataType, usize> = dag
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (*id, i))
+        .collect();
+    let mut waiting_for = vec![0usize; dag.nodes.len()];
+    for to in dag.edges.values().flatten() {
+        waiting_for[index[to]] += 1;
+    }
+
+    let mut schedule = Schedule {
+        start: NodeMap::default(),
+        worker: NodeMap::default(),
+        order: Vec::with_capacity(dag.nodes.len()),
+        makespan: T::default(),
+    };
+    // ready tasks by priority, then position
+    let mut ready: BinaryHeap<_> = (0..dag.nodes.len())
+        .filter(|i| waiting_for[*i] == 0)
+        .map(|i| (Ranked(priorities[&dag.nodes[i]], ()), Reverse(i)))
+        .collect();
+    // running tasks by finish time, then worker
+    let mut running = BinaryHeap::new();
+    let mut idle: Vec<usize> = (0..workers).rev().collect();
+    let mut now = T::default();
+    loop {
+        while let Some(worker) = idle.pop() {
+            let Some((_, Reverse(task))) = ready.pop() else {
+                idle.push(worker);
+                break;
+            };
+            let id = dag.nodes[task];
+            let finish = now + duration(id, &dag.node_data[&id]);
+            schedule.start.insert(id, now);
+            schedule.worker.insert(id, worker);
+            schedule.order.push(id);
+            running.push(Reverse((Ranked(finish, ()), worker, task)));
+        }
+
+        let Some(Reverse((Ranked(finish, ()), worker, task))) = running.pop() else {
+            break;
+        };
+        // finish every task that ends at the same time before picking the next ones
+        now = finish;
+        schedule.makespan = finish;
+        let mut finished = Vec::from([(worker, task)]);
+        while let Some(Reverse((Ranked(next, ()), _, _))) = running.peek() {
+            if *next > now {
+                break;
+            }
+            if let Some(Reverse((_, worker, task))) = running.pop() {
+                finished.push((worker, task));
+            }
+        }
+        for (worker, task) in finished {
+            idle.push(worker);
+            for next in dag.neighbors(dag.nodes[task]) {
+                let next = index[next];
+                waiting_for[next] -= 1;
+                if waiting_for[next] == 0 {
+                    ready.push((Ranked(priorities[&dag.nodes[next]], ()), Reverse(next)));
+                }
+            }
+        }
+        // free workers are handed out lowest first
+        idle.sort_unstable_by_key(|worker| Reverse(*worker));
+    }
+    Ok(schedule)
+}
+
+/// An iterator over the tasks of a dependency graph that are ready to run, created by
+/// `Graph::topological_iter`.
+///
+/// A task is yielded once all of its predecessors were reported finished with
+/// [`TopologicalIter::mark_done`], so an execution engine only starts tasks whose dependencies
+/// actually completed. `next` returns `None` whenever nothing is ready yet; iteration resumes
+/// after the next `mark_done`. Marking every task done as soon as it is yielded visits the nodes
+/// in topological order.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::Graph;
+///
+/// let dag = Graph::<u32, ()>::parse("0 -> 2; 1 -> 2 -> 3").unwrap();
+/// let mut tasks = dag.topological_iter();
+/// assert_eq!(tasks.by_ref().collect::<Vec<_>>(), vec![0, 1]);
+///
+/// tasks.mark_done(0);
+/// assert_eq!(tasks.next(), None);
+/// tasks.mark_done(1);
+/// assert_eq!(tasks.next(), Some(2));
+/// tasks.mark_done(2);
+/// assert_eq!(tasks.next(), Some(3));
+/// tasks.mark_done(3);
+/// assert!(tasks.is_finished());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TopologicalIter<'a, IDDataType, NodeDataType, EdgeDataType = ()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    graph: &'a Graph<IDDataType, NodeDataType, EdgeDataType>,
+    /// the number of unfinished predecessors of every task
+    waiting_for: NodeMap<IDDataType, usize>,
+    ready: VecDeque<IDDataType>,
+    running: NodeSet<IDDataType>,
+    done: usize,
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType>
+    TopologicalIter<'_, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Reports a yielded task as finished, which makes the tasks that were only waiting for it
+    /// ready. Returns `false`, and changes nothing, if the task was not yielded or was already
+    /// marked done.
+    pub fn mark_done(&mut self, id: IDDataType) -> bool {
+        if !self.running.remove(&id) {
+            return false;
+        }
+        self.done += 1;
+        for to in self.graph.neighbors(id) {
+            if let Some(waiting) = self.waiting_for.get_mut(to) {
+                *waiting -= 1;
+                if *waiting == 0 {
+                    self.ready.push_back(*to);
+                }
+            }
+        }
+        true
+    }
+
+    /// The number of tasks that were yielded but not marked done.
+    pub fn in_progress(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Whether every task was marked done.
+    pub fn is_finished(&self) -> bool {
+        self.done == self.graph.nodes.len()
+    }
+
+    /// Whether no task can become ready any more although some never ran, because they lie on a
+    /// cycle or after one.
+    pub fn is_stuck(&self) -> bool {
+        self.ready.is_empty() && self.running.is_empty() && !self.is_finished()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Iterator
+    for TopologicalIter<'_, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    type Item = IDDataType;
+
+    fn next(&mut self) -> Option<IDDataType> {
+        let id = self.ready.pop_front()?;
+        self.running.insert(id);
+        Some(id)
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Iterates over the nodes as tasks that become ready when their predecessors are marked
+    /// done, see [`TopologicalIter`]. The tasks without predecessors are ready at first, in the
+    /// order of `self.nodes`.
+    pub fn topological_iter(&self) -> TopologicalIter<'_, IDDataType, NodeDataType, EdgeDataType> {
+        let mut waiting_for: NodeMap<IDDataType, usize> =
+            self.nodes.iter().map(|id| (*id, 0)).collect();
+        for to in self.edges.values().flatten() {
+            if let Some(waiting) = waiting_for.get_mut(to) {
+                *waiting += 1;
+            }
+        }
+        TopologicalIter {
+            graph: self,
+            ready: self
+                .nodes
+                .iter()
+                .copied()
+                .filter(|id| waiting_for[id] == 0)
+                .collect(),
+            waiting_for,
+            running: NodeSet::default(),
+            done: 0,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! `serde` support for `Graph` (`serde` feature).
+//!
+//! A graph is written as a struct with the fields `nodes`, `node_data`, `edges`,
+//! `reverse_edges`, `edge_data` and `policy`. The maps are written as lists of key-value pairs
+//! rather than as maps, so that formats such as JSON, whose map keys must be strings, can store
+//! graphs with tuple IDs and the edge data keyed by `(from, to)`. Every field is read back as it
+//! was written, without any checks; use `Graph::check_consistency` on untrusted input.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    collections::{GraphHasher, NodeMap},
+    Adjacency, EdgePolicy, Graph,
+};
+
+/// the borrowed form of a graph that is written out.
+#[derive(Serialize)]
+#[serde(rename = "Graph")]
+struct GraphRef<'a, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Serialize + Eq + Hash,
+    NodeDataType: Serialize,
+    EdgeDataType: Serialize,
+{
+    nodes: &'a [IDDataType],
+    #[serde(serialize_with = "pairs")]
+    node_data: &'a NodeMap<IDDataType, NodeDataType>,
+    #[serde(serialize_with = "adjacency_pairs")]
+    edges: &'a NodeMap<IDDataType, Adjacency<IDDataType>>,
+    #[serde(serialize_with = "adjacency_pairs")]
+    reverse_edges: &'a NodeMap<IDDataType, Adjacency<IDDataType>>,
+    #[serde(serialize_with = "pairs")]
+    edge_data: &'a NodeMap<(IDDataType, IDDataType), EdgeDataType>,
+    policy: EdgePolicy,
+}
+
+/// the owned form of a graph that is read back.
+#[derive(Deserialize)]
+#[serde(rename = "Graph")]
+struct GraphRepr<IDDataType, NodeDataType, EdgeDataType> {
+    nodes: Vec<IDDataType>,
+    node_data: Vec<(IDDataType, NodeDataType)>,
+    edges: Vec<(IDDataType, Vec<IDDataType>)>,
+    reverse_edges: Vec<(IDDataType, Vec<IDDataType>)>,
+    edge_data: Vec<((IDDataType, IDDataType), EdgeDataType)>,
+    #[serde(default)]
+    policy: EdgePolicy,
+}
+
+/// writes a map as a list of key-value pairs.
+fn pairs<K: Serialize, V: Serialize, S: Serializer>(
+    map: &&NodeMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(map.iter())
+}
+
+/// writes an adjacency map as a list of pairs of a node and its neighbor list.
+fn adjacency_pairs<IDDataType: Serialize, S: Serializer>(
+    map: &&NodeMap<IDDataType, Adjacency<IDDataType>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(map.len()))?;
+    for (id, neighbors) in map.iter() {
+        seq.serialize_element(&(id, neighbors.as_slice()))?;
+    }
+    seq.end()
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Serialize
+    for Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + Serialize,
+    NodeDataType: Serialize,
+    EdgeDataType: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GraphRef {
+            nodes: &self.nodes,
+            node_data: &self.node_data,
+            edges: &self.edges,
+            reverse_edges: &self.reverse_edges,
+            edge_data: &self.edge_data,
+            policy: self.edge_policy(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, IDDataType, NodeDataType, EdgeDataType> Deserialize<'de>
+    for Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy + Deserialize<'de>,
+    NodeDataType: Deserialize<'de>,
+    EdgeDataType: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = GraphRepr::deserialize(deserializer)?;
+        let mut graph = Graph::with_hasher(GraphHasher::default());
+        graph.nodes = repr.nodes;
+        graph.node_data.extend(repr.node_data);
+        graph.edges.extend(
+            repr.edges
+                .into_iter()
+                .map(|(id, tos)| (id, tos.into_iter().collect())),
+        );
+        graph.reverse_edges.extend(
+            repr.reverse_edges
+                .into_iter()
+                .map(|(id, froms)| (id, froms.into_iter().collect())),
+        );
+        graph.edge_data.extend(repr.edge_data);
+        graph.set_edge_policy(repr.policy);
+        Ok(graph)
+    }
+}
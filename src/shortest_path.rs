@@ -0,0 +1,454 @@
+//! Shortest paths over weighted edges with Dijkstra's algorithm, or with A* search when a
+//! heuristic estimate of the remaining distance to the goal is known, and with the Bellman–Ford
+//! algorithm when some weights are negative.
+//!
+//! The weights are either the edge data of the graph or given by a function of the two nodes of
+//! a directed edge, so graphs without edge data can keep their weights in a separate map. Weights
+//! must not be negative except for Bellman–Ford, and the length of a path without edges is the
+//! `Default` of the weight.
+
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+use core::{cmp::Reverse, fmt::Debug, hash::Hash, ops::Add};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    routing::Ranked,
+    visit::Neighbors,
+    Graph, NegativeCycleError,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A shortest path from `start` to `end` and its length, with the edge data as the lengths
+    /// of the edges. Edges without data are not followed. Returns `None` if `end` cannot be
+    /// reached or either node is not in the graph; a path from a node to itself is just the node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, (), f64>::new();
+    /// g.add_directed_edge_with_data(0, 1, 4.0);
+    /// g.add_directed_edge_with_data(0, 2, 1.0);
+    /// g.add_directed_edge_with_data(2, 1, 2.0);
+    /// g.add_directed_edge_with_data(1, 3, 1.0);
+    ///
+    /// assert_eq!(g.shortest_path_dijkstra(0, 3), Some((4.0, vec![0, 2, 1, 3])));
+    /// assert_eq!(g.shortest_path_dijkstra(3, 0), None);
+    /// assert_eq!(g.shortest_path_lengths(0)[&1], 3.0);
+    /// ```
+    pub fn shortest_path_dijkstra(
+        &self,
+        start: IDDataType,
+        end: IDDataType,
+    ) -> Option<(EdgeDataType, Vec<IDDataType>)>
+    where
+        EdgeDataType: Copy + PartialOrd + Add<Output = EdgeDataType> + Default,
+    {
+        self.shortest_path_on_core(start, end, |from, to| self.edge_data(from, to).copied())
+    }
+
+    /// The length of a shortest path from `start` to every node it reaches, with the edge data as
+    /// the lengths of the edges, including `start` itself. Edges without data are not followed,
+    /// and the map is empty if `start` is not in the graph.
+    pub fn shortest_path_lengths(&self, start: IDDataType) -> NodeMap<IDDataType, EdgeDataType>
+    where
+        EdgeDataType: Copy + PartialOrd + Add<Output = EdgeDataType> + Default,
+    {
+        self.shortest_path_lengths_on_core(start, |from, to| self.edge_data(from, to).copied())
+    }
+
+    /// Like `shortest_path_dijkstra`, but with the length of every directed edge given by
+    /// `weight`, for example looked up in a map of weights.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{generate_random_weighted_graph, WeightDistribution};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let uniform = WeightDistribution::Uniform { low: 1.0, high: 2.0 };
+    /// let (g, weights) =
+    ///     generate_random_weighted_graph::<()>(50, 0.2, uniform, StdRng::seed_from_u64(3));
+    /// let (length, path) = g
+    ///     .shortest_path_dijkstra_by(0, 1, |from, to| weights[&(from, to)])
+    ///     .unwrap();
+    /// assert!(length >= path.len() as f64 - 1.0);
+    /// ```
+    pub fn shortest_path_dijkstra_by<C>(
+        &self,
+        start: IDDataType,
+        end: IDDataType,
+        weight: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> Option<(C, Vec<IDDataType>)>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        self.shortest_path_on_core(start, end, |from, to| Some(weight(from, to)))
+    }
+
+    /// Like `shortest_path_lengths`, but with the length of every directed edge given by
+    /// `weight`.
+    pub fn shortest_path_lengths_by<C>(
+        &self,
+        start: IDDataType,
+        weight: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> NodeMap<IDDataType, C>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        self.shortest_path_lengths_on_core(start, |from, to| Some(weight(from, to)))
+    }
+
+    /// The length of a shortest path from `start` to every node it reaches, like
+    /// `shortest_path_lengths`, but with the Bellman–Ford algorithm, so the edge data may be
+    /// negative. Edges without data are not followed. An undirected edge with a negative weight
+    /// is a negative cycle of two nodes.
+    ///
+    /// Every round relaxes all edges and the search stops after a round that shortens no path,
+    /// which takes `O(n·m)` time at most. If a round still shortens a path after `n - 1` of them,
+    /// a cycle with a negative total weight can be reached from `start`, and that cycle is
+    /// returned as the error. Cycles that `start` does not reach are not detected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, (), i32>::new();
+    /// g.add_directed_edge_with_data(0, 1, 4);
+    /// g.add_directed_edge_with_data(0, 2, 5);
+    /// g.add_directed_edge_with_data(2, 1, -3);
+    /// g.add_directed_edge_with_data(1, 3, 2);
+    ///
+    /// let lengths = g.shortest_path_bellman_ford(0).unwrap();
+    /// assert_eq!(lengths[&1], 2);
+    /// assert_eq!(lengths[&3], 4);
+    ///
+    /// g.add_directed_edge_with_data(3, 2, -1);
+    /// assert_eq!(g.shortest_path_bellman_ford(0).unwrap_err().cycle, vec![1, 3, 2]);
+    /// ```
+    pub fn shortest_path_bellman_ford(
+        &self,
+        start: IDDataType,
+    ) -> Result<NodeMap<IDDataType, EdgeDataType>, NegativeCycleError<IDDataType>>
+    where
+        EdgeDataType: Copy + PartialOrd + Add<Output = EdgeDataType> + Default,
+    {
+        self.bellman_ford(start, |from, to| self.edge_data(from, to).copied())
+    }
+
+    /// Like `shortest_path_bellman_ford`, but with the length of every directed edge given by
+    /// `weight`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{Graph, NodeMap};
+    ///
+    /// // exchange rates: trading around a cycle gains money if the product of its rates exceeds
+    /// // one, which is a negative cycle with the negated logarithms of the rates as weights
+    /// let mut rates: NodeMap<(&str, &str), f64> = NodeMap::default();
+    /// rates.insert(("USD", "EUR"), 0.9);
+    /// rates.insert(("EUR", "GBP"), 0.9);
+    /// rates.insert(("GBP", "USD"), 1.25);
+    /// rates.insert(("EUR", "USD"), 1.1);
+    /// let g = Graph::<&str, ()>::from_edges(&rates.keys().copied().collect::<Vec<_>>());
+    ///
+    /// let arbitrage = g
+    ///     .shortest_path_bellman_ford_by("USD", |from, to| -rates[&(from, to)].ln())
+    ///     .unwrap_err();
+    /// assert_eq!(arbitrage.cycle.len(), 3);
+    /// ```
+    pub fn shortest_path_bellman_ford_by<C>(
+        &self,
+        start: IDDataType,
+        weight: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> Result<NodeMap<IDDataType, C>, NegativeCycleError<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        self.bellman_ford(start, |from, to| Some(weight(from, to)))
+    }
+
+    /// A shortest path from `start` to `goal` and its length found by A* search, with the length
+    /// of every directed edge given by `cost`. Nodes are explored in the order of their distance
+    /// from `start` plus `heuristic`, an estimate of their distance to `goal`, so a good estimate
+    /// explores far fewer nodes than `shortest_path_dijkstra_by`. Returns `None` if `goal`
+    /// cannot be reached or either node is not in the graph.
+    ///
+    /// The path is a shortest one as long as the heuristic never overestimates the remaining
+    /// distance, such as the straight-line or, on a grid, the Manhattan distance. A heuristic
+    /// that is zero everywhere turns the search into Dijkstra's algorithm.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let mut g = generate_grid_graph::<()>(10, 10);
+    /// // a wall with a gap at the top
+    /// for y in 1..10 {
+    ///     g.remove_node((5, y));
+    /// }
+    /// let goal = (9, 9);
+    /// let manhattan = |(x, y): (usize, usize)| x.abs_diff(goal.0) + y.abs_diff(goal.1);
+    /// let (length, path) = g.astar((0, 9), goal, manhattan, |_, _| 1).unwrap();
+    /// assert_eq!(length, 9 + 9 + 9);
+    /// assert_eq!(path.len(), length + 1);
+    /// assert!(path.contains(&(5, 0)));
+    /// ```
+    pub fn astar<C>(
+        &self,
+        start: IDDataType,
+        goal: IDDataType,
+        heuristic: impl Fn(IDDataType) -> C,
+        cost: impl Fn(IDDataType, IDDataType) -> C,
+    ) -> Option<(C, Vec<IDDataType>)>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        if !self.node_data.contains_key(&start) || !self.node_data.contains_key(&goal) {
+            return None;
+        }
+        let mut lengths = NodeMap::default();
+        let mut parents = NodeMap::default();
+        let mut queue =
+            BinaryHeap::from([Reverse(Ranked(heuristic(start), (start, C::default())))]);
+        lengths.insert(start, C::default());
+        while let Some(Reverse(Ranked(_, (node, length)))) = queue.pop() {
+            // entries for nodes that were reached by a shorter path later are stale
+            if lengths.get(&node).is_some_and(|known| *known < length) {
+                continue;
+            }
+            if node == goal {
+                let mut path = Vec::from([goal]);
+                let mut current = goal;
+                while current != start {
+                    current = parents[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some((length, path));
+            }
+            for neighbor in self.neighbors(node) {
+                let through = length + cost(node, *neighbor);
+                // a node is explored again if a shorter path to it turns up, which only happens
+                // for heuristics that are not consistent
+                if lengths.get(neighbor).is_none_or(|known| through < *known) {
+                    lengths.insert(*neighbor, through);
+                    parents.insert(*neighbor, node);
+                    queue.push(Reverse(Ranked(
+                        through + heuristic(*neighbor),
+                        (*neighbor, through),
+                    )));
+                }
+            }
+        }
+        None
+    }
+
+    /// `dijkstra_path` on the core of the graph, with the slots translated to and from node IDs.
+    fn shortest_path_on_core<C>(
+        &self,
+        start: IDDataType,
+        end: IDDataType,
+        weight: impl Fn(IDDataType, IDDataType) -> Option<C>,
+    ) -> Option<(C, Vec<IDDataType>)>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        let core = self.core();
+        let (start, end) = (core.index_of(&start)?, core.index_of(&end)?);
+        let (length, path) = dijkstra_path(core, start, end, |from, to| {
+            weight(core.id_of(from), core.id_of(to))
+        })?;
+        Some((length, core.ids_of(path)))
+    }
+
+    /// `dijkstra` from `start` on the core of the graph, with the slots translated to and from
+    /// node IDs.
+    fn shortest_path_lengths_on_core<C>(
+        &self,
+        start: IDDataType,
+        weight: impl Fn(IDDataType, IDDataType) -> Option<C>,
+    ) -> NodeMap<IDDataType, C>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        let core = self.core();
+        let Some(start) = core.index_of(&start) else {
+            return NodeMap::default();
+        };
+        let (lengths, _) = dijkstra(core, start, None, |from, to| {
+            weight(core.id_of(from), core.id_of(to))
+        });
+        core.map_slots(lengths)
+    }
+
+    /// the shortest path lengths from `start` with the Bellman–Ford algorithm, or the first
+    /// negative cycle found. Edges without a weight are not followed.
+    fn bellman_ford<C>(
+        &self,
+        start: IDDataType,
+        weight: impl Fn(IDDataType, IDDataType) -> Option<C>,
+    ) -> Result<NodeMap<IDDataType, C>, NegativeCycleError<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        let Some(start) = self.nodes.iter().position(|id| *id == start) else {
+            return Ok(NodeMap::default());
+        };
+        let edges = self.index_weighted_edges(weight);
+        let mut lengths = vec![None; self.nodes.len()];
+        lengths[start] = Some(C::default());
+        self.bellman_ford_rounds(&edges, &mut lengths)?;
+        Ok(lengths
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, length)| Some((self.nodes[i], length?)))
+            .collect())
+    }
+
+    /// every directed edge with a weight, by the positions of its nodes in `nodes`.
+    pub(crate) fn index_weighted_edges<C>(
+        &self,
+        weight: impl Fn(IDDataType, IDDataType) -> Option<C>,
+    ) -> Vec<(usize, usize, C)> {
+        let (forward, _) = self.index_adjacency(false);
+        forward
+            .iter()
+            .enumerate()
+            .flat_map(|(from, tos)| tos.iter().map(move |to| (from, *to)))
+            .filter_map(|(from, to)| Some((from, to, weight(self.nodes[from], self.nodes[to])?)))
+            .collect()
+    }
+
+    /// shortens the `lengths` by position in `nodes` along `edges` in rounds until a round
+    /// changes nothing, or returns a negative cycle that a node with a length reaches.
+    pub(crate) fn bellman_ford_rounds<C>(
+        &self,
+        edges: &[(usize, usize, C)],
+        lengths: &mut [Option<C>],
+    ) -> Result<(), NegativeCycleError<IDDataType>>
+    where
+        C: Copy + PartialOrd + Add<Output = C> + Default,
+    {
+        let n = lengths.len();
+        let mut parents = vec![usize::MAX; n];
+        for round in 0..n {
+            let mut relaxed = None;
+            for &(from, to, w) in edges {
+                let Some(length) = lengths[from] else {
+                    continue;
+                };
+                let through = length + w;
+                if lengths[to].is_none_or(|known| through < known) {
+                    lengths[to] = Some(through);
+                    parents[to] = from;
+                    relaxed = Some(to);
+                }
+            }
+            let Some(mut node) = relaxed else {
+                break;
+            };
+            if round + 1 < n {
+                continue;
+            }
+            // a path that still got shorter after n - 1 rounds has more than n - 1 edges, so
+            // walking n steps back along it ends up on the negative cycle it goes around
+            for _ in 0..n {
+                node = parents[node];
+            }
+            let mut cycle = vec![node];
+            let mut current = parents[node];
+            while current != node {
+                cycle.push(current);
+                current = parents[current];
+            }
+            cycle.reverse();
+            let first = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap_or(0);
+            cycle.rotate_left(first);
+            return Err(NegativeCycleError {
+                cycle: cycle.into_iter().map(|i| self.nodes[i]).collect(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// the node every node was reached from.
+type Parents<Id> = NodeMap<Id, Id>;
+
+/// the shortest path lengths from `start` and the node every node was reached from, settling
+/// nodes in the order of their length until `end` is settled. Edges without a weight are not
+/// followed.
+pub(crate) fn dijkstra<G: Neighbors, C>(
+    graph: &G,
+    start: G::Id,
+    end: Option<G::Id>,
+    weight: impl Fn(G::Id, G::Id) -> Option<C>,
+) -> (NodeMap<G::Id, C>, Parents<G::Id>)
+where
+    C: Copy + PartialOrd + Add<Output = C> + Default,
+{
+    let mut lengths = NodeMap::default();
+    let mut parents = NodeMap::default();
+    if !graph.contains_node(start) {
+        return (lengths, parents);
+    }
+    let mut done = NodeSet::default();
+    let mut queue = BinaryHeap::from([Reverse(Ranked(C::default(), start))]);
+    lengths.insert(start, C::default());
+    while let Some(Reverse(Ranked(length, node))) = queue.pop() {
+        // entries for nodes that were reached by a shorter path later are stale
+        if !done.insert(node) {
+            continue;
+        }
+        if Some(node) == end {
+            break;
+        }
+        for neighbor in graph.successors(node) {
+            let Some(edge) = weight(node, neighbor) else {
+                continue;
+            };
+            let through = length + edge;
+            if !done.contains(&neighbor)
+                && lengths.get(&neighbor).is_none_or(|known| through < *known)
+            {
+                lengths.insert(neighbor, through);
+                parents.insert(neighbor, node);
+                queue.push(Reverse(Ranked(through, neighbor)));
+            }
+        }
+    }
+    (lengths, parents)
+}
+
+/// the path and length to `end` found by `dijkstra`.
+pub(crate) fn dijkstra_path<G: Neighbors, C>(
+    graph: &G,
+    start: G::Id,
+    end: G::Id,
+    weight: impl Fn(G::Id, G::Id) -> Option<C>,
+) -> Option<(C, Vec<G::Id>)>
+where
+    C: Copy + PartialOrd + Add<Output = C> + Default,
+{
+    if !graph.contains_node(end) {
+        return None;
+    }
+    let (lengths, parents) = dijkstra(graph, start, Some(end), weight);
+    let length = *lengths.get(&end)?;
+    let mut path = Vec::from([end]);
+    let mut current = end;
+    while current != start {
+        current = parents[&current];
+        path.push(current);
+    }
+    path.reverse();
+    Some((length, path))
+}
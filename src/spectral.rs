@@ -0,0 +1,224 @@
+//! Eigenvectors of the graph Laplacian by power iteration, without a linear algebra dependency.
+
+use core::hash::Hash;
+use std::fmt::Debug;
+
+use crate::Graph;
+
+/// the most power iterations the eigenvalue estimates of `Graph` run.
+const MAX_ITERATIONS: usize = 10_000;
+/// the largest change of any eigenvector entry at which the iteration counts as converged.
+const TOLERANCE: f64 = 1e-10;
+
+/// The Laplacian `L = D - A` of a graph given as neighbor lists over dense indices.
+/// Every neighbor relation should be listed in both directions, so that `L` is symmetric.
+pub(crate) struct Laplacian<'a> {
+    neighbors: &'a [Vec<usize>],
+}
+
+impl<'a> Laplacian<'a> {
+    pub(crate) fn new(neighbors: &'a [Vec<usize>]) -> Self {
+        Self { neighbors }
+    }
+
+    /// computes `L v`.
+    pub(crate) fn apply(&self, v: &[f64]) -> Vec<f64> {
+        self.neighbors
+            .iter()
+            .enumerate()
+            .map(|(i, ns)| ns.iter().map(|&j| v[i] - v[j]).sum())
+            .collect()
+    }
+
+    /// the Rayleigh quotient `v^T L v` of a unit vector, i.e. its eigenvalue if it is an eigenvector.
+    pub(crate) fn rayleigh_quotient(&self, v: &[f64]) -> f64 {
+        self.apply(v).iter().zip(v).map(|(l, x)| l * x).sum()
+    }
+
+    /// The `count` smallest eigenpairs after the trivial constant eigenvector, in increasing order.
+    ///
+    /// Runs simultaneous power iteration on `shift * I - L`, whose dominant eigenvectors orthogonal to
+    /// the constant vector are the wanted ones, re-orthonormalizing after every step. Stops after
+    /// `max_iterations` or once no entry of any vector changes by more than `tolerance`.
+    pub(crate) fn smallest_eigenpairs(
+        &self,
+        count: usize,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> Vec<(f64, Vec<f64>)> {
+        let n = self.neighbors.len();
+        let count = count.min(n.saturating_sub(1));
+        let max_degree = self.neighbors.iter().map(Vec::len).max().unwrap_or(0);
+        // Gershgorin: every eigenvalue of L is at most twice the maximum degree
+        let shift = 2.0 * max_degree as f64;
+
+        let mut vectors = start_vectors(n, count);
+
+        for _ in 0..max_iterations {
+            let previous = vectors.clone();
+            for v in vectors.iter_mut() {
+                let lv = self.apply(v);
+                v.iter_mut().zip(lv).for_each(|(x, l)| *x = shift * *x - l);
+            }
+            orthonormalize(&mut vectors);
+            let change = vectors
+                .iter()
+                .zip(&previous)
+                .flat_map(|(v, p)| v.iter().zip(p).map(|(a, b)| (a - b).abs()))
+                .fold(0.0, f64::max);
+            if change <= tolerance {
+                break;
+            }
+        }
+
+        vectors
+            .into_iter()
+            .map(|v| (self.rayleigh_quotient(&v), v))
+            .collect()
+    }
+
+    /// The `count` largest eigenpairs, in decreasing order.
+    ///
+    /// Runs simultaneous power iteration on `L` itself with the same re-orthonormalization as
+    /// `smallest_eigenpairs`; the constant vector it projects out has the eigenvalue zero, so at
+    /// most `n - 1` pairs are found.
+    pub(crate) fn largest_eigenpairs(
+        &self,
+        count: usize,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> Vec<(f64, Vec<f64>)> {
+        let n = self.neighbors.len();
+        let count = count.min(n.saturating_sub(1));
+        let mut vectors = start_vectors(n, count);
+
+        for _ in 0..max_iterations {
+            let previous = vectors.clone();
+            for v in vectors.iter_mut() {
+                *v = self.apply(v);
+            }
+            orthonormalize(&mut vectors);
+            let change = vectors
+                .iter()
+                .zip(&previous)
+                .flat_map(|(v, p)| v.iter().zip(p).map(|(a, b)| (a - b).abs()))
+                .fold(0.0, f64::max);
+            if change <= tolerance {
+                break;
+            }
+        }
+
+        vectors
+            .into_iter()
+            .map(|v| (self.rayleigh_quotient(&v), v))
+            .collect()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Estimates the algebraic connectivity, the second smallest eigenvalue of the Laplacian of
+    /// the graph taken as simple and undirected, also called the Fiedler value. It is zero exactly
+    /// when the graph is disconnected, and grows with how hard the graph is to cut in two.
+    ///
+    /// Computed like [`Graph::smallest_laplacian_eigenvalues`]; an empty or single-node graph has
+    /// an algebraic connectivity of zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_cycle_graph;
+    ///
+    /// let g = generate_cycle_graph::<()>(12);
+    /// let expected = 2.0 - 2.0 * (std::f64::consts::TAU / 12.0).cos();
+    /// assert!((g.algebraic_connectivity() - expected).abs() < 1e-6);
+    /// ```
+    pub fn algebraic_connectivity(&self) -> f64 {
+        self.smallest_laplacian_eigenvalues(2)
+            .get(1)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Estimates the `count` smallest eigenvalues of the Laplacian of the graph taken as simple
+    /// and undirected, in increasing order, starting with the trivial zero. The number of zeros is
+    /// the number of connected components and the gaps after them show how well the graph
+    /// splits into that many parts.
+    ///
+    /// The eigenvalues are found by power iteration with deflation: the vectors are iterated
+    /// together and kept orthogonal to the constant vector and to each other, until they change by
+    /// less than `1e-10` or for at most 10000 iterations. Close eigenvalues converge slowly.
+    pub fn smallest_laplacian_eigenvalues(&self, count: usize) -> Vec<f64> {
+        if count == 0 || self.nodes.is_empty() {
+            return Vec::new();
+        }
+        let neighbors = self.symmetric_neighbors();
+        let mut values = vec![0.0];
+        values.extend(
+            Laplacian::new(&neighbors)
+                .smallest_eigenpairs(count - 1, MAX_ITERATIONS, TOLERANCE)
+                .into_iter()
+                .map(|(value, _)| value),
+        );
+        values
+    }
+
+    /// Estimates the `count` largest eigenvalues of the Laplacian of the graph taken as simple and
+    /// undirected, in decreasing order, with deflated power iteration like
+    /// [`Graph::smallest_laplacian_eigenvalues`]. The largest eigenvalue lies between the maximum
+    /// degree plus one and twice the maximum degree, and equals twice it only for a bipartite
+    /// regular component.
+    pub fn largest_laplacian_eigenvalues(&self, count: usize) -> Vec<f64> {
+        let neighbors = self.symmetric_neighbors();
+        Laplacian::new(&neighbors)
+            .largest_eigenpairs(count, MAX_ITERATIONS, TOLERANCE)
+            .into_iter()
+            .map(|(value, _)| value)
+            .collect()
+    }
+
+    /// the undirected neighbors of every node by position in `nodes`, as the Laplacian needs them.
+    fn symmetric_neighbors(&self) -> Vec<Vec<usize>> {
+        self.undirected_sets()
+            .into_iter()
+            .map(|set| {
+                let mut neighbors: Vec<usize> = set.into_iter().collect();
+                neighbors.sort_unstable();
+                neighbors
+            })
+            .collect()
+    }
+}
+
+/// `count` deterministic, irregular, orthonormal start vectors of length `n`.
+fn start_vectors(n: usize, count: usize) -> Vec<Vec<f64>> {
+    let mut vectors: Vec<Vec<f64>> = (0..count)
+        .map(|k| {
+            (0..n)
+                .map(|i| ((i as f64 + 1.0) * (0.618_034 + 0.414_214 * k as f64)).fract() - 0.5)
+                .collect()
+        })
+        .collect();
+    orthonormalize(&mut vectors);
+    vectors
+}
+
+/// Gram-Schmidt against the constant vector and each other, normalizing every vector.
+fn orthonormalize(vectors: &mut [Vec<f64>]) {
+    for k in 0..vectors.len() {
+        let (done, rest) = vectors.split_at_mut(k);
+        let v = &mut rest[0];
+        let mean = v.iter().sum::<f64>() / v.len() as f64;
+        v.iter_mut().for_each(|x| *x -= mean);
+        for u in done.iter() {
+            let dot: f64 = u.iter().zip(v.iter()).map(|(a, b)| a * b).sum();
+            v.iter_mut().zip(u).for_each(|(x, y)| *x -= dot * y);
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            v.iter_mut().for_each(|x| *x /= norm);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Carving smaller graphs out of a graph: induced subgraphs, neighborhoods, edge filters and
+//! spanning trees.
+//!
+//! The new graphs are independent copies with the node and edge data cloned, the same edge
+//! policy and hasher, and the nodes and neighbor lists in the order of the original graph.
+
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Clone,
+    EdgeDataType: Clone,
+{
+    /// The subgraph induced by `nodes`: those nodes with their data and every edge between two
+    /// of them. Nodes that are not in the graph are ignored, and the nodes keep the order of
+    /// `nodes` of this graph rather than of the argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 3").unwrap();
+    /// let sub = g.subgraph(&[2, 1, 3, 7]);
+    /// assert_eq!(sub.nodes, vec![1, 2, 3]);
+    /// assert_eq!(sub.neighbors(1), &[2]);
+    /// assert_eq!(sub.neighbors(2), &[3]);
+    /// ```
+    pub fn subgraph(&self, nodes: &[IDDataType]) -> Self {
+        let kept: NodeSet<IDDataType> = nodes.iter().copied().collect();
+        self.copy_where(
+            |id| kept.contains(&id),
+            |from, to, _| kept.contains(&from) && kept.contains(&to),
+        )
+    }
+
+    /// The subgraph induced by the nodes within `radius` edges of `center` along outgoing
+    /// edges, including `center` itself: the neighborhood to study a node in. The graph is empty
+    /// if `center` is not in the graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let g = generate_grid_graph::<()>(5, 5);
+    /// let ego = g.ego_graph((2, 2), 1);
+    /// assert_eq!(ego.nodes.len(), 5);
+    /// assert_eq!(ego.neighbors((2, 2)).len(), 4);
+    /// assert_eq!(ego.neighbors((1, 2)), &[(2, 2)]);
+    /// ```
+    pub fn ego_graph(&self, center: IDDataType, radius: usize) -> Self {
+        let within: Vec<IDDataType> = self
+            .bfs_with_depth(center)
+            .take_while(|(_, depth)| *depth <= radius)
+            .map(|(id, _)| id)
+            .collect();
+        self.subgraph(&within)
+    }
+
+    /// A copy of the graph with all nodes but only the edges for which `keep` returns `true`,
+    /// given the two nodes of a directed edge and its data. Parallel edges are tested one copy
+    /// at a time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let mut g = Graph::<u32, (), f64>::new();
+    /// g.add_directed_edge_with_data(0, 1, 0.9);
+    /// g.add_directed_edge_with_data(1, 2, 0.2);
+    /// g.add_directed_edge(2, 0);
+    ///
+    /// let strong = g.filter_edges(|_, _, weight| weight.is_some_and(|w| *w > 0.5));
+    /// assert_eq!(strong.nodes, vec![0, 1, 2]);
+    /// assert_eq!(strong.edge_tuples(), vec![(0, 1)]);
+    /// assert_eq!(strong.edge_data(0, 1), Some(&0.9));
+    /// ```
+    pub fn filter_edges(
+        &self,
+        keep: impl FnMut(IDDataType, IDDataType, Option<&EdgeDataType>) -> bool,
+    ) -> Self {
+        self.copy_where(|_| true, keep)
+    }
+
+    /// The breadth-first tree from `root`: the nodes reachable from it with their data and, for
+    /// every node but the root, the edge it was first reached along, together with the parent of
+    /// every node but the root. The depth of a node in the tree is its hop distance from the root.
+    ///
+    /// The tree edges keep their data and lead from parent to child; if the graph is undirected,
+    /// they are undirected too. The graph is empty if `root` is not in the graph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3; 3 -> 0; 4 -> 0").unwrap();
+    /// let (tree, parents) = g.bfs_tree(0);
+    /// assert_eq!(tree.nodes, vec![0, 1, 3, 2]);
+    /// assert_eq!(tree.edge_tuples(), vec![(0, 1), (0, 2), (1, 3)]);
+    /// assert_eq!(parents[&3], 1);
+    /// assert!(!parents.contains_key(&0));
+    /// ```
+    pub fn bfs_tree(&self, root: IDDataType) -> (Self, NodeMap<IDDataType, IDDataType>) {
+        let parents = self
+            .bfs_with_parent(root)
+            .filter_map(|(id, parent)| Some((id, parent?)))
+            .collect();
+        (self.tree_copy(root, &parents), parents)
+    }
+
+    /// The depth-first tree from `root`, like `bfs_tree` but with the edges that a depth-first
+    /// search follows the neighbors in their stored order along, so the paths in the tree are
+    /// long rather than short.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2; 0 -> 2").unwrap();
+    /// assert_eq!(g.bfs_tree(0).0.edge_tuples(), vec![(0, 1), (0, 2)]);
+    /// assert_eq!(g.dfs_tree(0).0.edge_tuples(), vec![(0, 1), (1, 2)]);
+    ///
+    /// let cycle = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3 -- 0").unwrap();
+    /// let (tree, parents) = cycle.dfs_tree(0);
+    /// assert_eq!(parents[&3], 2);
+    /// assert!(tree.is_undirected());
+    /// assert_eq!(tree.edge_count(), 6);
+    /// ```
+    pub fn dfs_tree(&self, root: IDDataType) -> (Self, NodeMap<IDDataType, IDDataType>) {
+        let parents = self
+            .dfs_with_parent(root)
+            .filter_map(|(id, parent)| Some((id, parent?)))
+            .collect();
+        (self.tree_copy(root, &parents), parents)
+    }
+
+    /// A spanning forest: every node with its data and one breadth-first tree per connected
+    /// component, ignoring edge directions, rooted at the first node of the component in `nodes`.
+    /// Also returns the parent of every node but the roots.
+    ///
+    /// The tree edges keep their direction and data, so the forest is a subgraph of the graph and
+    /// an edge may lead from child to parent; on an undirected graph the edges are undirected. A
+    /// forest has one edge fewer than nodes per component.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 3 -- 4; 5").unwrap();
+    /// let (forest, parents) = g.spanning_forest();
+    /// assert_eq!(forest.nodes, g.nodes);
+    /// assert_eq!(forest.edge_count(), 2 * 3);
+    /// assert_eq!(forest.connected_components(), g.connected_components());
+    /// assert_eq!(parents.len(), 3);
+    ///
+    /// let directed = Graph::<u32, ()>::parse("1 -> 0; 1 -> 2").unwrap();
+    /// assert_eq!(directed.spanning_forest().0.edge_tuples(), vec![(1, 0), (1, 2)]);
+    /// ```
+    pub fn spanning_forest(&self) -> (Self, NodeMap<IDDataType, IDDataType>) {
+        let mut parents = NodeMap::default();
+        let mut reached = NodeSet::default();
+        let mut queue = VecDeque::new();
+        for root in &self.nodes {
+            if !reached.insert(*root) {
+                continue;
+            }
+            queue.push_back(*root);
+            while let Some(node) = queue.pop_front() {
+                let linked = self
+                    .neighbors(node)
+                    .iter()
+                    .chain(self.reverse_neighbors(node));
+                for next in linked {
+                    if reached.insert(*next) {
+                        parents.insert(*next, node);
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+
+        let mut forest = self.copy_where(|_| true, |_, _, _| false);
+        self.add_tree_edges(&mut forest, &parents);
+        (forest, parents)
+    }
+
+    /// a copy of `root` and the nodes in `parents` with the tree edges between them.
+    fn tree_copy(&self, root: IDDataType, parents: &NodeMap<IDDataType, IDDataType>) -> Self {
+        let mut tree = self.copy_where(
+            |id| id == root || parents.contains_key(&id),
+            |_, _, _| false,
+        );
+        self.add_tree_edges(&mut tree, parents);
+        tree
+    }
+
+    /// adds the edge between every node of `tree` and its parent, in the direction it has in this
+    /// graph, and in both directions if this graph is undirected.
+    fn add_tree_edges(&self, tree: &mut Self, parents: &NodeMap<IDDataType, IDDataType>) {
+        let undirected = self.is_undirected();
+        for child in &self.nodes {
+            let Some(parent) = parents.get(child) else {
+                continue;
+            };
+            let downward = self.neighbors(*parent).contains(child);
+            let mut links = Vec::with_capacity(2);
+            if downward || undirected {
+                links.push((*parent, *child));
+            }
+            if !downward || undirected {
+                links.push((*child, *parent));
+            }
+            for (from, to) in links {
+                tree.edges.entry(from).or_default().push(to);
+                tree.reverse_edges.entry(to).or_default().push(from);
+                if let Some(data) = self.edge_data(from, to) {
+                    tree.edge_data.insert((from, to), data.clone());
+                }
+            }
+        }
+    }
+
+    /// a copy of the nodes for which `keep_node` returns `true` and the edges between them for
+    /// which `keep_edge` does.
+    fn copy_where(
+        &self,
+        keep_node: impl Fn(IDDataType) -> bool,
+        mut keep_edge: impl FnMut(IDDataType, IDDataType, Option<&EdgeDataType>) -> bool,
+    ) -> Self {
+        let mut copy = Graph::with_hasher(self.node_data.hasher().clone());
+        copy.set_edge_policy(self.edge_policy());
+        for id in self.nodes.iter().filter(|id| keep_node(**id)) {
+            copy.add_node_with_data(*id, self.node_data[id].clone());
+        }
+        for from in &self.nodes {
+            if !keep_node(*from) {
+                continue;
+            }
+            for to in self.neighbors(*from) {
+                let data = self.edge_data(*from, *to);
+                if !keep_node(*to) || !keep_edge(*from, *to, data) {
+                    continue;
+                }
+                copy.edges.entry(*from).or_default().push(*to);
+                copy.reverse_edges.entry(*to).or_default().push(*from);
+                if let Some(data) = data {
+                    copy.edge_data.insert((*from, *to), data.clone());
+                }
+            }
+        }
+        copy
+    }
+}
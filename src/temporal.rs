@@ -0,0 +1,463 @@
+//! Graphs whose edges only exist during given time intervals.
+
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+use core::{cmp::Ordering, fmt::Debug, hash::Hash, ops::Add};
+
+use crate::{
+    collections::NodeMap,
+    view::{AllNodes, GraphView},
+    visit::{Neighbors, NodeData, Nodes},
+    Graph,
+};
+
+/// A directed edge that is present from `start` to `end`, both inclusive.
+/// A contact at a single instant has `start == end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TemporalEdge<IDDataType, TimeDataType> {
+    /// The source node.
+    pub from: IDDataType,
+    /// The target node.
+    pub to: IDDataType,
+    /// The first moment the edge is present.
+    pub start: TimeDataType,
+    /// The last moment the edge is present.
+    pub end: TimeDataType,
+}
+
+impl<IDDataType, TimeDataType: PartialOrd> TemporalEdge<IDDataType, TimeDataType> {
+    /// Whether the edge is present at time `t`.
+    pub fn is_active_at(&self, t: TimeDataType) -> bool {
+        self.start <= t && t <= self.end
+    }
+
+    /// Whether the edge is present at some moment between `from` and `to`, both inclusive.
+    pub fn overlaps(&self, from: TimeDataType, to: TimeDataType) -> bool {
+        self.start <= to && from <= self.end
+    }
+}
+
+/// A graph whose edges carry time intervals, such as a contact network.
+///
+/// Nodes and their data are static; only the edges change over time. Plain [`Graph`]s can be
+/// extracted for a single moment (`snapshot_at`) or for a time window (`aggregate`), or viewed
+/// without copying with `view_at`, and time-respecting paths can be queried with
+/// `earliest_arrival` and `temporal_path`.
+///
+/// The temporal graph itself implements the traits of [`crate::visit`] as the static graph of
+/// every pair of nodes that is linked at some time, once per pair.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::TemporalGraph;
+///
+/// let mut contacts = TemporalGraph::<&str, (), u32>::new();
+/// contacts.add_edge("ann", "bob", 1, 1);
+/// contacts.add_edge("bob", "cat", 5, 6);
+/// contacts.add_edge("cat", "dan", 3, 3);
+///
+/// // ann can reach cat through bob, but dan was met before cat was reached
+/// let arrival = contacts.earliest_arrival("ann", 0);
+/// assert_eq!(arrival[&"cat"], 5);
+/// assert!(!arrival.contains_key(&"dan"));
+///
+/// assert_eq!(contacts.snapshot_at(5).neighbors("bob"), vec!["cat"]);
+/// assert_eq!(contacts.edges_between(2, 4).count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TemporalGraph<IDDataType, NodeDataType, TimeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// the nodes with their data, and one edge for every pair of nodes linked at some time
+    graph: Graph<IDDataType, NodeDataType>,
+    edges: Vec<TemporalEdge<IDDataType, TimeDataType>>,
+    outgoing: NodeMap<IDDataType, Vec<usize>>,
+    /// the edges between every pair of nodes, by position in `edges`
+    intervals: NodeMap<(IDDataType, IDDataType), Vec<usize>>,
+}
+
+impl<IDDataType, NodeDataType, TimeDataType> TemporalGraph<IDDataType, NodeDataType, TimeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    TimeDataType: Copy + PartialOrd,
+{
+    /// Creates a new, empty temporal graph.
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::new(),
+            edges: Vec::new(),
+            outgoing: NodeMap::default(),
+            intervals: NodeMap::default(),
+        }
+    }
+
+    /// Adds a new node with the given ID and data.
+    ///
+    /// If a node with the given ID already exists, this function will print a warning message and do nothing.
+    pub fn add_node_with_data(&mut self, id: IDDataType, data: NodeDataType) {
+        self.graph.add_node_with_data(id, data);
+    }
+
+    /// Adds a directed edge that is present from `start` to `end`, both inclusive.
+    /// Missing nodes are added with default data.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The ID of the node to add the edge from.
+    /// * `to` - The ID of the node to add the edge to.
+    /// * `start` - The first moment the edge is present.
+    /// * `end` - The last moment the edge is present.
+    ///
+    pub fn add_directed_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        start: TimeDataType,
+        end: TimeDataType,
+    ) where
+        NodeDataType: Default,
+    {
+        let intervals = self.intervals.entry((from, to)).or_default();
+        if intervals.is_empty() {
+            // the first interval puts the edge into the graph, which adds missing nodes
+            self.graph.add_directed_edge(from, to);
+        }
+        intervals.push(self.edges.len());
+        self.outgoing
+            .entry(from)
+            .or_default()
+            .push(self.edges.len());
+        self.edges.push(TemporalEdge {
+            from,
+            to,
+            start,
+            end,
+        });
+    }
+
+    /// Adds an edge in both directions that is present from `start` to `end`, both inclusive.
+    pub fn add_edge(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        start: TimeDataType,
+        end: TimeDataType,
+    ) where
+        NodeDataType: Default,
+    {
+        self.add_directed_edge(from, to, start, end);
+        self.add_directed_edge(to, from, start, end);
+    }
+
+    /// The IDs of all nodes, in insertion order.
+    pub fn nodes(&self) -> &[IDDataType] {
+        &self.graph.nodes
+    }
+
+    /// The data of a node, if it exists.
+    pub fn node_data(&self, id: IDDataType) -> Option<&NodeDataType> {
+        self.graph.node_data.get(&id)
+    }
+
+    /// All timed edges, in insertion order.
+    pub fn edges(&self) -> &[TemporalEdge<IDDataType, TimeDataType>] {
+        &self.edges
+    }
+
+    /// The edges present at some moment between `from` and `to`, both inclusive, in insertion
+    /// order.
+    pub fn edges_between(
+        &self,
+        from: TimeDataType,
+        to: TimeDataType,
+    ) -> impl Iterator<Item = &TemporalEdge<IDDataType, TimeDataType>> + '_ {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.overlaps(from, to))
+    }
+
+    /// Whether a directed edge from `from` to `to` is present at time `t`.
+    pub fn has_edge_at(&self, from: IDDataType, to: IDDataType, t: TimeDataType) -> bool {
+        self.intervals
+            .get(&(from, to))
+            .into_iter()
+            .flatten()
+            .any(|e| self.edges[*e].is_active_at(t))
+    }
+
+    /// The earliest start and latest end of any edge, or `None` if there are no edges.
+    pub fn time_span(&self) -> Option<(TimeDataType, TimeDataType)> {
+        let first = self.edges.first()?;
+        Some(
+            self.edges
+                .iter()
+                .fold((first.start, first.end), |(s, e), edge| {
+                    (
+                        if edge.start < s { edge.start } else { s },
+                        if edge.end > e { edge.end } else { e },
+                    )
+                }),
+        )
+    }
+
+    /// The static graph of all nodes and the edges present at time `t`.
+    pub fn snapshot_at(&self, t: TimeDataType) -> Graph<IDDataType, NodeDataType>
+    where
+        NodeDataType: Clone,
+    {
+        self.collect(|edge| edge.is_active_at(t))
+    }
+
+    /// A view of all nodes and the edges present at time `t`, without copying them like
+    /// `snapshot_at` does. An edge is shown once even if several of its intervals contain `t`.
+    /// Every access tests the intervals of the edge, so use `snapshot_at` if the snapshot is
+    /// queried often.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::{visit::bfs_distances, TemporalGraph};
+    ///
+    /// let mut contacts = TemporalGraph::<u32, (), u32>::new();
+    /// contacts.add_edge(0, 1, 0, 10);
+    /// contacts.add_edge(1, 2, 5, 10);
+    /// contacts.add_edge(2, 3, 0, 3);
+    ///
+    /// assert_eq!(bfs_distances(&contacts.view_at(2), 0).len(), 2);
+    /// assert_eq!(bfs_distances(&contacts.view_at(7), 0)[&2], 2);
+    /// ```
+    pub fn view_at(
+        &self,
+        t: TimeDataType,
+    ) -> GraphView<'_, Self, AllNodes<IDDataType>, impl Fn(IDDataType, IDDataType) -> bool + '_>
+    {
+        GraphView::new(self).filter_edges(move |from, to| self.has_edge_at(from, to, t))
+    }
+
+    /// The static graph of all nodes and the edges present at some moment between `from` and `to`,
+    /// both inclusive. An edge that is present several times in the window appears once per interval,
+    /// so the number of parallel edges counts the contacts.
+    pub fn aggregate(&self, from: TimeDataType, to: TimeDataType) -> Graph<IDDataType, NodeDataType>
+    where
+        NodeDataType: Clone,
+    {
+        self.collect(|edge| edge.overlaps(from, to))
+    }
+
+    /// Aggregates consecutive windows of length `width`, starting every `step` from the earliest edge
+    /// until the last edge has ended. Returns the start of every window with its aggregated graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The length of every window; a window covers `[start, start + width]`.
+    /// * `step` - The distance between the starts of consecutive windows; must be positive.
+    ///
+    pub fn sliding_windows(
+        &self,
+        width: TimeDataType,
+        step: TimeDataType,
+    ) -> Vec<(TimeDataType, Graph<IDDataType, NodeDataType>)>
+    where
+        NodeDataType: Clone,
+        TimeDataType: Add<Output = TimeDataType>,
+    {
+        let Some((first, last)) = self.time_span() else {
+            return Vec::new();
+        };
+        // a non-positive step would never reach the end
+        if first + step <= first {
+            return Vec::new();
+        }
+        let mut windows = Vec::new();
+        let mut start = first;
+        while start <= last {
+            windows.push((start, self.aggregate(start, start + width)));
+            start = start + step;
+        }
+        windows
+    }
+
+    /// The earliest time every node can be reached from `source` by a time-respecting path
+    /// that departs no earlier than `departure`.
+    ///
+    /// A time-respecting path only uses each edge while it is present and never goes back in time;
+    /// traversing an edge takes no time. Nodes that cannot be reached are missing from the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The node the paths start at.
+    /// * `departure` - The earliest time the paths may start.
+    ///
+    pub fn earliest_arrival(
+        &self,
+        source: IDDataType,
+        departure: TimeDataType,
+    ) -> NodeMap<IDDataType, TimeDataType> {
+        self.search(source, departure)
+            .into_iter()
+            .map(|(id, (time, _))| (id, time))
+            .collect()
+    }
+
+    /// A time-respecting path from `source` to `target` with the earliest possible arrival,
+    /// as the list of visited nodes together with the time each was reached.
+    /// Returns `None` if `target` cannot be reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The node the path starts at.
+    /// * `target` - The node the path ends at.
+    /// * `departure` - The earliest time the path may start.
+    ///
+    pub fn temporal_path(
+        &self,
+        source: IDDataType,
+        target: IDDataType,
+        departure: TimeDataType,
+    ) -> Option<Vec<(IDDataType, TimeDataType)>> {
+        let labels = self.search(source, departure);
+        let mut path = vec![(target, labels.get(&target)?.0)];
+        let mut current = target;
+        while let Some(previous) = labels[&current].1 {
+            path.push((previous, labels[&previous].0));
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// builds a static graph from all nodes and the edges accepted by `keep`.
+    fn collect(
+        &self,
+        mut keep: impl FnMut(&TemporalEdge<IDDataType, TimeDataType>) -> bool,
+    ) -> Graph<IDDataType, NodeDataType>
+    where
+        NodeDataType: Clone,
+    {
+        let mut graph = Graph::new();
+        for id in &self.graph.nodes {
+            graph.add_node_with_data(*id, self.graph.node_data[id].clone());
+        }
+        for edge in self.edges.iter().filter(|edge| keep(edge)) {
+            graph.edges.entry(edge.from).or_default().push(edge.to);
+            graph
+                .reverse_edges
+                .entry(edge.to)
+                .or_default()
+                .push(edge.from);
+        }
+        graph.invalidate_caches();
+        graph
+    }
+
+    /// label-setting search for earliest arrival times, with the predecessor of every reached node.
+    fn search(
+        &self,
+        source: IDDataType,
+        departure: TimeDataType,
+    ) -> NodeMap<IDDataType, (TimeDataType, Option<IDDataType>)> {
+        let mut labels = NodeMap::default();
+        if !self.graph.node_data.contains_key(&source) {
+            return labels;
+        }
+        let mut queue = BinaryHeap::new();
+        labels.insert(source, (departure, None));
+        queue.push(Arrival(departure, source));
+
+        while let Some(Arrival(time, id)) = queue.pop() {
+            if labels[&id].0 < time {
+                continue;
+            }
+            for &e in self.outgoing.get(&id).into_iter().flatten() {
+                let edge = &self.edges[e];
+                if edge.end < time {
+                    continue;
+                }
+                let arrival = if edge.start > time { edge.start } else { time };
+                let improves = labels.get(&edge.to).is_none_or(|(best, _)| arrival < *best);
+                if improves {
+                    labels.insert(edge.to, (arrival, Some(id)));
+                    queue.push(Arrival(arrival, edge.to));
+                }
+            }
+        }
+        labels
+    }
+}
+
+impl<IDDataType, NodeDataType, TimeDataType> Default
+    for TemporalGraph<IDDataType, NodeDataType, TimeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    TimeDataType: Copy + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IDDataType, NodeDataType, TimeDataType> Nodes
+    for TemporalGraph<IDDataType, NodeDataType, TimeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    type Id = IDDataType;
+
+    fn node_ids(&self) -> impl Iterator<Item = IDDataType> + '_ {
+        self.graph.node_ids()
+    }
+
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    fn contains_node(&self, id: IDDataType) -> bool {
+        self.graph.contains_node(id)
+    }
+}
+
+impl<IDDataType, NodeDataType, TimeDataType> Neighbors
+    for TemporalGraph<IDDataType, NodeDataType, TimeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn successors(&self, id: IDDataType) -> impl Iterator<Item = IDDataType> + '_ {
+        self.graph.successors(id)
+    }
+}
+
+impl<IDDataType, NodeDataType, TimeDataType> NodeData
+    for TemporalGraph<IDDataType, NodeDataType, TimeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    type Data = NodeDataType;
+
+    fn node_data(&self, id: IDDataType) -> Option<&NodeDataType> {
+        self.graph.node_data.get(&id)
+    }
+}
+
+/// a queue entry ordered so that `BinaryHeap` pops the earliest arrival first.
+struct Arrival<TimeDataType, IDDataType>(TimeDataType, IDDataType);
+
+impl<TimeDataType: PartialOrd, IDDataType> PartialEq for Arrival<TimeDataType, IDDataType> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<TimeDataType: PartialOrd, IDDataType> Eq for Arrival<TimeDataType, IDDataType> {}
+
+impl<TimeDataType: PartialOrd, IDDataType> PartialOrd for Arrival<TimeDataType, IDDataType> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<TimeDataType: PartialOrd, IDDataType> Ord for Arrival<TimeDataType, IDDataType> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal)
+    }
+}
@@ -0,0 +1,692 @@
+//! Random graphs and assertions for tests, behind the `testing` feature.
+//!
+//! Every kind of graph has a small representation over the node IDs `0..n`: a directed graph, a
+//! directed acyclic graph, an undirected graph, a tree and a connected graph. Any value of a
+//! representation describes a valid graph of its kind (out of range endpoints wrap around), so
+//! both `proptest` and `quickcheck` can shrink them freely, dropping nodes and edges, and failures
+//! come out as small graphs of the same kind. The `*_with` strategies take the range of node
+//! counts and the density of the graphs.
+//!
+//! ```
+//! use grafferous::testing::dags;
+//! use proptest::{prelude::*, test_runner::TestRunner};
+//!
+//! TestRunner::default()
+//!     .run(&dags::<()>(20), |dag| {
+//!         prop_assert!(dag.edge_tuples().iter().all(|(from, to)| from < to));
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! The [`assert_graph_eq!`](crate::assert_graph_eq), [`assert_is_dag!`](crate::assert_is_dag) and
+//! [`assert_connected!`](crate::assert_connected) macros explain what is wrong when they fail:
+//! which nodes and edges differ, which cycle was found or which nodes cannot reach each other.
+
+use core::hash::Hash;
+use proptest::{
+    collection::{vec, SizeRange},
+    prelude::*,
+};
+use quickcheck::{Arbitrary, Gen};
+use std::{collections::VecDeque, fmt::Debug};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+/// A directed graph without self-loops or parallel edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitraryGraph {
+    /// The nodes are `0..node_count`.
+    pub node_count: usize,
+    /// The edges; endpoints are taken modulo `node_count` and self-loops are dropped.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl ArbitraryGraph {
+    /// Builds the graph, adding the nodes in order of their IDs.
+    pub fn to_graph<NodeDataType: Default>(&self) -> Graph<usize, NodeDataType> {
+        let edges = wrapped(&self.edges, self.node_count);
+        let mut graph = with_nodes(self.node_count);
+        graph.add_directed_edges(&edges);
+        graph.simplify(true);
+        graph
+    }
+}
+
+/// A directed acyclic graph in which every edge goes from a lower to a higher ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitraryDag {
+    /// The nodes are `0..node_count`.
+    pub node_count: usize,
+    /// The edges; endpoints are taken modulo `node_count` and every edge points to the higher ID.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl ArbitraryDag {
+    /// Builds the graph, adding the nodes in order of their IDs.
+    pub fn to_graph<NodeDataType: Default>(&self) -> Graph<usize, NodeDataType> {
+        let edges: Vec<(usize, usize)> = wrapped(&self.edges, self.node_count)
+            .into_iter()
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        let mut graph = with_nodes(self.node_count);
+        graph.add_directed_edges(&edges);
+        graph.simplify(true);
+        graph
+    }
+}
+
+/// An undirected graph without self-loops or parallel edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitraryUndirectedGraph {
+    /// The nodes are `0..node_count`.
+    pub node_count: usize,
+    /// The undirected edges; endpoints are taken modulo `node_count` and self-loops are dropped.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl ArbitraryUndirectedGraph {
+    /// Builds the graph, adding the nodes in order of their IDs.
+    pub fn to_graph<NodeDataType: Default>(&self) -> Graph<usize, NodeDataType> {
+        let edges = wrapped(&self.edges, self.node_count);
+        let mut graph = with_nodes(self.node_count);
+        graph.add_edges(&edges);
+        graph.simplify(true);
+        graph
+    }
+}
+
+/// An undirected tree with at least one node, rooted at node `0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitraryTree {
+    /// Node `i + 1` is connected to its parent `parents[i] % (i + 1)`, so there are
+    /// `parents.len() + 1` nodes.
+    pub parents: Vec<usize>,
+}
+
+impl ArbitraryTree {
+    /// Builds the graph, adding the nodes in order of their IDs.
+    pub fn to_graph<NodeDataType: Default>(&self) -> Graph<usize, NodeDataType> {
+        let mut graph = with_nodes(self.parents.len() + 1);
+        graph.add_edges(&self.tree_edges());
+        graph
+    }
+
+    /// the `(parent, child)` pairs of the tree.
+    fn tree_edges(&self) -> Vec<(usize, usize)> {
+        self.parents
+            .iter()
+            .enumerate()
+            .map(|(i, parent)| (parent % (i + 1), i + 1))
+            .collect()
+    }
+}
+
+/// A connected undirected graph: a spanning tree plus extra edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitraryConnectedGraph {
+    /// The spanning tree, which also fixes the nodes.
+    pub tree: ArbitraryTree,
+    /// Further edges; endpoints are taken modulo the number of nodes, and self-loops and edges
+    /// that already exist are dropped.
+    pub extra_edges: Vec<(usize, usize)>,
+}
+
+impl ArbitraryConnectedGraph {
+    /// Builds the graph, adding the nodes in order of their IDs.
+    pub fn to_graph<NodeDataType: Default>(&self) -> Graph<usize, NodeDataType> {
+        let mut edges = self.tree.tree_edges();
+        edges.extend(wrapped(&self.extra_edges, self.tree.parents.len() + 1));
+        let mut graph = with_nodes(self.tree.parents.len() + 1);
+        graph.add_edges(&edges);
+        graph.simplify(true);
+        graph
+    }
+}
+
+/// a graph with the nodes `0..node_count` and no edges.
+fn with_nodes<NodeDataType: Default>(node_count: usize) -> Graph<usize, NodeDataType> {
+    let mut graph = Graph::new();
+    for id in 0..node_count {
+        graph.add_node(id);
+    }
+    graph
+}
+
+/// takes both endpoints of every edge modulo `node_count`; without nodes there are no edges.
+fn wrapped(edges: &[(usize, usize)], node_count: usize) -> Vec<(usize, usize)> {
+    if node_count == 0 {
+        return Vec::new();
+    }
+    edges
+        .iter()
+        .map(|&(a, b)| (a % node_count, b % node_count))
+        .collect()
+}
+
+/// A strategy for edge lists of up to `2 * max_nodes` edges between nodes below `max_nodes`.
+fn edge_lists(max_nodes: usize) -> impl Strategy<Value = Vec<(usize, usize)>> {
+    let end = max_nodes.max(1);
+    vec((0..end, 0..end), 0..=2 * max_nodes)
+}
+
+/// A strategy for a node count in `nodes` and up to `density` times `pairs(node_count)` edges
+/// between the nodes.
+fn sized_edge_lists(
+    nodes: impl Into<SizeRange>,
+    density: f64,
+    pairs: fn(usize) -> usize,
+) -> impl Strategy<Value = (usize, Vec<(usize, usize)>)> {
+    let (min, max) = nodes.into().start_end_incl();
+    (min..=max).prop_flat_map(move |node_count| {
+        let end = node_count.max(1);
+        let max_edges = (density.clamp(0.0, 1.0) * pairs(node_count) as f64).ceil() as usize;
+        (Just(node_count), vec((0..end, 0..end), 0..=max_edges))
+    })
+}
+
+/// the number of ordered and unordered pairs of different nodes.
+fn ordered_pairs(node_count: usize) -> usize {
+    node_count * node_count.saturating_sub(1)
+}
+
+fn unordered_pairs(node_count: usize) -> usize {
+    ordered_pairs(node_count) / 2
+}
+
+/// A strategy for directed graphs with up to `max_nodes` nodes.
+pub fn arbitrary_graphs(max_nodes: usize) -> impl Strategy<Value = ArbitraryGraph> {
+    (0..=max_nodes, edge_lists(max_nodes))
+        .prop_map(|(node_count, edges)| ArbitraryGraph { node_count, edges })
+}
+
+/// A strategy for directed acyclic graphs with up to `max_nodes` nodes.
+pub fn arbitrary_dags(max_nodes: usize) -> impl Strategy<Value = ArbitraryDag> {
+    (0..=max_nodes, edge_lists(max_nodes))
+        .prop_map(|(node_count, edges)| ArbitraryDag { node_count, edges })
+}
+
+/// A strategy for undirected graphs with up to `max_nodes` nodes.
+pub fn arbitrary_undirected_graphs(
+    max_nodes: usize,
+) -> impl Strategy<Value = ArbitraryUndirectedGraph> {
+    (0..=max_nodes, edge_lists(max_nodes))
+        .prop_map(|(node_count, edges)| ArbitraryUndirectedGraph { node_count, edges })
+}
+
+/// A strategy for directed graphs with a number of nodes in `nodes` and at most a `density`
+/// fraction of all possible edges. Edges are drawn with repetition, so dense graphs come out with
+/// fewer edges than the limit.
+pub fn arbitrary_graphs_with(
+    nodes: impl Into<SizeRange>,
+    density: f64,
+) -> impl Strategy<Value = ArbitraryGraph> {
+    sized_edge_lists(nodes, density, ordered_pairs)
+        .prop_map(|(node_count, edges)| ArbitraryGraph { node_count, edges })
+}
+
+/// Like [`arbitrary_graphs_with`], but for directed acyclic graphs.
+pub fn arbitrary_dags_with(
+    nodes: impl Into<SizeRange>,
+    density: f64,
+) -> impl Strategy<Value = ArbitraryDag> {
+    sized_edge_lists(nodes, density, unordered_pairs)
+        .prop_map(|(node_count, edges)| ArbitraryDag { node_count, edges })
+}
+
+/// Like [`arbitrary_graphs_with`], but for undirected graphs.
+pub fn arbitrary_undirected_graphs_with(
+    nodes: impl Into<SizeRange>,
+    density: f64,
+) -> impl Strategy<Value = ArbitraryUndirectedGraph> {
+    sized_edge_lists(nodes, density, unordered_pairs)
+        .prop_map(|(node_count, edges)| ArbitraryUndirectedGraph { node_count, edges })
+}
+
+/// A strategy for trees with between one and `max_nodes` nodes.
+pub fn arbitrary_trees(max_nodes: usize) -> impl Strategy<Value = ArbitraryTree> {
+    let end = max_nodes.max(1);
+    vec(0..end, 0..end).prop_map(|parents| ArbitraryTree { parents })
+}
+
+/// A strategy for connected graphs with between one and `max_nodes` nodes.
+pub fn arbitrary_connected_graphs(
+    max_nodes: usize,
+) -> impl Strategy<Value = ArbitraryConnectedGraph> {
+    (arbitrary_trees(max_nodes), edge_lists(max_nodes))
+        .prop_map(|(tree, extra_edges)| ArbitraryConnectedGraph { tree, extra_edges })
+}
+
+/// A strategy for directed graphs with up to `max_nodes` nodes, see [`ArbitraryGraph`].
+///
+/// # Arguments
+///
+/// * `max_nodes` - The largest number of nodes to generate.
+///
+pub fn graphs<NodeDataType: Default + Debug>(
+    max_nodes: usize,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_graphs(max_nodes).prop_map(|graph| graph.to_graph())
+}
+
+/// A strategy for directed acyclic graphs with up to `max_nodes` nodes, see [`ArbitraryDag`].
+///
+/// # Arguments
+///
+/// * `max_nodes` - The largest number of nodes to generate.
+///
+pub fn dags<NodeDataType: Default + Debug>(
+    max_nodes: usize,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_dags(max_nodes).prop_map(|dag| dag.to_graph())
+}
+
+/// A strategy for undirected graphs with up to `max_nodes` nodes, see
+/// [`ArbitraryUndirectedGraph`].
+///
+/// # Arguments
+///
+/// * `max_nodes` - The largest number of nodes to generate.
+///
+pub fn undirected_graphs<NodeDataType: Default + Debug>(
+    max_nodes: usize,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_undirected_graphs(max_nodes).prop_map(|graph| graph.to_graph())
+}
+
+/// A strategy for directed graphs of a given size and density, see [`arbitrary_graphs_with`].
+///
+/// # Arguments
+///
+/// * `nodes` - The number of nodes to generate, e.g. `5..=20`.
+/// * `density` - The largest fraction of all possible edges to generate, between 0 and 1.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::testing::graphs_with;
+/// use proptest::{prelude::*, test_runner::TestRunner};
+///
+/// TestRunner::default()
+///     .run(&graphs_with::<()>(10..=20, 0.1), |g| {
+///         prop_assert!(g.nodes.len() >= 10);
+///         prop_assert!(g.edge_count() <= 38);
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+pub fn graphs_with<NodeDataType: Default + Debug>(
+    nodes: impl Into<SizeRange>,
+    density: f64,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_graphs_with(nodes, density).prop_map(|graph| graph.to_graph())
+}
+
+/// A strategy for directed acyclic graphs of a given size and density, see
+/// [`arbitrary_dags_with`].
+///
+/// # Arguments
+///
+/// * `nodes` - The number of nodes to generate, e.g. `5..=20`.
+/// * `density` - The largest fraction of all possible edges to generate, between 0 and 1.
+///
+pub fn dags_with<NodeDataType: Default + Debug>(
+    nodes: impl Into<SizeRange>,
+    density: f64,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_dags_with(nodes, density).prop_map(|dag| dag.to_graph())
+}
+
+/// A strategy for undirected graphs of a given size and density, see
+/// [`arbitrary_undirected_graphs_with`].
+///
+/// # Arguments
+///
+/// * `nodes` - The number of nodes to generate, e.g. `5..=20`.
+/// * `density` - The largest fraction of all possible edges to generate, between 0 and 1.
+///
+pub fn undirected_graphs_with<NodeDataType: Default + Debug>(
+    nodes: impl Into<SizeRange>,
+    density: f64,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_undirected_graphs_with(nodes, density).prop_map(|graph| graph.to_graph())
+}
+
+/// A strategy for undirected trees with between one and `max_nodes` nodes, see [`ArbitraryTree`].
+///
+/// # Arguments
+///
+/// * `max_nodes` - The largest number of nodes to generate.
+///
+pub fn trees<NodeDataType: Default + Debug>(
+    max_nodes: usize,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_trees(max_nodes).prop_map(|tree| tree.to_graph())
+}
+
+/// A strategy for connected undirected graphs with between one and `max_nodes` nodes,
+/// see [`ArbitraryConnectedGraph`].
+///
+/// # Arguments
+///
+/// * `max_nodes` - The largest number of nodes to generate.
+///
+pub fn connected_graphs<NodeDataType: Default + Debug>(
+    max_nodes: usize,
+) -> impl Strategy<Value = Graph<usize, NodeDataType>> {
+    arbitrary_connected_graphs(max_nodes).prop_map(|graph| graph.to_graph())
+}
+
+/// an edge list with up to twice as many edges as nodes, for `quickcheck`.
+fn arbitrary_edges(g: &mut Gen, node_count: usize) -> Vec<(usize, usize)> {
+    let end = node_count.max(1);
+    let count = usize::arbitrary(g) % (2 * node_count + 1);
+    (0..count)
+        .map(|_| (usize::arbitrary(g) % end, usize::arbitrary(g) % end))
+        .collect()
+}
+
+impl Arbitrary for ArbitraryGraph {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let node_count = usize::arbitrary(g) % (g.size() + 1);
+        let edges = arbitrary_edges(g, node_count);
+        Self { node_count, edges }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let shrunk = (self.node_count, self.edges.clone()).shrink();
+        Box::new(shrunk.map(|(node_count, edges)| Self { node_count, edges }))
+    }
+}
+
+impl Arbitrary for ArbitraryDag {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let node_count = usize::arbitrary(g) % (g.size() + 1);
+        let edges = arbitrary_edges(g, node_count);
+        Self { node_count, edges }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let shrunk = (self.node_count, self.edges.clone()).shrink();
+        Box::new(shrunk.map(|(node_count, edges)| Self { node_count, edges }))
+    }
+}
+
+impl Arbitrary for ArbitraryUndirectedGraph {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let node_count = usize::arbitrary(g) % (g.size() + 1);
+        let edges = arbitrary_edges(g, node_count);
+        Self { node_count, edges }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let shrunk = (self.node_count, self.edges.clone()).shrink();
+        Box::new(shrunk.map(|(node_count, edges)| Self { node_count, edges }))
+    }
+}
+
+impl Arbitrary for ArbitraryTree {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let count = usize::arbitrary(g) % g.size().max(1);
+        let parents = (0..count).map(|i| usize::arbitrary(g) % (i + 1)).collect();
+        Self { parents }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.parents.shrink().map(|parents| Self { parents }))
+    }
+}
+
+impl Arbitrary for ArbitraryConnectedGraph {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let tree = ArbitraryTree::arbitrary(g);
+        let extra_edges = arbitrary_edges(g, tree.parents.len() + 1);
+        Self { tree, extra_edges }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let shrunk = (self.tree.clone(), self.extra_edges.clone()).shrink();
+        Box::new(shrunk.map(|(tree, extra_edges)| Self { tree, extra_edges }))
+    }
+}
+
+/// Asserts that two graphs have the same nodes, node data, edges and edge data, ignoring the order
+/// of nodes and edges. On failure the message lists the nodes and edges found in only one of the
+/// graphs and the nodes and edges whose data differ, see
+/// [`graph_difference`](crate::testing::graph_difference).
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{assert_graph_eq, Graph};
+///
+/// let a = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3)]);
+/// let b = Graph::<u32, ()>::from_edges(&[(2, 3), (1, 2)]);
+/// assert_graph_eq!(a, b);
+/// ```
+#[macro_export]
+macro_rules! assert_graph_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if let Some(difference) = $crate::testing::graph_difference(left, right) {
+                    panic!("assertion `left == right` failed: the graphs differ\n{}", difference);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if let Some(difference) = $crate::testing::graph_difference(left, right) {
+                    panic!(
+                        "assertion `left == right` failed: {}\n{}",
+                        format_args!($($arg)+),
+                        difference
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Asserts that a graph is a directed acyclic graph; on failure the message shows a cycle.
+///
+/// Unlike `Graph::is_directed_acyclic`, a graph without edges passes.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{assert_is_dag, Graph};
+///
+/// let g = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3), (1, 3)]);
+/// assert_is_dag!(g);
+/// ```
+#[macro_export]
+macro_rules! assert_is_dag {
+    ($graph:expr $(,)?) => {
+        if let Some(cycle) = $crate::testing::find_cycle(&$graph) {
+            panic!("assertion failed: the graph has a cycle: {}", $crate::testing::format_path(&cycle));
+        }
+    };
+    ($graph:expr, $($arg:tt)+) => {
+        if let Some(cycle) = $crate::testing::find_cycle(&$graph) {
+            panic!(
+                "assertion failed: {}\nthe graph has a cycle: {}",
+                format_args!($($arg)+),
+                $crate::testing::format_path(&cycle)
+            );
+        }
+    };
+}
+
+/// Asserts that a graph is weakly connected, i.e. connected when edge directions are ignored;
+/// on failure the message names two nodes that cannot reach each other.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{assert_connected, generate_grid_graph};
+///
+/// assert_connected!(generate_grid_graph::<()>(4, 4));
+/// ```
+#[macro_export]
+macro_rules! assert_connected {
+    ($graph:expr $(,)?) => {
+        if let Some((a, b, components)) = $crate::testing::disconnected_pair(&$graph) {
+            panic!(
+                "assertion failed: the graph is not connected: it has {} components, e.g. {:?} and {:?} are not connected",
+                components, a, b
+            );
+        }
+    };
+    ($graph:expr, $($arg:tt)+) => {
+        if let Some((a, b, components)) = $crate::testing::disconnected_pair(&$graph) {
+            panic!(
+                "assertion failed: {}\nthe graph is not connected: it has {} components, e.g. {:?} and {:?} are not connected",
+                format_args!($($arg)+),
+                components, a, b
+            );
+        }
+    };
+}
+
+/// Describes how two graphs differ, ignoring the order of nodes and edges, or returns `None` if
+/// they have the same nodes, node data, edges (counting parallel edges) and edge data.
+pub fn graph_difference<IDDataType, NodeDataType, EdgeDataType>(
+    left: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    right: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Option<String>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: PartialEq + Debug,
+    EdgeDataType: PartialEq + Debug,
+{
+    let only_in = |a: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+                   b: &Graph<IDDataType, NodeDataType, EdgeDataType>| {
+        sorted(
+            a.nodes
+                .iter()
+                .filter(|id| !b.node_data.contains_key(id))
+                .map(|id| format!("{id:?}")),
+        )
+    };
+    let mut edge_counts: NodeMap<(IDDataType, IDDataType), isize> = NodeMap::default();
+    for edge in left.edges_iter() {
+        *edge_counts.entry(edge).or_default() += 1;
+    }
+    for edge in right.edges_iter() {
+        *edge_counts.entry(edge).or_default() -= 1;
+    }
+    let edges_where =
+        |keep: fn(isize) -> bool| {
+            sorted(edge_counts.iter().filter(|(_, count)| keep(**count)).map(
+                |((from, to), count)| match count.abs() {
+                    1 => format!("{from:?} -> {to:?}"),
+                    n => format!("{from:?} -> {to:?} ({n} times)"),
+                },
+            ))
+        };
+
+    let mut lines = Vec::new();
+    let sections = [
+        ("nodes only in left", only_in(left, right)),
+        ("nodes only in right", only_in(right, left)),
+        ("edges only in left", edges_where(|count| count > 0)),
+        ("edges only in right", edges_where(|count| count < 0)),
+    ];
+    for (title, items) in sections {
+        if !items.is_empty() {
+            lines.push(format!("{title}: {}", items.join(", ")));
+        }
+    }
+    let changed = sorted(left.nodes.iter().filter_map(|id| {
+        let (a, b) = (&left.node_data[id], right.node_data.get(id)?);
+        (a != b).then(|| format!("  {id:?}: left {a:?}, right {b:?}"))
+    }));
+    if !changed.is_empty() {
+        lines.push("node data differs:".to_string());
+        lines.extend(changed);
+    }
+    let changed_edges = sorted(
+        left.edge_data
+            .keys()
+            .chain(right.edge_data.keys())
+            .filter(|edge| left.edge_data.get(edge) != right.edge_data.get(edge))
+            .collect::<NodeSet<_>>()
+            .into_iter()
+            .map(|(from, to)| {
+                let (a, b) = (left.edge_data(*from, *to), right.edge_data(*from, *to));
+                format!("  {from:?} -> {to:?}: left {a:?}, right {b:?}")
+            }),
+    );
+    if !changed_edges.is_empty() {
+        lines.push("edge data differs:".to_string());
+        lines.extend(changed_edges);
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Finds a directed cycle, returned as its nodes with the first node repeated at the end, or
+/// `None` if the graph is acyclic, see `Graph::find_cycle`.
+pub fn find_cycle<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Option<Vec<IDDataType>>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    graph.find_cycle()
+}
+
+/// Finds two nodes that are not connected even when edge directions are ignored, together with
+/// the number of weakly connected components, or returns `None` if the graph is weakly connected.
+pub fn disconnected_pair<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> Option<(IDDataType, IDDataType, usize)>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    let mut component: NodeMap<IDDataType, usize> = NodeMap::default();
+    let mut representatives = Vec::new();
+    for root in &graph.nodes {
+        if component.contains_key(root) {
+            continue;
+        }
+        let label = representatives.len();
+        representatives.push(*root);
+        component.insert(*root, label);
+        let mut queue = VecDeque::from([*root]);
+        while let Some(node) = queue.pop_front() {
+            let out = graph.edges.get(&node).into_iter().flatten();
+            let into = graph.reverse_edges.get(&node).into_iter().flatten();
+            for next in out.chain(into) {
+                if !component.contains_key(next) {
+                    component.insert(*next, label);
+                    queue.push_back(*next);
+                }
+            }
+        }
+    }
+    match representatives[..] {
+        [first, second, ..] => Some((first, second, representatives.len())),
+        _ => None,
+    }
+}
+
+/// Formats a path of nodes as `a -> b -> c`.
+pub fn format_path<IDDataType: Debug>(path: &[IDDataType]) -> String {
+    path.iter()
+        .map(|id| format!("{id:?}"))
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// sorts formatted items so that failure messages do not depend on hash order.
+fn sorted(items: impl Iterator<Item = String>) -> Vec<String> {
+    let mut items: Vec<String> = items.collect();
+    items.sort();
+    items
+}
@@ -0,0 +1,407 @@
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+use crate::Graph;
+
+/// A single primitive change to a graph.
+/// Applying an edit returns the edit that reverts it.
+#[derive(Debug, Clone)]
+enum Edit<IDDataType, NodeDataType, EdgeDataType> {
+    AddNode(IDDataType, NodeDataType),
+    /// adds the node back at its old position in `nodes`
+    InsertNode(IDDataType, NodeDataType, usize),
+    /// removes the node together with its edges
+    RemoveNode(IDDataType),
+    AddEdge(IDDataType, IDDataType),
+    /// adds a copy of the edge back at its old positions in `edges` and `reverse_edges`,
+    /// restoring its data if it was removed with the last copy
+    InsertEdge(IDDataType, IDDataType, (usize, usize), Option<EdgeDataType>),
+    RemoveEdge(IDDataType, IDDataType),
+    SetData(IDDataType, NodeDataType),
+    /// edits applied in order, reverted in the opposite order
+    Batch(Vec<Self>),
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Edit<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// applies the edit, returning its inverse, or `None` if the edit had no effect.
+    fn apply(self, graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>) -> Option<Self> {
+        match self {
+            Edit::AddNode(id, data) => {
+                if graph.node_data.contains_key(&id) {
+                    return None;
+                }
+                graph.add_node_with_data(id, data);
+                Some(Edit::RemoveNode(id))
+            }
+            Edit::InsertNode(id, data, position) => {
+                if graph.node_data.contains_key(&id) {
+                    return None;
+                }
+                graph.insert_node_at(id, data, position);
+                Some(Edit::RemoveNode(id))
+            }
+            Edit::RemoveNode(id) => {
+                if !graph.node_data.contains_key(&id) {
+                    return None;
+                }
+                // remove the edges one by one so each copy is restored where it was
+                let mut inverse = Vec::new();
+                while let Some(to) = graph.neighbors(id).last().copied() {
+                    inverse.extend(Edit::RemoveEdge(id, to).apply(graph));
+                }
+                while let Some(from) = graph.reverse_neighbors(id).last().copied() {
+                    inverse.extend(Edit::RemoveEdge(from, id).apply(graph));
+                }
+                let position = graph.nodes.iter().rposition(|node| *node == id)?;
+                let data = graph.remove_node(id)?;
+                inverse.push(Edit::InsertNode(id, data, position));
+                inverse.reverse();
+                Some(Edit::Batch(inverse))
+            }
+            Edit::AddEdge(from, to) => {
+                // the edge policy may reject the edge, then there is nothing to revert
+                let before = graph.edge_multiplicity(from, to);
+                graph.add_directed_edge(from, to);
+                (graph.edge_multiplicity(from, to) != before).then_some(Edit::RemoveEdge(from, to))
+            }
+            Edit::InsertEdge(from, to, positions, data) => {
+                if !graph.node_data.contains_key(&from) || !graph.node_data.contains_key(&to) {
+                    return None;
+                }
+                graph.insert_directed_edge_at(from, to, positions);
+                if let Some(data) = data {
+                    graph.edge_data.insert((from, to), data);
+                }
+                Some(Edit::RemoveEdge(from, to))
+            }
+            Edit::RemoveEdge(from, to) => {
+                let forward = graph.neighbors(from).iter().rposition(|node| *node == to)?;
+                let backward = graph
+                    .reverse_neighbors(to)
+                    .iter()
+                    .rposition(|node| *node == from)?;
+                let data = if graph.edge_multiplicity(from, to) == 1 {
+                    graph.edge_data.remove(&(from, to))
+                } else {
+                    None
+                };
+                graph
+                    .remove_directed_edge(from, to)
+                    .then_some(Edit::InsertEdge(from, to, (forward, backward), data))
+            }
+            Edit::SetData(id, data) => graph
+                .node_data
+                .get_mut(&id)
+                .map(|slot| Edit::SetData(id, core::mem::replace(slot, data))),
+            Edit::Batch(edits) => {
+                let mut inverse: Vec<Self> = edits
+                    .into_iter()
+                    .filter_map(|edit| edit.apply(graph))
+                    .collect();
+                inverse.reverse();
+                (!inverse.is_empty()).then_some(Edit::Batch(inverse))
+            }
+        }
+    }
+}
+
+/// A recorded group of changes that can be reverted as a unit.
+///
+/// Produced by `Transaction::commit` and consumed by a [`History`].
+#[derive(Debug, Clone)]
+pub struct Changeset<IDDataType, NodeDataType, EdgeDataType = ()> {
+    /// the edits that revert the change, in the order they were recorded
+    inverse: Vec<Edit<IDDataType, NodeDataType, EdgeDataType>>,
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType>
+    Changeset<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The number of primitive edits in the changeset.
+    pub fn len(&self) -> usize {
+        self.inverse.len()
+    }
+
+    /// Whether the changeset contains no edits.
+    pub fn is_empty(&self) -> bool {
+        self.inverse.is_empty()
+    }
+
+    /// reverts the changeset and returns the changeset that reapplies it.
+    fn revert(self, graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>) -> Self {
+        let inverse = self
+            .inverse
+            .into_iter()
+            .rev()
+            .filter_map(|edit| edit.apply(graph))
+            .collect();
+        Self { inverse }
+    }
+}
+
+/// A group of mutations applied to a graph that can be committed or rolled back as a whole.
+///
+/// Created by `Graph::begin`. Every mutation is applied to the graph immediately, so the
+/// graph can be inspected mid-transaction with `graph()`. Dropping the transaction without
+/// calling `commit` rolls all of its changes back.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{Graph, History};
+///
+/// let mut g = Graph::<usize, u32>::new();
+/// let mut history = History::new();
+///
+/// let mut tx = g.begin();
+/// tx.add_edge(0, 1);
+/// tx.set_node_data(0, 5);
+/// history.record(tx.commit());
+/// assert_eq!(g.node_data[&0], 5);
+///
+/// history.undo(&mut g);
+/// assert!(g.nodes.is_empty());
+///
+/// history.redo(&mut g);
+/// assert_eq!(g.node_data[&0], 5);
+/// assert_eq!(g.neighbors(1), vec![0]);
+/// ```
+pub struct Transaction<'a, IDDataType, NodeDataType: Default, EdgeDataType = ()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    graph: &'a mut Graph<IDDataType, NodeDataType, EdgeDataType>,
+    log: Vec<Edit<IDDataType, NodeDataType, EdgeDataType>>,
+    committed: bool,
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType>
+    Transaction<'_, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn record(&mut self, edit: Edit<IDDataType, NodeDataType, EdgeDataType>) {
+        if let Some(inverse) = edit.apply(self.graph) {
+            self.log.push(inverse);
+        }
+    }
+
+    /// Read access to the graph, including the changes made so far.
+    pub fn graph(&self) -> &Graph<IDDataType, NodeDataType, EdgeDataType> {
+        self.graph
+    }
+
+    /// Adds a node with default data, see `Graph::add_node`.
+    pub fn add_node(&mut self, id: IDDataType) {
+        self.add_node_with_data(id, NodeDataType::default());
+    }
+
+    /// Adds a node with the given data, see `Graph::add_node_with_data`.
+    pub fn add_node_with_data(&mut self, id: IDDataType, data: NodeDataType) {
+        self.record(Edit::AddNode(id, data));
+    }
+
+    /// Adds a directed edge, creating missing nodes, see `Graph::add_directed_edge`.
+    pub fn add_directed_edge(&mut self, from: IDDataType, to: IDDataType) {
+        // record implicitly created nodes separately so they are removed again on rollback
+        self.add_node(from);
+        self.add_node(to);
+        self.record(Edit::AddEdge(from, to));
+    }
+
+    /// Adds an undirected edge, creating missing nodes, see `Graph::add_edge`.
+    pub fn add_edge(&mut self, from: IDDataType, to: IDDataType) {
+        self.add_directed_edge(from, to);
+        self.add_directed_edge(to, from);
+    }
+
+    /// Removes a node and its edges, see `Graph::remove_node`. Rolling back restores the node, its
+    /// data and every removed edge with its data, at their old positions.
+    /// Returns `false` and does nothing if the node does not exist.
+    pub fn remove_node(&mut self, id: IDDataType) -> bool {
+        let before = self.log.len();
+        self.record(Edit::RemoveNode(id));
+        self.log.len() > before
+    }
+
+    /// Removes the most recently added copy of a directed edge, see
+    /// `Graph::remove_directed_edge`. Rolling back restores the copy and its data.
+    /// Returns whether the edge existed.
+    pub fn remove_directed_edge(&mut self, from: IDDataType, to: IDDataType) -> bool {
+        let before = self.log.len();
+        self.record(Edit::RemoveEdge(from, to));
+        self.log.len() > before
+    }
+
+    /// Removes one copy of the edge in each direction, see `Graph::remove_edge`.
+    /// Returns whether an edge was removed in either direction.
+    pub fn remove_edge(&mut self, from: IDDataType, to: IDDataType) -> bool {
+        let forward = self.remove_directed_edge(from, to);
+        let backward = self.remove_directed_edge(to, from);
+        forward || backward
+    }
+
+    /// Replaces the data of an existing node.
+    /// Returns `false` and does nothing if the node does not exist.
+    pub fn set_node_data(&mut self, id: IDDataType, data: NodeDataType) -> bool {
+        let before = self.log.len();
+        self.record(Edit::SetData(id, data));
+        self.log.len() > before
+    }
+
+    /// Keeps the changes and returns them as a changeset that can be undone later.
+    pub fn commit(mut self) -> Changeset<IDDataType, NodeDataType, EdgeDataType> {
+        self.committed = true;
+        Changeset {
+            inverse: core::mem::take(&mut self.log),
+        }
+    }
+
+    /// Reverts every change made in this transaction.
+    pub fn rollback(self) {
+        // dropping an uncommitted transaction rolls it back
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Drop
+    for Transaction<'_, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn drop(&mut self) {
+        if !self.committed {
+            let log = core::mem::take(&mut self.log);
+            Changeset { inverse: log }.revert(self.graph);
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Starts a transaction, see [`Transaction`].
+    pub fn begin(&mut self) -> Transaction<'_, IDDataType, NodeDataType, EdgeDataType> {
+        Transaction {
+            graph: self,
+            log: Vec::new(),
+            committed: false,
+        }
+    }
+
+    /// adds a node at `position` in `nodes`, or at the end if the list has become shorter.
+    fn insert_node_at(&mut self, id: IDDataType, data: NodeDataType, position: usize) {
+        self.add_node_with_data(id, data);
+        if position < self.nodes.len() - 1 {
+            self.nodes.pop();
+            self.nodes.insert(position, id);
+        }
+    }
+
+    /// adds a copy of an edge between existing nodes at the given positions in `edges[from]` and
+    /// `reverse_edges[to]`, or at the end of lists that have become shorter, bypassing the edge
+    /// policy because the edge was in the graph before.
+    fn insert_directed_edge_at(
+        &mut self,
+        from: IDDataType,
+        to: IDDataType,
+        (forward, backward): (usize, usize),
+    ) {
+        let tos = self.edges.entry(from).or_default();
+        tos.insert(forward.min(tos.len()), to);
+        let froms = self.reverse_edges.entry(to).or_default();
+        froms.insert(backward.min(froms.len()), from);
+        self.undirected.invalidate();
+        self.compact.invalidate();
+        self.edge_index.insert(from, to);
+        self.observers.edge_added(from, to);
+    }
+}
+
+/// An undo/redo stack of committed changesets.
+///
+/// Undo and redo assume the graph has not been changed in conflicting ways outside
+/// the history since the changesets were recorded; edits that no longer apply are skipped.
+/// The data of edges that undo or redo removes is kept and restored with the edges.
+#[derive(Debug, Clone)]
+pub struct History<IDDataType, NodeDataType, EdgeDataType = ()> {
+    undo: Vec<Changeset<IDDataType, NodeDataType, EdgeDataType>>,
+    redo: Vec<Changeset<IDDataType, NodeDataType, EdgeDataType>>,
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType>
+    History<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Pushes a committed changeset onto the undo stack and clears the redo stack.
+    /// Empty changesets are ignored.
+    pub fn record(&mut self, changeset: Changeset<IDDataType, NodeDataType, EdgeDataType>) {
+        if changeset.is_empty() {
+            return;
+        }
+        self.undo.push(changeset);
+        self.redo.clear();
+    }
+
+    /// Reverts the most recent changeset. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>) -> bool {
+        match self.undo.pop() {
+            Some(changeset) => {
+                self.redo.push(changeset.revert(graph));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapplies the most recently undone changeset. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, graph: &mut Graph<IDDataType, NodeDataType, EdgeDataType>) -> bool {
+        match self.redo.pop() {
+            Some(changeset) => {
+                self.undo.push(changeset.revert(graph));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether there is a changeset to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether there is a changeset to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets all recorded changesets.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+impl<IDDataType, NodeDataType: Default, EdgeDataType> Default
+    for History<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
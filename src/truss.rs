@@ -0,0 +1,166 @@
+//! Truss decomposition: cohesive subgraphs in which every edge lies on many triangles.
+
+use alloc::{collections::BinaryHeap, vec::Vec};
+use core::{cmp::Reverse, fmt::Debug, hash::Hash};
+
+use crate::{
+    collections::{NodeMap, NodeSet},
+    Graph,
+};
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The number of triangles every edge lies on, its support. Edge directions are ignored,
+    /// parallel edges count once and self-loops are left out; every edge is listed in both
+    /// orientations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 2 -- 3").unwrap();
+    /// let support = g.edge_triangles();
+    /// assert_eq!(support[&(0, 1)], 1);
+    /// assert_eq!(support[&(3, 2)], 0);
+    /// ```
+    pub fn edge_triangles(&self) -> NodeMap<(IDDataType, IDDataType), usize> {
+        let adjacency = self.undirected_sets();
+        let mut support = NodeMap::default();
+        for (u, neighbors) in adjacency.iter().enumerate() {
+            for v in neighbors {
+                let triangles = common_neighbors(&adjacency, u, *v).count();
+                support.insert((self.nodes[u], self.nodes[*v]), triangles);
+            }
+        }
+        support
+    }
+
+    /// The truss number of every edge: the largest `k` such that the edge belongs to the
+    /// `k`-truss, see [`Graph::k_truss`]. Every edge has a truss number of at least 2. Edge
+    /// directions are ignored and every edge is listed in both orientations.
+    ///
+    /// The edges are peeled off in the order of their support, updating the support of the edges
+    /// they form triangles with, which takes `O(m^1.5)` time.
+    pub fn truss_decomposition(&self) -> NodeMap<(IDDataType, IDDataType), usize> {
+        let mut adjacency = self.undirected_sets();
+        let mut support: NodeMap<(usize, usize), usize> = NodeMap::default();
+        let mut queue = BinaryHeap::new();
+        for (u, neighbors) in adjacency.iter().enumerate() {
+            for v in neighbors.iter().filter(|v| u < **v) {
+                let triangles = common_neighbors(&adjacency, u, *v).count();
+                support.insert((u, *v), triangles);
+                queue.push(Reverse((triangles, u, *v)));
+            }
+        }
+
+        let mut truss = NodeMap::default();
+        let mut k = 2;
+        while let Some(Reverse((triangles, u, v))) = queue.pop() {
+            // entries whose support dropped since they were queued are stale
+            if support.get(&(u, v)) != Some(&triangles) {
+                continue;
+            }
+            support.remove(&(u, v));
+            k = k.max(triangles + 2);
+            truss.insert((self.nodes[u], self.nodes[v]), k);
+            truss.insert((self.nodes[v], self.nodes[u]), k);
+
+            let closing: Vec<usize> = common_neighbors(&adjacency, u, v).collect();
+            adjacency[u].remove(&v);
+            adjacency[v].remove(&u);
+            for w in closing {
+                for edge in [(u.min(w), u.max(w)), (v.min(w), v.max(w))] {
+                    if let Some(triangles) = support.get_mut(&edge) {
+                        *triangles -= 1;
+                        queue.push(Reverse((*triangles, edge.0, edge.1)));
+                    }
+                }
+            }
+        }
+        truss
+    }
+
+    /// The `k`-truss: the largest subgraph in which every edge lies on at least `k - 2` triangles
+    /// within the subgraph. It contains the nodes that keep at least one edge, with their data,
+    /// and all their original edges whose truss number is at least `k`, with their data and in
+    /// both directions if they had both. The edge policy is kept.
+    ///
+    /// Trusses are nested, cohesive cores that hold up better against single stray edges than
+    /// [k-cores](Graph::k_core), as every edge needs the support of triangles rather than just the
+    /// node degree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// // a complete graph on four nodes with a triangle and a loose edge attached
+    /// let g = Graph::<u32, ()>::parse(
+    ///     "0 -- 1 -- 2 -- 3 -- 0; 0 -- 2; 1 -- 3; 3 -- 4 -- 5 -- 3; 5 -- 6",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(g.k_truss(4).nodes, vec![0, 1, 2, 3]);
+    /// assert_eq!(g.k_truss(3).nodes.len(), 6);
+    /// ```
+    pub fn k_truss(&self, k: usize) -> Self
+    where
+        NodeDataType: Clone,
+        EdgeDataType: Clone,
+    {
+        let truss = self.truss_decomposition();
+        let mut core = Graph::with_hasher(self.node_data.hasher().clone());
+        core.set_edge_policy(self.edge_policy());
+        let mut kept = Vec::new();
+        for from in &self.nodes {
+            for to in self.neighbors(*from) {
+                if truss.get(&(*from, *to)).is_some_and(|t| *t >= k) {
+                    kept.push((*from, *to));
+                }
+            }
+        }
+        let touched: NodeSet<IDDataType> = kept.iter().flat_map(|(a, b)| [*a, *b]).collect();
+        for id in self.nodes.iter().filter(|id| touched.contains(*id)) {
+            core.add_node_with_data(*id, self.node_data[id].clone());
+        }
+        for (from, to) in kept {
+            core.edges.entry(from).or_default().push(to);
+            core.reverse_edges.entry(to).or_default().push(from);
+            if let Some(data) = self.edge_data(from, to) {
+                core.edge_data.insert((from, to), data.clone());
+            }
+        }
+        core
+    }
+
+    /// the neighbors of every node by position in `nodes`, ignoring edge directions, parallel
+    /// edges and self-loops.
+    pub(crate) fn undirected_sets(&self) -> Vec<NodeSet<usize>> {
+        let (forward, _) = self.index_adjacency(false);
+        let mut adjacency: Vec<NodeSet<usize>> =
+            (0..self.nodes.len()).map(|_| NodeSet::default()).collect();
+        for (u, tos) in forward.iter().enumerate() {
+            for v in tos.iter().filter(|v| **v != u) {
+                adjacency[u].insert(*v);
+                adjacency[*v].insert(u);
+            }
+        }
+        adjacency
+    }
+}
+
+/// the nodes adjacent to both `u` and `v`, found by scanning the smaller neighborhood.
+fn common_neighbors(
+    adjacency: &[NodeSet<usize>],
+    u: usize,
+    v: usize,
+) -> impl Iterator<Item = usize> + '_ {
+    let (small, large) = if adjacency[u].len() <= adjacency[v].len() {
+        (&adjacency[u], &adjacency[v])
+    } else {
+        (&adjacency[v], &adjacency[u])
+    };
+    small.iter().copied().filter(move |w| large.contains(w))
+}
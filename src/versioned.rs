@@ -0,0 +1,163 @@
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+use crate::{Graph, GraphDiff};
+
+/// A labeled snapshot stored by a [`VersionedGraph`].
+#[derive(Debug, Clone)]
+pub struct Version<IDDataType, NodeDataType, EdgeDataType = ()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// The label given when the snapshot was taken.
+    pub label: String,
+    /// The state of the graph at the time of the snapshot.
+    pub graph: Arc<Graph<IDDataType, NodeDataType, EdgeDataType>>,
+}
+
+/// The differences between two versions of a graph, as computed by `VersionedGraph::compare`.
+pub type VersionDiff<IDDataType> = GraphDiff<IDDataType>;
+
+/// A graph together with a history of labeled snapshots.
+///
+/// Snapshots share storage with the working graph through `Arc`s: taking a snapshot
+/// clones the graph only if it was modified since the previous snapshot, so labelling
+/// the same state repeatedly is free.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{Graph, VersionedGraph};
+///
+/// let mut history = VersionedGraph::new(Graph::<usize, u32>::new());
+/// history.graph_mut().add_edge(0, 1);
+/// history.snapshot("epoch 0");
+/// history.graph_mut().add_edge(1, 2);
+/// history.snapshot("epoch 1");
+///
+/// let diff = history.compare("epoch 0", "epoch 1").unwrap();
+/// assert_eq!(diff.added_nodes, vec![2]);
+///
+/// history.checkout("epoch 0");
+/// assert_eq!(history.graph().nodes.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VersionedGraph<IDDataType, NodeDataType, EdgeDataType = ()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    working: Arc<Graph<IDDataType, NodeDataType, EdgeDataType>>,
+    versions: Vec<Version<IDDataType, NodeDataType, EdgeDataType>>,
+}
+
+impl<IDDataType, NodeDataType: Clone, EdgeDataType: Clone>
+    VersionedGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Starts a history with `graph` as the working graph and no snapshots.
+    pub fn new(graph: Graph<IDDataType, NodeDataType, EdgeDataType>) -> Self {
+        Self {
+            working: Arc::new(graph),
+            versions: Vec::new(),
+        }
+    }
+
+    /// The working graph.
+    pub fn graph(&self) -> &Graph<IDDataType, NodeDataType, EdgeDataType> {
+        &self.working
+    }
+
+    /// Mutable access to the working graph.
+    /// If the current state is shared with a snapshot, it is copied first.
+    pub fn graph_mut(&mut self) -> &mut Graph<IDDataType, NodeDataType, EdgeDataType> {
+        Arc::make_mut(&mut self.working)
+    }
+
+    /// Records the current state of the working graph under `label` and returns its version number.
+    /// Labels do not have to be unique; lookups by label find the most recent version.
+    pub fn snapshot(&mut self, label: impl Into<String>) -> usize {
+        self.versions.push(Version {
+            label: label.into(),
+            graph: self.working.clone(),
+        });
+        self.versions.len() - 1
+    }
+
+    /// The number of recorded versions.
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// Whether no snapshot has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Iterates over the versions from oldest to newest.
+    pub fn versions(
+        &self,
+    ) -> impl Iterator<Item = &Version<IDDataType, NodeDataType, EdgeDataType>> {
+        self.versions.iter()
+    }
+
+    /// The version with the given number.
+    pub fn version(
+        &self,
+        number: usize,
+    ) -> Option<&Version<IDDataType, NodeDataType, EdgeDataType>> {
+        self.versions.get(number)
+    }
+
+    /// The most recent version with the given label.
+    pub fn get(&self, label: &str) -> Option<&Version<IDDataType, NodeDataType, EdgeDataType>> {
+        self.versions.iter().rev().find(|v| v.label == label)
+    }
+
+    /// Replaces the working graph with the most recent version labeled `label`.
+    /// Returns `false` if there is no such version.
+    pub fn checkout(&mut self, label: &str) -> bool {
+        match self.get(label) {
+            Some(version) => {
+                self.working = version.graph.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the working graph with the version with the given number.
+    /// Returns `false` if there is no such version.
+    pub fn checkout_version(&mut self, number: usize) -> bool {
+        match self.versions.get(number) {
+            Some(version) => {
+                self.working = version.graph.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consumes the history and returns the working graph.
+    pub fn into_graph(self) -> Graph<IDDataType, NodeDataType, EdgeDataType> {
+        Arc::unwrap_or_clone(self.working)
+    }
+}
+
+impl<IDDataType, NodeDataType: Clone + PartialEq, EdgeDataType: Clone>
+    VersionedGraph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Compares the most recent versions labeled `older` and `newer`.
+    /// Returns `None` if either label is unknown.
+    pub fn compare(&self, older: &str, newer: &str) -> Option<VersionDiff<IDDataType>> {
+        Some(self.get(older)?.graph.diff(&self.get(newer)?.graph))
+    }
+
+    /// Compares a version with the working graph.
+    /// Returns `None` if the label is unknown.
+    pub fn changes_since(&self, label: &str) -> Option<VersionDiff<IDDataType>> {
+        Some(self.get(label)?.graph.diff(&self.working))
+    }
+}
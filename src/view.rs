@@ -0,0 +1,156 @@
+//! Lazily filtered views of a graph.
+//!
+//! A [`GraphView`] borrows a graph and hides the nodes and edges that fail its predicates. It
+//! implements the traits of [`crate::visit`] that the graph does, so the traversals and shortest
+//! paths in [`crate::visit`], the components
+//! in [`crate::components`] and everything else written against those traits run on the
+//! filtered graph without copying it. Use `Graph::subgraph` or `Graph::filter_edges` instead
+//! when the filtered graph is queried often, since a view tests the predicates on every access.
+
+use crate::visit::{EdgeWeights, Neighbors, NodeData, Nodes};
+
+/// A predicate that keeps every node.
+pub type AllNodes<Id> = fn(Id) -> bool;
+
+/// A predicate that keeps every edge.
+pub type AllEdges<Id> = fn(Id, Id) -> bool;
+
+/// A view of a graph that only shows the nodes for which the node predicate returns `true` and
+/// the directed edges between them for which the edge predicate does.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{components, view::GraphView, visit::bfs_distances, Graph};
+///
+/// let mut g = Graph::<u32, &str>::parse("0 -- 1 -- 2 -- 3 -- 0").unwrap();
+/// g.node_data.insert(2, "closed");
+///
+/// let open = GraphView::new(&g).filter_nodes(|id| g.node_data[&id] != "closed");
+/// assert_eq!(bfs_distances(&open, 1)[&3], 2);
+///
+/// let cut = open.filter_edges(|from, to| (from, to) != (0, 3) && (from, to) != (3, 0));
+/// assert_eq!(components::connected_components(&cut), vec![vec![0, 1], vec![3]]);
+/// ```
+pub struct GraphView<'a, G: Nodes, N = AllNodes<<G as Nodes>::Id>, E = AllEdges<<G as Nodes>::Id>> {
+    graph: &'a G,
+    keep_node: N,
+    keep_edge: E,
+}
+
+impl<'a, G: Nodes> GraphView<'a, G> {
+    /// A view that shows the whole graph, to be narrowed with `filter_nodes` and
+    /// `filter_edges`.
+    pub fn new(graph: &'a G) -> Self {
+        Self {
+            graph,
+            keep_node: |_| true,
+            keep_edge: |_, _| true,
+        }
+    }
+}
+
+impl<'a, G, N, E> GraphView<'a, G, N, E>
+where
+    G: Nodes,
+    N: Fn(G::Id) -> bool,
+    E: Fn(G::Id, G::Id) -> bool,
+{
+    /// Additionally hides the nodes for which `keep` returns `false`, along with their edges.
+    pub fn filter_nodes(
+        self,
+        keep: impl Fn(G::Id) -> bool,
+    ) -> GraphView<'a, G, impl Fn(G::Id) -> bool, E> {
+        let keep_node = self.keep_node;
+        GraphView {
+            graph: self.graph,
+            keep_node: move |id| keep_node(id) && keep(id),
+            keep_edge: self.keep_edge,
+        }
+    }
+
+    /// Additionally hides the directed edges for which `keep` returns `false`.
+    pub fn filter_edges(
+        self,
+        keep: impl Fn(G::Id, G::Id) -> bool,
+    ) -> GraphView<'a, G, N, impl Fn(G::Id, G::Id) -> bool> {
+        let keep_edge = self.keep_edge;
+        GraphView {
+            graph: self.graph,
+            keep_node: self.keep_node,
+            keep_edge: move |from, to| keep_edge(from, to) && keep(from, to),
+        }
+    }
+
+    /// The underlying graph.
+    pub fn graph(&self) -> &'a G {
+        self.graph
+    }
+}
+
+impl<G, N, E> Nodes for GraphView<'_, G, N, E>
+where
+    G: Nodes,
+    N: Fn(G::Id) -> bool,
+{
+    type Id = G::Id;
+
+    fn node_ids(&self) -> impl Iterator<Item = G::Id> + '_ {
+        self.graph.node_ids().filter(|id| (self.keep_node)(*id))
+    }
+
+    fn node_count(&self) -> usize {
+        self.node_ids().count()
+    }
+
+    fn contains_node(&self, id: G::Id) -> bool {
+        self.graph.contains_node(id) && (self.keep_node)(id)
+    }
+}
+
+impl<G, N, E> Neighbors for GraphView<'_, G, N, E>
+where
+    G: Neighbors,
+    N: Fn(G::Id) -> bool,
+    E: Fn(G::Id, G::Id) -> bool,
+{
+    fn successors(&self, id: G::Id) -> impl Iterator<Item = G::Id> + '_ {
+        let shown = (self.keep_node)(id);
+        self.graph
+            .successors(id)
+            .filter(move |to| shown && (self.keep_node)(*to) && (self.keep_edge)(id, *to))
+    }
+}
+
+impl<G, N, E> NodeData for GraphView<'_, G, N, E>
+where
+    G: NodeData,
+    N: Fn(G::Id) -> bool,
+{
+    type Data = G::Data;
+
+    fn node_data(&self, id: G::Id) -> Option<&G::Data> {
+        if (self.keep_node)(id) {
+            self.graph.node_data(id)
+        } else {
+            None
+        }
+    }
+}
+
+impl<G, N, E> EdgeWeights for GraphView<'_, G, N, E>
+where
+    G: EdgeWeights,
+    N: Fn(G::Id) -> bool,
+    E: Fn(G::Id, G::Id) -> bool,
+{
+    type Weight = G::Weight;
+
+    fn edge_weight(&self, from: G::Id, to: G::Id) -> Option<&G::Weight> {
+        if (self.keep_node)(from) && (self.keep_node)(to) && (self.keep_edge)(from, to) {
+            self.graph.edge_weight(from, to)
+        } else {
+            None
+        }
+    }
+}
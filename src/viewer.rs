@@ -0,0 +1,308 @@
+//! An interactive window for exploring graphs, built on `eframe`.
+//!
+//! Drag the background to pan, scroll to zoom, click a node to inspect its data and neighbors, and
+//! drag a node to move it. While the live layout is on, the nodes keep relaxing with
+//! Fruchterman-Reingold style forces; this takes quadratic time per frame, so it is meant for
+//! graphs of up to a few thousand nodes.
+
+use core::hash::Hash;
+use eframe::egui::{self, Color32, Pos2, Sense, Shape, Stroke, Vec2};
+use std::fmt::Debug;
+
+use crate::{
+    collections::NodeMap,
+    layout::{circular, Layout},
+    render::drawn_edges,
+    Graph,
+};
+
+const NODE_RADIUS: f32 = 6.0;
+
+/// Opens a window showing the graph and blocks until it is closed.
+/// The nodes start on a circle and spread out through the live layout.
+///
+/// # Example
+///
+/// ```no_run
+/// let g = grafferous::generate_grid_graph::<()>(10, 10);
+/// grafferous::viewer::view(&g).unwrap();
+/// ```
+pub fn view<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+) -> eframe::Result
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Debug,
+{
+    view_with_layout(graph, &circular(graph), true)
+}
+
+/// Opens a window showing the graph at the given positions and blocks until it is closed.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to show.
+/// * `layout` - The starting position of every node; nodes without one start at the origin.
+/// * `live` - Whether the live layout starts switched on; it can be toggled in the window.
+///
+pub fn view_with_layout<IDDataType, NodeDataType, EdgeDataType>(
+    graph: &Graph<IDDataType, NodeDataType, EdgeDataType>,
+    layout: &Layout<IDDataType>,
+    live: bool,
+) -> eframe::Result
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Debug,
+{
+    let viewer = Viewer::new(graph, layout, live);
+    eframe::run_native(
+        "grafferous",
+        eframe::NativeOptions::default(),
+        Box::new(|_| Ok(Box::new(viewer))),
+    )
+}
+
+/// the state of the window: node positions in layout units and the view transform.
+struct Viewer<'a, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    graph: &'a Graph<IDDataType, NodeDataType, EdgeDataType>,
+    ids: Vec<IDDataType>,
+    edges: Vec<(usize, usize, bool)>,
+    positions: Vec<Vec2>,
+    live: bool,
+    temperature: f32,
+    pan: Vec2,
+    zoom: f32,
+    fitted: bool,
+    selected: Option<usize>,
+    dragged: Option<usize>,
+}
+
+impl<'a, IDDataType, NodeDataType, EdgeDataType> Viewer<'a, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Debug,
+{
+    fn new(
+        graph: &'a Graph<IDDataType, NodeDataType, EdgeDataType>,
+        layout: &Layout<IDDataType>,
+        live: bool,
+    ) -> Self {
+        let ids = graph.nodes.clone();
+        let index: NodeMap<IDDataType, usize> =
+            ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let edges = drawn_edges(graph)
+            .into_iter()
+            .filter_map(|(from, to, directed)| {
+                Some((*index.get(&from)?, *index.get(&to)?, directed))
+            })
+            .collect();
+        // the forces assume unit edge lengths, so spread the layout over a comparable area
+        let scale = (ids.len() as f32).sqrt().max(1.0);
+        let positions = ids
+            .iter()
+            .map(|id| {
+                let (x, y) = layout.get(id).copied().unwrap_or_default();
+                Vec2::new(x as f32, y as f32) * scale
+            })
+            .collect();
+        Self {
+            graph,
+            ids,
+            edges,
+            positions,
+            live,
+            temperature: 0.1 * scale,
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+            fitted: false,
+            selected: None,
+            dragged: None,
+        }
+    }
+
+    /// moves every node once along its net force, by at most the current temperature.
+    fn relax(&mut self) {
+        let n = self.positions.len();
+        let mut displacement = vec![Vec2::ZERO; n];
+        for i in 0..n {
+            for j in i + 1..n {
+                let delta = self.positions[i] - self.positions[j];
+                let force = delta / delta.length_sq().max(0.01);
+                displacement[i] += force;
+                displacement[j] -= force;
+            }
+        }
+        for &(a, b, _) in &self.edges {
+            let delta = self.positions[a] - self.positions[b];
+            let force = delta * delta.length();
+            displacement[a] -= force;
+            displacement[b] += force;
+        }
+        for (i, moved) in displacement.into_iter().enumerate() {
+            if Some(i) != self.dragged && moved.length() > 0.0 {
+                self.positions[i] += moved.normalized() * moved.length().min(self.temperature);
+            }
+        }
+        self.temperature = (self.temperature * 0.995).max(0.005);
+    }
+
+    fn to_screen(&self, center: Pos2, position: Vec2) -> Pos2 {
+        center + self.pan + position * self.zoom
+    }
+
+    /// chooses pan and zoom so that all nodes fit into `rect`.
+    fn fit(&mut self, rect: egui::Rect) {
+        let Some(bounds) = self
+            .positions
+            .iter()
+            .map(|p| egui::Rect::from_min_max(p.to_pos2(), p.to_pos2()))
+            .reduce(|a, b| a.union(b))
+        else {
+            return;
+        };
+        let size = bounds.size().max(Vec2::splat(1e-3));
+        let room = (rect.size() - Vec2::splat(4.0 * NODE_RADIUS)).max(Vec2::splat(1.0));
+        self.zoom = (room.x / size.x).min(room.y / size.y);
+        self.pan = -bounds.center().to_vec2() * self.zoom;
+    }
+
+    /// the node drawn under a screen position, if any.
+    fn node_at(&self, center: Pos2, pointer: Pos2) -> Option<usize> {
+        (0..self.positions.len())
+            .map(|i| {
+                (
+                    i,
+                    self.to_screen(center, self.positions[i]).distance(pointer),
+                )
+            })
+            .filter(|(_, distance)| *distance <= NODE_RADIUS + 2.0)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    fn side_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Graph");
+        let edge_count: usize = self.graph.edges.values().map(|n| n.len()).sum();
+        ui.label(format!("{} nodes, {edge_count} edges", self.ids.len()));
+        if ui.checkbox(&mut self.live, "live layout").changed() && self.live {
+            self.temperature = self.temperature.max(0.05);
+        }
+        if ui.button("fit to window").clicked() {
+            self.fitted = false;
+        }
+        ui.separator();
+        let Some(selected) = self.selected else {
+            ui.label("click a node to inspect it");
+            return;
+        };
+        let id = self.ids[selected];
+        ui.label(format!("node {id:?}"));
+        if let Some(data) = self.graph.node_data.get(&id) {
+            ui.label(format!("data: {data:?}"));
+        }
+        ui.label("neighbors:");
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for neighbor in self.graph.edges.get(&id).into_iter().flatten() {
+                ui.label(format!("{neighbor:?}"));
+            }
+        });
+    }
+
+    fn canvas(&mut self, ui: &mut egui::Ui) {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let rect = response.rect;
+        let center = rect.center();
+        if !self.fitted {
+            self.fit(rect);
+            self.fitted = true;
+        }
+
+        if response.drag_started() {
+            self.dragged = response
+                .interact_pointer_pos()
+                .and_then(|pointer| self.node_at(center, pointer));
+            if self.dragged.is_some() {
+                self.selected = self.dragged;
+            }
+        }
+        if response.dragged() {
+            match self.dragged {
+                Some(node) => self.positions[node] += response.drag_delta() / self.zoom,
+                None => self.pan += response.drag_delta(),
+            }
+        }
+        if response.drag_stopped() {
+            self.dragged = None;
+        }
+        if response.clicked() {
+            self.selected = response
+                .interact_pointer_pos()
+                .and_then(|pointer| self.node_at(center, pointer));
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = (scroll * 0.002).exp() * pinch;
+            if factor != 1.0 {
+                // keep the point under the pointer in place
+                let anchor = pointer - center;
+                self.pan = anchor - (anchor - self.pan) * factor;
+                self.zoom *= factor;
+            }
+        }
+
+        let is_selected = |i: usize| Some(i) == self.selected;
+        for &(a, b, directed) in &self.edges {
+            let (from, to) = (
+                self.to_screen(center, self.positions[a]),
+                self.to_screen(center, self.positions[b]),
+            );
+            let color = if is_selected(a) || is_selected(b) {
+                Color32::from_rgb(230, 140, 30)
+            } else {
+                Color32::GRAY
+            };
+            painter.line_segment([from, to], Stroke::new(1.5, color));
+            let length = from.distance(to);
+            if directed && length > 2.0 * NODE_RADIUS {
+                let direction = (to - from) / length;
+                let tip = to - direction * NODE_RADIUS;
+                let base = tip - direction * NODE_RADIUS;
+                let side = direction.rot90() * NODE_RADIUS / 2.0;
+                painter.add(Shape::convex_polygon(
+                    vec![tip, base + side, base - side],
+                    color,
+                    Stroke::NONE,
+                ));
+            }
+        }
+        for (i, position) in self.positions.iter().enumerate() {
+            let color = if is_selected(i) {
+                Color32::from_rgb(230, 140, 30)
+            } else {
+                Color32::from_rgb(70, 130, 180)
+            };
+            painter.circle_filled(self.to_screen(center, *position), NODE_RADIUS, color);
+        }
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> eframe::App
+    for Viewer<'_, IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    NodeDataType: Debug,
+{
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::SidePanel::left("details")
+            .resizable(true)
+            .show(ctx, |ui| self.side_panel(ui));
+        egui::CentralPanel::default().show(ctx, |ui| self.canvas(ui));
+        if self.live {
+            self.relax();
+            ctx.request_repaint();
+        }
+    }
+}
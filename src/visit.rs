@@ -0,0 +1,794 @@
+//! Read-only traits describing the topology and data of a graph.
+//!
+//! Algorithms written against these traits run unchanged on every graph representation
+//! in the crate, e.g. [`Graph`], [`CompactGraph`], the memory-mapped graph and filtered
+//! [`GraphView`](crate::view::GraphView)s. [`Nodes`] and [`Neighbors`] describe the topology and
+//! are implemented by all of them, [`NodeData`] and [`EdgeWeights`] by the ones that store data.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    ops::Add,
+};
+
+use crate::{
+    collections::{Entry, NodeMap, NodeSet},
+    shortest_path, CompactGraph, Graph,
+};
+
+/// Access to the set of nodes of a graph.
+pub trait Nodes {
+    /// The type identifying a node.
+    type Id: Debug + PartialEq + Eq + Hash + Clone + Copy;
+
+    /// Iterates over all node IDs.
+    fn node_ids(&self) -> impl Iterator<Item = Self::Id> + '_;
+
+    /// The number of nodes.
+    fn node_count(&self) -> usize;
+
+    /// Whether the node exists.
+    fn contains_node(&self, id: Self::Id) -> bool;
+}
+
+/// Access to the outgoing edges of a graph.
+pub trait Neighbors: Nodes {
+    /// Iterates over the outgoing neighbors of a node; empty if the node does not exist.
+    fn successors(&self, id: Self::Id) -> impl Iterator<Item = Self::Id> + '_;
+}
+
+/// Access to the data stored in the nodes of a graph.
+pub trait NodeData: Nodes {
+    /// The type of the data of a node.
+    type Data;
+
+    /// The data of a node, or `None` if the node does not exist.
+    fn node_data(&self, id: Self::Id) -> Option<&Self::Data>;
+}
+
+/// Access to the data of the directed edges of a graph, such as their weights.
+pub trait EdgeWeights: Neighbors {
+    /// The type of the data of an edge.
+    type Weight;
+
+    /// The data of the directed edge from `from` to `to`, or `None` if the edge does not exist or
+    /// has no data.
+    fn edge_weight(&self, from: Self::Id, to: Self::Id) -> Option<&Self::Weight>;
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Nodes for Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    type Id = IDDataType;
+
+    fn node_ids(&self) -> impl Iterator<Item = IDDataType> + '_ {
+        self.nodes.iter().copied()
+    }
+
+    fn node_count(&self) -> usize {
+        Graph::node_count(self)
+    }
+
+    fn contains_node(&self, id: IDDataType) -> bool {
+        Graph::contains_node(self, id)
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Neighbors
+    for Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn successors(&self, id: IDDataType) -> impl Iterator<Item = IDDataType> + '_ {
+        self.edges.get(&id).into_iter().flatten().copied()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> NodeData
+    for Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    type Data = NodeDataType;
+
+    fn node_data(&self, id: IDDataType) -> Option<&NodeDataType> {
+        self.node_data.get(&id)
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> EdgeWeights
+    for Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    type Weight = EdgeDataType;
+
+    fn edge_weight(&self, from: IDDataType, to: IDDataType) -> Option<&EdgeDataType> {
+        self.edge_data(from, to)
+    }
+}
+
+/// A compact graph is traversed by slot, so its node IDs are the `u32` slots.
+impl<IDDataType> Nodes for CompactGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    type Id = u32;
+
+    fn node_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        0..self.len() as u32
+    }
+
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+
+    fn contains_node(&self, id: u32) -> bool {
+        (id as usize) < self.len()
+    }
+}
+
+impl<IDDataType> Neighbors for CompactGraph<IDDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    fn successors(&self, id: u32) -> impl Iterator<Item = u32> + '_ {
+        let neighbors: &[u32] = if self.contains_node(id) {
+            self.neighbors(id)
+        } else {
+            &[]
+        };
+        neighbors.iter().copied()
+    }
+}
+
+/// Node IDs that are small unsigned integers, so that traversals can keep them in a vector
+/// indexed by the ID instead of hashing them into a set.
+///
+/// This is implemented for every hashable type and detected from how the type hashes: an ID whose
+/// `Hash` implementation writes exactly one non-negative integer, such as a `usize`, a `u32` or a
+/// newtype deriving `Hash` around one, is compact. Tuples, strings and negative integers are not. [`Bfs`],
+/// [`Dfs`], `bfs_distances` and the component algorithms use a plain vector for compact IDs
+/// automatically, as long as the IDs stay below a few times the node count; larger IDs fall back
+/// to hashing, so sparse integer IDs still work.
+///
+/// The index does not have to be unique: a type may hash only some of its fields, so that two
+/// IDs share an index. The vectors store the ID that owns each slot and compare it on every
+/// lookup, and an ID whose slot is taken by another one is hashed instead.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::visit::CompactIds;
+///
+/// assert_eq!(7usize.compact_index(), Some(7));
+/// assert_eq!('a'.compact_index(), Some(97));
+/// assert_eq!((1usize, 2usize).compact_index(), None);
+/// assert_eq!("a".compact_index(), None);
+/// assert_eq!(5i32.compact_index(), Some(5));
+/// assert_eq!((-1i32).compact_index(), None);
+/// ```
+pub trait CompactIds {
+    /// The ID as an index into a vector, or `None` if it is not a single non-negative integer.
+    fn compact_index(&self) -> Option<usize>;
+}
+
+impl<T: Hash + ?Sized> CompactIds for T {
+    #[inline]
+    fn compact_index(&self) -> Option<usize> {
+        let mut capture = IndexCapture::Empty;
+        self.hash(&mut capture);
+        match capture {
+            IndexCapture::Index(index) => Some(index),
+            IndexCapture::Empty | IndexCapture::Invalid => None,
+        }
+    }
+}
+
+/// a hasher that records the value written by a type that hashes as a single non-negative
+/// integer.
+enum IndexCapture {
+    Empty,
+    Index(usize),
+    Invalid,
+}
+
+impl IndexCapture {
+    #[inline]
+    fn record(&mut self, value: u64) {
+        *self = match (&self, usize::try_from(value)) {
+            (IndexCapture::Empty, Ok(index)) => IndexCapture::Index(index),
+            _ => IndexCapture::Invalid,
+        };
+    }
+
+    #[inline]
+    fn record_signed(&mut self, value: i64) {
+        match u64::try_from(value) {
+            Ok(value) => self.record(value),
+            Err(_) => *self = IndexCapture::Invalid,
+        }
+    }
+}
+
+impl Hasher for IndexCapture {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {
+        *self = IndexCapture::Invalid;
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.record(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.record(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.record(i.into());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.record(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.record(i as u64);
+    }
+
+    fn write_i8(&mut self, i: i8) {
+        self.record_signed(i.into());
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.record_signed(i.into());
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.record_signed(i.into());
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.record_signed(i);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.record_signed(i as i64);
+    }
+}
+
+/// how far beyond the node count compact IDs may reach before a vector indexed by them
+/// would waste more memory than hashing saves.
+fn compact_limit(node_count: usize) -> usize {
+    node_count.saturating_mul(4).saturating_add(64)
+}
+
+/// the set of nodes a traversal has reached: a vector of slots for compact IDs below the limit,
+/// each holding the first ID with that index, and a hash set for all others.
+pub(crate) struct VisitedSet<Id> {
+    slots: Vec<Option<Id>>,
+    others: NodeSet<Id>,
+}
+
+impl<Id: Hash + Eq + Copy> VisitedSet<Id> {
+    /// an empty set sized for a graph with `node_count` nodes.
+    pub(crate) fn new(node_count: usize) -> Self {
+        Self {
+            slots: vec![None; compact_limit(node_count)],
+            others: NodeSet::default(),
+        }
+    }
+
+    /// adds a node, returning whether it was not in the set yet.
+    #[inline]
+    pub(crate) fn insert(&mut self, id: Id) -> bool {
+        match id
+            .compact_index()
+            .and_then(|index| self.slots.get_mut(index))
+        {
+            Some(slot @ None) => {
+                *slot = Some(id);
+                true
+            }
+            Some(Some(owner)) if *owner == id => false,
+            // a slot is never emptied, so an ID sharing it with another one is always hashed
+            _ => self.others.insert(id),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, id: &Id) -> bool {
+        match id.compact_index().and_then(|index| self.slots.get(index)) {
+            Some(None) => false,
+            Some(Some(owner)) if owner == id => true,
+            _ => self.others.contains(id),
+        }
+    }
+}
+
+/// the positions of the nodes in a list of IDs: a vector indexed by the ID if all of them are
+/// compact, below the limit and with distinct indices, and a hash map otherwise.
+enum PositionIndex<'a, Id> {
+    Dense {
+        positions: Vec<usize>,
+        nodes: &'a [Id],
+    },
+    Hashed(NodeMap<Id, usize>),
+}
+
+impl<'a, Id: Hash + Eq + Copy> PositionIndex<'a, Id> {
+    fn new(nodes: &'a [Id]) -> Self {
+        let limit = compact_limit(nodes.len());
+        let mut positions = Vec::new();
+        for (position, id) in nodes.iter().enumerate() {
+            let Some(index) = id.compact_index().filter(|index| *index < limit) else {
+                return Self::hashed(nodes);
+            };
+            if positions.len() <= index {
+                positions.resize(index + 1, usize::MAX);
+            }
+            if positions[index] != usize::MAX {
+                return Self::hashed(nodes);
+            }
+            positions[index] = position;
+        }
+        PositionIndex::Dense { positions, nodes }
+    }
+
+    fn hashed(nodes: &[Id]) -> Self {
+        PositionIndex::Hashed(nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect())
+    }
+
+    #[inline]
+    fn get(&self, id: &Id) -> Option<usize> {
+        match self {
+            // an ID outside the list can share its index with one in it
+            PositionIndex::Dense { positions, nodes } => id
+                .compact_index()
+                .and_then(|index| positions.get(index))
+                .copied()
+                .filter(|position| nodes.get(*position) == Some(id)),
+            PositionIndex::Hashed(positions) => positions.get(id).copied(),
+        }
+    }
+}
+
+/// the node IDs in the order of `node_ids` and the successors of every node by position among
+/// them, so that algorithms over any representation can work on plain vectors.
+pub(crate) fn index_successors<G: Neighbors>(graph: &G) -> (Vec<G::Id>, Vec<Vec<usize>>) {
+    let nodes: Vec<G::Id> = graph.node_ids().collect();
+    let index = PositionIndex::new(&nodes);
+    let adjacency = nodes
+        .iter()
+        .map(|id| {
+            graph
+                .successors(*id)
+                .filter_map(|to| index.get(&to))
+                .collect()
+        })
+        .collect();
+    (nodes, adjacency)
+}
+
+/// Breadth-first hop distances from `start` to every node reachable from it.
+/// Returns an empty map if `start` is not in the graph.
+pub fn bfs_distances<G: Neighbors>(graph: &G, start: G::Id) -> NodeMap<G::Id, usize> {
+    let mut distances = NodeMap::default();
+    if !graph.contains_node(start) {
+        return distances;
+    }
+
+    let mut discovered = VisitedSet::new(graph.node_count());
+    let mut queue = VecDeque::from([(start, 0)]);
+    discovered.insert(start);
+    while let Some((node, distance)) = queue.pop_front() {
+        distances.insert(node, distance);
+        for neighbor in graph.successors(node) {
+            if discovered.insert(neighbor) {
+                queue.push_back((neighbor, distance + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// A path with the fewest edges from `start` to `target`, including both ends.
+/// Returns `None` if `target` cannot be reached or either node is not in the graph.
+pub fn bfs_path<G: Neighbors>(graph: &G, start: G::Id, target: G::Id) -> Option<Vec<G::Id>> {
+    if !graph.contains_node(start) || !graph.contains_node(target) {
+        return None;
+    }
+
+    let mut parents = NodeMap::default();
+    let mut queue = VecDeque::from([start]);
+    parents.insert(start, start);
+    while let Some(node) = queue.pop_front() {
+        if node == target {
+            let mut path = vec![target];
+            let mut current = target;
+            while current != start {
+                current = parents[&current];
+                path.push(current);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for neighbor in graph.successors(node) {
+            if let Entry::Vacant(entry) = parents.entry(neighbor) {
+                entry.insert(node);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    None
+}
+
+/// A node reached by a [`Bfs`] or [`Dfs`] traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Visit<Id> {
+    /// The node.
+    pub id: Id,
+    /// The depth of the node in the traversal tree; for a breadth-first traversal, its hop
+    /// distance from the start.
+    pub depth: usize,
+    /// The node it was reached from, or `None` for the start.
+    pub parent: Option<Id>,
+}
+
+/// A lazy breadth-first traversal, yielding every node reachable from the start once, in the
+/// order they are discovered.
+pub struct Bfs<'a, G: Neighbors> {
+    graph: &'a G,
+    queue: VecDeque<Visit<G::Id>>,
+    discovered: VisitedSet<G::Id>,
+}
+
+impl<'a, G: Neighbors> Bfs<'a, G> {
+    /// Starts a traversal at `start`; it yields nothing if `start` is not in the graph.
+    pub fn new(graph: &'a G, start: G::Id) -> Self {
+        let mut bfs = Self {
+            graph,
+            queue: VecDeque::new(),
+            discovered: VisitedSet::new(graph.node_count()),
+        };
+        if graph.contains_node(start) {
+            bfs.discovered.insert(start);
+            bfs.queue.push_back(Visit {
+                id: start,
+                depth: 0,
+                parent: None,
+            });
+        }
+        bfs
+    }
+}
+
+impl<G: Neighbors> Iterator for Bfs<'_, G> {
+    type Item = Visit<G::Id>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.queue.pop_front()?;
+        for neighbor in self.graph.successors(visit.id) {
+            if self.discovered.insert(neighbor) {
+                self.queue.push_back(Visit {
+                    id: neighbor,
+                    depth: visit.depth + 1,
+                    parent: Some(visit.id),
+                });
+            }
+        }
+        Some(visit)
+    }
+}
+
+/// A lazy depth-first traversal, yielding every node reachable from the start once, in
+/// preorder: the order in which a recursive depth-first search that follows the neighbors in
+/// their stored order enters them.
+pub struct Dfs<'a, G: Neighbors> {
+    graph: &'a G,
+    stack: Vec<Visit<G::Id>>,
+    visited: VisitedSet<G::Id>,
+}
+
+impl<'a, G: Neighbors> Dfs<'a, G> {
+    /// Starts a traversal at `start`; it yields nothing if `start` is not in the graph.
+    pub fn new(graph: &'a G, start: G::Id) -> Self {
+        let mut stack = Vec::new();
+        if graph.contains_node(start) {
+            stack.push(Visit {
+                id: start,
+                depth: 0,
+                parent: None,
+            });
+        }
+        Self {
+            graph,
+            stack,
+            visited: VisitedSet::new(graph.node_count()),
+        }
+    }
+}
+
+impl<G: Neighbors> Iterator for Dfs<'_, G> {
+    type Item = Visit<G::Id>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // a node can be on the stack several times, only its topmost copy is entered
+        let visit = loop {
+            let visit = self.stack.pop()?;
+            if self.visited.insert(visit.id) {
+                break visit;
+            }
+        };
+        let start = self.stack.len();
+        for neighbor in self.graph.successors(visit.id) {
+            if !self.visited.contains(&neighbor) {
+                self.stack.push(Visit {
+                    id: neighbor,
+                    depth: visit.depth + 1,
+                    parent: Some(visit.id),
+                });
+            }
+        }
+        // the first neighbor has to be on top
+        self.stack[start..].reverse();
+        Some(visit)
+    }
+}
+
+/// The source closest to a node, as found by `multi_source_bfs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Nearest<Id> {
+    /// The closest source; on ties, the one given first.
+    pub source: Id,
+    /// The number of hops from the source to the node.
+    pub distance: usize,
+}
+
+/// One breadth-first search from several sources at once: for every node reachable from any
+/// source, the closest source and its hop distance. Grouping the nodes by source gives the graph
+/// Voronoi cells of the sources. Sources that are not in the graph are ignored.
+pub fn multi_source_bfs<G: Neighbors>(
+    graph: &G,
+    sources: impl IntoIterator<Item = G::Id>,
+) -> NodeMap<G::Id, Nearest<G::Id>> {
+    let mut nearest = NodeMap::default();
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if graph.contains_node(source) {
+            if let Entry::Vacant(entry) = nearest.entry(source) {
+                entry.insert(Nearest {
+                    source,
+                    distance: 0,
+                });
+                queue.push_back(source);
+            }
+        }
+    }
+    // the queue holds every level grouped by source in the order the sources were given, so
+    // ties go to the earlier source
+    while let Some(node) = queue.pop_front() {
+        let Nearest { source, distance } = nearest[&node];
+        for neighbor in graph.successors(node) {
+            if let Entry::Vacant(entry) = nearest.entry(neighbor) {
+                entry.insert(Nearest {
+                    source,
+                    distance: distance + 1,
+                });
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    nearest
+}
+
+/// A shortest path from `start` to `end` and its length, with the edge weights as the lengths
+/// of the edges, found with Dijkstra's algorithm. Edges without a weight are not followed and
+/// weights must not be negative. Returns `None` if `end` cannot be reached or either node is not
+/// in the graph. `Graph::shortest_path_dijkstra` is the same search on a graph.
+///
+/// # Example
+///
+/// ```
+/// use grafferous::{view::GraphView, visit::shortest_path, Graph};
+///
+/// let mut g = Graph::<u32, (), u32>::new();
+/// g.add_directed_edge_with_data(0, 1, 1);
+/// g.add_directed_edge_with_data(1, 2, 1);
+/// g.add_directed_edge_with_data(0, 2, 5);
+/// assert_eq!(shortest_path(&g, 0, 2), Some((2, vec![0, 1, 2])));
+///
+/// let detour = GraphView::new(&g).filter_nodes(|id| id != 1);
+/// assert_eq!(shortest_path(&detour, 0, 2), Some((5, vec![0, 2])));
+/// ```
+pub fn shortest_path<G: EdgeWeights>(
+    graph: &G,
+    start: G::Id,
+    end: G::Id,
+) -> Option<(G::Weight, Vec<G::Id>)>
+where
+    G::Weight: Copy + PartialOrd + Add<Output = G::Weight> + Default,
+{
+    shortest_path::dijkstra_path(graph, start, end, |from, to| {
+        graph.edge_weight(from, to).copied()
+    })
+}
+
+/// The length of a shortest path from `start` to every node it reaches, including `start`
+/// itself, with the edge weights as the lengths of the edges, see `shortest_path`. The map is
+/// empty if `start` is not in the graph.
+pub fn shortest_path_lengths<G: EdgeWeights>(graph: &G, start: G::Id) -> NodeMap<G::Id, G::Weight>
+where
+    G::Weight: Copy + PartialOrd + Add<Output = G::Weight> + Default,
+{
+    shortest_path::dijkstra(graph, start, None, |from, to| {
+        graph.edge_weight(from, to).copied()
+    })
+    .0
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// A path with the fewest edges from `start` to `end`, including both ends, see
+    /// [`bfs_path`]. Returns `None` if `end` cannot be reached or either node does not exist.
+    /// Use `shortest_path_dijkstra` for weighted edges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_grid_graph;
+    ///
+    /// let g = generate_grid_graph::<()>(4, 4);
+    /// let path = g.shortest_path((0, 0), (3, 2)).unwrap();
+    /// assert_eq!(path.len(), 6);
+    /// assert_eq!(g.distances_from((0, 0))[&(3, 3)], 6);
+    /// ```
+    pub fn shortest_path(&self, start: IDDataType, end: IDDataType) -> Option<Vec<IDDataType>> {
+        let core = self.core();
+        let path = bfs_path(core, core.index_of(&start)?, core.index_of(&end)?)?;
+        Some(core.ids_of(path))
+    }
+
+    /// The hop distance from `start` to every node it reaches, including `start` itself, see
+    /// [`bfs_distances`]. The map is empty if `start` does not exist.
+    pub fn distances_from(&self, start: IDDataType) -> NodeMap<IDDataType, usize> {
+        self.core().distances_from(start)
+    }
+
+    /// The nodes reachable from `start` in breadth-first order, starting with `start`. The
+    /// traversal is lazy and follows the neighbors in their stored order; it is empty if `start`
+    /// does not exist. See [`Bfs`] for the depth and parent of every node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3").unwrap();
+    /// assert_eq!(g.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    /// assert_eq!(g.dfs(0).collect::<Vec<_>>(), vec![0, 1, 3, 2]);
+    /// assert_eq!(g.bfs(1).collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    pub fn bfs(&self, start: IDDataType) -> impl Iterator<Item = IDDataType> + '_ {
+        traverse_core(self.core(), start, Bfs::new).map(|visit| visit.id)
+    }
+
+    /// Like `bfs`, but yields every node with its hop distance from `start`.
+    pub fn bfs_with_depth(
+        &self,
+        start: IDDataType,
+    ) -> impl Iterator<Item = (IDDataType, usize)> + '_ {
+        traverse_core(self.core(), start, Bfs::new).map(|visit| (visit.id, visit.depth))
+    }
+
+    /// Like `bfs`, but yields every node with the node it was discovered from, `None` for
+    /// `start`. Following the parents leads back to `start` along a path with the fewest edges.
+    pub fn bfs_with_parent(
+        &self,
+        start: IDDataType,
+    ) -> impl Iterator<Item = (IDDataType, Option<IDDataType>)> + '_ {
+        traverse_core(self.core(), start, Bfs::new).map(|visit| (visit.id, visit.parent))
+    }
+
+    /// The nodes reachable from `start` in depth-first preorder, starting with `start`. The
+    /// traversal is lazy and follows the neighbors in their stored order; it is empty if `start`
+    /// does not exist. See [`Dfs`] for the depth and parent of every node.
+    pub fn dfs(&self, start: IDDataType) -> impl Iterator<Item = IDDataType> + '_ {
+        traverse_core(self.core(), start, Dfs::new).map(|visit| visit.id)
+    }
+
+    /// Like `dfs`, but yields every node with its depth in the depth-first tree.
+    pub fn dfs_with_depth(
+        &self,
+        start: IDDataType,
+    ) -> impl Iterator<Item = (IDDataType, usize)> + '_ {
+        traverse_core(self.core(), start, Dfs::new).map(|visit| (visit.id, visit.depth))
+    }
+
+    /// Like `dfs`, but yields every node with its parent in the depth-first tree, `None` for
+    /// `start`.
+    pub fn dfs_with_parent(
+        &self,
+        start: IDDataType,
+    ) -> impl Iterator<Item = (IDDataType, Option<IDDataType>)> + '_ {
+        traverse_core(self.core(), start, Dfs::new).map(|visit| (visit.id, visit.parent))
+    }
+
+    /// The closest of the given sources for every node that one of them reaches, see
+    /// [`multi_source_bfs`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    ///
+    /// let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3 -- 4 -- 5").unwrap();
+    /// let nearest = g.multi_source_bfs(&[0, 5]);
+    /// assert_eq!(nearest[&2].source, 0);
+    /// assert_eq!(nearest[&3].source, 5);
+    /// assert_eq!(nearest[&3].distance, 2);
+    /// ```
+    pub fn multi_source_bfs(
+        &self,
+        sources: &[IDDataType],
+    ) -> NodeMap<IDDataType, Nearest<IDDataType>> {
+        let core = self.core();
+        let sources = sources.iter().filter_map(|id| core.index_of(id));
+        multi_source_bfs(core, sources)
+            .into_iter()
+            .map(|(slot, nearest)| {
+                let source = core.id_of(nearest.source);
+                let distance = nearest.distance;
+                (core.id_of(slot), Nearest { source, distance })
+            })
+            .collect()
+    }
+
+    /// The graph Voronoi cells of the sources: for each source, in the order given, the nodes that
+    /// are closer to it than to any other source, in the order of `self.nodes`. Nodes that no
+    /// source reaches are left out, and a repeated or missing source gets an empty cell.
+    pub fn voronoi_cells(&self, sources: &[IDDataType]) -> Vec<Vec<IDDataType>> {
+        let nearest = self.multi_source_bfs(sources);
+        let mut cell_of: NodeMap<IDDataType, usize> = NodeMap::default();
+        for (i, source) in sources.iter().enumerate() {
+            cell_of.entry(*source).or_insert(i);
+        }
+        let mut cells = vec![Vec::new(); sources.len()];
+        for id in &self.nodes {
+            if let Some(found) = nearest.get(id) {
+                cells[cell_of[&found.source]].push(*id);
+            }
+        }
+        cells
+    }
+}
+
+/// a traversal started by `traversal` on the core of a graph, with its slots translated back to
+/// node IDs. It is empty if `start` is not in the graph.
+fn traverse_core<'a, IDDataType, T>(
+    core: &'a CompactGraph<IDDataType>,
+    start: IDDataType,
+    traversal: impl FnOnce(&'a CompactGraph<IDDataType>, u32) -> T,
+) -> impl Iterator<Item = Visit<IDDataType>> + 'a
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    T: Iterator<Item = Visit<u32>> + 'a,
+{
+    core.index_of(&start)
+        .map(|start| traversal(core, start))
+        .into_iter()
+        .flatten()
+        .map(|visit| Visit {
+            id: core.id_of(visit.id),
+            depth: visit.depth,
+            parent: visit.parent.map(|parent| core.id_of(parent)),
+        })
+}
@@ -0,0 +1,353 @@
+//! Random walks and quantities estimated by sampling them.
+
+use alloc::vec::{self, Vec};
+use core::{fmt::Debug, hash::Hash};
+use rand::Rng;
+
+use crate::{collections::NodeMap, Graph};
+
+/// An iterator over the nodes visited by a random walk, created by `Graph::random_walk`
+/// or `Graph::weighted_random_walk`.
+///
+/// The first item is the start node. Every further item is an outgoing neighbor of the previous
+/// one, so the walk only ends when it reaches a node without outgoing edges (or, for weighted
+/// walks, whose outgoing edges all have zero weight). Use `take(steps + 1)` for a walk of at
+/// most `steps` steps.
+///
+/// With [`RandomWalk::with_restart`], the walk instead jumps back to the start node with a fixed
+/// probability in every step and whenever it gets stuck, so it never ends.
+pub struct RandomWalk<
+    'a,
+    IDDataType,
+    NodeDataType,
+    R,
+    EdgeDataType = (),
+    W = fn(IDDataType, IDDataType) -> f64,
+> where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    graph: &'a Graph<IDDataType, NodeDataType, EdgeDataType>,
+    current: Option<IDDataType>,
+    started: bool,
+    rng: R,
+    weight: Option<W>,
+    start: IDDataType,
+    restart: f64,
+}
+
+impl<IDDataType, NodeDataType, R, W, EdgeDataType>
+    RandomWalk<'_, IDDataType, NodeDataType, R, EdgeDataType, W>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Makes the walk jump back to its start node with probability `probability` in every step
+    /// instead of moving on, and whenever it reaches a node it cannot leave. In the long run such
+    /// a walk visits the nodes in proportion to `Graph::personalized_pagerank` with the start node
+    /// as the seed and `probability` as `alpha`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::Graph;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// // the walk gets stuck at 2, but always restarts from 0
+    /// let g = Graph::<u32, ()>::parse("0 -> 1 -> 2").unwrap();
+    /// let walk: Vec<u32> = g
+    ///     .random_walk(0, StdRng::seed_from_u64(1))
+    ///     .with_restart(0.2)
+    ///     .take(100)
+    ///     .collect();
+    /// assert_eq!(walk.len(), 100);
+    /// assert!(walk.windows(2).all(|step| step[1] == 0 || step[1] == step[0] + 1));
+    /// ```
+    pub fn with_restart(mut self, probability: f64) -> Self {
+        self.restart = probability;
+        self
+    }
+}
+
+impl<IDDataType, NodeDataType, R, W, EdgeDataType> Iterator
+    for RandomWalk<'_, IDDataType, NodeDataType, R, EdgeDataType, W>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    R: Rng,
+    W: FnMut(IDDataType, IDDataType) -> f64,
+{
+    type Item = IDDataType;
+
+    fn next(&mut self) -> Option<IDDataType> {
+        let current = self.current?;
+        if !self.started {
+            self.started = true;
+            return Some(current);
+        }
+        if self.restart > 0.0 && self.rng.gen::<f64>() < self.restart {
+            self.current = Some(self.start);
+            return self.current;
+        }
+
+        let neighbors = self.graph.edges.get(&current).map_or(&[][..], |n| &n[..]);
+        self.current = match &mut self.weight {
+            None if neighbors.is_empty() => None,
+            None => Some(neighbors[self.rng.gen_range(0..neighbors.len())]),
+            Some(weight) => {
+                let weights: Vec<f64> = neighbors
+                    .iter()
+                    .map(|to| weight(current, *to).max(0.0))
+                    .collect();
+                let total: f64 = weights.iter().sum();
+                if total > 0.0 {
+                    let mut target = self.rng.gen::<f64>() * total;
+                    let mut chosen = neighbors.len() - 1;
+                    for (i, w) in weights.iter().enumerate() {
+                        if target < *w {
+                            chosen = i;
+                            break;
+                        }
+                        target -= w;
+                    }
+                    Some(neighbors[chosen])
+                } else {
+                    None
+                }
+            }
+        };
+        if self.current.is_none() && self.restart > 0.0 {
+            self.current = Some(self.start);
+        }
+        self.current
+    }
+}
+
+/// An iterator over random walks from a sequence of start nodes, created by
+/// `Graph::random_walks`. Every item is one walk of at most `steps` steps, collected into a
+/// vector, and all walks share the same source of randomness.
+pub struct RandomWalks<'a, IDDataType, NodeDataType, R, EdgeDataType = ()>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    graph: &'a Graph<IDDataType, NodeDataType, EdgeDataType>,
+    starts: vec::IntoIter<IDDataType>,
+    steps: usize,
+    rng: R,
+    restart: f64,
+}
+
+impl<IDDataType, NodeDataType, R, EdgeDataType>
+    RandomWalks<'_, IDDataType, NodeDataType, R, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Makes every walk restart at its start node with probability `probability` in every step,
+    /// as with [`RandomWalk::with_restart`].
+    pub fn with_restart(mut self, probability: f64) -> Self {
+        self.restart = probability;
+        self
+    }
+}
+
+impl<IDDataType, NodeDataType, R, EdgeDataType> Iterator
+    for RandomWalks<'_, IDDataType, NodeDataType, R, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    R: Rng,
+{
+    type Item = Vec<IDDataType>;
+
+    fn next(&mut self) -> Option<Vec<IDDataType>> {
+        let start = self.starts.next()?;
+        Some(
+            self.graph
+                .random_walk(start, &mut self.rng)
+                .with_restart(self.restart)
+                .take(self.steps + 1)
+                .collect(),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.starts.size_hint()
+    }
+}
+
+impl<IDDataType, NodeDataType, EdgeDataType> Graph<IDDataType, NodeDataType, EdgeDataType>
+where
+    IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+{
+    /// Starts a random walk that moves to a uniformly chosen outgoing neighbor in every step.
+    /// Parallel edges make a neighbor proportionally more likely.
+    ///
+    /// If `start` is not in the graph the walk is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The node the walk starts at; it is the first item of the iterator.
+    /// * `rng` - The source of randomness, e.g. `&mut rng` for a seeded RNG.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_cycle_graph;
+    ///
+    /// let g = generate_cycle_graph::<()>(10);
+    /// let walk: Vec<usize> = g.random_walk(0, rand::thread_rng()).take(5).collect();
+    ///
+    /// assert_eq!(walk[0], 0);
+    /// assert!(walk.windows(2).all(|step| g.neighbors(step[0]).contains(&step[1])));
+    /// ```
+    pub fn random_walk<R: Rng>(
+        &self,
+        start: IDDataType,
+        rng: R,
+    ) -> RandomWalk<'_, IDDataType, NodeDataType, R, EdgeDataType> {
+        RandomWalk {
+            graph: self,
+            current: self.node_data.contains_key(&start).then_some(start),
+            started: false,
+            rng,
+            weight: None,
+            start,
+            restart: 0.0,
+        }
+    }
+
+    /// Starts a random walk that moves from `from` to the outgoing neighbor `to` with probability
+    /// proportional to `weight(from, to)`. Negative weights are treated as zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The node the walk starts at; it is the first item of the iterator.
+    /// * `rng` - The source of randomness.
+    /// * `weight` - The weight of the edge `(from, to)`.
+    ///
+    pub fn weighted_random_walk<R, W>(
+        &self,
+        start: IDDataType,
+        rng: R,
+        weight: W,
+    ) -> RandomWalk<'_, IDDataType, NodeDataType, R, EdgeDataType, W>
+    where
+        R: Rng,
+        W: FnMut(IDDataType, IDDataType) -> f64,
+    {
+        RandomWalk {
+            graph: self,
+            current: self.node_data.contains_key(&start).then_some(start),
+            started: false,
+            rng,
+            weight: Some(weight),
+            start,
+            restart: 0.0,
+        }
+    }
+
+    /// Random walks of at most `steps` steps from each of `starts` in turn, one item per start,
+    /// for example to sample many walks per node for node embeddings. A walk from a node that is
+    /// not in the graph is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `starts` - The nodes the walks start at, in the order the walks are produced.
+    /// * `steps` - The number of steps of every walk; a walk that gets stuck is shorter.
+    /// * `rng` - The source of randomness shared by all walks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use grafferous::generate_cycle_graph;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let g = generate_cycle_graph::<()>(10);
+    /// let starts = g.nodes.iter().flat_map(|id| [*id; 3]);
+    /// let walks: Vec<Vec<usize>> = g.random_walks(starts, 4, StdRng::seed_from_u64(7)).collect();
+    /// assert_eq!(walks.len(), 30);
+    /// assert!(walks.iter().all(|walk| walk.len() == 5));
+    ///
+    /// // the same seed gives the same walks
+    /// let again: Vec<Vec<usize>> = g
+    ///     .random_walks(g.nodes.iter().flat_map(|id| [*id; 3]), 4, StdRng::seed_from_u64(7))
+    ///     .collect();
+    /// assert_eq!(walks, again);
+    /// ```
+    pub fn random_walks<R: Rng>(
+        &self,
+        starts: impl IntoIterator<Item = IDDataType>,
+        steps: usize,
+        rng: R,
+    ) -> RandomWalks<'_, IDDataType, NodeDataType, R, EdgeDataType> {
+        RandomWalks {
+            graph: self,
+            starts: starts.into_iter().collect::<Vec<_>>().into_iter(),
+            steps,
+            rng,
+            restart: 0.0,
+        }
+    }
+
+    /// Estimates the expected number of steps a random walk from `start` needs to first reach `target`.
+    ///
+    /// Walks that have not reached `target` after `max_steps` steps, or that get stuck, are discarded,
+    /// so the estimate is biased low when many walks are cut off. Returns `None` if no walk reached `target`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The node the walks start at.
+    /// * `target` - The node to reach.
+    /// * `walks` - The number of walks to sample.
+    /// * `max_steps` - The length after which a walk is abandoned.
+    /// * `rng` - The source of randomness.
+    ///
+    pub fn estimate_hitting_time<R: Rng>(
+        &self,
+        start: IDDataType,
+        target: IDDataType,
+        walks: usize,
+        max_steps: usize,
+        rng: &mut R,
+    ) -> Option<f64> {
+        let mut hits = 0;
+        let mut total_steps = 0;
+        for _ in 0..walks {
+            if let Some(steps) = self
+                .random_walk(start, &mut *rng)
+                .take(max_steps + 1)
+                .position(|node| node == target)
+            {
+                hits += 1;
+                total_steps += steps;
+            }
+        }
+        (hits > 0).then(|| total_steps as f64 / hits as f64)
+    }
+
+    /// Estimates the stationary distribution of the random walk from the fraction of time
+    /// a single long walk spends at each node.
+    ///
+    /// The estimate is only meaningful if the walk can reach every node and does not get stuck,
+    /// i.e. for strongly connected graphs. Nodes that were never visited are missing from the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The node the walk starts at.
+    /// * `steps` - The number of steps to walk.
+    /// * `rng` - The source of randomness.
+    ///
+    pub fn estimate_stationary_distribution<R: Rng>(
+        &self,
+        start: IDDataType,
+        steps: usize,
+        rng: &mut R,
+    ) -> NodeMap<IDDataType, f64> {
+        let mut visits: NodeMap<IDDataType, usize> = NodeMap::default();
+        let mut total = 0;
+        for node in self.random_walk(start, rng).skip(1).take(steps) {
+            *visits.entry(node).or_default() += 1;
+            total += 1;
+        }
+        visits
+            .into_iter()
+            .map(|(node, count)| (node, count as f64 / total as f64))
+            .collect()
+    }
+}
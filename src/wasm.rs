@@ -0,0 +1,198 @@
+//! JavaScript bindings built with `wasm-bindgen`, behind the `wasm` feature.
+//!
+//! Build with `wasm-pack build bindings/wasm` and use the exported `Graph` class:
+//!
+//! ```js
+//! import { Graph } from "grafferous-wasm";
+//!
+//! const g = Graph.grid(10, 10);
+//! g.shortestPath(0, 99);  // Uint32Array of node IDs
+//! JSON.parse(g.toJson()); // { nodes: [{ id, label }], edges: [[from, to]] }
+//! ```
+//!
+//! Node IDs are `u32` and every node carries an optional string label.
+
+use core::hash::Hash;
+use std::fmt::{Debug, Write};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    generate_cycle_graph, generate_grid_graph, generate_random_graph,
+    visit::{bfs_distances, bfs_path},
+    Graph,
+};
+
+/// A graph with `u32` node IDs and string labels, exported to JavaScript as `Graph`.
+#[wasm_bindgen(js_name = Graph)]
+#[derive(Debug, Clone, Default)]
+pub struct WasmGraph {
+    graph: Graph<u32, String>,
+}
+
+#[wasm_bindgen(js_class = Graph)]
+impl WasmGraph {
+    /// Creates a new, empty graph.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A `width` by `height` grid; the node at column `x` and row `y` has the ID `y * width + x`.
+    pub fn grid(width: u32, height: u32) -> Self {
+        let grid = generate_grid_graph::<String>(width as usize, height as usize);
+        Self::relabel(&grid, |&(x, y)| (y * width as usize + x) as u32)
+    }
+
+    /// A cycle of `n` nodes with IDs `0..n`.
+    pub fn cycle(n: u32) -> Self {
+        Self::relabel(&generate_cycle_graph::<String>(n as usize), |&i| i as u32)
+    }
+
+    /// A random directed graph of `n` nodes in which every edge exists with probability `p`.
+    pub fn random(n: u32, p: f64) -> Self {
+        Self::relabel(&generate_random_graph::<String>(n as usize, p), |&i| {
+            i as u32
+        })
+    }
+
+    /// Adds a node, or sets the label of an existing one.
+    #[wasm_bindgen(js_name = addNode)]
+    pub fn add_node(&mut self, id: u32, label: Option<String>) {
+        let label = label.unwrap_or_default();
+        match self.graph.node_data.get_mut(&id) {
+            Some(data) => *data = label,
+            None => self.graph.add_node_with_data(id, label),
+        }
+    }
+
+    /// Adds an edge in both directions, adding missing nodes without a label.
+    #[wasm_bindgen(js_name = addEdge)]
+    pub fn add_edge(&mut self, from: u32, to: u32) {
+        self.graph.add_edge(from, to);
+    }
+
+    /// Adds an edge from `from` to `to`, adding missing nodes without a label.
+    #[wasm_bindgen(js_name = addDirectedEdge)]
+    pub fn add_directed_edge(&mut self, from: u32, to: u32) {
+        self.graph.add_directed_edge(from, to);
+    }
+
+    /// The number of nodes.
+    #[wasm_bindgen(js_name = nodeCount)]
+    pub fn node_count(&self) -> usize {
+        self.graph.nodes.len()
+    }
+
+    /// The number of directed edges; an edge added with `addEdge` counts twice.
+    #[wasm_bindgen(js_name = edgeCount)]
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// The IDs of all nodes, in insertion order.
+    pub fn nodes(&self) -> Vec<u32> {
+        self.graph.nodes.clone()
+    }
+
+    /// The label of a node, if it exists.
+    pub fn label(&self, id: u32) -> Option<String> {
+        self.graph.node_data.get(&id).cloned()
+    }
+
+    /// The outgoing neighbors of a node.
+    pub fn neighbors(&self, id: u32) -> Vec<u32> {
+        self.graph.neighbors(id).to_vec()
+    }
+
+    /// The nodes reachable from `start` in breadth-first order, starting with `start`.
+    pub fn bfs(&self, start: u32) -> Vec<u32> {
+        let mut reached: Vec<(u32, usize)> =
+            bfs_distances(&self.graph, start).into_iter().collect();
+        reached.sort_by_key(|&(id, distance)| (distance, id));
+        reached.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// The number of edges on a shortest path, or `undefined` if `to` cannot be reached.
+    pub fn distance(&self, from: u32, to: u32) -> Option<usize> {
+        bfs_distances(&self.graph, from).get(&to).copied()
+    }
+
+    /// A path with the fewest edges from `from` to `to`, or `undefined` if there is none.
+    #[wasm_bindgen(js_name = shortestPath)]
+    pub fn shortest_path(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        bfs_path(&self.graph, from, to)
+    }
+
+    /// The graph as JSON: `{"nodes": [{"id": 0, "label": "a"}, ...], "edges": [[0, 1], ...]}`,
+    /// with nodes in insertion order.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"nodes\":[");
+        for (i, id) in self.graph.nodes.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            // writing to a String cannot fail
+            let _ = write!(
+                json,
+                "{separator}{{\"id\":{id},\"label\":\"{}\"}}",
+                escape_json(&self.graph.node_data[id])
+            );
+        }
+        json.push_str("],\"edges\":[");
+        let edges = self.graph.nodes.iter().flat_map(|from| {
+            self.graph
+                .neighbors(*from)
+                .iter()
+                .map(move |to| (*from, *to))
+        });
+        for (i, (from, to)) in edges.enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(json, "{separator}[{from},{to}]");
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+impl WasmGraph {
+    /// copies the topology of a generated graph, mapping its IDs with `id`, in order of the new IDs.
+    fn relabel<IDDataType, NodeDataType>(
+        graph: &Graph<IDDataType, NodeDataType>,
+        id: impl Fn(&IDDataType) -> u32,
+    ) -> Self
+    where
+        IDDataType: Debug + PartialEq + Eq + Hash + Clone + Copy,
+    {
+        // generators fill their nodes in hash order, so sort them for stable IDs
+        let mut nodes: Vec<&IDDataType> = graph.nodes.iter().collect();
+        nodes.sort_by_key(|node| id(node));
+        let mut relabeled = Self::new();
+        for from in &nodes {
+            relabeled.add_node(id(from), None);
+        }
+        for from in nodes {
+            for to in graph.edges.get(from).into_iter().flatten() {
+                relabeled.add_directed_edge(id(from), id(to));
+            }
+        }
+        relabeled
+    }
+}
+
+/// escapes a string for use inside a JSON string literal.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -43,6 +43,19 @@ fn test_count_paths() {
     assert_eq!(count_paths(&g, &6, &6, None), 1);
 }
 
+// the trivial path from a node to itself counts once, whatever the depth and cycles
+#[test]
+fn test_count_paths_trivial_path() {
+    let dag = Graph::<u32, ()>::parse("0 -> 1 -> 2").unwrap();
+    assert_eq!(count_paths(&dag, &1, &1, None), 1);
+    assert_eq!(count_paths(&dag, &1, &1, Some(0)), 1);
+    assert_eq!(count_paths(&dag, &0, &1, Some(0)), 0);
+
+    let cycle = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    assert_eq!(count_paths(&cycle, &0, &0, Some(10)), 1);
+    assert_eq!(count_paths(&cycle, &0, &2, Some(10)), 1);
+}
+
 //test count paths fails on cycles
 #[test]
 #[should_panic]
@@ -86,6 +99,80 @@ fn test_graph_macro() {
 
     assert_eq!(g.nodes.len(), 7);
     assert_eq!(g.edge_tuples().len(), 12);
+
+    let name = "middle";
+    let g: Graph<i32, String> = grafferous::graph! {
+        0:{"first".to_string()} => 1,
+        1:{name.to_string()} => 2,
+        (1 + 1) ; 3:{"last".to_string()},
+    };
+
+    assert_eq!(g.nodes, vec![0, 1, 2, 3]);
+    assert_eq!(g.node_data[&1], "middle");
+    assert_eq!(g.node_data[&2], "");
+    assert_eq!(g.node_data[&3], "last");
+    assert_eq!(g.edge_tuples().len(), 4);
+
+    let (g, weights): (Graph<i32, ()>, _) = grafferous::graph! {
+        0 -(3.5)-> 1,
+        1 => 2,
+        2 -(-1.0)-> 0
+    };
+
+    assert_eq!(g.edge_tuples().len(), 3);
+    assert_eq!(weights.len(), 2);
+    assert_eq!(weights[&(0, 1)], 3.5);
+    assert_eq!(weights[&(2, 0)], -1.0);
+
+    let g: Graph<i32, &str> = grafferous::graph! {
+        0 => 1 => 2:{"junction"} => 3,
+        2 ; 4 ; 5,
+        5 => 0
+    };
+    let mut edges = g.edge_tuples();
+    edges.sort();
+
+    assert_eq!(
+        edges,
+        vec![
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (2, 4),
+            (4, 2),
+            (4, 5),
+            (5, 0),
+            (5, 4)
+        ]
+    );
+    assert_eq!(g.node_data[&2], "junction");
+
+    let (g, weights): (Graph<i32, ()>, _) = grafferous::graph! {
+        0 -(1)-> 1 -(2)-> 2 => 3 ; 0
+    };
+
+    assert_eq!(g.edge_tuples().len(), 5);
+    assert_eq!(weights.len(), 2);
+    assert_eq!(weights[&(1, 2)], 2);
+
+    // node declarations mix with the edges, and data is any single token
+    let label = "hub";
+    let (g, weights): (Graph<i32, &str>, _) = grafferous::graph! {
+        7,
+        0: label -(0.5)> 1: "leaf" ; (2 + 1),
+        8: "isolated",
+        1: "renamed",
+        (2 + 1) -(1.5)-> 0,
+    };
+
+    assert_eq!(g.nodes, vec![7, 0, 1, 3, 8]);
+    assert_eq!(g.node_data[&0], "hub");
+    assert_eq!(g.node_data[&1], "renamed");
+    assert_eq!(g.node_data[&7], "");
+    assert_eq!(g.node_data[&8], "isolated");
+    assert_eq!(g.edge_tuples().len(), 4);
+    assert_eq!(weights[&(0, 1)], 0.5);
+    assert_eq!(weights[&(3, 0)], 1.5);
 }
 
 //random graph test
@@ -94,3 +181,5863 @@ fn test_random_graph() {
     let g = grafferous::generate_random_graph::<u32>(100, 0.1);
     assert_eq!(g.nodes.len(), 100);
 }
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_node_data() {
+    use rayon::prelude::*;
+
+    let mut g = generate_grid_graph::<usize>(20, 20);
+    assert_eq!(g.par_nodes().count(), 400);
+
+    g.par_node_data_mut()
+        .for_each(|(id, data)| *data = id.0 * id.1);
+    let total: usize = g.par_node_data().map(|(_, data)| *data).sum();
+    assert_eq!(total, (0..20).sum::<usize>().pow(2));
+}
+
+#[test]
+fn test_bulk_edge_insertion() {
+    let edges = [(0, 1), (1, 2), (0, 2), (3, 0)];
+
+    let mut bulk = Graph::<usize, u32>::new();
+    bulk.add_directed_edges(&edges);
+    let mut one_by_one = Graph::<usize, u32>::new();
+    for (from, to) in edges {
+        one_by_one.add_directed_edge(from, to);
+    }
+    assert_eq!(bulk, one_by_one);
+    assert_eq!(bulk.nodes, vec![0, 1, 2, 3]);
+
+    let mut undirected = Graph::<usize, u32>::new();
+    undirected.add_edges(&edges);
+    assert!(undirected.is_undirected());
+    assert_eq!(undirected.edge_tuples().len(), 8);
+    assert_eq!(undirected.reverse_neighbors(0)[..], [1, 2, 3]);
+}
+
+#[test]
+fn test_simplify() {
+    let mut g = Graph::<usize, u32>::new();
+    g.add_edge(0, 1);
+    g.add_edge(0, 1);
+    g.add_directed_edge(1, 2);
+    g.add_directed_edge(2, 2);
+
+    let mut keep_loops = g.clone();
+    let removed = keep_loops.simplify(false);
+    assert_eq!(removed.parallel_edges, 2);
+    assert_eq!(removed.self_loops, 0);
+    assert_eq!(keep_loops.edge_tuples().len(), 4);
+
+    let removed = g.simplify(true);
+    assert_eq!(removed.parallel_edges, 2);
+    assert_eq!(removed.self_loops, 1);
+    assert_eq!(g.neighbors(0), vec![1]);
+    assert_eq!(g.reverse_neighbors(1)[..], [0]);
+    assert!(g.reverse_neighbors(2).iter().all(|from| *from == 1));
+}
+
+#[test]
+fn test_memory_usage() {
+    let mut g = Graph::<usize, u64>::new();
+    assert_eq!(g.approx_memory_usage().total(), 0);
+
+    g.nodes.reserve(1000);
+    for i in 0..100 {
+        g.add_edge(i, (i + 1) % 100);
+    }
+    let before = g.approx_memory_usage();
+    assert!(before.nodes >= 1000 * std::mem::size_of::<usize>());
+    assert!(before.adjacency >= 400 * std::mem::size_of::<usize>());
+    assert!(before.data >= 100 * std::mem::size_of::<(usize, u64)>());
+
+    g.shrink_to_fit();
+    let after = g.approx_memory_usage();
+    assert!(after.total() < before.total());
+    assert_eq!(after.nodes, 100 * std::mem::size_of::<usize>());
+}
+
+#[test]
+fn test_is_undirected_cache() {
+    let mut g = Graph::<usize, u32>::new();
+    g.add_edge(0, 1);
+    g.add_edge(1, 2);
+    assert!(g.is_undirected());
+    assert!(g.is_undirected());
+
+    g.add_directed_edge(2, 3);
+    assert!(!g.is_undirected());
+
+    g.add_directed_edge(3, 2);
+    assert!(g.is_undirected());
+
+    g.edges.get_mut(&3).unwrap().clear();
+    g.invalidate_caches();
+    assert!(!g.is_undirected());
+
+    let grid = generate_grid_graph::<u32>(10, 10);
+    assert!(grid.is_undirected());
+}
+
+#[test]
+fn test_compact_graph() {
+    let g = generate_grid_graph::<u32>(8, 5);
+    let compact = g.to_compact();
+    assert_eq!(compact.len(), 40);
+    assert_eq!(compact.edge_count(), g.edge_tuples().len());
+
+    let corner = compact.index_of(&(0, 0)).unwrap();
+    assert_eq!(compact.id_of(corner), (0, 0));
+    assert_eq!(compact.neighbors(corner).len(), 2);
+
+    let distances = compact.distances_from((0, 0));
+    assert_eq!(distances.len(), 40);
+    assert!(distances.iter().all(|(id, d)| *d == id.0 + id.1));
+    assert!(compact.distances_from((100, 100)).is_empty());
+
+    let mut directed = Graph::<char, ()>::new();
+    directed.add_directed_edge('a', 'b');
+    directed.add_directed_edge('c', 'b');
+    let compact = grafferous::CompactGraph::from(&directed);
+    let b = compact.index_of(&'b').unwrap();
+    assert!(compact.neighbors(b).is_empty());
+    assert_eq!(compact.reverse_neighbors(b).len(), 2);
+}
+
+#[test]
+fn test_traversals_follow_changes_to_the_core() {
+    fn shared<T: Send + Sync>(_: &T) {}
+
+    // the traversals run on an index-based core that is rebuilt after every change
+    let mut g = Graph::<(u32, u32), ()>::new();
+    g.add_directed_edge((0, 0), (0, 1));
+    g.add_directed_edge((0, 1), (0, 2));
+    shared(&g);
+    assert_eq!(g.distances_from((0, 0))[&(0, 2)], 2);
+    assert_eq!(g.bfs((0, 0)).count(), 3);
+
+    g.add_directed_edge((0, 0), (0, 2));
+    assert_eq!(g.distances_from((0, 0))[&(0, 2)], 1);
+    g.add_node((5, 5));
+    assert_eq!(g.connected_components().len(), 2);
+    g.remove_node((0, 1));
+    assert_eq!(g.dfs((0, 0)).collect::<Vec<_>>(), vec![(0, 0), (0, 2)]);
+    g.transpose();
+    assert_eq!(g.bfs((0, 0)).count(), 1);
+    assert_eq!(g.shortest_path((0, 2), (0, 0)), Some(vec![(0, 2), (0, 0)]));
+    g.remove_directed_edge((0, 2), (0, 0));
+    assert_eq!(g.shortest_path((0, 2), (0, 0)), None);
+    assert_eq!(g.strongly_connected_components().len(), 3);
+
+    // edges written to the public maps are seen after invalidating the caches
+    g.edges.get_mut(&(0, 2)).unwrap().push((5, 5));
+    g.reverse_edges.get_mut(&(5, 5)).unwrap().push((0, 2));
+    g.invalidate_caches();
+    assert_eq!(g.reachable_from((0, 2)).len(), 2);
+    assert_eq!(g.shortest_path_lengths_by((0, 2), |_, _| 1)[&(5, 5)], 1);
+
+    // a clone starts without a core and both stay correct
+    let copy = g.clone();
+    g.add_directed_edge((5, 5), (9, 9));
+    assert_eq!(copy.bfs((0, 2)).count(), 2);
+    assert_eq!(g.bfs((0, 2)).count(), 3);
+    assert_eq!(g.to_compact().len(), 4);
+}
+
+#[test]
+fn test_observers() {
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut g = Graph::<usize, u32>::new();
+
+    let log = events.clone();
+    g.on_node_added(move |id| log.lock().unwrap().push(format!("node {id}")));
+    let log = events.clone();
+    g.on_edge_added(move |from, to| log.lock().unwrap().push(format!("edge {from}->{to}")));
+
+    g.add_node(0);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edges(&[(1, 2)]);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["node 0", "node 1", "edge 0->1", "node 2", "edge 1->2"]
+    );
+
+    // observers stay with the original graph and do not affect equality
+    let mut copy = g.clone();
+    assert_eq!(copy, g);
+    copy.add_node(10);
+    assert_eq!(events.lock().unwrap().len(), 5);
+
+    g.clear_observers();
+    g.add_node(11);
+    assert_eq!(events.lock().unwrap().len(), 5);
+}
+
+#[test]
+fn test_observers_track_edge_counts() {
+    use std::sync::{
+        atomic::{AtomicIsize, Ordering},
+        Arc,
+    };
+
+    // a derived edge count kept up to date through the observers only
+    let count = Arc::new(AtomicIsize::new(0));
+    let mut g = Graph::<u32, ()>::new();
+    let added = count.clone();
+    g.on_edge_added(move |_, _| {
+        added.fetch_add(1, Ordering::SeqCst);
+    });
+    let removed = count.clone();
+    g.on_edge_removed(move |_, _| {
+        removed.fetch_sub(1, Ordering::SeqCst);
+    });
+    let current = |count: &AtomicIsize| count.load(Ordering::SeqCst) as usize;
+
+    g.add_edges(&[(0, 1), (1, 2), (0, 1)]);
+    g.add_directed_edge(2, 2);
+    g.add_directed_edge(2, 2);
+    g.add_directed_edge(3, 3);
+    assert_eq!(current(&count), g.edge_count());
+
+    let simplification = g.simplify(false);
+    assert_eq!(simplification.parallel_edges, 3);
+    assert_eq!(current(&count), g.edge_count());
+
+    assert_eq!(g.remove_self_loops(), 2);
+    assert_eq!(current(&count), g.edge_count());
+
+    g.remove_edge(0, 1);
+    g.transpose();
+    assert_eq!(current(&count), g.edge_count());
+}
+
+#[test]
+fn test_observers_after_remove_node() {
+    use std::sync::{Arc, Mutex};
+
+    // an edge count and a degree table kept up to date through the observers only
+    let degrees = Arc::new(Mutex::new(std::collections::HashMap::<u32, usize>::new()));
+    let mut g = Graph::<u32, ()>::new();
+    let added = degrees.clone();
+    g.on_edge_added(move |from, to| {
+        let mut degrees = added.lock().unwrap();
+        *degrees.entry(from).or_default() += 1;
+        *degrees.entry(to).or_default() += 1;
+    });
+    let removed = degrees.clone();
+    g.on_edge_removed(move |from, to| {
+        let mut degrees = removed.lock().unwrap();
+        *degrees.get_mut(&from).unwrap() -= 1;
+        *degrees.get_mut(&to).unwrap() -= 1;
+    });
+    let forgotten = degrees.clone();
+    g.on_node_removed(move |id| {
+        // the edges of the node are reported before the node itself
+        assert_eq!(forgotten.lock().unwrap().remove(&id), Some(0));
+    });
+
+    g.add_edges(&[(0, 1), (1, 2), (1, 3)]);
+    g.add_directed_edge(1, 1);
+    g.add_directed_edge(3, 1);
+    g.remove_node(1);
+    assert_eq!(g.edge_count(), 0);
+    let degrees = degrees.lock().unwrap();
+    assert_eq!(degrees.len(), 3);
+    assert!(degrees.values().all(|degree| *degree == 0));
+    drop(degrees);
+
+    // a connectivity structure attached to the graph splits the components of the node
+    let mut g = Graph::<usize, ()>::parse("0 -- 1 -- 2; 1 -- 3").unwrap();
+    let connectivity = grafferous::DynamicConnectivity::attach(&mut g);
+    g.remove_node(1);
+    g.add_edge(0, 2);
+    let connectivity = connectivity.lock().unwrap();
+    assert_eq!(connectivity.component_count(), 2);
+    assert!(connectivity.connected(0, 2));
+    assert!(!connectivity.connected(0, 3));
+}
+
+#[test]
+fn test_transaction_rollback() {
+    let mut g = Graph::<usize, u32>::new();
+    g.add_edge(0, 1);
+    let before = g.clone();
+
+    let mut tx = g.begin();
+    tx.add_edge(1, 2);
+    tx.add_directed_edge(0, 1);
+    assert!(tx.set_node_data(0, 7));
+    assert!(!tx.set_node_data(42, 7));
+    assert_eq!(tx.graph().nodes.len(), 3);
+    tx.rollback();
+    assert_eq!(g, before);
+
+    {
+        let mut tx = g.begin();
+        tx.add_node(5);
+    }
+    assert_eq!(g, before);
+}
+
+#[test]
+fn test_undo_redo() {
+    let mut g = Graph::<usize, u32>::new();
+    let mut history = grafferous::History::new();
+
+    let mut tx = g.begin();
+    tx.add_edge(0, 1);
+    history.record(tx.commit());
+    let after_first = g.clone();
+
+    let mut tx = g.begin();
+    tx.add_node_with_data(2, 9);
+    tx.add_directed_edge(1, 2);
+    history.record(tx.commit());
+    let after_second = g.clone();
+
+    assert!(history.undo(&mut g));
+    assert_eq!(g, after_first);
+    assert!(history.undo(&mut g));
+    assert_eq!(g, Graph::new());
+    assert!(!history.undo(&mut g));
+
+    assert!(history.redo(&mut g));
+    assert!(history.redo(&mut g));
+    assert_eq!(g, after_second);
+    assert_eq!(g.node_data[&2], 9);
+    assert!(!history.can_redo());
+
+    // undoing and redoing an edge keeps the data it was given after the commit
+    let mut g = Graph::<usize, (), f64>::new();
+    let mut history = grafferous::History::new();
+    let mut tx = g.begin();
+    tx.add_directed_edge(0, 1);
+    history.record(tx.commit());
+    *g.edge_data.entry((0, 1)).or_default() = 2.5;
+    assert!(history.undo(&mut g));
+    assert_eq!(g.edge_data(0, 1), None);
+    assert!(history.redo(&mut g));
+    assert_eq!(g.edge_data(0, 1), Some(&2.5));
+}
+
+// removed nodes and edges come back with their data, in their old places
+#[test]
+fn test_transaction_remove() {
+    let mut g = Graph::<usize, u32, f64>::new();
+    g.add_node_with_data(0, 5);
+    g.add_directed_edge_with_data(0, 1, 1.5);
+    g.add_directed_edge_with_data(2, 0, 2.5);
+    g.add_directed_edge_with_data(0, 0, 3.5);
+    g.add_directed_edge(1, 2);
+    g.add_directed_edge(0, 2);
+    g.add_directed_edge(1, 0);
+    g.add_directed_edge(0, 1);
+    let before = g.clone();
+
+    let mut tx = g.begin();
+    assert!(tx.remove_node(0));
+    assert!(!tx.remove_node(0));
+    assert!(tx.remove_edge(1, 2));
+    assert!(!tx.remove_directed_edge(1, 2));
+    assert_eq!(tx.graph().nodes, vec![1, 2]);
+    assert_eq!(tx.graph().edge_count(), 0);
+    tx.rollback();
+    assert_eq!(g, before);
+    assert_eq!(g.edge_data(2, 0), Some(&2.5));
+
+    let mut history = grafferous::History::new();
+    let mut tx = g.begin();
+    tx.remove_directed_edge(0, 1);
+    tx.remove_node(2);
+    history.record(tx.commit());
+    let after = g.clone();
+    assert_eq!(g.neighbors(0), &[1, 0]);
+    assert_eq!(g.edge_data(0, 1), Some(&1.5));
+
+    assert!(history.undo(&mut g));
+    assert_eq!(g, before);
+    assert!(history.redo(&mut g));
+    assert_eq!(g, after);
+    assert!(history.undo(&mut g));
+    assert_eq!(g, before);
+    assert!(g.has_edge(2, 0));
+}
+
+#[test]
+fn test_rollback_rejected_edge() {
+    use grafferous::EdgePolicy;
+
+    // the edge existed before the transaction, so rolling back the rejected copy keeps it
+    let mut g = Graph::<usize, ()>::new();
+    g.set_edge_policy(EdgePolicy::SIMPLE);
+    g.add_directed_edge(0, 1);
+    let mut tx = g.begin();
+    tx.add_directed_edge(0, 1);
+    tx.add_directed_edge(2, 2);
+    tx.rollback();
+    assert!(g.has_edge(0, 1));
+    assert_eq!(g.edge_count(), 1);
+    assert_eq!(g.nodes, vec![0, 1]);
+}
+
+#[cfg(feature = "im")]
+#[test]
+fn test_persistent_graph() {
+    use grafferous::PersistentGraph;
+
+    let mut g = Graph::<usize, u32>::new();
+    g.add_node_with_data(0, 3);
+    g.add_edge(0, 1);
+    g.add_directed_edge(1, 2);
+
+    let base = PersistentGraph::from(&g);
+    assert_eq!(base.to_graph(), g);
+
+    let branch = base.add_directed_edge(2, 0).set_node_data(1, 8);
+    let pruned = branch.remove_node(0);
+
+    assert_eq!(base.edge_count(), 3);
+    assert_eq!(base.node_data(1), Some(&0));
+    assert_eq!(branch.edge_count(), 4);
+    assert_eq!(branch.node_data(1), Some(&8));
+    assert_eq!(branch.reverse_neighbors(0), vec![1, 2]);
+    assert_eq!(pruned.node_count(), 2);
+    assert_eq!(pruned.edge_count(), 1);
+    assert!(pruned.neighbors(2).is_empty());
+    assert_eq!(branch.remove_directed_edge(2, 0), base.set_node_data(1, 8));
+
+    // edge data is shared by the versions and removed with the edge
+    let mut roads = Graph::<usize, (), f64>::new();
+    roads.add_directed_edge_with_data(0, 1, 2.5);
+    let base = PersistentGraph::from(&roads);
+    let branch = base.add_directed_edge_with_data(1, 2, 4.0);
+    assert_eq!(base.edge_data(1, 2), None);
+    assert_eq!(branch.edge_data(1, 2), Some(&4.0));
+    assert_eq!(branch.to_graph().edge_data(0, 1), Some(&2.5));
+    assert_eq!(branch.remove_node(0).edge_data(0, 1), None);
+    assert_eq!(branch.remove_directed_edge(1, 2).edge_data(1, 2), None);
+}
+
+#[test]
+fn test_concurrent_graph() {
+    use grafferous::ConcurrentGraph;
+
+    let g = ConcurrentGraph::<usize, u32>::with_shards(8);
+    std::thread::scope(|scope| {
+        for t in 0..4 {
+            let g = &g;
+            scope.spawn(move || {
+                for i in t * 250..(t + 1) * 250 {
+                    g.add_edge(i, (i + 1) % 1000);
+                    g.update_node_data(i, |data| *data += 1);
+                    let _ = g.neighbors((i + 500) % 1000);
+                }
+                g.add_node_with_data(1000 + t, t as u32);
+            });
+        }
+    });
+
+    assert_eq!(g.node_count(), 1004);
+    assert_eq!(g.edge_count(), 2000);
+    assert_eq!(g.node_data(3), Some(1));
+    assert_eq!(g.node_data(1002), Some(2));
+    assert!(g.contains_node(250));
+    assert!(!g.contains_node(2000));
+
+    let snapshot = g.to_graph();
+    assert!(snapshot.is_undirected());
+    assert_eq!(snapshot.nodes.len(), 1004);
+    assert_eq!(g.into_graph(), snapshot);
+
+    // edge data lives with the start of the edge and survives the conversions
+    let g = ConcurrentGraph::<usize, (), f64>::with_shards(4);
+    g.add_directed_edge_with_data(0, 1, 1.5);
+    g.add_directed_edge(1, 2);
+    assert_eq!(g.edge_data(0, 1), Some(1.5));
+    assert_eq!(g.with_edge_data(0, 1, |w| w * 2.0), Some(3.0));
+    assert_eq!(g.edge_data(1, 2), None);
+    let snapshot = g.to_graph();
+    assert_eq!(snapshot.edge_data(0, 1), Some(&1.5));
+    let back = ConcurrentGraph::from(g.into_graph());
+    assert_eq!(back.edge_data(0, 1), Some(1.5));
+}
+
+#[test]
+fn test_dynamic_connectivity() {
+    use grafferous::DynamicConnectivity;
+
+    let grid = generate_grid_graph::<u32>(4, 4);
+    let mut connectivity = DynamicConnectivity::from_graph(&grid);
+    assert_eq!(connectivity.component_count(), 1);
+
+    // cut the grid into two halves along x = 1 | x = 2, every grid edge is stored in both directions
+    for y in 0..4 {
+        assert!(connectivity.connected((0, 0), (3, 3)));
+        connectivity.delete_edge((1, y), (2, y));
+        connectivity.delete_edge((2, y), (1, y));
+    }
+    assert!(!connectivity.connected((0, 0), (3, 3)));
+    assert!(connectivity.connected((3, 0), (2, 3)));
+    assert_eq!(connectivity.component_count(), 2);
+    assert_eq!(connectivity.component_size((0, 0)), Some(8));
+    assert!(!connectivity.delete_edge((0, 0), (3, 3)));
+
+    connectivity.insert_edge((0, 0), (3, 3));
+    assert!(connectivity.connected((1, 1), (2, 2)));
+    assert!(connectivity.remove_node((0, 0)));
+    assert!(!connectivity.connected((1, 1), (2, 2)));
+    assert_eq!(connectivity.component_count(), 2);
+}
+
+#[test]
+fn test_disjoint_set() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // streaming random edges agrees with recomputing the components
+    let mut rng = StdRng::seed_from_u64(11);
+    let mut g = Graph::<u32, ()>::new();
+    for id in 0..200 {
+        g.add_node(id);
+    }
+    let sets = grafferous::DisjointSet::attach(&mut g);
+    for step in 0..300 {
+        g.add_directed_edge(rng.gen_range(0..200), rng.gen_range(0..210));
+        if step % 50 == 0 {
+            let sets = sets.lock().unwrap();
+            assert_eq!(sets.sets(), g.connected_components());
+            assert_eq!(sets.set_count(), g.connected_components().len());
+        }
+    }
+    let sets = sets.lock().unwrap();
+    assert_eq!(sets.len(), g.nodes.len());
+    assert_eq!(sets.sets(), g.connected_components());
+    for (a, b) in [(0, 1), (5, 150), (199, 3)] {
+        assert_eq!(
+            sets.same_component(a, b),
+            g.component_labels()[&a] == g.component_labels()[&b]
+        );
+        assert_eq!(sets.find(a) == sets.find(b), sets.same_component(a, b));
+    }
+    assert!(!sets.same_component(0, 1000));
+    assert_eq!(sets.find(1000), None);
+
+    let union_find = g.components_union_find();
+    assert_eq!(union_find.sets(), sets.sets());
+}
+
+#[test]
+fn test_dynamic_connectivity_attached() {
+    let mut g = Graph::<usize, ()>::new();
+    g.add_edge(0, 1);
+    let connectivity = grafferous::DynamicConnectivity::attach(&mut g);
+
+    g.add_node(2);
+    assert!(!connectivity.lock().unwrap().connected(0, 2));
+    g.add_directed_edge(1, 2);
+    assert!(connectivity.lock().unwrap().connected(0, 2));
+    assert_eq!(connectivity.lock().unwrap().component_count(), 1);
+
+    g.remove_directed_edge(1, 2);
+    assert!(!connectivity.lock().unwrap().connected(0, 2));
+    g.remove_node(1);
+    assert_eq!(connectivity.lock().unwrap().component_count(), 2);
+
+    // graphs with edge data can be tracked as well
+    let mut roads = Graph::<usize, (), f64>::new();
+    roads.add_edge_with_data(0, 1, 2.5);
+    let connectivity = grafferous::DynamicConnectivity::attach(&mut roads);
+    roads.add_edge_with_data(1, 2, 1.0);
+    assert!(connectivity.lock().unwrap().connected(0, 2));
+    roads.remove_edge(0, 1);
+    assert!(!connectivity.lock().unwrap().connected(0, 2));
+    let rebuilt = grafferous::DynamicConnectivity::from_graph(&roads);
+    assert_eq!(rebuilt.component_count(), 2);
+}
+
+#[test]
+fn test_from_edge_stream() {
+    use grafferous::io::{LoadProgress, StreamOptions};
+
+    let mut reports = Vec::new();
+    let options = StreamOptions::new()
+        .chunk_size(7)
+        .undirected(true)
+        .progress_every(10, |progress| reports.push(progress));
+    let g = Graph::<usize, ()>::from_edge_stream((0..25).map(|i| (i, (i + 1) % 25)), options);
+
+    assert_eq!(g.nodes.len(), 25);
+    assert_eq!(g.edge_tuples().len(), 50);
+    assert!(g.is_undirected());
+    assert_eq!(
+        reports,
+        vec![
+            LoadProgress {
+                edges: 10,
+                nodes: 11
+            },
+            LoadProgress {
+                edges: 20,
+                nodes: 21
+            },
+            LoadProgress {
+                edges: 25,
+                nodes: 25
+            },
+        ]
+    );
+
+    let lines = ["0 1", "1 2", "oops"];
+    let parsed = lines.iter().map(|line| {
+        let mut parts = line.split_whitespace().map(str::parse::<usize>);
+        match (parts.next(), parts.next()) {
+            (Some(Ok(from)), Some(Ok(to))) => Ok((from, to)),
+            _ => Err(format!("bad line: {line}")),
+        }
+    });
+    let result = Graph::<usize, ()>::try_from_edge_stream(parsed, StreamOptions::new());
+    assert_eq!(result.unwrap_err(), "bad line: oops");
+}
+
+#[test]
+fn test_bfs_distances_on_any_representation() {
+    use grafferous::visit::bfs_distances;
+
+    let g = generate_grid_graph::<u32>(6, 6);
+    let distances = bfs_distances(&g, (0, 0));
+    assert_eq!(distances.len(), 36);
+    assert_eq!(distances[&(5, 5)], 10);
+
+    let compact = g.to_compact();
+    let slot = compact.index_of(&(5, 5)).unwrap();
+    let from = compact.index_of(&(0, 0)).unwrap();
+    assert_eq!(bfs_distances(&compact, from)[&slot], 10);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_graph() {
+    use grafferous::{visit::bfs_distances, MmapGraph};
+
+    let mut g = Graph::<i64, ()>::new();
+    for i in -50..50 {
+        g.add_directed_edge(i, i + 1);
+    }
+    g.add_directed_edge(50, -50);
+    let path = std::env::temp_dir().join(format!("grafferous-mmap-{}.csr", std::process::id()));
+
+    let mapped = MmapGraph::create(&path, &g).unwrap();
+    assert_eq!(mapped.node_count(), 101);
+    assert_eq!(mapped.edge_count(), 101);
+    assert_eq!(mapped.neighbors(-3).collect::<Vec<_>>(), vec![-2]);
+    assert_eq!(mapped.neighbors(50).collect::<Vec<_>>(), vec![-50]);
+    assert_eq!(mapped.out_degree(1000), None);
+    assert_eq!(mapped.ids().collect::<Vec<_>>(), g.nodes);
+    assert_eq!(bfs_distances(&mapped, 0), bfs_distances(&g, 0));
+
+    std::fs::write(&path, b"not a graph").unwrap();
+    assert!(MmapGraph::<i64>::open(&path).is_err());
+
+    // corrupt files are rejected when opened instead of panicking on lookups
+    MmapGraph::write(&path, &g).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let (n, m) = (101, 101);
+    let offsets = 24;
+    let sorted_slots = offsets + 8 * (n + 1) + 16 * n;
+    let targets = sorted_slots + 4 * n;
+    let corruptions: [(usize, &[u8]); 5] = [
+        (8, &u64::MAX.to_le_bytes()),
+        (16, &(u64::MAX / 2).to_le_bytes()),
+        (offsets + 8 * 5, &200u64.to_le_bytes()),
+        (sorted_slots + 4 * 7, &500u32.to_le_bytes()),
+        (targets + 4 * (m - 1), &u32::MAX.to_le_bytes()),
+    ];
+    for (at, patch) in corruptions {
+        let mut corrupt = bytes.clone();
+        corrupt[at..at + patch.len()].copy_from_slice(patch);
+        std::fs::write(&path, &corrupt).unwrap();
+        let error = MmapGraph::<i64>::open(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_versioned_graph() {
+    use grafferous::VersionedGraph;
+    use std::sync::Arc;
+
+    let mut history = VersionedGraph::new(Graph::<usize, u32>::new());
+    history.graph_mut().add_edge(0, 1);
+    history.snapshot("start");
+    history.snapshot("unchanged");
+    assert!(Arc::ptr_eq(
+        &history.get("start").unwrap().graph,
+        &history.get("unchanged").unwrap().graph
+    ));
+
+    history.graph_mut().add_directed_edge(1, 2);
+    history.graph_mut().node_data.insert(0, 5);
+    assert!(!history.changes_since("start").unwrap().is_empty());
+    history.snapshot("grown");
+
+    let diff = history.compare("start", "grown").unwrap();
+    assert_eq!(diff.added_nodes, vec![2]);
+    assert_eq!(diff.added_edges, vec![(1, 2)]);
+    assert_eq!(diff.changed_data, vec![0]);
+    assert!(diff.removed_nodes.is_empty() && diff.removed_edges.is_empty());
+    assert!(history.compare("start", "missing").is_none());
+
+    let labels: Vec<&str> = history.versions().map(|v| v.label.as_str()).collect();
+    assert_eq!(labels, vec!["start", "unchanged", "grown"]);
+
+    assert!(history.checkout("start"));
+    assert_eq!(history.graph().nodes.len(), 2);
+    assert!(history.changes_since("unchanged").unwrap().is_empty());
+
+    let mut weighted = VersionedGraph::new(Graph::<usize, (), f64>::new());
+    weighted.graph_mut().add_directed_edge_with_data(0, 1, 0.5);
+    weighted.snapshot("light");
+    *weighted.graph_mut().edge_data.get_mut(&(0, 1)).unwrap() = 9.0;
+    assert!(weighted.checkout("light"));
+    assert_eq!(weighted.into_graph().edge_data(0, 1), Some(&0.5));
+    assert!(history.checkout_version(2));
+    assert_eq!(history.into_graph().nodes.len(), 3);
+}
+
+#[test]
+fn test_pregel_shortest_paths() {
+    use grafferous::compute::Pregel;
+
+    // hop distances from (0, 0), with every node starting at "infinity"
+    let mut g = generate_grid_graph::<usize>(8, 8);
+    for data in g.node_data.values_mut() {
+        *data = usize::MAX;
+    }
+    let program = |ctx: &mut grafferous::compute::Context<(usize, usize), usize>,
+                   distance: &mut usize,
+                   messages: &[usize]| {
+        let candidate = if ctx.id() == (0, 0) {
+            0
+        } else {
+            messages.iter().copied().min().unwrap_or(usize::MAX)
+        };
+        if candidate < *distance {
+            *distance = candidate;
+            ctx.send_to_neighbors(candidate + 1);
+        }
+        ctx.vote_to_halt();
+    };
+
+    let summary = Pregel::new().run(&mut g, program);
+    assert!(summary.converged);
+    assert_eq!(summary.supersteps, 16);
+    assert!(g.node_data.iter().all(|(id, d)| *d == id.0 + id.1));
+
+    let mut limited = generate_grid_graph::<usize>(8, 8);
+    for data in limited.node_data.values_mut() {
+        *data = usize::MAX;
+    }
+    let summary = Pregel::new().max_supersteps(3).run(&mut limited, program);
+    assert!(!summary.converged);
+    assert_eq!(summary.supersteps, 3);
+    assert_eq!(limited.node_data[&(2, 0)], 2);
+    assert_eq!(limited.node_data[&(3, 0)], usize::MAX);
+
+    #[cfg(feature = "rayon")]
+    {
+        let mut parallel = generate_grid_graph::<usize>(8, 8);
+        for data in parallel.node_data.values_mut() {
+            *data = usize::MAX;
+        }
+        let summary = Pregel::new().par_run(&mut parallel, program);
+        assert!(summary.converged);
+        assert_eq!(parallel.node_data, g.node_data);
+    }
+}
+
+#[test]
+fn test_synchronous_step() {
+    use grafferous::compute::StopCondition;
+
+    // a rule that reads neighbor data would see half-updated states without double buffering
+    let mut g = generate_cycle_graph::<u32>(5);
+    *g.node_data.get_mut(&0).unwrap() = 1;
+    g.step(|_, data, neighbors| *data + neighbors.iter().copied().sum::<u32>());
+    assert_eq!(g.node_data[&4], 1);
+    assert_eq!(g.node_data[&1], 1);
+    assert_eq!(g.node_data[&0], 1);
+    assert_eq!(g.node_data[&2], 0);
+
+    let steps = g.run_until(StopCondition::Steps(3), |_, data, _| *data + 1);
+    assert_eq!(steps, 3);
+    assert_eq!(g.node_data[&2], 3);
+
+    // flood fill the maximum: converges after the value has crossed the cycle, plus one idle step
+    let mut g = generate_cycle_graph::<u32>(10);
+    *g.node_data.get_mut(&0).unwrap() = 7;
+    let steps = g.run_until(
+        StopCondition::Convergence { max_steps: 100 },
+        |_, data, neighbors| neighbors.iter().copied().fold(*data, |a, b| a.max(*b)),
+    );
+    assert_eq!(steps, 6);
+    assert!(g.node_data.values().all(|&v| v == 7));
+
+    g.update_all_data(|id, data| *data = *id as u32 * 2);
+    assert_eq!(g.node_data[&4], 8);
+
+    #[cfg(feature = "rayon")]
+    {
+        let rule =
+            |_, data: &u32, neighbors: &[&u32]| *data + neighbors.iter().copied().sum::<u32>();
+        let mut parallel = g.clone();
+        parallel.par_update_all_data(|id, data| *data = *id as u32 * 2);
+        parallel.par_step(rule);
+        g.step(rule);
+        assert_eq!(parallel.node_data, g.node_data);
+    }
+}
+
+#[test]
+fn test_diffuse() {
+    use grafferous::dynamics::diffuse;
+
+    #[derive(Default)]
+    struct Cell {
+        temperature: f64,
+    }
+
+    // open boundaries: heat spreads but nothing leaks out of the grid
+    let mut g = generate_grid_graph::<Cell>(5, 5);
+    g.node_data.get_mut(&(2, 2)).unwrap().temperature = 25.0;
+    diffuse(&mut g, 0.2, 1, |cell| &mut cell.temperature);
+    assert!((g.node_data[&(2, 2)].temperature - 5.0).abs() < 1e-12);
+    assert!((g.node_data[&(1, 2)].temperature - 5.0).abs() < 1e-12);
+    assert_eq!(g.node_data[&(0, 0)].temperature, 0.0);
+
+    diffuse(&mut g, 0.2, 1_000, |cell| &mut cell.temperature);
+    let total: f64 = g.node_data.values().map(|cell| cell.temperature).sum();
+    assert!((total - 25.0).abs() < 1e-9);
+    assert!(g
+        .node_data
+        .values()
+        .all(|cell| (cell.temperature - 1.0).abs() < 1e-6));
+}
+
+#[test]
+fn test_epidemic() {
+    use grafferous::dynamics::epidemic::{Counts, Epidemic, Health};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // with certain infection and recovery the outbreak is a deterministic wave around the cycle
+    let g = generate_cycle_graph::<()>(10);
+    let mut rng = StdRng::seed_from_u64(0);
+    let outbreak = Epidemic::sir(1.0, 1.0).run(&g, &[0], &mut rng);
+    assert_eq!(outbreak.steps(), 6);
+    assert_eq!(outbreak.peak(), (1, 2));
+    assert_eq!(
+        outbreak.curve[3],
+        Counts {
+            susceptible: 3,
+            infected: 2,
+            recovered: 5
+        }
+    );
+    assert!(outbreak.snapshots.is_empty());
+    assert!(outbreak
+        .final_state
+        .values()
+        .all(|h| *h == Health::Recovered));
+
+    // SIS keeps re-infecting recovered nodes
+    let outbreak = Epidemic::sis(1.0, 1.0)
+        .max_steps(20)
+        .record_snapshots(true)
+        .run(&g, &[0], &mut rng);
+    assert_eq!(outbreak.steps(), 20);
+    assert_eq!(outbreak.snapshots.len(), 21);
+    assert_eq!(outbreak.snapshots[2][&0], Health::Infected);
+    assert_eq!(outbreak.curve.last().unwrap().recovered, 0);
+
+    // the same seed reproduces the same run
+    let g = generate_grid_graph::<()>(10, 10);
+    let a = Epidemic::sir(0.3, 0.1).run(&g, &[(0, 0)], &mut StdRng::seed_from_u64(42));
+    let b = Epidemic::sir(0.3, 0.1).run(&g, &[(0, 0)], &mut StdRng::seed_from_u64(42));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_random_walk() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let g = generate_cycle_graph::<()>(10);
+    let walk: Vec<usize> = g.random_walk(3, &mut rng).take(50).collect();
+    assert_eq!(walk.len(), 50);
+    assert_eq!(walk[0], 3);
+    assert!(walk.windows(2).all(|s| g.neighbors(s[0]).contains(&s[1])));
+    assert_eq!(g.random_walk(99, &mut rng).next(), None);
+
+    // a walk ends at a sink, and a weighted walk never takes a zero-weight edge
+    let mut path = Graph::<u32, ()>::new();
+    path.add_directed_edge(0, 1);
+    path.add_directed_edge(0, 2);
+    path.add_directed_edge(2, 3);
+    let walk: Vec<u32> = path
+        .weighted_random_walk(0, &mut rng, |_, to| if to == 1 { 0.0 } else { 1.0 })
+        .collect();
+    assert_eq!(walk, vec![0, 2, 3]);
+
+    // on a directed cycle the walk is deterministic
+    let mut ring = Graph::<u32, ()>::new();
+    for i in 0..4 {
+        ring.add_directed_edge(i, (i + 1) % 4);
+    }
+    assert_eq!(
+        ring.estimate_hitting_time(0, 3, 10, 100, &mut rng),
+        Some(3.0)
+    );
+    assert_eq!(ring.estimate_hitting_time(0, 3, 10, 2, &mut rng), None);
+    let stationary = ring.estimate_stationary_distribution(0, 400, &mut rng);
+    assert!(stationary.values().all(|p| (p - 0.25).abs() < 1e-12));
+
+    // the undirected cycle is regular, so its stationary distribution is uniform too
+    let stationary = g.estimate_stationary_distribution(0, 100_000, &mut rng);
+    assert!(stationary.values().all(|p| (p - 0.1).abs() < 0.01));
+}
+
+#[test]
+fn test_personalized_pagerank() {
+    let g = generate_grid_graph::<()>(15, 15);
+    let exact = g.personalized_pagerank(&[(7, 7), (0, 0)], 0.2);
+    assert_eq!(exact.len(), 225);
+    assert!((exact.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(exact[&(7, 7)] > exact[&(3, 3)]);
+    assert!((exact[&(7, 6)] - exact[&(6, 7)]).abs() < 1e-9);
+
+    let epsilon = 1e-6;
+    let approximate = g.approximate_personalized_pagerank(&[(7, 7), (0, 0)], 0.2, epsilon);
+    for (id, score) in &exact {
+        let estimate = approximate.get(id).copied().unwrap_or(0.0);
+        assert!(estimate <= score + 1e-12);
+        assert!(score - estimate <= 4.0 * epsilon);
+    }
+
+    // dangling nodes send their mass back to the seeds
+    let mut path = Graph::<u32, ()>::new();
+    path.add_directed_edge(0, 1);
+    path.add_directed_edge(1, 2);
+    let scores = path.personalized_pagerank(&[0], 0.5);
+    assert!((scores[&0] - 4.0 / 7.0).abs() < 1e-9);
+    assert!((scores[&2] - 1.0 / 7.0).abs() < 1e-9);
+    assert!(path.personalized_pagerank(&[42], 0.5).is_empty());
+}
+
+#[test]
+fn test_spin_models() {
+    use grafferous::dynamics::spin::{
+        geometric_schedule, linear_schedule, Dynamics, SpinModel, SpinSampler,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let ising = SpinSampler::new(
+        SpinModel::Ising {
+            coupling: 1.0,
+            field: 0.5,
+        },
+        Dynamics::Metropolis,
+    );
+    let mut g = generate_grid_graph::<i32>(4, 4);
+    for spin in g.node_data.values_mut() {
+        *spin = 1;
+    }
+    // 24 aligned pairs in a 4x4 grid plus the field on 16 spins
+    assert_eq!(ising.energy(&g, |s| *s), -24.0 - 8.0);
+    assert_eq!(ising.magnetization(&g, |s| *s), 1.0);
+
+    // the ground state is stable at zero temperature
+    let mut rng = StdRng::seed_from_u64(5);
+    let observed = ising.sample(&mut g, 0.0, 10, &mut rng, |s| s);
+    assert_eq!(observed.energy, -32.0);
+    assert!(g.node_data.values().all(|s| *s == 1));
+
+    // zero sweeps only measure, even at a temperature that would flip spins
+    *g.node_data.get_mut(&(0, 0)).unwrap() = -1;
+    let before = g.clone();
+    let observed = ising.sample(&mut g, 100.0, 0, &mut rng, |s| s);
+    assert_eq!(g, before);
+    assert_eq!(observed.temperature, 100.0);
+    assert_eq!(observed.energy, ising.energy(&g, |s| *s));
+    assert_eq!(observed.magnetization, 14.0 / 16.0);
+
+    // cooling a random Potts configuration orders it
+    let potts = SpinSampler::new(
+        SpinModel::Potts {
+            states: 3,
+            coupling: 1.0,
+        },
+        Dynamics::Glauber,
+    );
+    let mut g = generate_cycle_graph::<i32>(30);
+    potts.randomize(&mut g, &mut rng, |s| s);
+    assert!(g.node_data.values().all(|s| (0..3).contains(s)));
+    let trace = potts.anneal(&mut g, geometric_schedule(2.0, 0.05, 300), &mut rng, |s| s);
+    let last = trace.last().unwrap();
+    assert_eq!(last.temperature, 0.05);
+    assert_eq!(potts.energy(&g, |s| *s), last.energy);
+    assert!(last.energy <= trace[0].energy);
+
+    assert_eq!(linear_schedule(3.0, 1.0, 3), vec![3.0, 2.0, 1.0]);
+    let geometric = geometric_schedule(4.0, 1.0, 3);
+    assert!((geometric[1] - 2.0).abs() < 1e-12);
+
+    // a Potts model without states is rejected up front instead of panicking in `randomize`
+    let empty = std::panic::catch_unwind(|| {
+        SpinSampler::new(
+            SpinModel::Potts {
+                states: 0,
+                coupling: 1.0,
+            },
+            Dynamics::Metropolis,
+        )
+    });
+    assert!(empty.is_err());
+    let single = SpinSampler::new(
+        SpinModel::Potts {
+            states: 1,
+            coupling: 1.0,
+        },
+        Dynamics::Metropolis,
+    );
+    single.randomize(&mut g, &mut rng, |s| s);
+    assert!(g.node_data.values().all(|s| *s == 0));
+}
+
+#[test]
+fn test_belief_propagation() {
+    use grafferous::bp::{BeliefPropagation, FactorGraph, FactorNode};
+
+    // a chain is a tree, so sum-product is exact: compare with brute-force marginals
+    let mut chain = Graph::<u32, f64>::new();
+    chain.add_edge(0, 1);
+    chain.add_edge(1, 2);
+    chain.node_data.insert(0, 0.9);
+    let coupling = [0.7, 0.3, 0.3, 0.7];
+    let fg = FactorGraph::from_pairwise(&chain, 2, &coupling, |p| {
+        if *p > 0.0 {
+            vec![1.0 - p, *p]
+        } else {
+            vec![0.5, 0.5]
+        }
+    });
+    assert_eq!(fg.variable_count(), 3);
+    assert_eq!(fg.factor_count(), 2);
+
+    let beliefs = BeliefPropagation::sum_product().run(&fg);
+    assert!(beliefs.converged);
+    let mut exact = [0.0; 2];
+    let mut total = 0.0;
+    for x0 in 0..2 {
+        for x1 in 0..2 {
+            for x2 in 0..2 {
+                let p = [0.1, 0.9][x0] * coupling[x0 * 2 + x1] * coupling[x1 * 2 + x2];
+                exact[x2] += p;
+                total += p;
+            }
+        }
+    }
+    assert!((beliefs.marginals[&2][1] - exact[1] / total).abs() < 1e-9);
+
+    // max-product on a loopy, damped field agrees on the obvious assignment
+    let g = generate_cycle_graph::<f64>(6);
+    let fg = FactorGraph::from_pairwise(&g, 2, &coupling, |_| vec![0.4, 0.6]);
+    let beliefs = BeliefPropagation::max_product()
+        .damping(0.5)
+        .max_iterations(500)
+        .run(&fg);
+    assert!(beliefs.converged);
+    assert!(beliefs.assignment().values().all(|s| *s == 1));
+
+    let bipartite = fg.to_graph();
+    assert_eq!(bipartite.nodes.len(), 12);
+    assert_eq!(bipartite.neighbors(FactorNode::Factor(0)).len(), 2);
+}
+
+#[test]
+fn test_consensus_and_algebraic_connectivity() {
+    use grafferous::dynamics::{consensus, estimate_algebraic_connectivity};
+    use std::f64::consts::PI;
+
+    let mut g = generate_cycle_graph::<f64>(10);
+    for (id, value) in g.node_data.iter_mut() {
+        *value = *id as f64;
+    }
+    consensus(&mut g, 1_000, |value| value);
+    assert!(g.node_data.values().all(|value| (value - 4.5).abs() < 1e-9));
+
+    let expected = 2.0 - 2.0 * (2.0 * PI / 10.0).cos();
+    let estimate = estimate_algebraic_connectivity(&g, 300);
+    assert!((estimate - expected).abs() < 1e-6);
+
+    let grid = generate_grid_graph::<()>(5, 5);
+    let expected = 2.0 - 2.0 * (PI / 5.0).cos();
+    assert!((estimate_algebraic_connectivity(&grid, 500) - expected).abs() < 1e-6);
+
+    let mut split = Graph::<u32, ()>::new();
+    split.add_edge(0, 1);
+    split.add_edge(2, 3);
+    assert!(estimate_algebraic_connectivity(&split, 100).abs() < 1e-9);
+}
+
+#[test]
+fn test_temporal_graph() {
+    use grafferous::{visit::Neighbors, TemporalGraph};
+
+    let mut g = TemporalGraph::<u32, (), f64>::new();
+    g.add_directed_edge(0, 1, 0.0, 2.0);
+    g.add_directed_edge(1, 2, 1.0, 1.5);
+    g.add_directed_edge(0, 2, 4.0, 4.0);
+    g.add_directed_edge(2, 3, 3.0, 10.0);
+    assert_eq!(g.nodes(), &[0, 1, 2, 3]);
+    assert_eq!(g.time_span(), Some((0.0, 10.0)));
+
+    let snapshot = g.snapshot_at(1.2);
+    assert_eq!(snapshot.nodes.len(), 4);
+    assert_eq!(snapshot.neighbors(0), vec![1]);
+    assert_eq!(snapshot.neighbors(1), vec![2]);
+    assert!(snapshot.neighbors(2).is_empty());
+    assert_eq!(snapshot.reverse_neighbors(2)[..], [1]);
+
+    // departing at 0 the fastest way to 3 goes through 1 and 2, waiting at 2 until 3.0
+    let path = g.temporal_path(0, 3, 0.0).unwrap();
+    assert_eq!(path, vec![(0, 0.0), (1, 0.0), (2, 1.0), (3, 3.0)]);
+    // departing at 1.8 the edge 1 -> 2 is gone by the time 1 is reached
+    let arrival = g.earliest_arrival(0, 1.8);
+    assert_eq!(arrival[&1], 1.8);
+    assert_eq!(arrival[&2], 4.0);
+    assert_eq!(g.temporal_path(0, 3, 1.8).unwrap().len(), 3);
+    assert!(g.temporal_path(3, 0, 0.0).is_none());
+
+    let windows = g.sliding_windows(2.0, 3.0);
+    let starts: Vec<f64> = windows.iter().map(|(t, _)| *t).collect();
+    assert_eq!(starts, vec![0.0, 3.0, 6.0, 9.0]);
+    assert_eq!(windows[0].1.edges.values().flatten().count(), 2);
+    assert_eq!(windows[1].1.edges.values().flatten().count(), 2);
+    assert_eq!(g.aggregate(0.0, 10.0).edges.values().flatten().count(), 4);
+
+    // the edges of a window, and views that agree with the snapshots
+    let between: Vec<(u32, u32)> = g.edges_between(1.6, 3.5).map(|e| (e.from, e.to)).collect();
+    assert_eq!(between, vec![(0, 1), (2, 3)]);
+    assert!(g.has_edge_at(1, 2, 1.5) && !g.has_edge_at(1, 2, 1.6));
+    g.add_directed_edge(0, 1, 5.0, 6.0);
+    for t in [0.5, 1.2, 3.0, 4.0, 5.5, 11.0] {
+        let snapshot = g.snapshot_at(t);
+        let view = g.view_at(t);
+        for id in g.nodes() {
+            let mut expected = snapshot.neighbors(*id).to_vec();
+            expected.dedup();
+            assert_eq!(view.successors(*id).collect::<Vec<_>>(), expected);
+        }
+    }
+    // as a static graph, every linked pair appears once
+    assert_eq!(g.successors(0).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(grafferous::visit::bfs_distances(&g, 0)[&3], 2);
+}
+
+#[test]
+fn test_fruchterman_reingold() {
+    use grafferous::layout::{fruchterman_reingold, ForceConfig};
+
+    let g = generate_cycle_graph::<()>(12);
+    let config = ForceConfig::new().size(100.0, 50.0).iterations(300).seed(9);
+    let layout = fruchterman_reingold(&g, &config);
+    assert_eq!(layout, fruchterman_reingold(&g, &config));
+    assert!(layout
+        .values()
+        .all(|(x, y)| (0.0..=100.0).contains(x) && (0.0..=50.0).contains(y)));
+
+    let distance = |a: usize, b: usize| {
+        let (p, q) = (layout[&a], layout[&b]);
+        ((p.0 - q.0).powi(2) + (p.1 - q.1).powi(2)).sqrt()
+    };
+    let neighbors: f64 = (0..12).map(|i| distance(i, (i + 1) % 12)).sum::<f64>() / 12.0;
+    let opposite: f64 = (0..12).map(|i| distance(i, (i + 6) % 12)).sum::<f64>() / 12.0;
+    assert!(neighbors < opposite / 2.0);
+
+    let single = Graph::<u8, ()>::from_edges(&[]);
+    assert!(fruchterman_reingold(&single, &config).is_empty());
+}
+
+#[test]
+fn test_seeded_spring_layouts() {
+    use grafferous::layout::{
+        fruchterman_reingold, fruchterman_reingold_from, grid_spring_layout, spring_layout,
+        ForceConfig, Layout,
+    };
+
+    let cycle = generate_cycle_graph::<()>(6);
+    assert_eq!(
+        spring_layout(&cycle),
+        fruchterman_reingold(&cycle, &ForceConfig::new())
+    );
+
+    // known positions are kept as a start, clamped to the frame, and the rest are random
+    let start: Layout<usize> = [(0, (-1.0, 0.5)), (1, (0.5, 2.0))].into_iter().collect();
+    let config = ForceConfig::new().iterations(0);
+    let positions = fruchterman_reingold_from(&cycle, &config, &start);
+    assert_eq!(positions[&0], (0.0, 0.5));
+    assert_eq!(positions[&1], (0.5, 1.0));
+    assert_eq!(positions.len(), 6);
+
+    // a grid relaxed from its own shape keeps its columns in order
+    let grid = generate_grid_graph::<()>(5, 5);
+    let layout = grid_spring_layout(&grid, &ForceConfig::new().size(10.0, 10.0));
+    let column = |x: usize| (0..5).map(|y| layout[&(x, y)].0).sum::<f64>() / 5.0;
+    assert!((0..4).all(|x| column(x) < column(x + 1)));
+    let row = |y: usize| (0..5).map(|x| layout[&(x, y)].1).sum::<f64>() / 5.0;
+    assert!((0..4).all(|y| row(y) < row(y + 1)));
+}
+
+#[test]
+fn test_force_atlas2() {
+    use grafferous::layout::{force_atlas2, ForceConfig};
+
+    // two cliques joined by a single edge end up as two separated clusters
+    let mut g = Graph::<u32, ()>::new();
+    for offset in [0, 10] {
+        for a in 0..10 {
+            for b in a + 1..10 {
+                g.add_edge(offset + a, offset + b);
+            }
+        }
+    }
+    g.add_edge(0, 10);
+    let config = ForceConfig::new()
+        .size(100.0, 100.0)
+        .iterations(300)
+        .seed(4);
+    let layout = force_atlas2(&g, &config);
+    assert_eq!(layout.len(), 20);
+    assert_eq!(layout, force_atlas2(&g, &config));
+
+    assert!(clusters_separated(&layout));
+
+    // the exact computation gives the same picture
+    assert!(clusters_separated(&force_atlas2(&g, &config.theta(0.0))));
+}
+
+/// whether the nodes 0..10 and 10..20 form two clusters further apart than their own radius.
+fn clusters_separated(layout: &grafferous::layout::Layout<u32>) -> bool {
+    let centroid = |range: std::ops::Range<u32>| {
+        let (x, y) = range
+            .clone()
+            .map(|i| layout[&i])
+            .fold((0.0, 0.0), |a, p| (a.0 + p.0, a.1 + p.1));
+        (x / range.len() as f64, y / range.len() as f64)
+    };
+    let spread = |range: std::ops::Range<u32>| {
+        let c = centroid(range.clone());
+        range
+            .map(|i| ((layout[&i].0 - c.0).powi(2) + (layout[&i].1 - c.1).powi(2)).sqrt())
+            .fold(0.0, f64::max)
+    };
+    let (a, b) = (centroid(0..10), centroid(10..20));
+    let separation = ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+    separation > spread(0..10) && separation > spread(10..20)
+}
+
+#[test]
+fn test_spectral_layout() {
+    use grafferous::layout::spectral;
+
+    // a path is laid out monotonically along one axis
+    let mut path = Graph::<u32, ()>::new();
+    for i in 0..9 {
+        path.add_edge(i, i + 1);
+    }
+    let layout = spectral(&path);
+    assert_eq!(layout, spectral(&path));
+    let xs: Vec<f64> = (0..10).map(|i| layout[&i].0).collect();
+    let increasing = xs.windows(2).all(|w| w[0] < w[1]);
+    let decreasing = xs.windows(2).all(|w| w[0] > w[1]);
+    assert!(increasing || decreasing);
+
+    assert_eq!(spectral(&Graph::<u32, ()>::new()).len(), 0);
+    let mut single = Graph::<u32, ()>::new();
+    single.add_node(1);
+    assert_eq!(spectral(&single).len(), 1);
+}
+
+#[test]
+fn test_layered_layout() {
+    use grafferous::layout::layered;
+
+    // the long edge 0 -> 3 needs two dummy nodes; a crossing-free drawing exists
+    let dag = Graph::<u32, ()>::from_edges(&[(0, 1), (1, 2), (2, 3), (0, 3), (0, 4), (4, 5)]);
+    let layout = layered(&dag);
+    let y = |i: u32| layout[&i].1;
+    assert!(y(0) < y(1) && y(1) < y(2) && y(2) < y(3));
+    assert_eq!(y(4), y(1));
+    assert_eq!(y(5), y(2));
+    assert!(layout
+        .values()
+        .all(|(x, y)| (0.0..=1.0).contains(x) && (0.0..=1.0).contains(y)));
+    // nodes of a layer never share a position
+    assert_ne!(layout[&1], layout[&4]);
+
+    // a cycle is broken up instead of looping forever
+    let cyclic = Graph::<u32, ()>::from_edges(&[(0, 1), (1, 2), (2, 0)]);
+    let layout = layered(&cyclic);
+    assert!(layout[&0].1 < layout[&1].1 && layout[&1].1 < layout[&2].1);
+}
+
+#[test]
+fn test_circular_and_shell_layouts() {
+    use grafferous::layout::{circular, shell};
+
+    let mut star = Graph::<u32, ()>::new();
+    for leaf in 1..=4 {
+        star.add_edge(0, leaf);
+    }
+    let layout = circular(&star);
+    assert!((layout[&0].0 - 1.0).abs() < 1e-12 && (layout[&0].1 - 0.5).abs() < 1e-12);
+
+    // the hub in the center, the leaves on the outer shell
+    let layout = shell(&star, &[vec![0]]);
+    assert_eq!(layout[&0], (0.5, 0.5));
+    let radius = |(x, y): (f64, f64)| ((x - 0.5).powi(2) + (y - 0.5).powi(2)).sqrt();
+    assert!((1..=4).all(|leaf| (radius(layout[&leaf]) - 0.5).abs() < 1e-12));
+
+    // two shells of a bipartite graph, ignoring unknown IDs
+    let layout = shell(&star, &[vec![1, 2], vec![3, 4, 0, 99]]);
+    assert_eq!(layout.len(), 5);
+    assert!((radius(layout[&1]) - 0.25).abs() < 1e-12);
+    assert!((radius(layout[&0]) - 0.5).abs() < 1e-12);
+}
+
+#[test]
+fn test_lattice_layout() {
+    use grafferous::layout::{lattice, LatticeKind};
+
+    let g = generate_grid_graph::<()>(4, 4);
+    let distance =
+        |a: (f64, f64), b: (f64, f64)| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+
+    let square = lattice(&g, LatticeKind::Square);
+    assert_eq!(square[&(3, 2)], (3.0, 2.0));
+
+    // every triangular neighbor, including the diagonal ones, is at unit distance
+    let triangular = lattice(&g, LatticeKind::Triangular);
+    for (a, b) in [
+        ((1, 1), (2, 1)),
+        ((1, 1), (1, 2)),
+        ((1, 1), (2, 2)),
+        ((1, 2), (0, 3)),
+    ] {
+        assert!((distance(triangular[&a], triangular[&b]) - 1.0).abs() < 1e-12);
+    }
+
+    // honeycomb: zigzag neighbors and the vertical bond of an odd node are at unit distance
+    let hexagonal = lattice(&g, LatticeKind::Hexagonal);
+    for (a, b) in [
+        ((0, 0), (1, 0)),
+        ((1, 0), (2, 0)),
+        ((1, 0), (1, 1)),
+        ((0, 1), (0, 2)),
+    ] {
+        assert!((distance(hexagonal[&a], hexagonal[&b]) - 1.0).abs() < 1e-12);
+    }
+    assert!(distance(hexagonal[&(0, 0)], hexagonal[&(0, 1)]) > 1.5);
+}
+
+#[test]
+fn test_render_svg() {
+    use grafferous::render::{render_svg, SvgStyle};
+
+    let mut g = Graph::<u32, String>::new();
+    g.add_node_with_data(0, "a < b".to_string());
+    g.add_node_with_data(1, "b".to_string());
+    g.add_node_with_data(2, "c".to_string());
+    g.add_edge(0, 1);
+    g.add_directed_edge(1, 2);
+    let layout = grafferous::layout::circular(&g);
+
+    let style = SvgStyle::new()
+        .labels(|_, data: &String| data.clone())
+        .node_color(|id, _| format!("#00{id}000"))
+        .background("white");
+    let svg = render_svg(&g, &layout, &style);
+
+    // the two-way edge is a single plain line, the one-way edge has an arrowhead
+    assert_eq!(svg.matches("<line").count(), 2);
+    assert_eq!(svg.matches("marker-end").count(), 1);
+    assert_eq!(svg.matches("<circle").count(), 3);
+    assert!(svg.contains(">a &lt; b</text>"));
+    assert!(svg.contains("fill=\"#002000\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[cfg(feature = "plotters")]
+#[test]
+fn test_render_png() {
+    use grafferous::render::{render_png, PngStyle};
+
+    let g = generate_grid_graph::<bool>(4, 4);
+    let layout = grafferous::layout::circular(&g);
+    let style = PngStyle::new()
+        .size(64, 48)
+        .node_color(|_, &on: &bool| if on { (255, 0, 0) } else { (0, 0, 255) });
+
+    let path = std::env::temp_dir().join(format!("grafferous-render-{}.png", std::process::id()));
+    render_png(&g, &layout, &path, &style).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+}
+
+#[test]
+fn test_render_ascii() {
+    use grafferous::render::render_ascii;
+
+    // grids are drawn with their longer side horizontal
+    let tall = generate_grid_graph::<()>(2, 4);
+    assert_eq!(render_ascii(&tall, 80, 24), "┌─┬─┬─┐\n│ │ │ │\n└─┴─┴─┘\n");
+    let path = generate_grid_graph::<()>(1, 3);
+    assert_eq!(render_ascii(&path, 80, 24), "╶───╴\n");
+
+    // a grid that does not fit, or is not complete, falls back to the adjacency list
+    let mut g = generate_grid_graph::<()>(3, 3);
+    assert_eq!(render_ascii(&g, 4, 24).lines().count(), 9);
+    g.edges.get_mut(&(0, 0)).unwrap().retain(|n| *n != (0, 1));
+    g.edges.get_mut(&(0, 1)).unwrap().retain(|n| *n != (0, 0));
+    g.invalidate_caches();
+    let text = render_ascii(&g, 80, 24);
+    assert_eq!(text.lines().count(), 9);
+    assert!(text.contains("(0, 0): (1, 0)\n"));
+
+    // long lists are cut to the requested size
+    let cycle = generate_cycle_graph::<()>(100);
+    let text = render_ascii(&cycle, 6, 5);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines.iter().all(|line| line.chars().count() <= 6));
+    assert_eq!(lines[4], "… 96 …");
+}
+
+#[test]
+fn test_bfs_path() {
+    use grafferous::visit::bfs_path;
+
+    let g = generate_grid_graph::<()>(5, 5);
+    let path = bfs_path(&g, (0, 0), (4, 3)).unwrap();
+    assert_eq!(path.len(), 8);
+    assert_eq!((path[0], path[7]), ((0, 0), (4, 3)));
+    assert!(path
+        .windows(2)
+        .all(|step| g.neighbors(step[0]).contains(&step[1])));
+    assert_eq!(bfs_path(&g, (2, 2), (2, 2)), Some(vec![(2, 2)]));
+
+    let mut directed = Graph::<u32, ()>::from_edges(&[(0, 1), (1, 2)]);
+    directed.add_node(3);
+    assert_eq!(bfs_path(&directed, 2, 0), None);
+    assert_eq!(bfs_path(&directed, 0, 3), None);
+    assert_eq!(bfs_path(&directed, 0, 7), None);
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_graph() {
+    use grafferous::wasm::WasmGraph;
+
+    let grid = WasmGraph::grid(4, 3);
+    assert_eq!(grid.node_count(), 12);
+    assert_eq!(grid.nodes(), (0..12).collect::<Vec<_>>());
+    assert_eq!(grid.distance(0, 11), Some(5));
+    assert_eq!(grid.shortest_path(0, 11).unwrap().len(), 6);
+    assert_eq!(grid.bfs(0)[..3], [0, 1, 4]);
+
+    let mut g = WasmGraph::new();
+    g.add_node(7, Some("say \"hi\"".to_string()));
+    g.add_directed_edge(7, 8);
+    assert_eq!(g.label(8), Some(String::new()));
+    assert_eq!(g.shortest_path(8, 7), None);
+    assert_eq!(
+        g.to_json(),
+        r#"{"nodes":[{"id":7,"label":"say \"hi\""},{"id":8,"label":""}],"edges":[[7,8]]}"#
+    );
+}
+
+#[cfg(feature = "python")]
+#[test]
+fn test_python_graph() {
+    use grafferous::python::{grid_graph, PyGraph};
+    use pyo3::{prelude::*, types::PyDict};
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let grid = grid_graph(4, 3);
+        assert_eq!(grid.__len__(), 12);
+        assert_eq!(grid.shortest_path(0, 11).unwrap().len(), 6);
+        assert_eq!(grid.bfs_distances(0)[&11], 5);
+
+        let mut g = PyGraph::new();
+        g.add_edge(1, 2);
+        // data dicts are created on first access and shared with the graph
+        g.__getitem__(py, 1)
+            .unwrap()
+            .bind(py)
+            .set_item("state", "infected")
+            .unwrap();
+        let data = g.__getitem__(py, 1).unwrap();
+        let state: String = data
+            .bind(py)
+            .get_item("state")
+            .unwrap()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(state, "infected");
+        assert!(g.__getitem__(py, 3).is_err());
+
+        g.__setitem__(3, PyDict::new(py));
+        assert!(g.__contains__(3));
+        assert!(g.count_paths(1, 2, None).is_err());
+        assert_eq!(g.count_paths(1, 2, Some(1)).unwrap(), 1);
+        assert!(g.count_paths(1, 9, None).is_err());
+        assert_eq!(g.__repr__(), "Graph(nodes=3, edges=2)");
+    });
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_ffi() {
+    use grafferous::ffi::*;
+    use std::ptr;
+
+    unsafe {
+        let g = grafferous_graph_new();
+        assert!(grafferous_graph_add_node(g, 7));
+        assert!(!grafferous_graph_add_node(g, 7));
+        assert!(grafferous_graph_add_edges(
+            g,
+            [1, 2, 2, 3].as_ptr(),
+            2,
+            false
+        ));
+        // a count whose pairs do not fit in memory is rejected before reading
+        assert!(!grafferous_graph_add_edges(
+            g,
+            [1, 2].as_ptr(),
+            usize::MAX,
+            true
+        ));
+        assert!(!grafferous_graph_add_edges(g, ptr::null(), 1, true));
+        grafferous_graph_add_directed_edge(g, 3, 7);
+        assert_eq!(grafferous_graph_node_count(g), 4);
+        assert!(grafferous_graph_contains_node(g, 3));
+
+        // a zero capacity only reports the length
+        assert_eq!(grafferous_graph_neighbors(g, 2, ptr::null_mut(), 0), 2);
+        let mut out = [0u64; 4];
+        assert_eq!(
+            grafferous_graph_shortest_path(g, 1, 7, out.as_mut_ptr(), 4),
+            4
+        );
+        assert_eq!(out, [1, 2, 3, 7]);
+        assert_eq!(
+            grafferous_graph_shortest_path(g, 7, 1, out.as_mut_ptr(), 4),
+            0
+        );
+        let mut short = [0u64; 2];
+        assert_eq!(grafferous_graph_nodes(g, short.as_mut_ptr(), 2), 4);
+        assert_eq!(short, [7, 1]);
+
+        grafferous_graph_free(g);
+        grafferous_graph_free(ptr::null_mut());
+        assert_eq!(grafferous_graph_node_count(ptr::null()), 0);
+    }
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_testing_generators() {
+    use grafferous::testing::*;
+    use grafferous::visit::bfs_distances;
+    use proptest::{prelude::*, test_runner::TestRunner};
+    use quickcheck::{Arbitrary, QuickCheck};
+
+    let mut runner = TestRunner::default();
+    runner
+        .run(&trees::<()>(30), |tree| {
+            prop_assert_eq!(tree.edge_tuples().len(), 2 * (tree.nodes.len() - 1));
+            prop_assert_eq!(bfs_distances(&tree, 0).len(), tree.nodes.len());
+            Ok(())
+        })
+        .unwrap();
+    runner
+        .run(&connected_graphs::<()>(30), |graph| {
+            prop_assert!(graph.is_undirected());
+            prop_assert_eq!(bfs_distances(&graph, 0).len(), graph.nodes.len());
+            Ok(())
+        })
+        .unwrap();
+    runner
+        .run(&graphs::<()>(30), |graph| {
+            prop_assert!(graph.edge_tuples().iter().all(|(from, to)| from != to));
+            Ok(())
+        })
+        .unwrap();
+
+    runner
+        .run(&undirected_graphs_with::<()>(5..=15, 0.3), |graph| {
+            prop_assert!((5..=15).contains(&graph.nodes.len()));
+            prop_assert!(graph.is_undirected());
+            let n = graph.nodes.len();
+            prop_assert!(
+                graph.edge_count() <= 2 * (0.3 * (n * (n - 1) / 2) as f64).ceil() as usize
+            );
+            Ok(())
+        })
+        .unwrap();
+    runner
+        .run(&dags_with::<()>(0..10, 1.0), |dag| {
+            prop_assert!(dag.edge_tuples().iter().all(|(from, to)| from < to));
+            Ok(())
+        })
+        .unwrap();
+
+    fn connected(graph: ArbitraryConnectedGraph) -> bool {
+        let graph: Graph<usize, ()> = graph.to_graph();
+        bfs_distances(&graph, 0).len() == graph.nodes.len()
+    }
+    QuickCheck::new().quickcheck(connected as fn(ArbitraryConnectedGraph) -> bool);
+
+    // shrinking keeps the kind of graph
+    let dag = ArbitraryDag {
+        node_count: 5,
+        edges: vec![(4, 0), (1, 3), (2, 2)],
+    };
+    let mut edges = dag.to_graph::<()>().edge_tuples();
+    edges.sort();
+    assert_eq!(edges, vec![(0, 4), (1, 3)]);
+    for smaller in dag.shrink() {
+        let graph = smaller.to_graph::<()>();
+        assert!(graph.edge_tuples().iter().all(|(from, to)| from < to));
+    }
+    let undirected = ArbitraryUndirectedGraph {
+        node_count: 4,
+        edges: vec![(0, 1), (1, 2), (2, 7)],
+    };
+    assert!(undirected
+        .shrink()
+        .all(|smaller| smaller.to_graph::<()>().is_undirected()));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let run = |args: &[&str], input: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_grafferous"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        (
+            output.status.success(),
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    };
+
+    let edges = "1 2\n2 3 # a comment\n\n4 5\n";
+    assert_eq!(run(&["topo", "-"], edges).1, "1 4 2 5 3\n");
+    assert_eq!(run(&["components", "-"], edges).1, "1 2 3\n4 5\n");
+    assert_eq!(run(&["path", "-", "1", "3"], edges).1, "1 2 3\n");
+    assert_eq!(
+        run(&["--undirected", "convert", "-", "edges"], edges).1,
+        "1 2\n2 3\n4 5\n"
+    );
+    let (_, stats, _) = run(&["stats", "-"], edges);
+    assert!(stats.contains("nodes: 5\n"));
+    assert!(stats.contains("weakly connected components: 2\n"));
+    assert!(stats.contains("acyclic: true\n"));
+
+    let (success, _, error) = run(&["topo", "-"], "1 2\n2 1\n");
+    assert!(!success);
+    assert!(error.contains("cycle"));
+    let (success, _, error) = run(&["stats", "-"], "1 2\nx 3\n");
+    assert!(!success);
+    assert!(error.contains("-:2:"));
+}
+
+#[test]
+fn test_perturb() {
+    use grafferous::perturb::{PerturbAction, PerturbSpec};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let original = generate_grid_graph::<()>(6, 6);
+    let edge_count = |g: &Graph<(usize, usize), ()>| g.edge_tuples().len();
+
+    let mut g = original.clone();
+    let delta = g.perturb(&mut rng, &PerturbSpec::remove_edges(5));
+    assert_eq!(delta.removed_edges.len(), 10);
+    assert_eq!(edge_count(&g), edge_count(&original) - 10);
+    assert!(g.is_undirected());
+    delta.revert(&mut g);
+    assert_eq!(edge_count(&g), edge_count(&original));
+
+    // rewiring keeps the number of edges and never creates self-loops
+    let mut g = original.clone();
+    let delta = g.perturb(&mut rng, &PerturbSpec::rewire_edges(8).degree_biased());
+    assert_eq!(delta.added_edges.len(), delta.removed_edges.len());
+    assert_eq!(edge_count(&g), edge_count(&original));
+    assert!(g.edge_tuples().iter().all(|(from, to)| from != to));
+
+    let mut g = original.clone();
+    let delta = g.perturb(&mut rng, &PerturbSpec::new(PerturbAction::AddEdges, 3));
+    assert_eq!(delta.added_edges.len(), 6);
+    delta.revert(&mut g);
+    let mut edges = g.edge_tuples();
+    let mut expected = original.edge_tuples();
+    edges.sort();
+    expected.sort();
+    assert_eq!(edges, expected);
+
+    // a degree-biased attack on a star removes the hub first
+    let mut star = Graph::<usize, ()>::new();
+    star.add_edges(&(1..20).map(|leaf| (0, leaf)).collect::<Vec<_>>());
+    let hubs = (0..20)
+        .filter(|_| {
+            let mut g = star.clone();
+            let delta = g.perturb(&mut rng, &PerturbSpec::remove_nodes(1).degree_biased());
+            delta.removed_nodes[0].0 == 0
+        })
+        .count();
+    assert!(hubs >= 5);
+
+    // asking for more than there is removes everything
+    let mut g = star.clone();
+    let delta = g.perturb(&mut rng, &PerturbSpec::remove_nodes(100));
+    assert_eq!(delta.removed_nodes.len(), 20);
+    assert!(g.nodes.is_empty());
+}
+
+#[test]
+fn test_strict_mode_and_logging() {
+    use grafferous::GraphError;
+    use std::sync::Mutex;
+
+    struct Recorder(Mutex<Vec<String>>);
+    impl log::Log for Recorder {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record) {
+            if record.target().starts_with("grafferous") {
+                let line = format!("{} {}", record.level(), record.args());
+                self.0.lock().unwrap().push(line);
+            }
+        }
+        fn flush(&self) {}
+    }
+    static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+    log::set_logger(&RECORDER).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let mut g = Graph::<u32, u32>::new();
+    g.add_node_with_data(1, 10);
+    g.add_node_with_data(1, 20);
+    assert_eq!(g.node_data[&1], 10);
+    assert!(RECORDER
+        .0
+        .lock()
+        .unwrap()
+        .contains(&"WARN attempt to add node 1, that already exists".to_string()));
+
+    assert_eq!(g.try_add_node(1), Err(GraphError::DuplicateNode(1)));
+    assert_eq!(g.try_add_node(2), Ok(()));
+    assert_eq!(g.try_add_edge(1, 3), Err(GraphError::MissingNode(3)));
+    assert_eq!(
+        g.try_add_directed_edge(4, 1),
+        Err(GraphError::MissingNode(4))
+    );
+    assert_eq!(g.nodes, vec![1, 2]);
+    assert_eq!(g.try_add_edge(1, 2), Ok(()));
+    assert_eq!(g.neighbors(2), vec![1]);
+    assert_eq!(
+        GraphError::MissingNode(3).to_string(),
+        "node 3 does not exist"
+    );
+}
+
+#[test]
+fn test_with_hasher() {
+    use grafferous::{GraphHasher, NodeMap};
+
+    let mut g = Graph::<(u64, u64, u64), ()>::with_hasher(GraphHasher::default());
+    g.add_edge((0, 0, 0), (1, 2, 3));
+    assert_eq!(g.neighbors((1, 2, 3)), vec![(0, 0, 0)]);
+
+    let distances: NodeMap<_, _> = grafferous::visit::bfs_distances(&g, (0, 0, 0));
+    assert_eq!(distances[&(1, 2, 3)], 1);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_graph_assertions() {
+    use grafferous::{assert_connected, assert_graph_eq, assert_is_dag};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let message = |result: std::thread::Result<()>| match result {
+        Err(payload) => payload.downcast::<String>().map(|s| *s).unwrap(),
+        Ok(()) => panic!("the assertion passed"),
+    };
+
+    let mut left = Graph::<u32, u32>::from_edges(&[(1, 2), (2, 3)]);
+    let mut right = Graph::<u32, u32>::from_edges(&[(2, 3), (1, 2)]);
+    assert_graph_eq!(left, right);
+
+    left.add_directed_edge(3, 4);
+    right.add_directed_edge(1, 2);
+    *right.node_data.get_mut(&2).unwrap() = 7;
+    let failure = message(catch_unwind(AssertUnwindSafe(|| {
+        assert_graph_eq!(left, right, "case {}", 1)
+    })));
+    assert_eq!(
+        failure,
+        "assertion `left == right` failed: case 1\n\
+         nodes only in left: 4\n\
+         edges only in left: 3 -> 4\n\
+         edges only in right: 1 -> 2\n\
+         node data differs:\n  2: left 0, right 7"
+    );
+
+    // edge data is compared too
+    let mut left = Graph::<u32, (), f64>::new();
+    left.add_directed_edge_with_data(1, 2, 0.5);
+    left.add_directed_edge_with_data(2, 3, 1.0);
+    let mut right = left.clone();
+    assert_graph_eq!(left, right);
+    right.edge_data.insert((1, 2), 2.0);
+    right.edge_data.remove(&(2, 3));
+    let failure = message(catch_unwind(AssertUnwindSafe(|| {
+        assert_graph_eq!(left, right)
+    })));
+    assert!(failure.ends_with(
+        "edge data differs:\n  1 -> 2: left Some(0.5), right Some(2.0)\n  2 -> 3: left Some(1.0), right None"
+    ));
+
+    let dag = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3), (1, 3)]);
+    assert_is_dag!(dag);
+    let cyclic = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3), (3, 2)]);
+    let failure = message(catch_unwind(AssertUnwindSafe(|| assert_is_dag!(cyclic))));
+    assert!(failure.ends_with("the graph has a cycle: 2 -> 3 -> 2"));
+
+    assert_connected!(dag);
+    let mut split = dag.clone();
+    split.add_node(9);
+    let failure = message(catch_unwind(AssertUnwindSafe(|| assert_connected!(split))));
+    assert!(failure.contains("it has 2 components, e.g. 1 and 9 are not connected"));
+}
+
+#[test]
+fn test_graph_builder() {
+    use grafferous::{builder::DuplicatePolicy, GraphBuilder, GraphError};
+
+    let g = GraphBuilder::<u32, &str>::new()
+        .node_with(3, "c")
+        .node_with(1, "a")
+        .edge(1, 2)
+        .directed(2, 3)
+        .directed(2, 3)
+        .edge(4, 4)
+        .build()
+        .unwrap();
+
+    assert_eq!(g.nodes, vec![3, 1, 2, 4]);
+    assert_eq!(g.node_data[&1], "a");
+    assert_eq!(g.node_data[&2], "");
+    let mut edges = g.edge_tuples();
+    edges.sort();
+    assert_eq!(edges, vec![(1, 2), (2, 1), (2, 3), (4, 4)]);
+
+    let duplicate_node = GraphBuilder::<u32, &str>::new()
+        .node_with(1, "a")
+        .node_with(1, "b");
+    assert_eq!(
+        duplicate_node.clone().build().unwrap_err(),
+        GraphError::DuplicateNode(1)
+    );
+    let first = duplicate_node
+        .clone()
+        .duplicate_nodes(DuplicatePolicy::Ignore)
+        .build()
+        .unwrap();
+    assert_eq!(first.node_data[&1], "a");
+    let last = duplicate_node
+        .duplicate_nodes(DuplicatePolicy::Allow)
+        .build()
+        .unwrap();
+    assert_eq!(last.node_data[&1], "b");
+    assert_eq!(last.nodes, vec![1]);
+
+    let parallel = GraphBuilder::<u32, ()>::new().edge(1, 2).directed(2, 1);
+    assert_eq!(
+        parallel
+            .clone()
+            .duplicate_edges(DuplicatePolicy::Reject)
+            .build()
+            .unwrap_err(),
+        GraphError::DuplicateEdge(2, 1)
+    );
+    let kept = parallel
+        .clone()
+        .duplicate_edges(DuplicatePolicy::Allow)
+        .build()
+        .unwrap();
+    assert_eq!(kept.edge_tuples().len(), 3);
+
+    let strict = parallel.node(1).require_nodes(true).build();
+    assert_eq!(strict.unwrap_err(), GraphError::MissingNode(2));
+}
+
+#[test]
+fn test_add_node_auto() {
+    use grafferous::{visit::CompactIds, GraphError, NodeId};
+
+    let mut g = Graph::<u32, &str>::new();
+    let ids: Vec<u32> = ["a", "b", "c"].map(|data| g.add_node_auto(data)).to_vec();
+    assert_eq!(ids, vec![0, 1, 2]);
+    assert_eq!(g.node_data[&2], "c");
+
+    // the counter skips chosen IDs and does not go back to removed ones
+    g.add_node(4);
+    assert_eq!(g.add_node_auto("d"), 3);
+    assert_eq!(g.add_node_auto("e"), 5);
+    assert_eq!(g.nodes.len(), 6);
+    g.remove_node(1);
+    assert_eq!(g.add_node_auto("f"), 6);
+
+    let mut full = Graph::<u8, ()>::new();
+    for _ in 0..256 {
+        full.add_node_auto(());
+    }
+    assert_eq!(full.nodes.len(), 256);
+    let overflow =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| full.add_node_auto(())));
+    assert!(overflow.is_err());
+
+    // the search for a free ID wraps around to the smallest value of the type
+    let mut high = Graph::<u8, ()>::new();
+    for id in 1..=255 {
+        high.add_node(id);
+    }
+    assert_eq!(high.try_add_node_auto(()), Ok(0));
+    assert_eq!(high.try_add_node_auto(()), Err(GraphError::NoFreeId));
+    let mut signed = Graph::<i8, ()>::new();
+    for id in 0..=127 {
+        signed.add_node(id);
+    }
+    assert_eq!(signed.try_add_node_auto(()), Ok(-128));
+    assert_eq!(signed.try_add_node_auto(()), Ok(-127));
+
+    // anonymous nodes mix with chosen IDs and are compact
+    let mut anonymous = Graph::<NodeId, u8>::new();
+    let a = anonymous.add_anonymous_node(1);
+    anonymous.add_node_with_data(NodeId(1), 2);
+    let b = anonymous.add_anonymous_node(3);
+    anonymous.add_edge(a, b);
+    assert_eq!((a, b), (NodeId(0), NodeId(2)));
+    assert_eq!(anonymous.node_data(b), Some(&3));
+    assert_eq!(anonymous.neighbors(b), &[a]);
+    assert_eq!(b.compact_index(), Some(2));
+    assert_eq!(b.to_string(), "2");
+}
+
+#[test]
+fn test_neighbor_lookups() {
+    let mut g = Graph::<u32, ()>::new();
+    g.add_directed_edge(0, 1);
+    g.add_node(2);
+
+    assert_eq!(g.neighbors(0), [1]);
+    assert_eq!(g.reverse_neighbors(1), [0]);
+    assert!(g.neighbors(7).is_empty());
+    assert!(g.reverse_neighbors(7).is_empty());
+
+    assert_eq!(g.try_neighbors(2), Some(&[][..]));
+    assert_eq!(g.try_reverse_neighbors(0), Some(&[][..]));
+    assert_eq!(g.try_neighbors(7), None);
+    assert_eq!(g.try_reverse_neighbors(7), None);
+}
+
+#[test]
+fn test_debug_output() {
+    let mut g = Graph::<u32, &str>::new();
+    g.add_node_with_data(2, "b");
+    g.add_node_with_data(0, "a");
+    g.add_directed_edge(2, 1);
+    g.add_directed_edge(2, 0);
+
+    assert_eq!(
+        format!("{g:?}"),
+        "Graph {2: Node { data: \"b\", neighbors: [0, 1] }, \
+         0: Node { data: \"a\", neighbors: [] }, \
+         1: Node { data: \"\", neighbors: [] }}"
+    );
+    assert_eq!(
+        format!("{g:#?}").lines().take(7).collect::<Vec<_>>(),
+        [
+            "Graph {",
+            "    2: Node {",
+            "        data: \"b\",",
+            "        neighbors: [",
+            "            0,",
+            "            1,",
+            "        ],",
+        ]
+    );
+}
+
+#[test]
+fn test_describe() {
+    let mut g = generate_grid_graph::<()>(3, 3);
+    g.add_edge((5, 5), (6, 6));
+    let report = g.describe();
+
+    assert!(report.contains("nodes: 11\n"));
+    assert!(report.contains("directed edges: 26\n"));
+    assert!(report.contains("undirected: true\n"));
+    assert!(report.contains("acyclic: false\n"));
+    assert!(report.contains("out-degree: min 1, max 4, mean 2.364\n"));
+    assert!(report.contains("highest degree (in + out): (1, 1) (8)"));
+    assert!(report.contains("weakly connected components: 2, largest: 9, 2\n"));
+
+    let dag: Graph<i32, ()> = grafferous::graph! { 0 => 1 => 2, 0 => 2 };
+    assert!(dag.describe().contains("acyclic: true\n"));
+    assert!(Graph::<i32, ()>::new().describe().contains("nodes: 0\n"));
+}
+
+#[test]
+fn test_stats() {
+    use grafferous::GraphStats;
+
+    let mut g = generate_grid_graph::<()>(3, 3);
+    g.add_edge((5, 5), (6, 6));
+    g.add_node((9, 9));
+    g.add_directed_edge((0, 0), (0, 0));
+    let stats = g.stats();
+    assert_eq!(stats.nodes, 12);
+    assert_eq!(stats.edges, 27);
+    assert_eq!(stats.min_out_degree, 0);
+    assert_eq!(stats.max_out_degree, 4);
+    assert!((stats.mean_out_degree - 2.25).abs() < 1e-12);
+    assert!((stats.density - 27.0 / 132.0).abs() < 1e-12);
+    assert_eq!(stats.self_loops, 1);
+    assert_eq!(stats.isolated_nodes, 1);
+    assert!(!stats.weakly_connected);
+    let text = stats.to_string();
+    assert!(text.contains("isolated nodes: 1\n"));
+    assert!(text.contains("weakly connected: false\n"));
+
+    assert!(generate_cycle_graph::<()>(5).stats().weakly_connected);
+    assert_eq!(
+        Graph::<u8, ()>::new().stats(),
+        GraphStats {
+            nodes: 0,
+            edges: 0,
+            density: 0.0,
+            min_out_degree: 0,
+            max_out_degree: 0,
+            mean_out_degree: 0.0,
+            self_loops: 0,
+            isolated_nodes: 0,
+            weakly_connected: true,
+        }
+    );
+}
+
+#[test]
+fn test_parse_dsl() {
+    let g = Graph::<i32, ()>::parse(
+        "# a small fixture\n\
+         0 -> 1 -> 2 -- 3; 7\n\
+         \n\
+         3->-1 # negative IDs work too\n",
+    )
+    .unwrap();
+
+    assert_eq!(g.nodes, vec![0, 1, 2, 3, 7, -1]);
+    let mut edges = g.edge_tuples();
+    edges.sort();
+    assert_eq!(edges, vec![(0, 1), (1, 2), (2, 3), (3, -1), (3, 2)]);
+
+    let from_macro: Graph<i32, ()> = grafferous::graph! { 0 => 1 => 2 ; 3, 3 => (-1) };
+    let mut macro_edges = from_macro.edge_tuples();
+    macro_edges.sort();
+    assert_eq!(edges, macro_edges);
+
+    let error = |text: &str| Graph::<i32, ()>::parse(text).unwrap_err().to_string();
+    assert_eq!(
+        error("0 -> 1; 1 2"),
+        "1:11: expected `->` or `--` before `2`"
+    );
+    assert_eq!(error("-> 1"), "1:1: expected a node ID before `->`");
+    assert_eq!(error("0 -- -- 1"), "1:6: expected a node ID before `--`");
+    assert_eq!(error("0\n1 ->"), "2:3: expected a node ID after the edge");
+    assert_eq!(error("0 -> x"), "1:6: `x` is not a valid node ID");
+}
+
+#[test]
+fn test_attach_node_data() {
+    use std::collections::HashMap;
+
+    let topology = generate_cycle_graph::<()>(4);
+    let labels: HashMap<usize, String> = (0..3).map(|i| (i, format!("node {i}"))).collect();
+    let labeled = topology.with_data_from(labels);
+
+    assert_eq!(labeled.nodes, topology.nodes);
+    assert_eq!(labeled.edges, topology.edges);
+    assert_eq!(labeled.node_data[&1], "node 1");
+    assert_eq!(labeled.node_data[&3], "");
+
+    let mut weights = Graph::<usize, f64>::new();
+    weights.add_node_with_data(1, 0.5);
+    weights.add_node_with_data(7, 2.0);
+    let zipped = labeled.zip_data(&weights);
+
+    assert_eq!(zipped.node_data[&1], ("node 1".to_string(), 0.5));
+    assert_eq!(zipped.node_data[&2], ("node 2".to_string(), 0.0));
+    assert_eq!(zipped.nodes.len(), 4);
+    assert_eq!(zipped.edge_tuples().len(), 8);
+
+    // edge data and the edge policy are carried over
+    use grafferous::{EdgePolicy, SelfLoopPolicy};
+    let policy = EdgePolicy {
+        self_loops: SelfLoopPolicy::Ignore,
+        allow_parallel_edges: false,
+    };
+    let mut roads = Graph::<usize, (), f64>::new();
+    roads.set_edge_policy(policy);
+    roads.add_directed_edge_with_data(1, 2, 4.5);
+    let named = roads.with_data_from([(1, "home")]);
+    assert_eq!(named.edge_data(1, 2), Some(&4.5));
+    assert_eq!(named.edge_policy(), policy);
+    let zipped = named.zip_data(&weights.map_data(|_, w| w as f32));
+    assert_eq!(zipped.edge_data(1, 2), Some(&4.5));
+    assert_eq!(zipped.edge_policy(), policy);
+}
+
+#[test]
+fn test_distance_matrix() {
+    use grafferous::{distance::DistanceMatrix, visit::bfs_distances};
+
+    let g = generate_grid_graph::<()>(6, 4);
+    let matrix = g.distance_matrix();
+
+    assert_eq!(matrix.as_slice().len(), 24 * 24);
+    for source in &g.nodes {
+        let expected = bfs_distances(&g, *source);
+        for target in &g.nodes {
+            assert_eq!(matrix.get(*source, *target), Some(expected[target] as u32));
+        }
+    }
+    assert_eq!(matrix.get((0, 0), (5, 3)), Some(8));
+
+    let corners = g.distance_matrix_from(&[(0, 0), (5, 3), (0, 0), (9, 9)]);
+    assert_eq!(corners.sources(), [(0, 0), (5, 3)]);
+    let column = corners.index_of((2, 2)).unwrap();
+    assert_eq!(corners.row((5, 3)).unwrap()[column], 4);
+    assert_eq!(corners.get((1, 1), (0, 0)), None);
+
+    let compact = g.to_compact();
+    let corner = compact.index_of(&(0, 0)).unwrap();
+    let on_compact = DistanceMatrix::compute(&compact, [corner]);
+    assert_eq!(on_compact.row(corner).unwrap().iter().max(), Some(&8));
+}
+
+#[test]
+fn test_approx_diameter_and_center() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let path = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3 -- 4 -- 5 -- 6").unwrap();
+    assert_eq!(path.approx_diameter(1, StdRng::seed_from_u64(1)), 6);
+    assert_eq!(path.approx_center(), Some(3));
+
+    // a directed cycle: every node reaches every other one in at most 9 hops
+    let cycle =
+        Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 7 -> 8 -> 9 -> 0").unwrap();
+    assert_eq!(cycle.approx_diameter(3, StdRng::seed_from_u64(2)), 9);
+
+    // the hub of a star reaches everything, the leaves reach nothing
+    let star = Graph::<u32, ()>::parse("0 -> 1; 0 -> 2; 0 -> 3; 4").unwrap();
+    assert_eq!(star.approx_center(), Some(0));
+
+    let empty = Graph::<u32, ()>::new();
+    assert_eq!(empty.approx_diameter(5, StdRng::seed_from_u64(3)), 0);
+    assert_eq!(empty.approx_center(), None);
+}
+
+#[test]
+fn test_constrained_routing() {
+    let g = generate_grid_graph::<()>(4, 4);
+
+    let direct = g.shortest_path_avoiding((0, 0), (3, 0), &[], &[]).unwrap();
+    assert_eq!(direct.len(), 4);
+
+    let wall = [(2, 0), (2, 1), (2, 2)];
+    let around = g
+        .shortest_path_avoiding((0, 0), (3, 0), &wall, &[])
+        .unwrap();
+    assert_eq!(around.len(), 10);
+    assert!(around.iter().all(|id| !wall.contains(id)));
+
+    let cut = [((1, 0), (2, 0)), ((1, 1), (2, 1)), ((1, 2), (2, 2))];
+    let through_gap = g.shortest_path_avoiding((0, 0), (3, 0), &[], &cut).unwrap();
+    assert!(through_gap.contains(&(1, 3)) && through_gap.contains(&(2, 3)));
+
+    assert_eq!(
+        g.shortest_path_avoiding((0, 0), (3, 0), &[(3, 0)], &[]),
+        None
+    );
+    let full_wall = [(2, 0), (2, 1), (2, 2), (2, 3)];
+    assert_eq!(
+        g.shortest_path_avoiding((0, 0), (3, 0), &full_wall, &[]),
+        None
+    );
+
+    let route = g.route_via((0, 0), &[(3, 3), (0, 3)], (0, 0)).unwrap();
+    assert_eq!(route.len(), 13);
+    assert_eq!(route.first(), Some(&(0, 0)));
+    assert_eq!(route.last(), Some(&(0, 0)));
+    assert!(route.contains(&(3, 3)) && route.contains(&(0, 3)));
+    assert_eq!(g.route_via((0, 0), &[], (0, 0)), Some(vec![(0, 0)]));
+    assert_eq!(g.route_via((0, 0), &[(9, 9)], (1, 1)), None);
+}
+
+#[test]
+fn test_alt_index() {
+    use grafferous::{routing::AltIndex, visit::bfs_distances};
+
+    let mut g = generate_grid_graph::<()>(12, 8);
+    // one-way shortcuts make the graph directed
+    g.add_directed_edge((0, 0), (11, 7));
+    g.add_directed_edge((6, 0), (0, 7));
+    let index = AltIndex::build(&g, 6);
+    assert_eq!(index.landmarks().len(), 6);
+
+    for from in g.nodes.iter().step_by(7) {
+        let distances = bfs_distances(&g, *from);
+        for to in g.nodes.iter().step_by(5) {
+            assert!(index.lower_bound(*from, *to) as usize <= distances[to]);
+            let path = index.shortest_path(&g, *from, *to).unwrap();
+            assert_eq!(path.len(), distances[to] + 1);
+            assert!(path
+                .windows(2)
+                .all(|step| g.neighbors(step[0]).contains(&step[1])));
+        }
+    }
+
+    let two_parts = Graph::<u32, ()>::parse("0 -> 1 -> 2; 3 -- 4").unwrap();
+    let index = AltIndex::build(&two_parts, 10);
+    assert_eq!(index.landmarks().len(), 5);
+    assert_eq!(index.shortest_path(&two_parts, 0, 4), None);
+    assert_eq!(index.shortest_path(&two_parts, 2, 0), None);
+    assert_eq!(index.shortest_path(&two_parts, 0, 2), Some(vec![0, 1, 2]));
+    assert_eq!(index.lower_bound(0, 99), 0);
+}
+
+#[test]
+fn test_multi_source_bfs() {
+    use grafferous::visit::{bfs_distances, multi_source_bfs};
+
+    let g = generate_grid_graph::<()>(10, 10);
+    let sources = [(0, 0), (9, 9), (0, 9)];
+    let nearest = g.multi_source_bfs(&sources);
+    assert_eq!(nearest.len(), 100);
+
+    let single: Vec<_> = sources.iter().map(|s| bfs_distances(&g, *s)).collect();
+    for id in &g.nodes {
+        let best = single.iter().map(|d| d[id]).min().unwrap();
+        let first = single.iter().position(|d| d[id] == best).unwrap();
+        assert_eq!(nearest[id].distance, best);
+        assert_eq!(nearest[id].source, sources[first]);
+    }
+
+    let cells = g.voronoi_cells(&sources);
+    assert_eq!(cells.iter().map(Vec::len).sum::<usize>(), 100);
+    assert!(cells[0].contains(&(0, 0)) && cells[0].contains(&(4, 4)));
+    assert!(cells[1].contains(&(5, 5)));
+
+    let directed = Graph::<u32, ()>::parse("0 -> 1 -> 2; 3 -> 2; 4").unwrap();
+    let nearest = multi_source_bfs(&directed, [3, 0, 9]);
+    assert_eq!(nearest[&2].source, 3);
+    assert_eq!(nearest[&1].distance, 1);
+    assert!(!nearest.contains_key(&4));
+    assert_eq!(
+        directed.voronoi_cells(&[3, 0, 3]),
+        vec![vec![2, 3], vec![0, 1], vec![]]
+    );
+}
+
+#[test]
+fn test_widest_path() {
+    use std::collections::HashMap;
+
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 5; 0 -> 3 -> 4 -> 5; 1 -> 4; 6").unwrap();
+    let capacities: HashMap<(u32, u32), u32> = HashMap::from([
+        ((0, 1), 9),
+        ((1, 2), 8),
+        ((2, 5), 2),
+        ((0, 3), 4),
+        ((3, 4), 4),
+        ((4, 5), 7),
+        ((1, 4), 3),
+    ]);
+    let capacity = |from: u32, to: u32| capacities[&(from, to)];
+    let path = g.widest_path(0, 5, capacity).unwrap();
+    assert_eq!(path, vec![0, 3, 4, 5]);
+    let bottleneck = path.windows(2).map(|e| capacity(e[0], e[1])).min();
+    assert_eq!(bottleneck, Some(4));
+
+    assert_eq!(g.widest_path(0, 2, capacity), Some(vec![0, 1, 2]));
+    assert_eq!(g.widest_path(0, 0, capacity), Some(vec![0]));
+    assert_eq!(g.widest_path(0, 6, capacity), None);
+    assert_eq!(g.widest_path(0, 9, capacity), None);
+}
+
+#[test]
+fn test_constrained_shortest_path() {
+    use std::collections::HashMap;
+
+    // (cost, resource) of every edge
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 4; 0 -> 2 -> 4; 0 -> 3 -> 4; 1 -> 2; 5").unwrap();
+    let edges: HashMap<(u32, u32), (u32, u32)> = HashMap::from([
+        ((0, 1), (1, 10)),
+        ((1, 4), (1, 10)),
+        ((0, 2), (3, 4)),
+        ((2, 4), (3, 4)),
+        ((0, 3), (10, 0)),
+        ((3, 4), (10, 0)),
+        ((1, 2), (1, 1)),
+    ]);
+    let cost = |from: u32, to: u32| edges[&(from, to)].0;
+    let resource = |from: u32, to: u32| edges[&(from, to)].1;
+
+    let path = |budget| g.constrained_shortest_path(0, 4, cost, resource, budget);
+    assert_eq!(path(20), Some(vec![0, 1, 4]));
+    assert_eq!(path(15), Some(vec![0, 1, 2, 4]));
+    assert_eq!(path(8), Some(vec![0, 2, 4]));
+    assert_eq!(path(0), Some(vec![0, 3, 4]));
+    assert_eq!(g.constrained_shortest_path(0, 5, cost, resource, 100), None);
+    assert_eq!(g.constrained_shortest_path(4, 0, cost, resource, 100), None);
+    assert_eq!(
+        g.constrained_shortest_path(0, 0, cost, resource, 0),
+        Some(vec![0])
+    );
+
+    // on an undirected cycle the search must not loop
+    let cycle = generate_cycle_graph::<()>(12);
+    let hop = |_: usize, _: usize| 1.0;
+    let path = cycle
+        .constrained_shortest_path(0, 6, hop, hop, 6.0)
+        .unwrap();
+    assert_eq!(path.len(), 7);
+    assert_eq!(cycle.constrained_shortest_path(0, 6, hop, hop, 5.5), None);
+}
+
+#[test]
+fn test_list_schedule() {
+    use grafferous::{
+        schedule::{critical_path_lengths, list_schedule},
+        CycleError,
+    };
+
+    // a long chain 0 -> 1 -> 2 and short independent tasks 3, 4, 5 that 2 does not need
+    let mut dag = Graph::<u32, u32>::new();
+    for (id, duration) in [(3, 2), (4, 2), (5, 2), (0, 3), (1, 3), (2, 3)] {
+        dag.add_node_with_data(id, duration);
+    }
+    dag.add_directed_edges(&[(0, 1), (1, 2)]);
+    let duration = |_: u32, d: &u32| *d;
+
+    let lengths = critical_path_lengths(&dag, duration).unwrap();
+    assert_eq!(lengths[&0], 9);
+    assert_eq!(lengths[&2], 3);
+
+    // the critical chain starts first even though 3 comes first in the node list
+    let schedule = list_schedule(&dag, 2, duration).unwrap();
+    assert_eq!(schedule.order[0], 0);
+    assert_eq!(schedule.makespan, 9);
+    for (from, to) in dag.edge_tuples() {
+        assert!(schedule.start[&from] + dag.node_data[&from] <= schedule.start[&to]);
+    }
+    // no worker runs two tasks at once
+    for a in &dag.nodes {
+        for b in &dag.nodes {
+            if a != b && schedule.worker[a] == schedule.worker[b] {
+                let (sa, sb) = (schedule.start[a], schedule.start[b]);
+                assert!(sa + dag.node_data[a] <= sb || sb + dag.node_data[b] <= sa);
+            }
+        }
+    }
+
+    let serial = list_schedule(&dag, 1, duration).unwrap();
+    assert_eq!(serial.makespan, 15);
+    let wide = list_schedule(&dag, 10, duration).unwrap();
+    assert_eq!(wide.makespan, 9);
+    assert_eq!(wide.start[&5], 0);
+
+    dag.add_directed_edge(2, 0);
+    assert_eq!(
+        list_schedule(&dag, 2, duration),
+        Err(CycleError {
+            nodes: vec![0, 1, 2]
+        })
+    );
+}
+
+#[test]
+fn test_topological_iter() {
+    let dag = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3; 4").unwrap();
+    let mut tasks = dag.topological_iter();
+    assert_eq!(tasks.next(), Some(0));
+    assert_eq!(tasks.next(), Some(4));
+    assert_eq!(tasks.next(), None);
+    assert_eq!(tasks.in_progress(), 2);
+
+    // only yielded tasks can be marked done, and only once
+    assert!(!tasks.mark_done(1));
+    assert!(tasks.mark_done(0));
+    assert!(!tasks.mark_done(0));
+    assert_eq!(tasks.by_ref().collect::<Vec<_>>(), vec![1, 2]);
+    tasks.mark_done(2);
+    assert_eq!(tasks.next(), None);
+    tasks.mark_done(1);
+    assert_eq!(tasks.next(), Some(3));
+    tasks.mark_done(3);
+    assert!(!tasks.is_finished());
+    tasks.mark_done(4);
+    assert!(tasks.is_finished() && !tasks.is_stuck());
+
+    // finishing every task right away gives a topological order
+    let grid = Graph::<u32, ()>::parse("0 -> 1 -> 2; 0 -> 3 -> 4 -> 2; 5 -> 3").unwrap();
+    let mut tasks = grid.topological_iter();
+    let mut order = Vec::new();
+    while let Some(id) = tasks.next() {
+        tasks.mark_done(id);
+        order.push(id);
+    }
+    assert_eq!(order.len(), 6);
+    for (from, to) in grid.edge_tuples() {
+        let position = |id| order.iter().position(|x| *x == id).unwrap();
+        assert!(position(from) < position(to));
+    }
+
+    let cyclic = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 1").unwrap();
+    let mut tasks = cyclic.topological_iter();
+    let first = tasks.next().unwrap();
+    tasks.mark_done(first);
+    assert_eq!(tasks.next(), None);
+    assert!(tasks.is_stuck());
+}
+
+#[test]
+fn test_minimum_mean_cycle() {
+    use std::collections::HashMap;
+
+    // two cycles through 0: 0 -> 1 -> 2 -> 0 with mean 2 and 0 -> 3 -> 0 with mean 2.5
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 0 -> 3 -> 0; 2 -> 4").unwrap();
+    let weights: HashMap<(u32, u32), f64> = HashMap::from([
+        ((0, 1), 1.0),
+        ((1, 2), 4.0),
+        ((2, 0), 1.0),
+        ((0, 3), 2.0),
+        ((3, 0), 3.0),
+        ((2, 4), -100.0),
+    ]);
+    let weight = |from: u32, to: u32| weights[&(from, to)];
+    let (mean, mut cycle) = g.minimum_mean_cycle(weight).unwrap();
+    assert_eq!(mean, 2.0);
+    let start = cycle.iter().position(|id| *id == 0).unwrap();
+    cycle.rotate_left(start);
+    assert_eq!(cycle, vec![0, 1, 2]);
+
+    // negative weights, as with the logarithms of exchange rates
+    let (mean, cycle) = g.minimum_mean_cycle(|from, to| -weight(from, to)).unwrap();
+    assert_eq!(mean, -2.5);
+    assert_eq!(cycle.len(), 2);
+
+    let mut looped = g.clone();
+    looped.add_directed_edge(4, 4);
+    let (mean, cycle) = looped
+        .minimum_mean_cycle(|from, to| weights.get(&(from, to)).copied().unwrap_or(0.5))
+        .unwrap();
+    assert_eq!((mean, cycle), (0.5, vec![4]));
+
+    let dag = Graph::<u32, ()>::parse("0 -> 1 -> 2; 0 -> 2").unwrap();
+    assert_eq!(dag.minimum_mean_cycle(|_, _| 1.0), None);
+    assert_eq!(Graph::<u32, ()>::new().minimum_mean_cycle(|_, _| 1.0), None);
+}
+
+#[test]
+fn test_sampling() {
+    use grafferous::sampling::{
+        edge_sample, forest_fire_sample, node_sample, random_walk_sample, snowball_sample,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut g = generate_grid_graph::<usize>(20, 20);
+    for (i, id) in g.nodes.clone().iter().enumerate() {
+        g.node_data.insert(*id, i);
+    }
+    // a few isolated nodes that only uniform picks reach
+    for i in 0..5 {
+        g.add_node_with_data((100 + i, 0), 1000 + i);
+    }
+
+    let samples = [
+        node_sample(&g, 50, StdRng::seed_from_u64(1)),
+        edge_sample(&g, 50, StdRng::seed_from_u64(1)),
+        random_walk_sample(&g, 50, StdRng::seed_from_u64(1)),
+        forest_fire_sample(&g, 50, 0.7, StdRng::seed_from_u64(1)),
+    ];
+    for sample in &samples {
+        assert_eq!(sample.nodes.len(), 50);
+        for id in &sample.nodes {
+            assert_eq!(sample.node_data[id], g.node_data[id]);
+        }
+        // induced: exactly the edges of the original graph between picked nodes
+        let expected = g
+            .edge_tuples()
+            .into_iter()
+            .filter(|(a, b)| sample.node_data.contains_key(a) && sample.node_data.contains_key(b))
+            .count();
+        assert_eq!(sample.edge_tuples().len(), expected);
+        assert!(sample.is_undirected());
+    }
+    // walks and fires keep neighborhoods together, uniform picks do not
+    assert!(samples[2].edge_tuples().len() > samples[0].edge_tuples().len());
+    assert!(samples[3].edge_tuples().len() > samples[0].edge_tuples().len());
+
+    // seeded samples are reproducible, and asking for too much returns everything
+    let again = random_walk_sample(&g, 50, StdRng::seed_from_u64(1));
+    assert_eq!(again.nodes, samples[2].nodes);
+    assert_eq!(
+        edge_sample(&g, 1000, StdRng::seed_from_u64(2)).nodes,
+        g.nodes
+    );
+    assert_eq!(
+        random_walk_sample(&g, 1000, StdRng::seed_from_u64(2))
+            .nodes
+            .len(),
+        405
+    );
+    assert_eq!(
+        forest_fire_sample(&g, 1000, 1.0, StdRng::seed_from_u64(2))
+            .nodes
+            .len(),
+        405
+    );
+
+    // a snowball is the balls around its seeds, which are picked the same for every depth
+    let seeds = snowball_sample(&g, 3, 0, StdRng::seed_from_u64(5)).nodes;
+    assert_eq!(seeds.len(), 3);
+    let snowball = snowball_sample(&g, 3, 2, StdRng::seed_from_u64(5));
+    let balls: Vec<(usize, usize)> = g
+        .nodes
+        .iter()
+        .copied()
+        .filter(|id| {
+            seeds.iter().any(|seed| {
+                grafferous::visit::bfs_distances(&g, *seed)
+                    .get(id)
+                    .is_some_and(|d| *d <= 2)
+            })
+        })
+        .collect();
+    assert_eq!(snowball.nodes, balls);
+    assert!(snowball.is_undirected());
+    assert_eq!(
+        snowball_sample(&g, 1000, 1, StdRng::seed_from_u64(2)).nodes,
+        g.nodes
+    );
+
+    // edges keep their data in the sample
+    let mut weighted = Graph::<u32, (), u32>::new();
+    for i in 0..10 {
+        weighted.add_directed_edge_with_data(i, (i + 1) % 10, i);
+    }
+    let sample = node_sample(&weighted, 10, StdRng::seed_from_u64(3));
+    assert_eq!(sample.edge_data(4, 5), Some(&4));
+}
+
+#[test]
+fn test_coarsen() {
+    use grafferous::coarsen::MatchingStrategy;
+
+    let g = generate_grid_graph::<()>(16, 16);
+    for strategy in [
+        MatchingStrategy::HeavyEdge,
+        MatchingStrategy::NormalizedHeavyEdge,
+        MatchingStrategy::FirstNeighbor,
+    ] {
+        let hierarchy = g.coarsen(4, strategy);
+        assert_eq!(hierarchy.levels.len(), 4);
+        let mut below = g.nodes.len();
+        for (level, coarse) in hierarchy.levels.iter().enumerate() {
+            let n = coarse.graph.nodes.len();
+            assert!(
+                n >= below.div_ceil(2) && n < below,
+                "{strategy:?} level {level}"
+            );
+            assert_eq!(coarse.prolongation.len(), below);
+            assert_eq!(coarse.graph.node_data.values().sum::<usize>(), 256);
+            let members = hierarchy.members(level);
+            for (node, contained) in members.iter().enumerate() {
+                assert_eq!(contained.len(), coarse.graph.node_data[&node]);
+            }
+            // the edge weights add up to the original edges between different coarse nodes
+            let projection = hierarchy.projection(level);
+            let position = |id| g.nodes.iter().position(|x| *x == id).unwrap();
+            let crossing = g
+                .edge_tuples()
+                .iter()
+                .filter(|(a, b)| projection[position(*a)] != projection[position(*b)])
+                .count();
+            assert_eq!(coarse.edge_weights.values().sum::<usize>(), crossing);
+            assert_eq!(coarse.graph.edge_tuples().len(), coarse.edge_weights.len());
+            below = n;
+        }
+        let top = hierarchy.levels.len() - 1;
+        let labels: Vec<usize> = (0..below).collect();
+        assert_eq!(
+            hierarchy.prolong(top, &labels),
+            hierarchy.levels[top].prolongation
+        );
+    }
+
+    // coarsening stops once nothing can be matched
+    let pairs = Graph::<u32, ()>::parse("0 -- 1; 2 -- 3; 4").unwrap();
+    let hierarchy = pairs.coarsen(5, MatchingStrategy::HeavyEdge);
+    assert_eq!(hierarchy.levels.len(), 1);
+    assert_eq!(hierarchy.members(0), vec![vec![0, 1], vec![2, 3], vec![4]]);
+}
+
+#[test]
+fn test_truss_decomposition() {
+    // two complete graphs on five and four nodes sharing node 4, plus a pendant path
+    let mut g = Graph::<u32, ()>::new();
+    for a in 0..5 {
+        for b in a + 1..5 {
+            g.add_edge(a, b);
+        }
+    }
+    for a in 4..8 {
+        for b in a + 1..8 {
+            g.add_edge(a, b);
+        }
+    }
+    g.add_edges(&[(7, 8), (8, 9)]);
+
+    let support = g.edge_triangles();
+    assert_eq!(support[&(0, 1)], 3);
+    assert_eq!(support[&(5, 6)], 2);
+    assert_eq!(support[&(7, 8)], 0);
+
+    let truss = g.truss_decomposition();
+    assert_eq!(truss.len(), g.edge_tuples().len());
+    assert_eq!(truss[&(0, 1)], 5);
+    assert_eq!(truss[&(4, 0)], 5);
+    assert_eq!(truss[&(5, 6)], 4);
+    assert_eq!(truss[&(9, 8)], 2);
+
+    assert_eq!(g.k_truss(5).nodes, vec![0, 1, 2, 3, 4]);
+    assert_eq!(g.k_truss(5).edge_tuples().len(), 20);
+    assert_eq!(g.k_truss(4).nodes.len(), 8);
+    assert!(g.k_truss(4).is_undirected());
+    assert_eq!(g.k_truss(2).nodes.len(), 10);
+    assert!(g.k_truss(6).nodes.is_empty());
+
+    // directions are ignored, so a directed triangle is a 3-truss
+    let directed = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 3").unwrap();
+    let core = directed.k_truss(3);
+    assert_eq!(core.nodes, vec![0, 1, 2]);
+    assert_eq!(core.neighbors(2), &[0]);
+
+    // the kept edges keep their data
+    let mut weighted = Graph::<u32, (), u32>::new();
+    for (from, to) in directed.edges_iter() {
+        weighted.add_directed_edge_with_data(from, to, from * 10 + to);
+    }
+    let core = weighted.k_truss(3);
+    assert_eq!(core.edge_data(2, 0), Some(&20));
+    assert_eq!(core.edge_data(2, 3), None);
+}
+
+#[test]
+fn test_link_prediction() {
+    use grafferous::link_prediction::{LinkPredictor, Similarity};
+
+    // 0 and 1 share the hub 2 and the node 3; 4 and 5 hang off the hub
+    let g = Graph::<u32, ()>::parse("0 -> 2; 1 -> 2; 0 -- 3 -- 1; 4 -- 2 -- 5; 6").unwrap();
+    let predictor = LinkPredictor::new(&g);
+
+    assert_eq!(predictor.score(0, 1, Similarity::CommonNeighbors), 2.0);
+    assert_eq!(predictor.score(0, 1, Similarity::Jaccard), 1.0);
+    let adamic_adar = 1.0 / 4f64.ln() + 1.0 / 2f64.ln();
+    assert!((predictor.score(0, 1, Similarity::AdamicAdar) - adamic_adar).abs() < 1e-12);
+    assert_eq!(
+        predictor.score(0, 2, Similarity::PreferentialAttachment),
+        8.0
+    );
+    assert_eq!(predictor.score(0, 6, Similarity::Jaccard), 0.0);
+    assert_eq!(predictor.score(0, 9, Similarity::CommonNeighbors), 0.0);
+    assert_eq!(
+        predictor.scores(&[(4, 5), (0, 4)], Similarity::CommonNeighbors),
+        vec![1.0, 1.0]
+    );
+
+    let top = predictor.top_k(0, 2, Similarity::CommonNeighbors);
+    assert_eq!(top, vec![(1, 2.0), (4, 1.0)]);
+    let top = predictor.top_k(4, 10, Similarity::Jaccard);
+    assert_eq!(top.len(), 3);
+    assert!(top.iter().all(|(id, _)| ![4, 2, 6].contains(id)));
+    let top = predictor.top_k(6, 1, Similarity::PreferentialAttachment);
+    assert!(top.is_empty());
+    let top = predictor.top_k(4, 1, Similarity::PreferentialAttachment);
+    assert_eq!(top, vec![(0, 2.0)]);
+}
+
+#[test]
+fn test_rich_club_and_degree_correlations() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // five hubs that are all linked, each with three leaves of its own
+    let mut g = Graph::<u32, ()>::new();
+    for a in 0..5 {
+        for b in a + 1..5 {
+            g.add_edge(a, b);
+        }
+        for leaf in 0..3 {
+            g.add_edge(a, 10 + 3 * a + leaf);
+        }
+    }
+    assert_eq!(g.rich_club_coefficient(1), Some(1.0));
+    assert_eq!(g.rich_club_coefficient(7), None);
+    let normalized = g
+        .normalized_rich_club_coefficient(1, 5, StdRng::seed_from_u64(4))
+        .unwrap();
+    assert!(normalized >= 1.0);
+
+    let joint = g.joint_degree_distribution();
+    assert_eq!(joint[&(7, 7)], 10);
+    assert_eq!(joint[&(1, 7)], 15);
+    assert_eq!(joint.values().sum::<usize>(), 25);
+
+    // hubs see other hubs and leaves, leaves only see hubs: disassortative
+    let profile = g.average_neighbor_degree();
+    assert_eq!(profile[&1], 7.0);
+    assert_eq!(profile[&7], (4.0 * 7.0 + 3.0) / 7.0);
+
+    // directions and parallel edges do not matter
+    let mut directed = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 0 -> 1").unwrap();
+    assert_eq!(directed.rich_club_coefficient(1), Some(1.0));
+    directed.add_directed_edge(3, 3);
+    assert_eq!(directed.joint_degree_distribution()[&(2, 2)], 3);
+    assert_eq!(directed.average_neighbor_degree().len(), 1);
+}
+
+#[test]
+fn test_laplacian_spectrum() {
+    use std::f64::consts::PI;
+
+    // the Laplacian of a path on n nodes has the eigenvalues 2 - 2 cos(k pi / n)
+    let path = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3 -- 4 -- 5").unwrap();
+    let expected = |k: usize| 2.0 - 2.0 * (k as f64 * PI / 6.0).cos();
+    let smallest = path.smallest_laplacian_eigenvalues(3);
+    assert_eq!(smallest.len(), 3);
+    for (k, value) in smallest.iter().enumerate() {
+        assert!((value - expected(k)).abs() < 1e-6, "{k}: {value}");
+    }
+    assert!((path.algebraic_connectivity() - expected(1)).abs() < 1e-6);
+    let largest = path.largest_laplacian_eigenvalues(2);
+    assert!((largest[0] - expected(5)).abs() < 1e-6);
+    assert!((largest[1] - expected(4)).abs() < 1e-6);
+
+    // directions are ignored; a complete graph on n nodes has all nonzero eigenvalues n
+    let mut complete = Graph::<u32, ()>::new();
+    for a in 0..5 {
+        for b in a + 1..5 {
+            complete.add_directed_edge(a, b);
+        }
+    }
+    assert!((complete.algebraic_connectivity() - 5.0).abs() < 1e-6);
+    assert_eq!(complete.largest_laplacian_eigenvalues(10).len(), 4);
+
+    let split = Graph::<u32, ()>::parse("0 -- 1 -- 2; 3 -- 4").unwrap();
+    assert!(split.algebraic_connectivity().abs() < 1e-6);
+    assert_eq!(Graph::<u32, ()>::new().algebraic_connectivity(), 0.0);
+    assert!(Graph::<u32, ()>::new()
+        .smallest_laplacian_eigenvalues(2)
+        .is_empty());
+}
+
+#[test]
+fn test_graph_rewriting() {
+    use grafferous::rewrite::{RewriteSystem, Rule};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // matching is injective and checks every pattern edge, in both directions
+    let chain = Graph::<usize, ()>::parse("0 -> 1 -> 2").unwrap();
+    let host = Graph::<u32, u8>::parse("0 -> 1 -> 2 -> 3").unwrap();
+    let rule = Rule::new(chain, Graph::<usize, u8>::parse("0 -> 1 -> 2").unwrap());
+    assert_eq!(rule.matches(&host).len(), 2);
+    assert_eq!(rule.first_match(&host).unwrap()[&2], 2);
+    let triangle = Graph::<usize, ()>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    let kept = Graph::<usize, u8>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    let mut complete = Graph::<u32, u8>::new();
+    for a in 0..4 {
+        for b in 0..4 {
+            if a != b {
+                complete.add_directed_edge(a, b);
+            }
+        }
+    }
+    assert_eq!(Rule::new(triangle, kept).matches(&complete).len(), 24);
+
+    // subdividing an edge adds a node and swaps the edge for two
+    let edge = Graph::<usize, ()>::parse("0 -> 1").unwrap();
+    let mut subdivided = Graph::<usize, u8>::parse("0 -> 2 -> 1").unwrap();
+    subdivided.node_data.insert(2, 7);
+    let subdivide = Rule::new(edge.clone(), subdivided);
+    let mut g = Graph::<u32, u8>::parse("0 -> 1").unwrap();
+    let matched = subdivide.first_match(&g).unwrap();
+    assert_eq!(subdivide.apply(&mut g, &matched), vec![2]);
+    assert_eq!(g.nodes, vec![0, 1, 2]);
+    assert_eq!(g.neighbors(0), &[2]);
+    assert_eq!(g.neighbors(2), &[1]);
+    assert_eq!(g.node_data[&2], 7);
+
+    // orienting every undirected edge, one rewrite at a time
+    let both = Graph::<usize, ()>::parse("0 -- 1").unwrap();
+    let one = Graph::<usize, u8>::parse("0 -> 1").unwrap();
+    let orient = RewriteSystem::new().rule(Rule::new(both, one));
+    let mut grid = Graph::<u32, u8>::parse("0 -- 1 -- 2 -- 3 -- 0; 0 -- 2; 2 -- 4").unwrap();
+    let mut steps = 0;
+    while orient.step_first(&mut grid) {
+        steps += 1;
+    }
+    assert_eq!(steps, 6);
+    assert_eq!(grid.edge_tuples().len(), 6);
+    for (from, to) in grid.edge_tuples() {
+        assert!(!grid.neighbors(to).contains(&from));
+    }
+
+    // deleting all marked nodes in one parallel step, or one at random
+    let node = Graph::<usize, ()>::parse("0").unwrap();
+    let delete = || Rule::new(node.clone(), Graph::new()).when(0, |data: &u8| *data == 1);
+    let mut marked = Graph::<u32, u8>::parse("0 -> 1 -> 2 -> 3 -> 4").unwrap();
+    marked.node_data.insert(1, 1);
+    marked.node_data.insert(3, 1);
+    let mut random = marked.clone();
+    let system = RewriteSystem::new().rule(delete());
+    assert_eq!(system.step_parallel(&mut marked), 2);
+    assert_eq!(marked.nodes, vec![0, 2, 4]);
+    assert!(marked.edge_tuples().is_empty());
+    assert_eq!(system.step_parallel(&mut marked), 0);
+    assert!(system.step_random(&mut random, StdRng::seed_from_u64(5)));
+    assert_eq!(random.nodes.len(), 4);
+    assert!(!RewriteSystem::new()
+        .rule(Rule::new(edge, Graph::new()).when(0, |data: &u8| *data == 9))
+        .step_random(&mut random, StdRng::seed_from_u64(5)));
+}
+
+#[test]
+fn test_random_weights() {
+    use grafferous::{generate_random_weighted_graph, WeightDistribution};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let normal = WeightDistribution::Normal {
+        mean: 10.0,
+        std_dev: 2.0,
+    };
+    let (g, weights) =
+        generate_random_weighted_graph::<()>(50, 0.2, normal, StdRng::seed_from_u64(3));
+    assert_eq!(g.nodes.len(), 50);
+    assert!(g.edge_tuples().iter().all(|(from, to)| from != to));
+    assert_eq!(weights.len(), g.edge_tuples().len());
+    for (from, to) in g.edge_tuples() {
+        assert!(g.reverse_neighbors(to).contains(&from));
+    }
+    let mean = weights.values().sum::<f64>() / weights.len() as f64;
+    assert!((mean - 10.0).abs() < 0.5);
+
+    // the same seed gives the same weights, parallel edges share theirs
+    let mut multi = generate_cycle_graph::<()>(100);
+    multi.add_directed_edge(0, 1);
+    let exponential = WeightDistribution::Exponential { rate: 0.5 };
+    let weights = multi.assign_random_weights(exponential, StdRng::seed_from_u64(8));
+    assert_eq!(weights.len(), 200);
+    assert_eq!(
+        weights,
+        multi.assign_random_weights(exponential, StdRng::seed_from_u64(8))
+    );
+    assert!(weights.values().all(|w| *w >= 0.0));
+    let mean = weights.values().sum::<f64>() / weights.len() as f64;
+    assert!((mean - 2.0).abs() < 0.5);
+
+    let fixed = WeightDistribution::Uniform {
+        low: 4.0,
+        high: 4.0,
+    };
+    assert_eq!(fixed.sample(StdRng::seed_from_u64(0)), 4.0);
+}
+
+#[test]
+fn test_stochastic_block_model() {
+    use grafferous::generate_stochastic_block_model;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let sizes = [40, 60, 0, 50];
+    let p = [
+        vec![0.4, 0.05, 0.0, 0.02],
+        vec![0.05, 0.3, 0.0, 0.0],
+        vec![0.0, 0.0, 0.0, 0.0],
+        vec![0.02, 0.0, 0.0, 0.5],
+    ];
+    let g = generate_stochastic_block_model::<()>(&sizes, &p, StdRng::seed_from_u64(4));
+    assert_eq!(g.nodes, (0..150).collect::<Vec<usize>>());
+    assert!(g.is_undirected());
+    assert!(g.edge_tuples().iter().all(|(from, to)| from != to));
+
+    // the edge density between every two blocks matches their probability
+    let block = |id: usize| match id {
+        0..40 => 0,
+        40..100 => 1,
+        _ => 3,
+    };
+    let mut counts = [[0usize; 4]; 4];
+    for (from, to) in g.edge_tuples() {
+        counts[block(from)][block(to)] += 1;
+    }
+    for a in [0, 1, 3] {
+        for b in [0, 1, 3] {
+            let pairs = if a == b {
+                sizes[a] * (sizes[a] - 1)
+            } else {
+                sizes[a] * sizes[b]
+            };
+            let density = counts[a][b] as f64 / pairs as f64;
+            assert!((density - p[a][b]).abs() < 0.05, "{a} {b} {density}");
+        }
+    }
+
+    // the planted blocks are found again, and a seed always gives the same graph
+    let communities = g.communities_louvain();
+    for id in 0..150 {
+        let first = [0, 40, 0, 100][block(id)];
+        assert_eq!(communities[&id], communities[&first]);
+    }
+    assert_ne!(communities[&0], communities[&40]);
+    assert_ne!(communities[&40], communities[&100]);
+    let again = generate_stochastic_block_model::<()>(&sizes, &p, StdRng::seed_from_u64(4));
+    assert_eq!(again.edge_tuples(), g.edge_tuples());
+    assert!(
+        generate_stochastic_block_model::<()>(&[], &[], StdRng::seed_from_u64(4))
+            .nodes
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_self_loops_and_edge_policy() {
+    use grafferous::{EdgePolicy, GraphError, SelfLoopPolicy};
+
+    let mut g = Graph::<u32, ()>::parse("0 -> 0 -> 1 -> 1; 2").unwrap();
+    g.add_directed_edge(1, 1);
+    assert_eq!(g.self_loops(), vec![0, 1]);
+    assert_eq!(g.remove_self_loops(), 3);
+    assert!(g.self_loops().is_empty());
+    assert_eq!(g.edge_tuples(), vec![(0, 1)]);
+    assert_eq!(g.reverse_neighbors(1), &[0]);
+    assert!(g.reverse_neighbors(0).is_empty());
+
+    // loops alone can be forbidden, parallel edges stay allowed
+    assert_eq!(g.edge_policy(), EdgePolicy::default());
+    g.set_edge_policy(EdgePolicy {
+        self_loops: SelfLoopPolicy::Ignore,
+        allow_parallel_edges: true,
+    });
+    g.add_directed_edge(2, 2);
+    g.add_directed_edges(&[(0, 1), (3, 3)]);
+    assert_eq!(g.neighbors(0), &[1, 1]);
+    assert!(g.self_loops().is_empty());
+    assert_eq!(g.nodes, vec![0, 1, 2]);
+    assert_eq!(g.try_add_edge(2, 2), Err(GraphError::SelfLoop(2)));
+
+    // a simple graph rejects repeats within one batch and either direction of an undirected edge
+    g.set_edge_policy(EdgePolicy::SIMPLE);
+    g.add_edges(&[(1, 2), (2, 1), (2, 0)]);
+    assert_eq!(g.neighbors(1), &[2]);
+    assert_eq!(g.neighbors(2), &[1, 0]);
+    assert_eq!(g.neighbors(0), &[1, 1, 2]);
+    assert_eq!(g.try_add_edge(0, 2), Err(GraphError::DuplicateEdge(0, 2)));
+    assert_eq!(g.try_add_edge(2, 1), Err(GraphError::DuplicateEdge(2, 1)));
+    assert_eq!(g.try_add_directed_edge(1, 0), Ok(()));
+    assert_eq!(g.neighbors(1), &[2, 0]);
+
+    let random = grafferous::generate_random_graph::<()>(30, 0.9);
+    assert!(random.self_loops().is_empty());
+
+    // self-loops can be made an error of the graph itself, for the plain methods too
+    let mut strict = Graph::<u32, ()>::parse("0 -> 1").unwrap();
+    strict.set_edge_policy(EdgePolicy {
+        self_loops: SelfLoopPolicy::Error,
+        allow_parallel_edges: true,
+    });
+    strict.add_edge(0, 1);
+    assert_eq!(
+        strict.try_add_directed_edge(1, 1),
+        Err(GraphError::SelfLoop(1))
+    );
+    for add in [
+        (|g: &mut Graph<u32, ()>| g.add_directed_edge(1, 1)) as fn(&mut Graph<u32, ()>),
+        |g| g.add_edge(0, 0),
+        |g| g.add_directed_edges(&[(0, 1), (1, 1)]),
+    ] {
+        let mut g = strict.clone();
+        let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| add(&mut g)));
+        assert!(outcome.is_err());
+        assert!(g.self_loops().is_empty());
+    }
+    assert_eq!(strict.edge_tuples().len(), 3);
+}
+
+#[test]
+fn test_consistency_check_and_repair() {
+    use grafferous::consistency::InvariantViolation;
+
+    // the generators mirror their edges
+    assert!(generate_grid_graph::<()>(4, 3)
+        .check_consistency()
+        .is_empty());
+    assert!(generate_cycle_graph::<()>(5).check_consistency().is_empty());
+    assert_eq!(generate_cycle_graph::<()>(5).reverse_neighbors(0).len(), 2);
+
+    let mut g = Graph::<u32, u8>::parse("0 -> 1 -> 2").unwrap();
+    g.nodes.push(1);
+    g.nodes.push(7);
+    g.node_data.insert(5, 0);
+    g.edges.get_mut(&2).unwrap().push(9);
+    g.reverse_edges.get_mut(&0).unwrap().push(2);
+    g.reverse_edges.get_mut(&2).unwrap().clear();
+    let violations = g.check_consistency();
+    assert_eq!(
+        &violations[..4],
+        &[
+            InvariantViolation::DuplicateNode(1),
+            InvariantViolation::MissingData(7),
+            InvariantViolation::MissingAdjacency(7),
+            InvariantViolation::UnlistedNode(5),
+        ]
+    );
+    for expected in [
+        InvariantViolation::DanglingEdge(2, 9),
+        InvariantViolation::MissingReverseEdge(2, 9),
+        InvariantViolation::MissingReverseEdge(1, 2),
+        InvariantViolation::StrayReverseEdge(2, 0),
+    ] {
+        assert!(violations.contains(&expected), "{expected}");
+    }
+    assert_eq!(violations.len(), 8);
+
+    assert_eq!(g.repair(), violations);
+    assert!(g.check_consistency().is_empty());
+    assert!(g.repair().is_empty());
+    assert_eq!(g.nodes, vec![0, 1, 2, 5]);
+    assert_eq!(g.edge_tuples().len(), 2);
+    assert_eq!(g.reverse_neighbors(2), &[1]);
+    assert!(g.reverse_neighbors(0).is_empty());
+    assert_eq!(g.try_neighbors(5), Some(&[][..]));
+    assert_eq!(g.validate(), Ok(()));
+    g.reverse_edges.get_mut(&2).unwrap().push(5);
+    assert_eq!(
+        g.validate(),
+        Err(vec![InvariantViolation::StrayReverseEdge(5, 2)])
+    );
+
+    // the accessors change node data without touching the structure
+    let mut g = Graph::<u32, u8>::parse("0 -> 1 -> 2").unwrap();
+    *g.node_data_mut(1).unwrap() = 4;
+    for (_, data) in g.nodes_with_data_mut() {
+        *data += 1;
+    }
+    assert!(g.node_data_mut(3).is_none());
+    let data: Vec<(u32, u8)> = g.nodes_with_data().map(|(id, d)| (id, *d)).collect();
+    assert_eq!(data, vec![(0, 1), (1, 5), (2, 1)]);
+    assert_eq!(g.node_data(1), Some(&5));
+    assert_eq!(g.validate(), Ok(()));
+}
+
+#[test]
+fn test_edge_data() {
+    use grafferous::consistency::InvariantViolation;
+
+    let mut g = Graph::<u32, (), u32>::new();
+    g.add_directed_edge_with_data(0, 1, 5);
+    g.add_edge_with_data(1, 2, 7);
+    g.add_directed_edge(2, 3);
+    assert_eq!(g.edge_data(0, 1), Some(&5));
+    assert_eq!(g.edge_data(2, 1), Some(&7));
+    assert_eq!(g.edge_data(2, 3), None);
+    *g.edge_data_mut(0, 1).unwrap() += 1;
+    let weighted: Vec<(u32, u32, u32)> = g.weighted_edges().map(|(a, b, w)| (a, b, *w)).collect();
+    assert_eq!(weighted, vec![(0, 1, 6), (1, 2, 7), (2, 1, 7)]);
+
+    // parallel edges share their data until the last copy is gone
+    g.add_directed_edge_with_data(0, 1, 9);
+    assert_eq!(g.neighbors(0), &[1, 1]);
+    assert_eq!(g.edge_data(0, 1), Some(&9));
+    g.begin().add_directed_edge(0, 1);
+    assert_eq!(g.neighbors(0), &[1, 1]);
+    assert_eq!(g.edge_data(0, 1), Some(&9));
+    assert!(g.check_consistency().is_empty());
+
+    g.add_directed_edge_with_data(3, 3, 1);
+    assert_eq!(g.remove_self_loops(), 1);
+    assert_eq!(g.edge_data(3, 3), None);
+
+    // stray data is found and repaired
+    g.edge_data.insert((3, 0), 4);
+    assert_eq!(
+        g.check_consistency(),
+        vec![InvariantViolation::StrayEdgeData(3, 0)]
+    );
+    g.repair();
+    assert_eq!(g.edge_data(3, 0), None);
+
+    // algorithm methods work on graphs with edge data
+    assert_eq!(
+        g.widest_path(0, 2, |a, b| g.edge_data[&(a, b)]),
+        Some(vec![0, 1, 2])
+    );
+    let unweighted: Graph<u32, ()> = Graph::parse("0 -> 1").unwrap();
+    assert!(unweighted.edge_data.is_empty());
+}
+
+#[test]
+fn test_remove_nodes_and_edges() {
+    let mut g = generate_grid_graph::<u8>(3, 3);
+    g.node_data.insert((1, 1), 4);
+    assert_eq!(g.remove_node((1, 1)), Some(4));
+    assert_eq!(g.remove_node((1, 1)), None);
+    assert_eq!(g.nodes.len(), 8);
+    assert!(!g.node_data.contains_key(&(1, 1)));
+    assert!(g.try_neighbors((1, 1)).is_none());
+    assert!(!g.neighbors((0, 1)).contains(&(1, 1)));
+    assert!(!g.reverse_neighbors((0, 1)).contains(&(1, 1)));
+    assert_eq!(g.edge_tuples().len(), 16);
+    assert!(g.check_consistency().is_empty());
+
+    // one copy of a directed edge at a time, the reverse edges follow
+    g.add_directed_edge((0, 0), (0, 1));
+    assert!(g.remove_directed_edge((0, 0), (0, 1)));
+    assert_eq!(g.neighbors((0, 0)), &[(1, 0), (0, 1)]);
+    assert_eq!(
+        g.reverse_neighbors((0, 1))
+            .iter()
+            .filter(|id| **id == (0, 0))
+            .count(),
+        1
+    );
+    assert!(g.remove_directed_edge((0, 0), (0, 1)));
+    assert!(!g.remove_directed_edge((0, 0), (0, 1)));
+    assert!(g.neighbors((0, 1)).contains(&(0, 0)));
+    assert!(g.check_consistency().is_empty());
+
+    // undirected removal takes both directions
+    assert!(g.remove_edge((0, 1), (0, 0)));
+    assert!(!g.remove_edge((0, 1), (0, 0)));
+    assert!(g.is_undirected());
+    assert_eq!(g.edge_tuples().len(), 14);
+    assert!(g.check_consistency().is_empty());
+
+    // an edge removal is reported to observers
+    let removed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = removed.clone();
+    g.on_edge_removed(move |from, to| log.lock().unwrap().push((from, to)));
+    g.remove_edge((2, 2), (2, 1));
+    assert_eq!(
+        *removed.lock().unwrap(),
+        vec![((2, 2), (2, 1)), ((2, 1), (2, 2))]
+    );
+}
+
+#[test]
+fn test_graph_errors() {
+    use grafferous::GraphError;
+
+    let mut g = Graph::<u32, ()>::parse("0 -> 1; 1 -- 2").unwrap();
+
+    // a one-way edge cannot be removed as an undirected one, and nothing is removed
+    assert_eq!(g.try_remove_edge(0, 1), Err(GraphError::MissingEdge(1, 0)));
+    assert_eq!(g.neighbors(0), &[1]);
+    assert_eq!(
+        g.try_remove_directed_edge(0, 3),
+        Err(GraphError::MissingNode(3))
+    );
+    assert_eq!(g.try_remove_directed_edge(0, 1), Ok(()));
+    assert_eq!(
+        g.try_remove_directed_edge(0, 1),
+        Err(GraphError::MissingEdge(0, 1))
+    );
+    assert_eq!(g.try_remove_edge(2, 1), Ok(()));
+    assert!(g.edge_tuples().is_empty());
+
+    assert_eq!(g.try_remove_node(2), Ok(()));
+    assert_eq!(g.try_remove_node(2), Err(GraphError::MissingNode(2)));
+    assert_eq!(g.try_add_node_auto(()), Ok(2));
+    assert!(g.check_consistency().is_empty());
+
+    assert_eq!(
+        GraphError::MissingEdge(0, 1).to_string(),
+        "edge 0 -> 1 does not exist"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    use grafferous::{EdgePolicy, SelfLoopPolicy};
+
+    let mut g = Graph::<(u32, u32), String, f64>::new();
+    g.add_node_with_data((0, 0), "origin".to_string());
+    g.add_edge_with_data((0, 0), (0, 1), 0.5);
+    g.add_directed_edge((0, 1), (1, 1));
+    g.add_directed_edge((0, 1), (1, 1));
+    g.set_edge_policy(EdgePolicy {
+        self_loops: SelfLoopPolicy::Ignore,
+        ..EdgePolicy::default()
+    });
+
+    let json = serde_json::to_string(&g).unwrap();
+    let from_json: Graph<(u32, u32), String, f64> = serde_json::from_str(&json).unwrap();
+    assert_eq!(from_json, g);
+    assert_eq!(from_json.nodes, g.nodes);
+    assert_eq!(from_json.neighbors((0, 1)), &[(0, 0), (1, 1), (1, 1)]);
+    assert_eq!(from_json.reverse_neighbors((1, 1)), &[(0, 1), (0, 1)]);
+    assert_eq!(from_json.edge_data((0, 1), (0, 0)), Some(&0.5));
+    assert_eq!(from_json.edge_policy(), g.edge_policy());
+
+    let bytes = bincode::serialize(&g).unwrap();
+    let from_bincode: Graph<(u32, u32), String, f64> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(from_bincode, g);
+
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(&g, &mut cbor).unwrap();
+    let from_cbor: Graph<(u32, u32), String, f64> = ciborium::de::from_reader(&cbor[..]).unwrap();
+    assert_eq!(from_cbor, g);
+}
+
+#[test]
+fn test_edge_list_io() {
+    use grafferous::io::EdgeListError;
+
+    let text = "# a SNAP-style header\n\n10\t20\n20 30 # trailing comment\n30,10,2.5\n";
+    let g = Graph::<u64, (), f64>::from_edge_list_reader(text.as_bytes()).unwrap();
+    assert_eq!(g.nodes, vec![10, 20, 30]);
+    assert_eq!(g.neighbors(30), &[10]);
+    assert_eq!(g.edge_data(30, 10), Some(&2.5));
+    assert_eq!(g.edge_data(10, 20), None);
+
+    // writing and reading back keeps the edges, their order and the weights
+    let mut written = Vec::new();
+    g.write_edge_list(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written.clone()).unwrap(),
+        "10\t20\n20\t30\n30\t10\t2.5\n"
+    );
+    let reread = Graph::<u64, (), f64>::from_edge_list_reader(&written[..]).unwrap();
+    assert_eq!(reread, g);
+
+    // graphs without edge data ignore the weights
+    let unweighted = Graph::<u64, ()>::from_edge_list_reader(text.as_bytes()).unwrap();
+    assert!(unweighted.edge_data.is_empty());
+    assert_eq!(unweighted.edge_tuples().len(), 3);
+
+    match Graph::<u64, (), f64>::from_edge_list_reader("1 2 heavy".as_bytes()) {
+        Err(EdgeListError::Parse(error)) => {
+            assert_eq!((error.line, error.column), (1, 5));
+            assert_eq!(error.message, "`heavy` is not a valid weight");
+        }
+        _ => panic!("expected a parse error"),
+    }
+    let error = Graph::<u64, ()>::from_edge_list_reader("1 2 3 4".as_bytes()).unwrap_err();
+    assert_eq!(error.to_string(), "1:7: expected `from to [weight]`");
+}
+
+#[test]
+fn test_bfs_and_dfs_iterators() {
+    use grafferous::visit::{Dfs, Visit};
+
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 3 -> 4; 0 -> 2 -> 3; 2 -> 0; 5 -> 0").unwrap();
+
+    assert_eq!(g.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(
+        g.bfs_with_depth(0).collect::<Vec<_>>(),
+        vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 3)]
+    );
+    assert_eq!(
+        g.bfs_with_parent(2).collect::<Vec<_>>(),
+        vec![
+            (2, None),
+            (3, Some(2)),
+            (0, Some(2)),
+            (4, Some(3)),
+            (1, Some(0))
+        ]
+    );
+
+    assert_eq!(g.dfs(0).collect::<Vec<_>>(), vec![0, 1, 3, 4, 2]);
+    assert_eq!(
+        g.dfs_with_depth(0).collect::<Vec<_>>(),
+        vec![(0, 0), (1, 1), (3, 2), (4, 3), (2, 1)]
+    );
+    assert_eq!(g.dfs_with_parent(0).last(), Some((2, Some(0))));
+
+    // traversals are lazy and empty for missing nodes
+    assert_eq!(g.dfs(0).take(2).count(), 2);
+    assert_eq!(g.bfs(9).count(), 0);
+    assert_eq!(g.dfs(9).count(), 0);
+
+    // the iterators also run on other graph representations
+    let compact = grafferous::CompactGraph::from(&g);
+    assert_eq!(
+        Dfs::new(&compact, 0).next(),
+        Some(Visit {
+            id: 0,
+            depth: 0,
+            parent: None
+        })
+    );
+    assert_eq!(Dfs::new(&compact, 0).count(), 5);
+}
+
+#[test]
+fn test_traversals_with_compact_and_sparse_ids() {
+    use grafferous::visit::{bfs_distances, CompactIds};
+
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    struct Id(u32);
+    assert_eq!(Id(3).compact_index(), Some(3));
+
+    // dense, sparse and negative integer IDs traverse the same way
+    let dense = Graph::<usize, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 3; 4 -> 3").unwrap();
+    let sparse = dense.clone().map_ids(|id| id * 1_000_000_007).unwrap();
+    let signed = dense.clone().map_ids(|id| -(id as i64)).unwrap();
+    let tuples = dense.clone().map_ids(|id| (id, id)).unwrap();
+
+    assert_eq!(dense.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    assert_eq!(
+        sparse.bfs(0).collect::<Vec<_>>(),
+        vec![0, 1_000_000_007, 2_000_000_014, 3_000_000_021]
+    );
+    assert_eq!(signed.dfs(0).collect::<Vec<_>>(), vec![0, -1, -2, -3]);
+    assert_eq!(tuples.dfs((0, 0)).count(), 4);
+    assert_eq!(bfs_distances(&dense, 0)[&3], 3);
+    assert_eq!(bfs_distances(&sparse, 0)[&3_000_000_021], 3);
+
+    for components in [
+        dense.connected_components().len(),
+        sparse.connected_components().len(),
+        signed.connected_components().len(),
+    ] {
+        assert_eq!(components, 1);
+    }
+    assert_eq!(
+        dense.strongly_connected_components(),
+        vec![vec![4], vec![0, 1, 2], vec![3]]
+    );
+    assert_eq!(sparse.strongly_connected_components().len(), 3);
+
+    // a large cycle is traversed completely
+    let cycle = grafferous::generate_cycle_graph::<()>(10_000);
+    assert_eq!(cycle.bfs(0).count(), 10_000);
+    assert_eq!(cycle.dfs(5_000).count(), 10_000);
+    assert!(cycle.is_connected());
+
+    // IDs that hash only some of their fields share an index without being mixed up
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    struct Bucketed {
+        bucket: u32,
+        n: u32,
+    }
+    impl std::hash::Hash for Bucketed {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.bucket.hash(state);
+        }
+    }
+    let (a, b, c) = (
+        Bucketed { bucket: 0, n: 0 },
+        Bucketed { bucket: 0, n: 1 },
+        Bucketed { bucket: 1, n: 0 },
+    );
+    let mut shared = Graph::<Bucketed, ()>::new();
+    shared.add_directed_edge(a, c);
+    shared.add_directed_edge(c, b);
+    assert_eq!(a.compact_index(), b.compact_index());
+    assert_eq!(shared.bfs(a).collect::<Vec<_>>(), vec![a, c, b]);
+    assert_eq!(shared.dfs(a).collect::<Vec<_>>(), vec![a, c, b]);
+    assert_eq!(shared.reachable_from(a).len(), 3);
+    assert_eq!(bfs_distances(&shared, a)[&b], 2);
+    assert_eq!(shared.connected_components(), vec![vec![a, c, b]]);
+    assert_eq!(shared.strongly_connected_components().len(), 3);
+}
+
+#[test]
+fn test_dijkstra_shortest_paths() {
+    let mut g = Graph::<&str, (), u32>::new();
+    g.add_edge_with_data("a", "b", 7);
+    g.add_edge_with_data("a", "c", 9);
+    g.add_edge_with_data("a", "f", 14);
+    g.add_edge_with_data("b", "c", 10);
+    g.add_edge_with_data("b", "d", 15);
+    g.add_edge_with_data("c", "d", 11);
+    g.add_edge_with_data("c", "f", 2);
+    g.add_edge_with_data("d", "e", 6);
+    g.add_edge_with_data("e", "f", 9);
+
+    assert_eq!(
+        g.shortest_path_dijkstra("a", "e"),
+        Some((20, vec!["a", "c", "f", "e"]))
+    );
+    assert_eq!(g.shortest_path_dijkstra("a", "a"), Some((0, vec!["a"])));
+    assert_eq!(g.shortest_path_dijkstra("a", "z"), None);
+
+    let lengths = g.shortest_path_lengths("a");
+    assert_eq!(lengths.len(), 6);
+    assert_eq!(lengths["d"], 20);
+    assert_eq!(lengths["f"], 11);
+
+    // edges without data are not followed
+    g.add_directed_edge("a", "e");
+    g.add_directed_edge("a", "g");
+    assert_eq!(g.shortest_path_lengths("a")["e"], 20);
+    assert!(!g.shortest_path_lengths("a").contains_key("g"));
+
+    // weights given by a function work on graphs without edge data
+    let grid = grafferous::generate_grid_graph::<()>(5, 5);
+    let cost = |from: (usize, usize), to: (usize, usize)| if to.0 > from.0 { 1 } else { 3 };
+    let (length, path) = grid
+        .shortest_path_dijkstra_by((0, 0), (4, 4), cost)
+        .unwrap();
+    assert_eq!(length, 4 + 3 * 4);
+    assert_eq!(path.len(), 9);
+    assert_eq!(grid.shortest_path_lengths_by((4, 0), cost)[&(0, 0)], 12);
+}
+
+#[test]
+fn test_unweighted_shortest_paths() {
+    let g = generate_grid_graph::<()>(10, 10);
+
+    let path = g.shortest_path((2, 3), (7, 1)).unwrap();
+    assert_eq!(path.len(), 5 + 2 + 1);
+    assert_eq!(path.first(), Some(&(2, 3)));
+    assert_eq!(path.last(), Some(&(7, 1)));
+    assert!(path
+        .windows(2)
+        .all(|step| g.neighbors(step[0]).contains(&step[1])));
+    assert_eq!(g.shortest_path((2, 3), (2, 3)), Some(vec![(2, 3)]));
+    assert_eq!(g.shortest_path((2, 3), (20, 3)), None);
+
+    let distances = g.distances_from((0, 0));
+    assert_eq!(distances.len(), 100);
+    assert!(distances
+        .iter()
+        .all(|((x, y), distance)| *distance == x + y));
+    assert!(g.distances_from((20, 3)).is_empty());
+
+    let one_way = Graph::<u32, ()>::parse("0 -> 1 -> 2").unwrap();
+    assert_eq!(one_way.shortest_path(2, 0), None);
+    assert_eq!(one_way.distances_from(1).len(), 2);
+}
+
+#[test]
+fn test_topological_sort() {
+    use grafferous::CycleError;
+
+    let dag = Graph::<&str, ()>::from_edges(&[
+        ("shirt", "tie"),
+        ("tie", "jacket"),
+        ("trousers", "shoes"),
+        ("trousers", "belt"),
+        ("belt", "jacket"),
+        ("socks", "shoes"),
+    ]);
+    let order = dag.topological_sort().unwrap();
+    assert_eq!(order.len(), dag.nodes.len());
+    let position = |id| order.iter().position(|x| *x == id).unwrap();
+    for (from, to) in dag.edge_tuples() {
+        assert!(position(from) < position(to));
+    }
+    assert!(dag.is_directed_acyclic());
+
+    let mut cyclic = dag.clone();
+    cyclic.add_directed_edge("jacket", "shirt");
+    assert_eq!(
+        cyclic.topological_sort(),
+        Err(CycleError {
+            nodes: vec!["shirt", "tie", "jacket"]
+        })
+    );
+    assert!(!cyclic.is_directed_acyclic());
+
+    // a self-loop is a cycle, and a graph without edges sorts but is not directed acyclic
+    assert!(Graph::<u32, ()>::parse("0 -> 0")
+        .unwrap()
+        .topological_sort()
+        .is_err());
+    let edgeless = Graph::<u32, ()>::parse("0; 1").unwrap();
+    assert_eq!(edgeless.topological_sort(), Ok(vec![0, 1]));
+    assert!(!edgeless.is_directed_acyclic());
+}
+
+#[test]
+fn test_strongly_connected_components() {
+    // two dependency cycles, one feeding the other, and a library used by both
+    let g = Graph::<u32, ()>::parse(
+        "1 -> 2 -> 3 -> 1; 3 -> 4; 4 -> 5 -> 6 -> 4; 2 -> 7; 6 -> 7; 8 -> 8",
+    )
+    .unwrap();
+    let components = g.strongly_connected_components();
+    assert_eq!(
+        components,
+        vec![vec![8], vec![1, 2, 3], vec![4, 5, 6], vec![7]]
+    );
+    assert_eq!(
+        components.iter().map(Vec::len).sum::<usize>(),
+        g.nodes.len()
+    );
+
+    let condensed = g.condensation();
+    assert_eq!(condensed.nodes, vec![0, 1, 2, 3]);
+    assert_eq!(condensed.node_data[&2], vec![4, 5, 6]);
+    assert_eq!(condensed.neighbors(1), &[3, 2]);
+    assert_eq!(condensed.neighbors(2), &[3]);
+    assert!(condensed.neighbors(0).is_empty());
+    assert!(condensed.topological_sort().is_ok());
+
+    // a long path does not overflow the stack
+    let path = Graph::<u32, ()>::from_edges(&(0..100_000).map(|i| (i, i + 1)).collect::<Vec<_>>());
+    assert_eq!(path.strongly_connected_components().len(), 100_001);
+    let cycle = generate_cycle_graph::<()>(1_000);
+    assert_eq!(cycle.strongly_connected_components().len(), 1);
+}
+
+#[test]
+fn test_connected_components() {
+    let mut g = generate_grid_graph::<()>(3, 3);
+    assert!(g.is_connected());
+    assert_eq!(g.connected_components().len(), 1);
+    assert_eq!(g.reachable_from((0, 0)).len(), 9);
+
+    // cutting the middle column splits the grid in two
+    for y in 0..3 {
+        g.remove_edge((0, y), (1, y));
+        g.remove_edge((1, y), (2, y));
+    }
+    let components = g.connected_components();
+    assert_eq!(components.len(), 3);
+    assert!(!g.is_connected());
+    let labels = g.component_labels();
+    assert_eq!(labels[&(0, 0)], labels[&(0, 2)]);
+    assert_ne!(labels[&(0, 0)], labels[&(2, 0)]);
+    assert!(components[labels[&(1, 1)]].contains(&(1, 0)));
+    assert_eq!(g.reachable_from((2, 1)).len(), 3);
+    assert!(g.reachable_from((9, 9)).is_empty());
+
+    // directed edges connect both ways for components, but not for reachability
+    let directed = Graph::<u32, ()>::parse("0 -> 1; 2 -> 1").unwrap();
+    assert!(directed.is_connected());
+    assert_eq!(directed.reachable_from(0).len(), 2);
+    assert!(Graph::<u32, ()>::new().is_connected());
+    assert_eq!(directed.weakly_connected_components(), vec![vec![0, 1, 2]]);
+    assert_eq!(directed.sources(), vec![0, 2]);
+    assert_eq!(directed.sinks(), vec![1]);
+
+    // self-loops and undirected edges count as both incoming and outgoing
+    let mixed = Graph::<u32, ()>::parse("0 -> 1 -- 2; 3 -> 3; 4").unwrap();
+    assert_eq!(mixed.sources(), vec![0, 4]);
+    assert_eq!(mixed.sinks(), vec![4]);
+    assert_eq!(mixed.weakly_connected_components().len(), 3);
+}
+
+#[test]
+fn test_count_paths_dense_dag() {
+    // every pair i < j is linked, so there are 2^(n - 2) paths from the first to the last node
+    let n = 40;
+    let mut dag = Graph::<usize, ()>::new();
+    for i in 0..n {
+        for j in i + 1..n {
+            dag.add_directed_edge(i, j);
+        }
+    }
+    assert_eq!(count_paths(&dag, &0, &(n - 1), None), 1 << (n - 2));
+    assert_eq!(count_paths(&dag, &0, &(n - 1), Some(1)), 1);
+    assert_eq!(count_paths(&dag, &0, &(n - 1), Some(2)), n - 1);
+
+    // a cycle that is not on any path to the end does not matter
+    dag.add_edge(n, n + 1);
+    assert_eq!(count_paths(&dag, &0, &3, None), 4);
+
+    // with a depth limit, walks may go around a cycle but not back through the start
+    let cycle = Graph::<usize, ()>::parse("0 -> 1 -> 2 -> 1; 2 -> 3").unwrap();
+    assert_eq!(count_paths(&cycle, &0, &3, Some(3)), 1);
+    assert_eq!(count_paths(&cycle, &0, &3, Some(5)), 2);
+    assert_eq!(count_paths(&cycle, &0, &3, Some(7)), 3);
+}
+
+#[test]
+fn test_find_cycle() {
+    let dag = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 3; 0 -> 2; 1 -> 3").unwrap();
+    assert_eq!(dag.find_cycle(), None);
+    assert!(dag.is_directed_acyclic());
+
+    let mut cyclic = dag.clone();
+    cyclic.add_directed_edge(3, 1);
+    let cycle = cyclic.find_cycle().unwrap();
+    assert_eq!(cycle.first(), cycle.last());
+    assert!(cycle
+        .windows(2)
+        .all(|step| cyclic.neighbors(step[0]).contains(&step[1])));
+    assert!(!cyclic.is_directed_acyclic());
+
+    assert_eq!(
+        Graph::<u32, ()>::parse("0 -> 0").unwrap().find_cycle(),
+        Some(vec![0, 0])
+    );
+
+    // a long chain is searched without recursion
+    let mut chain =
+        Graph::<u32, ()>::from_edges(&(0..100_000).map(|i| (i, i + 1)).collect::<Vec<_>>());
+    assert_eq!(chain.find_cycle(), None);
+    chain.add_directed_edge(100_000, 99_998);
+    assert_eq!(
+        chain.find_cycle(),
+        Some(vec![99_998, 99_999, 100_000, 99_998])
+    );
+}
+
+#[test]
+fn test_max_flow_and_min_cut() {
+    // the classic example from CLRS, with a maximum flow of 23
+    let mut g = Graph::<&str, (), u64>::new();
+    for (from, to, capacity) in [
+        ("s", "v1", 16),
+        ("s", "v2", 13),
+        ("v2", "v1", 4),
+        ("v1", "v3", 12),
+        ("v3", "v2", 9),
+        ("v2", "v4", 14),
+        ("v4", "v3", 7),
+        ("v3", "t", 20),
+        ("v4", "t", 4),
+    ] {
+        g.add_directed_edge_with_data(from, to, capacity);
+    }
+    let result = g.max_flow("s", "t").unwrap();
+    assert_eq!(result.value, 23);
+    let cut_capacity: u64 = result
+        .cut
+        .iter()
+        .map(|(a, b)| g.edge_data(*a, *b).unwrap())
+        .sum();
+    assert_eq!(cut_capacity, 23);
+    assert!(result.source_side.contains("s"));
+    assert!(!result.source_side.contains("t"));
+
+    // the flow respects the capacities and is conserved at every inner node
+    for ((from, to), carried) in &result.flow {
+        assert!(carried <= g.edge_data(*from, *to).unwrap());
+    }
+    for id in ["v1", "v2", "v3", "v4"] {
+        let inflow: u64 = g
+            .reverse_neighbors(id)
+            .iter()
+            .map(|p| result.flow.get(&(*p, id)).copied().unwrap_or(0))
+            .sum();
+        let outflow: u64 = g
+            .neighbors(id)
+            .iter()
+            .map(|n| result.flow.get(&(id, *n)).copied().unwrap_or(0))
+            .sum();
+        assert_eq!(inflow, outflow);
+    }
+
+    // without a path there is no flow, and the cut is empty
+    assert_eq!(g.max_flow("t", "s").unwrap().value, 0);
+    assert!(g.max_flow("t", "s").unwrap().cut.is_empty());
+    assert!(g.max_flow("s", "s").is_none());
+    assert!(g.max_flow("s", "x").is_none());
+
+    // unit capacities count edge-disjoint paths
+    let grid = generate_grid_graph::<()>(5, 5);
+    assert_eq!(grid.max_flow_by((2, 2), (0, 0), |_, _| 1).unwrap().value, 2);
+    assert_eq!(grid.max_flow_by((1, 1), (3, 3), |_, _| 1).unwrap().value, 4);
+}
+
+#[test]
+fn test_greedy_coloring() {
+    fn is_proper<Id: std::fmt::Debug + Eq + std::hash::Hash + Copy>(
+        g: &Graph<Id, ()>,
+        colors: &grafferous::NodeMap<Id, usize>,
+    ) -> bool {
+        g.edge_tuples()
+            .iter()
+            .all(|(a, b)| a == b || colors[a] != colors[b])
+    }
+
+    // at most one color more than the largest degree is needed
+    let grid = generate_grid_graph::<()>(6, 6);
+    let colors = grid.greedy_coloring();
+    assert_eq!(colors.len(), 36);
+    assert!(is_proper(&grid, &colors));
+    assert!(grid.chromatic_number_upper_bound() <= 5);
+
+    // a star and a path are colored with two colors, the hub first
+    let star = Graph::<u32, ()>::parse("0 -- 1; 2 -- 0; 3 -- 0; 1 -- 4 -- 5").unwrap();
+    let colors = star.greedy_coloring();
+    assert!(is_proper(&star, &colors));
+    assert_eq!(colors[&0], 0);
+    assert_eq!(star.chromatic_number_upper_bound(), 2);
+
+    // a complete graph needs a color per node, a self-loop changes nothing
+    let mut complete = Graph::<u32, ()>::new();
+    for a in 0..5 {
+        for b in a + 1..5 {
+            complete.add_edge(a, b);
+        }
+    }
+    complete.add_directed_edge(0, 0);
+    assert!(is_proper(&complete, &complete.greedy_coloring()));
+    assert_eq!(complete.chromatic_number_upper_bound(), 5);
+
+    // odd cycles need three colors, directions do not matter
+    let cycle = generate_cycle_graph::<()>(7);
+    assert_eq!(cycle.chromatic_number_upper_bound(), 3);
+    assert_eq!(
+        Graph::<u32, ()>::parse("0; 1")
+            .unwrap()
+            .chromatic_number_upper_bound(),
+        1
+    );
+    assert_eq!(Graph::<u32, ()>::new().chromatic_number_upper_bound(), 0);
+}
+
+#[test]
+fn test_pagerank_and_degree_centrality() {
+    // on a cycle every node is equally important
+    let cycle = generate_cycle_graph::<()>(8);
+    let scores = cycle.pagerank(0.85, 100, 1e-12);
+    assert!(scores.values().all(|score| (score - 0.125).abs() < 1e-9));
+
+    // a hub that everyone links to ranks first, and a dangling node spreads its score evenly
+    let g = Graph::<u32, ()>::parse("1 -> 0; 2 -> 0; 3 -> 0; 4 -> 0; 0 -> 5; 3 -> 5").unwrap();
+    let scores = g.pagerank(0.85, 200, 1e-12);
+    let best = g
+        .nodes
+        .iter()
+        .max_by(|a, b| scores[*a].total_cmp(&scores[*b]))
+        .unwrap();
+    assert_eq!(*best, 5);
+    assert!(scores[&0] > scores[&1]);
+    assert!((scores.values().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // a single iteration from the uniform start is not yet converged
+    let rough = g.pagerank(0.85, 1, 0.0);
+    assert!((rough[&0] - scores[&0]).abs() > 1e-3);
+
+    let centrality = g.degree_centrality();
+    assert_eq!(centrality[&0], 5.0 / 5.0);
+    assert_eq!(centrality[&1], 1.0 / 5.0);
+    assert_eq!(centrality[&3], 2.0 / 5.0);
+}
+
+#[test]
+fn test_betweenness_and_closeness_centrality() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // two triangles joined by a bridge through node 3
+    let g = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 0; 2 -- 3 -- 4; 4 -- 5 -- 6 -- 4").unwrap();
+    let betweenness = g.betweenness_centrality(false);
+    // 3 lies on every path between the 3 nodes on one side and the 3 on the other, both ways
+    assert_eq!(betweenness[&3], 2.0 * 9.0);
+    assert_eq!(betweenness[&2], 2.0 * 2.0 * 4.0);
+    assert_eq!(betweenness[&0], 0.0);
+    let normalized = g.betweenness_centrality(true);
+    assert!((normalized[&3] - 18.0 / 30.0).abs() < 1e-12);
+
+    // sampling every node gives the exact values
+    let sampled = g.approximate_betweenness_centrality(100, false, StdRng::seed_from_u64(1));
+    for id in &g.nodes {
+        assert!((sampled[id] - betweenness[id]).abs() < 1e-9);
+    }
+
+    let closeness = g.closeness_centrality();
+    assert_eq!(closeness[&3], 6.0 / 10.0);
+    assert!(closeness[&3] > closeness[&2] && closeness[&2] > closeness[&0]);
+    let approximate = g.approximate_closeness_centrality(7, StdRng::seed_from_u64(2));
+    for id in &g.nodes {
+        assert!((approximate[id] - closeness[id]).abs() < 1e-9);
+    }
+
+    // on a larger graph a sample is close to the exact values
+    let grid = generate_grid_graph::<()>(15, 15);
+    let exact = grid.betweenness_centrality(true);
+    let estimate = grid.approximate_betweenness_centrality(100, true, StdRng::seed_from_u64(3));
+    let center = (7, 7);
+    assert!((estimate[&center] - exact[&center]).abs() < 0.3 * exact[&center]);
+    let exact = grid.closeness_centrality();
+    let estimate = grid.approximate_closeness_centrality(100, StdRng::seed_from_u64(4));
+    assert!((estimate[&center] - exact[&center]).abs() < 0.2 * exact[&center]);
+}
+
+#[test]
+fn test_community_detection() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // four cliques of eight nodes, joined in a ring by one edge each
+    let mut g = Graph::<usize, ()>::new();
+    for clique in 0..4 {
+        for a in 0..8 {
+            for b in a + 1..8 {
+                g.add_edge(clique * 8 + a, clique * 8 + b);
+            }
+        }
+        g.add_edge(clique * 8, (clique + 1) % 4 * 8 + 7);
+    }
+    let planted: grafferous::NodeMap<usize, usize> = (0..32).map(|i| (i, i / 8)).collect();
+    let single: grafferous::NodeMap<usize, usize> = (0..32).map(|i| (i, 0)).collect();
+    assert_eq!(g.modularity(&single), 0.0);
+    assert!(g.modularity(&planted) > 0.7);
+
+    let same_partition = |communities: &grafferous::NodeMap<usize, usize>| {
+        (0..32).all(|a| (0..32).all(|b| (communities[&a] == communities[&b]) == (a / 8 == b / 8)))
+    };
+    let louvain = g.communities_louvain();
+    assert!(same_partition(&louvain));
+    assert_eq!(louvain[&g.nodes[0]], 0);
+    assert!((g.modularity(&louvain) - g.modularity(&planted)).abs() < 1e-12);
+    let propagated = g.communities_label_propagation(StdRng::seed_from_u64(5));
+    assert!(same_partition(&propagated));
+
+    // isolated nodes and graphs without edges keep every node on its own
+    let empty = Graph::<u32, ()>::parse("0; 1; 2").unwrap();
+    assert_eq!(empty.communities_louvain().len(), 3);
+    assert_eq!(empty.modularity(&empty.communities_louvain()), 0.0);
+}
+
+#[test]
+fn test_random_walks_with_restart() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 3; 3 -> 0; 5").unwrap();
+    let walks: Vec<Vec<u32>> = g
+        .random_walks([0, 2, 5, 9], 6, StdRng::seed_from_u64(3))
+        .collect();
+    assert_eq!(walks.len(), 4);
+    assert_eq!(walks[0], vec![0, 1, 2, 3, 0, 1, 2]);
+    assert_eq!(walks[1], vec![2, 3, 0, 1, 2, 3, 0]);
+    // a walk from an isolated node stops at once, one from a missing node is empty
+    assert_eq!(walks[2], vec![5]);
+    assert!(walks[3].is_empty());
+
+    // visit frequencies of a restarting walk approximate personalized PageRank
+    let g = generate_cycle_graph::<()>(6);
+    let mut visits = [0usize; 6];
+    let steps = 200_000;
+    for node in g
+        .random_walk(0, StdRng::seed_from_u64(4))
+        .with_restart(0.5)
+        .take(steps)
+    {
+        visits[node] += 1;
+    }
+    let pagerank = g.personalized_pagerank(&[0], 0.5);
+    for (node, count) in visits.iter().enumerate() {
+        assert!((*count as f64 / steps as f64 - pagerank[&node]).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_subgraphs() {
+    let mut g = Graph::<u32, &str, u32>::new();
+    for (id, name) in [(0, "a"), (1, "b"), (2, "c"), (3, "d")] {
+        g.add_node_with_data(id, name);
+    }
+    g.add_edge_with_data(0, 1, 5);
+    g.add_edge_with_data(1, 2, 7);
+    g.add_directed_edge_with_data(2, 3, 1);
+    g.add_directed_edge(3, 0);
+
+    let sub = g.subgraph(&[0, 1, 3]);
+    assert_eq!(sub.nodes, vec![0, 1, 3]);
+    assert_eq!(sub.node_data[&3], "d");
+    assert_eq!(sub.neighbors(0), &[1]);
+    assert_eq!(sub.reverse_neighbors(0), &[1, 3]);
+    assert_eq!(sub.edge_data(1, 0), Some(&5));
+    assert_eq!(sub.edge_data(1, 2), None);
+    assert!(sub.check_consistency().is_empty());
+
+    let ego = g.ego_graph(1, 1);
+    assert_eq!(ego.nodes, vec![0, 1, 2]);
+    assert!(ego.is_undirected());
+    assert!(g.ego_graph(9, 3).nodes.is_empty());
+
+    let heavy = g.filter_edges(|_, _, data| data.is_some_and(|w| *w >= 5));
+    assert_eq!(heavy.nodes, g.nodes);
+    assert_eq!(heavy.neighbors(2), &[1]);
+    assert!(heavy.neighbors(3).is_empty());
+    assert!(heavy.check_consistency().is_empty());
+    // the original graph is untouched
+    assert_eq!(g.neighbors(3), &[0]);
+}
+
+#[test]
+fn test_graph_set_operations() {
+    let mut a = Graph::<u32, &str, u32>::new();
+    a.add_node_with_data(0, "a0");
+    a.add_node_with_data(1, "a1");
+    a.add_directed_edge_with_data(0, 1, 1);
+    a.add_directed_edge(1, 0);
+    let mut b = Graph::<u32, &str, u32>::new();
+    b.add_node_with_data(1, "b1");
+    b.add_node_with_data(2, "b2");
+    b.add_node_with_data(0, "b0");
+    b.add_directed_edge_with_data(1, 0, 7);
+    b.add_directed_edge_with_data(0, 1, 9);
+    b.add_directed_edge(1, 2);
+
+    let union = a.union(&b, |ours, _| ours);
+    assert_eq!(union.nodes, vec![0, 1, 2]);
+    assert_eq!(union.node_data[&2], "b2");
+    assert_eq!(union.node_data[&1], "a1");
+    assert_eq!(union.neighbors(1), &[0, 2]);
+    assert_eq!(union.edge_data(0, 1), Some(&1));
+    assert_eq!(union.edge_data(1, 0), Some(&7));
+    assert!(union.check_consistency().is_empty());
+
+    let common = a.intersection(&b, |_, theirs| theirs);
+    assert_eq!(common.nodes, vec![0, 1]);
+    assert_eq!(common.node_data[&0], "b0");
+    assert!(common.is_undirected());
+    assert_eq!(common.edge_data(1, 0), Some(&7));
+
+    let only_b = b.difference(&a);
+    assert_eq!(only_b.nodes, b.nodes);
+    assert_eq!(only_b.edge_tuples(), vec![(1, 2)]);
+
+    let mut reversed = b.reversed();
+    assert_eq!(reversed.neighbors(2), &[1]);
+    assert_eq!(reversed.edge_data(0, 1), Some(&7));
+    assert!(reversed.check_consistency().is_empty());
+    reversed.transpose();
+    assert_eq!(reversed, b);
+}
+
+#[test]
+fn test_contraction() {
+    use grafferous::{EdgePolicy, GraphError, SelfLoopPolicy};
+
+    // a path 0 - 1 - 2 - 3 with a chord 1 - 3
+    let mut g = Graph::<u32, u32, u32>::new();
+    for id in 0..4 {
+        g.add_node_with_data(id, 1);
+    }
+    g.add_edge_with_data(0, 1, 10);
+    g.add_edge_with_data(1, 2, 20);
+    g.add_edge_with_data(2, 3, 30);
+    g.add_edge_with_data(1, 3, 40);
+
+    assert_eq!(
+        g.contract_edge(0, 2, |a, b| a + b, false),
+        Err(GraphError::MissingEdge(0, 2))
+    );
+    assert_eq!(
+        g.contract_nodes(&[1, 7], |a, b| a + b, false),
+        Err(GraphError::MissingNode(7))
+    );
+    assert_eq!(g.nodes.len(), 4);
+
+    // merging 2 into 3 leaves two parallel edges between 1 and 3
+    let mut multi = g.clone();
+    multi.contract_edge(3, 2, |a, b| a + b, false).unwrap();
+    assert_eq!(multi.nodes, vec![0, 1, 3]);
+    assert_eq!(multi.node_data[&3], 2);
+    assert_eq!(multi.neighbors(1).iter().filter(|to| **to == 3).count(), 2);
+    assert_eq!(multi.edge_data(1, 3), Some(&40));
+    assert!(multi.self_loops().is_empty());
+    assert!(multi.check_consistency().is_empty());
+
+    // a simple graph keeps one copy, and self-loops can be kept
+    let mut simple = g.clone();
+    simple.set_edge_policy(EdgePolicy {
+        self_loops: SelfLoopPolicy::Allow,
+        allow_parallel_edges: false,
+    });
+    simple.contract_edge(3, 2, |a, b| a + b, true).unwrap();
+    assert_eq!(simple.neighbors(1).iter().filter(|to| **to == 3).count(), 1);
+    assert_eq!(simple.self_loops(), vec![3]);
+    assert_eq!(simple.edge_data(3, 3), Some(&30));
+
+    // contracting everything leaves a single node with all the data
+    g.contract_nodes(&[2, 0, 1, 3, 2], |a, b| a + b, false)
+        .unwrap();
+    assert_eq!(g.nodes, vec![2]);
+    assert_eq!(g.node_data[&2], 4);
+    assert!(g.edge_tuples().is_empty());
+    assert!(g.edge_data.is_empty());
+}
+
+#[test]
+fn test_graph_views() {
+    use grafferous::{
+        components,
+        view::GraphView,
+        visit::{bfs_path, Bfs, Neighbors, Nodes},
+    };
+
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 2 -> 3 -> 4 -> 3; 4 -> 5").unwrap();
+    let whole = GraphView::new(&g);
+    assert_eq!(whole.node_count(), 6);
+    assert_eq!(
+        components::strongly_connected_components(&whole),
+        g.strongly_connected_components()
+    );
+    assert_eq!(
+        components::connected_components(&whole),
+        g.connected_components()
+    );
+
+    // hiding node 2 splits the graph and breaks the first cycle
+    let without_two = GraphView::new(&g).filter_nodes(|id| id != 2);
+    assert!(!without_two.contains_node(2));
+    assert_eq!(without_two.successors(1).count(), 0);
+    assert_eq!(
+        components::connected_components(&without_two),
+        vec![vec![0, 1], vec![3, 4, 5]]
+    );
+    assert_eq!(
+        components::strongly_connected_components(&without_two).len(),
+        4
+    );
+    assert_eq!(bfs_path(&without_two, 0, 4), None);
+
+    // hiding an edge instead keeps every node
+    let forward = GraphView::new(&g).filter_edges(|from, to| from < to);
+    assert_eq!(forward.node_count(), 6);
+    assert_eq!(components::strongly_connected_components(&forward).len(), 6);
+    let reached: Vec<u32> = Bfs::new(&forward, 0).map(|visit| visit.id).collect();
+    assert_eq!(reached, vec![0, 1, 2, 3, 4, 5]);
+    // the graph itself is untouched
+    assert_eq!(g.strongly_connected_components().len(), 3);
+}
+
+#[test]
+fn test_data_traits() {
+    use grafferous::{
+        view::GraphView,
+        visit::{shortest_path, shortest_path_lengths, EdgeWeights, NodeData},
+    };
+
+    // written once against the traits, runs on graphs and views alike
+    fn heaviest_edge<G>(graph: &G) -> Option<(G::Id, G::Id)>
+    where
+        G: EdgeWeights<Weight = u32>,
+    {
+        graph
+            .node_ids()
+            .flat_map(|from| graph.successors(from).map(move |to| (from, to)))
+            .max_by_key(|(from, to)| graph.edge_weight(*from, *to).copied())
+    }
+    fn labels<G: NodeData<Data = &'static str>>(graph: &G) -> Vec<&'static str> {
+        graph
+            .node_ids()
+            .filter_map(|id| graph.node_data(id).copied())
+            .collect()
+    }
+
+    let mut g = Graph::<u32, &str, u32>::new();
+    for (id, label) in [(0, "a"), (1, "b"), (2, "c"), (3, "d")] {
+        g.add_node_with_data(id, label);
+    }
+    g.add_edge_with_data(0, 1, 2);
+    g.add_edge_with_data(1, 2, 2);
+    g.add_edge_with_data(0, 3, 1);
+    g.add_edge_with_data(3, 2, 9);
+
+    assert_eq!(shortest_path(&g, 0, 2), g.shortest_path_dijkstra(0, 2));
+    assert_eq!(shortest_path_lengths(&g, 0), g.shortest_path_lengths(0));
+    assert_eq!(heaviest_edge(&g), Some((3, 2)));
+    assert_eq!(labels(&g), vec!["a", "b", "c", "d"]);
+
+    let view = GraphView::new(&g)
+        .filter_nodes(|id| id != 1)
+        .filter_edges(|from, to| (from, to) != (2, 3));
+    assert_eq!(shortest_path(&view, 0, 2), Some((10, vec![0, 3, 2])));
+    assert_eq!(shortest_path(&view, 2, 0), None);
+    assert_eq!(shortest_path_lengths(&view, 0).len(), 3);
+    assert_eq!(view.edge_weight(0, 1), None);
+    assert_eq!(heaviest_edge(&view), Some((3, 2)));
+    assert_eq!(labels(&view), vec!["a", "c", "d"]);
+}
+
+#[test]
+fn test_map_data_and_ids() {
+    use grafferous::{EdgePolicy, GraphError};
+
+    let mut g = Graph::<u32, u32, f64>::new();
+    g.set_edge_policy(EdgePolicy::SIMPLE);
+    for id in 0..4 {
+        g.add_node_with_data(id, id * 10);
+    }
+    g.add_edge_with_data(0, 1, 0.5);
+    g.add_directed_edge_with_data(1, 2, 1.5);
+    g.add_directed_edge(2, 3);
+
+    let labelled = g.clone().map_data(|id, data| format!("{id}:{data}"));
+    assert_eq!(labelled.node_data[&3], "3:30");
+    assert_eq!(labelled.edge_data(1, 2), Some(&1.5));
+    assert_eq!(labelled.edge_policy(), EdgePolicy::SIMPLE);
+    assert_eq!(labelled.nodes, g.nodes);
+
+    let shifted = g.clone().map_ids(|id| (id as u64) + 100).unwrap();
+    assert_eq!(shifted.nodes, vec![100, 101, 102, 103]);
+    assert_eq!(shifted.node_data[&102], 20);
+    assert_eq!(shifted.neighbors(101), &[100, 102]);
+    assert_eq!(shifted.reverse_neighbors(103), &[102]);
+    assert_eq!(shifted.edge_data(100, 101), Some(&0.5));
+    assert_eq!(shifted.edge_data(102, 103), None);
+    assert_eq!(shifted.edge_policy(), EdgePolicy::SIMPLE);
+    assert!(shifted.check_consistency().is_empty());
+
+    assert_eq!(
+        g.map_ids(|id| id.min(2) as u8).unwrap_err(),
+        GraphError::DuplicateNode(2)
+    );
+}
+
+#[cfg(feature = "petgraph")]
+#[test]
+fn test_petgraph_round_trip() {
+    use petgraph::algo::{is_cyclic_directed, tarjan_scc};
+
+    let mut g = Graph::<char, u32, f64>::new();
+    for (i, id) in ['a', 'b', 'c', 'd'].into_iter().enumerate() {
+        g.add_node_with_data(id, i as u32);
+    }
+    g.add_directed_edge_with_data('a', 'b', 1.0);
+    g.add_directed_edge_with_data('b', 'c', 2.0);
+    g.add_directed_edge('c', 'a');
+    g.add_directed_edge('c', 'd');
+
+    let (pg, index) = g.to_petgraph();
+    assert_eq!(pg.node_count(), 4);
+    assert_eq!(pg.edge_count(), 4);
+    assert_eq!(pg[index[&'c']], 2);
+    let edge = pg.find_edge(index[&'b'], index[&'c']).unwrap();
+    assert_eq!(pg[edge], 2.0);
+    let edge = pg.find_edge(index[&'c'], index[&'a']).unwrap();
+    assert_eq!(pg[edge], 0.0);
+    assert!(is_cyclic_directed(&pg));
+    assert_eq!(
+        tarjan_scc(&pg).len(),
+        g.strongly_connected_components().len()
+    );
+    let same: petgraph::Graph<u32, f64> = (&g).into();
+    assert_eq!(same.edge_count(), 4);
+
+    // back again, with the original IDs restored from the node order
+    let back = Graph::from(&pg).map_ids(|i| g.nodes[i]).unwrap();
+    assert_eq!(back.nodes, g.nodes);
+    assert_eq!(back.node_data, g.node_data);
+    assert_eq!(back.neighbors('c'), g.neighbors('c'));
+    assert_eq!(back.edge_data('a', 'b'), Some(&1.0));
+    assert_eq!(back.edge_data('c', 'd'), Some(&0.0));
+    assert!(back.check_consistency().is_empty());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_algorithms() {
+    use grafferous::generate_random_graph;
+
+    // sparse enough to fall apart into many components
+    let g = generate_random_graph::<u32>(2_000, 0.0006);
+    assert_eq!(g.par_connected_components(), g.connected_components());
+    assert!(g.connected_components().len() > 1);
+    for start in [0, 7, 1_999] {
+        assert_eq!(g.par_distances_from(start), g.distances_from(start));
+    }
+    assert!(g.par_distances_from(5_000).is_empty());
+
+    let grid = generate_grid_graph::<()>(30, 30);
+    assert_eq!(grid.par_connected_components().len(), 1);
+    let directed = Graph::<u32, ()>::parse("0 -> 1 -> 2; 3 -> 2; 4").unwrap();
+    assert_eq!(
+        directed.par_connected_components(),
+        vec![vec![0, 1, 2, 3], vec![4]]
+    );
+
+    let doubled = g.clone().par_map_data(|id, data| (*id, data * 2));
+    assert_eq!(doubled.nodes, g.nodes);
+    assert_eq!(doubled.node_data[&3], (3, 0));
+    assert_eq!(doubled.edge_tuples().len(), g.edge_tuples().len());
+}
+
+#[test]
+fn test_classic_generators() {
+    use grafferous::{
+        generate_complete_bipartite_graph, generate_complete_graph, generate_path_graph,
+        generate_star_graph, generate_wheel_graph,
+    };
+
+    let edge_count = |g: &Graph<usize, ()>| g.edge_tuples().len() / 2;
+
+    let complete = generate_complete_graph::<()>(6);
+    assert_eq!(complete.nodes, (0..6).collect::<Vec<_>>());
+    assert_eq!(edge_count(&complete), 15);
+    assert!(complete.is_undirected() && complete.self_loops().is_empty());
+    assert_eq!(complete.chromatic_number_upper_bound(), 6);
+
+    let star = generate_star_graph::<()>(7);
+    assert_eq!(edge_count(&star), 6);
+    assert_eq!(star.betweenness_centrality(true)[&0], 1.0);
+
+    let wheel = generate_wheel_graph::<()>(7);
+    assert_eq!(edge_count(&wheel), 12);
+    assert!(wheel
+        .nodes
+        .iter()
+        .skip(1)
+        .all(|id| wheel.neighbors(*id).len() == 3));
+    assert_eq!(edge_count(&generate_wheel_graph(3)), 3);
+
+    let path = generate_path_graph::<()>(5);
+    assert_eq!(edge_count(&path), 4);
+    assert_eq!(path.distances_from(0)[&4], 4);
+    assert!(generate_path_graph::<()>(0).nodes.is_empty());
+    assert_eq!(generate_path_graph::<()>(1).nodes, vec![0]);
+
+    let bipartite = generate_complete_bipartite_graph::<()>(3, 4);
+    assert_eq!(edge_count(&bipartite), 12);
+    assert!(bipartite.neighbors(0).iter().all(|id| *id >= 3));
+    assert!(bipartite.neighbors(6).iter().all(|id| *id < 3));
+
+    for g in [complete, star, wheel, path, bipartite] {
+        assert!(g.check_consistency().is_empty());
+        assert!(g.is_connected());
+    }
+}
+
+#[test]
+fn test_triangular_and_hex_grids() {
+    use grafferous::{
+        generate_hex_grid_graph, generate_triangular_grid_graph,
+        layout::{lattice, LatticeKind},
+    };
+
+    let (width, height) = (6, 5);
+    let triangular = generate_triangular_grid_graph::<()>(width, height);
+    assert_eq!(triangular.nodes.len(), width * height);
+    assert_eq!(
+        triangular.edge_tuples().len() / 2,
+        height * (width - 1) + (height - 1) * width + (height - 1) * (width - 1)
+    );
+    let hex = generate_hex_grid_graph::<()>(width, height);
+    assert_eq!(hex.nodes.len(), width * height);
+    assert!(hex.nodes.iter().all(|id| hex.neighbors(*id).len() <= 3));
+    assert_eq!(hex.neighbors((3, 2)).len(), 3);
+    // every inner hexagon is a cycle of six: three nodes in a row and the three above them
+    let hexagon = [(2, 1), (3, 1), (4, 1), (4, 2), (3, 2), (2, 2)];
+    for (i, a) in hexagon.iter().enumerate() {
+        assert!(hex.neighbors(*a).contains(&hexagon[(i + 1) % 6]));
+    }
+
+    // the conventions match the lattice layout, where all edges have unit length
+    for (g, kind) in [
+        (&triangular, LatticeKind::Triangular),
+        (&hex, LatticeKind::Hexagonal),
+    ] {
+        assert!(g.is_undirected() && g.is_connected());
+        assert!(g.check_consistency().is_empty());
+        let positions = lattice(g, kind);
+        for (a, b) in g.edge_tuples() {
+            let (pa, pb) = (positions[&a], positions[&b]);
+            let length = ((pa.0 - pb.0).powi(2) + (pa.1 - pb.1).powi(2)).sqrt();
+            assert!((length - 1.0).abs() < 1e-9, "{a:?} - {b:?}");
+        }
+    }
+    assert_eq!(triangular.neighbors((2, 2)).len(), 6);
+}
+
+#[test]
+fn test_periodic_and_3d_grids() {
+    use grafferous::{generate_grid_graph_3d, generate_grid_graph_periodic};
+
+    let torus = generate_grid_graph_periodic::<()>(5, 4);
+    assert_eq!(torus.nodes.len(), 20);
+    assert_eq!(torus.edge_tuples().len(), 2 * 20 * 2);
+    assert!(torus.is_undirected() && torus.check_consistency().is_empty());
+    assert!(torus.nodes.iter().all(|id| torus.neighbors(*id).len() == 4));
+    assert!(torus.neighbors((4, 3)).contains(&(0, 3)));
+    assert!(torus.neighbors((4, 3)).contains(&(4, 0)));
+
+    // narrow tori neither duplicate the wrapped edges nor loop back onto a node
+    let ring = generate_grid_graph_periodic::<()>(2, 1);
+    assert_eq!(ring.neighbors((0, 0)), &[(1, 0)]);
+    let single = generate_grid_graph_periodic::<()>(1, 1);
+    assert!(single.neighbors((0, 0)).is_empty());
+
+    let cube = generate_grid_graph_3d::<()>(4, 3, 2);
+    assert_eq!(cube.nodes.len(), 24);
+    assert_eq!(cube.edge_tuples().len() / 2, 3 * 3 * 2 + 4 * 2 * 2 + 4 * 3);
+    assert!(cube.is_undirected() && cube.check_consistency().is_empty());
+    assert_eq!(cube.neighbors((1, 1, 0)).len(), 5);
+    assert!(cube.neighbors((3, 2, 1)).contains(&(3, 2, 0)));
+}
+
+#[test]
+fn test_random_dag_and_tree() {
+    use grafferous::{count_paths, generate_random_dag, generate_random_tree};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(11);
+    let dag = generate_random_dag::<()>(40, 0.15, &mut rng);
+    assert_eq!(dag.nodes, (0..40).collect::<Vec<_>>());
+    assert!(dag.is_directed_acyclic());
+    let order = dag.topological_sort().unwrap();
+    assert_eq!(order.len(), 40);
+    let complete = generate_random_dag::<()>(6, 1.0, &mut rng);
+    assert_eq!(complete.edge_tuples().len(), 15);
+    // every subset of the four inner nodes gives one path from the first node to the last
+    assert_eq!(count_paths(&complete, &0, &5, None), 16);
+    assert!(generate_random_dag::<()>(6, 0.0, &mut rng)
+        .edge_tuples()
+        .is_empty());
+
+    for n in [0, 1, 2, 3, 25] {
+        let tree = generate_random_tree::<()>(n, &mut rng);
+        assert_eq!(tree.nodes.len(), n);
+        assert_eq!(tree.edge_tuples().len(), 2 * n.saturating_sub(1));
+        assert!(tree.is_undirected() && tree.check_consistency().is_empty());
+        assert!(n == 0 || tree.is_connected());
+    }
+
+    // every one of the three labeled trees on three nodes turns up
+    let centers: std::collections::HashSet<usize> = (0..60)
+        .map(|_| {
+            let tree = generate_random_tree::<()>(3, &mut rng);
+            *tree
+                .nodes
+                .iter()
+                .find(|id| tree.neighbors(**id).len() == 2)
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(centers.len(), 3);
+}
+
+#[test]
+fn test_seeded_random_generators() {
+    use grafferous::{
+        generate_random_dag, generate_random_graph_with_rng, generate_random_tree,
+        generate_random_weighted_graph, WeightDistribution,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let sorted = |mut edges: Vec<(usize, usize)>| {
+        edges.sort();
+        edges
+    };
+    let mut first = StdRng::seed_from_u64(42);
+    let mut second = StdRng::seed_from_u64(42);
+    // one generator can be threaded through several calls and replays them all
+    for _ in 0..3 {
+        let a = generate_random_graph_with_rng::<()>(30, 0.1, &mut first);
+        let b = generate_random_graph_with_rng::<()>(30, 0.1, &mut second);
+        assert_eq!(sorted(a.edge_tuples()), sorted(b.edge_tuples()));
+        assert!(a.edge_tuples().iter().all(|(from, to)| from != to));
+    }
+    let a = generate_random_dag::<()>(30, 0.1, &mut first);
+    let b = generate_random_dag::<()>(30, 0.1, &mut second);
+    assert_eq!(sorted(a.edge_tuples()), sorted(b.edge_tuples()));
+    let a = generate_random_tree::<()>(30, &mut first);
+    let b = generate_random_tree::<()>(30, &mut second);
+    assert_eq!(sorted(a.edge_tuples()), sorted(b.edge_tuples()));
+    let normal = WeightDistribution::Normal {
+        mean: 0.0,
+        std_dev: 1.0,
+    };
+    let (_, a) = generate_random_weighted_graph::<()>(10, 0.5, normal, &mut first);
+    let (_, b) = generate_random_weighted_graph::<()>(10, 0.5, normal, &mut second);
+    assert_eq!(a, b);
+
+    assert!(generate_random_graph_with_rng::<()>(10, 0.0, &mut first)
+        .edge_tuples()
+        .is_empty());
+    assert_eq!(
+        generate_random_graph_with_rng::<()>(10, 1.0, &mut first)
+            .edge_tuples()
+            .len(),
+        90
+    );
+}
+
+#[test]
+fn test_degree_queries() {
+    use grafferous::generate_grid_graph;
+
+    let mut g = Graph::<u32, ()>::parse("0 -> 1 -> 2; 0 -> 2; 2 -> 2").unwrap();
+    g.add_directed_edge(0, 1);
+    assert_eq!(g.out_degree(0), 3);
+    assert_eq!(g.in_degree(1), 2);
+    assert_eq!(g.out_degree(2), 1);
+    assert_eq!(g.in_degree(2), 3);
+    assert_eq!(g.degree(2), 4);
+    assert_eq!(g.in_degree(9), 0);
+    for id in &g.nodes {
+        assert_eq!(g.out_degree(*id), g.neighbors(*id).len());
+        assert_eq!(g.degree(*id), g.out_degree(*id) + g.in_degree(*id));
+    }
+    assert_eq!(
+        g.degree_distribution().into_iter().collect::<Vec<_>>(),
+        vec![(3, 2), (4, 1)]
+    );
+
+    // every undirected edge of a grid counts once in each direction
+    let grid = generate_grid_graph::<()>(3, 3);
+    let distribution = grid.degree_distribution();
+    assert_eq!(distribution.get(&4), Some(&4));
+    assert_eq!(distribution.get(&6), Some(&4));
+    assert_eq!(distribution.get(&8), Some(&1));
+    assert_eq!(distribution.values().sum::<usize>(), grid.nodes.len());
+    assert!(Graph::<u32, ()>::new().degree_distribution().is_empty());
+}
+
+#[test]
+fn test_adjacency_matrices() {
+    use grafferous::{generate_cycle_graph, matrix::MatrixError};
+
+    let cycle = generate_cycle_graph::<()>(5);
+    let (ids, matrix) = cycle.to_adjacency_matrix();
+    assert_eq!(ids, cycle.nodes);
+    for (i, row) in matrix.iter().enumerate() {
+        assert_eq!(row.iter().sum::<f64>(), 2.0);
+        for (j, entry) in row.iter().enumerate() {
+            assert_eq!(*entry, matrix[j][i]);
+        }
+    }
+    // the rows of the Laplacian `D - A` built from the matrix sum to zero
+    let laplacian: Vec<Vec<f64>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let degree: f64 = row.iter().sum();
+            row.iter()
+                .enumerate()
+                .map(|(j, a)| if i == j { degree - a } else { -a })
+                .collect()
+        })
+        .collect();
+    let constant: Vec<f64> = laplacian.iter().map(|row| row.iter().sum()).collect();
+    assert!(constant.iter().all(|x| x.abs() < 1e-12));
+
+    // parallel edges add up
+    let mut multi = Graph::<u32, ()>::new();
+    multi.add_directed_edge(0, 1);
+    multi.add_directed_edge(0, 1);
+    multi.add_directed_edge(1, 1);
+    assert_eq!(
+        multi.to_adjacency_matrix().1,
+        vec![vec![0.0, 2.0], vec![0.0, 1.0]]
+    );
+
+    let boolean = vec![
+        vec![false, true, true],
+        vec![false; 3],
+        vec![true, false, false],
+    ];
+    let g = Graph::<u32, (), bool>::from_adjacency_matrix(&boolean, &[7, 8, 9]).unwrap();
+    assert_eq!(g.nodes, vec![7, 8, 9]);
+    assert_eq!(g.neighbors(7), &[8, 9]);
+    assert_eq!(g.neighbors(9), &[7]);
+    let (_, back) = g.to_adjacency_matrix();
+    assert_eq!(back[0], vec![0.0, 1.0, 1.0]);
+
+    let short = vec![vec![0u8, 1], vec![1]];
+    assert_eq!(
+        Graph::<u32, (), u8>::from_adjacency_matrix(&short, &[0, 1]).unwrap_err(),
+        MatrixError::RowLength {
+            row: 1,
+            len: 1,
+            ids: 2
+        }
+    );
+    assert_eq!(
+        Graph::<u32, (), u8>::from_adjacency_matrix(&short, &[0]).unwrap_err(),
+        MatrixError::RowCount { rows: 2, ids: 1 }
+    );
+    let square = vec![vec![0u8; 2]; 2];
+    let error = Graph::<u32, (), u8>::from_adjacency_matrix(&square, &[3, 3]).unwrap_err();
+    assert_eq!(error, MatrixError::DuplicateNode(3));
+    assert_eq!(error.to_string(), "node 3 is given twice");
+}
+
+#[test]
+fn test_graph_from_indexed_matrix() {
+    use grafferous::matrix::MatrixError;
+
+    let matrix = vec![
+        vec![1.0, 0.5, 0.0],
+        vec![0.5, 0.0, 0.0],
+        vec![0.0, 0.0, 0.0],
+    ];
+    let g = Graph::<usize, (), f64>::from_matrix(&matrix, true).unwrap();
+    assert_eq!(g.nodes, vec![0, 1, 2]);
+    assert_eq!(g.neighbors(0), &[0, 1]);
+    assert_eq!(g.neighbors(1), &[0]);
+    assert_eq!(g.edge_data(0, 0), Some(&1.0));
+    assert_eq!(g.to_adjacency_matrix_by(|_, _, w| *w.unwrap()).1, matrix);
+
+    let directed = Graph::<usize, (), f64>::from_matrix(&matrix, false).unwrap();
+    assert_eq!(directed.edge_tuples(), g.edge_tuples());
+
+    let jagged = vec![vec![0.0, 1.0], vec![0.0]];
+    assert_eq!(
+        Graph::<usize, (), f64>::from_matrix(&jagged, false).unwrap_err(),
+        MatrixError::RowLength {
+            row: 1,
+            len: 1,
+            ids: 2
+        }
+    );
+    let error = Graph::<usize, (), u8>::from_matrix(&[vec![0, 2], vec![1, 0]], true).unwrap_err();
+    assert_eq!(error, MatrixError::Asymmetric { row: 0, column: 1 });
+    assert_eq!(
+        error.to_string(),
+        "entry (0, 1) differs from entry (1, 0) of an undirected graph"
+    );
+    assert!(Graph::<usize, (), f64>::from_matrix(&[], true)
+        .unwrap()
+        .nodes
+        .is_empty());
+
+    #[cfg(feature = "ndarray")]
+    {
+        let array = ndarray::Array2::from_shape_fn((3, 3), |(i, j)| matrix[i][j]);
+        let from_array = Graph::<usize, (), f64>::from_ndarray(&array, true).unwrap();
+        assert_eq!(from_array.edge_tuples(), g.edge_tuples());
+        assert_eq!(
+            Graph::<usize, (), f64>::from_ndarray(&ndarray::Array2::zeros((2, 3)), false)
+                .unwrap_err(),
+            MatrixError::NotSquare {
+                rows: 2,
+                columns: 3
+            }
+        );
+    }
+}
+
+#[test]
+fn test_line_graph() {
+    // every node of degree d in an undirected graph gives d (d - 1) / 2 edges of the line graph
+    let g = grafferous::generate_grid_graph::<()>(4, 3);
+    let line = g.line_graph();
+    assert_eq!(line.nodes.len(), g.edge_count() / 2);
+    let expected: usize = g
+        .nodes
+        .iter()
+        .map(|id| g.neighbors(*id).len() * (g.neighbors(*id).len() - 1) / 2)
+        .sum();
+    assert_eq!(line.edge_count(), 2 * expected);
+    assert!(line.is_undirected());
+    assert!(line.contains_node(((0, 0), (1, 0))));
+    assert!(!line.contains_node(((1, 0), (0, 0))));
+
+    // the line graph of a directed cycle is a directed cycle
+    let mut cycle = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    let line = cycle.line_graph();
+    assert_eq!(line.nodes, vec![(0, 1), (1, 2), (2, 0)]);
+    assert_eq!(line.neighbors((2, 0)), &[(0, 1)]);
+    assert_eq!(line.edge_count(), 3);
+
+    // parallel edges collapse, self-loops follow themselves
+    cycle.add_directed_edge(0, 1);
+    cycle.add_directed_edge(1, 1);
+    let line = cycle.line_graph();
+    assert_eq!(line.nodes.len(), 4);
+    assert_eq!(line.neighbors((1, 1)), &[(1, 2), (1, 1)]);
+    assert_eq!(line.neighbors((0, 1)), &[(1, 2), (1, 1)]);
+    assert!(Graph::<u32, ()>::new().line_graph().nodes.is_empty());
+}
+
+#[test]
+fn test_graph_products() {
+    use grafferous::{
+        generate_cycle_graph, generate_grid_graph, generate_grid_graph_periodic,
+        generate_path_graph, NodeSet,
+    };
+
+    fn edges<N>(g: &Graph<(usize, usize), N>) -> NodeSet<((usize, usize), (usize, usize))> {
+        g.edge_tuples().into_iter().collect()
+    }
+
+    // the grid generators are products of paths and cycles
+    let grid = generate_path_graph::<()>(4).cartesian_product(&generate_path_graph::<()>(3));
+    assert_eq!(grid.nodes.len(), 12);
+    assert_eq!(edges(&grid), edges(&generate_grid_graph::<()>(4, 3)));
+    let torus = generate_cycle_graph::<()>(4).cartesian_product(&generate_cycle_graph::<()>(5));
+    assert_eq!(
+        edges(&torus),
+        edges(&generate_grid_graph_periodic::<()>(4, 5))
+    );
+
+    // node data is paired, directions are kept
+    let mut a = Graph::<u32, char>::new();
+    a.add_node_with_data(0, 'a');
+    a.add_node_with_data(1, 'b');
+    a.add_directed_edge(0, 1);
+    let b = Graph::<&str, u8>::from_edges(&[("x", "y")]);
+    let product = a.cartesian_product(&b);
+    assert_eq!(product.nodes, vec![(0, "x"), (0, "y"), (1, "x"), (1, "y")]);
+    assert_eq!(product.node_data[&(1, "x")], ('b', 0));
+    assert_eq!(product.neighbors((0, "x")), &[(1, "x"), (0, "y")]);
+    assert!(product.neighbors((1, "y")).is_empty());
+    assert_eq!(
+        a.tensor_product(&b).edge_tuples(),
+        vec![((0, "x"), (1, "y"))]
+    );
+
+    // the tensor product with a complete graph on two nodes is the bipartite double cover
+    let triangle = generate_cycle_graph::<()>(3);
+    let cover = triangle.tensor_product(&generate_path_graph::<()>(2));
+    assert!(cover.is_undirected());
+    assert_eq!(cover.connected_components().len(), 1);
+    assert_eq!(
+        cover
+            .find_all_cycles()
+            .iter()
+            .filter(|c| c.len() == 3)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn test_core_decomposition() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let g: Graph<usize, ()> =
+        grafferous::generate_random_graph_with_rng(200, 0.04, &mut StdRng::seed_from_u64(5));
+    let cores = g.core_numbers();
+    assert_eq!(cores.len(), 200);
+    assert_eq!(g.degeneracy(), cores.values().copied().max().unwrap());
+
+    // every k-core is the largest subgraph with minimum degree k, ignoring directions: peeling
+    // nodes with fewer than k neighbors one at a time leaves the same nodes
+    for k in 0..=g.degeneracy() + 1 {
+        let core = g.k_core(k);
+        let expected: Vec<usize> = g
+            .nodes
+            .iter()
+            .copied()
+            .filter(|id| cores[id] >= k)
+            .collect();
+        assert_eq!(core.nodes, expected);
+
+        let mut peeled = g.clone();
+        while let Some(id) = peeled.nodes.iter().copied().find(|id| {
+            let linked: grafferous::NodeSet<usize> = peeled
+                .neighbors(*id)
+                .iter()
+                .chain(peeled.reverse_neighbors(*id))
+                .copied()
+                .collect();
+            linked.len() < k
+        }) {
+            peeled.remove_node(id);
+        }
+        assert_eq!(peeled.nodes, core.nodes);
+        assert_eq!(peeled.edge_count(), core.edge_count());
+    }
+
+    // directions, parallel edges and self-loops do not add to the degree
+    let mut directed = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 0 -> 1; 1 -> 1").unwrap();
+    assert!(directed.core_numbers().values().all(|core| *core == 2));
+    directed.add_directed_edge(3, 3);
+    assert_eq!(directed.core_numbers()[&3], 0);
+    assert_eq!(Graph::<u32, ()>::new().degeneracy(), 0);
+}
+
+#[test]
+fn test_maximal_cliques() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // the cliques agree with checking every subset of nodes
+    let mut rng = StdRng::seed_from_u64(8);
+    for _ in 0..10 {
+        let mut g = Graph::<u32, ()>::new();
+        for id in 0..12 {
+            g.add_node(id);
+        }
+        for _ in 0..35 {
+            g.add_directed_edge(rng.gen_range(0..12), rng.gen_range(0..12));
+        }
+        let linked = |a: u32, b: u32| g.has_edge(a, b) || g.has_edge(b, a);
+        let is_clique = |set: &[u32]| {
+            set.iter()
+                .enumerate()
+                .all(|(i, a)| set[i + 1..].iter().all(|b| linked(*a, *b)))
+        };
+        let mut expected = Vec::new();
+        for mask in 1u32..1 << 12 {
+            let set: Vec<u32> = (0..12).filter(|id| mask >> id & 1 == 1).collect();
+            let maximal = (0..12)
+                .filter(|id| mask >> id & 1 == 0)
+                .all(|id| !set.iter().all(|member| linked(*member, id)));
+            if maximal && is_clique(&set) {
+                expected.push(set);
+            }
+        }
+        let mut cliques: Vec<Vec<u32>> = g.maximal_cliques().collect();
+        cliques.sort();
+        expected.sort();
+        assert_eq!(cliques, expected);
+    }
+
+    // a complete graph is one clique, and the iterator is lazy
+    let complete = grafferous::generate_complete_graph::<()>(30);
+    assert_eq!(
+        complete.maximal_cliques().collect::<Vec<_>>(),
+        vec![(0..30).collect::<Vec<usize>>()]
+    );
+    let grid = grafferous::generate_grid_graph::<()>(200, 200);
+    assert_eq!(grid.maximal_cliques().take(10).count(), 10);
+    assert!(grid.maximal_cliques().all(|clique| clique.len() == 2));
+    assert_eq!(Graph::<u32, ()>::new().maximal_cliques().count(), 0);
+}
+
+#[test]
+fn test_vertex_cover_and_independent_set() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(9);
+    for _ in 0..20 {
+        let mut g = Graph::<u32, ()>::new();
+        for id in 0..30 {
+            g.add_node(id);
+        }
+        for _ in 0..50 {
+            g.add_directed_edge(rng.gen_range(0..30), rng.gen_range(0..30));
+        }
+        let linked = |a: u32, b: u32| g.has_edge(a, b) || g.has_edge(b, a);
+
+        // the matching is disjoint and cannot be extended
+        let matching = g.maximal_matching();
+        let mut matched: Vec<u32> = matching.iter().flat_map(|(a, b)| [*a, *b]).collect();
+        matched.sort();
+        matched.dedup();
+        assert_eq!(matched.len(), 2 * matching.len());
+        assert!(g
+            .edge_tuples()
+            .iter()
+            .all(|(a, b)| a == b || matched.contains(a) || matched.contains(b)));
+
+        // the cover covers every edge, with at most two nodes per matching edge and the loops
+        let cover = g.approx_min_vertex_cover();
+        assert!(g
+            .edge_tuples()
+            .iter()
+            .all(|(a, b)| cover.contains(a) || cover.contains(b)));
+        let loops = g.nodes.iter().filter(|id| g.has_edge(**id, **id)).count();
+        assert!(cover.len() <= 2 * matching.len() + loops);
+
+        // the set is independent and maximal, and its complement is a cover
+        let set = g.maximal_independent_set(&mut rng);
+        for a in &set {
+            assert!(!g.has_edge(*a, *a));
+            assert!(set.iter().all(|b| !linked(*a, *b)));
+        }
+        for id in g.nodes.iter().filter(|id| !set.contains(id)) {
+            assert!(g.has_edge(*id, *id) || set.iter().any(|member| linked(*member, *id)));
+        }
+        assert!(g
+            .edge_tuples()
+            .iter()
+            .all(|(a, b)| !set.contains(a) || !set.contains(b)));
+    }
+
+    // a complete graph has one node in any maximal independent set
+    let complete = grafferous::generate_complete_graph::<()>(8);
+    assert_eq!(
+        complete
+            .maximal_independent_set(StdRng::seed_from_u64(2))
+            .len(),
+        1
+    );
+    assert_eq!(complete.approx_min_vertex_cover().len(), 8);
+    let empty = Graph::<u32, ()>::new();
+    assert!(empty
+        .maximal_independent_set(StdRng::seed_from_u64(2))
+        .is_empty());
+    assert!(empty.approx_min_vertex_cover().is_empty());
+}
+
+#[test]
+fn test_isomorphism() {
+    use grafferous::{
+        generate_complete_graph, generate_cycle_graph, generate_random_graph_with_rng,
+    };
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(5);
+    for _ in 0..10 {
+        let g = generate_random_graph_with_rng::<u32>(12, 0.25, &mut rng);
+        // the same graph with shuffled IDs in a shuffled order
+        let mut relabel: Vec<usize> = (0..12).collect();
+        relabel.shuffle(&mut rng);
+        let mut order = g.nodes.clone();
+        order.shuffle(&mut rng);
+        let mut copy = Graph::<usize, u32>::new();
+        for id in &order {
+            copy.add_node_with_data(relabel[*id] + 100, *id as u32 % 3);
+        }
+        for (from, to) in g.edge_tuples() {
+            copy.add_directed_edge(relabel[from] + 100, relabel[to] + 100);
+        }
+        let mut g = g;
+        for id in 0..12 {
+            g.node_data.insert(id, id as u32 % 3);
+        }
+        assert!(g.is_isomorphic_to(&copy));
+        assert!(copy.is_isomorphic_to(&g));
+        assert!(g.is_isomorphic_to_by(&copy, |a, b| a == b));
+
+        let mut changed = copy.clone();
+        changed.node_data.insert(100, 7);
+        assert!(g.is_isomorphic_to(&changed));
+        assert!(!g.is_isomorphic_to_by(&changed, |a, b| a == b));
+        if let Some((from, to)) = copy.edge_tuples().first() {
+            changed.remove_directed_edge(*from, *to);
+            assert!(!g.is_isomorphic_to(&changed));
+        }
+    }
+
+    let k4 = generate_complete_graph::<()>(4);
+    let triangle = generate_complete_graph::<()>(3);
+    let path = Graph::<char, ()>::parse("a -- b -- c").unwrap();
+    // four triangles, each found once for every one of its six automorphisms
+    assert_eq!(k4.find_subgraph_isomorphisms(&triangle).len(), 24);
+    assert!(k4.find_subgraph_isomorphisms(&path).is_empty());
+    let c5 = generate_cycle_graph::<()>(5);
+    let found = c5.find_subgraph_isomorphisms(&path);
+    assert_eq!(found.len(), 10);
+    for matched in &found {
+        assert!(c5.neighbors(matched[&'b']).contains(&matched[&'a']));
+        assert!(c5.neighbors(matched[&'b']).contains(&matched[&'c']));
+    }
+
+    // the matches agree with a brute force search over all injective maps
+    for _ in 0..5 {
+        let host = generate_random_graph_with_rng::<()>(7, 0.3, &mut rng);
+        let pattern = generate_random_graph_with_rng::<()>(3, 0.4, &mut rng);
+        let has_edge = |g: &Graph<usize, ()>, a: usize, b: usize| g.neighbors(a).contains(&b);
+        let mut expected = 0;
+        for a in 0..7 {
+            for b in (0..7).filter(|b| *b != a) {
+                for c in (0..7).filter(|c| *c != a && *c != b) {
+                    let image = [a, b, c];
+                    let induced = (0..3).all(|i| {
+                        (0..3).all(|j| {
+                            has_edge(&pattern, i, j) == has_edge(&host, image[i], image[j])
+                        })
+                    });
+                    expected += induced as usize;
+                }
+            }
+        }
+        assert_eq!(host.find_subgraph_isomorphisms(&pattern).len(), expected);
+    }
+}
+
+#[test]
+fn test_eulerian_paths() {
+    use grafferous::{
+        generate_complete_graph, generate_grid_graph, generate_random_graph_with_rng,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashMap;
+
+    // the walk uses every stored edge exactly once, counting an undirected edge once
+    fn check_walk(g: &Graph<usize, ()>, walk: &[usize]) {
+        let mut remaining: HashMap<(usize, usize), usize> = HashMap::new();
+        for (from, to) in g.edge_tuples() {
+            *remaining.entry((from, to)).or_default() += 1;
+        }
+        for step in walk.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            let count = remaining.get_mut(&(from, to)).unwrap();
+            *count -= 1;
+            if g.is_undirected() {
+                *remaining.get_mut(&(to, from)).unwrap() -= 1;
+            }
+        }
+        assert!(remaining.values().all(|count| *count == 0));
+    }
+
+    // complete graphs on an odd number of nodes have even degrees everywhere
+    let k5 = generate_complete_graph::<()>(5);
+    let circuit = k5.eulerian_path().unwrap();
+    assert_eq!(circuit.len(), 11);
+    assert_eq!(circuit.first(), circuit.last());
+    check_walk(&k5, &circuit);
+    assert!(k5.has_eulerian_circuit());
+    let k4 = generate_complete_graph::<()>(4);
+    assert!(!k4.has_eulerian_path());
+
+    // a 2 x 3 grid has exactly two nodes of odd degree, where the path starts and ends
+    let grid = generate_grid_graph::<()>(2, 3);
+    let mut relabeled = Graph::<usize, ()>::new();
+    for (from, to) in grid.edge_tuples() {
+        relabeled.add_directed_edge(from.0 * 3 + from.1, to.0 * 3 + to.1);
+    }
+    let path = relabeled.eulerian_path().unwrap();
+    check_walk(&relabeled, &path);
+    assert!(!relabeled.has_eulerian_circuit());
+    let mut ends = [path[0], *path.last().unwrap()];
+    ends.sort();
+    assert_eq!(ends, [1, 4]);
+
+    // self-loops and parallel edges
+    let mut multi = Graph::<usize, ()>::new();
+    multi.add_edge(0, 0);
+    multi.add_edge(0, 1);
+    multi.add_edge(0, 1);
+    multi.add_directed_edge(1, 2);
+    multi.add_directed_edge(2, 1);
+    let path = multi.eulerian_path().unwrap();
+    assert_eq!(path.len(), 5);
+    assert_eq!(path.last(), Some(&2));
+    check_walk(&multi, &path);
+
+    // directed graphs need balanced nodes and connected edges
+    let mut directed = Graph::<usize, ()>::new();
+    directed.add_directed_edges(&[(0, 1), (1, 2), (2, 0), (0, 1)]);
+    let path = directed.eulerian_path().unwrap();
+    assert_eq!((path[0], path[4]), (0, 1));
+    check_walk(&directed, &path);
+    directed.add_directed_edges(&[(5, 6), (6, 5)]);
+    assert!(!directed.has_eulerian_path());
+    assert!(Graph::<usize, ()>::new().has_eulerian_circuit());
+
+    // random directed graphs: a found walk is always valid
+    let mut rng = StdRng::seed_from_u64(2);
+    for _ in 0..20 {
+        let g = generate_random_graph_with_rng::<()>(6, 0.3, &mut rng);
+        if let Some(walk) = g.eulerian_path() {
+            check_walk(&g, &walk);
+        }
+    }
+}
+
+#[test]
+fn test_bellman_ford() {
+    use grafferous::{generate_random_graph_with_rng, NodeMap};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(11);
+    for trial in 0..30 {
+        let g = generate_random_graph_with_rng::<()>(15, 0.15, &mut rng);
+        // non-negative weights agree with Dijkstra
+        let low = if trial < 10 { 0 } else { -3 };
+        let weights: NodeMap<(usize, usize), i64> = g
+            .edge_tuples()
+            .into_iter()
+            .map(|edge| (edge, rng.gen_range(low..10)))
+            .collect();
+        let weight = |from: usize, to: usize| weights[&(from, to)];
+        match g.shortest_path_bellman_ford_by(0, weight) {
+            Ok(lengths) => {
+                if low == 0 {
+                    assert_eq!(lengths, g.shortest_path_lengths_by(0, weight));
+                }
+                // every node the start reaches has a length no edge can improve
+                assert_eq!(lengths.len(), grafferous::visit::bfs_distances(&g, 0).len());
+                assert_eq!(lengths[&0], 0);
+                for (from, to) in g.edge_tuples() {
+                    if let Some(length) = lengths.get(&from) {
+                        assert!(lengths[&to] <= length + weight(from, to));
+                    }
+                }
+            }
+            Err(error) => {
+                assert!(low < 0);
+                let cycle = error.cycle;
+                let total: i64 = (0..cycle.len())
+                    .map(|i| weight(cycle[i], cycle[(i + 1) % cycle.len()]))
+                    .sum();
+                assert!(total < 0);
+                assert_eq!(cycle[0], *cycle.iter().min().unwrap());
+            }
+        }
+    }
+
+    // a negative self-loop, a negative undirected edge and a start that is not in the graph
+    let mut g = Graph::<u32, (), i32>::new();
+    g.add_directed_edge_with_data(0, 1, 1);
+    g.add_directed_edge_with_data(1, 1, -1);
+    assert_eq!(g.shortest_path_bellman_ford(0).unwrap_err().cycle, vec![1]);
+    assert_eq!(g.shortest_path_bellman_ford(1).unwrap_err().cycle, vec![1]);
+    let mut g = Graph::<u32, (), i32>::new();
+    g.add_edge_with_data(0, 1, 2);
+    g.add_edge_with_data(1, 2, -1);
+    g.add_node(3);
+    assert_eq!(
+        g.shortest_path_bellman_ford(0).unwrap_err().cycle,
+        vec![1, 2]
+    );
+    assert_eq!(g.shortest_path_bellman_ford(3).unwrap().len(), 1);
+    assert!(g.shortest_path_bellman_ford(4).unwrap().is_empty());
+}
+
+#[test]
+fn test_all_pairs_shortest_paths() {
+    use grafferous::{generate_random_graph_with_rng, NodeMap};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(12);
+    for trial in 0..30 {
+        let g = generate_random_graph_with_rng::<()>(14, 0.15, &mut rng);
+        let low = if trial < 10 { 0 } else { -2 };
+        let weights: NodeMap<(usize, usize), i64> = g
+            .edge_tuples()
+            .into_iter()
+            .map(|edge| (edge, rng.gen_range(low..10)))
+            .collect();
+        let weight = |from: usize, to: usize| weights[&(from, to)];
+        let cycle_weight = |cycle: &[usize]| -> i64 {
+            (0..cycle.len())
+                .map(|i| weight(cycle[i], cycle[(i + 1) % cycle.len()]))
+                .sum()
+        };
+        match (
+            g.all_pairs_shortest_paths_by(weight),
+            g.all_pairs_shortest_paths_johnson_by(weight),
+        ) {
+            // both agree with a single-source search from every node
+            (Ok(dense), Ok(sparse)) => {
+                assert_eq!(dense, sparse);
+                assert_eq!(dense.nodes(), &g.nodes[..]);
+                for from in &g.nodes {
+                    let lengths = g.shortest_path_bellman_ford_by(*from, weight).unwrap();
+                    for to in &g.nodes {
+                        assert_eq!(dense.get(*from, *to), lengths.get(to).copied());
+                    }
+                }
+            }
+            (Err(dense), Err(sparse)) => {
+                assert!(low < 0);
+                assert!(cycle_weight(&dense.cycle) < 0);
+                assert!(cycle_weight(&sparse.cycle) < 0);
+            }
+            _ => panic!("only one algorithm found a negative cycle"),
+        }
+    }
+
+    // the edge data as weights, with unweighted edges left out, and the empty graph
+    let mut g = Graph::<u32, (), f64>::new();
+    g.add_edge_with_data(0, 1, 1.5);
+    g.add_edge_with_data(1, 2, 2.0);
+    g.add_edge(2, 3);
+    let matrix = g.all_pairs_shortest_paths().unwrap();
+    assert_eq!(matrix.get(2, 0), Some(3.5));
+    assert_eq!(matrix.get(0, 3), None);
+    assert_eq!(matrix.row(3), Some(&[None, None, None, Some(0.0)][..]));
+    assert_eq!(matrix.index_of(2), Some(2));
+    assert_eq!(matrix, g.all_pairs_shortest_paths_johnson().unwrap());
+    let empty = Graph::<u32, (), i32>::new();
+    assert!(empty
+        .all_pairs_shortest_paths()
+        .unwrap()
+        .as_slice()
+        .is_empty());
+    assert!(empty
+        .all_pairs_shortest_paths_johnson()
+        .unwrap()
+        .nodes()
+        .is_empty());
+}
+
+#[test]
+fn test_astar() {
+    use grafferous::generate_grid_graph;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::HashMap;
+
+    let mut rng = StdRng::seed_from_u64(9);
+    let mut g = generate_grid_graph::<()>(15, 15);
+    for _ in 0..40 {
+        let id = (rng.gen_range(0..15), rng.gen_range(0..15));
+        if id != (0, 0) && id != (14, 14) {
+            g.remove_node(id);
+        }
+    }
+    // every step costs between one and three
+    let costs: HashMap<_, u32> = g
+        .edge_tuples()
+        .into_iter()
+        .map(|edge| (edge, rng.gen_range(1..=3)))
+        .collect();
+    let cost = |from, to| costs[&(from, to)];
+    let manhattan = |(x, y): (usize, usize)| (x.abs_diff(14) + y.abs_diff(14)) as u32;
+    let expected = g.shortest_path_dijkstra_by((0, 0), (14, 14), cost);
+    let found = g.astar((0, 0), (14, 14), manhattan, cost);
+    assert_eq!(found.as_ref().map(|f| f.0), expected.as_ref().map(|e| e.0));
+    assert_eq!(
+        g.astar((0, 0), (14, 14), |_| 0, cost).map(|f| f.0),
+        expected.map(|e| e.0)
+    );
+    if let Some((length, path)) = found {
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(14, 14)));
+        let total: u32 = path.windows(2).map(|step| cost(step[0], step[1])).sum();
+        assert_eq!(total, length);
+    }
+
+    // an admissible heuristic that is not consistent still gives a shortest path
+    let mut weighted = Graph::<u32, (), f64>::new();
+    weighted.add_directed_edge_with_data(0, 1, 1.0);
+    weighted.add_directed_edge_with_data(0, 2, 4.0);
+    weighted.add_directed_edge_with_data(1, 2, 1.0);
+    weighted.add_directed_edge_with_data(2, 3, 5.0);
+    let guess = |id: u32| [6.0, 6.0, 0.0, 0.0][id as usize];
+    let weight = |from, to| *weighted.edge_data(from, to).unwrap();
+    assert_eq!(
+        weighted.astar(0, 3, guess, weight),
+        Some((7.0, vec![0, 1, 2, 3]))
+    );
+    assert_eq!(weighted.astar(3, 0, guess, weight), None);
+    assert_eq!(weighted.astar(0, 0, guess, weight), Some((0.0, vec![0])));
+    assert_eq!(weighted.astar(0, 9, guess, weight), None);
+}
+
+#[test]
+fn test_cycle_enumeration() {
+    use grafferous::{generate_random_dag, generate_random_graph_with_rng};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    // counts the elementary cycles by brute force, extending paths from their smallest node
+    fn count_cycles(g: &Graph<usize, ()>, max_length: usize) -> usize {
+        fn extend(g: &Graph<usize, ()>, path: &mut Vec<usize>, max_length: usize) -> usize {
+            let (start, last) = (path[0], *path.last().unwrap());
+            let mut count = 0;
+            let neighbors: HashSet<usize> = g.neighbors(last).iter().copied().collect();
+            for next in neighbors {
+                if next == start {
+                    count += 1;
+                } else if next > start && !path.contains(&next) && path.len() < max_length {
+                    path.push(next);
+                    count += extend(g, path, max_length);
+                    path.pop();
+                }
+            }
+            count
+        }
+        g.nodes
+            .iter()
+            .map(|start| extend(g, &mut vec![*start], max_length))
+            .sum()
+    }
+
+    let mut rng = StdRng::seed_from_u64(4);
+    for _ in 0..10 {
+        let mut g = generate_random_graph_with_rng::<()>(8, 0.3, &mut rng);
+        g.nodes.sort();
+        g.add_directed_edge(3, 3);
+        let cycles = g.find_all_cycles();
+        assert_eq!(cycles.len(), count_cycles(&g, 8));
+        let distinct: HashSet<&Vec<usize>> = cycles.iter().collect();
+        assert_eq!(distinct.len(), cycles.len());
+        for cycle in &cycles {
+            assert_eq!(cycle[0], *cycle.iter().min().unwrap());
+            for (i, node) in cycle.iter().enumerate() {
+                assert!(g.neighbors(*node).contains(&cycle[(i + 1) % cycle.len()]));
+            }
+        }
+        for max_length in 1..5 {
+            let bounded = g.find_cycles_limited(None, Some(max_length));
+            assert_eq!(bounded.len(), count_cycles(&g, max_length));
+            assert!(bounded.iter().all(|cycle| cycle.len() <= max_length));
+        }
+        let capped = g.find_cycles_limited(Some(2), None);
+        assert_eq!(capped[..], cycles[..2.min(cycles.len())]);
+    }
+
+    let dag = generate_random_dag::<()>(20, 0.3, &mut rng);
+    assert!(dag.find_all_cycles().is_empty());
+    assert!(Graph::<u32, ()>::new().find_all_cycles().is_empty());
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 0").unwrap();
+    assert!(g.find_cycles_limited(Some(0), None).is_empty());
+    assert!(g.find_cycles_limited(None, Some(1)).is_empty());
+}
+
+#[test]
+fn test_eccentricity_diameter_radius_center() {
+    use grafferous::{generate_grid_graph, generate_star_graph};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let grid = generate_grid_graph::<()>(7, 5);
+    assert_eq!(grid.eccentricity((0, 0)), Some(10));
+    assert_eq!(grid.eccentricity((3, 2)), Some(5));
+    assert_eq!(grid.diameter(), Some(10));
+    assert_eq!(grid.radius(), Some(5));
+    assert_eq!(grid.center(), vec![(3, 2)]);
+    // the double sweep finds the exact diameter of a grid
+    assert_eq!(
+        grid.approx_diameter(2, StdRng::seed_from_u64(1)),
+        grid.diameter().unwrap()
+    );
+    assert!(grid.center().contains(&grid.approx_center().unwrap()));
+
+    let star = generate_star_graph::<()>(6);
+    assert_eq!(star.diameter(), Some(2));
+    assert_eq!(star.radius(), Some(1));
+    assert_eq!(star.center(), vec![0]);
+
+    // distances follow the edge directions
+    let cycle = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 3 -> 0").unwrap();
+    assert_eq!(cycle.diameter(), Some(3));
+    assert_eq!(cycle.radius(), Some(3));
+    assert_eq!(cycle.center().len(), 4);
+    let chain = Graph::<u32, ()>::parse("0 -> 1 -> 2").unwrap();
+    assert_eq!(chain.eccentricity(0), Some(2));
+    assert_eq!(chain.eccentricity(2), None);
+    assert_eq!(chain.diameter(), None);
+    assert!(chain.center().is_empty());
+
+    let mut single = Graph::<u32, ()>::new();
+    assert_eq!(single.diameter(), None);
+    single.add_node(0);
+    assert_eq!(single.diameter(), Some(0));
+    assert_eq!(single.center(), vec![0]);
+}
+
+#[test]
+fn test_triangles_and_clustering() {
+    use grafferous::{generate_random_graph_with_rng, generate_triangular_grid_graph};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(8);
+    for _ in 0..5 {
+        let g = generate_random_graph_with_rng::<()>(25, 0.2, &mut rng);
+        let linked =
+            |a: usize, b: usize| g.neighbors(a).contains(&b) || g.neighbors(b).contains(&a);
+        let mut expected = 0;
+        let mut per_node = [0usize; 25];
+        for a in 0..25 {
+            for b in a + 1..25 {
+                for c in b + 1..25 {
+                    if linked(a, b) && linked(b, c) && linked(a, c) {
+                        expected += 1;
+                        for node in [a, b, c] {
+                            per_node[node] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(g.triangle_count(), expected);
+        let mut total = 0.0;
+        for (id, triangles) in per_node.iter().enumerate() {
+            let degree = (0..25)
+                .filter(|other| *other != id && linked(id, *other))
+                .count();
+            let coefficient = if degree < 2 {
+                0.0
+            } else {
+                2.0 * *triangles as f64 / (degree * (degree - 1)) as f64
+            };
+            assert!((g.local_clustering_coefficient(id) - coefficient).abs() < 1e-12);
+            total += coefficient;
+        }
+        assert!((g.average_clustering_coefficient() - total / 25.0).abs() < 1e-12);
+    }
+
+    // parallel edges and self-loops do not add triangles
+    let mut g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0").unwrap();
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(1, 1);
+    assert_eq!(g.triangle_count(), 1);
+    assert_eq!(g.local_clustering_coefficient(1), 1.0);
+    assert_eq!(g.local_clustering_coefficient(9), 0.0);
+    assert_eq!(
+        Graph::<u32, ()>::new().average_clustering_coefficient(),
+        0.0
+    );
+
+    // an inner node of a triangular lattice lies on six triangles among six neighbors
+    let lattice = generate_triangular_grid_graph::<()>(5, 5);
+    assert_eq!(lattice.local_clustering_coefficient((2, 2)), 6.0 / 15.0);
+    assert_eq!(lattice.triangle_count(), 2 * 4 * 4);
+}
+
+#[test]
+fn test_transitive_closure_and_reachability() {
+    use grafferous::{
+        generate_random_dag, generate_random_graph_with_rng, reachability::ReachabilityIndex,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    let mut rng = StdRng::seed_from_u64(14);
+    let mut graphs: Vec<Graph<usize, ()>> = (0..4)
+        .map(|_| generate_random_graph_with_rng(40, 0.03, &mut rng))
+        .collect();
+    graphs.extend((0..4).map(|_| generate_random_dag(60, 0.04, &mut rng)));
+    for g in &graphs {
+        let index = ReachabilityIndex::build(g);
+        let closure = g.transitive_closure();
+        assert_eq!(closure.nodes, g.nodes);
+        assert_eq!(
+            index.component_count(),
+            g.strongly_connected_components().len()
+        );
+        for a in g.nodes.iter().copied() {
+            let reachable = g.reachable_from(a);
+            let expected: HashSet<usize> = g
+                .neighbors(a)
+                .iter()
+                .flat_map(|s| g.reachable_from(*s))
+                .collect();
+            let closed: HashSet<usize> = closure.neighbors(a).iter().copied().collect();
+            assert_eq!(closed, expected);
+            assert_eq!(closure.neighbors(a).len(), expected.len());
+            for b in g.nodes.iter().copied() {
+                assert_eq!(index.reaches(a, b), reachable.contains(&b), "{a} -> {b}");
+            }
+        }
+    }
+
+    let g = Graph::<u32, ()>::parse("0 -> 1 -> 2 -> 0; 3").unwrap();
+    let index = ReachabilityIndex::build(&g);
+    assert!(index.reaches(3, 3));
+    assert!(!index.reaches(0, 3));
+    assert!(!index.reaches(0, 7));
+    assert!(!index.reaches(7, 7));
+    assert_eq!(g.transitive_closure().neighbors(1), &[0, 1, 2]);
+    assert!(g.transitive_closure().neighbors(3).is_empty());
+}
+
+#[test]
+fn test_find_nodes_and_node_index() {
+    use grafferous::NodeIndex;
+
+    let mut g = Graph::<u32, (&str, u32)>::new();
+    for (id, name) in ["a", "b", "a", "c", "b", "a"].into_iter().enumerate() {
+        g.add_node_with_data(id as u32, (name, id as u32 * 10));
+    }
+    let found: Vec<u32> = g
+        .find_nodes(|id, (_, age)| id % 2 == 0 && *age > 0)
+        .collect();
+    assert_eq!(found, vec![2, 4]);
+
+    let mut index = NodeIndex::from_graph(&g, |(name, _)| *name);
+    assert_eq!(index.len(), 6);
+    for name in ["a", "b", "c", "d"] {
+        let scanned: Vec<u32> = g.find_nodes(|_, data| data.0 == name).collect();
+        assert_eq!(index.get(&name), scanned.as_slice());
+    }
+    // moving a node to another key and removing it
+    g.node_data.insert(0, ("c", 0));
+    index.insert(0, &g.node_data[&0]);
+    assert_eq!(index.get(&"c"), &[3, 0]);
+    assert_eq!(index.key_of(0), Some(&"c"));
+    index.remove(3);
+    index.remove(3);
+    assert_eq!(index.get(&"c"), &[0]);
+    assert_eq!(index.key_of(3), None);
+
+    // an attached index follows additions, including nodes created by edges, and removals
+    let attached = NodeIndex::attach(&mut g, |(name, _)| *name);
+    g.add_node_with_data(10, ("d", 1));
+    g.add_edge(11, 1);
+    g.remove_node(2);
+    g.add_node_with_data(2, ("d", 2));
+    g.remove_node(10);
+    let mut attached = attached.lock().unwrap();
+    assert_eq!(attached.lookup(&g, &"d"), &[2]);
+    assert_eq!(attached.get(&""), &[11]);
+    assert_eq!(attached.get(&"a"), &[5]);
+    assert_eq!(attached.len(), g.nodes.len());
+    for id in g.nodes.iter() {
+        assert_eq!(attached.key_of(*id), Some(&g.node_data[id].0));
+    }
+}
+
+#[test]
+fn test_edge_multiplicity_and_simple_generators() {
+    use grafferous::{
+        generate_complete_graph, generate_grid_graph_periodic, generate_path_graph,
+        generate_star_graph, generate_wheel_graph, EdgePolicy, GraphError,
+    };
+
+    let mut g = Graph::<u32, ()>::new();
+    assert_eq!(g.edge_policy(), EdgePolicy::MULTIGRAPH);
+    g.add_edges(&[(0, 1), (0, 1), (1, 1)]);
+    g.add_directed_edge(0, 1);
+    assert_eq!(g.edge_multiplicity(0, 1), 3);
+    assert_eq!(g.edge_multiplicity(1, 0), 2);
+    assert_eq!(g.edge_multiplicity(1, 1), 2);
+    assert_eq!(g.edge_multiplicity(5, 1), 0);
+
+    // the duplicate check of a hub with many edges does not scan its neighbors
+    let mut hub = Graph::<u32, ()>::new();
+    hub.set_edge_policy(EdgePolicy::SIMPLE);
+    for leaf in 1..1000 {
+        hub.add_directed_edge(0, leaf);
+    }
+    assert!(hub.try_add_directed_edge(0, 999).is_err());
+    assert!(hub.try_add_directed_edge(999, 0).is_ok());
+    assert_eq!(hub.edge_multiplicity(0, 500), 1);
+
+    // the check follows removals, and edges written to the public maps after invalidating
+    hub.remove_directed_edge(0, 500);
+    assert!(hub.try_add_directed_edge(0, 500).is_ok());
+    hub.remove_node(999);
+    hub.add_directed_edge(0, 999);
+    assert_eq!(hub.edge_multiplicity(0, 999), 1);
+    hub.edges.get_mut(&0).unwrap().push(1000);
+    hub.reverse_edges
+        .insert(1000, vec![0].into_iter().collect());
+    hub.node_data.insert(1000, ());
+    hub.nodes.push(1000);
+    hub.invalidate_caches();
+    assert!(hub.try_add_directed_edge(0, 1000).is_err());
+    assert_eq!(hub.edge_count(), 1000);
+
+    // transposing reverses the edges the check sees
+    let mut g = Graph::<u32, ()>::new();
+    g.set_edge_policy(EdgePolicy::SIMPLE);
+    g.add_directed_edge(0, 1);
+    g.transpose();
+    assert_eq!(
+        g.try_add_directed_edge(1, 0),
+        Err(GraphError::DuplicateEdge(1, 0))
+    );
+    assert_eq!(g.try_add_directed_edge(0, 1), Ok(()));
+    assert_eq!(g.edge_multiplicity(1, 0), 1);
+
+    // generators give simple graphs whatever their size
+    for n in 0..6 {
+        let graphs = [
+            generate_cycle_graph::<()>(n),
+            generate_complete_graph(n),
+            generate_path_graph(n),
+            generate_star_graph(n),
+            generate_wheel_graph(n),
+        ];
+        for g in &graphs {
+            assert_eq!(g.nodes, (0..n).collect::<Vec<_>>());
+            assert!(g.self_loops().is_empty());
+            for (from, to) in g.edge_tuples() {
+                assert_eq!(g.edge_multiplicity(from, to), 1);
+            }
+        }
+        let torus = generate_grid_graph_periodic::<()>(n, 2);
+        for (from, to) in torus.edge_tuples() {
+            assert_eq!(torus.edge_multiplicity(from, to), 1);
+        }
+    }
+    assert_eq!(generate_cycle_graph::<()>(3).edge_tuples().len(), 6);
+}
+
+#[test]
+fn test_contains_and_counts() {
+    let mut g = Graph::<u32, ()>::parse("0 -> 1 -- 2; 3 -> 3; 4").unwrap();
+    assert_eq!(g.node_count(), 5);
+    assert_eq!(g.edge_count(), 4);
+    assert_eq!(g.edge_count(), g.edge_tuples().len());
+    assert!(g.contains_node(4) && !g.contains_node(5));
+    assert!(g.has_edge(0, 1) && !g.has_edge(1, 0));
+    assert!(g.has_edge(1, 2) && g.has_edge(2, 1));
+    assert!(g.has_edge(3, 3));
+    assert!(!g.has_edge(4, 4) && !g.has_edge(7, 0));
+
+    g.add_directed_edge(0, 1);
+    assert_eq!(g.edge_count(), 5);
+    g.remove_node(1);
+    assert_eq!(g.node_count(), 4);
+    assert_eq!(g.edge_count(), 1);
+    assert!(!g.has_edge(0, 1) && !g.contains_node(1));
+    assert_eq!(Graph::<u32, ()>::new().edge_count(), 0);
+}
+
+#[test]
+fn test_borrowing_iterators() {
+    let mut g = Graph::<u32, char>::new();
+    for (id, name) in ['a', 'b', 'c', 'd'].into_iter().enumerate() {
+        g.add_node_with_data(id as u32, name);
+    }
+    g.add_edges(&[(0, 1), (0, 2), (2, 3)]);
+    g.add_directed_edge(3, 3);
+
+    assert_eq!(g.nodes_iter().copied().collect::<Vec<_>>(), g.nodes);
+    assert_eq!(
+        g.neighbors_iter(0).copied().collect::<Vec<_>>(),
+        g.neighbors(0)
+    );
+    assert_eq!(g.neighbors_iter(9).count(), 0);
+    let named: Vec<(u32, char)> = g.neighbors_with_data(0).map(|(id, c)| (*id, *c)).collect();
+    assert_eq!(named, vec![(1, 'b'), (2, 'c')]);
+    assert_eq!(
+        g.neighbors_with_data(3)
+            .map(|(_, c)| *c)
+            .collect::<String>(),
+        "cd"
+    );
+
+    let edges: Vec<(u32, u32)> = g.edges_iter().collect();
+    assert_eq!(
+        edges,
+        vec![(0, 1), (0, 2), (1, 0), (2, 0), (2, 3), (3, 2), (3, 3)]
+    );
+    assert_eq!(edges, g.edge_tuples());
+    assert_eq!(edges.len(), g.edge_count());
+}
+
+#[test]
+fn test_builder_capacity_and_bulk_insertion() {
+    use grafferous::{builder::DuplicatePolicy, GraphBuilder, GraphError};
+
+    let g = GraphBuilder::<u32, char>::with_capacity(4, 8)
+        .extend_nodes_with([(0, 'a'), (1, 'b')])
+        .extend_nodes([2, 3])
+        .extend_edges([(0, 1), (1, 2)])
+        .extend_directed([(2, 3), (3, 4), (2, 3)])
+        .build()
+        .unwrap();
+    assert_eq!(g.nodes, vec![0, 1, 2, 3, 4]);
+    assert_eq!(g.node_data[&1], 'b');
+    assert_eq!(g.node_data[&3], char::default());
+    assert_eq!(
+        g.edge_tuples(),
+        vec![(0, 1), (1, 0), (1, 2), (2, 1), (2, 3), (3, 4)]
+    );
+
+    // the bulk methods follow the same validation as the single ones
+    let duplicate = GraphBuilder::<u32, ()>::new()
+        .extend_nodes([0, 1, 0])
+        .build();
+    assert_eq!(duplicate.unwrap_err(), GraphError::DuplicateNode(0));
+    let parallel = GraphBuilder::<u32, ()>::with_capacity(0, 0)
+        .extend_directed([(0, 1), (0, 1)])
+        .duplicate_edges(DuplicatePolicy::Allow)
+        .build()
+        .unwrap();
+    assert_eq!(parallel.edge_multiplicity(0, 1), 2);
+
+    let mut grown = Graph::<u32, ()>::with_capacity(100);
+    grown.reserve(1000);
+    for id in 0..1000 {
+        grown.add_node(id);
+    }
+    assert_eq!(grown.node_count(), 1000);
+}
+
+#[test]
+fn test_lowest_common_ancestors() {
+    use grafferous::ancestors::AncestorIndex;
+
+    // a complete binary tree with children 2i + 1 and 2i + 2
+    let mut tree = Graph::<usize, ()>::new();
+    for i in 1..1000 {
+        tree.add_directed_edge((i - 1) / 2, i);
+    }
+    let index = AncestorIndex::build(&tree, 0);
+    assert_eq!(index.root(), Some(0));
+    assert_eq!(index.lowest_common_ancestor(7, 8), Some(3));
+    assert_eq!(index.lowest_common_ancestor(7, 10), Some(1));
+    assert_eq!(index.lowest_common_ancestor(7, 6), Some(0));
+    assert_eq!(index.lowest_common_ancestor(511, 3), Some(3));
+    assert_eq!(index.lowest_common_ancestor(5, 5), Some(5));
+    assert_eq!(index.lowest_common_ancestor(5, 1000), None);
+    assert_eq!(index.depth(999), Some(9));
+    assert_eq!(index.parent(999), Some(499));
+    assert_eq!(index.parent(0), None);
+    assert_eq!(index.distance(7, 8), Some(2));
+
+    // the naive answer agrees on every pair of a subtree
+    let ancestors = |mut node: usize| {
+        let mut path = vec![node];
+        while node > 0 {
+            node = (node - 1) / 2;
+            path.push(node);
+        }
+        path
+    };
+    for a in 0..40 {
+        for b in 0..40 {
+            let of_b = ancestors(b);
+            let expected = ancestors(a).into_iter().find(|x| of_b.contains(x));
+            assert_eq!(index.lowest_common_ancestor(a, b), expected);
+            assert_eq!(
+                tree.lowest_common_ancestors(a, b),
+                expected.into_iter().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    // undirected trees work from any root
+    let path = Graph::<u32, ()>::parse("0 -- 1 -- 2 -- 3; 1 -- 4").unwrap();
+    let index = AncestorIndex::build(&path, 3);
+    assert_eq!(index.lowest_common_ancestor(0, 4), Some(1));
+    assert_eq!(AncestorIndex::build(&path, 9).root(), None);
+
+    // in a graph with several parents per node there can be several lowest common ancestors
+    let dag = Graph::<u32, ()>::parse("0 -> 1 -> 3; 0 -> 2 -> 3; 1 -> 4; 2 -> 4; 5 -> 4").unwrap();
+    assert_eq!(dag.lowest_common_ancestors(3, 4), vec![1, 2]);
+    assert_eq!(dag.lowest_common_ancestors(3, 5), Vec::<u32>::new());
+    assert_eq!(dag.lowest_common_ancestors(3, 9), Vec::<u32>::new());
+}
+
+#[test]
+fn test_dominators() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // the classic example of Cooper, Harvey and Kennedy, with an irreducible loop
+    let g =
+        Graph::<u32, ()>::parse("6 -> 5 -> 1 -> 2 -> 1; 6 -> 4 -> 3 -> 2 -> 3; 4 -> 2").unwrap();
+    let idom = g.dominators(6);
+    for node in 1..=5 {
+        assert_eq!(idom[&node], 6);
+    }
+    assert!(g.dominators(9).is_empty());
+
+    // a node dominates another if removing it cuts the other off from the root
+    let mut rng = StdRng::seed_from_u64(3);
+    for _ in 0..20 {
+        let mut g = Graph::<u32, ()>::new();
+        g.add_node(0);
+        for _ in 0..30 {
+            g.add_directed_edge(rng.gen_range(0..15), rng.gen_range(0..15));
+        }
+        let idom = g.dominators(0);
+        let reachable = g.reachable_from(0);
+        assert_eq!(idom.len(), reachable.len() - 1);
+        for node in reachable.iter().filter(|node| **node != 0) {
+            let dominators: Vec<u32> = reachable
+                .iter()
+                .copied()
+                .filter(|d| *d != *node)
+                .filter(|d| {
+                    *d == 0 || {
+                        let mut cut = g.clone();
+                        cut.remove_node(*d);
+                        !cut.reachable_from(0).contains(node)
+                    }
+                })
+                .collect();
+            // the immediate dominator is the one dominated by all others
+            let mut up = vec![];
+            let mut current = *node;
+            while let Some(parent) = idom.get(&current) {
+                up.push(*parent);
+                current = *parent;
+            }
+            up.sort_unstable();
+            let mut expected = dominators;
+            expected.sort_unstable();
+            assert_eq!(up, expected);
+        }
+    }
+}
+
+#[test]
+fn test_traversal_trees_and_spanning_forest() {
+    let mut g = Graph::<u32, (), f64>::new();
+    g.add_edge_with_data(0, 1, 1.0);
+    g.add_edge_with_data(1, 2, 2.0);
+    g.add_edge_with_data(2, 0, 3.0);
+    g.add_edge_with_data(2, 3, 4.0);
+    g.add_edge_with_data(5, 6, 5.0);
+    g.add_node(7);
+
+    let (tree, parents) = g.bfs_tree(0);
+    assert_eq!(tree.nodes, vec![0, 1, 2, 3]);
+    assert!(tree.is_undirected());
+    assert_eq!(tree.edge_count(), 2 * 3);
+    assert_eq!(parents[&2], 0);
+    assert_eq!(tree.edge_data(2, 0), Some(&3.0));
+    assert!(!tree.has_edge(1, 2));
+
+    let (tree, parents) = g.dfs_tree(0);
+    assert_eq!(parents[&2], 1);
+    assert!(tree.has_edge(1, 2) && !tree.has_edge(0, 2));
+
+    let (empty, parents) = g.bfs_tree(9);
+    assert!(empty.nodes.is_empty() && parents.is_empty());
+
+    let (forest, parents) = g.spanning_forest();
+    assert_eq!(forest.nodes, g.nodes);
+    assert_eq!(forest.edge_count(), 2 * (7 - 3));
+    assert_eq!(parents.len(), 7 - 3);
+    assert_eq!(forest.connected_components(), g.connected_components());
+
+    // on a directed graph the forest keeps the edge directions
+    let directed = Graph::<u32, ()>::parse("0 -> 1; 2 -> 1; 2 -> 3 -> 2; 4").unwrap();
+    let (forest, parents) = directed.spanning_forest();
+    assert_eq!(forest.edge_tuples(), vec![(0, 1), (2, 1), (2, 3)]);
+    assert_eq!(parents[&2], 1);
+    assert!(forest.is_directed_acyclic());
+}
+
+#[test]
+fn test_acyclic_graph() {
+    use grafferous::{AcyclicGraph, CycleError};
+
+    let mut dag = AcyclicGraph::<u32, ()>::new();
+    for id in 0..6 {
+        dag.add_node(id);
+    }
+    // every edge goes against the insertion order, so each one reorders
+    for (from, to) in [(5, 4), (4, 3), (3, 2), (2, 1), (1, 0)] {
+        dag.try_add_directed_edge(from, to).unwrap();
+    }
+    assert_eq!(dag.topological_order(), vec![5, 4, 3, 2, 1, 0]);
+    assert!(dag.would_create_cycle(0, 5));
+    assert!(!dag.would_create_cycle(5, 0));
+    assert_eq!(
+        dag.try_add_directed_edge(0, 3),
+        Err(CycleError {
+            nodes: vec![0, 3, 2, 1, 0]
+        })
+    );
+    assert_eq!(
+        dag.try_add_directed_edge(2, 2),
+        Err(CycleError { nodes: vec![2, 2] })
+    );
+    assert_eq!(dag.graph().edge_count(), 5);
+    dag.try_add_directed_edge(6, 5).unwrap();
+    assert!(dag.graph().is_directed_acyclic());
+
+    // removing an edge makes room for the reverse one
+    dag.remove_directed_edge(3, 2);
+    dag.try_add_directed_edge(0, 3).unwrap();
+    let order = dag.topological_order();
+    let position = |id| order.iter().position(|&other| other == id).unwrap();
+    for (from, to) in dag.graph().edge_tuples() {
+        assert!(position(from) < position(to));
+    }
+
+    let cyclic = Graph::<u32, ()>::parse("0 -> 1 -> 0").unwrap();
+    assert!(AcyclicGraph::from_graph(cyclic).is_err());
+    let wrapped = AcyclicGraph::from_graph(Graph::<u32, ()>::parse("0 -> 1 -> 2").unwrap());
+    assert!(wrapped.unwrap().would_create_cycle(2, 0));
+}
+
+#[test]
+fn test_diff_and_merge() {
+    let before = Graph::<u32, u32>::parse("0 -> 1 -> 2; 2 -> 3").unwrap();
+    let mut after = before.clone();
+    after.remove_node(3);
+    after.node_data.insert(1, 7);
+    after.add_directed_edge(2, 0);
+    after.add_directed_edge(4, 2);
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.added_nodes, vec![4]);
+    assert_eq!(diff.removed_nodes, vec![3]);
+    assert_eq!(diff.added_edges, vec![(2, 0), (4, 2)]);
+    assert_eq!(diff.removed_edges, vec![(2, 3)]);
+    assert_eq!(diff.changed_data, vec![1]);
+    assert!(before.diff(&before).is_empty());
+
+    // merging keeps everything of both, resolving the data conflict
+    let mut merged = before.clone();
+    let mut conflicts = Vec::new();
+    merged.merge(&after, |id, ours, theirs| {
+        conflicts.push(id);
+        ours + theirs
+    });
+    assert_eq!(conflicts, vec![1]);
+    assert_eq!(merged.node_data[&1], 7);
+    assert_eq!(merged.nodes, vec![0, 1, 2, 3, 4]);
+    let rest = merged.diff(&after);
+    assert_eq!(rest.removed_nodes, vec![3]);
+    assert_eq!(rest.removed_edges, vec![(2, 3)]);
+    assert!(rest.added_nodes.is_empty() && rest.added_edges.is_empty());
+
+    // edges are merged once, with the data of the other graph where this one has none
+    let mut ours = Graph::<u32, (), f64>::new();
+    ours.add_directed_edge(0, 1);
+    let mut theirs = Graph::<u32, (), f64>::new();
+    theirs.add_directed_edge_with_data(0, 1, 2.0);
+    theirs.add_directed_edge(1, 2);
+    theirs.add_directed_edge(1, 2);
+    ours.merge(&theirs, |_, _, _| ());
+    assert_eq!(ours.edge_data(0, 1), Some(&2.0));
+    assert_eq!(ours.edge_multiplicity(1, 2), 1);
+}
+
+#[test]
+fn test_deterministic_node_order() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let grid = generate_grid_graph::<()>(3, 2);
+    assert_eq!(
+        grid.nodes,
+        vec![(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)]
+    );
+    assert_eq!(
+        grid.edge_tuples()[..4],
+        [
+            ((0, 0), (1, 0)),
+            ((0, 0), (0, 1)),
+            ((0, 1), (1, 1)),
+            ((0, 1), (0, 0))
+        ]
+    );
+    assert_eq!(
+        grafferous::generate_grid_graph_3d::<()>(2, 2, 2).nodes[..3],
+        [(0, 0, 0), (0, 0, 1), (0, 1, 0)]
+    );
+    let random =
+        grafferous::generate_random_graph_with_rng::<()>(50, 0.1, StdRng::seed_from_u64(3));
+    assert_eq!(random.nodes, (0..50).collect::<Vec<_>>());
+
+    // the order of nodes added one by one is kept through removals
+    let mut g = Graph::<u64, ()>::new();
+    for id in [40, 3, 17, 8, 25] {
+        g.add_node(id);
+    }
+    g.remove_node(17);
+    assert_eq!(g.nodes, vec![40, 3, 8, 25]);
+}